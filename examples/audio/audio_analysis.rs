@@ -0,0 +1,139 @@
+//! Analyses the default input device, drawing the level, frequency bands, onsets and pitch.
+//!
+//! The analysis runs on the audio thread and is read by the main thread without locking.
+//!
+//! You can play and pause the stream by pressing space key
+use nannou::prelude::*;
+use nannou_audio as audio;
+use nannou_audio::analysis::{self, Analysis, Analyzer};
+use nannou_audio::Buffer;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    stream: audio::Stream<Analyzer>,
+    reader: analysis::Reader,
+    analysis: Analysis,
+    onsets: u64,
+    flash: f32,
+}
+
+fn model(app: &App) -> Model {
+    // Create a window to receive key pressed events.
+    app.new_window()
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+
+    // The analyzer lives on the audio thread while the reader lives on the main thread.
+    let (analyzer, mut reader) = analysis::Builder::new()
+        .bands(24, 40.0, 16_000.0)
+        .smoothing(0.7)
+        .build();
+    let analysis = reader.read().clone();
+
+    // Initialise the audio host so we can spawn an audio stream.
+    let audio_host = audio::Host::new();
+
+    // Create an input stream that analyses each captured buffer.
+    let stream = audio_host
+        .new_input_stream(analyzer)
+        .capture(capture)
+        .build()
+        .unwrap();
+
+    stream.play().unwrap();
+
+    Model {
+        stream,
+        reader,
+        analysis,
+        onsets: 0,
+        flash: 0.0,
+    }
+}
+
+fn capture(analyzer: &mut Analyzer, buffer: &Buffer) {
+    analyzer.process(buffer);
+}
+
+fn update(_app: &App, model: &mut Model, update: Update) {
+    // Copy the latest analysis, reusing the existing allocations.
+    model.analysis.clone_from(model.reader.read());
+
+    // Flash whenever an onset has occurred since the last update.
+    model.flash = (model.flash - update.since_last.secs() as f32 * 4.0).max(0.0);
+    if model.analysis.onsets != model.onsets {
+        model.onsets = model.analysis.onsets;
+        model.flash = 1.0;
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::Space => {
+            if model.stream.is_paused() {
+                model.stream.play().unwrap();
+            } else {
+                model.stream.pause().unwrap();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let win = app.window_rect();
+    let analysis = &model.analysis;
+    draw.background().color(gray(model.flash * 0.3));
+
+    // Map magnitudes to heights via decibels, from -60 dB up to 0 dB.
+    let height = |magnitude: f32| {
+        let db = audio::analysis::to_db(magnitude).max(-60.0);
+        map_range(db, -60.0, 0.0, 0.0, win.h() * 0.8)
+    };
+
+    // The bands.
+    let bands = analysis.bands.len();
+    let band_w = win.w() / bands as f32;
+    for (i, &magnitude) in analysis.bands.iter().enumerate() {
+        let h = height(magnitude);
+        let x = win.left() + band_w * (i as f32 + 0.5);
+        let hue = i as f32 / bands as f32 * 0.8;
+        draw.rect()
+            .x_y(x, win.bottom() + h / 2.0)
+            .w_h(band_w * 0.9, h)
+            .hsv(hue, 0.7, 0.9);
+    }
+
+    // The level of each channel.
+    for (i, level) in analysis.channel_levels.iter().enumerate() {
+        let x = win.right() - 20.0 - i as f32 * 20.0;
+        let rms = height(level.rms);
+        let peak = height(level.peak);
+        draw.rect()
+            .x_y(x, win.bottom() + rms / 2.0)
+            .w_h(12.0, rms)
+            .color(WHITE);
+        draw.line()
+            .start(pt2(x - 6.0, win.bottom() + peak))
+            .end(pt2(x + 6.0, win.bottom() + peak))
+            .color(RED);
+    }
+
+    // The pitch.
+    let text = match analysis.pitch {
+        Some(pitch) => format!("{:.1} Hz", pitch.frequency),
+        None => "-".to_string(),
+    };
+    draw.text(&text)
+        .x_y(0.0, win.top() - 40.0)
+        .font_size(32)
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
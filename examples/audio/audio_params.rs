@@ -0,0 +1,206 @@
+//! Controls a sine wave via parameters and triggers blips via a queue, without ever locking the
+//! audio thread or allocating on it.
+//!
+//! - Move the mouse horizontally to change the frequency and vertically to change the volume.
+//! - Press M to toggle mute.
+//! - Press space to trigger a blip. The window flashes until the audio thread reports that the
+//!   blip has finished.
+use nannou::prelude::*;
+use nannou_audio as audio;
+use nannou_audio::param::{BoolParam, FloatParam, Smoothed};
+use nannou_audio::queue::{self, Consumer, Producer};
+use nannou_audio::Buffer;
+use std::f32::consts::PI;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    _stream: audio::Stream<Audio>,
+    hz: FloatParam,
+    volume: FloatParam,
+    mute: BoolParam,
+    commands: Producer<Command>,
+    events: Consumer<Event>,
+    blips_playing: usize,
+}
+
+struct Audio {
+    phase: f32,
+    hz: Smoothed,
+    volume: Smoothed,
+    mute: bool,
+    blip: Option<Blip>,
+    commands: Consumer<Command>,
+    events: Producer<Event>,
+}
+
+struct Blip {
+    phase: f32,
+    hz: f32,
+    frame: usize,
+    len_frames: usize,
+}
+
+// Sent from the main thread to the audio thread.
+enum Command {
+    Blip { hz: f32 },
+}
+
+// Sent from the audio thread to the main thread.
+enum Event {
+    BlipFinished,
+}
+
+fn model(app: &App) -> Model {
+    // Create a window to receive key pressed events.
+    app.new_window()
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+
+    // The parameters are shared between the main thread and the audio thread.
+    let hz = FloatParam::new(220.0);
+    let volume = FloatParam::new(0.25);
+    let mute = BoolParam::new(false);
+
+    // One queue for each direction.
+    let (commands, audio_commands) = queue::bounded(16);
+    let (audio_events, events) = queue::bounded(16);
+
+    // Initialise the state that we want to live on the audio thread.
+    let audio_model = Audio {
+        phase: 0.0,
+        hz: Smoothed::new(0.0).time(0.05),
+        volume: Smoothed::new(0.0),
+        mute: false,
+        blip: None,
+        commands: audio_commands,
+        events: audio_events,
+    };
+
+    // Initialise the audio host so we can spawn an audio stream.
+    let audio_host = audio::Host::new();
+    let stream = audio_host
+        .new_output_stream(audio_model)
+        .render(render)
+        .float_param(&hz, |audio| &mut audio.hz)
+        .float_param(&volume, |audio| &mut audio.volume)
+        .bool_param(&mute, |audio| &mut audio.mute)
+        .build()
+        .unwrap();
+
+    stream.play().unwrap();
+
+    Model {
+        _stream: stream,
+        hz,
+        volume,
+        mute,
+        commands,
+        events,
+        blips_playing: 0,
+    }
+}
+
+// Renders the sine wave along with the current blip, if any.
+fn render(audio: &mut Audio, buffer: &mut Buffer) {
+    let sample_rate = buffer.sample_rate() as f32;
+
+    // Start a new blip for each command, interrupting the current one.
+    for command in audio.commands.by_ref() {
+        match command {
+            Command::Blip { hz } => {
+                if audio.blip.is_some() {
+                    audio.events.push(Event::BlipFinished).ok();
+                }
+                audio.blip = Some(Blip {
+                    phase: 0.0,
+                    hz,
+                    frame: 0,
+                    len_frames: (sample_rate * 0.2) as usize,
+                });
+            }
+        }
+    }
+
+    for frame in buffer.frames_mut() {
+        let hz = audio.hz.next_value();
+        let volume = audio.volume.next_value();
+        let mut amp = (2.0 * PI * audio.phase).sin() * volume;
+        audio.phase = (audio.phase + hz / sample_rate) % 1.0;
+
+        // Add the blip with a linear decay.
+        if let Some(blip) = audio.blip.as_mut() {
+            let env = 1.0 - blip.frame as f32 / blip.len_frames as f32;
+            amp += (2.0 * PI * blip.phase).sin() * env * 0.25;
+            blip.phase = (blip.phase + blip.hz / sample_rate) % 1.0;
+            blip.frame += 1;
+            if blip.frame == blip.len_frames {
+                audio.blip = None;
+                audio.events.push(Event::BlipFinished).ok();
+            }
+        }
+
+        if audio.mute {
+            amp = 0.0;
+        }
+        for channel in frame {
+            *channel = amp;
+        }
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    // Map the mouse position to the frequency and volume.
+    let win = app.window_rect();
+    let hz: f32 = map_range(app.mouse.x, win.left(), win.right(), 110.0, 880.0);
+    let volume: f32 = map_range(app.mouse.y, win.bottom(), win.top(), 0.0, 0.5);
+    model.hz.set(hz.clamp(110.0, 880.0));
+    model.volume.set(volume.clamp(0.0, 0.5));
+
+    // Handle events from the audio thread.
+    for event in model.events.by_ref() {
+        match event {
+            Event::BlipFinished => model.blips_playing -= 1,
+        }
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::M => {
+            model.mute.toggle();
+        }
+        Key::Space => {
+            let hz = model.hz.get() * 2.0;
+            if model.commands.push(Command::Blip { hz }).is_ok() {
+                model.blips_playing += 1;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let win = app.window_rect();
+    let background = match model.blips_playing {
+        0 => gray(0.1),
+        _ => gray(0.4),
+    };
+    draw.background().color(background);
+
+    // Draw the current frequency and volume as a circle.
+    let x = map_range(model.hz.get(), 110.0, 880.0, win.left(), win.right());
+    let y = map_range(model.volume.get(), 0.0, 0.5, win.bottom(), win.top());
+    let color = match model.mute.get() {
+        true => DIMGRAY,
+        false => WHITE,
+    };
+    draw.ellipse().x_y(x, y).radius(20.0).color(color);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -3,23 +3,17 @@
 //! The input data is recorded to "$CARGO_MANIFEST_DIR/recorded.wav".
 use nannou::prelude::*;
 use nannou_audio as audio;
-use nannou_audio::Buffer;
-use std::fs::File;
-use std::io::BufWriter;
-extern crate hound;
-
-type WavWriter = hound::WavWriter<BufWriter<File>>;
+use nannou_audio::file::RecorderHandle;
+use nannou_audio::{Buffer, FileRecorder};
 
 fn main() {
     nannou::app(model).run();
 }
 
 struct Model {
-    stream: audio::Stream<CaptureModel>,
-}
-
-struct CaptureModel {
-    writer: WavWriter,
+    stream: audio::Stream<FileRecorder>,
+    // When the program ends, the handle is dropped and the recording is finished.
+    _recording: RecorderHandle,
 }
 
 fn model(app: &App) -> Model {
@@ -33,34 +27,36 @@ fn model(app: &App) -> Model {
     // Initialise the audio host so we can spawn an audio stream.
     let audio_host = audio::Host::new();
 
-    // Create a writer
-    let spec = get_spec(&audio_host);
-    let writer = hound::WavWriter::create("recorded.wav", spec).unwrap();
-    let capture_model = CaptureModel { writer };
+    // Create a recorder matching the default input format.
+    let config = audio_host
+        .default_input_device()
+        .unwrap()
+        .default_input_config()
+        .unwrap();
+    let channels = config.channels() as usize;
+    let sample_rate = config.sample_rate().0;
+    let (recorder, recording) =
+        FileRecorder::create("recorded.wav", channels, sample_rate).unwrap();
 
     let stream = audio_host
-        .new_input_stream(capture_model)
+        .new_input_stream(recorder)
+        .channels(channels)
+        .sample_rate(sample_rate)
         .capture(capture_fn)
         .build()
         .unwrap();
 
     stream.play().unwrap();
 
-    Model { stream }
+    Model {
+        stream,
+        _recording: recording,
+    }
 }
 
-// A function that captures the audio from the buffer and
-// writes it into the the WavWriter.
-fn capture_fn(audio: &mut CaptureModel, buffer: &Buffer) {
-    // When the program ends, writer is dropped and data gets written to the disk.
-    for frame in buffer.frames() {
-        for sample in frame {
-            audio
-                .writer
-                .write_sample(*sample)
-                .expect("error while writing the sample");
-        }
-    }
+// A function that captures the audio from the buffer and queues it to be written to the file.
+fn capture_fn(recorder: &mut FileRecorder, buffer: &Buffer) {
+    recorder.record(buffer);
 }
 
 fn key_pressed(_app: &App, model: &mut Model, key: Key) {
@@ -86,25 +82,3 @@ fn view(app: &App, model: &Model, frame: Frame) {
         draw.to_frame(app, &frame).unwrap();
     }
 }
-
-// Get specification from default device format.
-fn get_spec(audio_host: &nannou_audio::Host) -> hound::WavSpec {
-    let default_input_config = audio_host
-        .default_input_device()
-        .unwrap()
-        .default_input_config()
-        .unwrap();
-
-    let (bits_per_sample, sample_format) = match default_input_config.sample_format() {
-        audio::cpal::SampleFormat::I16 => (16, hound::SampleFormat::Int),
-        audio::cpal::SampleFormat::U16 => (16, hound::SampleFormat::Int),
-        audio::cpal::SampleFormat::F32 => (32, hound::SampleFormat::Float),
-    };
-
-    hound::WavSpec {
-        channels: default_input_config.channels(),
-        sample_rate: default_input_config.sample_rate().0,
-        bits_per_sample,
-        sample_format,
-    }
-}
@@ -1,6 +1,7 @@
 use nannou::prelude::*;
 use nannou_audio as audio;
-use nannou_audio::Buffer;
+use nannou_audio::file::AudioFile;
+use nannou_audio::{Buffer, FilePlayer};
 
 fn main() {
     nannou::app(model).run();
@@ -8,10 +9,11 @@ fn main() {
 
 struct Model {
     stream: audio::Stream<Audio>,
+    sound: AudioFile,
 }
 
 struct Audio {
-    sounds: Vec<audrey::read::BufFileReader>,
+    players: Vec<FilePlayer>,
 }
 
 fn model(app: &App) -> Model {
@@ -22,12 +24,18 @@ fn model(app: &App) -> Model {
         .build()
         .unwrap();
 
+    // Decode the sound up front so that no decoding occurs on the audio thread.
+    let assets = app.assets_path().expect("could not find assets directory");
+    let path = assets.join("sounds").join("thumbpiano.wav");
+    let sound = AudioFile::open(path).expect("failed to load sound");
+
     // Initialise the audio host so we can spawn an audio stream.
     let audio_host = audio::Host::new();
 
     // Initialise the state that we want to live on the audio thread.
-    let sounds = vec![];
-    let model = Audio { sounds };
+    let model = Audio {
+        players: Vec::with_capacity(32),
+    };
     let stream = audio_host
         .new_output_stream(model)
         .render(audio)
@@ -36,49 +44,30 @@ fn model(app: &App) -> Model {
 
     stream.play().unwrap();
 
-    Model { stream }
+    Model { stream, sound }
 }
 
 // A function that renders the given `Audio` to the given `Buffer`.
 // In this case we play the audio file.
 fn audio(audio: &mut Audio, buffer: &mut Buffer) {
-    let mut have_ended = vec![];
-    let len_frames = buffer.len_frames();
-
-    // Sum all of the sounds onto the buffer.
-    for (i, sound) in audio.sounds.iter_mut().enumerate() {
-        let mut frame_count = 0;
-        let file_frames = sound.frames::<[f32; 2]>().filter_map(Result::ok);
-        for (frame, file_frame) in buffer.frames_mut().zip(file_frames) {
-            for (sample, file_sample) in frame.iter_mut().zip(&file_frame) {
-                *sample += *file_sample;
-            }
-            frame_count += 1;
-        }
-
-        // If the sound yielded less samples than are in the buffer, it must have ended.
-        if frame_count < len_frames {
-            have_ended.push(i);
-        }
+    // Mix all of the sounds into the buffer, resampling them to the stream's sample rate.
+    for player in &mut audio.players {
+        player.render(buffer);
     }
 
     // Remove all sounds that have ended.
-    for i in have_ended.into_iter().rev() {
-        audio.sounds.remove(i);
-    }
+    audio.players.retain(|player| !player.is_finished());
 }
 
-fn key_pressed(app: &App, model: &mut Model, key: Key) {
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
     match key {
         // Start playing another instance of the sound.
         Key::Space => {
-            let assets = app.assets_path().expect("could not find assets directory");
-            let path = assets.join("sounds").join("thumbpiano.wav");
-            let sound = audrey::open(path).expect("failed to load sound");
+            let player = FilePlayer::new(model.sound.clone());
             model
                 .stream
                 .send(move |audio| {
-                    audio.sounds.push(sound);
+                    audio.players.push(player);
                 })
                 .ok();
         }
@@ -0,0 +1,32 @@
+// Render a drawing to a PNG image on the CPU, without a window or GPU.
+//
+// The image is written to `draw_cpu.png` within the current directory.
+
+use nannou::draw::CpuRenderer;
+use nannou::prelude::*;
+
+fn main() {
+    let draw = Draw::new();
+    draw.background().color(PLUM);
+
+    let n = 12;
+    for i in 0..n {
+        let f = i as f32 / n as f32;
+        let p = pt2((f * TAU).cos(), (f * TAU).sin()) * 120.0;
+        draw.ellipse()
+            .xy(p)
+            .radius(30.0)
+            .hsla(f, 0.7, 0.5, 0.8)
+            .stroke(WHITE)
+            .stroke_weight(2.0);
+    }
+
+    draw.text("nannou").font_size(48).color(WHITE);
+
+    let image = CpuRenderer::new().render(&draw, [400, 400], 1.0);
+    let path = std::env::current_dir()
+        .expect("failed to locate the current directory")
+        .join("draw_cpu.png");
+    image.save(&path).expect("failed to save image");
+    println!("Saved {}", path.display());
+}
@@ -0,0 +1,94 @@
+// Press `S` to save the drawing as an SVG image or `P` to save it as a PDF. Files are written to
+// `/<path_to_nannou>/nannou/draw_export/`.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(720, 720)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model
+}
+
+// Describe the drawing so that it may be shared between the window and the exporter.
+fn sketch(app: &App, draw: &Draw) {
+    let win = app.window_rect();
+    let t = app.time;
+
+    draw.background().color(WHITE);
+
+    // A scissored group of rotating rects is exported with a clip path.
+    let clip = win.pad(80.0);
+    let scissored = draw.scissor(clip);
+    for i in 0..12 {
+        let f = i as f32 / 12.0;
+        scissored
+            .rect()
+            .w_h(win.w() * 0.8, 20.0)
+            .rotate(t * 0.2 + f * PI)
+            .hsla(f, 0.6, 0.5, 0.7);
+    }
+    draw.rect()
+        .xy(clip.xy())
+        .wh(clip.wh())
+        .no_fill()
+        .stroke(BLACK)
+        .stroke_weight(2.0);
+
+    draw.ellipse()
+        .radius(100.0)
+        .color(WHITE)
+        .stroke(BLACK)
+        .stroke_weight(4.0);
+
+    draw.polyline()
+        .weight(3.0)
+        .join_round()
+        .points_colored((0..=100).map(|i| {
+            let f = i as f32 / 100.0;
+            let p = pt2((f * TAU * 3.0 + t).cos(), (f * TAU * 3.0 + t).sin()) * 90.0 * f;
+            (p, hsl(f, 0.8, 0.4))
+        }));
+
+    draw.text("draw.export")
+        .y(win.bottom() + 40.0)
+        .font_size(32)
+        .color(BLACK);
+}
+
+fn key_pressed(app: &App, _model: &mut Model, key: Key) {
+    let ext = match key {
+        Key::S => "svg",
+        Key::P => "pdf",
+        _ => return,
+    };
+    let draw = Draw::new();
+    sketch(app, &draw);
+    let dir = app
+        .project_path()
+        .expect("failed to locate `project_path`")
+        .join(app.exe_name().unwrap());
+    std::fs::create_dir_all(&dir).expect("failed to create the export directory");
+    let path = dir
+        .join(format!("{:.0}", app.time * 1000.0))
+        .with_extension(ext);
+    draw.export(app.window_rect())
+        .save(&path)
+        .expect("failed to save the exported drawing");
+    println!("Saved {}", path.display());
+}
+
+fn view(app: &App, _model: &Model, frame: Frame) {
+    let draw = app.draw();
+    sketch(app, &draw);
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,90 @@
+//! A demonstration of lit 3D geometry via `Draw`'s lights and materials.
+//!
+//! Solids are shaded by an ambient light, a directional light and a point light orbiting the
+//! scene. The wavy surface is a mesh whose normals are produced via `smooth_normals`. Geometry
+//! drawn with the default `Unlit` material ignores the lights entirely, e.g. the point light's
+//! marker.
+
+use nannou::draw::{Camera, Light, Material};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let t = app.time;
+    let win = app.window_rect();
+    let step = win.w() / 4.0;
+    let x = |i: f32| win.left() + step * (i + 1.0);
+
+    // The point light orbits the scene in front of the solids.
+    let light_pos = vec3((t * 0.7).cos() * win.w() * 0.4, win.h() * 0.25, 200.0);
+    let lit = draw
+        .perspective(Camera::DEFAULT_FOV_Y)
+        .ambient_light(lin_srgb(0.08, 0.08, 0.1))
+        .directional_light(vec3(-1.0, -0.5, -1.0), lin_srgb(0.4, 0.4, 0.5))
+        .light(Light::point(light_pos, lin_srgb(1.0, 0.9, 0.7)).range(win.w()));
+
+    // Tumble all solids so that their shading is visible from every side.
+    let rotation = vec3(t * 0.5, t * 0.3, 0.0);
+
+    lit.material(Material::Lambert)
+        .cuboid()
+        .x(x(0.0))
+        .y(step * 0.3)
+        .side(step * 0.5)
+        .radians(rotation)
+        .color(STEELBLUE);
+
+    lit.material(Material::blinn_phong(0.8, 64.0))
+        .sphere()
+        .x(x(1.0))
+        .y(step * 0.3)
+        .radius(step * 0.3)
+        .resolution(48)
+        .color(CRIMSON);
+
+    lit.material(Material::blinn_phong(0.5, 16.0))
+        .torus()
+        .x(x(2.0))
+        .y(step * 0.3)
+        .radius(step * 0.25)
+        .tube_radius(step * 0.1)
+        .radians(rotation)
+        .color(GOLD);
+
+    // A wavy surface described by a grid of indexed points.
+    let n = 32;
+    let size = win.w() * 0.8;
+    let points = (0..=n).flat_map(|j| {
+        (0..=n).map(move |i| {
+            let u = i as f32 / n as f32 - 0.5;
+            let v = j as f32 / n as f32 - 0.5;
+            let z = ((u * 12.0 + t).sin() + (v * 9.0 + t * 0.7).cos()) * 15.0;
+            vec3(u * size, v * size * 0.5, z)
+        })
+    });
+    let indices = (0..n).flat_map(|j| {
+        (0..n).flat_map(move |i| {
+            let ix = |i: usize, j: usize| j * (n + 1) + i;
+            let (a, b, c, d) = (ix(i, j), ix(i + 1, j), ix(i + 1, j + 1), ix(i, j + 1));
+            vec![a, b, c, a, c, d]
+        })
+    });
+    lit.material(Material::blinn_phong(0.3, 32.0))
+        .mesh()
+        .indexed(points, indices)
+        .smooth_normals()
+        .y(-win.h() * 0.25)
+        .pitch(-1.0)
+        .color(SEAGREEN);
+
+    // Mark the position of the point light with an unlit sphere.
+    lit.sphere().xyz(light_pos).radius(8.0).color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,75 @@
+// This example demonstrates recording the window to a video or animated image file.
+//
+// Press `R` to start and stop recording. While recording, the app updates on a fixed timestep so
+// that the output plays back smoothly regardless of how quickly each frame is rendered.
+//
+// Files ending in `gif` are encoded in-process. All other formats, like the `mp4` used here, are
+// encoded by an `ffmpeg` subprocess and require that `ffmpeg` is installed.
+
+use nannou::frame::RecordingConfig;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    angle: f32,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(640, 480)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model { angle: 0.0 }
+}
+
+fn update(_app: &App, model: &mut Model, update: Update) {
+    // With a fixed timestep, `since_last` is exactly one frame of the recording.
+    model.angle += update.since_last.secs() as f32 * PI;
+}
+
+fn key_pressed(app: &App, _model: &mut Model, key: Key) {
+    if key != Key::R {
+        return;
+    }
+    let window = app.main_window();
+    if window.is_recording() {
+        window.stop_recording().expect("failed to finish recording");
+        println!("Recording stopped");
+    } else {
+        let path = app
+            .project_path()
+            .expect("failed to locate `project_path`")
+            .join(app.exe_name().unwrap())
+            .with_extension("mp4");
+        let config = RecordingConfig::new().fps(60.0).fixed_timestep(true);
+        window
+            .start_recording(&path, config)
+            .expect("failed to start recording");
+        println!("Recording to {}", path.display());
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(CORNFLOWERBLUE);
+
+    let win = app.window_rect();
+    draw.rect()
+        .w_h(win.w() * 0.4, win.w() * 0.4)
+        .rotate(model.angle)
+        .color(PALEGOLDENROD);
+
+    if app.main_window().is_recording() {
+        draw.ellipse()
+            .xy(win.top_right() - vec2(30.0, 30.0))
+            .radius(10.0)
+            .color(RED);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,60 @@
+//! A demonstration of the 3D solid primitives: `cuboid`, `sphere`, `cylinder`, `cone` and `torus`.
+//!
+//! Each solid may be colored uniformly via the usual color methods, or per face and per vertex via
+//! the `face_colors` and `vertex_colors` methods.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let t = app.time;
+    let win = app.window_rect();
+    let step = win.w() / 6.0;
+    let x = |i: f32| win.left() + step * (i + 1.0);
+
+    // Tumble all solids so that their 3D form is visible.
+    let rotation = vec3(t * 0.5, t * 0.3, 0.0);
+
+    draw.cuboid()
+        .x(x(0.0))
+        .side(step * 0.5)
+        .radians(rotation)
+        .face_colors([RED, GREEN, BLUE, YELLOW, CYAN, MAGENTA]);
+
+    let rainbow = (0..12).map(|i| hsl(i as f32 / 12.0, 0.8, 0.5));
+    draw.sphere()
+        .x(x(1.0))
+        .radius(step * 0.3)
+        .resolution(24)
+        .radians(rotation)
+        .vertex_colors(rainbow);
+
+    draw.cylinder()
+        .x(x(2.0))
+        .radius(step * 0.25)
+        .h(step * 0.6)
+        .radians(rotation)
+        .face_colors([STEELBLUE, LIGHTSTEELBLUE]);
+
+    draw.cone()
+        .x(x(3.0))
+        .radius(step * 0.25)
+        .h(step * 0.6)
+        .radians(rotation)
+        .face_colors([ORANGE, DARKORANGE]);
+
+    draw.torus()
+        .x(x(4.0))
+        .radius(step * 0.25)
+        .tube_radius(step * 0.1)
+        .radians(rotation)
+        .face_colors([PLUM, ORCHID]);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,65 @@
+//! Demonstrates laying out text along a path.
+//!
+//! The upper text follows a circle and is drawn via the glyph cache with `draw.text()`, while the
+//! lower text follows a wave and is drawn as filled path outlines, as in the `draw_text_path`
+//! example. Move the mouse along the *x* axis to shift the text along its path.
+
+use nannou::geom::path;
+use nannou::prelude::*;
+use nannou::text::path::Options;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+    let win = app.window_rect();
+    let offset = map_range(app.mouse.x, win.left(), win.right(), -200.0, 200.0);
+
+    // A circle traced clockwise from its left so that the text reads along its top.
+    let radius = win.h() * 0.2;
+    let centre = pt2(0.0, win.h() * 0.15);
+    let circle = (1..64)
+        .map(|i| {
+            let angle = PI - i as f32 / 64.0 * TAU;
+            centre + pt2(angle.cos(), angle.sin()) * radius
+        })
+        .fold(path().begin(centre - pt2(radius, 0.0)), |b, p| b.line_to(p))
+        .close()
+        .build();
+    draw.path().stroke().weight(1.0).color(GREY).events(&circle);
+    draw.text("Text along a circle, drawn via the glyph cache.")
+        .font_size(24)
+        .no_line_wrap()
+        .color(BLACK)
+        .along_path(&circle)
+        .path_start_offset(offset.max(0.0))
+        .path_spacing(1.0);
+
+    // A wave along the bottom of the window.
+    let mut wave = (0..=128).map(|i| {
+        let x = map_range(i, 0, 128, win.left() + 40.0, win.right() - 40.0);
+        let y = win.h() * -0.3 + (x * 0.01 + app.time).sin() * 40.0;
+        pt2(x, y)
+    });
+    let mut builder = path().begin(wave.next().unwrap());
+    builder = wave.fold(builder, |b, p| b.line_to(p));
+    builder.inner_mut().end(false);
+    let wave = builder.build();
+    draw.path().stroke().weight(1.0).color(GREY).events(&wave);
+    let text = text("Outlines along a wave")
+        .font_size(48)
+        .no_line_wrap()
+        .build(win);
+    let options = Options::default()
+        .align(text::Align::Middle)
+        .start_offset(offset);
+    draw.path()
+        .fill()
+        .color(BLACK)
+        .events(text.path_events_along_path(&wave, &options));
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,70 @@
+//! Demonstrates rendering text with signed distance fields.
+//!
+//! A custom `draw::Renderer` is built with `TextMode::Sdf`, allowing the text to be continuously
+//! scaled and rotated without re-rasterising glyphs, and enabling the outline, glow and drop
+//! shadow text styles.
+
+use nannou::draw::renderer::TextMode;
+use nannou::prelude::*;
+use std::cell::RefCell;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    // The renderer used to render text with signed distance fields.
+    renderer: RefCell<nannou::draw::Renderer>,
+}
+
+fn model(app: &App) -> Model {
+    let w_id = app.new_window().size(1024, 512).view(view).build().unwrap();
+    let window = app.window(w_id).unwrap();
+    let device = window.device();
+    let (w, h) = window.inner_size_pixels();
+    let renderer = nannou::draw::RendererBuilder::new()
+        .text_mode(TextMode::Sdf)
+        .build(
+            device,
+            [w, h],
+            window.scale_factor(),
+            window.msaa_samples(),
+            Frame::TEXTURE_FORMAT,
+        );
+    Model {
+        renderer: RefCell::new(renderer),
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.15));
+
+    let t = app.time;
+    let win = app.window_rect();
+
+    // Continuously scaled and rotated text remains crisp.
+    draw.scale(2.0 + (t * 0.5).sin() * 1.5)
+        .rotate(t.sin() * 0.25)
+        .text("nannou")
+        .font_size(48)
+        .wh(win.wh())
+        .color(WHITE)
+        .outline(2.0, BLACK)
+        .glow(6.0, rgba(0.2, 0.6, 1.0, 0.8))
+        .drop_shadow(vec2(6.0, -6.0), 3.0, rgba(0.0, 0.0, 0.0, 0.6));
+
+    draw.text("Signed distance field text")
+        .font_size(24)
+        .w(win.w())
+        .y(win.bottom() + 40.0)
+        .color(LIGHTGRAY);
+
+    let window = app.window(frame.window_id()).unwrap();
+    model.renderer.borrow_mut().render_to_frame(
+        window.device(),
+        &draw,
+        window.scale_factor(),
+        &frame,
+    );
+}
@@ -0,0 +1,60 @@
+//! Demonstrates styling ranges of text with spans.
+//!
+//! Each span applies its own font size, color, baseline offset, underline or strikethrough to a
+//! byte range of the text. Lines containing larger text grow to fit it.
+
+use nannou::draw::primitive::text::Span;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run();
+}
+
+// The byte range of the first occurrence of `pattern` within `text`.
+fn range_of(text: &str, pattern: &str) -> std::ops::Range<usize> {
+    let start = text.find(pattern).expect("pattern not found");
+    start..start + pattern.len()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.15));
+    let win = app.window_rect();
+
+    let text = "Spans can be BIG or small,\n\
+                colored, underlined or struck through,\n\
+                and raised like x2 or lowered like H2O.";
+
+    // The digits to raise and lower.
+    let x2 = range_of(text, "x2");
+    let h2o = range_of(text, "H2O");
+
+    // Pulse the size of the big text to show the line height adapting to it.
+    let big_size = 40 + ((app.time * 2.0).sin() * 16.0) as u32;
+
+    draw.text(text)
+        .font_size(24)
+        .wh(win.pad(40.0).wh())
+        .color(WHITE)
+        .span(range_of(text, "BIG"), Span::default().font_size(big_size))
+        .span(range_of(text, "small"), Span::default().font_size(14))
+        .span(
+            range_of(text, "colored"),
+            Span::default().color(rgb(1.0, 0.5, 0.2)),
+        )
+        .span(range_of(text, "underlined"), Span::default().underline())
+        .span(
+            range_of(text, "struck through"),
+            Span::default().strikethrough().color(GREY),
+        )
+        .span(
+            x2.start + 1..x2.end,
+            Span::default().font_size(14).baseline_offset(10.0),
+        )
+        .span(
+            h2o.start + 1..h2o.start + 2,
+            Span::default().font_size(14).baseline_offset(-5.0),
+        );
+
+    draw.to_frame(app, &frame).unwrap();
+}
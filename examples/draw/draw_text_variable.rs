@@ -0,0 +1,80 @@
+//! Demonstrates OpenType features and the variation axes of variable fonts.
+//!
+//! The upper text animates every axis of the first variable font found within
+//! `assets/fonts`, sweeping each axis between its minimum and maximum values. Variable
+//! TrueType fonts are available from many foundries, e.g. Google Fonts.
+//!
+//! The lower text toggles the standard ligatures (`liga`) and tabular figures (`tnum`) of the
+//! default font once per second.
+
+use nannou::prelude::*;
+use nannou::text::{font, Font};
+
+struct Model {
+    variable_font: Option<Font>,
+}
+
+fn main() {
+    nannou::app(model).simple_window(view).run();
+}
+
+fn model(app: &App) -> Model {
+    let assets = app
+        .assets_path()
+        .expect("failed to find the assets directory");
+    let fonts_dir = font::default_directory(&assets);
+    let variable_font = nannou::io::walk_dir(&fonts_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| font::from_file(entry.path()).ok())
+        .find(|font| font.is_variable());
+    Model { variable_font }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    let win = app.window_rect();
+    let top = win.pad(40.0).pad_bottom(win.h() * 0.5);
+    let bottom = win.pad(40.0).pad_top(win.h() * 0.5);
+
+    match model.variable_font {
+        None => {
+            draw.text("Add a variable TrueType font to `assets/fonts` to animate its axes.")
+                .xy(top.xy())
+                .wh(top.wh())
+                .font_size(20)
+                .color(GREY);
+        }
+        Some(ref font) => {
+            // Sweep each axis at a slightly different rate.
+            let variations = font.variation_axes().iter().enumerate().map(|(i, axis)| {
+                let phase = (app.time * (0.5 + i as f32 * 0.13)).sin() * 0.5 + 0.5;
+                let value = axis.min + (axis.max - axis.min) * phase;
+                font::Variation {
+                    tag: axis.tag,
+                    value,
+                }
+            });
+            draw.text("Variable")
+                .xy(top.xy())
+                .wh(top.wh())
+                .font(font.clone())
+                .font_size(96)
+                .variations(variations)
+                .color(WHITE);
+        }
+    }
+
+    let on = app.time as u32 % 2;
+    let label = if on == 1 { "on" } else { "off" };
+    draw.text(&format!("office affinity 1111 / 8080 (features {})", label))
+        .xy(bottom.xy())
+        .wh(bottom.wh())
+        .font_size(32)
+        .feature(b"liga", on)
+        .feature(b"tnum", on)
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,87 @@
+// Feeds the default audio input device into the `audioFFT` input of an ISF shader.
+//
+// Each buffer captured by the input stream is sent to the pipeline via an `AudioSender`. The
+// pipeline writes the waveform and spectrum of the latest audio to the shader's `audio` and
+// `audioFFT` textures on each update.
+
+use nannou::prelude::*;
+use nannou_audio as audio;
+use nannou_isf::{AudioSender, IsfPipeline, IsfTime};
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    _in_stream: audio::Stream<AudioSender>,
+    isf_pipeline: IsfPipeline,
+    isf_time: IsfTime,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().view(view).build().unwrap();
+
+    // Create the render pipeline.
+    let assets = app.assets_path().unwrap();
+    let images_dir = assets.join("images");
+    let fs_path = assets.join("isf").join("Test-AudioFFT.fs");
+    let window = app.main_window();
+    let device = window.device();
+    let desc = wgpu::CommandEncoderDescriptor {
+        label: Some("nannou_isf_pipeline_new"),
+    };
+    let mut encoder = device.create_command_encoder(&desc);
+    let (dst_w, dst_h) = window.inner_size_pixels();
+    let mut isf_pipeline = IsfPipeline::new(
+        device,
+        &mut encoder,
+        None,
+        fs_path,
+        Frame::TEXTURE_FORMAT,
+        [dst_w, dst_h],
+        window.msaa_samples(),
+        &images_dir,
+    );
+    window.queue().submit(Some(encoder.finish()));
+
+    // Send each captured buffer to the pipeline.
+    let audio_host = audio::Host::new();
+    let in_stream = audio_host
+        .new_input_stream(isf_pipeline.audio_sender())
+        .capture(capture)
+        .build()
+        .unwrap();
+    in_stream.play().unwrap();
+
+    Model {
+        _in_stream: in_stream,
+        isf_pipeline,
+        isf_time: Default::default(),
+    }
+}
+
+fn capture(sender: &mut AudioSender, buffer: &audio::Buffer) {
+    sender.send_buffer(buffer);
+}
+
+fn update(app: &App, model: &mut Model, update: Update) {
+    let window = app.main_window();
+    let device = window.device();
+    let images_dir = app.assets_path().unwrap().join("images");
+    let desc = wgpu::CommandEncoderDescriptor {
+        label: Some("nannou_isf_pipeline_update"),
+    };
+    let mut encoder = device.create_command_encoder(&desc);
+    let touched_shaders: Vec<std::path::PathBuf> = vec![];
+    model
+        .isf_pipeline
+        .encode_update(device, &mut encoder, &images_dir, touched_shaders);
+    window.queue().submit(Some(encoder.finish()));
+    model.isf_time.time = update.since_start.secs() as _;
+    model.isf_time.time_delta = update.since_last.secs() as _;
+}
+
+fn view(_app: &App, model: &Model, frame: Frame) {
+    frame.clear(BLACK);
+    model.isf_pipeline.encode_to_frame(&frame, model.isf_time);
+}
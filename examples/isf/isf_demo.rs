@@ -66,6 +66,12 @@ fn update(app: &App, model: &mut Model, update: Update) {
         label: Some("nannou_isf_pipeline_update"),
     };
     let mut encoder = device.create_command_encoder(&desc);
+
+    // Drive the `level` input of the shader with the mouse. Values are clamped to MIN and MAX.
+    let win = app.window_rect();
+    let level: f32 = map_range(app.mouse.x, win.left(), win.right(), 0.0, 1.0);
+    model.isf_pipeline.set_input("level", level).ok();
+
     model
         .isf_pipeline
         .encode_update(device, &mut encoder, &images_dir, touched_shaders);
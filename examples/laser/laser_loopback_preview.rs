@@ -0,0 +1,95 @@
+//! Preview a laser frame stream without any laser hardware.
+//!
+//! The stream is established with a software `LoopbackDac` which consumes points in real time.
+//! Each update, the points emitted by the DAC are collected and the most recent frame's worth
+//! are drawn to the window, with blank points drawn as faint dots.
+
+use nannou::prelude::*;
+use nannou_laser as laser;
+use nannou_laser::backend::loopback::LoopbackDac;
+
+const POINT_HZ: u32 = 20_000;
+const FRAME_HZ: u32 = 60;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    _laser_api: laser::Api,
+    _laser_stream: laser::FrameStream<Laser>,
+    dac: LoopbackDac,
+    // The most recently emitted frame's worth of points.
+    points: Vec<laser::RawPoint>,
+}
+
+struct Laser {
+    start: std::time::Instant,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().view(view).build().unwrap();
+
+    // Stream to the loopback DAC rather than a detected one.
+    let dac = LoopbackDac::default();
+    let laser_model = Laser {
+        start: std::time::Instant::now(),
+    };
+    let _laser_api = laser::Api::new();
+    let _laser_stream = _laser_api
+        .new_frame_stream(laser_model, laser)
+        .detected_dac(dac.detected_dac())
+        .point_hz(POINT_HZ)
+        .frame_hz(FRAME_HZ)
+        .build()
+        .unwrap();
+
+    Model {
+        _laser_api,
+        _laser_stream,
+        dac,
+        points: vec![],
+    }
+}
+
+// Draw a rotating triangle.
+fn laser(laser: &mut Laser, frame: &mut laser::Frame) {
+    let t = laser.start.elapsed().as_secs_f32();
+    let points: Vec<_> = (0..4)
+        .map(|i| {
+            let angle = t + i as f32 * TAU / 3.0;
+            let [x, y] = [angle.cos() * 0.75, angle.sin() * 0.75];
+            laser::Point::new([x, y], [1.0, 0.2, 0.6])
+        })
+        .collect();
+    frame.add_lines(points);
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    // Retain only the latest frame's worth of emitted points.
+    model.points.extend(model.dac.take_points());
+    let points_per_frame = (POINT_HZ / FRAME_HZ) as usize;
+    let excess = model.points.len().saturating_sub(points_per_frame);
+    model.points.drain(..excess);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    let win = app.window_rect();
+    let scale = win.w().min(win.h()) * 0.5;
+    for p in &model.points {
+        let [x, y] = p.position;
+        let [r, g, b] = p.color;
+        let (radius, color) = if p.is_blank() {
+            (1.0, rgba(1.0, 1.0, 1.0, 0.1))
+        } else {
+            (2.0, rgba(r, g, b, 1.0))
+        };
+        draw.ellipse()
+            .x_y(x * scale, y * scale)
+            .radius(radius)
+            .color(color);
+    }
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,67 @@
+//! A simple example demonstrating a fixed timestep simulation via `LoopMode::Rate`.
+//!
+//! The ball's motion is simulated at a deliberately low rate of 10 updates per second, while
+//! `view` is called on every pass of the loop. The `app.update_alpha` is used to blend between the
+//! previous and current simulated positions, resulting in smooth motion regardless of the display
+//! refresh rate.
+//!
+//! Press any key to toggle interpolation.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model)
+        .loop_mode(LoopMode::rate_fps(10.0))
+        .update(update)
+        .run();
+}
+
+struct Model {
+    prev_position: Point2,
+    position: Point2,
+    velocity: Vec2,
+    interpolate: bool,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        prev_position: Point2::ZERO,
+        position: Point2::ZERO,
+        velocity: vec2(240.0, 180.0),
+        interpolate: true,
+    }
+}
+
+fn update(app: &App, model: &mut Model, update: Update) {
+    // `since_last` is always exactly the fixed update interval in `Rate` mode.
+    let dt = update.since_last.secs() as f32;
+    let rect = app.window_rect();
+    model.prev_position = model.position;
+    model.position += model.velocity * dt;
+    if model.position.x.abs() > rect.right() {
+        model.velocity.x = -model.velocity.x;
+    }
+    if model.position.y.abs() > rect.top() {
+        model.velocity.y = -model.velocity.y;
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, _key: Key) {
+    model.interpolate = !model.interpolate;
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DIMGRAY);
+    let position = match model.interpolate {
+        true => model.prev_position.lerp(model.position, app.update_alpha),
+        false => model.position,
+    };
+    draw.ellipse().xy(position).radius(30.0).color(ORANGE);
+    draw.to_frame(app, &frame).unwrap();
+}
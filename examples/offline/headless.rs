@@ -0,0 +1,48 @@
+// Renders a short animation to a sequence of PNG images without opening a window.
+//
+// Headless apps render into offscreen textures, so this example runs on machines without a
+// display, falling back to a software adapter if no GPU is available.
+
+use nannou::prelude::*;
+
+const FRAMES: usize = 60;
+
+fn main() {
+    nannou::app(model)
+        .headless()
+        .loop_mode(LoopMode::loop_ntimes(FRAMES))
+        .run();
+}
+
+struct Model;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .offscreen(512, 512)
+        .view(view)
+        .build()
+        .unwrap();
+    Model
+}
+
+fn view(app: &App, _model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    let t = frame.nth() as f32 / FRAMES as f32;
+    let radius = 200.0;
+    let angle = t * TAU;
+    draw.ellipse()
+        .x_y(angle.cos() * radius * 0.5, angle.sin() * radius * 0.5)
+        .radius(radius * 0.25)
+        .hsv(t, 0.8, 1.0);
+    draw.to_frame(app, &frame).unwrap();
+
+    // Capture the frame to a PNG named after the frame number.
+    let path = app
+        .project_path()
+        .expect("failed to locate `project_path`")
+        .join(app.exe_name().unwrap())
+        .join(format!("{:03}", frame.nth()))
+        .with_extension("png");
+    app.main_window().capture_frame(path);
+}
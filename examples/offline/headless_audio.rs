@@ -0,0 +1,118 @@
+// Renders a short audio-visual piece to a sequence of PNG images along with a WAV file.
+//
+// The audio is rendered via an offline stream, so neither a display nor a sound card are required.
+// Each update renders the audio up until the time of the frame that is about to be captured,
+// keeping the two in sync regardless of how fast the frames are rendered.
+
+use nannou::prelude::*;
+use nannou_audio::stream::offline;
+use nannou_audio::Buffer;
+use std::time::Duration;
+
+const FRAMES: usize = 120;
+const FPS: f64 = 30.0;
+
+fn main() {
+    nannou::app(model)
+        .headless()
+        .loop_mode(LoopMode::loop_ntimes(FRAMES))
+        .update(update)
+        .run();
+}
+
+struct Model {
+    stream: offline::Stream<Audio, fn(&mut Audio, &mut Buffer)>,
+    wav: Option<offline::WavWriter>,
+    samples: Vec<f32>,
+    frames: usize,
+    // The loudness of the audio rendered for the current frame.
+    level: f32,
+}
+
+struct Audio {
+    phase: f64,
+    time: f64,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .offscreen(512, 512)
+        .view(view)
+        .build()
+        .unwrap();
+
+    let stream = offline::Builder::new(Audio {
+        phase: 0.0,
+        time: 0.0,
+    })
+    .render(audio as fn(&mut Audio, &mut Buffer))
+    .sample_rate(48_000)
+    .channels(2)
+    .build();
+
+    let path = output_dir(app).join("audio.wav");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let wav = offline::WavWriter::create(path, stream.channels(), stream.sample_rate()).unwrap();
+
+    Model {
+        stream,
+        wav: Some(wav),
+        samples: vec![],
+        frames: 0,
+        level: 0.0,
+    }
+}
+
+// A sine tone that swells in and out twice per second.
+fn audio(audio: &mut Audio, buffer: &mut Buffer) {
+    let sample_rate = buffer.sample_rate() as f64;
+    for frame in buffer.frames_mut() {
+        let swell = (audio.time * 2.0 * std::f64::consts::TAU).sin() * 0.5 + 0.5;
+        let amp = (audio.phase * std::f64::consts::TAU).sin() * swell * 0.5;
+        audio.phase = (audio.phase + 220.0 / sample_rate) % 1.0;
+        audio.time += 1.0 / sample_rate;
+        for sample in frame {
+            *sample = amp as f32;
+        }
+    }
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    // Render the audio for the frame that is about to be captured.
+    model.frames += 1;
+    let time = Duration::from_secs_f64(model.frames as f64 / FPS);
+    model.samples.clear();
+    model.stream.render_until(time, &mut model.samples);
+    let sum: f32 = model.samples.iter().map(|s| s * s).sum();
+    model.level = (sum / model.samples.len().max(1) as f32).sqrt();
+
+    if let Some(wav) = model.wav.as_mut() {
+        wav.write(&model.samples).unwrap();
+    }
+    // Finish the WAV file once the last frame's audio is written.
+    if model.frames == FRAMES {
+        if let Some(wav) = model.wav.take() {
+            wav.finalize().unwrap();
+        }
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    draw.ellipse()
+        .radius(50.0 + model.level * 400.0)
+        .color(CORNFLOWERBLUE);
+    draw.to_frame(app, &frame).unwrap();
+
+    let path = output_dir(app)
+        .join(format!("{:03}", frame.nth()))
+        .with_extension("png");
+    app.main_window().capture_frame(path);
+}
+
+fn output_dir(app: &App) -> std::path::PathBuf {
+    app.project_path()
+        .expect("failed to locate `project_path`")
+        .join(app.exe_name().unwrap())
+}
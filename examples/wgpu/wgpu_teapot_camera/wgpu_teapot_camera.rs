@@ -2,8 +2,6 @@ use nannou::prelude::*;
 use nannou::winit;
 use std::cell::RefCell;
 
-mod data;
-
 struct Model {
     camera_is_active: bool,
     graphics: RefCell<Graphics>,
@@ -14,6 +12,7 @@ struct Graphics {
     vertex_buffer: wgpu::Buffer,
     normal_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
+    index_count: u32,
     uniform_buffer: wgpu::Buffer,
     depth_texture: wgpu::Texture,
     depth_texture_view: wgpu::TextureView,
@@ -31,19 +30,6 @@ struct Camera {
     yaw: f32,
 }
 
-// The vertex type that we will use to represent a point on our triangle.
-#[repr(C)]
-#[derive(Clone, Copy)]
-pub struct Vertex {
-    position: (f32, f32, f32),
-}
-
-#[repr(C)]
-#[derive(Copy, Clone)]
-pub struct Normal {
-    normal: (f32, f32, f32),
-}
-
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct Uniforms {
@@ -105,10 +91,16 @@ fn model(app: &App) -> Model {
     let vs_mod = device.create_shader_module(vs_desc);
     let fs_mod = device.create_shader_module(fs_desc);
 
+    // Load the teapot model. Its single mesh describes a normal for each point.
+    let teapot_path = app.assets_path().unwrap().join("models").join("teapot.obj");
+    let teapot = nannou::mesh::obj::load(teapot_path).expect("failed to load teapot model");
+    let mesh = &teapot.meshes[0];
+    let index_count = mesh.indices.len() as u32;
+
     // Create the vertex, normal and index buffers.
-    let vertices_bytes = vertices_as_bytes(&data::VERTICES);
-    let normals_bytes = normals_as_bytes(&data::NORMALS);
-    let indices_bytes = indices_as_bytes(&data::INDICES);
+    let vertices_bytes = vectors_as_bytes(&mesh.points);
+    let normals_bytes = vectors_as_bytes(&mesh.normals);
+    let indices_bytes = indices_as_bytes(&mesh.indices);
     let vertex_usage = wgpu::BufferUsages::VERTEX;
     let index_usage = wgpu::BufferUsages::INDEX;
     let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
@@ -161,6 +153,7 @@ fn model(app: &App) -> Model {
         vertex_buffer,
         normal_buffer,
         index_buffer,
+        index_count,
         uniform_buffer,
         depth_texture,
         depth_texture_view,
@@ -298,8 +291,8 @@ fn view(_app: &App, model: &Model, frame: Frame) {
     render_pass.set_pipeline(&g.render_pipeline);
     render_pass.set_vertex_buffer(0, g.vertex_buffer.slice(..));
     render_pass.set_vertex_buffer(1, g.normal_buffer.slice(..));
-    render_pass.set_index_buffer(g.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-    let index_range = 0..data::INDICES.len() as u32;
+    render_pass.set_index_buffer(g.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+    let index_range = 0..g.index_count;
     let start_vertex = 0;
     let instance_range = 0..1;
     render_pass.draw_indexed(index_range, start_vertex, instance_range);
@@ -376,8 +369,8 @@ fn create_render_pipeline(
         .color_format(dst_format)
         .color_blend(wgpu::BlendComponent::REPLACE)
         .alpha_blend(wgpu::BlendComponent::REPLACE)
-        .add_vertex_buffer::<Vertex>(&wgpu::vertex_attr_array![0 => Float32x3])
-        .add_vertex_buffer::<Normal>(&wgpu::vertex_attr_array![1 => Float32x3])
+        .add_vertex_buffer::<Vec3>(&wgpu::vertex_attr_array![0 => Float32x3])
+        .add_vertex_buffer::<Vec3>(&wgpu::vertex_attr_array![1 => Float32x3])
         .depth_format(depth_format)
         .sample_count(sample_count)
         .build(device)
@@ -385,15 +378,11 @@ fn create_render_pipeline(
 
 // See the `nannou::wgpu::bytes` documentation for why the following are necessary.
 
-fn vertices_as_bytes(data: &[Vertex]) -> &[u8] {
-    unsafe { wgpu::bytes::from_slice(data) }
-}
-
-fn normals_as_bytes(data: &[Normal]) -> &[u8] {
+fn vectors_as_bytes(data: &[Vec3]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
-fn indices_as_bytes(data: &[u16]) -> &[u8] {
+fn indices_as_bytes(data: &[u32]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
@@ -70,6 +70,7 @@ pub struct Builder<M = (), E = Event> {
     capture_frame_timeout: Option<Option<Duration>>,
     max_capture_frame_jobs: Option<u32>,
     backends: wgpu::Backends,
+    headless: bool,
 }
 
 /// A nannou `Sketch` builder.
@@ -111,6 +112,8 @@ pub struct App {
     pub(crate) event_loop_window_target: Option<EventLoopWindowTarget>,
     pub(crate) event_loop_proxy: Proxy,
     pub(crate) windows: RefCell<HashMap<window::Id, Window>>,
+    /// Whether or not the app is running without a winit event loop.
+    headless: bool,
     /// The wgpu backends to choose between.
     backends: wgpu::Backends,
     /// The main wgpu instance.
@@ -156,6 +159,14 @@ pub struct App {
     /// the number becomes higher. Instead, we recommend using `app.duration.since_start` or
     /// `app.duration.since_prev_update` to access a more precise form of app time.
     pub time: f32,
+    /// The interpolation factor between the previous and current simulation states.
+    ///
+    /// When running with `LoopMode::Rate`, this is updated on every pass of the application loop
+    /// (not only when an `Update` is emitted) to the fraction of an `update_interval` that has
+    /// accumulated but not yet been simulated. This allows `view` to smoothly blend between the
+    /// previous and current state of the model, independently of the fixed update rate. For all
+    /// other loop modes this is always `1.0`.
+    pub update_alpha: f32,
 }
 
 /// Miscellaneous app configuration parameters.
@@ -177,7 +188,8 @@ struct DrawState {
 /// **App**'s inner event loop.
 #[derive(Clone)]
 pub struct Proxy {
-    event_loop_proxy: winit::event_loop::EventLoopProxy<()>,
+    // `None` for headless apps, which have no event loop to wake up.
+    event_loop_proxy: Option<winit::event_loop::EventLoopProxy<()>>,
     // Whether or not a wakeup is already queued.
     //
     // Used to avoid spuriously calling `EventLoopProxy::send_event` as this can be expensive on
@@ -191,6 +203,13 @@ struct LoopState {
     loop_start: Instant,
     last_update: Instant,
     total_updates: u64,
+    // The moment of the previous loop iteration, used to advance the `accumulator`.
+    last_iteration: Instant,
+    // Time that has elapsed but that has not yet been consumed by fixed `Rate` updates.
+    accumulator: Duration,
+    // The `since_start` of the latest fixed update. Each fixed update advances this by exactly
+    // one interval so that fixed updates do not depend on the wall clock.
+    fixed_since_start: Option<Duration>,
 }
 
 /// The mode in which the **App** is currently running the event loop and emitting `Update` events.
@@ -203,13 +222,25 @@ pub enum LoopMode {
     /// get called at a fairly consistent interval that is close to 60 times per second.
     RefreshSync,
 
-    /// Specifies that the application is continuously looping at a consistent rate.
+    /// Emits `Update` events on a fixed timestep, independently of the display refresh rate.
+    ///
+    /// Elapsed time is accumulated on each pass of the loop, and an `Update` is emitted for every
+    /// whole `update_interval` that has accumulated. Each `Update`'s `since_last` is exactly
+    /// `update_interval` and its `since_start` advances by exactly `update_interval`, making this
+    /// mode suitable for deterministic simulations.
     ///
-    /// **NOTE:** This currently behaves the same as `RefreshSync`. Need to upate this to handled a
-    /// fix step properly in the future. See #456.
+    /// A redraw is requested on every pass of the loop. The fraction of an `update_interval` that
+    /// remains in the accumulator once the pass's updates have been applied is available via
+    /// `app.update_alpha`, allowing `view` to interpolate between the previous and current
+    /// simulation states.
     Rate {
-        /// The minimum interval between emitted updates.
+        /// The fixed interval between emitted updates.
         update_interval: Duration,
+        /// The maximum number of updates that may be emitted during a single pass of the loop.
+        ///
+        /// If more time than this has accumulated (e.g. if an update or view is slow), the excess
+        /// time is discarded in order to avoid a "spiral of death".
+        max_catch_up: u32,
     },
 
     /// Waits for user input, window, device and wake-up events to occur before producing `Update`
@@ -271,6 +302,7 @@ where
             max_capture_frame_jobs: None,
             capture_frame_timeout: None,
             backends: Self::DEFAULT_BACKENDS,
+            headless: false,
         }
     }
 
@@ -297,6 +329,7 @@ where
             max_capture_frame_jobs,
             capture_frame_timeout,
             backends,
+            headless,
             ..
         } = self;
         Builder {
@@ -310,7 +343,8 @@ where
             default_window_size,
             max_capture_frame_jobs,
             capture_frame_timeout,
-            backends
+            backends,
+            headless
         }
     }
 }
@@ -444,6 +478,27 @@ where
         self
     }
 
+    /// Run the app without creating a winit event loop or any windows on the display.
+    ///
+    /// All windows built for a headless app, including the default window, are offscreen windows
+    /// that render to a `wgpu::Texture` (see `window::Builder::offscreen`). If no hardware adapter
+    /// is available, a software adapter is used instead. This is useful for rendering stills and
+    /// animations via `capture_frame` on servers that have no display.
+    ///
+    /// As there are no windowing or input events, the app loop behaves as follows:
+    ///
+    /// - Offscreen windows are redrawn following each update.
+    /// - `LoopMode::Wait` applies a single update and then exits, as nothing may wake it.
+    /// - `LoopMode::NTimes` exits once the given number of updates have been applied.
+    /// - `LoopMode::Rate` sleeps between updates, rendering one frame per update.
+    /// - `LoopMode::RefreshSync` loops as fast as possible.
+    ///
+    /// The app also exits once all windows have been closed, e.g. via `app.quit()`.
+    pub fn headless(mut self) -> Self {
+        self.headless = true;
+        self
+    }
+
     /// Build and run an `App` with the specified parameters.
     ///
     /// This function will not return until the application has exited.
@@ -473,11 +528,16 @@ where
     }
 
     pub async fn run_async(self) {
-        // Start the winit window event loop.
-        let event_loop = winit::event_loop::EventLoop::new();
+        // Start the winit window event loop, unless running headless.
+        let event_loop = match self.headless {
+            true => None,
+            false => Some(winit::event_loop::EventLoop::new()),
+        };
 
         // Create the proxy used to awaken the event loop.
-        let event_loop_proxy = event_loop.create_proxy();
+        let event_loop_proxy = event_loop
+            .as_ref()
+            .map(|event_loop| event_loop.create_proxy());
         let wakeup_queued = Arc::new(AtomicBool::new(false));
         let event_loop_proxy = Proxy {
             event_loop_proxy,
@@ -491,7 +551,7 @@ where
         let capture_frame_timeout = self
             .capture_frame_timeout
             .unwrap_or(Some(Self::DEFAULT_CAPTURE_FRAME_TIMEOUT));
-        let event_loop_window_target = Some(EventLoopWindowTarget::Owned(event_loop));
+        let event_loop_window_target = event_loop.map(EventLoopWindowTarget::Owned);
         let app = App::new(
             self.config,
             event_loop_proxy,
@@ -500,6 +560,7 @@ where
             max_capture_frame_jobs,
             capture_frame_timeout,
             self.backends,
+            self.headless,
         );
 
         // Create the default window if necessary
@@ -522,14 +583,24 @@ where
             }
         }
 
-        run_loop(
-            app,
-            model,
-            self.event,
-            self.update,
-            self.default_view,
-            self.exit,
-        );
+        match self.headless {
+            true => run_headless_loop(
+                app,
+                model,
+                self.event,
+                self.update,
+                self.default_view,
+                self.exit,
+            ),
+            false => run_loop(
+                app,
+                model,
+                self.event,
+                self.update,
+                self.default_view,
+                self.exit,
+            ),
+        }
     }
 }
 
@@ -551,6 +622,14 @@ where
         self
     }
 
+    /// Run the sketch without a winit event loop, rendering to an offscreen window.
+    ///
+    /// This method delegates to `Builder::headless`.
+    pub fn headless(mut self) -> Self {
+        self.builder = self.builder.headless();
+        self
+    }
+
     /// Build and run a `Sketch` with the specified parameters.
     ///
     /// This calls `App::run` internally. See that method for details!
@@ -585,6 +664,8 @@ fn update_interval(fps: f64) -> Duration {
 
 impl LoopMode {
     pub const DEFAULT_RATE_FPS: f64 = 60.0;
+    /// The default maximum number of updates emitted per loop pass in **Rate** mode.
+    pub const DEFAULT_RATE_MAX_CATCH_UP: u32 = 5;
     /// The minimum number of updates that will be emitted after an event is triggered in Wait
     /// mode.
     pub const UPDATES_PER_WAIT_EVENT: u32 = 3;
@@ -598,9 +679,20 @@ impl LoopMode {
     }
 
     /// Specify the **Rate** mode with the given frames-per-second.
+    ///
+    /// Uses `DEFAULT_RATE_MAX_CATCH_UP` as the maximum number of updates per loop pass.
     pub fn rate_fps(fps: f64) -> Self {
+        Self::rate_fps_max_catch_up(fps, Self::DEFAULT_RATE_MAX_CATCH_UP)
+    }
+
+    /// Specify the **Rate** mode with the given frames-per-second and the maximum number of
+    /// updates that may be emitted during a single pass of the loop in order to catch up.
+    pub fn rate_fps_max_catch_up(fps: f64, max_catch_up: u32) -> Self {
         let update_interval = update_interval(fps);
-        LoopMode::Rate { update_interval }
+        LoopMode::Rate {
+            update_interval,
+            max_catch_up,
+        }
     }
 
     /// Specify the **Wait** mode.
@@ -654,6 +746,7 @@ impl App {
         max_capture_frame_jobs: u32,
         capture_frame_timeout: Option<Duration>,
         backends: wgpu::Backends,
+        headless: bool,
     ) -> Self {
         let instance = wgpu::Instance::new(InstanceDescriptor {
             backends,
@@ -670,6 +763,7 @@ impl App {
         let keys = state::Keys::default();
         let duration = state::Time::default();
         let time = duration.since_start.secs() as _;
+        let update_alpha = 1.0;
         let app = App {
             event_loop_proxy,
             event_loop_window_target,
@@ -681,12 +775,14 @@ impl App {
             instance,
             adapters,
             windows,
+            headless,
             config,
             draw_state,
             mouse,
             keys,
             duration,
             time,
+            update_alpha,
         };
         app
     }
@@ -699,9 +795,9 @@ impl App {
             }
             _ => {
                 let windows = self.windows.borrow();
-                match windows.values().next() {
+                match windows.values().find_map(|window| window.winit()) {
                     None => vec![],
-                    Some(window) => window.window.available_monitors().collect(),
+                    Some(window) => window.available_monitors().collect(),
                 }
             }
         }
@@ -715,12 +811,14 @@ impl App {
             Some(EventLoopWindowTarget::Owned(ref event_loop)) => event_loop.primary_monitor(),
             _ => {
                 let windows = self.windows.borrow();
-                match windows.values().next() {
+                match windows.values().find_map(|window| window.winit()) {
+                    // Headless apps have no access to the display.
+                    None if self.headless => None,
                     None => unimplemented!(
                         "yet to implement a way to get `primary_monitor` if neither \
                          event loop or window can be safely accessed"
                     ),
-                    Some(window) => window.window.primary_monitor(),
+                    Some(window) => window.primary_monitor(),
                 }
             }
         }
@@ -748,6 +846,13 @@ impl App {
         find_project_path()
     }
 
+    /// Whether or not the app is running headless, i.e. without a winit event loop.
+    ///
+    /// All windows of a headless app are offscreen windows. See `app::Builder::headless`.
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
     /// Begin building a new window.
     pub fn new_window(&self) -> window::Builder {
         let builder = window::Builder::new(self);
@@ -948,9 +1053,15 @@ impl Proxy {
     /// immediately set the flag to false afterwards. This makes it safe to call the `wakeup`
     /// method as frequently as necessary across methods without causing any underlying OS methods
     /// to be called more than necessary.
+    ///
+    /// This is a no-op for headless apps, as they have no event loop to wake up.
     pub fn wakeup(&self) -> Result<(), winit::event_loop::EventLoopClosed<()>> {
+        let event_loop_proxy = match self.event_loop_proxy {
+            Some(ref proxy) => proxy,
+            None => return Ok(()),
+        };
         if !self.wakeup_queued.load(atomic::Ordering::SeqCst) {
-            self.event_loop_proxy.send_event(())?;
+            event_loop_proxy.send_event(())?;
             self.wakeup_queued.store(true, atomic::Ordering::SeqCst);
         }
         Ok(())
@@ -1099,12 +1210,7 @@ fn run_loop<M, E>(
     };
 
     // Keep track of state related to the loop mode itself.
-    let mut loop_state = LoopState {
-        updates_since_event: 0,
-        loop_start,
-        last_update: loop_start,
-        total_updates: 0,
-    };
+    let mut loop_state = LoopState::new(loop_start);
 
     // Run the event loop.
     event_loop.run(move |mut event, event_loop_window_target, control_flow| {
@@ -1121,12 +1227,61 @@ fn run_loop<M, E>(
                 if let Some(model) = model.as_mut() {
                     let loop_mode = app.loop_mode();
                     let now = Instant::now();
+                    let since_last_iteration = now.duration_since(loop_state.last_iteration);
+                    loop_state.last_iteration = now;
+                    let recording_interval = fixed_recording_interval(&app);
+                    match loop_mode {
+                        // Updates while recording on a fixed timestep are handled below.
+                        _ if recording_interval.is_some() => {}
+                        LoopMode::Rate {
+                            update_interval,
+                            max_catch_up,
+                        } => {
+                            let updates = loop_state.accumulate(
+                                since_last_iteration,
+                                update_interval,
+                                max_catch_up,
+                            );
+                            let fixed = Some(update_interval);
+                            for _ in 0..updates {
+                                apply_update(
+                                    &mut app,
+                                    model,
+                                    event_fn,
+                                    update_fn,
+                                    &mut loop_state,
+                                    now,
+                                    fixed,
+                                );
+                            }
+                            app.update_alpha = loop_state.alpha(update_interval);
+                            // Redraw on every pass so that `view` may interpolate between states.
+                            request_redraw(&app);
+                        }
+                        _ => {
+                            loop_state.accumulator = Duration::ZERO;
+                            app.update_alpha = 1.0;
+                        }
+                    }
                     let mut do_update = |loop_state: &mut LoopState| {
-                        apply_update(&mut app, model, event_fn, update_fn, loop_state, now);
+                        apply_update(&mut app, model, event_fn, update_fn, loop_state, now, None);
                     };
                     match loop_mode {
                         LoopMode::NTimes { number_of_updates }
                             if loop_state.total_updates >= number_of_updates as u64 => {}
+                        // Exactly one update per pass while recording on a fixed timestep.
+                        _ if recording_interval.is_some() => {
+                            apply_recording_update(
+                                &mut app,
+                                model,
+                                event_fn,
+                                update_fn,
+                                &mut loop_state,
+                                recording_interval.unwrap(),
+                            );
+                        }
+                        // Updates for the `Rate` mode are handled above.
+                        LoopMode::Rate { .. } => {}
                         // Sometimes winit interrupts ControlFlow::Wait for no good reason, so we
                         // make sure that there were some events in order to do an update when
                         // LoopMode::Wait is used.
@@ -1138,6 +1293,8 @@ fn run_loop<M, E>(
                             do_update(&mut loop_state);
                         },
                     }
+                    // Offscreen windows receive no `RedrawRequested` events, so draw them here.
+                    redraw_offscreen_windows(&app, model, &default_view);
                 }
             }
 
@@ -1146,144 +1303,8 @@ fn run_loop<M, E>(
             // TODO: Only request a frame from the user if this redraw was requested following an
             // update. Otherwise, just use the existing intermediary frame.
             winit::event::Event::RedrawRequested(window_id) => {
-                if let Some(model) = model.as_mut() {
-                    // Retrieve the surface frame and the number of this frame.
-                    // NOTE: We avoid mutably borrowing `windows` map any longer than necessary to
-                    // avoid restricting users from accessing `windows` during `view`.
-                    let (mut surface_tex_result, nth_frame) = {
-                        let mut windows = app.windows.borrow_mut();
-                        let window = windows
-                            .get_mut(&window_id)
-                            .expect("no window for `RedrawRequest`");
-                        let texture = window.surface.get_current_texture();
-                        let nth_frame = window.frame_count;
-                        (texture, nth_frame)
-                    };
-
-                    if let Err(e) = &surface_tex_result {
-                        match e {
-                            // Sometimes redraws get delivered before resizes on x11 for unclear reasons.
-                            // It goes all the way down to the API: if you ask x11 about the window size
-                            // at this time, it'll tell you that it hasn't changed. So... we skip
-                            // this frame. The resize will show up in a bit and then we can get on
-                            // with our lives.
-                            // If you turn on debug logging this does occasionally cause some vulkan
-                            // validation errors... that's not great.
-                            // TODO find a better long-term fix than ignoring.
-                            wgpu::SurfaceError::Lost => {
-                                // Attempt to reconfigure the surface.
-                                let mut windows = app.windows.borrow_mut();
-                                let window = windows
-                                    .get_mut(&window_id)
-                                    .expect("no window for `RedrawRequest`");
-                                window
-                                    .reconfigure_surface(window.tracked_state.physical_size.into());
-                                surface_tex_result = window.surface.get_current_texture();
-                            }
-                            wgpu::SurfaceError::Outdated => {} // skip frame
-                            wgpu::SurfaceError::Timeout => {}  // skip frame
-                            wgpu::SurfaceError::OutOfMemory => {
-                                panic!("out of memory acquiring the surface frame: {}", e);
-                            }
-                        }
-                    }
-
-                    if let Ok(surface_tex) = surface_tex_result {
-                        let surface_texture = &surface_tex
-                            .texture
-                            .create_view(&wgpu::TextureViewDescriptor::default());
-
-                        // Borrow the window now that we don't need it mutably until setting the render
-                        // data back.
-                        let windows = app.windows.borrow();
-                        let window = windows
-                            .get(&window_id)
-                            .expect("failed to find window for redraw request");
-                        let frame_data = &window.frame_data;
-
-                        // Construct and emit a frame via `view` for receiving the user's graphics commands.
-                        let sf = window.tracked_state.scale_factor;
-                        let (w, h) = window
-                            .tracked_state
-                            .physical_size
-                            .to_logical::<f32>(sf)
-                            .into();
-                        let window_rect = geom::Rect::from_w_h(w, h);
-                        let raw_frame = RawFrame::new_empty(
-                            window.device_queue_pair().clone(),
-                            window_id,
-                            nth_frame,
-                            surface_texture,
-                            window.surface_conf.format,
-                            window_rect,
-                        );
-
-                        // Clear the raw frame immediately once the window is invalidated
-                        if window.is_invalidated {
-                            if let Some(data) = frame_data {
-                                raw_frame.clear(&data.render.texture_view(), window.clear_color);
-                            }
-                        }
-
-                        // If the user specified a view function specifically for this window, use it.
-                        // Otherwise, use the fallback, default view passed to the app if there was one.
-                        let window_view = window.user_functions.view.clone();
-
-                        match window_view {
-                            Some(window::View::Sketch(view)) => {
-                                let data = frame_data.as_ref().expect("missing `frame_data`");
-                                let frame =
-                                    Frame::new_empty(raw_frame, &data.render, &data.capture);
-                                view(&app, frame);
-                            }
-                            Some(window::View::WithModel(view)) => {
-                                let data = frame_data.as_ref().expect("missing `frame_data`");
-                                let frame =
-                                    Frame::new_empty(raw_frame, &data.render, &data.capture);
-                                let view = view.to_fn_ptr::<M>().expect(
-                                    "unexpected model argument given to window view function",
-                                );
-                                (*view)(&app, model, frame);
-                            }
-                            Some(window::View::WithModelRaw(raw_view)) => {
-                                let raw_view = raw_view.to_fn_ptr::<M>().expect(
-                                    "unexpected model argument given to window raw_view function",
-                                );
-                                (*raw_view)(&app, &model, raw_frame);
-                            }
-                            None => match default_view {
-                                Some(View::Sketch(view)) => {
-                                    let data = frame_data.as_ref().expect("missing `frame_data`");
-                                    let frame =
-                                        Frame::new_empty(raw_frame, &data.render, &data.capture);
-                                    view(&app, frame);
-                                }
-                                Some(View::WithModel(view)) => {
-                                    let data = frame_data.as_ref().expect("missing `frame_data`");
-                                    let frame =
-                                        Frame::new_empty(raw_frame, &data.render, &data.capture);
-                                    view(&app, &model, frame);
-                                }
-                                None => raw_frame.submit(),
-                            },
-                        }
-
-                        // Queue has been submitted by now, time to present.
-                        surface_tex.present();
-
-                        // Release immutable lock
-                        drop(windows);
-
-                        // Increment the window's frame count.
-                        let mut windows = app.windows.borrow_mut();
-                        let window = windows
-                            .get_mut(&window_id)
-                            .expect("no window for redraw request ID");
-
-                        // Assume invalidated window was cleared above before `view()`
-                        window.is_invalidated = false;
-                        window.frame_count += 1;
-                    }
+                if let Some(model) = model.as_ref() {
+                    redraw_window(&app, model, window_id, &default_view);
                 }
             }
 
@@ -1357,12 +1378,14 @@ fn run_loop<M, E>(
         // Set the control flow based on the loop mode.
         let loop_mode = app.loop_mode();
         *control_flow = match loop_mode {
-            LoopMode::Wait => ControlFlow::Wait,
             LoopMode::NTimes { number_of_updates }
                 if loop_state.total_updates >= number_of_updates as u64 =>
             {
                 ControlFlow::Wait
             }
+            // Recording on a fixed timestep requires a continuous loop.
+            _ if fixed_recording_interval(&app).is_some() => ControlFlow::Poll,
+            LoopMode::Wait => ControlFlow::Wait,
             _ => ControlFlow::Poll,
         };
 
@@ -1388,8 +1411,348 @@ fn run_loop<M, E>(
     }
 }
 
+impl LoopState {
+    fn new(loop_start: Instant) -> Self {
+        LoopState {
+            updates_since_event: 0,
+            loop_start,
+            last_update: loop_start,
+            total_updates: 0,
+            last_iteration: loop_start,
+            accumulator: Duration::ZERO,
+            fixed_since_start: None,
+        }
+    }
+
+    // Accumulate the given elapsed time for the `Rate` loop mode.
+    //
+    // Returns the number of fixed updates that should be emitted, consuming their time from the
+    // accumulator.
+    fn accumulate(
+        &mut self,
+        elapsed: Duration,
+        update_interval: Duration,
+        max_catch_up: u32,
+    ) -> u32 {
+        if update_interval == Duration::ZERO {
+            self.accumulator = Duration::ZERO;
+            return 1;
+        }
+        self.accumulator += elapsed;
+        let interval_nanos = update_interval.as_nanos();
+        let pending = self.accumulator.as_nanos() / interval_nanos;
+        let updates = std::cmp::min(pending, max_catch_up as u128) as u32;
+        self.accumulator -= update_interval * updates;
+        // Discard any whole intervals that we could not catch up on.
+        if self.accumulator >= update_interval {
+            let remainder = self.accumulator.as_nanos() % interval_nanos;
+            self.accumulator = Duration::from_nanos(remainder as u64);
+        }
+        updates
+    }
+
+    // The interpolation alpha for the time that remains in the accumulator, in the range
+    // `0.0..1.0`.
+    //
+    // This should be called once the updates produced by `accumulate` have been applied.
+    fn alpha(&self, update_interval: Duration) -> f32 {
+        if update_interval == Duration::ZERO {
+            return 1.0;
+        }
+        let alpha = self.accumulator.as_secs_f64() / update_interval.as_secs_f64();
+        alpha as f32
+    }
+}
+
+// The application loop used by headless apps.
+//
+// No winit event loop is created, so there are no windowing or input events. Instead, updates are
+// applied in accordance with the loop mode and offscreen windows are redrawn following each update.
+// See `Builder::headless` for details on how each loop mode behaves.
+fn run_headless_loop<M, E>(
+    mut app: App,
+    mut model: M,
+    event_fn: Option<EventFn<M, E>>,
+    update_fn: Option<UpdateFn<M>>,
+    default_view: Option<View<M>>,
+    exit_fn: Option<ExitFn<M>>,
+) where
+    M: 'static,
+    E: LoopEvent,
+{
+    // Track the moment the loop starts.
+    let loop_start = Instant::now();
+    let mut loop_state = LoopState::new(loop_start);
+
+    loop {
+        let loop_mode = app.loop_mode();
+        let now = Instant::now();
+        let since_last_iteration = now.duration_since(loop_state.last_iteration);
+        loop_state.last_iteration = now;
+        let recording_interval = fixed_recording_interval(&app);
+        match loop_mode {
+            LoopMode::NTimes { number_of_updates }
+                if loop_state.total_updates >= number_of_updates as u64 =>
+            {
+                break
+            }
+            _ if recording_interval.is_some() => {
+                let interval = recording_interval.unwrap();
+                let (app, model) = (&mut app, &mut model);
+                apply_recording_update(app, model, event_fn, update_fn, &mut loop_state, interval);
+            }
+            LoopMode::Rate {
+                update_interval,
+                max_catch_up,
+            } => {
+                let updates =
+                    loop_state.accumulate(since_last_iteration, update_interval, max_catch_up);
+                let fixed = Some(update_interval);
+                for _ in 0..updates {
+                    let (app, model) = (&mut app, &mut model);
+                    apply_update(app, model, event_fn, update_fn, &mut loop_state, now, fixed);
+                }
+                app.update_alpha = loop_state.alpha(update_interval);
+            }
+            // There are no events that could end the wait.
+            LoopMode::Wait if loop_state.updates_since_event > 0 => break,
+            _ => {
+                loop_state.accumulator = Duration::ZERO;
+                app.update_alpha = 1.0;
+                let (app, model) = (&mut app, &mut model);
+                apply_update(app, model, event_fn, update_fn, &mut loop_state, now, None);
+            }
+        }
+
+        redraw_offscreen_windows(&app, &model, &default_view);
+
+        // Without a surface to present to, nothing else polls the device. Wait for any frame
+        // captures to complete so that they make progress and don't fall behind.
+        for window in app.windows.borrow().values() {
+            if window.await_capture_frame_jobs().is_err() {
+                // TODO: Replace eprintlns with proper logging.
+                eprintln!("timed out while waiting for capture jobs to complete");
+            }
+        }
+
+        // Exit once all windows have been closed.
+        if app.windows.borrow().is_empty() {
+            break;
+        }
+
+        // Sleep until the next fixed update is due, unless recording on a fixed timestep.
+        if let (
+            LoopMode::Rate {
+                update_interval, ..
+            },
+            None,
+        ) = (loop_mode, recording_interval)
+        {
+            if let Some(remaining) = update_interval.checked_sub(loop_state.accumulator) {
+                std::thread::sleep(remaining);
+            }
+        }
+    }
+
+    if let Some(exit_fn) = exit_fn {
+        exit_fn(&app, model);
+    }
+}
+
+// Call the user's view function for the given window with a new frame and present the result.
+//
+// For windows with a surface, the frame is rendered to the surface's next texture. For offscreen
+// windows, the frame is rendered to the window's offscreen texture.
+fn redraw_window<M>(app: &App, model: &M, window_id: window::Id, default_view: &Option<View<M>>)
+where
+    M: 'static,
+{
+    // Retrieve the surface texture (if any) and the number of this frame.
+    // NOTE: We avoid mutably borrowing `windows` map any longer than necessary to avoid
+    // restricting users from accessing `windows` during `view`.
+    let (mut surface_tex_result, nth_frame) = {
+        let mut windows = app.windows.borrow_mut();
+        let window = match windows.get_mut(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        let texture = match window.target {
+            window::Target::Surface { ref surface, .. } => Some(surface.get_current_texture()),
+            window::Target::Offscreen {
+                ref mut redraw_requested,
+                ..
+            } => {
+                *redraw_requested = false;
+                None
+            }
+        };
+        let nth_frame = window.frame_count;
+        (texture, nth_frame)
+    };
+
+    if let Some(Err(e)) = &surface_tex_result {
+        match e {
+            // Sometimes redraws get delivered before resizes on x11 for unclear reasons.
+            // It goes all the way down to the API: if you ask x11 about the window size
+            // at this time, it'll tell you that it hasn't changed. So... we skip
+            // this frame. The resize will show up in a bit and then we can get on
+            // with our lives.
+            // If you turn on debug logging this does occasionally cause some vulkan
+            // validation errors... that's not great.
+            // TODO find a better long-term fix than ignoring.
+            wgpu::SurfaceError::Lost => {
+                // Attempt to reconfigure the surface.
+                let mut windows = app.windows.borrow_mut();
+                let window = windows
+                    .get_mut(&window_id)
+                    .expect("no window for `RedrawRequest`");
+                window.reconfigure_surface(window.tracked_state.physical_size.into());
+                surface_tex_result = window.surface().map(|s| s.get_current_texture());
+            }
+            wgpu::SurfaceError::Outdated => {} // skip frame
+            wgpu::SurfaceError::Timeout => {}  // skip frame
+            wgpu::SurfaceError::OutOfMemory => {
+                panic!("out of memory acquiring the surface frame: {}", e);
+            }
+        }
+    }
+
+    // Retrieve the texture for this frame, skipping the frame if the surface is unavailable.
+    let surface_tex = match surface_tex_result {
+        Some(Ok(surface_tex)) => Some(surface_tex),
+        Some(Err(_)) => return,
+        None => None,
+    };
+
+    // Borrow the window now that we don't need it mutably until setting the render
+    // data back.
+    let windows = app.windows.borrow();
+    let window = windows
+        .get(&window_id)
+        .expect("failed to find window for redraw request");
+    let frame_data = &window.frame_data;
+    let texture_view_desc = wgpu::TextureViewDescriptor::default();
+    let surface_texture = match (&surface_tex, &window.target) {
+        (Some(surface_tex), _) => surface_tex.texture.create_view(&texture_view_desc),
+        (None, window::Target::Offscreen { texture, .. }) => {
+            texture.create_view(&texture_view_desc)
+        }
+        (None, window::Target::Surface { .. }) => unreachable!(),
+    };
+
+    // Construct and emit a frame via `view` for receiving the user's graphics commands.
+    let sf = window.tracked_state.scale_factor;
+    let (w, h) = window
+        .tracked_state
+        .physical_size
+        .to_logical::<f32>(sf)
+        .into();
+    let window_rect = geom::Rect::from_w_h(w, h);
+    let raw_frame = RawFrame::new_empty(
+        window.device_queue_pair().clone(),
+        window_id,
+        nth_frame,
+        &surface_texture,
+        window.surface_conf.format,
+        window_rect,
+    );
+
+    // Clear the raw frame immediately once the window is invalidated
+    if window.is_invalidated {
+        if let Some(data) = frame_data {
+            raw_frame.clear(&data.render.texture_view(), window.clear_color);
+        }
+    }
+
+    // If the user specified a view function specifically for this window, use it.
+    // Otherwise, use the fallback, default view passed to the app if there was one.
+    let window_view = window.user_functions.view.clone();
+
+    match window_view {
+        Some(window::View::Sketch(view)) => {
+            let data = frame_data.as_ref().expect("missing `frame_data`");
+            let frame = Frame::new_empty(raw_frame, &data.render, &data.capture);
+            view(app, frame);
+        }
+        Some(window::View::WithModel(view)) => {
+            let data = frame_data.as_ref().expect("missing `frame_data`");
+            let frame = Frame::new_empty(raw_frame, &data.render, &data.capture);
+            let view = view
+                .to_fn_ptr::<M>()
+                .expect("unexpected model argument given to window view function");
+            (*view)(app, model, frame);
+        }
+        Some(window::View::WithModelRaw(raw_view)) => {
+            let raw_view = raw_view
+                .to_fn_ptr::<M>()
+                .expect("unexpected model argument given to window raw_view function");
+            (*raw_view)(app, model, raw_frame);
+        }
+        None => match default_view {
+            Some(View::Sketch(view)) => {
+                let data = frame_data.as_ref().expect("missing `frame_data`");
+                let frame = Frame::new_empty(raw_frame, &data.render, &data.capture);
+                view(app, frame);
+            }
+            Some(View::WithModel(view)) => {
+                let data = frame_data.as_ref().expect("missing `frame_data`");
+                let frame = Frame::new_empty(raw_frame, &data.render, &data.capture);
+                view(app, model, frame);
+            }
+            None => raw_frame.submit(),
+        },
+    }
+
+    // Queue has been submitted by now, time to present.
+    if let Some(surface_tex) = surface_tex {
+        surface_tex.present();
+    }
+
+    // Release immutable lock
+    drop(windows);
+
+    // Increment the window's frame count.
+    let mut windows = app.windows.borrow_mut();
+    let window = windows
+        .get_mut(&window_id)
+        .expect("no window for redraw request ID");
+
+    // Assume invalidated window was cleared above before `view()`
+    window.is_invalidated = false;
+    window.frame_count += 1;
+}
+
+// Redraw all offscreen windows for which a redraw has been requested, first applying any sizes
+// requested via `set_inner_size_*`.
+fn redraw_offscreen_windows<M>(app: &App, model: &M, default_view: &Option<View<M>>)
+where
+    M: 'static,
+{
+    for window in app.windows.borrow_mut().values_mut() {
+        window.apply_requested_size();
+    }
+    let window_ids: Vec<_> = app
+        .windows
+        .borrow()
+        .iter()
+        .filter(|(_, window)| match window.target {
+            window::Target::Offscreen {
+                redraw_requested, ..
+            } => redraw_requested,
+            window::Target::Surface { .. } => false,
+        })
+        .map(|(&id, _)| id)
+        .collect();
+    for window_id in window_ids {
+        redraw_window(app, model, window_id, default_view);
+    }
+}
+
 // Apply an update to the model via the user's function and update the app and loop state
 // accordingly.
+//
+// If `fixed` is `Some` non-zero interval, the update is a fixed step. Fixed steps advance
+// `since_start` by exactly one interval, independently of the wall clock.
 fn apply_update<M, E>(
     app: &mut App,
     model: &mut M,
@@ -1397,13 +1760,27 @@ fn apply_update<M, E>(
     update_fn: Option<UpdateFn<M>>,
     loop_state: &mut LoopState,
     now: Instant,
+    fixed: Option<Duration>,
 ) where
     M: 'static,
     E: LoopEvent,
 {
     // Update the app's durations.
-    let since_last = now.duration_since(loop_state.last_update);
-    let since_start = now.duration_since(loop_state.loop_start);
+    let (since_last, since_start) = match fixed {
+        Some(update_interval) if update_interval > Duration::ZERO => {
+            let prev = loop_state
+                .fixed_since_start
+                .unwrap_or_else(|| loop_state.last_update.duration_since(loop_state.loop_start));
+            let since_start = prev + update_interval;
+            loop_state.fixed_since_start = Some(since_start);
+            (update_interval, since_start)
+        }
+        _ => {
+            loop_state.fixed_since_start = None;
+            let since_last = now.duration_since(loop_state.last_update);
+            (since_last, now.duration_since(loop_state.loop_start))
+        }
+    };
     app.duration.since_prev_update = since_last;
     app.duration.since_start = since_start;
     app.time = since_start.secs() as _;
@@ -1423,10 +1800,45 @@ fn apply_update<M, E>(
     loop_state.last_update = now;
     loop_state.total_updates += 1;
     loop_state.updates_since_event += 1;
-    // Request redraw from windows.
+    request_redraw(app);
+}
+
+// Apply a single update on the fixed timestep of an active recording.
+//
+// Time is advanced by exactly one frame `interval` regardless of how much time has actually
+// elapsed, so that recorded output does not depend on render speed.
+fn apply_recording_update<M, E>(
+    app: &mut App,
+    model: &mut M,
+    event_fn: Option<EventFn<M, E>>,
+    update_fn: Option<UpdateFn<M>>,
+    loop_state: &mut LoopState,
+    interval: Duration,
+) where
+    M: 'static,
+    E: LoopEvent,
+{
+    loop_state.accumulator = Duration::ZERO;
+    app.update_alpha = 1.0;
+    let now = loop_state.last_update + interval;
+    let fixed = Some(interval);
+    apply_update(app, model, event_fn, update_fn, loop_state, now, fixed);
+}
+
+// The frame interval of the shortest active recording that requires a fixed timestep, if any.
+fn fixed_recording_interval(app: &App) -> Option<Duration> {
     let windows = app.windows.borrow();
-    for window in windows.values() {
-        window.window.request_redraw();
+    windows
+        .values()
+        .filter_map(|window| window.fixed_recording_interval())
+        .min()
+}
+
+// Request a redraw from all windows.
+fn request_redraw(app: &App) {
+    let mut windows = app.windows.borrow_mut();
+    for window in windows.values_mut() {
+        window.request_redraw();
     }
 }
 
@@ -1748,3 +2160,53 @@ where
 
     exit
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LoopState;
+    use std::time::{Duration, Instant};
+
+    const INTERVAL: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn accumulate_whole_intervals() {
+        let mut state = LoopState::new(Instant::now());
+        assert_eq!(state.accumulate(Duration::from_millis(25), INTERVAL, 5), 2);
+        assert!((state.alpha(INTERVAL) - 0.5).abs() < 1e-6);
+        assert_eq!(state.accumulate(Duration::from_millis(5), INTERVAL, 5), 1);
+        assert_eq!(state.alpha(INTERVAL), 0.0);
+    }
+
+    #[test]
+    fn accumulate_caps_catch_up() {
+        let mut state = LoopState::new(Instant::now());
+        assert_eq!(
+            state.accumulate(Duration::from_millis(1_003), INTERVAL, 4),
+            4
+        );
+        // The excess whole intervals are discarded while the fraction is retained.
+        assert!((state.alpha(INTERVAL) - 0.3).abs() < 1e-6);
+        assert_eq!(state.accumulate(Duration::ZERO, INTERVAL, 4), 0);
+    }
+
+    #[test]
+    fn alpha_range() {
+        let mut state = LoopState::new(Instant::now());
+        for micros in (0..50_000).step_by(777) {
+            state.accumulate(Duration::from_micros(micros), INTERVAL, 3);
+            let alpha = state.alpha(INTERVAL);
+            assert!((0.0..1.0).contains(&alpha), "{}", alpha);
+        }
+    }
+
+    #[test]
+    fn accumulate_zero_interval() {
+        let mut state = LoopState::new(Instant::now());
+        assert_eq!(
+            state.accumulate(Duration::from_millis(25), Duration::ZERO, 5),
+            1
+        );
+        assert_eq!(state.alpha(Duration::ZERO), 1.0);
+        assert_eq!(state.accumulate(Duration::ZERO, Duration::ZERO, 5), 1);
+    }
+}
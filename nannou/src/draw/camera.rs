@@ -0,0 +1,150 @@
+//! The **Camera** through which a **Draw** instance's graphics are projected onto the output.
+
+use crate::glam::{Mat4, Vec2, Vec3};
+
+/// Describes the view and projection used to map the **Draw** API's "world space" (logical pixel
+/// coordinates with the origin at the centre of the output) onto the output attachment.
+///
+/// By default, the camera uses an orthographic projection where one unit along the *x* and *y*
+/// axes is equal to one logical pixel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// The projection used to map view space onto the output.
+    pub projection: Projection,
+    /// A transform from world space to view space.
+    ///
+    /// If `None`, a default view is used. For orthographic projections this is the identity
+    /// matrix. For perspective projections, the camera is placed on the positive *z* axis looking
+    /// towards the origin at a distance where the `z = 0` plane spans the output exactly.
+    pub view: Option<Mat4>,
+    /// The distance to the near clipping plane.
+    ///
+    /// Ignored by **Custom** projections. See **Camera::clip_planes** for default values.
+    pub near: Option<f32>,
+    /// The distance to the far clipping plane.
+    ///
+    /// Ignored by **Custom** projections. See **Camera::clip_planes** for default values.
+    pub far: Option<f32>,
+}
+
+/// The projection used by a **Camera**.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// A parallel projection spanning the logical dimensions of the output.
+    Orthographic,
+    /// A perspective projection with the given vertical field of view in radians.
+    Perspective { fov_y: f32 },
+    /// A user-provided projection matrix.
+    ///
+    /// The matrix should map view space into wgpu's clip space, where *z* is in the range `0.0`
+    /// to `1.0`.
+    Custom(Mat4),
+}
+
+impl Camera {
+    /// A reasonable default vertical field of view for perspective cameras, in radians.
+    pub const DEFAULT_FOV_Y: f32 = std::f32::consts::FRAC_PI_3;
+
+    /// An orthographic camera matching the default behaviour of **Draw**.
+    pub fn orthographic() -> Self {
+        Self::from(Projection::Orthographic)
+    }
+
+    /// A perspective camera with the given vertical field of view in radians.
+    pub fn perspective(fov_y: f32) -> Self {
+        Self::from(Projection::Perspective { fov_y })
+    }
+
+    /// A camera with the given custom projection matrix.
+    pub fn custom(projection: Mat4) -> Self {
+        Self::from(Projection::Custom(projection))
+    }
+
+    /// Specify the transform from world space to view space.
+    pub fn view(mut self, view: Mat4) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    /// Position the camera at `eye`, looking towards `target` with the given `up` direction.
+    pub fn look_at(self, eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        self.view(Mat4::look_at_rh(eye, target, up))
+    }
+
+    /// Specify the distances to the near and far clipping planes.
+    ///
+    /// By default, orthographic cameras clip at `-max_side` and `max_side`, where `max_side` is
+    /// the largest logical dimension of the output. Perspective cameras clip at one hundredth of
+    /// their default distance from the origin and at that distance plus `max_side`.
+    pub fn clip_planes(mut self, near: f32, far: f32) -> Self {
+        self.near = Some(near);
+        self.far = Some(far);
+        self
+    }
+
+    /// The distance from the `z = 0` plane at which a perspective camera with the given vertical
+    /// field of view sees exactly `h` units along the *y* axis.
+    pub fn perspective_distance(fov_y: f32, h: f32) -> f32 {
+        h * 0.5 / (fov_y * 0.5).tan()
+    }
+
+    /// The view matrix for an output with the given logical dimensions.
+    pub fn view_matrix(&self, logical_size: Vec2) -> Mat4 {
+        if let Some(view) = self.view {
+            return view;
+        }
+        match self.projection {
+            Projection::Perspective { fov_y } => {
+                let distance = Self::perspective_distance(fov_y, logical_size.y);
+                Mat4::look_at_rh(Vec3::Z * distance, Vec3::ZERO, Vec3::Y)
+            }
+            Projection::Orthographic | Projection::Custom(_) => Mat4::IDENTITY,
+        }
+    }
+
+    /// The projection matrix for an output with the given logical dimensions.
+    pub fn projection_matrix(&self, logical_size: Vec2) -> Mat4 {
+        let max_side = logical_size.x.max(logical_size.y);
+        match self.projection {
+            Projection::Orthographic => {
+                let right = logical_size.x * 0.5;
+                let top = logical_size.y * 0.5;
+                let near = self.near.unwrap_or(-max_side);
+                let far = self.far.unwrap_or(max_side);
+                Mat4::orthographic_rh(-right, right, -top, top, near, far)
+            }
+            Projection::Perspective { fov_y } => {
+                let distance = Self::perspective_distance(fov_y, logical_size.y);
+                let near = self.near.unwrap_or(distance * 0.01);
+                let far = self.far.unwrap_or(distance + max_side);
+                let aspect = logical_size.x / logical_size.y;
+                Mat4::perspective_rh(fov_y, aspect, near, far)
+            }
+            Projection::Custom(proj) => proj,
+        }
+    }
+
+    /// The combined view and projection matrix for an output with the given logical dimensions.
+    ///
+    /// This is the transform applied to all vertices by the **draw::Renderer**.
+    pub fn view_projection(&self, logical_size: Vec2) -> Mat4 {
+        self.projection_matrix(logical_size) * self.view_matrix(logical_size)
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::orthographic()
+    }
+}
+
+impl From<Projection> for Camera {
+    fn from(projection: Projection) -> Self {
+        Camera {
+            projection,
+            view: None,
+            near: None,
+            far: None,
+        }
+    }
+}
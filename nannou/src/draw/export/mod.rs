@@ -0,0 +1,326 @@
+//! Export the commands of a **Draw** instance to vector formats, i.e. SVG and PDF.
+//!
+//! Paths, polygons, ellipses, rects, quads, tris, lines and arrows are exported as vector paths
+//! that preserve their fill or stroke properties, colors and transforms. Text is exported as the
+//! outlines of its glyphs. Scissors are exported as clipping rectangles.
+//!
+//! All other geometry, e.g. meshes and 3D solids, is exported as flat colored triangles projected
+//! onto the XY plane. Textures and textured geometry are skipped, as the pixels of the textures
+//! are not available to the exporter. Cameras, lights and blend modes are ignored.
+//!
+//! ```no_run
+//! # use nannou::prelude::*;
+//! # fn view(app: &App, frame: Frame) {
+//! let draw = app.draw();
+//! draw.ellipse().color(STEELBLUE);
+//! // Export before rendering, as rendering drains the **Draw** of its commands.
+//! draw.export(app.window_rect()).save("ellipse.svg").unwrap();
+//! draw.to_frame(app, &frame).unwrap();
+//! # }
+//! ```
+
+use crate::color::LinSrgba;
+use crate::draw::mesh::vertex::Color;
+use crate::draw::primitive::path::Options;
+use crate::draw::renderer::{GlyphCache, RenderContext, RenderPrimitive, Renderer, TextMode};
+use crate::draw::{self, Draw, DrawCommand, Scissor};
+use crate::geom::{self, Point2};
+use crate::glam::{Mat4, Vec2};
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillRule, FillTessellator, StrokeOptions, StrokeTessellator};
+use std::path::Path;
+use std::{fs, io};
+
+mod pdf;
+mod svg;
+
+/// A vector representation of the drawing of a **Draw** instance, ready to be written as SVG or
+/// PDF.
+#[derive(Clone, Debug)]
+pub struct Document {
+    /// The region of the drawing that is covered by the page.
+    pub rect: geom::Rect,
+    /// The color with which the page is cleared, if any.
+    pub background: Option<LinSrgba>,
+    /// The exported elements, in the order in which they are drawn.
+    pub elements: Vec<Element>,
+}
+
+/// A single shape within a **Document**.
+#[derive(Clone, Debug)]
+pub struct Element {
+    /// The shape to draw.
+    pub shape: Shape,
+    /// The rectangle to which the shape is clipped, if any, in the drawing's coordinates.
+    pub clip: Option<geom::Rect>,
+}
+
+/// The geometry of a single **Element**.
+#[derive(Clone, Debug)]
+pub enum Shape {
+    /// A filled or stroked path.
+    Path(VectorPath),
+    /// A filled triangle, in the drawing's coordinates.
+    Triangle {
+        points: [Point2; 3],
+        color: LinSrgba,
+    },
+}
+
+/// A path along with the way in which it is painted.
+#[derive(Clone, Debug)]
+pub struct VectorPath {
+    /// The events describing the path in its local coordinates.
+    pub events: Vec<PathEvent>,
+    /// The transform from the path's local coordinates to the drawing's coordinates.
+    ///
+    /// Only the 2D affine part of the transform is exported.
+    pub transform: Mat4,
+    /// How the path is painted.
+    pub paint: Paint,
+}
+
+/// The way in which a **VectorPath** is painted.
+#[derive(Clone, Debug)]
+pub enum Paint {
+    /// Fill the inside of the path.
+    Fill { color: LinSrgba, rule: FillRule },
+    /// Stroke the path's outline.
+    Stroke {
+        color: LinSrgba,
+        options: StrokeOptions,
+    },
+}
+
+impl Document {
+    /// Export the commands of the given **Draw** instance.
+    ///
+    /// The `rect` describes the region of the drawing covered by the page, e.g.
+    /// `app.window_rect()`. The **Draw** is not drained of its commands, so it may still be
+    /// rendered afterwards.
+    pub fn new(draw: &Draw, rect: geom::Rect) -> Self {
+        draw.finish_remaining_drawings();
+        let state = draw.state.borrow();
+        let intermediary_state = state.intermediary_state.borrow();
+        let commands: Vec<DrawCommand> = state.draw_commands.iter().flatten().cloned().collect();
+
+        // Text is exported via its outlines, so the glyph cache is never populated.
+        let mut glyph_cache = GlyphCache::new(
+            [1, 1],
+            Renderer::DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE,
+            Renderer::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE,
+            TextMode::Bitmap,
+        );
+        let mut fill_tessellator = FillTessellator::new();
+        let mut stroke_tessellator = StrokeTessellator::new();
+        let mut sdf_edges = vec![];
+        let mut vertex_modes = vec![];
+        let mut vector_paths = vec![];
+        let mut mesh = draw::Mesh::default();
+
+        let mut elements = vec![];
+        let mut curr_ctxt = draw::Context::default();
+        for cmd in commands {
+            let prim = match cmd {
+                DrawCommand::Context(ctxt) => {
+                    curr_ctxt = ctxt;
+                    continue;
+                }
+                DrawCommand::Primitive(prim) => prim,
+            };
+            let clip = match curr_ctxt.scissor {
+                Scissor::Full => None,
+                Scissor::Rect(rect) => Some(rect),
+                Scissor::NoOverlap => continue,
+            };
+
+            let ctxt = RenderContext {
+                intermediary_mesh: &intermediary_state.intermediary_mesh,
+                path_event_buffer: &intermediary_state.path_event_buffer,
+                path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+                path_points_textured_buffer: &intermediary_state.path_points_textured_buffer,
+                text_buffer: &intermediary_state.text_buffer,
+                theme: &state.theme,
+                transform: &curr_ctxt.transform,
+                fill_tessellator: &mut fill_tessellator,
+                stroke_tessellator: &mut stroke_tessellator,
+                vector_paths: Some(&mut vector_paths),
+                glyph_cache: &mut glyph_cache,
+                sdf_edges: &mut sdf_edges,
+                vertex_modes: &mut vertex_modes,
+                output_attachment_size: Vec2::new(rect.w(), rect.h()),
+                output_attachment_scale_factor: 1.0,
+            };
+            let render = prim.render_primitive(ctxt, &mut mesh);
+            vertex_modes.resize(mesh.points().len(), render.vertex_mode);
+
+            // Paths are recorded as they are.
+            for path in vector_paths.drain(..) {
+                let shape = Shape::Path(path);
+                elements.push(Element { shape, clip });
+            }
+
+            // Any geometry that was tessellated into the mesh is exported as triangles.
+            for tri in mesh.indices().chunks_exact(3) {
+                let ix = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+                if ix
+                    .iter()
+                    .any(|&i| vertex_modes[i] != draw::renderer::VertexMode::Color)
+                {
+                    continue;
+                }
+                let points = ix.map(|i| mesh.points()[i].truncate());
+                let color = average_color(ix.iter().map(|&i| mesh.colors()[i]));
+                let shape = Shape::Triangle { points, color };
+                elements.push(Element { shape, clip });
+            }
+            mesh.clear();
+            vertex_modes.clear();
+            sdf_edges.clear();
+        }
+
+        Document {
+            rect,
+            background: state.background_color,
+            elements,
+        }
+    }
+
+    /// Write the document as an SVG image.
+    pub fn to_svg(&self) -> String {
+        svg::write(self)
+    }
+
+    /// Write the document as a single page PDF.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        pdf::write(self)
+    }
+
+    /// Save the document to the given path as either SVG or PDF, depending on the path's
+    /// extension.
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("svg") => fs::write(path, self.to_svg()),
+            Some("pdf") => fs::write(path, self.to_pdf()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "expected a path with an `svg` or `pdf` extension",
+            )),
+        }
+    }
+}
+
+impl VectorPath {
+    /// A path with the given events, transform and paint.
+    pub fn new(events: Vec<PathEvent>, transform: Mat4, paint: Paint) -> Self {
+        VectorPath {
+            events,
+            transform,
+            paint,
+        }
+    }
+}
+
+// Produce the vector paths for a path with a color per point.
+//
+// Filled paths use the average color of their points. Stroked paths with differing colors are
+// split into a path per line segment, each colored by the segment's start point.
+pub(crate) fn colored_points_paths(
+    points: &[(Point2, Color)],
+    close: bool,
+    transform: Mat4,
+    options: Options,
+) -> Vec<VectorPath> {
+    let polyline = |points: &mut dyn Iterator<Item = Point2>, close: bool| -> Vec<PathEvent> {
+        let points = points.map(|p| lyon::math::point(p.x, p.y));
+        lyon::path::iterator::FromPolyline::new(close, points).collect()
+    };
+    if points.is_empty() {
+        return vec![];
+    }
+    match options {
+        Options::Fill(options) => {
+            let events = polyline(&mut points.iter().map(|&(p, _)| p), close);
+            let color = average_color(points.iter().map(|&(_, c)| c));
+            let paint = Paint::Fill {
+                color,
+                rule: options.fill_rule,
+            };
+            vec![VectorPath::new(events, transform, paint)]
+        }
+        Options::Stroke(options) => {
+            let color = points[0].1;
+            if points.iter().all(|&(_, c)| c == color) {
+                let events = polyline(&mut points.iter().map(|&(p, _)| p), close);
+                let paint = Paint::Stroke { color, options };
+                return vec![VectorPath::new(events, transform, paint)];
+            }
+            let closing = match close {
+                true => Some((points[points.len() - 1], points[0])),
+                false => None,
+            };
+            points
+                .windows(2)
+                .map(|w| (w[0], w[1]))
+                .chain(closing)
+                .map(|((a, color), (b, _))| {
+                    let events = polyline(&mut [a, b].iter().cloned(), false);
+                    let paint = Paint::Stroke { color, options };
+                    VectorPath::new(events, transform, paint)
+                })
+                .collect()
+        }
+    }
+}
+
+fn average_color<I>(colors: I) -> LinSrgba
+where
+    I: IntoIterator<Item = LinSrgba>,
+{
+    let (mut sum, mut n) = ([0.0; 4], 0.0);
+    for c in colors {
+        let (r, g, b, a) = c.into_components();
+        for (s, v) in sum.iter_mut().zip(&[r, g, b, a]) {
+            *s += v;
+        }
+        n += 1.0;
+    }
+    if n == 0.0 {
+        return LinSrgba::new(0.0, 0.0, 0.0, 0.0);
+    }
+    LinSrgba::new(sum[0] / n, sum[1] / n, sum[2] / n, sum[3] / n)
+}
+
+// The 2D affine part of the given transform as `[a, b, c, d, e, f]`, mapping `(x, y)` to
+// `(a * x + c * y + e, b * x + d * y + f)`.
+fn affine(m: &Mat4) -> [f32; 6] {
+    [
+        m.x_axis.x, m.x_axis.y, m.y_axis.x, m.y_axis.y, m.w_axis.x, m.w_axis.y,
+    ]
+}
+
+// The given linear color as 8-bit sRGB channels along with its alpha.
+fn srgb8(color: LinSrgba) -> ([u8; 3], f32) {
+    let c = crate::color::Srgba::from_linear(color);
+    let u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    (
+        [u8(c.red), u8(c.green), u8(c.blue)],
+        c.alpha.clamp(0.0, 1.0),
+    )
+}
+
+// Format the given number compactly, rounding to four decimal places.
+fn num(v: f32) -> String {
+    let v = (v * 10_000.0).round() / 10_000.0;
+    // Avoid writing `-0`.
+    let v = if v == 0.0 { 0.0 } else { v };
+    format!("{}", v)
+}
@@ -0,0 +1,220 @@
+//! Writing a **Document** as a single page PDF.
+
+use super::{affine, num, srgb8, Document, Paint, Shape, VectorPath};
+use crate::color::LinSrgba;
+use crate::geom;
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillRule, LineCap, LineJoin};
+use std::fmt::Write;
+
+/// Write the given document as a single page PDF.
+///
+/// The page is one point per unit of the document's rect. The content stream is uncompressed.
+pub fn write(doc: &Document) -> Vec<u8> {
+    let r = doc.rect;
+    let mut content = String::new();
+    // Each distinct alpha requires its own graphics state.
+    let mut alphas: Vec<f32> = vec![];
+
+    if let Some(bg) = doc.background {
+        content.push_str("q ");
+        let gs = set_color(&mut content, &mut alphas, bg, "rg");
+        writeln!(
+            content,
+            "/GS{} gs 0 0 {} {} re f Q",
+            gs,
+            num(r.w()),
+            num(r.h())
+        )
+        .unwrap();
+    }
+
+    // PDF's y axis points up like the drawing's, so only the origin needs moving.
+    writeln!(
+        content,
+        "1 0 0 1 {} {} cm",
+        num(-r.left()),
+        num(-r.bottom())
+    )
+    .unwrap();
+
+    let mut curr_clip: Option<geom::Rect> = None;
+    for elem in &doc.elements {
+        if elem.clip != curr_clip {
+            if curr_clip.is_some() {
+                content.push_str("Q\n");
+            }
+            if let Some(c) = elem.clip {
+                writeln!(
+                    content,
+                    "q {} {} {} {} re W n",
+                    num(c.left()),
+                    num(c.bottom()),
+                    num(c.w()),
+                    num(c.h())
+                )
+                .unwrap();
+            }
+            curr_clip = elem.clip;
+        }
+        match elem.shape {
+            Shape::Path(ref path) => write_path(&mut content, &mut alphas, path),
+            Shape::Triangle { points, color } => {
+                content.push_str("q ");
+                let gs = set_color(&mut content, &mut alphas, color, "rg");
+                let [a, b, c] = points;
+                writeln!(
+                    content,
+                    "/GS{} gs {} {} m {} {} l {} {} l h f Q",
+                    gs,
+                    num(a.x),
+                    num(a.y),
+                    num(b.x),
+                    num(b.y),
+                    num(c.x),
+                    num(c.y)
+                )
+                .unwrap();
+            }
+        }
+    }
+    if curr_clip.is_some() {
+        content.push_str("Q\n");
+    }
+
+    let mut ext_g_states = String::new();
+    for (i, a) in alphas.iter().enumerate() {
+        write!(ext_g_states, "/GS{} << /ca {a} /CA {a} >> ", i, a = num(*a)).unwrap();
+    }
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /ExtGState << {}>> >> /Contents 4 0 R >>",
+            num(r.w()),
+            num(r.h()),
+            ext_g_states,
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ),
+    ];
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = vec![];
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        writeln!(pdf, "{} 0 obj\n{}\nendobj", i + 1, obj).unwrap();
+    }
+    let xref = pdf.len();
+    writeln!(pdf, "xref\n0 {}", objects.len() + 1).unwrap();
+    pdf.push_str("0000000000 65535 f \n");
+    for offset in offsets {
+        writeln!(pdf, "{:010} 00000 n ", offset).unwrap();
+    }
+    write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    )
+    .unwrap();
+    pdf.into_bytes()
+}
+
+fn write_path(s: &mut String, alphas: &mut Vec<f32>, path: &VectorPath) {
+    if path.events.is_empty() {
+        return;
+    }
+    let m = affine(&path.transform);
+    writeln!(
+        s,
+        "q {} {} {} {} {} {} cm",
+        num(m[0]),
+        num(m[1]),
+        num(m[2]),
+        num(m[3]),
+        num(m[4]),
+        num(m[5])
+    )
+    .unwrap();
+    let op = match path.paint {
+        Paint::Fill { color, rule } => {
+            let gs = set_color(s, alphas, color, "rg");
+            writeln!(s, "/GS{} gs", gs).unwrap();
+            match rule {
+                FillRule::EvenOdd => "f*",
+                FillRule::NonZero => "f",
+            }
+        }
+        Paint::Stroke { color, options } => {
+            let gs = set_color(s, alphas, color, "RG");
+            let cap = match options.start_cap {
+                LineCap::Butt => 0,
+                LineCap::Round => 1,
+                LineCap::Square => 2,
+            };
+            let join = match options.line_join {
+                LineJoin::Miter | LineJoin::MiterClip => 0,
+                LineJoin::Round => 1,
+                LineJoin::Bevel => 2,
+            };
+            writeln!(
+                s,
+                "/GS{} gs {} w {} J {} j {} M",
+                gs,
+                num(options.line_width),
+                cap,
+                join,
+                num(options.miter_limit)
+            )
+            .unwrap();
+            "S"
+        }
+    };
+    write_path_ops(s, &path.events);
+    writeln!(s, "{} Q", op).unwrap();
+}
+
+// Write the path construction operators for the given events.
+fn write_path_ops(s: &mut String, events: &[PathEvent]) {
+    let p = |p: lyon::math::Point| format!("{} {}", num(p.x), num(p.y));
+    for event in events {
+        match *event {
+            PathEvent::Begin { at } => writeln!(s, "{} m", p(at)).unwrap(),
+            PathEvent::Line { to, .. } => writeln!(s, "{} l", p(to)).unwrap(),
+            // PDF has no quadratic curves, so raise them to cubics.
+            PathEvent::Quadratic { from, ctrl, to } => {
+                let ctrl1 = from + (ctrl - from) * (2.0 / 3.0);
+                let ctrl2 = to + (ctrl - to) * (2.0 / 3.0);
+                writeln!(s, "{} {} {} c", p(ctrl1), p(ctrl2), p(to)).unwrap();
+            }
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => writeln!(s, "{} {} {} c", p(ctrl1), p(ctrl2), p(to)).unwrap(),
+            PathEvent::End { close, .. } => {
+                if close {
+                    s.push_str("h\n");
+                }
+            }
+        }
+    }
+}
+
+// Write the color operator for the given color and return the index of the graphics state that
+// applies its alpha.
+fn set_color(s: &mut String, alphas: &mut Vec<f32>, color: LinSrgba, op: &str) -> usize {
+    let ([r, g, b], a) = srgb8(color);
+    let c = |v: u8| num(v as f32 / 255.0);
+    write!(s, "{} {} {} {} ", c(r), c(g), c(b), op).unwrap();
+    let a = (a * 10_000.0).round() / 10_000.0;
+    match alphas.iter().position(|&x| x == a) {
+        Some(i) => i,
+        None => {
+            alphas.push(a);
+            alphas.len() - 1
+        }
+    }
+}
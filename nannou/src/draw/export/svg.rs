@@ -0,0 +1,170 @@
+//! Writing a **Document** as SVG.
+
+use super::{affine, num, srgb8, Document, Paint, Shape, VectorPath};
+use crate::color::LinSrgba;
+use crate::geom;
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillRule, LineCap, LineJoin};
+use std::fmt::Write;
+
+/// Write the given document as an SVG image.
+pub fn write(doc: &Document) -> String {
+    let r = doc.rect;
+    let mut s = String::new();
+    writeln!(
+        s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = num(r.w()),
+        h = num(r.h()),
+    )
+    .unwrap();
+    if let Some(bg) = doc.background {
+        writeln!(
+            s,
+            r#"<rect width="100%" height="100%" {}/>"#,
+            color_attrs("fill", bg)
+        )
+        .unwrap();
+    }
+
+    // Flip the y axis so that the drawing's coordinates may be used throughout.
+    writeln!(
+        s,
+        r#"<g transform="matrix(1 0 0 -1 {} {})">"#,
+        num(-r.left()),
+        num(r.top())
+    )
+    .unwrap();
+
+    let mut clips = 0;
+    let mut curr_clip: Option<geom::Rect> = None;
+    for elem in &doc.elements {
+        if elem.clip != curr_clip {
+            if curr_clip.is_some() {
+                s.push_str("</g>\n");
+            }
+            if let Some(c) = elem.clip {
+                writeln!(
+                    s,
+                    r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                    clips,
+                    num(c.left()),
+                    num(c.bottom()),
+                    num(c.w()),
+                    num(c.h()),
+                )
+                .unwrap();
+                writeln!(s, r#"<g clip-path="url(#clip{})">"#, clips).unwrap();
+                clips += 1;
+            }
+            curr_clip = elem.clip;
+        }
+        match elem.shape {
+            Shape::Path(ref path) => write_path(&mut s, path),
+            Shape::Triangle { points, color } => {
+                let [a, b, c] = points;
+                writeln!(
+                    s,
+                    r#"<path d="M{} {}L{} {}L{} {}Z" {}/>"#,
+                    num(a.x),
+                    num(a.y),
+                    num(b.x),
+                    num(b.y),
+                    num(c.x),
+                    num(c.y),
+                    color_attrs("fill", color),
+                )
+                .unwrap();
+            }
+        }
+    }
+    if curr_clip.is_some() {
+        s.push_str("</g>\n");
+    }
+    s.push_str("</g>\n</svg>\n");
+    s
+}
+
+fn write_path(s: &mut String, path: &VectorPath) {
+    let d = path_data(&path.events);
+    if d.is_empty() {
+        return;
+    }
+    let m = affine(&path.transform);
+    write!(
+        s,
+        r#"<path d="{}" transform="matrix({} {} {} {} {} {})" "#,
+        d,
+        num(m[0]),
+        num(m[1]),
+        num(m[2]),
+        num(m[3]),
+        num(m[4]),
+        num(m[5]),
+    )
+    .unwrap();
+    match path.paint {
+        Paint::Fill { color, rule } => {
+            let rule = match rule {
+                FillRule::EvenOdd => "evenodd",
+                FillRule::NonZero => "nonzero",
+            };
+            write!(s, r#"{} fill-rule="{}""#, color_attrs("fill", color), rule).unwrap();
+        }
+        Paint::Stroke { color, options } => {
+            let cap = match options.start_cap {
+                LineCap::Butt => "butt",
+                LineCap::Square => "square",
+                LineCap::Round => "round",
+            };
+            let join = match options.line_join {
+                LineJoin::Miter | LineJoin::MiterClip => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            };
+            write!(
+                s,
+                r#"fill="none" {} stroke-width="{}" stroke-linecap="{}" stroke-linejoin="{}" stroke-miterlimit="{}""#,
+                color_attrs("stroke", color),
+                num(options.line_width),
+                cap,
+                join,
+                num(options.miter_limit),
+            )
+            .unwrap();
+        }
+    }
+    s.push_str("/>\n");
+}
+
+// The SVG path data for the given events.
+fn path_data(events: &[PathEvent]) -> String {
+    let mut d = String::new();
+    let p = |p: lyon::math::Point| format!("{} {}", num(p.x), num(p.y));
+    for event in events {
+        match *event {
+            PathEvent::Begin { at } => write!(d, "M{}", p(at)).unwrap(),
+            PathEvent::Line { to, .. } => write!(d, "L{}", p(to)).unwrap(),
+            PathEvent::Quadratic { ctrl, to, .. } => write!(d, "Q{} {}", p(ctrl), p(to)).unwrap(),
+            PathEvent::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => write!(d, "C{} {} {}", p(ctrl1), p(ctrl2), p(to)).unwrap(),
+            PathEvent::End { close, .. } => {
+                if close {
+                    d.push('Z');
+                }
+            }
+        }
+    }
+    d
+}
+
+// The color and opacity attributes for the given color, e.g. `fill="#ff0000" fill-opacity="0.5"`.
+fn color_attrs(attr: &str, color: LinSrgba) -> String {
+    let ([r, g, b], a) = srgb8(color);
+    let mut s = format!(r##"{}="#{:02x}{:02x}{:02x}""##, attr, r, g, b);
+    if a < 1.0 {
+        write!(s, r#" {}-opacity="{}""#, attr, num(a)).unwrap();
+    }
+    s
+}
@@ -0,0 +1,130 @@
+//! The **Light**s and **Material**s used to shade the geometry of a **Draw** instance.
+//!
+//! By default, all geometry is drawn with the **Unlit** material, in which case vertex and texture
+//! colors are displayed as they are. Geometry drawn with a lit material is instead shaded by the
+//! **Lights** of the **Draw** instance's context. Lights are described in the **Draw** API's world
+//! space and are unaffected by the transform of the **Draw** instance that adds them.
+
+use crate::color::LinSrgb;
+use crate::glam::Vec3;
+
+/// The maximum number of directional and point lights that may illuminate a single drawing.
+///
+/// Lights beyond this limit are ignored.
+pub const MAX_LIGHTS: usize = 8;
+
+/// A single source of light.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Light {
+    /// A light infinitely far away, whose rays travel along the given direction, e.g. the sun.
+    Directional { direction: Vec3, color: LinSrgb },
+    /// A light emitted in all directions from the given position, e.g. a light bulb.
+    ///
+    /// If a `range` is given, the light fades out smoothly towards zero at that distance.
+    /// Otherwise, the light does not fade with distance.
+    Point {
+        position: Vec3,
+        color: LinSrgb,
+        range: Option<f32>,
+    },
+}
+
+/// The set of lights illuminating lit geometry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lights {
+    /// Light applied uniformly to all lit geometry regardless of its position or orientation.
+    pub ambient: LinSrgb,
+    /// The directional and point lights.
+    ///
+    /// Only the first **MAX_LIGHTS** lights are used.
+    pub lights: Vec<Light>,
+}
+
+/// Describes how geometry responds to the **Lights** of its context.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Material {
+    /// Colors are displayed as they are, ignoring all lights. This is the default.
+    #[default]
+    Unlit,
+    /// Diffuse shading, where surfaces are brightest when facing a light.
+    Lambert,
+    /// Diffuse shading along with specular highlights.
+    BlinnPhong {
+        /// The intensity of the specular highlights.
+        specular: f32,
+        /// The sharpness of the specular highlights, where greater values produce smaller, more
+        /// focused highlights.
+        shininess: f32,
+    },
+}
+
+impl Light {
+    /// A directional light whose rays travel along the given direction.
+    pub fn directional(direction: Vec3, color: LinSrgb) -> Self {
+        Light::Directional { direction, color }
+    }
+
+    /// A point light at the given position that does not fade with distance.
+    pub fn point(position: Vec3, color: LinSrgb) -> Self {
+        let range = None;
+        Light::Point {
+            position,
+            color,
+            range,
+        }
+    }
+
+    /// Specify the distance at which a point light fades out.
+    ///
+    /// Has no effect on directional lights.
+    pub fn range(self, range: f32) -> Self {
+        match self {
+            Light::Directional { .. } => self,
+            Light::Point {
+                position, color, ..
+            } => Light::Point {
+                position,
+                color,
+                range: Some(range),
+            },
+        }
+    }
+}
+
+impl Lights {
+    /// Specify the ambient light.
+    pub fn ambient(mut self, color: LinSrgb) -> Self {
+        self.ambient = color;
+        self
+    }
+
+    /// Add the given light.
+    pub fn light(mut self, light: Light) -> Self {
+        self.lights.push(light);
+        self
+    }
+}
+
+impl Material {
+    /// The **BlinnPhong** material with the given specular intensity and shininess.
+    pub fn blinn_phong(specular: f32, shininess: f32) -> Self {
+        Material::BlinnPhong {
+            specular,
+            shininess,
+        }
+    }
+
+    /// Whether or not geometry with this material is shaded by lights.
+    pub fn is_lit(&self) -> bool {
+        *self != Material::Unlit
+    }
+}
+
+impl Default for Lights {
+    fn default() -> Self {
+        Lights {
+            ambient: LinSrgb::new(0.0, 0.0, 0.0),
+            lights: vec![],
+        }
+    }
+}
@@ -14,15 +14,21 @@ pub type Points = Vec<vertex::Point>;
 pub type Indices = Vec<u32>;
 pub type Colors = Vec<vertex::Color>;
 pub type TexCoords = Vec<vertex::TexCoords>;
+pub type Normals = Vec<vertex::Normal>;
 
 /// The inner mesh type used by the **draw::Mesh**.
 pub type MeshType =
     WithTexCoords<WithColors<WithIndices<MeshPoints<Points>, Indices>, Colors>, TexCoords>;
 
 /// The custom mesh type used internally by the **Draw** API.
+///
+/// Along with the channels of the inner **MeshType**, the mesh has an optional channel of vertex
+/// normals used to shade geometry drawn with a lit **draw::Material**. The normals channel may be
+/// shorter than the other channels, in which case the remaining vertices have no normal.
 #[derive(Clone, Debug)]
 pub struct Mesh {
     mesh: MeshType,
+    normals: Normals,
 }
 
 impl Mesh {
@@ -61,6 +67,33 @@ impl Mesh {
         mesh::TexCoords::tex_coords(self)
     }
 
+    /// The **Mesh**'s vertex normals channel.
+    ///
+    /// This may contain fewer normals than there are vertices, in which case the remaining
+    /// vertices have no normal. A zero-length normal also indicates that a vertex has no normal.
+    pub fn normals(&self) -> &[vertex::Normal] {
+        &self.normals
+    }
+
+    /// Assign the given normals to consecutive vertices, beginning with the vertex at `start`.
+    ///
+    /// If the normals channel is shorter than `start`, the vertices between are assigned no
+    /// normal.
+    pub fn set_normals<I>(&mut self, start: usize, normals: I)
+    where
+        I: IntoIterator<Item = vertex::Normal>,
+    {
+        if self.normals.len() < start {
+            self.normals.resize(start, vertex::Normal::ZERO);
+        }
+        for (i, n) in normals.into_iter().enumerate() {
+            match self.normals.get_mut(start + i) {
+                Some(normal) => *normal = n,
+                None => self.normals.push(n),
+            }
+        }
+    }
+
     /// Push the given vertex onto the inner channels.
     pub fn push_vertex(&mut self, v: Vertex) {
         mesh::push_vertex(self, v);
@@ -97,7 +130,7 @@ impl Mesh {
         self.extend_indices(is);
     }
 
-    /// Clear all vertices from the mesh.
+    /// Clear all vertices and normals from the mesh.
     pub fn clear_vertices(&mut self) {
         mesh::clear_vertices(self);
     }
@@ -107,7 +140,7 @@ impl Mesh {
         mesh::clear_indices(self);
     }
 
-    /// Clear all vertices and indices from the mesh.
+    /// Clear all vertices, normals and indices from the mesh.
     pub fn clear(&mut self) {
         mesh::clear(self);
     }
@@ -179,7 +212,8 @@ impl Mesh {
 impl Default for Mesh {
     fn default() -> Self {
         let mesh = Default::default();
-        Mesh { mesh }
+        let normals = Default::default();
+        Mesh { mesh, normals }
     }
 }
 
@@ -265,6 +299,7 @@ impl mesh::ClearIndices for Mesh {
 impl mesh::ClearVertices for Mesh {
     fn clear_vertices(&mut self) {
         self.mesh.clear_vertices();
+        self.normals.clear();
     }
 }
 
@@ -2,6 +2,7 @@
 //!
 //! See the [**Draw** type](./struct.Draw.html) for more details.
 
+use crate::color::conv::IntoLinSrgba;
 use crate::geom::{self, Point2};
 use crate::glam::{vec3, EulerRot, Mat4, Quat, Vec2, Vec3};
 use crate::math::{deg_to_rad, turns_to_rad};
@@ -13,15 +14,20 @@ use std::mem;
 use std::rc::Rc;
 
 pub use self::background::Background;
+pub use self::camera::Camera;
 pub use self::drawing::{Drawing, DrawingContext};
+pub use self::light::{Light, Lights, Material};
 use self::mesh::vertex::{Color, TexCoords};
 pub use self::mesh::Mesh;
 use self::primitive::Primitive;
-pub use self::renderer::{Builder as RendererBuilder, Renderer};
+pub use self::renderer::{Builder as RendererBuilder, CpuRenderer, Renderer};
 pub use self::theme::Theme;
 
 pub mod background;
+pub mod camera;
 mod drawing;
+pub mod export;
+pub mod light;
 pub mod mesh;
 pub mod primitive;
 pub mod properties;
@@ -58,7 +64,8 @@ pub struct Draw {
     context: Context,
 }
 
-/// The current **Transform**, alpha **BlendState** and **Scissor** of a **Draw** instance.
+/// The current **Transform**, alpha **BlendState**, **Scissor**, **Camera**, **Lights** and
+/// **Material** of a **Draw** instance.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Context {
     pub transform: Mat4,
@@ -68,6 +75,9 @@ pub struct Context {
     // here.
     pub topology: wgpu::PrimitiveTopology,
     pub sampler: wgpu::SamplerDescriptor<'static>,
+    pub camera: Camera,
+    pub lights: Lights,
+    pub material: Material,
 }
 
 /// Commands generated by drawings.
@@ -461,6 +471,126 @@ impl Draw {
         self.context(context)
     }
 
+    /// Produce a new **Draw** instance that will render through the given camera.
+    ///
+    /// Different **Draw** instances may render through different cameras within the same frame.
+    pub fn camera(&self, camera: Camera) -> Self {
+        let mut context = self.context.clone();
+        context.camera = camera;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance that will render with an orthographic projection.
+    ///
+    /// This is the default projection, where one unit is equal to one logical pixel.
+    pub fn orthographic(&self) -> Self {
+        self.projection(camera::Projection::Orthographic)
+    }
+
+    /// Produce a new **Draw** instance that will render with a perspective projection with the
+    /// given vertical field of view in radians.
+    ///
+    /// Unless a view matrix is specified, the camera is positioned such that the `z = 0` plane
+    /// appears exactly as it would via the default orthographic projection.
+    pub fn perspective(&self, fov_y: f32) -> Self {
+        self.projection(camera::Projection::Perspective { fov_y })
+    }
+
+    /// Produce a new **Draw** instance that will render with the given projection matrix.
+    pub fn projection_matrix(&self, projection: Mat4) -> Self {
+        self.projection(camera::Projection::Custom(projection))
+    }
+
+    /// Produce a new **Draw** instance that will render with the given world-to-view transform.
+    pub fn view_matrix(&self, view: Mat4) -> Self {
+        let mut context = self.context.clone();
+        context.camera.view = Some(view);
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance whose camera is positioned at `eye`, looking towards
+    /// `target` with the given `up` direction.
+    pub fn look_at(&self, eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        self.view_matrix(Mat4::look_at_rh(eye, target, up))
+    }
+
+    /// Produce a new **Draw** instance whose camera clips at the given near and far distances.
+    pub fn clip_planes(&self, near: f32, far: f32) -> Self {
+        let mut context = self.context.clone();
+        context.camera = context.camera.clip_planes(near, far);
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance whose lit geometry is illuminated by the given lights.
+    ///
+    /// This replaces all lights of the current context.
+    pub fn lights(&self, lights: Lights) -> Self {
+        let mut context = self.context.clone();
+        context.lights = lights;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance with the given ambient light.
+    ///
+    /// Ambient light illuminates all lit geometry uniformly, regardless of its orientation.
+    pub fn ambient_light<C>(&self, color: C) -> Self
+    where
+        C: IntoLinSrgba<properties::ColorScalar>,
+    {
+        let mut context = self.context.clone();
+        context.lights.ambient = color.into_lin_srgba().color;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance with the given light added to its lights.
+    ///
+    /// Lights are described in world space and are unaffected by the transform of the **Draw**.
+    /// Only the first **light::MAX_LIGHTS** lights illuminate geometry.
+    pub fn light(&self, light: Light) -> Self {
+        let mut context = self.context.clone();
+        context.lights.lights.push(light);
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance with a directional light whose rays travel along the given
+    /// `direction`.
+    pub fn directional_light<C>(&self, direction: Vec3, color: C) -> Self
+    where
+        C: IntoLinSrgba<properties::ColorScalar>,
+    {
+        self.light(Light::directional(direction, color.into_lin_srgba().color))
+    }
+
+    /// Produce a new **Draw** instance with a point light at the given `position`.
+    ///
+    /// The light does not fade with distance. See **Light::range** for a light that does.
+    pub fn point_light<C>(&self, position: Vec3, color: C) -> Self
+    where
+        C: IntoLinSrgba<properties::ColorScalar>,
+    {
+        self.light(Light::point(position, color.into_lin_srgba().color))
+    }
+
+    /// Produce a new **Draw** instance whose geometry is shaded with the given material.
+    ///
+    /// Geometry is shaded per-pixel using the vertex normals of its mesh. Vertices without a
+    /// normal are shaded using the normal of the triangle to which they belong.
+    pub fn material(&self, material: Material) -> Self {
+        let mut context = self.context.clone();
+        context.material = material;
+        self.context(context)
+    }
+
+    /// Specify the camera projection, retaining the current view and clip planes.
+    ///
+    /// This method is shared between the `orthographic`, `perspective` and `projection_matrix`
+    /// methods.
+    fn projection(&self, projection: camera::Projection) -> Self {
+        let mut context = self.context.clone();
+        context.camera.projection = projection;
+        self.context(context)
+    }
+
     /// Specify the primitive topology to use within the render pipeline.
     ///
     /// This method is shared between the `line_mode`, `point_mode` and `triangle_mode` methods.
@@ -543,6 +673,31 @@ impl Draw {
         self.a(Default::default())
     }
 
+    /// Begin drawing a **Cuboid**.
+    pub fn cuboid(&self) -> Drawing<primitive::Cuboid> {
+        self.a(Default::default())
+    }
+
+    /// Begin drawing a **Sphere**.
+    pub fn sphere(&self) -> Drawing<primitive::Sphere> {
+        self.a(Default::default())
+    }
+
+    /// Begin drawing a **Cylinder**.
+    pub fn cylinder(&self) -> Drawing<primitive::Cylinder> {
+        self.a(Default::default())
+    }
+
+    /// Begin drawing a **Cone**.
+    pub fn cone(&self) -> Drawing<primitive::Cone> {
+        self.a(Default::default())
+    }
+
+    /// Begin drawing a **Torus**.
+    pub fn torus(&self) -> Drawing<primitive::Torus> {
+        self.a(Default::default())
+    }
+
     /// Begin drawing a **Polygon**.
     pub fn polygon(&self) -> Drawing<primitive::PolygonInit> {
         self.a(Default::default())
@@ -588,6 +743,14 @@ impl Draw {
         cmds.into_iter().filter_map(|opt| opt)
     }
 
+    /// Export the drawing to a vector **Document** that may be saved as SVG or PDF.
+    ///
+    /// The `rect` describes the region of the drawing covered by the page, e.g.
+    /// `app.window_rect()`. Unlike `drain_commands`, the draw commands are left in place.
+    pub fn export(&self, rect: geom::Rect) -> export::Document {
+        export::Document::new(self, rect)
+    }
+
     /// Drain any remaining `drawing`s and convert them to draw commands.
     pub fn finish_remaining_drawings(&self) {
         self.state.borrow_mut().finish_remaining_drawings()
@@ -649,6 +812,9 @@ impl Default for Context {
             scissor: Scissor::Full,
             topology: wgpu::RenderPipelineBuilder::DEFAULT_PRIMITIVE_TOPOLOGY,
             sampler: wgpu::SamplerBuilder::new().into_descriptor(),
+            camera: Camera::default(),
+            lights: Lights::default(),
+            material: Material::default(),
         }
     }
 }
@@ -223,6 +223,7 @@ impl draw::renderer::RenderPrimitive for Arrow {
             &draw::theme::Primitive::Arrow,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
             mesh,
         );
 
@@ -241,6 +242,7 @@ impl draw::renderer::RenderPrimitive for Arrow {
                 &draw::theme::Primitive::Arrow,
                 &mut ctxt.fill_tessellator,
                 &mut ctxt.stroke_tessellator,
+                ctxt.vector_paths.as_deref_mut(),
                 mesh,
             );
         }
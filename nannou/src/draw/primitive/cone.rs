@@ -0,0 +1,149 @@
+use crate::draw::primitive::solid::{self, SetSolid, SolidOptions};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{dimension, orientation, position};
+use crate::draw::properties::{
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition,
+};
+use crate::draw::{self, Drawing};
+use crate::glam::{vec3, Vec3};
+use std::f32::consts::PI;
+
+/// Properties related to drawing a **Cone**.
+///
+/// The cone is aligned with the *y* axis with its apex pointing towards positive *y*. The width and
+/// depth describe the diameter of the base along the *x* and *z* axes while the height describes
+/// the distance from the base to the apex.
+#[derive(Clone, Debug, Default)]
+pub struct Cone {
+    dimensions: dimension::Properties,
+    resolution: Option<usize>,
+    solid: SolidOptions,
+}
+
+/// The drawing context for a `Cone`.
+pub type DrawingCone<'a> = Drawing<'a, Cone>;
+
+// Cone-specific methods.
+
+impl Cone {
+    /// The default number of segments around the cone's axis.
+    pub const DEFAULT_RESOLUTION: usize = 32;
+
+    /// Specify the width and depth of the **Cone**'s base via a given **radius**.
+    pub fn radius(self, radius: f32) -> Self {
+        let side = radius * 2.0;
+        self.w(side).d(side)
+    }
+
+    /// The number of segments around the cone's axis.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingCone<'a> {
+    /// Specify the width and depth of the **Cone**'s base via a given **radius**.
+    pub fn radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.radius(radius))
+    }
+
+    /// The number of segments around the cone's axis.
+    pub fn resolution(self, resolution: usize) -> Self {
+        self.map_ty(|ty| ty.resolution(resolution))
+    }
+}
+
+/// Produce the geometry for a cone centered on the origin and aligned with the *y* axis.
+///
+/// `radii` describes the radius of the base along the *x* and *z* axes and half of the height
+/// along the *y* axis. Each side triangle is a face, as is the base.
+pub fn geometry(radii: Vec3, segments: usize) -> solid::Geometry {
+    let segments = segments.max(3) as u32;
+    let mut geometry = solid::Geometry::default();
+    for s in 0..segments {
+        let theta = 2.0 * PI * s as f32 / segments as f32;
+        let (sin, cos) = theta.sin_cos();
+        geometry.push_point(vec3(cos * radii.x, -radii.y, sin * radii.z));
+    }
+    let apex = geometry.push_point(vec3(0.0, radii.y, 0.0));
+    let base = |s: u32| s % segments;
+
+    for s in 0..segments {
+        geometry.push_face(&[base(s), apex, base(s + 1)]);
+    }
+    let base_cap: Vec<u32> = (0..segments).map(base).collect();
+    geometry.push_face(&base_cap);
+    geometry
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Cone {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Cone {
+            dimensions,
+            resolution,
+            solid,
+        } = self;
+        let w = dimensions.x.map(f32::abs).unwrap_or(100.0);
+        let h = dimensions.y.map(f32::abs).unwrap_or(100.0);
+        let d = dimensions.z.map(f32::abs).unwrap_or(100.0);
+        let segments = resolution.unwrap_or(Self::DEFAULT_RESOLUTION);
+        let geometry = geometry(vec3(w, h, d) * 0.5, segments);
+        solid::render_themed(solid, &geometry, ctxt, &draw::theme::Primitive::Cone, mesh)
+    }
+}
+
+impl SetOrientation for Cone {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.solid)
+    }
+}
+
+impl SetPosition for Cone {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.solid)
+    }
+}
+
+impl SetDimensions for Cone {
+    fn properties(&mut self) -> &mut dimension::Properties {
+        SetDimensions::properties(&mut self.dimensions)
+    }
+}
+
+impl SetColor<ColorScalar> for Cone {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.solid)
+    }
+}
+
+impl SetSolid for Cone {
+    fn solid_options_mut(&mut self) -> &mut SolidOptions {
+        SetSolid::solid_options_mut(&mut self.solid)
+    }
+}
+
+// Primitive conversions.
+
+impl From<Cone> for Primitive {
+    fn from(prim: Cone) -> Self {
+        Primitive::Cone(prim)
+    }
+}
+
+impl Into<Option<Cone>> for Primitive {
+    fn into(self) -> Option<Cone> {
+        match self {
+            Primitive::Cone(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
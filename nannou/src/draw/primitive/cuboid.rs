@@ -0,0 +1,171 @@
+use crate::draw::primitive::solid::{self, SetSolid, SolidOptions};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{dimension, orientation, position};
+use crate::draw::properties::{
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition,
+};
+use crate::draw::{self, Drawing};
+use crate::geom::{self, Point3};
+use crate::glam::{vec3, Vec3};
+
+/// Properties related to drawing a **Cuboid**.
+#[derive(Clone, Debug, Default)]
+pub struct Cuboid {
+    dimensions: dimension::Properties,
+    resolution: Option<usize>,
+    solid: SolidOptions,
+}
+
+/// The drawing context for a `Cuboid`.
+pub type DrawingCuboid<'a> = Drawing<'a, Cuboid>;
+
+// Cuboid-specific methods.
+
+impl Cuboid {
+    /// Specify the width, height and depth of the **Cuboid** via the length of a single side.
+    pub fn side(self, side: f32) -> Self {
+        self.w_h_d(side, side, side)
+    }
+
+    /// The number of segments along each edge of each face.
+    ///
+    /// By default, each face is described by a single quad (two triangles). Higher resolutions
+    /// are useful for per-vertex coloring.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingCuboid<'a> {
+    /// Specify the width, height and depth of the **Cuboid** via the length of a single side.
+    pub fn side(self, side: f32) -> Self {
+        self.map_ty(|ty| ty.side(side))
+    }
+
+    /// The number of segments along each edge of each face.
+    pub fn resolution(self, resolution: usize) -> Self {
+        self.map_ty(|ty| ty.resolution(resolution))
+    }
+}
+
+/// Produce the geometry for a cuboid of the given dimensions centered on the origin.
+///
+/// Each of the six faces is subdivided into a `resolution` x `resolution` grid.
+pub fn geometry(whd: Vec3, resolution: usize) -> solid::Geometry {
+    let resolution = resolution.max(1);
+    let cuboid = geom::Cuboid::from_xyz_whd(Point3::ZERO, whd);
+    let mut geometry = solid::Geometry::default();
+    for quad in cuboid.faces_iter() {
+        let [a, b, c, d]: [Vec3; 4] = [
+            quad[0].into(),
+            quad[1].into(),
+            quad[2].into(),
+            quad[3].into(),
+        ];
+        // Bilinearly interpolate a grid of points across the face.
+        let start = geometry.points.len() as u32;
+        let n = resolution as u32;
+        for j in 0..=n {
+            let v = j as f32 / n as f32;
+            let ad = a.lerp(d, v);
+            let bc = b.lerp(c, v);
+            for i in 0..=n {
+                let u = i as f32 / n as f32;
+                geometry.push_point(ad.lerp(bc, u));
+            }
+        }
+        let ix = |i: u32, j: u32| start + j * (n + 1) + i;
+        let triangles = (0..n).flat_map(|j| {
+            (0..n).flat_map(move |i| {
+                let (a, b, c, d) = (ix(i, j), ix(i + 1, j), ix(i + 1, j + 1), ix(i, j + 1));
+                [a, b, c, a, c, d]
+            })
+        });
+        geometry.push_triangulated_face(triangles);
+    }
+    geometry
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Cuboid {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Cuboid {
+            dimensions,
+            resolution,
+            solid,
+        } = self;
+        let w = dimensions.x.map(f32::abs).unwrap_or(100.0);
+        let h = dimensions.y.map(f32::abs).unwrap_or(100.0);
+        let d = dimensions.z.map(f32::abs).unwrap_or(100.0);
+        let geometry = geometry(vec3(w, h, d), resolution.unwrap_or(1));
+        solid::render_themed(
+            solid,
+            &geometry,
+            ctxt,
+            &draw::theme::Primitive::Cuboid,
+            mesh,
+        )
+    }
+}
+
+impl From<geom::Cuboid<f32>> for Cuboid {
+    fn from(c: geom::Cuboid<f32>) -> Self {
+        let (p, whd) = c.xyz_whd();
+        Self::default().xyz(p).whd(whd)
+    }
+}
+
+impl SetOrientation for Cuboid {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.solid)
+    }
+}
+
+impl SetPosition for Cuboid {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.solid)
+    }
+}
+
+impl SetDimensions for Cuboid {
+    fn properties(&mut self) -> &mut dimension::Properties {
+        SetDimensions::properties(&mut self.dimensions)
+    }
+}
+
+impl SetColor<ColorScalar> for Cuboid {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.solid)
+    }
+}
+
+impl SetSolid for Cuboid {
+    fn solid_options_mut(&mut self) -> &mut SolidOptions {
+        SetSolid::solid_options_mut(&mut self.solid)
+    }
+}
+
+// Primitive conversions.
+
+impl From<Cuboid> for Primitive {
+    fn from(prim: Cuboid) -> Self {
+        Primitive::Cuboid(prim)
+    }
+}
+
+impl Into<Option<Cuboid>> for Primitive {
+    fn into(self) -> Option<Cuboid> {
+        match self {
+            Primitive::Cuboid(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::draw::primitive::solid::{self, SetSolid, SolidOptions};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{dimension, orientation, position};
+use crate::draw::properties::{
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition,
+};
+use crate::draw::{self, Drawing};
+use crate::glam::{vec3, Vec3};
+use std::f32::consts::PI;
+
+/// Properties related to drawing a **Cylinder**.
+///
+/// The cylinder is aligned with the *y* axis. The width and depth describe the diameter of the
+/// cylinder along the *x* and *z* axes while the height describes its length.
+#[derive(Clone, Debug, Default)]
+pub struct Cylinder {
+    dimensions: dimension::Properties,
+    resolution: Option<usize>,
+    solid: SolidOptions,
+}
+
+/// The drawing context for a `Cylinder`.
+pub type DrawingCylinder<'a> = Drawing<'a, Cylinder>;
+
+// Cylinder-specific methods.
+
+impl Cylinder {
+    /// The default number of segments around the cylinder's axis.
+    pub const DEFAULT_RESOLUTION: usize = 32;
+
+    /// Specify the width and depth of the **Cylinder** via a given **radius**.
+    pub fn radius(self, radius: f32) -> Self {
+        let side = radius * 2.0;
+        self.w(side).d(side)
+    }
+
+    /// The number of segments around the cylinder's axis.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingCylinder<'a> {
+    /// Specify the width and depth of the **Cylinder** via a given **radius**.
+    pub fn radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.radius(radius))
+    }
+
+    /// The number of segments around the cylinder's axis.
+    pub fn resolution(self, resolution: usize) -> Self {
+        self.map_ty(|ty| ty.resolution(resolution))
+    }
+}
+
+/// Produce the geometry for a cylinder centered on the origin and aligned with the *y* axis.
+///
+/// `radii` describes the radius along the *x* and *z* axes and half of the height along the *y*
+/// axis. Each side quad is a face, as is each of the two caps.
+pub fn geometry(radii: Vec3, segments: usize) -> solid::Geometry {
+    let segments = segments.max(3) as u32;
+    let mut geometry = solid::Geometry::default();
+    let ring_point = |s: u32, y: f32| {
+        let theta = 2.0 * PI * s as f32 / segments as f32;
+        let (sin, cos) = theta.sin_cos();
+        vec3(cos * radii.x, y, sin * radii.z)
+    };
+    for s in 0..segments {
+        geometry.push_point(ring_point(s, -radii.y));
+    }
+    for s in 0..segments {
+        geometry.push_point(ring_point(s, radii.y));
+    }
+    let bottom = |s: u32| s % segments;
+    let top = |s: u32| segments + s % segments;
+
+    for s in 0..segments {
+        geometry.push_face(&[bottom(s), top(s), top(s + 1), bottom(s + 1)]);
+    }
+    let top_cap: Vec<u32> = (0..segments).rev().map(top).collect();
+    geometry.push_face(&top_cap);
+    let bottom_cap: Vec<u32> = (0..segments).map(bottom).collect();
+    geometry.push_face(&bottom_cap);
+    geometry
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Cylinder {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Cylinder {
+            dimensions,
+            resolution,
+            solid,
+        } = self;
+        let w = dimensions.x.map(f32::abs).unwrap_or(100.0);
+        let h = dimensions.y.map(f32::abs).unwrap_or(100.0);
+        let d = dimensions.z.map(f32::abs).unwrap_or(100.0);
+        let segments = resolution.unwrap_or(Self::DEFAULT_RESOLUTION);
+        let geometry = geometry(vec3(w, h, d) * 0.5, segments);
+        solid::render_themed(
+            solid,
+            &geometry,
+            ctxt,
+            &draw::theme::Primitive::Cylinder,
+            mesh,
+        )
+    }
+}
+
+impl SetOrientation for Cylinder {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.solid)
+    }
+}
+
+impl SetPosition for Cylinder {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.solid)
+    }
+}
+
+impl SetDimensions for Cylinder {
+    fn properties(&mut self) -> &mut dimension::Properties {
+        SetDimensions::properties(&mut self.dimensions)
+    }
+}
+
+impl SetColor<ColorScalar> for Cylinder {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.solid)
+    }
+}
+
+impl SetSolid for Cylinder {
+    fn solid_options_mut(&mut self) -> &mut SolidOptions {
+        SetSolid::solid_options_mut(&mut self.solid)
+    }
+}
+
+// Primitive conversions.
+
+impl From<Cylinder> for Primitive {
+    fn from(prim: Cylinder) -> Self {
+        Primitive::Cylinder(prim)
+    }
+}
+
+impl Into<Option<Cylinder>> for Primitive {
+    fn into(self) -> Option<Cylinder> {
+        match self {
+            Primitive::Cylinder(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
@@ -157,6 +157,7 @@ impl draw::renderer::RenderPrimitive for Line {
             &draw::theme::Primitive::Line,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
             mesh,
         );
 
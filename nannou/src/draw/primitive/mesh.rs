@@ -1,10 +1,12 @@
 use crate::color::conv::IntoLinSrgba;
-use crate::draw::mesh::vertex::{self, Point, TexCoords, Vertex};
+use crate::draw::mesh::vertex::{self, Normal, Point, TexCoords, Vertex};
 use crate::draw::primitive::Primitive;
 use crate::draw::properties::spatial::{orientation, position};
 use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
 use crate::draw::{self, Drawing};
 use crate::geom;
+use crate::glam::Mat3;
+use crate::mesh::MeshData;
 use crate::wgpu;
 use std::ops;
 
@@ -287,6 +289,66 @@ impl Vertexless {
         mesh
     }
 
+    /// Describe the mesh with the given **MeshData**, e.g. a mesh loaded via `mesh::obj` or
+    /// `mesh::gltf`.
+    ///
+    /// If the mesh data has vertex colors, the mesh is colored by them unless a color is
+    /// specified via one of the builder methods. Otherwise, the entire mesh is coloured with a
+    /// single colour, retrieved from the inner `Theme` if not specified. The mesh data's normals,
+    /// if any, are used to shade the mesh with a lit **draw::Material**.
+    pub fn mesh_data(self, inner_mesh: &mut draw::Mesh, data: &MeshData) -> Mesh {
+        let vertex_mode = draw::renderer::VertexMode::Color;
+        let mut mesh = self.mesh_data_inner(inner_mesh, data, vertex_mode, None);
+        if data.colors.is_empty() {
+            mesh.fill_color = Some(FillColor(None));
+        }
+        mesh
+    }
+
+    /// Describe the mesh with the given **MeshData**, textured via its texture coordinates.
+    ///
+    /// The mesh data's normals, if any, are used to shade the mesh with a lit
+    /// **draw::Material**.
+    pub fn mesh_data_textured(
+        self,
+        inner_mesh: &mut draw::Mesh,
+        texture_view: &dyn wgpu::ToTextureView,
+        data: &MeshData,
+    ) -> Mesh {
+        let vertex_mode = draw::renderer::VertexMode::Texture;
+        let texture_view = Some(texture_view.to_texture_view());
+        self.mesh_data_inner(inner_mesh, data, vertex_mode, texture_view)
+    }
+
+    fn mesh_data_inner(
+        self,
+        inner_mesh: &mut draw::Mesh,
+        data: &MeshData,
+        vertex_mode: draw::renderer::VertexMode,
+        texture_view: Option<wgpu::TextureView>,
+    ) -> Mesh {
+        let v_start = inner_mesh.points().len();
+        let vertices = data.points.iter().enumerate().map(|(i, &point)| {
+            let color = data
+                .colors
+                .get(i)
+                .cloned()
+                .unwrap_or(vertex::DEFAULT_VERTEX_COLOR);
+            let tex_coords = data
+                .tex_coords
+                .get(i)
+                .cloned()
+                .unwrap_or_else(vertex::default_tex_coords);
+            ((point, color), tex_coords).into()
+        });
+        let indices = data.indices.iter().map(|&i| v_start + i as usize);
+        let mesh = self.indexed_inner(inner_mesh, vertices, indices, vertex_mode, texture_view);
+        if !data.normals.is_empty() {
+            inner_mesh.set_normals(v_start, data.normals.iter().cloned());
+        }
+        mesh
+    }
+
     fn indexed_inner<V, I>(
         self,
         inner_mesh: &mut draw::Mesh,
@@ -332,6 +394,40 @@ impl Mesh {
     }
 }
 
+impl Mesh {
+    /// Specify the normal of each vertex, used to shade the mesh with a lit **draw::Material**.
+    ///
+    /// Normals are assigned to vertices in the order in which the vertices were described. If
+    /// there are fewer normals than vertices, the remaining vertices are shaded using the normal
+    /// of the triangle to which they belong.
+    pub fn normals<I>(self, inner_mesh: &mut draw::Mesh, normals: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Normal>,
+    {
+        let normals = normals
+            .into_iter()
+            .take(self.vertex_range.len())
+            .map(Into::into);
+        inner_mesh.set_normals(self.vertex_range.start, normals);
+        self
+    }
+
+    /// Assign each vertex the average normal of the triangles that share it.
+    ///
+    /// Each triangle's contribution is weighted by its area. This produces smooth shading for
+    /// meshes whose triangles share vertices via their indices.
+    pub fn smooth_normals(self, inner_mesh: &mut draw::Mesh) -> Self {
+        let normals = smooth_normals(
+            &inner_mesh.points()[self.vertex_range.clone()],
+            &inner_mesh.indices()[self.index_range.clone()],
+            self.vertex_range.start,
+        );
+        inner_mesh.set_normals(self.vertex_range.start, normals);
+        self
+    }
+}
+
 impl<'a> Drawing<'a, Vertexless> {
     /// Describe the mesh with a sequence of points.
     ///
@@ -479,6 +575,77 @@ impl<'a> Drawing<'a, Vertexless> {
     {
         self.map_ty_with_context(|ty, ctxt| ty.indexed_textured(ctxt.mesh, view, points, indices))
     }
+
+    /// Describe the mesh with the given **MeshData**, e.g. a mesh loaded via `mesh::obj` or
+    /// `mesh::gltf`.
+    ///
+    /// If the mesh data has vertex colors, the mesh is colored by them unless a color is
+    /// specified via one of the builder methods. Otherwise, the entire mesh is coloured with a
+    /// single colour, retrieved from the inner `Theme` if not specified. The mesh data's normals,
+    /// if any, are used to shade the mesh with a lit **draw::Material**.
+    pub fn mesh_data(self, data: &MeshData) -> DrawingMesh<'a> {
+        self.map_ty_with_context(|ty, ctxt| ty.mesh_data(ctxt.mesh, data))
+    }
+
+    /// Describe the mesh with the given **MeshData**, textured via its texture coordinates.
+    ///
+    /// The mesh data's normals, if any, are used to shade the mesh with a lit
+    /// **draw::Material**.
+    pub fn mesh_data_textured(
+        self,
+        view: &dyn wgpu::ToTextureView,
+        data: &MeshData,
+    ) -> DrawingMesh<'a> {
+        self.map_ty_with_context(|ty, ctxt| ty.mesh_data_textured(ctxt.mesh, view, data))
+    }
+}
+
+impl<'a> DrawingMesh<'a> {
+    /// Specify the normal of each vertex, used to shade the mesh with a lit **draw::Material**.
+    ///
+    /// Normals are assigned to vertices in the order in which the vertices were described. If
+    /// there are fewer normals than vertices, the remaining vertices are shaded using the normal
+    /// of the triangle to which they belong.
+    pub fn normals<I>(self, normals: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Normal>,
+    {
+        self.map_ty_with_context(|ty, ctxt| ty.normals(ctxt.mesh, normals))
+    }
+
+    /// Assign each vertex the average normal of the triangles that share it.
+    ///
+    /// Each triangle's contribution is weighted by its area. This produces smooth shading for
+    /// meshes whose triangles share vertices via their indices.
+    pub fn smooth_normals(self) -> Self {
+        self.map_ty_with_context(|ty, ctxt| ty.smooth_normals(ctxt.mesh))
+    }
+}
+
+/// Produce the area-weighted average normal of each of the given points.
+///
+/// Every trio of `indices` describes a single, counter-clockwise wound triangle. Each index is
+/// offset by `index_offset` prior to indexing into `points`. Points that are not referred to by
+/// any triangle have a zero-length normal.
+pub fn smooth_normals(points: &[Point], indices: &[u32], index_offset: usize) -> Vec<Normal> {
+    let mut normals = vec![Normal::ZERO; points.len()];
+    for tri in indices.chunks_exact(3) {
+        let ix = |i: usize| (tri[i] as usize).checked_sub(index_offset);
+        let (a, b, c) = match (ix(0), ix(1), ix(2)) {
+            (Some(a), Some(b), Some(c)) if a.max(b).max(c) < points.len() => (a, b, c),
+            _ => continue,
+        };
+        // The cross product's length is twice the triangle's area, weighting the normal.
+        let n = (points[b] - points[a]).cross(points[c] - points[a]);
+        for &i in &[a, b, c] {
+            normals[i] += n;
+        }
+    }
+    for n in &mut normals {
+        *n = n.normalize_or_zero();
+    }
+    normals
 }
 
 impl draw::renderer::RenderPrimitive for Mesh {
@@ -512,6 +679,17 @@ impl draw::renderer::RenderPrimitive for Mesh {
         // A small function for transforming a point via the transform matrix.
         let transform_point = |p: geom::Point3| -> geom::Point3 { transform.transform_point3(p) };
 
+        // Transform the normals of the vertices that have them, if any.
+        let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+        let normals = ctxt.intermediary_mesh.normals();
+        if normals.len() > vertex_range.start {
+            let normals = vertex_range.clone().map(|i| match normals.get(i) {
+                Some(&n) => (normal_matrix * n).normalize_or_zero(),
+                None => Normal::ZERO,
+            });
+            mesh.set_normals(new_mesh_vertex_start as usize, normals);
+        }
+
         // Color the vertices based on whether or not we should fill, then extend the mesh!
         match fill_color {
             Some(fill) => {
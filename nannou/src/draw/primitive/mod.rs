@@ -1,4 +1,7 @@
 pub mod arrow;
+pub mod cone;
+pub mod cuboid;
+pub mod cylinder;
 pub mod ellipse;
 pub mod line;
 pub mod mesh;
@@ -6,11 +9,17 @@ pub mod path;
 pub mod polygon;
 pub mod quad;
 pub mod rect;
+pub mod solid;
+pub mod sphere;
 pub mod text;
 pub mod texture;
+pub mod torus;
 pub mod tri;
 
 pub use self::arrow::Arrow;
+pub use self::cone::Cone;
+pub use self::cuboid::Cuboid;
+pub use self::cylinder::Cylinder;
 pub use self::ellipse::Ellipse;
 pub use self::line::Line;
 pub use self::mesh::Mesh;
@@ -18,8 +27,11 @@ pub use self::path::{Path, PathFill, PathInit, PathStroke};
 pub use self::polygon::{Polygon, PolygonInit};
 pub use self::quad::Quad;
 pub use self::rect::Rect;
+pub use self::solid::{SetSolid, SolidOptions};
+pub use self::sphere::Sphere;
 pub use self::text::Text;
 pub use self::texture::Texture;
+pub use self::torus::Torus;
 pub use self::tri::Tri;
 
 /// A wrapper around all primitive sets of properties so that they may be stored within the
@@ -30,6 +42,9 @@ pub use self::tri::Tri;
 #[derive(Clone, Debug)]
 pub enum Primitive {
     Arrow(Arrow),
+    Cone(Cone),
+    Cuboid(Cuboid),
+    Cylinder(Cylinder),
     Ellipse(Ellipse),
     Line(Line),
     MeshVertexless(mesh::Vertexless),
@@ -42,7 +57,9 @@ pub enum Primitive {
     Polygon(Polygon),
     Quad(Quad),
     Rect(Rect),
+    Sphere(Sphere),
     Text(Text),
     Texture(Texture),
+    Torus(Torus),
     Tri(Tri),
 }
@@ -1,5 +1,6 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::color::LinSrgba;
+use crate::draw::export::{self, Paint, VectorPath};
 use crate::draw::mesh::vertex::{Color, TexCoords};
 use crate::draw::primitive::Primitive;
 use crate::draw::properties::spatial::{orientation, position};
@@ -360,10 +361,28 @@ pub(crate) fn render_path_events<I>(
     theme_prim: &draw::theme::Primitive,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<VectorPath>>,
     mesh: &mut draw::Mesh,
 ) where
     I: IntoIterator<Item = lyon::path::PathEvent>,
 {
+    // When exporting, record the path as it is rather than tessellating it.
+    if let Some(vector_paths) = vector_paths {
+        let paint = match options {
+            Options::Fill(options) => Paint::Fill {
+                color: color.unwrap_or_else(|| theme.fill_lin_srgba(theme_prim)),
+                rule: options.fill_rule,
+            },
+            Options::Stroke(options) => Paint::Stroke {
+                color: color.unwrap_or_else(|| theme.stroke_lin_srgba(theme_prim)),
+                options,
+            },
+        };
+        let events = events.into_iter().collect();
+        vector_paths.push(VectorPath::new(events, transform, paint));
+        return;
+    }
+
     let res = match options {
         Options::Fill(options) => {
             let color = color.unwrap_or_else(|| theme.fill_lin_srgba(theme_prim));
@@ -388,10 +407,19 @@ pub(crate) fn render_path_points_colored<I>(
     options: Options,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<VectorPath>>,
     mesh: &mut draw::Mesh,
 ) where
     I: IntoIterator<Item = (Point2, Color)>,
 {
+    if let Some(vector_paths) = vector_paths {
+        let points: Vec<_> = points_colored.into_iter().collect();
+        vector_paths.extend(export::colored_points_paths(
+            &points, close, transform, options,
+        ));
+        return;
+    }
+
     let path = match points_colored_to_lyon_path(points_colored, close) {
         None => return,
         Some(p) => p,
@@ -427,10 +455,16 @@ pub(crate) fn render_path_points_textured<I>(
     options: Options,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<VectorPath>>,
     mesh: &mut draw::Mesh,
 ) where
     I: IntoIterator<Item = (Point2, TexCoords)>,
 {
+    // The pixels of textures are unavailable to the exporter, so textured paths are skipped.
+    if vector_paths.is_some() {
+        return;
+    }
+
     let path = match points_textured_to_lyon_path(points_textured, close) {
         None => return,
         Some(p) => p,
@@ -469,6 +503,7 @@ pub(crate) fn render_path_source(
     theme_prim: &draw::theme::Primitive,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<VectorPath>>,
     mesh: &mut draw::Mesh,
 ) {
     match path_src {
@@ -481,6 +516,7 @@ pub(crate) fn render_path_source(
             theme_prim,
             fill_tessellator,
             stroke_tessellator,
+            vector_paths,
             mesh,
         ),
         PathEventSourceIter::ColoredPoints { points, close } => render_path_points_colored(
@@ -490,6 +526,7 @@ pub(crate) fn render_path_source(
             options,
             fill_tessellator,
             stroke_tessellator,
+            vector_paths,
             mesh,
         ),
         PathEventSourceIter::TexturedPoints { points, close } => render_path_points_textured(
@@ -499,6 +536,7 @@ pub(crate) fn render_path_source(
             options,
             fill_tessellator,
             stroke_tessellator,
+            vector_paths,
             mesh,
         ),
     }
@@ -526,23 +564,24 @@ impl draw::renderer::RenderPrimitive for Path {
         let transform = global_transform * local_transform;
 
         // A function for rendering the path.
-        let render =
-            |src: PathEventSourceIter,
-             theme: &draw::Theme,
-             fill_tessellator: &mut lyon::tessellation::FillTessellator,
-             stroke_tessellator: &mut lyon::tessellation::StrokeTessellator| {
-                render_path_source(
-                    src,
-                    color,
-                    transform,
-                    options,
-                    theme,
-                    &draw::theme::Primitive::Path,
-                    fill_tessellator,
-                    stroke_tessellator,
-                    mesh,
-                )
-            };
+        let render = |src: PathEventSourceIter,
+                      theme: &draw::Theme,
+                      fill_tessellator: &mut lyon::tessellation::FillTessellator,
+                      stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+                      vector_paths: Option<&mut Vec<VectorPath>>| {
+            render_path_source(
+                src,
+                color,
+                transform,
+                options,
+                theme,
+                &draw::theme::Primitive::Path,
+                fill_tessellator,
+                stroke_tessellator,
+                vector_paths,
+                mesh,
+            )
+        };
 
         match path_event_src {
             PathEventSource::Buffered(range) => {
@@ -553,6 +592,7 @@ impl draw::renderer::RenderPrimitive for Path {
                     &ctxt.theme,
                     &mut ctxt.fill_tessellator,
                     &mut ctxt.stroke_tessellator,
+                    ctxt.vector_paths.as_deref_mut(),
                 );
             }
             PathEventSource::ColoredPoints { range, close } => {
@@ -566,6 +606,7 @@ impl draw::renderer::RenderPrimitive for Path {
                     &ctxt.theme,
                     &mut ctxt.fill_tessellator,
                     &mut ctxt.stroke_tessellator,
+                    ctxt.vector_paths.as_deref_mut(),
                 );
             }
             PathEventSource::TexturedPoints { range, close } => {
@@ -579,6 +620,7 @@ impl draw::renderer::RenderPrimitive for Path {
                     &ctxt.theme,
                     &mut ctxt.fill_tessellator,
                     &mut ctxt.stroke_tessellator,
+                    ctxt.vector_paths.as_deref_mut(),
                 );
             }
         }
@@ -1,5 +1,6 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::drawing::DrawingContext;
+use crate::draw::export::VectorPath;
 use crate::draw::mesh::vertex::TexCoords;
 use crate::draw::primitive::path::{self, PathEventSource};
 use crate::draw::primitive::Primitive;
@@ -199,24 +200,25 @@ pub fn render_events_themed<F, I>(
     let transform = global_transform * local_transform;
 
     // A function for rendering the path.
-    let mut render =
-        |opts: path::Options,
-         color: Option<LinSrgba>,
-         theme: &draw::Theme,
-         fill_tessellator: &mut lyon::tessellation::FillTessellator,
-         stroke_tessellator: &mut lyon::tessellation::StrokeTessellator| {
-            path::render_path_events(
-                events(),
-                color,
-                transform,
-                opts,
-                theme,
-                theme_primitive,
-                fill_tessellator,
-                stroke_tessellator,
-                mesh,
-            )
-        };
+    let mut render = |opts: path::Options,
+                      color: Option<LinSrgba>,
+                      theme: &draw::Theme,
+                      fill_tessellator: &mut lyon::tessellation::FillTessellator,
+                      stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+                      vector_paths: Option<&mut Vec<VectorPath>>| {
+        path::render_path_events(
+            events(),
+            color,
+            transform,
+            opts,
+            theme,
+            theme_primitive,
+            fill_tessellator,
+            stroke_tessellator,
+            vector_paths,
+            mesh,
+        )
+    };
 
     // Do the fill tessellation first.
     if !no_fill {
@@ -227,6 +229,7 @@ pub fn render_events_themed<F, I>(
             &ctxt.theme,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
         );
     }
 
@@ -240,6 +243,7 @@ pub fn render_events_themed<F, I>(
             &ctxt.theme,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
         );
     }
 }
@@ -285,6 +289,7 @@ impl Polygon {
         let draw::renderer::RenderContext {
             fill_tessellator,
             stroke_tessellator,
+            mut vector_paths,
             path_event_buffer,
             path_points_colored_buffer,
             path_points_textured_buffer,
@@ -299,25 +304,26 @@ impl Polygon {
         let transform = global_transform * local_transform;
 
         // A function for rendering the path.
-        let mut render =
-            |src: path::PathEventSourceIter,
-             opts: path::Options,
-             color: Option<LinSrgba>,
-             theme: &draw::Theme,
-             fill_tessellator: &mut lyon::tessellation::FillTessellator,
-             stroke_tessellator: &mut lyon::tessellation::StrokeTessellator| {
-                path::render_path_source(
-                    src,
-                    color,
-                    transform,
-                    opts,
-                    theme,
-                    theme_primitive,
-                    fill_tessellator,
-                    stroke_tessellator,
-                    mesh,
-                )
-            };
+        let mut render = |src: path::PathEventSourceIter,
+                          opts: path::Options,
+                          color: Option<LinSrgba>,
+                          theme: &draw::Theme,
+                          fill_tessellator: &mut lyon::tessellation::FillTessellator,
+                          stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+                          vector_paths: Option<&mut Vec<VectorPath>>| {
+            path::render_path_source(
+                src,
+                color,
+                transform,
+                opts,
+                theme,
+                theme_primitive,
+                fill_tessellator,
+                stroke_tessellator,
+                vector_paths,
+                mesh,
+            )
+        };
 
         // Do the fill tessellation first.
         if !no_fill {
@@ -333,6 +339,7 @@ impl Polygon {
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
+                        vector_paths.as_deref_mut(),
                     );
                 }
                 PathEventSource::ColoredPoints { ref range, close } => {
@@ -349,6 +356,7 @@ impl Polygon {
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
+                        vector_paths.as_deref_mut(),
                     );
                 }
                 PathEventSource::TexturedPoints { ref range, close } => {
@@ -365,6 +373,7 @@ impl Polygon {
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
+                        vector_paths.as_deref_mut(),
                     );
                 }
             }
@@ -384,6 +393,7 @@ impl Polygon {
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
+                        vector_paths.as_deref_mut(),
                     );
                 }
                 PathEventSource::ColoredPoints { range, close } => {
@@ -404,6 +414,7 @@ impl Polygon {
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
+                        vector_paths.as_deref_mut(),
                     );
                 }
                 PathEventSource::TexturedPoints { range, close } => {
@@ -419,6 +430,7 @@ impl Polygon {
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
+                        vector_paths,
                     );
                 }
             }
@@ -0,0 +1,282 @@
+//! Items shared between the 3D solid primitives, i.e. **Cuboid**, **Sphere**, **Cylinder**,
+//! **Cone** and **Torus**.
+
+use crate::color::conv::IntoLinSrgba;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
+use crate::draw::{self, Drawing};
+use crate::geom::Point3;
+use crate::glam::{Mat3, Vec3};
+use std::ops;
+
+/// A trait implemented for all 3D solid draw primitives.
+pub trait SetSolid: Sized {
+    /// Access to the solid builder parameters.
+    fn solid_options_mut(&mut self) -> &mut SolidOptions;
+
+    /// Color each face of the solid with the given colors.
+    ///
+    /// Colors are assigned to faces in the order in which the faces are generated. If there are
+    /// fewer colors than faces, the colors are cycled.
+    ///
+    /// This overrides any previously specified `vertex_colors`.
+    fn face_colors<I, C>(mut self, colors: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let colors = colors.into_iter().map(|c| c.into_lin_srgba()).collect();
+        let opts = self.solid_options_mut();
+        opts.face_colors = Some(colors);
+        opts.vertex_colors = None;
+        self
+    }
+
+    /// Color each vertex of the solid with the given colors.
+    ///
+    /// Colors are assigned to vertices in the order in which the vertices are generated. If there
+    /// are fewer colors than vertices, the colors are cycled.
+    ///
+    /// This overrides any previously specified `face_colors`.
+    fn vertex_colors<I, C>(mut self, colors: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let colors = colors.into_iter().map(|c| c.into_lin_srgba()).collect();
+        let opts = self.solid_options_mut();
+        opts.vertex_colors = Some(colors);
+        opts.face_colors = None;
+        self
+    }
+
+    /// Specify the whole set of solid options.
+    fn solid_options(mut self, opts: SolidOptions) -> Self {
+        *self.solid_options_mut() = opts;
+        self
+    }
+}
+
+/// The set of options shared by all solid types.
+#[derive(Clone, Debug, Default)]
+pub struct SolidOptions {
+    pub position: position::Properties,
+    pub orientation: orientation::Properties,
+    pub color: Option<LinSrgba>,
+    pub face_colors: Option<Vec<LinSrgba>>,
+    pub vertex_colors: Option<Vec<LinSrgba>>,
+}
+
+/// The triangulated geometry of a solid, grouped into faces.
+///
+/// All triangles are wound counter-clockwise when viewed from outside the solid.
+#[derive(Clone, Debug, Default)]
+pub struct Geometry {
+    /// The unique vertex positions of the solid.
+    pub points: Vec<Point3>,
+    /// Every trio of indices describes a single triangle.
+    pub indices: Vec<u32>,
+    /// One range into `indices` for each face.
+    pub faces: Vec<ops::Range<usize>>,
+    /// The normal of each vertex, used to smoothly shade curved solids.
+    ///
+    /// If empty, each triangle is shaded using its own normal.
+    pub normals: Vec<Vec3>,
+}
+
+impl Geometry {
+    /// Push a new vertex position, returning its index.
+    pub fn push_point(&mut self, point: Point3) -> u32 {
+        let index = self.points.len() as u32;
+        self.points.push(point);
+        index
+    }
+
+    /// Push a new vertex position along with its normal, returning its index.
+    ///
+    /// Either all or none of the vertices of a **Geometry** should have a normal.
+    pub fn push_point_with_normal(&mut self, point: Point3, normal: Vec3) -> u32 {
+        self.normals.push(normal);
+        self.push_point(point)
+    }
+
+    /// Push a convex, planar face described by the given vertex indices.
+    ///
+    /// The face is triangulated as a fan around its first vertex.
+    pub fn push_face(&mut self, face: &[u32]) {
+        let start = self.indices.len();
+        if let Some((&first, rest)) = face.split_first() {
+            for pair in rest.windows(2) {
+                self.indices
+                    .extend([first, pair[0], pair[1]].iter().cloned());
+            }
+        }
+        let end = self.indices.len();
+        self.faces.push(start..end);
+    }
+
+    /// Push a face described by the given, already triangulated, vertex indices.
+    ///
+    /// Every trio of indices describes a single triangle.
+    pub fn push_triangulated_face<I>(&mut self, indices: I)
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        let start = self.indices.len();
+        self.indices.extend(indices);
+        let end = self.indices.len();
+        self.faces.push(start..end);
+    }
+
+    /// Produce an iterator yielding each triangle as a trio of points.
+    pub fn triangles(&self) -> impl Iterator<Item = [Point3; 3]> + '_ {
+        self.indices.chunks(3).map(move |ixs| {
+            let p = |i: u32| self.points[i as usize];
+            [p(ixs[0]), p(ixs[1]), p(ixs[2])]
+        })
+    }
+}
+
+/// Render the given solid geometry into the mesh, transformed and colored according to `opts`.
+pub(crate) fn render_themed(
+    opts: SolidOptions,
+    geometry: &Geometry,
+    ctxt: draw::renderer::RenderContext,
+    theme_primitive: &draw::theme::Primitive,
+    mesh: &mut draw::Mesh,
+) -> draw::renderer::PrimitiveRender {
+    let SolidOptions {
+        position,
+        orientation,
+        color,
+        face_colors,
+        vertex_colors,
+    } = opts;
+
+    // Determine the transform to apply to all points.
+    let global_transform = *ctxt.transform;
+    let local_transform = position.transform() * orientation.transform();
+    let transform = global_transform * local_transform;
+    let transform_point = |p: Point3| -> Point3 { transform.transform_point3(p) };
+    let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+    let transform_normal = |n: Vec3| -> Vec3 { (normal_matrix * n).normalize_or_zero() };
+
+    let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(theme_primitive));
+    let tex_coords = draw::mesh::vertex::default_tex_coords();
+    let v_start = mesh.raw_vertex_count() as u32;
+
+    // Whether or not faces share vertices.
+    let shared = match (face_colors, vertex_colors) {
+        // Faces do not share vertices, so that each may be uniquely colored.
+        (Some(colors), _) if !colors.is_empty() => {
+            for (i, face) in geometry.faces.iter().enumerate() {
+                let color = colors[i % colors.len()];
+                let start = mesh.raw_vertex_count() as u32;
+                let indices = &geometry.indices[face.clone()];
+                let vertices = indices.iter().map(|&ix| {
+                    let point = transform_point(geometry.points[ix as usize]);
+                    ((point, color), tex_coords).into()
+                });
+                let indices = (0..indices.len() as u32).map(|i| start + i);
+                mesh.extend(vertices, indices);
+            }
+            false
+        }
+        (_, Some(colors)) if !colors.is_empty() => {
+            let vertices = geometry.points.iter().enumerate().map(|(i, &p)| {
+                let color = colors[i % colors.len()];
+                ((transform_point(p), color), tex_coords).into()
+            });
+            let indices = geometry.indices.iter().map(|&i| v_start + i);
+            mesh.extend(vertices, indices);
+            true
+        }
+        _ => {
+            let vertices = geometry
+                .points
+                .iter()
+                .map(|&p| ((transform_point(p), color), tex_coords).into());
+            let indices = geometry.indices.iter().map(|&i| v_start + i);
+            mesh.extend(vertices, indices);
+            true
+        }
+    };
+
+    // Assign normals to the new vertices, if the solid has any.
+    if !geometry.normals.is_empty() {
+        let normal = |ix: u32| transform_normal(geometry.normals[ix as usize]);
+        if shared {
+            let normals = (0..geometry.points.len() as u32).map(normal);
+            mesh.set_normals(v_start as usize, normals);
+        } else {
+            let normals = geometry
+                .faces
+                .iter()
+                .flat_map(|face| geometry.indices[face.clone()].iter())
+                .map(|&ix| normal(ix));
+            mesh.set_normals(v_start as usize, normals);
+        }
+    }
+
+    draw::renderer::PrimitiveRender::default()
+}
+
+impl<'a, T> Drawing<'a, T>
+where
+    T: SetSolid + Into<Primitive>,
+    Primitive: Into<Option<T>>,
+{
+    /// Color each face of the solid with the given colors.
+    ///
+    /// Colors are assigned to faces in the order in which the faces are generated. If there are
+    /// fewer colors than faces, the colors are cycled.
+    pub fn face_colors<I, C>(self, colors: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.map_ty(|ty| ty.face_colors(colors))
+    }
+
+    /// Color each vertex of the solid with the given colors.
+    ///
+    /// Colors are assigned to vertices in the order in which the vertices are generated. If there
+    /// are fewer colors than vertices, the colors are cycled.
+    pub fn vertex_colors<I, C>(self, colors: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.map_ty(|ty| ty.vertex_colors(colors))
+    }
+
+    /// Specify the whole set of solid options.
+    pub fn solid_options(self, opts: SolidOptions) -> Self {
+        self.map_ty(|ty| ty.solid_options(opts))
+    }
+}
+
+impl SetSolid for SolidOptions {
+    fn solid_options_mut(&mut self) -> &mut SolidOptions {
+        self
+    }
+}
+
+impl SetOrientation for SolidOptions {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl SetPosition for SolidOptions {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl SetColor<ColorScalar> for SolidOptions {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.color)
+    }
+}
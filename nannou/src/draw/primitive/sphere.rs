@@ -0,0 +1,176 @@
+use crate::draw::primitive::solid::{self, SetSolid, SolidOptions};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{dimension, orientation, position};
+use crate::draw::properties::{
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition,
+};
+use crate::draw::{self, Drawing};
+use crate::glam::{vec3, Vec3};
+use std::f32::consts::PI;
+
+/// Properties related to drawing a **Sphere**.
+///
+/// The dimensions describe the diameter of the sphere along each axis, allowing for ellipsoids.
+#[derive(Clone, Debug, Default)]
+pub struct Sphere {
+    dimensions: dimension::Properties,
+    resolution: Option<usize>,
+    solid: SolidOptions,
+}
+
+/// The drawing context for a `Sphere`.
+pub type DrawingSphere<'a> = Drawing<'a, Sphere>;
+
+// Sphere-specific methods.
+
+impl Sphere {
+    /// The default number of segments around the sphere's vertical axis.
+    pub const DEFAULT_RESOLUTION: usize = 32;
+
+    /// Specify the width, height and depth of the **Sphere** via a given **radius**.
+    pub fn radius(self, radius: f32) -> Self {
+        let side = radius * 2.0;
+        self.w_h_d(side, side, side)
+    }
+
+    /// The number of segments around the sphere's vertical axis.
+    ///
+    /// The number of rings from pole to pole is half of this value.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingSphere<'a> {
+    /// Specify the width, height and depth of the **Sphere** via a given **radius**.
+    pub fn radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.radius(radius))
+    }
+
+    /// The number of segments around the sphere's vertical axis.
+    pub fn resolution(self, resolution: usize) -> Self {
+        self.map_ty(|ty| ty.resolution(resolution))
+    }
+}
+
+/// Produce the geometry for a UV sphere with the given radii centered on the origin.
+///
+/// The sphere has `segments` segments around the *y* axis and `rings` rings from pole to pole.
+/// Each quad (or triangle at the poles) of the resulting grid is a single face. Each vertex is
+/// given the normal of the ellipsoid's surface so that the sphere may be shaded smoothly.
+pub fn geometry(radii: Vec3, segments: usize, rings: usize) -> solid::Geometry {
+    let segments = segments.max(3) as u32;
+    let rings = rings.max(2) as u32;
+    let mut geometry = solid::Geometry::default();
+    let point = |phi: f32, theta: f32| {
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        vec3(sin_phi * cos_theta, cos_phi, sin_phi * sin_theta) * radii
+    };
+    let normal = |p: Vec3| (p / (radii * radii)).normalize_or_zero();
+
+    // The poles and the `rings - 1` rings of vertices between them.
+    let top = geometry.push_point_with_normal(vec3(0.0, radii.y, 0.0), Vec3::Y);
+    for r in 1..rings {
+        let phi = PI * r as f32 / rings as f32;
+        for s in 0..segments {
+            let theta = 2.0 * PI * s as f32 / segments as f32;
+            let p = point(phi, theta);
+            geometry.push_point_with_normal(p, normal(p));
+        }
+    }
+    let bottom = geometry.push_point_with_normal(vec3(0.0, -radii.y, 0.0), -Vec3::Y);
+    let ix = |r: u32, s: u32| 1 + (r - 1) * segments + s % segments;
+
+    for s in 0..segments {
+        geometry.push_face(&[top, ix(1, s + 1), ix(1, s)]);
+    }
+    for r in 1..rings - 1 {
+        for s in 0..segments {
+            geometry.push_face(&[ix(r, s), ix(r, s + 1), ix(r + 1, s + 1), ix(r + 1, s)]);
+        }
+    }
+    for s in 0..segments {
+        geometry.push_face(&[ix(rings - 1, s), ix(rings - 1, s + 1), bottom]);
+    }
+    geometry
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Sphere {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Sphere {
+            dimensions,
+            resolution,
+            solid,
+        } = self;
+        let w = dimensions.x.map(f32::abs).unwrap_or(100.0);
+        let h = dimensions.y.map(f32::abs).unwrap_or(100.0);
+        let d = dimensions.z.map(f32::abs).unwrap_or(100.0);
+        let segments = resolution.unwrap_or(Self::DEFAULT_RESOLUTION);
+        let rings = segments / 2;
+        let geometry = geometry(vec3(w, h, d) * 0.5, segments, rings);
+        solid::render_themed(
+            solid,
+            &geometry,
+            ctxt,
+            &draw::theme::Primitive::Sphere,
+            mesh,
+        )
+    }
+}
+
+impl SetOrientation for Sphere {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.solid)
+    }
+}
+
+impl SetPosition for Sphere {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.solid)
+    }
+}
+
+impl SetDimensions for Sphere {
+    fn properties(&mut self) -> &mut dimension::Properties {
+        SetDimensions::properties(&mut self.dimensions)
+    }
+}
+
+impl SetColor<ColorScalar> for Sphere {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.solid)
+    }
+}
+
+impl SetSolid for Sphere {
+    fn solid_options_mut(&mut self) -> &mut SolidOptions {
+        SetSolid::solid_options_mut(&mut self.solid)
+    }
+}
+
+// Primitive conversions.
+
+impl From<Sphere> for Primitive {
+    fn from(prim: Sphere) -> Self {
+        Primitive::Sphere(prim)
+    }
+}
+
+impl Into<Option<Sphere>> for Primitive {
+    fn into(self) -> Option<Sphere> {
+        match self {
+            Primitive::Sphere(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
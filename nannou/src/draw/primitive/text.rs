@@ -1,13 +1,19 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::drawing::DrawingContext;
+use crate::draw::export::{Paint, VectorPath};
 use crate::draw::primitive::Primitive;
 use crate::draw::properties::spatial::{self, dimension, orientation, position};
 use crate::draw::properties::{
     ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition,
 };
+use crate::draw::renderer::{SdfEdge, TextMode, VertexMode};
 use crate::draw::{self, theme, Drawing};
 use crate::geom::{self, Point2};
+use crate::glam::{Mat4, Vec2};
 use crate::text::{self, Align, Font, FontSize, Justify, Layout, Scalar, Wrap};
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillOptions, FillRule};
+use std::ops::Range;
 
 /// Properties related to drawing the **Text** primitive.
 #[derive(Clone, Debug)]
@@ -16,19 +22,114 @@ pub struct Text {
     style: Style,
     // The byte range into the `Draw` context's text buffer.
     text: std::ops::Range<usize>,
+    // The range into the `Draw` context's path event buffer of the path to lay the text along.
+    path: Option<std::ops::Range<usize>>,
 }
 
 /// Styling properties for the **Text** primitive.
+///
+/// The `outline`, `glow` and `drop_shadow` effects are only displayed by renderers using
+/// `TextMode::Sdf`. The extent of each effect is limited by the spread of the glyph distance
+/// fields, relative to the font size.
 #[derive(Clone, Debug, Default)]
 pub struct Style {
     pub color: Option<LinSrgba>,
     pub glyph_colors: Vec<LinSrgba>, // Overrides `color` if non-empty.
     pub layout: text::layout::Builder,
+    pub outline: Option<Outline>,
+    pub glow: Option<Glow>,
+    pub drop_shadow: Option<DropShadow>,
+    /// Styling applied to byte ranges of the text, where later spans take precedence.
+    pub spans: Vec<(Range<usize>, Span)>,
+    /// The layout of the text along its path, if it has one.
+    pub path: text::path::Options,
+}
+
+/// Styling applied to a byte range of the **Text**.
+///
+/// Unspecified attributes are inherited from the **Text**. A character is underlined or struck
+/// through if any span containing it is.
+#[derive(Clone, Debug, Default)]
+pub struct Span {
+    pub layout: text::span::Style,
+    /// Overridden by the **Text**'s `glyph_colors`, if any.
+    pub color: Option<LinSrgba>,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+/// An outline drawn around the outside of each glyph.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Outline {
+    /// The width of the outline in points.
+    pub width: Scalar,
+    pub color: LinSrgba,
+}
+
+/// A glow that fades out from the edge of each glyph, or its outline if it has one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glow {
+    /// The distance in points over which the glow fades out.
+    pub radius: Scalar,
+    pub color: LinSrgba,
+}
+
+/// A shadow drawn beneath each glyph and its outline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DropShadow {
+    /// The offset of the shadow from the text in points.
+    pub offset: Vec2,
+    /// The distance in points over which the edge of the shadow is blurred.
+    pub softness: Scalar,
+    pub color: LinSrgba,
 }
 
 /// The drawing context for the **Text** primitive.
 pub type DrawingText<'a> = Drawing<'a, Text>;
 
+impl Span {
+    /// The primary font used to display the span.
+    pub fn font(mut self, font: Font) -> Self {
+        self.layout = self.layout.font(font);
+        self
+    }
+
+    /// The font size of the span.
+    pub fn font_size(mut self, size: FontSize) -> Self {
+        self.layout = self.layout.font_size(size);
+        self
+    }
+
+    /// Shift the span up from the line's baseline by the given distance.
+    ///
+    /// Negative values shift the span down, e.g. for subscripts.
+    pub fn baseline_offset(mut self, offset: Scalar) -> Self {
+        self.layout = self.layout.baseline_offset(offset);
+        self
+    }
+
+    /// The color of the span.
+    pub fn color<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.color = Some(color.into_lin_srgba());
+        self
+    }
+
+    /// Draw a line beneath the span.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Draw a line through the middle of the span.
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+}
+
 impl Text {
     /// Begin drawing some text.
     pub fn new(ctxt: DrawingContext, text: &str) -> Self {
@@ -42,6 +143,7 @@ impl Text {
             spatial,
             style,
             text,
+            path: None,
         }
     }
 
@@ -92,6 +194,45 @@ impl Text {
         self.map_layout(|l| l.font(font))
     }
 
+    /// Specify the fonts used to display characters that are missing from the primary font.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.map_layout(|l| l.fallback_fonts(fonts))
+    }
+
+    /// Append a font to the end of the font fallback chain.
+    pub fn fallback_font(self, font: Font) -> Self {
+        self.map_layout(|l| l.fallback_font(font))
+    }
+
+    /// Specify the OpenType features applied to the text.
+    pub fn features<I>(self, features: I) -> Self
+    where
+        I: IntoIterator<Item = text::font::Feature>,
+    {
+        self.map_layout(|l| l.features(features))
+    }
+
+    /// Set the OpenType feature with the given tag, e.g. `feature(b"smcp", 1)`.
+    pub fn feature(self, tag: &[u8; 4], value: u32) -> Self {
+        self.map_layout(|l| l.feature(tag, value))
+    }
+
+    /// Specify the values of the variation axes applied to variable fonts.
+    pub fn variations<I>(self, variations: I) -> Self
+    where
+        I: IntoIterator<Item = text::font::Variation>,
+    {
+        self.map_layout(|l| l.variations(variations))
+    }
+
+    /// Set the value of the variation axis with the given tag, e.g. `variation(b"wght", 700.0)`.
+    pub fn variation(self, tag: &[u8; 4], value: f32) -> Self {
+        self.map_layout(|l| l.variation(tag, value))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: Justify) -> Self {
         self.map_layout(|l| l.justify(justify))
@@ -156,6 +297,73 @@ impl Text {
         self.style.glyph_colors = colors;
         self
     }
+
+    /// Draw an outline of the given width in points around each glyph.
+    ///
+    /// Only displayed when rendering with `TextMode::Sdf`.
+    pub fn outline(mut self, width: Scalar, color: LinSrgba) -> Self {
+        self.style.outline = Some(Outline { width, color });
+        self
+    }
+
+    /// Draw a glow that fades out over the given radius in points from the edge of each glyph.
+    ///
+    /// Only displayed when rendering with `TextMode::Sdf`.
+    pub fn glow(mut self, radius: Scalar, color: LinSrgba) -> Self {
+        self.style.glow = Some(Glow { radius, color });
+        self
+    }
+
+    /// Draw a shadow beneath the text at the given offset, blurred by the given softness in
+    /// points.
+    ///
+    /// Only displayed when rendering with `TextMode::Sdf`.
+    pub fn drop_shadow(mut self, offset: Vec2, softness: Scalar, color: LinSrgba) -> Self {
+        self.style.drop_shadow = Some(DropShadow {
+            offset,
+            softness,
+            color,
+        });
+        self
+    }
+
+    /// Apply the given span's styling to the given byte range of the text.
+    pub fn span(mut self, range: Range<usize>, span: Span) -> Self {
+        self.style.spans.push((range, span));
+        self
+    }
+
+    /// Lay the text out along the given path rather than along straight lines.
+    ///
+    /// See the `text::path` module for details.
+    pub fn along_path<I>(mut self, ctxt: DrawingContext, path: I) -> Self
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        let start = ctxt.path_event_buffer.len();
+        ctxt.path_event_buffer.extend(path);
+        let end = ctxt.path_event_buffer.len();
+        self.path = Some(start..end);
+        self
+    }
+
+    /// Shift each line along the path by the given distance after alignment.
+    pub fn path_start_offset(mut self, offset: Scalar) -> Self {
+        self.style.path.start_offset = offset;
+        self
+    }
+
+    /// Specify the alignment of each line along the length of the path.
+    pub fn path_align(mut self, align: Align) -> Self {
+        self.style.path.align = align;
+        self
+    }
+
+    /// Insert the given additional space between each glyph along the path.
+    pub fn path_spacing(mut self, spacing: Scalar) -> Self {
+        self.style.path.spacing = spacing;
+        self
+    }
 }
 
 impl<'a> DrawingText<'a> {
@@ -184,6 +392,53 @@ impl<'a> DrawingText<'a> {
         self.map_ty(|ty| ty.font(font))
     }
 
+    /// Specify the fonts used to display characters that are missing from the primary font.
+    ///
+    /// A font fallback chain may be produced from a `text::font::Map` via
+    /// `Map::fallback_chain`.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = text::Font>,
+    {
+        self.map_ty(|ty| ty.fallback_fonts(fonts))
+    }
+
+    /// Append a font to the end of the font fallback chain.
+    pub fn fallback_font(self, font: text::Font) -> Self {
+        self.map_ty(|ty| ty.fallback_font(font))
+    }
+
+    /// Specify the OpenType features applied to the text.
+    pub fn features<I>(self, features: I) -> Self
+    where
+        I: IntoIterator<Item = text::font::Feature>,
+    {
+        self.map_ty(|ty| ty.features(features))
+    }
+
+    /// Set the OpenType feature with the given tag, e.g. `feature(b"smcp", 1)`.
+    ///
+    /// A value of `0` disables the feature, while `1` enables it.
+    pub fn feature(self, tag: &[u8; 4], value: u32) -> Self {
+        self.map_ty(|ty| ty.feature(tag, value))
+    }
+
+    /// Specify the values of the variation axes applied to variable fonts.
+    pub fn variations<I>(self, variations: I) -> Self
+    where
+        I: IntoIterator<Item = text::font::Variation>,
+    {
+        self.map_ty(|ty| ty.variations(variations))
+    }
+
+    /// Set the value of the variation axis with the given tag, e.g. `variation(b"wght", 700.0)`.
+    ///
+    /// This only affects variable fonts that have the axis. As the value may change every frame,
+    /// this allows for animating the weight, width or slant of text.
+    pub fn variation(self, tag: &[u8; 4], value: f32) -> Self {
+        self.map_ty(|ty| ty.variation(tag, value))
+    }
+
     /// Build the **Text** with the given **Style**.
     pub fn with_style(self, style: Style) -> Self {
         self.map_ty(|ty| ty.with_style(style))
@@ -257,23 +512,100 @@ impl<'a> DrawingText<'a> {
 
         self.map_ty(|ty| ty.glyph_colors(glyph_colors))
     }
+
+    /// Draw an outline of the given width in points around each glyph.
+    ///
+    /// Only displayed when rendering with `TextMode::Sdf`.
+    pub fn outline<C>(self, width: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.map_ty(|ty| ty.outline(width, color))
+    }
+
+    /// Draw a glow that fades out over the given radius in points from the edge of each glyph.
+    ///
+    /// Only displayed when rendering with `TextMode::Sdf`.
+    pub fn glow<C>(self, radius: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.map_ty(|ty| ty.glow(radius, color))
+    }
+
+    /// Draw a shadow beneath the text at the given offset, blurred by the given softness in
+    /// points.
+    ///
+    /// Only displayed when rendering with `TextMode::Sdf`.
+    pub fn drop_shadow<C>(self, offset: Vec2, softness: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.map_ty(|ty| ty.drop_shadow(offset, softness, color))
+    }
+
+    /// Apply the given span's styling to the given byte range of the text.
+    ///
+    /// Spans may specify their own font, font size, baseline offset, color, underline and
+    /// strikethrough. Where spans overlap, later spans take precedence.
+    pub fn span(self, range: Range<usize>, span: Span) -> Self {
+        self.map_ty(|ty| ty.span(range, span))
+    }
+
+    /// Lay the text out along the given path rather than along straight lines.
+    ///
+    /// Each glyph is rotated to follow the path's tangent. The baseline of the first line lies on
+    /// the path, while following lines are offset along the path's normal. Glyphs that do not fit
+    /// along the path are not drawn.
+    pub fn along_path<I>(self, path: I) -> Self
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        self.map_ty_with_context(|ty, ctxt| ty.along_path(ctxt, path))
+    }
+
+    /// Shift each line along the path by the given distance after alignment.
+    pub fn path_start_offset(self, offset: text::Scalar) -> Self {
+        self.map_ty(|ty| ty.path_start_offset(offset))
+    }
+
+    /// Specify the alignment of each line along the length of the path.
+    ///
+    /// By default, lines begin at the start of the path.
+    pub fn path_align(self, align: Align) -> Self {
+        self.map_ty(|ty| ty.path_align(align))
+    }
+
+    /// Insert the given additional space between each glyph along the path.
+    pub fn path_spacing(self, spacing: text::Scalar) -> Self {
+        self.map_ty(|ty| ty.path_spacing(spacing))
+    }
 }
 
 impl draw::renderer::RenderPrimitive for Text {
     fn render_primitive(
         self,
-        ctxt: draw::renderer::RenderContext,
+        mut ctxt: draw::renderer::RenderContext,
         mesh: &mut draw::Mesh,
     ) -> draw::renderer::PrimitiveRender {
         let Text {
             spatial,
             style,
             text,
+            path,
         } = self;
         let Style {
             color,
             glyph_colors,
             layout,
+            outline,
+            glow,
+            drop_shadow,
+            spans,
+            path: path_options,
         } = style;
         let layout = layout.build();
         let (maybe_x, maybe_y, maybe_z) = (
@@ -291,17 +623,106 @@ impl draw::renderer::RenderPrimitive for Text {
         let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
 
         let text_str = &ctxt.text_buffer[text.clone()];
-        let text = text::text(text_str).layout(&layout).build(rect);
+        let text_spans = spans.iter().map(|(range, span)| text::span::Span {
+            range: range.clone(),
+            style: span.layout.clone(),
+        });
+        let text = text::text(text_str)
+            .layout(&layout)
+            .spans(text_spans)
+            .build(rect);
+
+        // Each glyph is colored by the first character that it represents, using `glyph_colors`,
+        // then the color of the spans containing the character, then `color`.
+        let glyph_colors: Vec<LinSrgba> = text
+            .line_infos()
+            .iter()
+            .zip(text.shaped_lines())
+            .flat_map(|(li, line)| line.glyphs.iter().map(move |g| (li, g)))
+            .map(|(li, g)| {
+                let span_color = || span_style(&spans, li.start_byte + g.byte).color;
+                glyph_colors
+                    .get(li.start_char + g.char)
+                    .cloned()
+                    .or_else(span_color)
+                    .unwrap_or(color)
+            })
+            .collect();
+        let path = path.map(|range| &ctxt.path_event_buffer[range]);
+        let placements = glyph_placements(&text, path, &path_options);
+        let along_path = path.is_some();
+        let decorations = decoration_quads(&text, &spans, &glyph_colors, &placements, along_path);
+
+        // Determine the transform to apply to all points.
+        let global_transform = *ctxt.transform;
+        let local_transform = spatial.position.transform() * spatial.orientation.transform();
+        let transform = global_transform * local_transform;
+
+        // When exporting, record the outline of each glyph and each decoration as a vector path.
+        if let Some(vector_paths) = ctxt.vector_paths.as_deref_mut() {
+            let rule = FillRule::NonZero;
+            let glyphs = text
+                .shaped_lines()
+                .iter()
+                .flat_map(|line| line.glyphs.iter());
+            for ((g, &color), &(_, placement)) in glyphs.zip(&glyph_colors).zip(&placements) {
+                let placement = match placement {
+                    None => continue,
+                    Some(placement) => placement,
+                };
+                let events = text.glyph_path_events(g);
+                if events.is_empty() {
+                    continue;
+                }
+                let origin = placement.origin.extend(0.0);
+                let glyph_transform = transform
+                    * Mat4::from_translation(origin)
+                    * Mat4::from_rotation_z(placement.rotation);
+                let paint = Paint::Fill { color, rule };
+                vector_paths.push(VectorPath::new(events, glyph_transform, paint));
+            }
+            for &(corners, color) in &decorations {
+                let points = corners.iter().map(|p| lyon::math::point(p.x, p.y));
+                let events = lyon::path::iterator::FromPolyline::closed(points).collect();
+                let paint = Paint::Fill { color, rule };
+                vector_paths.push(VectorPath::new(events, transform, paint));
+            }
+            return draw::renderer::PrimitiveRender::default();
+        }
+
+        // Distance field glyphs are resolution independent, so are laid out in local coordinates.
+        if ctxt.glyph_cache.mode == TextMode::Sdf {
+            let effects = Effects {
+                outline,
+                glow,
+                drop_shadow,
+            };
+            let (colors, placements) = (&glyph_colors, &placements);
+            render_sdf_glyphs(
+                &mut ctxt, mesh, &text, &transform, colors, placements, effects,
+            );
+            let mode = VertexMode::SdfText;
+            push_decorations(ctxt.vertex_modes, mesh, &transform, &decorations, mode);
+            return draw::renderer::PrimitiveRender::sdf_text();
+        }
+
+        // The glyph cache cannot rasterise font variations, so varied glyphs are tessellated from
+        // their outlines instead.
+        let varied: Vec<bool> = text
+            .shaped_lines()
+            .iter()
+            .flat_map(|line| line.glyphs.iter())
+            .map(|g| text.is_glyph_varied(g))
+            .collect();
 
         // Queue the glyphs to be cached
-        let font_id = text::font::id(text.font());
         let positioned_glyphs: Vec<_> = text
-            .rt_glyphs(
+            .rt_glyphs_with_font_ids(
                 ctxt.output_attachment_size,
                 ctxt.output_attachment_scale_factor,
             )
             .collect();
-        for glyph in positioned_glyphs.iter() {
+        for ((font_id, glyph), _) in positioned_glyphs.iter().zip(&varied).filter(|(_, &v)| !v) {
             ctxt.glyph_cache.queue_glyph(font_id.index(), glyph.clone());
         }
 
@@ -340,11 +761,6 @@ impl draw::renderer::RenderPrimitive for Text {
             }
         }
 
-        // Determine the transform to apply to all points.
-        let global_transform = *ctxt.transform;
-        let local_transform = spatial.position.transform() * spatial.orientation.transform();
-        let transform = global_transform * local_transform;
-
         // A function for converting RustType rects to nannou rects.
         let scale_factor = ctxt.output_attachment_scale_factor;
         let (out_w, out_h) = ctxt.output_attachment_size.into();
@@ -357,25 +773,53 @@ impl draw::renderer::RenderPrimitive for Text {
             geom::Rect::from_corners([l, b].into(), [r, t].into())
         };
 
-        // Skips non-rendered colors (e.g. due to line breaks),
-        //   assuming LineInfos are ordered by ascending character position.
-        let glyph_colors_iter = text
-            .line_infos()
-            .iter()
-            .flat_map(|li| li.char_range())
-            .take_while(|&i| i < glyph_colors.len())
-            .map(|i| &glyph_colors[i])
-            // Repeat `color` if more glyphs than glyph_colors
-            .chain(std::iter::repeat(&color));
-
         // Extend the mesh with a rect for each displayed glyph.
-        for (g, g_color) in positioned_glyphs.iter().zip(glyph_colors_iter) {
+        let shaped_glyphs = text
+            .shaped_lines()
+            .iter()
+            .flat_map(|line| line.glyphs.iter());
+        let glyphs = positioned_glyphs
+            .iter()
+            .zip(shaped_glyphs)
+            .zip(&varied)
+            .zip(&glyph_colors)
+            .zip(&placements);
+        for (((((font_id, g), shaped_g), &varied), g_color), &(layout_origin, placement)) in glyphs
+        {
+            // Skip glyphs that do not fit along the path.
+            let placement = match placement {
+                None => continue,
+                Some(placement) => placement,
+            };
+            if varied {
+                let events = text.glyph_path_events(shaped_g);
+                let origin = placement.origin.extend(0.0);
+                let glyph_transform = transform
+                    * Mat4::from_translation(origin)
+                    * Mat4::from_rotation_z(placement.rotation);
+                let start_ix = mesh.points().len();
+                let mut mesh_builder =
+                    draw::mesh::MeshBuilder::single_color(mesh, glyph_transform, *g_color);
+                let options = FillOptions::default().with_fill_rule(FillRule::NonZero);
+                let res = ctxt
+                    .fill_tessellator
+                    .tessellate(events, &options, &mut mesh_builder);
+                if let Err(err) = res {
+                    eprintln!("failed to tessellate glyph outline: {:?}", err);
+                }
+                ctxt.vertex_modes.resize(start_ix, VertexMode::Text);
+                ctxt.vertex_modes
+                    .resize(mesh.points().len(), VertexMode::Color);
+                continue;
+            }
             if let Ok(Some((uv_rect, screen_rect))) = ctxt.glyph_cache.rect_for(font_id.index(), &g)
             {
                 let rect = to_nannou_rect(screen_rect);
 
-                // Create a mesh-compatible vertex from the position and tex_coords.
+                // Create a mesh-compatible vertex from the position and tex_coords, placing the
+                // position relative to the glyph's origin.
                 let v = |p: Point2, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
+                    let p = placement.transform_point(p - layout_origin);
                     let p = transform.transform_point3([p.x, p.y, 0.0].into());
                     let point = draw::mesh::vertex::Point::from(p);
                     draw::mesh::vertex::new(point, g_color.to_owned(), tex_coords.into())
@@ -412,10 +856,361 @@ impl draw::renderer::RenderPrimitive for Text {
             }
         }
 
+        let mode = VertexMode::Text;
+        push_decorations(ctxt.vertex_modes, mesh, &transform, &decorations, mode);
         draw::renderer::PrimitiveRender::text()
     }
 }
 
+// The effects applied to distance field text.
+struct Effects {
+    outline: Option<Outline>,
+    glow: Option<Glow>,
+    drop_shadow: Option<DropShadow>,
+}
+
+// Extend the mesh with a quad for each layer of each glyph, using the glyph cache's signed
+// distance field atlas.
+//
+// Layers are drawn from back to front: the drop shadow, glow, outline and finally the glyph.
+fn render_sdf_glyphs(
+    ctxt: &mut draw::renderer::RenderContext,
+    mesh: &mut draw::Mesh,
+    text: &text::Text,
+    transform: &Mat4,
+    glyph_colors: &[LinSrgba],
+    placements: &[GlyphPlacement],
+    effects: Effects,
+) {
+    let draw::renderer::RenderContext {
+        ref mut glyph_cache,
+        ref mut sdf_edges,
+        ..
+    } = *ctxt;
+    let fonts = text.fonts();
+    let font_ids: Vec<_> = fonts.iter().map(text::font::id).collect();
+    let variations = &text.layout().variations;
+    let glyph_px = glyph_cache.sdf_atlas.glyph_px();
+    let spread = glyph_cache.sdf_atlas.spread();
+
+    // Collect each placed glyph along with the size of an atlas pixel in points at its font size.
+    let mut glyphs = vec![];
+    let line_glyphs = text
+        .shaped_lines()
+        .iter()
+        .flat_map(|line| line.glyphs.iter());
+    let line_glyphs = line_glyphs.zip(glyph_colors).zip(placements);
+    for ((g, &g_color), &(_, placement)) in line_glyphs {
+        let placement = match placement {
+            None => continue,
+            Some(placement) => placement,
+        };
+        let font = &fonts[g.font];
+        if let Some(entry) = glyph_cache.sdf_entry(font_ids[g.font], font, g.id, variations) {
+            let atlas_scale = text::pt_to_scale(g.font_size).y / glyph_px;
+            glyphs.push((entry, placement, atlas_scale, g_color));
+        }
+    }
+
+    // Convert a distance in points to a normalised distance field value at the given atlas scale.
+    let to_value = |pts: Scalar, atlas_scale: Scalar| pts / atlas_scale / (2.0 * spread);
+
+    // Push a quad for every glyph with the given offset, edge and color.
+    //
+    // The edge of each glyph is produced from its atlas scale via `edge`.
+    let mut push_layer = |offset: Vec2,
+                          edge: &dyn Fn(Scalar) -> SdfEdge,
+                          layer_color: Option<LinSrgba>| {
+        for &(entry, placement, atlas_scale, g_color) in &glyphs {
+            let edge = edge(atlas_scale);
+            let b = entry.bounds;
+            let color = layer_color.unwrap_or(g_color);
+
+            // Create a mesh-compatible vertex from the position relative to the glyph's origin in
+            // atlas pixels and tex_coords.
+            let v = |[x, y]: [Scalar; 2], tex_coords: [f32; 2]| -> draw::mesh::Vertex {
+                let p = placement.transform_point(Vec2::new(x, y) * atlas_scale) + offset;
+                let p = transform.transform_point3([p.x, p.y, 0.0].into());
+                let point = draw::mesh::vertex::Point::from(p);
+                draw::mesh::vertex::new(point, color, tex_coords.into())
+            };
+
+            // The sides of the UV rect.
+            let uv_l = entry.uv.min.x;
+            let uv_t = entry.uv.min.y;
+            let uv_r = entry.uv.max.x;
+            let uv_b = entry.uv.max.y;
+
+            // Insert the vertices along with their edges.
+            let start_ix = mesh.points().len() as u32;
+            mesh.push_vertex(v([b.left(), b.top()], [uv_l, uv_t]));
+            mesh.push_vertex(v([b.left(), b.bottom()], [uv_l, uv_b]));
+            mesh.push_vertex(v([b.right(), b.bottom()], [uv_r, uv_b]));
+            mesh.push_vertex(v([b.right(), b.top()], [uv_r, uv_t]));
+            sdf_edges.resize(start_ix as usize, SdfEdge::default());
+            sdf_edges.extend((0..4).map(|_| edge));
+
+            // Now the indices.
+            let [tl_ix, bl_ix, br_ix, tr_ix] = [start_ix, start_ix + 1, start_ix + 2, start_ix + 3];
+            mesh.push_index(tl_ix);
+            mesh.push_index(bl_ix);
+            mesh.push_index(br_ix);
+            mesh.push_index(tl_ix);
+            mesh.push_index(br_ix);
+            mesh.push_index(tr_ix);
+        }
+    };
+
+    // Effects outside of the outline begin at the outline's edge.
+    let outline = effects.outline;
+    let base = |atlas_scale| {
+        0.5 - outline
+            .map(|o| to_value(o.width, atlas_scale))
+            .unwrap_or(0.0)
+    };
+    if let Some(shadow) = effects.drop_shadow {
+        let edge = |atlas_scale| SdfEdge {
+            threshold: base(atlas_scale),
+            softness: to_value(shadow.softness, atlas_scale),
+        };
+        push_layer(shadow.offset, &edge, Some(shadow.color));
+    }
+    if let Some(glow) = effects.glow {
+        let edge = |atlas_scale| {
+            let half_radius = to_value(glow.radius, atlas_scale) / 2.0;
+            SdfEdge {
+                threshold: base(atlas_scale) - half_radius,
+                softness: half_radius,
+            }
+        };
+        push_layer(Vec2::ZERO, &edge, Some(glow.color));
+    }
+    if let Some(outline) = effects.outline {
+        let edge = |atlas_scale| SdfEdge {
+            threshold: base(atlas_scale),
+            softness: 0.0,
+        };
+        push_layer(Vec2::ZERO, &edge, Some(outline.color));
+    }
+    let edge = |_| SdfEdge {
+        threshold: 0.5,
+        softness: 0.0,
+    };
+    push_layer(Vec2::ZERO, &edge, None);
+}
+
+// The draw-specific styling of a single character, resolved from the spans that contain it.
+#[derive(Default)]
+struct SpanStyle {
+    color: Option<LinSrgba>,
+    underline: bool,
+    strikethrough: bool,
+    baseline_offset: Scalar,
+}
+
+// A contiguous run of glyphs within a line that share an underline or strikethrough.
+//
+// Along a path, each run contains a single glyph with the given layout origin and placement.
+struct DecorationRun {
+    font: usize,
+    font_size: FontSize,
+    baseline_offset: Scalar,
+    color: LinSrgba,
+    x: geom::Range,
+    placement: Option<(Point2, text::path::Placement)>,
+}
+
+// The origin of a glyph within the text's layout, along with its placement. The placement is
+// `None` if the glyph does not fit along the text's path.
+type GlyphPlacement = (Point2, Option<text::path::Placement>);
+
+// Produce the placement of every glyph in the text in order.
+//
+// Without a path, each glyph is placed at its origin within the layout.
+fn glyph_placements(
+    text: &text::Text,
+    path: Option<&[PathEvent]>,
+    options: &text::path::Options,
+) -> Vec<GlyphPlacement> {
+    let mut placements = vec![];
+    let mut line_starts = vec![];
+    for (line_ix, (line, line_rect)) in text.shaped_lines_with_rects().enumerate() {
+        line_starts.push(placements.len());
+        for (glyph, g) in line.glyphs.iter().enumerate() {
+            let origin = line_rect.bottom_left() + g.position;
+            let placement = text::path::Placement {
+                line: line_ix,
+                glyph,
+                origin,
+                rotation: 0.0,
+            };
+            placements.push((origin, Some(placement)));
+        }
+    }
+    if let Some(path) = path {
+        for (_, placement) in &mut placements {
+            *placement = None;
+        }
+        for p in text::path::place_glyphs(text, path.iter().cloned(), options) {
+            placements[line_starts[p.line] + p.glyph].1 = Some(p);
+        }
+    }
+    placements
+}
+
+// Resolve the styling of the character at the given byte index.
+fn span_style(spans: &[(Range<usize>, Span)], byte: usize) -> SpanStyle {
+    let mut style = SpanStyle::default();
+    for (_, span) in spans.iter().filter(|(range, _)| range.contains(&byte)) {
+        style.color = span.color.or(style.color);
+        style.underline |= span.underline;
+        style.strikethrough |= span.strikethrough;
+        style.baseline_offset = span.layout.baseline_offset.unwrap_or(style.baseline_offset);
+    }
+    style
+}
+
+// Produce a quad and color for the underline and strikethrough of each run of decorated glyphs.
+//
+// `glyph_colors` and `placements` contain the color and placement of every glyph in the text in
+// order. Quad corners are ordered top left, bottom left, bottom right, top right.
+fn decoration_quads(
+    text: &text::Text,
+    spans: &[(Range<usize>, Span)],
+    glyph_colors: &[LinSrgba],
+    placements: &[GlyphPlacement],
+    along_path: bool,
+) -> Vec<([Point2; 4], LinSrgba)> {
+    let mut quads = vec![];
+    if !spans.iter().any(|(_, s)| s.underline || s.strikethrough) {
+        return quads;
+    }
+    let fonts = text.fonts();
+    let mut glyphs = glyph_colors.iter().zip(placements);
+    for ((line, line_rect), li) in text.shaped_lines_with_rects().zip(text.line_infos()) {
+        // Finish the given run, producing its quad.
+        let mut finish = |run: DecorationRun, strikethrough: bool| {
+            let font = &fonts[run.font];
+            let m = if strikethrough {
+                text::span::strikethrough_metrics(font, run.font_size)
+            } else {
+                text::span::underline_metrics(font, run.font_size)
+            };
+            let y = line_rect.bottom() + run.baseline_offset + m.offset;
+            let y = geom::Range::new(y - m.thickness / 2.0, y + m.thickness / 2.0);
+            let rect = geom::Rect { x: run.x, y };
+            let mut corners = [
+                rect.top_left(),
+                rect.bottom_left(),
+                rect.bottom_right(),
+                rect.top_right(),
+            ];
+            if let Some((layout_origin, placement)) = run.placement {
+                for c in &mut corners {
+                    *c = placement.transform_point(*c - layout_origin);
+                }
+            }
+            quads.push((corners, run.color));
+        };
+
+        // The current underline and strikethrough runs.
+        let mut runs: [Option<DecorationRun>; 2] = [None, None];
+        for g in &line.glyphs {
+            let (&color, &(layout_origin, placement)) = glyphs.next().expect("no glyph color");
+            let style = span_style(spans, li.start_byte + g.byte);
+            // Glyphs that do not fit along the path are not decorated.
+            let placement = match placement {
+                Some(p) if along_path => Some((layout_origin, p)),
+                Some(_) => None,
+                None => {
+                    for (i, run) in runs.iter_mut().enumerate() {
+                        if let Some(r) = run.take() {
+                            finish(r, i == 1);
+                        }
+                    }
+                    continue;
+                }
+            };
+            let x = line_rect.left() + g.position.x;
+            let x = geom::Range::new(x, x + g.advance);
+            let decorated = [style.underline, style.strikethrough];
+            for (i, (run, decorated)) in runs.iter_mut().zip(decorated).enumerate() {
+                let strikethrough = i == 1;
+                match run {
+                    Some(r)
+                        if decorated
+                            && !along_path
+                            && r.font == g.font
+                            && r.font_size == g.font_size
+                            && r.baseline_offset == style.baseline_offset
+                            && r.color == color =>
+                    {
+                        r.x.end = r.x.end.max(x.end);
+                    }
+                    _ => {
+                        if let Some(r) = run.take() {
+                            finish(r, strikethrough);
+                        }
+                        if decorated {
+                            *run = Some(DecorationRun {
+                                font: g.font,
+                                font_size: g.font_size,
+                                baseline_offset: style.baseline_offset,
+                                color,
+                                x,
+                                placement,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        for (i, run) in runs.iter_mut().enumerate() {
+            if let Some(r) = run.take() {
+                finish(r, i == 1);
+            }
+        }
+    }
+    quads
+}
+
+// Extend the mesh with a solid colored quad for each of the given decoration quads.
+//
+// Vertices pushed by the primitive before the decorations are assigned the given `glyph_mode`.
+fn push_decorations(
+    vertex_modes: &mut Vec<VertexMode>,
+    mesh: &mut draw::Mesh,
+    transform: &Mat4,
+    decorations: &[([Point2; 4], LinSrgba)],
+    glyph_mode: VertexMode,
+) {
+    for &(corners, color) in decorations {
+        // Create a mesh-compatible vertex from the position.
+        let v = |p: Point2| -> draw::mesh::Vertex {
+            let p = transform.transform_point3([p.x, p.y, 0.0].into());
+            let point = draw::mesh::vertex::Point::from(p);
+            draw::mesh::vertex::new(point, color, [0.0, 0.0].into())
+        };
+
+        // Insert the vertices along with their modes.
+        let start_ix = mesh.points().len() as u32;
+        for &corner in &corners {
+            mesh.push_vertex(v(corner));
+        }
+        vertex_modes.resize(start_ix as usize, glyph_mode);
+        vertex_modes.extend((0..4).map(|_| VertexMode::Color));
+
+        // Now the indices.
+        let [tl_ix, bl_ix, br_ix, tr_ix] = [start_ix, start_ix + 1, start_ix + 2, start_ix + 3];
+        mesh.push_index(tl_ix);
+        mesh.push_index(bl_ix);
+        mesh.push_index(br_ix);
+        mesh.push_index(tl_ix);
+        mesh.push_index(br_ix);
+        mesh.push_index(tr_ix);
+    }
+}
+
 impl SetOrientation for Text {
     fn properties(&mut self) -> &mut orientation::Properties {
         SetOrientation::properties(&mut self.spatial)
@@ -0,0 +1,182 @@
+use crate::draw::primitive::solid::{self, SetSolid, SolidOptions};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
+use crate::draw::{self, Drawing};
+use crate::glam::vec3;
+use std::f32::consts::PI;
+
+/// Properties related to drawing a **Torus**.
+///
+/// The torus lies within the *xz* plane, encircling the *y* axis.
+#[derive(Clone, Debug, Default)]
+pub struct Torus {
+    radius: Option<f32>,
+    tube_radius: Option<f32>,
+    resolution: Option<usize>,
+    tube_resolution: Option<usize>,
+    solid: SolidOptions,
+}
+
+/// The drawing context for a `Torus`.
+pub type DrawingTorus<'a> = Drawing<'a, Torus>;
+
+// Torus-specific methods.
+
+impl Torus {
+    /// The default distance from the centre of the torus to the centre of the tube.
+    pub const DEFAULT_RADIUS: f32 = 50.0;
+    /// The default radius of the tube.
+    pub const DEFAULT_TUBE_RADIUS: f32 = 20.0;
+    /// The default number of segments around the centre of the torus.
+    pub const DEFAULT_RESOLUTION: usize = 32;
+
+    /// The distance from the centre of the torus to the centre of the tube.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    /// The radius of the tube.
+    pub fn tube_radius(mut self, radius: f32) -> Self {
+        self.tube_radius = Some(radius);
+        self
+    }
+
+    /// The number of segments around the centre of the torus.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.resolution = Some(resolution);
+        self
+    }
+
+    /// The number of segments around the tube.
+    ///
+    /// By default, this is half of the `resolution`.
+    pub fn tube_resolution(mut self, resolution: usize) -> Self {
+        self.tube_resolution = Some(resolution);
+        self
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingTorus<'a> {
+    /// The distance from the centre of the torus to the centre of the tube.
+    pub fn radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.radius(radius))
+    }
+
+    /// The radius of the tube.
+    pub fn tube_radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.tube_radius(radius))
+    }
+
+    /// The number of segments around the centre of the torus.
+    pub fn resolution(self, resolution: usize) -> Self {
+        self.map_ty(|ty| ty.resolution(resolution))
+    }
+
+    /// The number of segments around the tube.
+    pub fn tube_resolution(self, resolution: usize) -> Self {
+        self.map_ty(|ty| ty.tube_resolution(resolution))
+    }
+}
+
+/// Produce the geometry for a torus centered on the origin, encircling the *y* axis.
+///
+/// Each quad of the resulting grid is a single face. Each vertex is given the normal of the
+/// tube's surface so that the torus may be shaded smoothly.
+pub fn geometry(
+    radius: f32,
+    tube_radius: f32,
+    segments: usize,
+    tube_segments: usize,
+) -> solid::Geometry {
+    let segments = segments.max(3) as u32;
+    let tube_segments = tube_segments.max(3) as u32;
+    let mut geometry = solid::Geometry::default();
+    for s in 0..segments {
+        let u = 2.0 * PI * s as f32 / segments as f32;
+        let (sin_u, cos_u) = u.sin_cos();
+        for t in 0..tube_segments {
+            let v = 2.0 * PI * t as f32 / tube_segments as f32;
+            let (sin_v, cos_v) = v.sin_cos();
+            let r = radius + tube_radius * cos_v;
+            let point = vec3(r * cos_u, tube_radius * sin_v, r * sin_u);
+            let normal = vec3(cos_v * cos_u, sin_v, cos_v * sin_u);
+            geometry.push_point_with_normal(point, normal);
+        }
+    }
+    let ix = |s: u32, t: u32| (s % segments) * tube_segments + t % tube_segments;
+    for s in 0..segments {
+        for t in 0..tube_segments {
+            geometry.push_face(&[ix(s, t), ix(s, t + 1), ix(s + 1, t + 1), ix(s + 1, t)]);
+        }
+    }
+    geometry
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Torus {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Torus {
+            radius,
+            tube_radius,
+            resolution,
+            tube_resolution,
+            solid,
+        } = self;
+        let radius = radius.unwrap_or(Self::DEFAULT_RADIUS);
+        let tube_radius = tube_radius.unwrap_or(Self::DEFAULT_TUBE_RADIUS);
+        let segments = resolution.unwrap_or(Self::DEFAULT_RESOLUTION);
+        let tube_segments = tube_resolution.unwrap_or(segments / 2);
+        let geometry = geometry(radius, tube_radius, segments, tube_segments);
+        solid::render_themed(solid, &geometry, ctxt, &draw::theme::Primitive::Torus, mesh)
+    }
+}
+
+impl SetOrientation for Torus {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.solid)
+    }
+}
+
+impl SetPosition for Torus {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.solid)
+    }
+}
+
+impl SetColor<ColorScalar> for Torus {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.solid)
+    }
+}
+
+impl SetSolid for Torus {
+    fn solid_options_mut(&mut self) -> &mut SolidOptions {
+        SetSolid::solid_options_mut(&mut self.solid)
+    }
+}
+
+// Primitive conversions.
+
+impl From<Torus> for Primitive {
+    fn from(prim: Torus) -> Self {
+        Primitive::Torus(prim)
+    }
+}
+
+impl Into<Option<Torus>> for Primitive {
+    fn into(self) -> Option<Torus> {
+        match self {
+            Primitive::Torus(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
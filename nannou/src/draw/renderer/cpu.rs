@@ -0,0 +1,884 @@
+//! A renderer that rasterises **Draw** commands on the CPU.
+//!
+//! The **CpuRenderer** consumes the same draw commands and tessellated meshes as the GPU
+//! **Renderer**, but writes the result to an `image::RgbaImage` rather than a texture. This allows
+//! for rendering sketches on machines without a GPU, e.g. headless servers and CI.
+//!
+//! The renderer mirrors the GPU renderer's shaders, including the vertex modes, text modes,
+//! lighting, blending, scissoring, depth testing and texture sampling. Differences include:
+//!
+//! - No multisampling is performed, so only text is anti-aliased.
+//! - Textures are not readable from the CPU, so their pixels must be provided via
+//!   **CpuRenderer::set_texture**. Textures without pixels are sampled as transparent black.
+//! - Colors are blended in linear space with `f32` precision and written as sRGB.
+
+use super::{create_uniforms, GlyphCache, SdfEdge, TextMode, Uniforms, VertexMode};
+use crate::draw::{self, Material};
+use crate::geom::Rect;
+use crate::glam::{Vec2, Vec3, Vec4};
+use crate::image;
+use crate::wgpu;
+use lyon::tessellation::{FillTessellator, StrokeTessellator};
+use std::collections::HashMap;
+
+/// Renders **Draw** commands to images on the CPU.
+///
+/// See the [module docs](./index.html) for details.
+#[derive(Debug)]
+pub struct CpuRenderer {
+    glyph_cache: GlyphCache,
+    textures: HashMap<wgpu::TextureViewId, Texture>,
+    mesh: draw::Mesh,
+    vertex_mode_buffer: Vec<VertexMode>,
+    sdf_edge_buffer: Vec<SdfEdge>,
+    // The linear color and depth of each pixel of the current target.
+    color_buffer: Vec<Vec4>,
+    depth_buffer: Vec<f32>,
+}
+
+// A texture's pixels in linear space.
+#[derive(Debug)]
+struct Texture {
+    size: [u32; 2],
+    texels: Vec<Vec4>,
+}
+
+// The attributes of a vertex that are interpolated across a primitive.
+#[derive(Copy, Clone, Debug)]
+struct Varying {
+    clip: Vec4,
+    color: Vec4,
+    tex_coords: Vec2,
+    sdf_edge: Vec2,
+    world_pos: Vec3,
+    normal: Vec3,
+}
+
+// A vertex projected onto the target, where `pos` is in pixels and depth.
+#[derive(Copy, Clone, Debug)]
+struct ScreenVertex {
+    pos: Vec3,
+    inv_w: f32,
+    varying: Varying,
+}
+
+// The region of the target in pixels to which drawing is limited.
+#[derive(Copy, Clone, Debug)]
+struct PixelRect {
+    left: i64,
+    top: i64,
+    right: i64,
+    bottom: i64,
+}
+
+// The state shared by all fragments of a single primitive.
+struct Shader<'a> {
+    mode: VertexMode,
+    texture: Option<&'a Texture>,
+    sampler: &'a wgpu::SamplerDescriptor<'static>,
+    glyph_cache: &'a GlyphCache,
+    material: Material,
+    uniforms: &'a Uniforms,
+    face_normal: Vec3,
+}
+
+// The target to which fragments are written.
+struct Target<'a> {
+    size: [u32; 2],
+    color: &'a mut [Vec4],
+    depth: &'a mut [f32],
+    blend: wgpu::BlendState,
+    rect: PixelRect,
+}
+
+impl CpuRenderer {
+    /// Create a new **CpuRenderer**.
+    ///
+    /// See **Builder::build_cpu** for specifying the glyph cache and text mode.
+    pub fn new() -> Self {
+        super::Builder::new().build_cpu()
+    }
+
+    pub(crate) fn from_glyph_cache(glyph_cache: GlyphCache) -> Self {
+        CpuRenderer {
+            glyph_cache,
+            textures: Default::default(),
+            mesh: Default::default(),
+            vertex_mode_buffer: vec![],
+            sdf_edge_buffer: vec![],
+            color_buffer: vec![],
+            depth_buffer: vec![],
+        }
+    }
+
+    /// The mode with which the renderer rasterises and displays text.
+    pub fn text_mode(&self) -> TextMode {
+        self.glyph_cache.mode
+    }
+
+    /// Provide the pixels of the given texture, so that they may be sampled when drawing it.
+    ///
+    /// The image is assumed to be in sRGB space, as is the case for textures loaded via
+    /// `wgpu::Texture::from_image`.
+    pub fn set_texture<T>(&mut self, texture: &T, image: &image::DynamicImage)
+    where
+        T: wgpu::ToTextureView + ?Sized,
+    {
+        let id = texture.to_texture_view().id();
+        let image = image.to_rgba8();
+        let size = [image.width(), image.height()];
+        let texels = image.pixels().map(|p| srgba8_to_linear(p.0)).collect();
+        self.textures.insert(id, Texture { size, texels });
+    }
+
+    /// Remove the pixels of the given texture, if any.
+    pub fn remove_texture<T>(&mut self, texture: &T)
+    where
+        T: wgpu::ToTextureView + ?Sized,
+    {
+        self.textures.remove(&texture.to_texture_view().id());
+    }
+
+    /// Render the given **Draw** to a new image with the given size in pixels.
+    ///
+    /// The `scale_factor` describes the number of pixels per point. Pixels that are not drawn to
+    /// remain transparent.
+    ///
+    /// Note that the given **Draw** instance will be *drained* of its commands.
+    pub fn render(
+        &mut self,
+        draw: &draw::Draw,
+        size: [u32; 2],
+        scale_factor: f32,
+    ) -> image::RgbaImage {
+        let [w, h] = size;
+        let mut image = image::RgbaImage::new(w, h);
+        self.render_to_image(draw, scale_factor, &mut image);
+        image
+    }
+
+    /// Render the given **Draw** onto the given image.
+    ///
+    /// Unless the **Draw** has a background color, the drawing is blended over the image's
+    /// existing pixels.
+    ///
+    /// Note that the given **Draw** instance will be *drained* of its commands.
+    pub fn render_to_image(
+        &mut self,
+        draw: &draw::Draw,
+        scale_factor: f32,
+        image: &mut image::RgbaImage,
+    ) {
+        let size = [image.width(), image.height()];
+        let [w_px, h_px] = size;
+        let px_to_pt = |s: u32| s as f32 / scale_factor;
+        let full_rect = Rect::from_w_h(px_to_pt(w_px), px_to_pt(h_px));
+        let full_pixels = PixelRect {
+            left: 0,
+            top: 0,
+            right: w_px as i64,
+            bottom: h_px as i64,
+        };
+
+        // Load the target, or clear it to the background color.
+        let bg_color = draw.state.borrow().background_color;
+        self.color_buffer.clear();
+        match bg_color {
+            None => {
+                let pixels = image.pixels().map(|p| srgba8_to_linear(p.0));
+                self.color_buffer.extend(pixels);
+            }
+            Some(color) => {
+                let color = Vec4::from(<[f32; 4]>::from(color.into_components()));
+                let len = w_px as usize * h_px as usize;
+                self.color_buffer.resize(len, color);
+            }
+        }
+        self.depth_buffer.clear();
+        self.depth_buffer.resize(self.color_buffer.len(), 1.0);
+
+        let mut fill_tessellator = FillTessellator::new();
+        let mut stroke_tessellator = StrokeTessellator::new();
+        let mut curr_ctxt = draw::Context::default();
+        // The uniforms of the most recently used camera and set of lights.
+        let mut curr_uniforms: Option<(draw::Camera, draw::Lights, Uniforms)> = None;
+
+        self.glyph_cache.begin_frame();
+
+        // Collect all draw commands to avoid borrow errors.
+        let draw_cmds: Vec<_> = draw.drain_commands().collect();
+        let draw_state = draw.state.borrow_mut();
+        let intermediary_state = draw_state.intermediary_state.borrow();
+        for cmd in draw_cmds {
+            let prim = match cmd {
+                draw::DrawCommand::Context(ctxt) => {
+                    curr_ctxt = ctxt;
+                    continue;
+                }
+                draw::DrawCommand::Primitive(prim) => prim,
+            };
+
+            // Determine the region of the target to which the primitive is limited.
+            let rect = match curr_ctxt.scissor {
+                draw::Scissor::Full => full_pixels,
+                draw::Scissor::Rect(rect) => match full_rect.overlap(rect) {
+                    None => continue,
+                    Some(rect) => {
+                        let x = |x: f32| ((x - full_rect.left()) * scale_factor).round() as i64;
+                        let y = |y: f32| ((full_rect.top() - y) * scale_factor).round() as i64;
+                        PixelRect {
+                            left: x(rect.left()),
+                            top: y(rect.top()),
+                            right: x(rect.right()),
+                            bottom: y(rect.bottom()),
+                        }
+                    }
+                },
+                draw::Scissor::NoOverlap => continue,
+            };
+
+            self.mesh.clear();
+            self.vertex_mode_buffer.clear();
+            self.sdf_edge_buffer.clear();
+            let ctxt = super::RenderContext {
+                intermediary_mesh: &intermediary_state.intermediary_mesh,
+                path_event_buffer: &intermediary_state.path_event_buffer,
+                path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+                path_points_textured_buffer: &intermediary_state.path_points_textured_buffer,
+                text_buffer: &intermediary_state.text_buffer,
+                theme: &draw_state.theme,
+                transform: &curr_ctxt.transform,
+                fill_tessellator: &mut fill_tessellator,
+                stroke_tessellator: &mut stroke_tessellator,
+                vector_paths: None,
+                glyph_cache: &mut self.glyph_cache,
+                sdf_edges: &mut self.sdf_edge_buffer,
+                vertex_modes: &mut self.vertex_mode_buffer,
+                output_attachment_size: Vec2::new(px_to_pt(w_px), px_to_pt(h_px)),
+                output_attachment_scale_factor: scale_factor,
+            };
+            let render = super::RenderPrimitive::render_primitive(prim, ctxt, &mut self.mesh);
+            if self.mesh.indices().is_empty() {
+                continue;
+            }
+
+            // Assign the primitive's vertex mode and default SDF edge to the remaining vertices.
+            let len = self.mesh.points().len();
+            self.vertex_mode_buffer.resize(len, render.vertex_mode);
+            self.sdf_edge_buffer.resize(len, SdfEdge::default());
+
+            // Retrieve the uniforms for the context's camera and lights.
+            let uniforms_changed = match curr_uniforms {
+                Some((ref camera, ref lights, _)) => {
+                    *camera != curr_ctxt.camera || *lights != curr_ctxt.lights
+                }
+                None => true,
+            };
+            if uniforms_changed {
+                let uniforms =
+                    create_uniforms(&curr_ctxt.camera, &curr_ctxt.lights, size, scale_factor);
+                curr_uniforms = Some((curr_ctxt.camera, curr_ctxt.lights.clone(), uniforms));
+            }
+            let uniforms = &curr_uniforms.as_ref().expect("no uniforms").2;
+
+            let textures = &self.textures;
+            let glyph_cache = &self.glyph_cache;
+            let texture = render
+                .texture_view
+                .as_ref()
+                .and_then(|view| textures.get(&view.id()));
+            let mut target = Target {
+                size,
+                color: &mut self.color_buffer,
+                depth: &mut self.depth_buffer,
+                blend: curr_ctxt.blend,
+                rect,
+            };
+            let vertices = VertexSource {
+                mesh: &self.mesh,
+                modes: &self.vertex_mode_buffer,
+                sdf_edges: &self.sdf_edge_buffer,
+                uniforms,
+            };
+            let shader = |mode, face_normal| Shader {
+                mode,
+                texture,
+                sampler: &curr_ctxt.sampler,
+                glyph_cache,
+                material: curr_ctxt.material,
+                uniforms,
+                face_normal,
+            };
+            draw_indices(&mut target, &vertices, curr_ctxt.topology, &shader);
+        }
+
+        // Write the result to the image.
+        for (pixel, color) in image.pixels_mut().zip(&self.color_buffer) {
+            pixel.0 = linear_to_srgba8(*color);
+        }
+    }
+}
+
+impl Default for CpuRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Varying {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Varying {
+            clip: self.clip.lerp(other.clip, t),
+            color: self.color.lerp(other.color, t),
+            tex_coords: self.tex_coords.lerp(other.tex_coords, t),
+            sdf_edge: self.sdf_edge.lerp(other.sdf_edge, t),
+            world_pos: self.world_pos.lerp(other.world_pos, t),
+            normal: self.normal.lerp(other.normal, t),
+        }
+    }
+
+    // The sum of the given varyings multiplied by their weights.
+    fn weighted(vs: [&Self; 3], w: [f32; 3]) -> Self {
+        Varying {
+            clip: vs[0].clip * w[0] + vs[1].clip * w[1] + vs[2].clip * w[2],
+            color: vs[0].color * w[0] + vs[1].color * w[1] + vs[2].color * w[2],
+            tex_coords: vs[0].tex_coords * w[0] + vs[1].tex_coords * w[1] + vs[2].tex_coords * w[2],
+            sdf_edge: vs[0].sdf_edge * w[0] + vs[1].sdf_edge * w[1] + vs[2].sdf_edge * w[2],
+            world_pos: vs[0].world_pos * w[0] + vs[1].world_pos * w[1] + vs[2].world_pos * w[2],
+            normal: vs[0].normal * w[0] + vs[1].normal * w[1] + vs[2].normal * w[2],
+        }
+    }
+}
+
+// Produces the varyings of the mesh's vertices.
+struct VertexSource<'a> {
+    mesh: &'a draw::Mesh,
+    modes: &'a [VertexMode],
+    sdf_edges: &'a [SdfEdge],
+    uniforms: &'a Uniforms,
+}
+
+impl<'a> VertexSource<'a> {
+    fn varying(&self, i: usize) -> Varying {
+        let world_pos = self.mesh.points()[i];
+        let color = self.mesh.colors()[i];
+        let edge = self.sdf_edges[i];
+        Varying {
+            clip: self.uniforms.proj * world_pos.extend(1.0),
+            color: Vec4::from(<[f32; 4]>::from(color.into_components())),
+            tex_coords: self.mesh.tex_coords()[i],
+            sdf_edge: Vec2::new(edge.threshold, edge.softness),
+            world_pos,
+            normal: self.mesh.normals().get(i).copied().unwrap_or(Vec3::ZERO),
+        }
+    }
+}
+
+// Draw the mesh's indices as the given topology.
+fn draw_indices<'a, F>(
+    target: &mut Target,
+    vertices: &VertexSource,
+    topology: wgpu::PrimitiveTopology,
+    shader: &F,
+) where
+    F: Fn(VertexMode, Vec3) -> Shader<'a>,
+{
+    let indices = vertices.mesh.indices();
+    let ix = |i: &u32| *i as usize;
+    match topology {
+        wgpu::PrimitiveTopology::TriangleList => {
+            for tri in indices.chunks_exact(3) {
+                draw_triangle(
+                    target,
+                    vertices,
+                    [ix(&tri[0]), ix(&tri[1]), ix(&tri[2])],
+                    shader,
+                );
+            }
+        }
+        wgpu::PrimitiveTopology::TriangleStrip => {
+            for tri in indices.windows(3) {
+                draw_triangle(
+                    target,
+                    vertices,
+                    [ix(&tri[0]), ix(&tri[1]), ix(&tri[2])],
+                    shader,
+                );
+            }
+        }
+        wgpu::PrimitiveTopology::LineList => {
+            for line in indices.chunks_exact(2) {
+                draw_line(target, vertices, [ix(&line[0]), ix(&line[1])], shader);
+            }
+        }
+        wgpu::PrimitiveTopology::LineStrip => {
+            for line in indices.windows(2) {
+                draw_line(target, vertices, [ix(&line[0]), ix(&line[1])], shader);
+            }
+        }
+        wgpu::PrimitiveTopology::PointList => {
+            for i in indices {
+                let v = vertices.varying(ix(i));
+                if let Some(sv) = clip_to_screen(&v, target.size) {
+                    let shader = shader(vertices.modes[ix(i)], Vec3::ZERO);
+                    let (x, y) = (sv.pos.x.floor() as i64, sv.pos.y.floor() as i64);
+                    target.fragment(x, y, sv.pos.z, &shader, &v, None);
+                }
+            }
+        }
+    }
+}
+
+fn draw_triangle<'a, F>(target: &mut Target, vertices: &VertexSource, ix: [usize; 3], shader: &F)
+where
+    F: Fn(VertexMode, Vec3) -> Shader<'a>,
+{
+    let vs = ix.map(|i| vertices.varying(i));
+    let face_normal = (vs[1].world_pos - vs[0].world_pos).cross(vs[2].world_pos - vs[0].world_pos);
+    // Like the GPU renderer's integer attributes, the mode is taken from the first vertex.
+    let shader = shader(vertices.modes[ix[0]], face_normal);
+
+    // Clip the triangle against the near and far planes, then fill it as a fan.
+    let polygon = clip_polygon(vs.to_vec());
+    let screen: Vec<_> = polygon
+        .iter()
+        .filter_map(|v| clip_to_screen(v, target.size))
+        .collect();
+    if screen.len() != polygon.len() {
+        return;
+    }
+    for i in 1..screen.len().saturating_sub(1) {
+        fill_triangle(target, [&screen[0], &screen[i], &screen[i + 1]], &shader);
+    }
+}
+
+fn draw_line<'a, F>(target: &mut Target, vertices: &VertexSource, ix: [usize; 2], shader: &F)
+where
+    F: Fn(VertexMode, Vec3) -> Shader<'a>,
+{
+    let shader = shader(vertices.modes[ix[0]], Vec3::ZERO);
+    let (mut a, mut b) = (vertices.varying(ix[0]), vertices.varying(ix[1]));
+    // Clip the line against the near and far planes.
+    for dist in &[near_dist as fn(&Varying) -> f32, far_dist] {
+        let (da, db) = (dist(&a), dist(&b));
+        match (da < 0.0, db < 0.0) {
+            (true, true) => return,
+            (true, false) => a = a.lerp(&b, da / (da - db)),
+            (false, true) => b = a.lerp(&b, da / (da - db)),
+            (false, false) => (),
+        }
+    }
+    let (sa, sb) = match (
+        clip_to_screen(&a, target.size),
+        clip_to_screen(&b, target.size),
+    ) {
+        (Some(sa), Some(sb)) => (sa, sb),
+        _ => return,
+    };
+    let delta = sb.pos - sa.pos;
+    let steps = delta.x.abs().max(delta.y.abs()).ceil().max(1.0) as usize;
+    for step in 0..steps {
+        let t = (step as f32 + 0.5) / steps as f32;
+        let pos = sa.pos + delta * t;
+        let v = a.lerp(&b, t);
+        let (x, y) = (pos.x.floor() as i64, pos.y.floor() as i64);
+        target.fragment(x, y, pos.z, &shader, &v, None);
+    }
+}
+
+// The signed distance of the vertex from the near and far planes, where wgpu's clip space depth
+// ranges from `0` to `w`.
+fn near_dist(v: &Varying) -> f32 {
+    v.clip.z
+}
+
+fn far_dist(v: &Varying) -> f32 {
+    v.clip.w - v.clip.z
+}
+
+// Clip the given convex polygon against the near and far planes.
+fn clip_polygon(mut polygon: Vec<Varying>) -> Vec<Varying> {
+    for dist in &[near_dist as fn(&Varying) -> f32, far_dist] {
+        if polygon.iter().all(|v| dist(v) >= 0.0) {
+            continue;
+        }
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (i, a) in polygon.iter().enumerate() {
+            let b = &polygon[(i + 1) % polygon.len()];
+            let (da, db) = (dist(a), dist(b));
+            if da >= 0.0 {
+                clipped.push(*a);
+            }
+            if (da >= 0.0) != (db >= 0.0) {
+                clipped.push(a.lerp(b, da / (da - db)));
+            }
+        }
+        polygon = clipped;
+    }
+    polygon
+}
+
+// Project the vertex onto the target, with `y` pointing down.
+fn clip_to_screen(v: &Varying, [w, h]: [u32; 2]) -> Option<ScreenVertex> {
+    if v.clip.w <= f32::EPSILON {
+        return None;
+    }
+    let inv_w = 1.0 / v.clip.w;
+    let ndc = v.clip.truncate() * inv_w;
+    let pos = Vec3::new(
+        (ndc.x * 0.5 + 0.5) * w as f32,
+        (0.5 - ndc.y * 0.5) * h as f32,
+        ndc.z,
+    );
+    Some(ScreenVertex {
+        pos,
+        inv_w,
+        varying: *v,
+    })
+}
+
+// Twice the signed area of the triangle `abc`.
+fn edge(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn fill_triangle(target: &mut Target, vs: [&ScreenVertex; 3], shader: &Shader) {
+    let [a, b, c] = vs.map(|v| v.pos.truncate());
+    let area = edge(a, b, c);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    // Orient the triangle so that its edge functions are positive within.
+    let sign = area.signum();
+    let area = area.abs();
+
+    // Whether or not pixel centers lying exactly on each edge belong to the triangle, so that
+    // adjacent triangles sharing an edge do not both draw to its pixels.
+    let owns = |p: Vec2, q: Vec2| {
+        let d = (q - p) * sign;
+        d.y < 0.0 || (d.y == 0.0 && d.x > 0.0)
+    };
+    let owned = [owns(b, c), owns(c, a), owns(a, b)];
+
+    let rect = target.rect;
+    let min = a.min(b).min(c);
+    let max = a.max(b).max(c);
+    let x0 = (min.x.floor() as i64).max(rect.left);
+    let x1 = (max.x.ceil() as i64).min(rect.right);
+    let y0 = (min.y.floor() as i64).max(rect.top);
+    let y1 = (max.y.ceil() as i64).min(rect.bottom);
+
+    // The perspective correct weights of each vertex at the given point.
+    let weights = |p: Vec2| {
+        let b = [
+            edge(b, c, p) * sign / area,
+            edge(c, a, p) * sign / area,
+            edge(a, b, p) * sign / area,
+        ];
+        let w = [b[0] * vs[0].inv_w, b[1] * vs[1].inv_w, b[2] * vs[2].inv_w];
+        let sum = w[0] + w[1] + w[2];
+        (b, [w[0] / sum, w[1] / sum, w[2] / sum])
+    };
+    let varyings = [&vs[0].varying, &vs[1].varying, &vs[2].varying];
+    let needs_derivatives = shader.mode != VertexMode::Color;
+
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let e = [
+                edge(b, c, p) * sign,
+                edge(c, a, p) * sign,
+                edge(a, b, p) * sign,
+            ];
+            let inside = e
+                .iter()
+                .zip(&owned)
+                .all(|(&e, &owned)| e > 0.0 || (e == 0.0 && owned));
+            if !inside {
+                continue;
+            }
+            let (bary, w) = weights(p);
+            // Interpolate relative to the first vertex, so that coplanar primitives have equal
+            // depth.
+            let z = vs.map(|v| v.pos.z);
+            let depth = z[0] + bary[1] * (z[1] - z[0]) + bary[2] * (z[2] - z[0]);
+            let v = Varying::weighted(varyings, w);
+            let derivatives = match needs_derivatives {
+                false => None,
+                true => {
+                    let dx = Varying::weighted(varyings, weights(p + Vec2::X).1);
+                    let dy = Varying::weighted(varyings, weights(p + Vec2::Y).1);
+                    Some((dx, dy))
+                }
+            };
+            target.fragment(x, y, depth, shader, &v, derivatives.as_ref());
+        }
+    }
+}
+
+impl<'a> Target<'a> {
+    // Shade, depth test and blend a single fragment.
+    //
+    // The `derivatives` are the varyings at the neighbouring pixels along *x* and *y*.
+    fn fragment(
+        &mut self,
+        x: i64,
+        y: i64,
+        depth: f32,
+        shader: &Shader,
+        v: &Varying,
+        derivatives: Option<&(Varying, Varying)>,
+    ) {
+        let r = self.rect;
+        if x < r.left || x >= r.right || y < r.top || y >= r.bottom {
+            return;
+        }
+        let i = y as usize * self.size[0] as usize + x as usize;
+        if !(0.0..=1.0).contains(&depth) || depth > self.depth[i] {
+            return;
+        }
+        let src = shader.shade(v, derivatives);
+        self.depth[i] = depth;
+        self.color[i] = blend(&self.blend, src, self.color[i]);
+    }
+}
+
+impl<'a> Shader<'a> {
+    // The equivalent of the GPU renderer's fragment shader.
+    fn shade(&self, v: &Varying, derivatives: Option<&(Varying, Varying)>) -> Vec4 {
+        // The texture coordinates of the neighbouring pixels.
+        let (uv_dx, uv_dy) = match derivatives {
+            Some((dx, dy)) => (dx.tex_coords, dy.tex_coords),
+            None => (v.tex_coords, v.tex_coords),
+        };
+        let text = |uv: Vec2| {
+            let cache = self.glyph_cache;
+            let (w, h) = cache.cache.dimensions();
+            let sampler = &wgpu::SamplerBuilder::DEFAULT_DESCRIPTOR;
+            let fetch = |x: u32, y: u32| {
+                let value = cache.pixel_buffer[y as usize * w as usize + x as usize];
+                Vec4::new(value as f32 / 255.0, 0.0, 0.0, 1.0)
+            };
+            sample([w, h], uv, 1.0, sampler, fetch).x
+        };
+        let color = match self.mode {
+            VertexMode::Color => v.color,
+            VertexMode::Texture => match self.texture {
+                None => Vec4::ZERO,
+                Some(tex) => {
+                    let [w, h] = tex.size;
+                    let size = Vec2::new(w as f32, h as f32);
+                    let dx = (uv_dx - v.tex_coords) * size;
+                    let dy = (uv_dy - v.tex_coords) * size;
+                    let footprint = dx.length().max(dy.length());
+                    let fetch = |x: u32, y: u32| tex.texels[y as usize * w as usize + x as usize];
+                    sample(tex.size, v.tex_coords, footprint, self.sampler, fetch)
+                }
+            },
+            VertexMode::Text => v.color * Vec4::new(1.0, 1.0, 1.0, text(v.tex_coords)),
+            VertexMode::SdfText => {
+                let alpha = text(v.tex_coords);
+                // Sampling the neighbouring pixels may stray into other glyphs within the cache,
+                // so estimate the rate of change via the gradient of the field at this pixel.
+                let (w, h) = self.glyph_cache.cache.dimensions();
+                let texel = Vec2::new(0.5 / w as f32, 0.5 / h as f32);
+                let gradient = Vec2::new(
+                    text(v.tex_coords + texel * Vec2::X) - text(v.tex_coords - texel * Vec2::X),
+                    text(v.tex_coords + texel * Vec2::Y) - text(v.tex_coords - texel * Vec2::Y),
+                ) / (texel * 2.0);
+                let dx = uv_dx - v.tex_coords;
+                let dy = uv_dy - v.tex_coords;
+                let fwidth = gradient.dot(dx).abs() + gradient.dot(dy).abs();
+                let aa = (0.5 * fwidth).max(v.sdf_edge.y).max(0.0001);
+                let threshold = v.sdf_edge.x;
+                let coverage = smoothstep(threshold - aa, threshold + aa, alpha);
+                v.color * Vec4::new(1.0, 1.0, 1.0, coverage)
+            }
+        };
+        match self.material {
+            Material::Unlit => color,
+            material => shade_lit(color, v, self.face_normal, material, self.uniforms),
+        }
+    }
+}
+
+// Shade the given color with the Lambert or Blinn-Phong lighting model.
+fn shade_lit(
+    color: Vec4,
+    v: &Varying,
+    face_normal: Vec3,
+    material: Material,
+    uniforms: &Uniforms,
+) -> Vec4 {
+    let eye = uniforms.eye;
+    let view_dir = match eye.w != 0.0 {
+        true => (eye.truncate() - v.world_pos).normalize_or_zero(),
+        false => eye.truncate(),
+    };
+    let n = if v.normal.length_squared() > 0.000001 {
+        v.normal.normalize()
+    } else {
+        // The winding of the face is unknown, so shade whichever side faces the viewer.
+        let n = face_normal.normalize_or_zero();
+        match n.dot(view_dir) < 0.0 {
+            true => -n,
+            false => n,
+        }
+    };
+    let mut light = uniforms.ambient.truncate();
+    let mut highlight = Vec3::ZERO;
+    for l in uniforms.lights.iter().take(uniforms.light_count as usize) {
+        let mut light_dir = -l.position.truncate();
+        let mut attenuation = 1.0;
+        if l.position.w != 0.0 {
+            let to_light = l.position.truncate() - v.world_pos;
+            let dist_sq = to_light.dot(to_light);
+            light_dir = to_light / dist_sq.max(0.000001).sqrt();
+            let falloff = (1.0 - dist_sq * l.color.w).clamp(0.0, 1.0);
+            attenuation = falloff * falloff;
+        }
+        let diffuse = n.dot(light_dir).max(0.0);
+        light += l.color.truncate() * diffuse * attenuation;
+        if let Material::BlinnPhong {
+            specular,
+            shininess,
+        } = material
+        {
+            if diffuse > 0.0 {
+                let half_dir = (light_dir + view_dir).normalize_or_zero();
+                let s = n.dot(half_dir).max(0.0).powf(shininess);
+                highlight += l.color.truncate() * specular * s * attenuation;
+            }
+        }
+    }
+    (color.truncate() * light + highlight).extend(color.w)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Sample a texture of the given size via the given sampler.
+//
+// The `footprint` is the number of texels covered by a single pixel, used to select between the
+// magnification and minification filters.
+fn sample<F>(
+    [w, h]: [u32; 2],
+    uv: Vec2,
+    footprint: f32,
+    sampler: &wgpu::SamplerDescriptor,
+    fetch: F,
+) -> Vec4
+where
+    F: Fn(u32, u32) -> Vec4,
+{
+    if w == 0 || h == 0 {
+        return Vec4::ZERO;
+    }
+    let border = match sampler.border_color {
+        Some(wgpu::SamplerBorderColor::OpaqueBlack) => Vec4::W,
+        Some(wgpu::SamplerBorderColor::OpaqueWhite) => Vec4::ONE,
+        _ => Vec4::ZERO,
+    };
+    let texel = |x: i64, y: i64| match (
+        address(x, w, sampler.address_mode_u),
+        address(y, h, sampler.address_mode_v),
+    ) {
+        (Some(x), Some(y)) => fetch(x, y),
+        _ => border,
+    };
+    let filter = match footprint > 1.0 {
+        true => sampler.min_filter,
+        false => sampler.mag_filter,
+    };
+    let p = uv * Vec2::new(w as f32, h as f32);
+    match filter {
+        wgpu::FilterMode::Nearest => texel(p.x.floor() as i64, p.y.floor() as i64),
+        wgpu::FilterMode::Linear => {
+            let p = p - Vec2::splat(0.5);
+            let (x, y) = (p.x.floor(), p.y.floor());
+            let (fx, fy) = (p.x - x, p.y - y);
+            let (x, y) = (x as i64, y as i64);
+            let top = texel(x, y).lerp(texel(x + 1, y), fx);
+            let bottom = texel(x, y + 1).lerp(texel(x + 1, y + 1), fx);
+            top.lerp(bottom, fy)
+        }
+    }
+}
+
+// Map the given texel coordinate into the texture via the given address mode.
+fn address(i: i64, size: u32, mode: wgpu::AddressMode) -> Option<u32> {
+    let n = size as i64;
+    let i = match mode {
+        wgpu::AddressMode::ClampToEdge => i.clamp(0, n - 1),
+        wgpu::AddressMode::Repeat => i.rem_euclid(n),
+        wgpu::AddressMode::MirrorRepeat => {
+            let i = i.rem_euclid(2 * n);
+            match i < n {
+                true => i,
+                false => 2 * n - 1 - i,
+            }
+        }
+        wgpu::AddressMode::ClampToBorder => match i >= 0 && i < n {
+            true => i,
+            false => return None,
+        },
+    };
+    Some(i as u32)
+}
+
+// Blend the source color onto the destination color.
+fn blend(state: &wgpu::BlendState, src: Vec4, dst: Vec4) -> Vec4 {
+    let color = blend_component(&state.color, src, dst);
+    let alpha = blend_component(&state.alpha, src, dst);
+    color.truncate().extend(alpha.w)
+}
+
+fn blend_component(component: &wgpu::BlendComponent, src: Vec4, dst: Vec4) -> Vec4 {
+    let s = src * blend_factor(component.src_factor, src, dst);
+    let d = dst * blend_factor(component.dst_factor, src, dst);
+    match component.operation {
+        wgpu::BlendOperation::Add => s + d,
+        wgpu::BlendOperation::Subtract => s - d,
+        wgpu::BlendOperation::ReverseSubtract => d - s,
+        wgpu::BlendOperation::Min => src.min(dst),
+        wgpu::BlendOperation::Max => src.max(dst),
+    }
+}
+
+fn blend_factor(factor: wgpu::BlendFactor, src: Vec4, dst: Vec4) -> Vec4 {
+    match factor {
+        wgpu::BlendFactor::Zero => Vec4::ZERO,
+        wgpu::BlendFactor::One => Vec4::ONE,
+        wgpu::BlendFactor::Src => src,
+        wgpu::BlendFactor::OneMinusSrc => Vec4::ONE - src,
+        wgpu::BlendFactor::SrcAlpha => Vec4::splat(src.w),
+        wgpu::BlendFactor::OneMinusSrcAlpha => Vec4::splat(1.0 - src.w),
+        wgpu::BlendFactor::Dst => dst,
+        wgpu::BlendFactor::OneMinusDst => Vec4::ONE - dst,
+        wgpu::BlendFactor::DstAlpha => Vec4::splat(dst.w),
+        wgpu::BlendFactor::OneMinusDstAlpha => Vec4::splat(1.0 - dst.w),
+        wgpu::BlendFactor::SrcAlphaSaturated => {
+            let f = src.w.min(1.0 - dst.w);
+            Vec4::new(f, f, f, 1.0)
+        }
+        // The blend constant is never set, so remains transparent black.
+        wgpu::BlendFactor::Constant => Vec4::ZERO,
+        wgpu::BlendFactor::OneMinusConstant => Vec4::ONE,
+    }
+}
+
+fn srgba8_to_linear([r, g, b, a]: [u8; 4]) -> Vec4 {
+    let srgb = crate::color::Srgba::new(r, g, b, a).into_format::<f32, f32>();
+    let (r, g, b, a) = srgb.into_linear().into_components();
+    Vec4::new(r, g, b, a)
+}
+
+fn linear_to_srgba8(color: Vec4) -> [u8; 4] {
+    let c = color.clamp(Vec4::ZERO, Vec4::ONE);
+    let linear = crate::color::LinSrgba::new(c.x, c.y, c.z, c.w);
+    let srgb = crate::color::Srgba::from_linear(linear).into_format::<u8, u8>();
+    let (r, g, b, a) = srgb.into_components();
+    [r, g, b, a]
+}
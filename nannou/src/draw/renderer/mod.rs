@@ -1,8 +1,9 @@
 use crate::draw;
-use crate::draw::mesh::vertex::{Color, TexCoords};
+use crate::draw::light::{self, Light, Material};
+use crate::draw::mesh::vertex::{Color, Normal, TexCoords};
 use crate::frame::Frame;
 use crate::geom::{self, Point2, Rect};
-use crate::glam::{Mat4, Vec2, Vec3};
+use crate::glam::{Mat4, Vec2, Vec3, Vec4};
 use crate::math::map_range;
 use crate::text;
 use crate::wgpu;
@@ -14,6 +15,10 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
+pub use self::cpu::CpuRenderer;
+
+pub mod cpu;
+
 /// Draw API primitives that may be rendered via the **Renderer** type.
 pub trait RenderPrimitive {
     /// Render self into the given mesh.
@@ -42,8 +47,20 @@ pub struct RenderContext<'a> {
     pub text_buffer: &'a str,
     pub theme: &'a draw::Theme,
     pub glyph_cache: &'a mut GlyphCache,
+    /// The SDF edge of each vertex pushed by primitives rendered with `VertexMode::SdfText`.
+    ///
+    /// Vertices without an entry are assigned the default edge.
+    pub sdf_edges: &'a mut Vec<SdfEdge>,
+    /// The vertex mode of each vertex pushed by the primitive.
+    ///
+    /// Vertices without an entry are assigned the `vertex_mode` of the returned `PrimitiveRender`.
+    /// This allows a primitive to mix vertex modes, e.g. text with underlines.
+    pub vertex_modes: &'a mut Vec<VertexMode>,
     pub fill_tessellator: &'a mut FillTessellator,
     pub stroke_tessellator: &'a mut StrokeTessellator,
+    /// If `Some`, paths and text are recorded here as vector paths rather than being tessellated
+    /// into the mesh. Used by **draw::export**.
+    pub vector_paths: Option<&'a mut Vec<draw::export::VectorPath>>,
     pub output_attachment_size: Vec2, // logical coords
     pub output_attachment_scale_factor: f32,
 }
@@ -55,6 +72,46 @@ pub struct GlyphCache {
     pub pixel_buffer: Vec<u8>,
     /// Will be set to `true` after the cache has been updated if the texture requires re-uploading.
     pub requires_upload: bool,
+    /// The way in which text is rasterised and stored within the cache.
+    pub mode: TextMode,
+    /// Tracks glyph signed distance fields and their location within the cache.
+    ///
+    /// Only used when `mode` is `TextMode::Sdf`, in which case it shares the `pixel_buffer`.
+    pub sdf_atlas: text::sdf::Atlas,
+    /// Set to `true` if a glyph did not fit within the `sdf_atlas` during the current frame.
+    ///
+    /// The atlas is cleared at the beginning of the next frame.
+    pub sdf_atlas_full: bool,
+}
+
+/// The way in which the **Renderer** rasterises and displays text.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TextMode {
+    /// Glyphs are rasterised at the exact scale and subpixel position at which they are
+    /// displayed.
+    ///
+    /// This is the highest quality mode for static, screen-aligned text, but text that is large,
+    /// zoomed or continuously scaled may quickly fill the glyph cache.
+    Bitmap,
+    /// Each glyph's signed distance field is generated once and stored within the glyph cache.
+    ///
+    /// Text remains crisp under arbitrary scales and transforms without regenerating glyphs.
+    /// This mode also enables the outline, glow and drop shadow text styles.
+    Sdf,
+}
+
+/// The edge of a glyph's signed distance field for vertices using `VertexMode::SdfText`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SdfEdge {
+    /// The normalised distance field value at which the edge lies.
+    ///
+    /// `0.5` is the glyph's outline, while lesser values lie further outside the glyph.
+    pub threshold: f32,
+    /// The normalised distance over which the edge fades out on either side of the threshold.
+    ///
+    /// If less than a pixel, the edge is anti-aliased over a single pixel instead.
+    pub softness: f32,
 }
 
 /// A top-level indicator of whether or not
@@ -69,6 +126,21 @@ pub enum VertexMode {
     ///
     /// Uses the color values, but multiplies the alpha by the glyph cache texture's red value.
     Text = 2,
+    /// A special mode used by the text primitive when rendering with `TextMode::Sdf`.
+    ///
+    /// Uses the color values, but multiplies the alpha by the coverage of the vertex's `SdfEdge`
+    /// within the glyph cache texture's red value.
+    SdfText = 3,
+}
+
+/// The lighting model and parameters of a single vertex, derived from its **draw::Material**.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct VertexMaterial {
+    /// `0` for unlit, `1` for Lambert and `2` for Blinn-Phong.
+    model: u32,
+    specular: f32,
+    shininess: f32,
 }
 
 /// A helper type aimed at simplifying the rendering of nannou primitives via wgpu.
@@ -85,7 +157,9 @@ pub struct Renderer {
     _default_texture: wgpu::Texture,
     default_texture_view: wgpu::TextureView,
     uniform_bind_group_layout: wgpu::BindGroupLayout,
-    uniform_bind_group: wgpu::BindGroup,
+    // One uniform buffer and bind group per unique camera and set of lights used within a single
+    // frame.
+    camera_uniforms: Vec<CameraUniforms>,
     text_bind_group_layout: wgpu::BindGroupLayout,
     text_bind_group: wgpu::BindGroup,
     texture_samplers: HashMap<SamplerId, wgpu::Sampler>,
@@ -93,11 +167,13 @@ pub struct Renderer {
     texture_bind_groups: HashMap<BindGroupId, wgpu::BindGroup>,
     output_color_format: wgpu::TextureFormat,
     sample_count: u32,
-    scale_factor: f32,
     render_commands: Vec<RenderCommand>,
     mesh: draw::Mesh,
     vertex_mode_buffer: Vec<VertexMode>,
-    uniform_buffer: wgpu::Buffer,
+    sdf_edge_buffer: Vec<SdfEdge>,
+    material_buffer: Vec<VertexMaterial>,
+    // The uniforms for each unique camera and set of lights used within the current frame.
+    uniforms: Vec<Uniforms>,
 }
 
 /// A type aimed at simplifying construction of a `draw::Renderer`.
@@ -107,6 +183,7 @@ pub struct Builder {
     pub glyph_cache_size: [u32; 2],
     pub glyph_cache_scale_tolerance: f32,
     pub glyph_cache_position_tolerance: f32,
+    pub text_mode: TextMode,
 }
 
 /// Commands that map to wgpu encodable commands.
@@ -118,6 +195,8 @@ enum RenderCommand {
     SetBindGroup(BindGroupId),
     /// Set the rectangular scissor.
     SetScissor(Scissor),
+    /// Change the uniform bind group for the camera and lights at the given index.
+    SetCamera(usize),
     /// Draw the given vertex range.
     DrawIndexed {
         start_vertex: i32,
@@ -142,12 +221,39 @@ pub struct DrawError;
 struct Uniforms {
     /// Translates from "logical pixel coordinate space" (our "world space") to screen space.
     ///
-    /// Specifically:
+    /// This is the combined view and projection matrix of a **draw::Camera**. By default:
     ///
     /// - x is transformed from (-half_logical_win_w, half_logical_win_w) to (-1, 1).
     /// - y is transformed from (-half_logical_win_h, half_logical_win_h) to (1, -1).
     /// - z is transformed from (-max_logical_win_side, max_logical_win_side) to (0, 1).
     proj: Mat4,
+    /// The camera's position in world space if `w` is `1.0`, otherwise the direction towards the
+    /// viewer of an orthographic camera.
+    eye: Vec4,
+    /// The color of the ambient light.
+    ambient: Vec4,
+    lights: [LightUniforms; light::MAX_LIGHTS],
+    light_count: u32,
+    _padding: [u32; 3],
+}
+
+/// A single light as described to the shaders.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+struct LightUniforms {
+    /// The position of a point light if `w` is `1.0`, otherwise the direction of a directional
+    /// light.
+    position: Vec4,
+    /// The color of the light, where `w` is the reciprocal of the square of its range, or `0.0`
+    /// if the light does not fade.
+    color: Vec4,
+}
+
+/// The uniform buffer and bind group associated with a single camera and set of lights.
+#[derive(Debug)]
+struct CameraUniforms {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
 }
 
 type SamplerId = u64;
@@ -175,6 +281,9 @@ impl RenderPrimitive for draw::Primitive {
     fn render_primitive(self, ctxt: RenderContext, mesh: &mut draw::Mesh) -> PrimitiveRender {
         match self {
             draw::Primitive::Arrow(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Cone(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Cuboid(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Cylinder(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Mesh(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Path(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Polygon(prim) => prim.render_primitive(ctxt, mesh),
@@ -182,6 +291,8 @@ impl RenderPrimitive for draw::Primitive {
             draw::Primitive::Ellipse(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Quad(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Rect(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Sphere(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Torus(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Line(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Text(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Texture(prim) => prim.render_primitive(ctxt, mesh),
@@ -190,12 +301,59 @@ impl RenderPrimitive for draw::Primitive {
     }
 }
 
+impl From<Material> for VertexMaterial {
+    fn from(material: Material) -> Self {
+        match material {
+            Material::Unlit => VertexMaterial::default(),
+            Material::Lambert => VertexMaterial {
+                model: 1,
+                ..Default::default()
+            },
+            Material::BlinnPhong {
+                specular,
+                shininess,
+            } => VertexMaterial {
+                model: 2,
+                specular,
+                shininess,
+            },
+        }
+    }
+}
+
+impl From<Light> for LightUniforms {
+    fn from(light: Light) -> Self {
+        let color = |c: crate::color::LinSrgb, inv_range_sq: f32| {
+            let (r, g, b) = c.into_components();
+            Vec4::new(r, g, b, inv_range_sq)
+        };
+        match light {
+            Light::Directional {
+                direction,
+                color: c,
+            } => LightUniforms {
+                position: direction.normalize_or_zero().extend(0.0),
+                color: color(c, 0.0),
+            },
+            Light::Point {
+                position,
+                color: c,
+                range,
+            } => LightUniforms {
+                position: position.extend(1.0),
+                color: color(c, range.map(|r| 1.0 / (r * r)).unwrap_or(0.0)),
+            },
+        }
+    }
+}
+
 impl fmt::Debug for GlyphCache {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("GlyphCache")
             .field("cache", &self.cache.dimensions())
             .field("pixel_buffer", &self.pixel_buffer.len())
             .field("requires_upload", &self.requires_upload)
+            .field("mode", &self.mode)
             .finish()
     }
 }
@@ -223,6 +381,10 @@ impl PrimitiveRender {
     pub fn text() -> Self {
         Self::vertex_mode(VertexMode::Text)
     }
+
+    pub fn sdf_text() -> Self {
+        Self::vertex_mode(VertexMode::SdfText)
+    }
 }
 
 impl Builder {
@@ -236,6 +398,8 @@ impl Builder {
     /// The default position tolerance for the glyph cache.
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 =
         Renderer::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE;
+    /// The default mode used to render text.
+    pub const DEFAULT_TEXT_MODE: TextMode = Renderer::DEFAULT_TEXT_MODE;
 
     /// Begin building a new **draw::Renderer**.
     pub fn new() -> Self {
@@ -244,6 +408,7 @@ impl Builder {
             glyph_cache_size: Self::DEFAULT_GLYPH_CACHE_SIZE,
             glyph_cache_scale_tolerance: Self::DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE,
             glyph_cache_position_tolerance: Self::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE,
+            text_mode: Self::DEFAULT_TEXT_MODE,
         }
     }
 
@@ -283,6 +448,16 @@ impl Builder {
         self
     }
 
+    /// Specify the way in which text should be rasterised and displayed.
+    ///
+    /// `TextMode::Sdf` is recommended for text that is large, zoomed, rotated or continuously
+    /// scaled, as glyphs need not be re-rasterised for each new scale. The outline, glow and drop
+    /// shadow text styles are only displayed in this mode.
+    pub fn text_mode(mut self, mode: TextMode) -> Self {
+        self.text_mode = mode;
+        self
+    }
+
     /// Build the **draw::Renderer** ready to target an output attachment of the given descriptor.
     pub fn build_from_texture_descriptor(
         self,
@@ -319,12 +494,31 @@ impl Builder {
             self.glyph_cache_size,
             self.glyph_cache_scale_tolerance,
             self.glyph_cache_position_tolerance,
+            self.text_mode,
         )
     }
+
+    /// Build a **draw::CpuRenderer** that renders to images without a GPU.
+    ///
+    /// The `depth_format` is ignored, as the CPU renderer always uses `f32` depth.
+    pub fn build_cpu(self) -> CpuRenderer {
+        let glyph_cache = GlyphCache::new(
+            self.glyph_cache_size,
+            self.glyph_cache_scale_tolerance,
+            self.glyph_cache_position_tolerance,
+            self.text_mode,
+        );
+        CpuRenderer::from_glyph_cache(glyph_cache)
+    }
 }
 
 impl GlyphCache {
-    fn new(size: [u32; 2], scale_tolerance: f32, position_tolerance: f32) -> Self {
+    pub(crate) fn new(
+        size: [u32; 2],
+        scale_tolerance: f32,
+        position_tolerance: f32,
+        mode: TextMode,
+    ) -> Self {
         let [w, h] = size;
         let cache = text::GlyphCache::builder()
             .dimensions(w, h)
@@ -334,10 +528,79 @@ impl GlyphCache {
             .into();
         let pixel_buffer = vec![0u8; w as usize * h as usize];
         let requires_upload = false;
+        let sdf_atlas =
+            text::sdf::Atlas::new(size, text::sdf::DEFAULT_GLYPH_PX, text::sdf::DEFAULT_SPREAD);
         GlyphCache {
             cache,
             pixel_buffer,
             requires_upload,
+            mode,
+            sdf_atlas,
+            sdf_atlas_full: false,
+        }
+    }
+
+    /// Prepare the cache for a new frame.
+    ///
+    /// If the `sdf_atlas` filled up during the previous frame, it is cleared along with the
+    /// `pixel_buffer` so that the glyphs of the new frame may be cached.
+    pub(crate) fn begin_frame(&mut self) {
+        if self.sdf_atlas_full {
+            self.sdf_atlas.clear();
+            for px in &mut self.pixel_buffer {
+                *px = 0;
+            }
+            self.requires_upload = true;
+            self.sdf_atlas_full = false;
+        }
+    }
+
+    /// Retrieve the signed distance field atlas entry for the given glyph, generating the glyph's
+    /// field and writing it to the `pixel_buffer` if necessary.
+    ///
+    /// The given `variations` are applied to glyphs of variable fonts. See
+    /// `text::sdf::Atlas::get_or_insert` for details.
+    ///
+    /// If the atlas is full, the glyph is skipped and the atlas is cleared at the beginning of the
+    /// next frame. Clearing the atlas immediately would invalidate the texture coordinates of the
+    /// glyphs that have already been drawn during this frame.
+    ///
+    /// Returns `None` if the glyph has no outline, is too large for the atlas, or if the atlas is
+    /// full.
+    pub fn sdf_entry(
+        &mut self,
+        font_id: text::font::Id,
+        font: &text::Font,
+        id: text::GlyphId,
+        variations: &[text::font::Variation],
+    ) -> Option<text::sdf::Entry> {
+        let GlyphCache {
+            ref mut sdf_atlas,
+            ref mut pixel_buffer,
+            ref mut requires_upload,
+            ref mut sdf_atlas_full,
+            ..
+        } = *self;
+        let atlas_w = sdf_atlas.size()[0] as usize;
+        let upload = |rect: text::rt::Rect<u32>, data: &[u8]| {
+            let width = (rect.max.x - rect.min.x) as usize;
+            for (row, src) in data.chunks(width).enumerate() {
+                let dst_ix = (rect.min.y as usize + row) * atlas_w + rect.min.x as usize;
+                pixel_buffer[dst_ix..dst_ix + width].copy_from_slice(src);
+            }
+            *requires_upload = true;
+        };
+        match sdf_atlas.get_or_insert(font_id, font, id, variations, upload) {
+            Ok(entry) => entry,
+            // Clearing an empty atlas would not make room for the glyph.
+            Err(err) if sdf_atlas.is_empty() => {
+                eprintln!("failed to cache glyph distance field: {}", err);
+                None
+            }
+            Err(_) => {
+                *sdf_atlas_full = true;
+                None
+            }
         }
     }
 }
@@ -351,6 +614,8 @@ impl Renderer {
     pub const DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE: f32 = 0.1;
     /// The default position tolerance for the glyph cache.
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 = 0.1;
+    /// The default mode used to render text.
+    pub const DEFAULT_TEXT_MODE: TextMode = TextMode::Bitmap;
     /// The texture format of the inner glyph cache.
     pub const GLYPH_CACHE_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
     /// The index format used to index into vertices.
@@ -366,6 +631,8 @@ impl Renderer {
     ///
     /// The `glyph_cache_size` will be used to create a texture on which glyphs will be stored for
     /// efficient look-up.
+    ///
+    /// The `text_mode` determines whether glyphs are cached as bitmaps or signed distance fields.
     pub fn new(
         device: &wgpu::Device,
         output_attachment_size: [u32; 2],
@@ -376,12 +643,14 @@ impl Renderer {
         glyph_cache_size: [u32; 2],
         glyph_cache_scale_tolerance: f32,
         glyph_cache_position_tolerance: f32,
+        text_mode: TextMode,
     ) -> Self {
         // Construct the glyph cache.
         let glyph_cache = GlyphCache::new(
             glyph_cache_size,
             glyph_cache_scale_tolerance,
             glyph_cache_position_tolerance,
+            text_mode,
         );
 
         // Load shader modules.
@@ -414,20 +683,20 @@ impl Renderer {
             .build(device);
         let default_texture_view = default_texture.view().build();
 
-        // Initial uniform buffer values. These will be overridden on draw.
-        let uniforms = create_uniforms(output_attachment_size, output_scale_factor);
-        let contents = uniforms_as_bytes(&uniforms);
-        let usage = wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST;
-        let uniform_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
-            label: Some("nannou Renderer uniform_buffer"),
-            contents,
-            usage,
-        });
-
-        // Bind group for uniforms.
+        // Bind group for uniforms. Initial values are for the default camera and will be
+        // overridden on draw.
         let uniform_bind_group_layout = create_uniform_bind_group_layout(device);
-        let uniform_bind_group =
-            create_uniform_bind_group(device, &uniform_bind_group_layout, &uniform_buffer);
+        let uniforms = create_uniforms(
+            &draw::Camera::default(),
+            &draw::Lights::default(),
+            output_attachment_size,
+            output_scale_factor,
+        );
+        let camera_uniforms = vec![create_camera_uniforms(
+            device,
+            &uniform_bind_group_layout,
+            &uniforms,
+        )];
 
         // Bind group for text.
         let text_bind_group_layout = create_text_bind_group_layout(device, text_sampler_filtering);
@@ -454,6 +723,8 @@ impl Renderer {
         let render_commands = vec![];
         let mesh = Default::default();
         let vertex_mode_buffer = vec![];
+        let sdf_edge_buffer = vec![];
+        let material_buffer = vec![];
 
         Self {
             vs_mod,
@@ -465,7 +736,7 @@ impl Renderer {
             _default_texture: default_texture,
             default_texture_view,
             uniform_bind_group_layout,
-            uniform_bind_group,
+            camera_uniforms,
             text_bind_group_layout,
             text_bind_group,
             texture_samplers,
@@ -474,11 +745,12 @@ impl Renderer {
             pipelines,
             output_color_format,
             sample_count,
-            scale_factor: output_scale_factor,
             render_commands,
             mesh,
             vertex_mode_buffer,
-            uniform_buffer,
+            sdf_edge_buffer,
+            material_buffer,
+            uniforms: vec![],
         }
     }
 
@@ -487,6 +759,14 @@ impl Renderer {
         self.render_commands.clear();
         self.mesh.clear();
         self.vertex_mode_buffer.clear();
+        self.sdf_edge_buffer.clear();
+        self.material_buffer.clear();
+        self.uniforms.clear();
+    }
+
+    /// The mode with which the renderer rasterises and displays text.
+    pub fn text_mode(&self) -> TextMode {
+        self.glyph_cache.mode
     }
 
     /// Generate a list of `RenderCommand`s from the given **Draw** instance and prepare any
@@ -551,6 +831,11 @@ impl Renderer {
         let mut curr_pipeline_id = None;
         let mut curr_scissor = None;
         let mut curr_tex_sampler_id = None;
+        let mut curr_camera_index = None;
+        // Each unique camera and set of lights used within the frame.
+        let mut cameras: Vec<(draw::Camera, draw::Lights)> = vec![];
+
+        self.glyph_cache.begin_frame();
 
         // Collect all draw commands to avoid borrow errors.
         let draw_cmds: Vec<_> = draw.drain_commands().collect();
@@ -576,7 +861,10 @@ impl Renderer {
                         transform: &curr_ctxt.transform,
                         fill_tessellator: &mut fill_tessellator,
                         stroke_tessellator: &mut stroke_tessellator,
+                        vector_paths: None,
                         glyph_cache: &mut self.glyph_cache,
+                        sdf_edges: &mut self.sdf_edge_buffer,
+                        vertex_modes: &mut self.vertex_mode_buffer,
                         output_attachment_size: Vec2::new(px_to_pt(w_px), px_to_pt(h_px)),
                         output_attachment_scale_factor: scale_factor,
                     };
@@ -623,17 +911,27 @@ impl Renderer {
                         (sampler_id, tex_view_id)
                     };
                     let new_scissor = curr_ctxt.scissor;
+                    let new_camera_index = cameras
+                        .iter()
+                        .position(|(camera, lights)| {
+                            *camera == curr_ctxt.camera && *lights == curr_ctxt.lights
+                        })
+                        .unwrap_or_else(|| {
+                            cameras.push((curr_ctxt.camera, curr_ctxt.lights.clone()));
+                            cameras.len() - 1
+                        });
 
                     // Determine which have changed and in turn which require submitting new
                     // commands.
                     let pipeline_changed = Some(new_pipeline_id) != curr_pipeline_id;
                     let bind_group_changed = Some(new_bind_group_id) != curr_tex_sampler_id;
                     let scissor_changed = Some(new_scissor) != curr_scissor;
+                    let camera_changed = Some(new_camera_index) != curr_camera_index;
 
-                    // If we require submitting a scissor, pipeline or bind group command, first
-                    // draw whatever pending vertices we have collected so far. If there have been
-                    // no graphics yet, this will do nothing.
-                    if scissor_changed || pipeline_changed || bind_group_changed {
+                    // If we require submitting a scissor, pipeline, bind group or camera command,
+                    // first draw whatever pending vertices we have collected so far. If there have
+                    // been no graphics yet, this will do nothing.
+                    if scissor_changed || pipeline_changed || bind_group_changed || camera_changed {
                         push_draw_cmd(
                             &mut curr_start_index,
                             prev_index_count,
@@ -686,10 +984,35 @@ impl Renderer {
                         self.render_commands.push(cmd);
                     }
 
-                    // Extend the vertex mode channel.
+                    // If necessary, push a new camera command.
+                    if camera_changed {
+                        curr_camera_index = Some(new_camera_index);
+                        let cmd = RenderCommand::SetCamera(new_camera_index);
+                        self.render_commands.push(cmd);
+                    }
+
+                    // Extend the vertex mode channel for vertices that were not assigned a mode.
                     let mode = render.vertex_mode;
                     let new_vs = self.mesh.points().len() - self.vertex_mode_buffer.len();
                     self.vertex_mode_buffer.extend((0..new_vs).map(|_| mode));
+
+                    // Extend the SDF edge channel for vertices that were not assigned an edge.
+                    let new_vs = self.mesh.points().len() - self.sdf_edge_buffer.len();
+                    self.sdf_edge_buffer
+                        .extend((0..new_vs).map(|_| SdfEdge::default()));
+
+                    // Vertices without a normal are shaded using their triangle's normal.
+                    let len = self.mesh.points().len();
+                    if self.mesh.normals().len() < len {
+                        let new_vs = len - self.mesh.normals().len();
+                        let normals = (0..new_vs).map(|_| Normal::ZERO);
+                        self.mesh.set_normals(len - new_vs, normals);
+                    }
+
+                    // Extend the material channel with the context's material.
+                    let material = VertexMaterial::from(curr_ctxt.material);
+                    let new_vs = self.mesh.points().len() - self.material_buffer.len();
+                    self.material_buffer.extend((0..new_vs).map(|_| material));
                 }
             }
         }
@@ -701,6 +1024,18 @@ impl Renderer {
             &mut self.render_commands,
         );
 
+        // Determine the uniforms for each camera and set of lights, ensuring we have a buffer and
+        // bind group for each.
+        self.uniforms.extend(cameras.iter().map(|(camera, lights)| {
+            create_uniforms(camera, lights, output_attachment_size, scale_factor)
+        }));
+        while self.camera_uniforms.len() < self.uniforms.len() {
+            let uniforms = &self.uniforms[self.camera_uniforms.len()];
+            let camera_uniforms =
+                create_camera_uniforms(device, &self.uniform_bind_group_layout, uniforms);
+            self.camera_uniforms.push(camera_uniforms);
+        }
+
         // Clear out unnecessary pipelines.
         self.pipelines
             .retain(|id, _| new_pipeline_ids.contains_key(id));
@@ -789,14 +1124,15 @@ impl Renderer {
             ref glyph_cache_texture,
             ref mut depth_texture,
             ref mut depth_texture_view,
-            ref uniform_bind_group,
+            ref camera_uniforms,
             ref text_bind_group,
             ref texture_bind_groups,
             ref mesh,
             ref vertex_mode_buffer,
+            ref sdf_edge_buffer,
+            ref material_buffer,
             ref mut render_commands,
-            ref uniform_buffer,
-            scale_factor: ref mut old_scale_factor,
+            ref uniforms,
             ..
         } = *self;
 
@@ -847,6 +1183,9 @@ impl Renderer {
         let colors_bytes = colors_as_bytes(mesh.colors());
         let tex_coords_bytes = tex_coords_as_bytes(mesh.tex_coords());
         let modes_bytes = vertex_modes_as_bytes(vertex_mode_buffer);
+        let sdf_edges_bytes = sdf_edges_as_bytes(sdf_edge_buffer);
+        let normals_bytes = normals_as_bytes(mesh.normals());
+        let materials_bytes = materials_as_bytes(material_buffer);
         let indices_bytes = indices_as_bytes(mesh.indices());
         let point_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("nannou Renderer point_buffer"),
@@ -868,27 +1207,45 @@ impl Renderer {
             contents: modes_bytes,
             usage: vertex_usage,
         });
+        let sdf_edge_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer sdf_edge_buffer"),
+            contents: sdf_edges_bytes,
+            usage: vertex_usage,
+        });
+        let normal_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer normal_buffer"),
+            contents: normals_bytes,
+            usage: vertex_usage,
+        });
+        let material_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer material_buffer"),
+            contents: materials_bytes,
+            usage: vertex_usage,
+        });
         let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("nannou Renderer index_buffer"),
             contents: indices_bytes,
             usage: wgpu::BufferUsages::INDEX,
         });
 
-        // If the scale factor or window size has changed, update the uniforms for vertex scaling.
-        if *old_scale_factor != scale_factor || output_attachment_size != depth_size {
-            *old_scale_factor = scale_factor;
-            // Upload uniform data for vertex scaling.
-            let uniforms = create_uniforms(output_attachment_size, scale_factor);
-            let uniforms_size = std::mem::size_of::<Uniforms>() as wgpu::BufferAddress;
-            let uniforms_bytes = uniforms_as_bytes(&uniforms);
-            let usage = wgpu::BufferUsages::COPY_SRC;
-            let new_uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
-                label: Some("nannou Renderer uniform_buffer"),
-                contents: uniforms_bytes,
-                usage,
-            });
-            // Copy new uniform buffer state.
-            encoder.copy_buffer_to_buffer(&new_uniform_buffer, 0, uniform_buffer, 0, uniforms_size);
+        // Upload the uniform data for each camera and set of lights used within the frame.
+        let uniforms_size = std::mem::size_of::<Uniforms>() as wgpu::BufferAddress;
+        let uniforms_bytes = uniforms_slice_as_bytes(uniforms);
+        let usage = wgpu::BufferUsages::COPY_SRC;
+        let new_uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer uniform_buffer"),
+            contents: uniforms_bytes,
+            usage,
+        });
+        for (i, camera_uniforms) in camera_uniforms.iter().take(uniforms.len()).enumerate() {
+            let src_offset = i as wgpu::BufferAddress * uniforms_size;
+            encoder.copy_buffer_to_buffer(
+                &new_uniform_buffer,
+                src_offset,
+                &camera_uniforms.buffer,
+                0,
+                uniforms_size,
+            );
         }
 
         // Encode the render pass.
@@ -900,9 +1257,11 @@ impl Renderer {
         render_pass.set_vertex_buffer(1, color_buffer.slice(..));
         render_pass.set_vertex_buffer(2, tex_coords_buffer.slice(..));
         render_pass.set_vertex_buffer(3, mode_buffer.slice(..));
+        render_pass.set_vertex_buffer(4, sdf_edge_buffer.slice(..));
+        render_pass.set_vertex_buffer(5, normal_buffer.slice(..));
+        render_pass.set_vertex_buffer(6, material_buffer.slice(..));
 
-        // Set the uniform and text bind groups here.
-        render_pass.set_bind_group(0, uniform_bind_group, &[]);
+        // Set the text bind group here. The uniform bind group is set per camera and set of lights.
         render_pass.set_bind_group(1, text_bind_group, &[]);
 
         // Follow the render commands.
@@ -927,6 +1286,11 @@ impl Renderer {
                     render_pass.set_scissor_rect(left, bottom, width, height);
                 }
 
+                RenderCommand::SetCamera(index) => {
+                    let bind_group = &camera_uniforms[index].bind_group;
+                    render_pass.set_bind_group(0, bind_group, &[]);
+                }
+
                 RenderCommand::DrawIndexed {
                     start_vertex,
                     index_range,
@@ -1021,27 +1385,64 @@ fn create_depth_texture(
         .build(device)
 }
 
-fn create_uniforms([img_w, img_h]: [u32; 2], scale_factor: f32) -> Uniforms {
-    let right = img_w as f32 * 0.5 / scale_factor;
-    let left = -right;
-    let top = img_h as f32 * 0.5 / scale_factor;
-    let bottom = -top;
-    let far = std::cmp::max(img_w, img_h) as f32 / scale_factor;
-    let near = -far;
-    let proj = Mat4::orthographic_rh_gl(left, right, bottom, top, near, far);
-    // By default, ortho scales z values to the range -1.0 to 1.0. We want to scale and translate
-    // the z axis so that it is in the range of 0.0 to 1.0.
-    // TODO: Can possibly solve this more easily by using `Mat4::orthographic_rh` above instead.
-    let trans = Mat4::from_translation(Vec3::Z);
-    let scale = Mat4::from_scale([1.0, 1.0, 0.5].into());
-    let proj = scale * trans * proj;
-    let proj = proj.into();
-    Uniforms { proj }
+fn create_uniforms(
+    camera: &draw::Camera,
+    lights: &draw::Lights,
+    [img_w, img_h]: [u32; 2],
+    scale_factor: f32,
+) -> Uniforms {
+    let logical_size = Vec2::new(img_w as f32, img_h as f32) / scale_factor;
+    let proj = camera.view_projection(logical_size);
+
+    // For projections without perspective, the viewer lies infinitely far along view space's
+    // positive *z* axis.
+    let inv_view = camera.view_matrix(logical_size).inverse();
+    let is_perspective = camera.projection_matrix(logical_size).row(3) != Vec4::W;
+    let eye = if is_perspective {
+        inv_view.transform_point3(Vec3::ZERO).extend(1.0)
+    } else {
+        inv_view
+            .transform_vector3(Vec3::Z)
+            .normalize_or_zero()
+            .extend(0.0)
+    };
+
+    let (r, g, b) = lights.ambient.into_components();
+    let ambient = Vec4::new(r, g, b, 1.0);
+    let mut light_uniforms = [LightUniforms::default(); light::MAX_LIGHTS];
+    for (u, light) in light_uniforms.iter_mut().zip(&lights.lights) {
+        *u = LightUniforms::from(*light);
+    }
+    let light_count = lights.lights.len().min(light::MAX_LIGHTS) as u32;
+    Uniforms {
+        proj,
+        eye,
+        ambient,
+        lights: light_uniforms,
+        light_count,
+        _padding: [0; 3],
+    }
+}
+
+fn create_camera_uniforms(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniforms: &Uniforms,
+) -> CameraUniforms {
+    let contents = uniforms_as_bytes(uniforms);
+    let usage = wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST;
+    let buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+        label: Some("nannou Renderer uniform_buffer"),
+        contents,
+        usage,
+    });
+    let bind_group = create_uniform_bind_group(device, layout, &buffer);
+    CameraUniforms { buffer, bind_group }
 }
 
 fn create_uniform_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     wgpu::BindGroupLayoutBuilder::new()
-        .uniform_buffer(wgpu::ShaderStages::VERTEX, false)
+        .uniform_buffer(wgpu::ShaderStages::VERTEX_FRAGMENT, false)
         .build(device)
 }
 
@@ -1123,6 +1524,8 @@ fn create_render_pipeline(
     alpha_blend: wgpu::BlendComponent,
     topology: wgpu::PrimitiveTopology,
 ) -> wgpu::RenderPipeline {
+    const MATERIAL_ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![6 => Uint32, 7 => Float32x2];
     let bind_group_layouts = &[uniform_layout, text_layout, texture_layout];
     wgpu::RenderPipelineBuilder::from_layout_descriptor(&bind_group_layouts[..], vs_mod)
         .fragment_shader(fs_mod)
@@ -1133,6 +1536,9 @@ fn create_render_pipeline(
             &wgpu::vertex_attr_array![2 => Float32x2],
         )
         .add_vertex_buffer::<VertexMode>(&wgpu::vertex_attr_array![3 => Uint32])
+        .add_vertex_buffer::<SdfEdge>(&wgpu::vertex_attr_array![4 => Float32x2])
+        .add_vertex_buffer::<Normal>(&wgpu::vertex_attr_array![5 => Float32x3])
+        .add_vertex_buffer::<VertexMaterial>(&MATERIAL_ATTRIBUTES)
         .depth_format(depth_format)
         .sample_count(sample_count)
         .color_blend(color_blend)
@@ -1171,6 +1577,10 @@ fn uniforms_as_bytes(uniforms: &Uniforms) -> &[u8] {
     unsafe { wgpu::bytes::from(uniforms) }
 }
 
+fn uniforms_slice_as_bytes(uniforms: &[Uniforms]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(uniforms) }
+}
+
 fn points_as_bytes(data: &[draw::mesh::vertex::Point]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
@@ -1187,6 +1597,63 @@ fn vertex_modes_as_bytes(data: &[VertexMode]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
+fn sdf_edges_as_bytes(data: &[SdfEdge]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
+fn normals_as_bytes(data: &[Normal]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
+fn materials_as_bytes(data: &[VertexMaterial]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
 fn indices_as_bytes(data: &[u32]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
+
+#[cfg(all(test, feature = "notosans"))]
+mod tests {
+    use super::{GlyphCache, TextMode};
+    use crate::text;
+
+    fn glyph_cache(size: [u32; 2]) -> GlyphCache {
+        GlyphCache::new(size, 0.1, 0.1, TextMode::Sdf)
+    }
+
+    #[test]
+    fn full_sdf_atlas_is_cleared_next_frame() {
+        let font = text::font::default_notosans();
+        let font_id = text::font::id(&font);
+        let [a, b] = [font.glyph('A').id(), font.glyph('B').id()];
+        // Only a single glyph fits within the atlas at the default glyph size.
+        let mut cache = glyph_cache([64, 64]);
+
+        let entry_a = cache.sdf_entry(font_id, &font, a, &[]).unwrap();
+        assert!(cache.requires_upload);
+        assert!(cache.sdf_entry(font_id, &font, b, &[]).is_none());
+        assert!(cache.sdf_atlas_full);
+        // Glyphs already drawn during the frame remain valid.
+        assert_eq!(cache.sdf_entry(font_id, &font, a, &[]), Some(entry_a));
+
+        cache.requires_upload = false;
+        cache.begin_frame();
+        assert!(!cache.sdf_atlas_full);
+        assert!(cache.sdf_atlas.is_empty());
+        assert!(cache.pixel_buffer.iter().all(|&px| px == 0));
+        assert!(cache.requires_upload);
+        assert!(cache.sdf_entry(font_id, &font, b, &[]).is_some());
+    }
+
+    #[test]
+    fn glyph_too_large_for_sdf_atlas() {
+        let font = text::font::default_notosans();
+        let font_id = text::font::id(&font);
+        let mut cache = glyph_cache([16, 16]);
+        let a = font.glyph('A').id();
+        assert!(cache.sdf_entry(font_id, &font, a, &[]).is_none());
+        // Clearing the atlas would not help, so it is left as is.
+        assert!(!cache.sdf_atlas_full);
+    }
+}
@@ -1,7 +1,22 @@
+struct Light {
+    position: vec4<f32>,
+    color: vec4<f32>,
+};
+
+struct Data {
+    proj: mat4x4<f32>,
+    eye: vec4<f32>,
+    ambient: vec4<f32>,
+    lights: array<Light, 8>,
+    light_count: u32,
+};
+
 struct FragmentOutput {
     @location(0) color: vec4<f32>,
 };
 
+@group(0) @binding(0)
+var<uniform> uniforms: Data;
 @group(1) @binding(0)
 var text_sampler: sampler;
 @group(1) @binding(1)
@@ -16,10 +31,19 @@ fn main(
     @location(0) color: vec4<f32>,
     @location(1) tex_coords: vec2<f32>,
     @location(2) mode: u32,
+    @location(3) sdf_edge: vec2<f32>,
+    @location(4) world_pos: vec3<f32>,
+    @location(5) normal: vec3<f32>,
+    @location(6) material: u32,
+    @location(7) material_params: vec2<f32>,
 ) -> FragmentOutput {
     let tex_color: vec4<f32> = textureSample(tex, tex_sampler, tex_coords);
     let text_color: vec4<f32> = textureSample(text, text_sampler, tex_coords);
     let text_alpha: f32 = text_color.x;
+    // The distance field's rate of change per pixel, used to anti-alias SDF text edges.
+    let sdf_aa: f32 = max(max(0.5 * fwidth(text_alpha), sdf_edge.y), 0.0001);
+    // The normal of the triangle, used to shade vertices without a normal.
+    let face_normal: vec3<f32> = cross(dpdx(world_pos), dpdy(world_pos));
     var out_color: vec4<f32>;
     if (mode == u32(0)) {
         out_color = color;
@@ -30,9 +54,65 @@ fn main(
             if (mode == u32(2)) {
                 out_color = vec4<f32>(color.xyz, color.w * text_alpha);
             } else {
-                out_color = vec4<f32>(1.0, 0.0, 0.0, 1.0);
+                if (mode == u32(3)) {
+                    let threshold: f32 = sdf_edge.x;
+                    let coverage: f32 = smoothstep(threshold - sdf_aa, threshold + sdf_aa, text_alpha);
+                    out_color = vec4<f32>(color.xyz, color.w * coverage);
+                } else {
+                    out_color = vec4<f32>(1.0, 0.0, 0.0, 1.0);
+                }
             }
         }
     }
+    if (material != u32(0)) {
+        out_color = shade(out_color, world_pos, normal, face_normal, material, material_params);
+    }
     return FragmentOutput(out_color);
 }
+
+// Shade the given color with the Lambert (`1`) or Blinn-Phong (`2`) lighting model.
+fn shade(
+    color: vec4<f32>,
+    world_pos: vec3<f32>,
+    normal: vec3<f32>,
+    face_normal: vec3<f32>,
+    material: u32,
+    material_params: vec2<f32>,
+) -> vec4<f32> {
+    var view_dir: vec3<f32> = uniforms.eye.xyz;
+    if (uniforms.eye.w != 0.0) {
+        view_dir = normalize(uniforms.eye.xyz - world_pos);
+    }
+    var n: vec3<f32>;
+    if (dot(normal, normal) > 0.000001) {
+        n = normalize(normal);
+    } else {
+        // The winding of the face is unknown, so shade whichever side faces the viewer.
+        n = normalize(face_normal);
+        if (dot(n, view_dir) < 0.0) {
+            n = -n;
+        }
+    }
+    var light: vec3<f32> = uniforms.ambient.xyz;
+    var highlight: vec3<f32> = vec3<f32>(0.0, 0.0, 0.0);
+    for (var i: u32 = u32(0); i < uniforms.light_count; i = i + u32(1)) {
+        let l = uniforms.lights[i];
+        var light_dir: vec3<f32> = -l.position.xyz;
+        var attenuation: f32 = 1.0;
+        if (l.position.w != 0.0) {
+            let to_light = l.position.xyz - world_pos;
+            let dist_sq = dot(to_light, to_light);
+            light_dir = to_light * inverseSqrt(max(dist_sq, 0.000001));
+            let falloff = clamp(1.0 - dist_sq * l.color.w, 0.0, 1.0);
+            attenuation = falloff * falloff;
+        }
+        let diffuse = max(dot(n, light_dir), 0.0);
+        light = light + l.color.xyz * diffuse * attenuation;
+        if (material == u32(2) && diffuse > 0.0) {
+            let half_dir = normalize(light_dir + view_dir);
+            let specular = pow(max(dot(n, half_dir), 0.0), material_params.y);
+            highlight = highlight + l.color.xyz * material_params.x * specular * attenuation;
+        }
+    }
+    return vec4<f32>(color.xyz * light + highlight, color.w);
+}
@@ -1,11 +1,25 @@
+struct Light {
+    position: vec4<f32>,
+    color: vec4<f32>,
+};
+
 struct Data {
     proj: mat4x4<f32>,
+    eye: vec4<f32>,
+    ambient: vec4<f32>,
+    lights: array<Light, 8>,
+    light_count: u32,
 };
 
 struct VertexOutput {
     @location(0) color: vec4<f32>,
     @location(1) tex_coords: vec2<f32>,
     @location(2) mode: u32,
+    @location(3) sdf_edge: vec2<f32>,
+    @location(4) world_pos: vec3<f32>,
+    @location(5) normal: vec3<f32>,
+    @location(6) material: u32,
+    @location(7) material_params: vec2<f32>,
     @builtin(position) pos: vec4<f32>,
 };
 
@@ -18,7 +32,21 @@ fn main(
     @location(1) color: vec4<f32>,
     @location(2) tex_coords: vec2<f32>,
     @location(3) mode: u32,
+    @location(4) sdf_edge: vec2<f32>,
+    @location(5) normal: vec3<f32>,
+    @location(6) material: u32,
+    @location(7) material_params: vec2<f32>,
 ) -> VertexOutput {
     let out_pos: vec4<f32> = uniforms.proj * vec4<f32>(position, 1.0);
-    return VertexOutput(color, tex_coords, mode, out_pos);
+    return VertexOutput(
+        color,
+        tex_coords,
+        mode,
+        sdf_edge,
+        position,
+        normal,
+        material,
+        material_params,
+        out_pos,
+    );
 }
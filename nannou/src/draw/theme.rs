@@ -24,7 +24,9 @@ pub struct Color {
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Primitive {
     Arrow,
+    Cone,
     Cuboid,
+    Cylinder,
     Ellipse,
     Line,
     Mesh,
@@ -32,8 +34,10 @@ pub enum Primitive {
     Polygon,
     Quad,
     Rect,
+    Sphere,
     Text,
     Texture,
+    Torus,
     Tri,
 }
 
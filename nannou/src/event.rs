@@ -30,10 +30,15 @@ pub struct Update {
     /// The duration since the last update was emitted.
     ///
     /// The first update's delta will be the time since the given `model` function returned.
+    ///
+    /// When running with `LoopMode::Rate`, this is always the fixed `update_interval`.
     pub since_last: std::time::Duration,
     /// The duration since the start of the app loop.
     ///
     /// Specifically, this is the duration of time since the given `model` function returned.
+    ///
+    /// When running with `LoopMode::Rate`, this is the simulated time, advancing by exactly
+    /// `update_interval` with each update regardless of how much time has actually elapsed.
     pub since_start: std::time::Duration,
 }
 
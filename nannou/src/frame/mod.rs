@@ -8,8 +8,10 @@ use std::sync::Mutex;
 use std::time::Duration;
 
 pub mod raw;
+pub mod recording;
 
 pub use self::raw::RawFrame;
+pub use self::recording::{RecordingConfig, RecordingError};
 
 /// A **Frame** to which the user can draw graphics before it is presented to the display.
 ///
@@ -40,6 +42,8 @@ pub struct RenderData {
 pub(crate) struct CaptureData {
     // If `Some`, indicates a path to which the current frame should be written.
     pub(crate) next_frame_path: Mutex<Option<PathBuf>>,
+    // If `Some`, the active recording to which submitted frames are written.
+    pub(crate) recorder: Mutex<Option<recording::Recorder>>,
     // The `TextureCapturer` used to capture the frame.
     pub(crate) texture_capturer: wgpu::TextureCapturer,
 }
@@ -120,6 +124,21 @@ impl<'swap_chain> Frame<'swap_chain> {
             }
         }
 
+        // Check to see if the frame should be recorded.
+        let mut snapshot_record = None;
+        if let Ok(mut guard) = capture_data.recorder.lock() {
+            if let Some((slot, tx)) = guard.as_mut().and_then(|rec| rec.next_frame()) {
+                let device = raw_frame.device_queue_pair().device();
+                let mut encoder = raw_frame.command_encoder();
+                let snapshot = capture_data.texture_capturer.capture(
+                    device,
+                    &mut *encoder,
+                    &render_data.intermediary_lin_srgba.texture,
+                );
+                snapshot_record = Some((slot, tx, snapshot));
+            }
+        }
+
         // Convert the linear sRGBA image to the swapchain image.
         //
         // To do so, we sample the linear sRGBA image and draw it to the swapchain image using
@@ -156,6 +175,27 @@ impl<'swap_chain> Frame<'swap_chain> {
                 eprintln!("timed out while waiting for a worker thread to capture the frame");
             }
         }
+
+        // If recording, submit the asynchronous read and send the frame to the encoder.
+        if let Some((slot, tx, snapshot)) = snapshot_record {
+            let missing_tx = tx.clone();
+            let result = snapshot.read(move |result| {
+                let frame = match result {
+                    // TODO: Log errors, don't print to stderr.
+                    Err(e) => {
+                        eprintln!("failed to async read recorded frame: {:?}", e);
+                        recording::RecordedFrame::missing(slot)
+                    }
+                    Ok(image) => recording::RecordedFrame::new(slot, image.to_owned()),
+                };
+                tx.send(frame);
+            });
+            if let Err(wgpu::TextureCapturerAwaitWorkerTimeout(_)) = result {
+                // TODO: Log errors, don't print to stderr.
+                eprintln!("timed out while waiting for a worker thread to record the frame");
+                missing_tx.send(recording::RecordedFrame::missing(slot));
+            }
+        }
     }
 
     /// The texture to which all graphics should be drawn this frame.
@@ -272,6 +312,7 @@ impl CaptureData {
     pub(crate) fn new(max_jobs: u32, timeout: Option<Duration>) -> Self {
         CaptureData {
             next_frame_path: Default::default(),
+            recorder: Default::default(),
             texture_capturer: wgpu::TextureCapturer::new(Some(max_jobs), timeout),
         }
     }
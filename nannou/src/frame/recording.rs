@@ -0,0 +1,753 @@
+//! Items related to recording a sequence of frames to a video or animated image file.
+//!
+//! See the **Window::start_recording** and **Window::stop_recording** methods.
+
+use crate::image::codecs::gif::{GifEncoder, Repeat};
+use crate::image::{self, Delay, RgbaImage};
+use instant::Instant;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::atomic::{self, AtomicU64};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use std::{fmt, fs, thread};
+
+/// Describes how the frames of a window should be recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordingConfig {
+    /// The frame rate of the output file in frames per second.
+    pub fps: f64,
+    /// The encoder used to write frames to the output file.
+    pub encoder: Encoder,
+    /// Whether or not the app should update on a fixed timestep while recording.
+    ///
+    /// See **RecordingConfig::fixed_timestep** for details.
+    pub fixed_timestep: bool,
+}
+
+/// The encoder used to write recorded frames to the output file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Encoder {
+    /// Select an encoder based on the extension of the output path.
+    ///
+    /// Paths ending in `gif` use the **Gif** encoder. All other paths use the **Ffmpeg** encoder
+    /// with its default arguments.
+    Auto,
+    /// Stream raw frames to an `ffmpeg` subprocess.
+    ///
+    /// The container and codec are inferred by `ffmpeg` from the output path, e.g. `mp4` or
+    /// `webm`. The `ffmpeg` program must be installed.
+    Ffmpeg {
+        /// The `ffmpeg` program to run.
+        program: PathBuf,
+        /// Arguments inserted between the raw video input and the output path, e.g. codec and
+        /// quality options.
+        output_args: Vec<String>,
+    },
+    /// Write an animated GIF in-process.
+    ///
+    /// Note that GIF frame delays have a precision of 10ms.
+    Gif {
+        /// The speed of color quantization in the range `1..=30`, where `1` is the slowest and
+        /// produces the best quality.
+        speed: i32,
+    },
+}
+
+/// Errors that might occur while recording.
+#[derive(Debug)]
+pub enum RecordingError {
+    /// An error occurred while creating, writing to or spawning the encoder.
+    Io(io::Error),
+    /// An error occurred while encoding an image.
+    Image(image::ImageError),
+    /// The encoder subprocess exited unsuccessfully.
+    EncoderExit {
+        /// The exit status of the subprocess.
+        status: ExitStatus,
+        /// Everything the subprocess wrote to stderr, which usually describes the failure.
+        stderr: String,
+    },
+    /// The encoder thread panicked.
+    EncoderPanicked,
+}
+
+/// Records the frames captured from a window and sends them to the encoder thread.
+pub(crate) struct Recorder {
+    config: RecordingConfig,
+    // The moment the first frame was recorded.
+    start: Option<Instant>,
+    // The number of frames submitted for capture so far.
+    submitted: u64,
+    // The output slot of the most recently submitted frame.
+    last_slot: Option<u64>,
+    tx: Option<FrameSender>,
+    thread: Option<thread::JoinHandle<Result<(), RecordingError>>>,
+}
+
+/// Describes where a captured frame belongs within the recording.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FrameSlot {
+    // Frames may be read from the GPU out of order, so they are re-ordered by this index.
+    index: u64,
+    // The index of the frame within the output, in units of `1 / fps`.
+    slot: u64,
+}
+
+/// A frame sent to the encoder thread.
+pub(crate) struct RecordedFrame {
+    slot: FrameSlot,
+    // `None` if the frame could not be read from the GPU.
+    image: Option<RgbaImage>,
+}
+
+/// The sending end of the channel to the encoder thread.
+///
+/// In real-time recordings, frames are sent without blocking. If the encoder has fallen behind and
+/// the queue is full, the frame is dropped and the previous frame is held in its place. Recordings
+/// on a fixed timestep block until there is room in the queue instead.
+#[derive(Clone)]
+pub(crate) struct FrameSender {
+    tx: mpsc::SyncSender<RecordedFrame>,
+    dropped: Arc<Dropped>,
+    // Whether to block on a full queue rather than drop the frame.
+    block: bool,
+}
+
+// Frames dropped because the encoder's queue was full.
+#[derive(Default)]
+struct Dropped {
+    // The total number of dropped frames.
+    count: AtomicU64,
+    // Dropped frames that the encoder has not yet been notified of.
+    slots: Mutex<Vec<FrameSlot>>,
+}
+
+// The destination to which the encoder thread writes frames.
+enum Sink {
+    Ffmpeg {
+        child: Child,
+        stdin: BufWriter<ChildStdin>,
+        // Collects stderr on a separate thread so that the subprocess never blocks on it.
+        stderr: thread::JoinHandle<String>,
+        size: [u32; 2],
+    },
+    Gif {
+        encoder: GifEncoder<BufWriter<fs::File>>,
+        interval: Duration,
+    },
+}
+
+impl RecordingConfig {
+    /// The default frame rate of recordings.
+    pub const DEFAULT_FPS: f64 = 60.0;
+
+    /// The default configuration.
+    ///
+    /// Records at `DEFAULT_FPS` with an encoder selected by the output path's extension.
+    pub fn new() -> Self {
+        RecordingConfig {
+            fps: Self::DEFAULT_FPS,
+            encoder: Encoder::Auto,
+            fixed_timestep: false,
+        }
+    }
+
+    /// The frame rate of the output file in frames per second.
+    pub fn fps(mut self, fps: f64) -> Self {
+        assert!(fps > 0.0, "recording `fps` must be greater than zero");
+        self.fps = fps;
+        self
+    }
+
+    /// The encoder used to write frames to the output file.
+    pub fn encoder(mut self, encoder: Encoder) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// Whether or not the app should update on a fixed timestep while recording.
+    ///
+    /// By default, frames are recorded in real-time: frames rendered faster than `fps` are
+    /// dropped and frames rendered slower are held for as many output frames as necessary. If the
+    /// encoder falls behind, frames are dropped rather than stalling the app.
+    ///
+    /// When enabled, the app applies exactly one `Update` per frame regardless of its loop mode,
+    /// where `since_last` is always `1 / fps`. Every rendered frame is recorded exactly once, so
+    /// the timing of the output does not depend on how quickly the app renders. If the encoder
+    /// falls behind, delivering captured frames blocks until it catches up.
+    pub fn fixed_timestep(mut self, fixed_timestep: bool) -> Self {
+        self.fixed_timestep = fixed_timestep;
+        self
+    }
+
+    /// The duration of a single frame of the output.
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+}
+
+impl Encoder {
+    /// The default `ffmpeg` program.
+    pub const DEFAULT_FFMPEG_PROGRAM: &'static str = "ffmpeg";
+    /// The default GIF color quantization speed.
+    pub const DEFAULT_GIF_SPEED: i32 = 10;
+
+    /// An `ffmpeg` encoder with the default arguments.
+    ///
+    /// The default arguments encode to the widely supported `yuv420p` pixel format, padding the
+    /// frame to even dimensions as this format requires.
+    pub fn ffmpeg() -> Self {
+        let output_args = [
+            "-pix_fmt",
+            "yuv420p",
+            "-vf",
+            "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+        ];
+        Encoder::Ffmpeg {
+            program: Self::DEFAULT_FFMPEG_PROGRAM.into(),
+            output_args: output_args.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// An animated GIF encoder with the default quantization speed.
+    pub fn gif() -> Self {
+        Encoder::Gif {
+            speed: Self::DEFAULT_GIF_SPEED,
+        }
+    }
+
+    // Resolve the `Auto` encoder for the given output path.
+    fn resolve(self, path: &Path) -> Self {
+        match self {
+            Encoder::Auto => match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ext) if ext.eq_ignore_ascii_case("gif") => Encoder::gif(),
+                _ => Encoder::ffmpeg(),
+            },
+            encoder => encoder,
+        }
+    }
+}
+
+impl Recorder {
+    // The maximum number of frames that may be queued for the encoder thread before frames are
+    // dropped, or before delivery blocks on a fixed timestep.
+    const QUEUE_CAPACITY: usize = 16;
+
+    /// Begin a recording at the given path with frames of the given size in pixels.
+    ///
+    /// Spawns the encoder thread, along with the encoder subprocess if necessary.
+    pub(crate) fn new(
+        path: &Path,
+        config: RecordingConfig,
+        size: [u32; 2],
+    ) -> Result<Self, RecordingError> {
+        let encoder = config.encoder.clone().resolve(path);
+        let sink = Sink::new(path, &encoder, config.fps, size)?;
+        let (tx, rx) = mpsc::sync_channel(Self::QUEUE_CAPACITY);
+        let dropped = Arc::new(Dropped::default());
+        let tx = FrameSender {
+            tx,
+            dropped: dropped.clone(),
+            block: config.fixed_timestep,
+        };
+        let thread = thread::Builder::new()
+            .name("nannou_recording".into())
+            .spawn(move || run_encoder(rx, &dropped, sink))?;
+        Ok(Recorder {
+            config,
+            start: None,
+            submitted: 0,
+            last_slot: None,
+            tx: Some(tx),
+            thread: Some(thread),
+        })
+    }
+
+    /// The number of frames dropped so far because the encoder could not keep up.
+    pub(crate) fn dropped_frames(&self) -> u64 {
+        match self.tx {
+            None => 0,
+            Some(ref tx) => tx.dropped.count.load(atomic::Ordering::Relaxed),
+        }
+    }
+
+    /// The recording's configuration.
+    pub(crate) fn config(&self) -> &RecordingConfig {
+        &self.config
+    }
+
+    /// Determine whether or not the frame that is about to be submitted should be recorded.
+    ///
+    /// Returns the frame's slot within the recording along with a sender for delivering the
+    /// captured frame to the encoder, or `None` if the frame should be skipped.
+    pub(crate) fn next_frame(&mut self) -> Option<(FrameSlot, FrameSender)> {
+        let tx = self.tx.clone()?;
+        let slot = match self.last_slot {
+            None => {
+                self.start = Some(Instant::now());
+                0
+            }
+            Some(last) if self.config.fixed_timestep => last + 1,
+            Some(last) => {
+                let start = self.start.expect("no start instant for recording");
+                let slot = (start.elapsed().as_secs_f64() * self.config.fps) as u64;
+                // Skip frames rendered faster than the recording's frame rate.
+                if slot <= last {
+                    return None;
+                }
+                slot
+            }
+        };
+        let index = self.submitted;
+        self.submitted += 1;
+        self.last_slot = Some(slot);
+        Some((FrameSlot { index, slot }, tx))
+    }
+
+    /// Finish the recording, blocking until the encoder has written all queued frames.
+    ///
+    /// All pending frame captures should be awaited before calling this.
+    pub(crate) fn finish(mut self) -> Result<(), RecordingError> {
+        self.finish_inner()
+    }
+
+    fn finish_inner(&mut self) -> Result<(), RecordingError> {
+        // Dropping the sender closes the channel once all in-flight captures complete.
+        self.tx.take();
+        match self.thread.take() {
+            None => Ok(()),
+            Some(thread) => thread
+                .join()
+                .unwrap_or(Err(RecordingError::EncoderPanicked)),
+        }
+    }
+}
+
+impl RecordedFrame {
+    /// A frame that was successfully read from the GPU.
+    pub(crate) fn new(slot: FrameSlot, image: RgbaImage) -> Self {
+        let image = Some(image);
+        RecordedFrame { slot, image }
+    }
+
+    /// A frame that failed to be read from the GPU.
+    ///
+    /// The encoder continues to hold the previous frame in its place.
+    pub(crate) fn missing(slot: FrameSlot) -> Self {
+        let image = None;
+        RecordedFrame { slot, image }
+    }
+}
+
+impl FrameSender {
+    /// Deliver the frame to the encoder.
+    ///
+    /// If the encoder's queue is full, the frame is dropped and the encoder is notified so that
+    /// it holds the previous frame in its place. On a fixed timestep, this blocks until there is
+    /// room in the queue instead so that no frames are lost.
+    pub(crate) fn send(&self, frame: RecordedFrame) {
+        if self.block {
+            // The encoder may have stopped early due to an error, which is reported when the
+            // recording is stopped.
+            let _ = self.tx.send(frame);
+            return;
+        }
+        match self.tx.try_send(frame) {
+            Ok(()) => (),
+            Err(mpsc::TrySendError::Full(frame)) => {
+                self.dropped.count.fetch_add(1, atomic::Ordering::Relaxed);
+                if let Ok(mut slots) = self.dropped.slots.lock() {
+                    slots.push(frame.slot);
+                }
+            }
+            // The encoder may have stopped early due to an error, which is reported when the
+            // recording is stopped.
+            Err(mpsc::TrySendError::Disconnected(_)) => (),
+        }
+    }
+}
+
+impl Sink {
+    fn new(
+        path: &Path,
+        encoder: &Encoder,
+        fps: f64,
+        size: [u32; 2],
+    ) -> Result<Self, RecordingError> {
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        match *encoder {
+            Encoder::Ffmpeg {
+                ref program,
+                ref output_args,
+            } => {
+                let [w, h] = size;
+                let mut child = Command::new(program)
+                    .args(["-y", "-f", "rawvideo", "-pix_fmt", "rgba"])
+                    .arg("-s")
+                    .arg(format!("{}x{}", w, h))
+                    .arg("-r")
+                    .arg(fps.to_string())
+                    .args(["-i", "-"])
+                    .args(output_args)
+                    .arg(path)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
+                let stdin = child.stdin.take().expect("failed to take `ffmpeg` stdin");
+                let stdin = BufWriter::new(stdin);
+                let mut pipe = child.stderr.take().expect("failed to take `ffmpeg` stderr");
+                let stderr = thread::Builder::new()
+                    .name("nannou_recording_stderr".into())
+                    .spawn(move || {
+                        let mut stderr = vec![];
+                        let _ = pipe.read_to_end(&mut stderr);
+                        String::from_utf8_lossy(&stderr).into_owned()
+                    })?;
+                Ok(Sink::Ffmpeg {
+                    child,
+                    stdin,
+                    stderr,
+                    size,
+                })
+            }
+            Encoder::Gif { speed } => {
+                let file = BufWriter::new(fs::File::create(path)?);
+                let mut encoder = GifEncoder::new_with_speed(file, speed);
+                encoder.set_repeat(Repeat::Infinite)?;
+                let interval = Duration::from_secs_f64(1.0 / fps);
+                Ok(Sink::Gif { encoder, interval })
+            }
+            Encoder::Auto => unreachable!("`Encoder::Auto` must be resolved first"),
+        }
+    }
+
+    // Write the image so that it is displayed for the given number of output frames.
+    fn write(&mut self, image: RgbaImage, frames: u64) -> Result<(), RecordingError> {
+        match *self {
+            Sink::Ffmpeg {
+                ref mut stdin,
+                size,
+                ..
+            } => {
+                // The raw video stream requires a constant size, e.g. if the window was resized.
+                let [w, h] = size;
+                let image = match image.dimensions() == (w, h) {
+                    true => image,
+                    false => image::imageops::resize(&image, w, h, image::imageops::Triangle),
+                };
+                for _ in 0..frames {
+                    stdin.write_all(&image)?;
+                }
+            }
+            Sink::Gif {
+                ref mut encoder,
+                interval,
+            } => {
+                let duration = interval * frames as u32;
+                let delay = Delay::from_saturating_duration(duration);
+                let frame = image::Frame::from_parts(image, 0, 0, delay);
+                encoder.encode_frame(frame)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Flush the output and wait for the encoder to complete.
+    //
+    // If `result` is an error, e.g. because the subprocess closed its stdin early, it is returned
+    // unless the subprocess reports a more descriptive failure.
+    fn finish(self, result: Result<(), RecordingError>) -> Result<(), RecordingError> {
+        match self {
+            Sink::Ffmpeg {
+                mut child,
+                mut stdin,
+                stderr,
+                ..
+            } => {
+                let result = result.and_then(|()| stdin.flush().map_err(From::from));
+                // Closing stdin signals the end of the stream.
+                drop(stdin);
+                let status = child.wait()?;
+                let stderr = stderr.join().unwrap_or_default();
+                if !status.success() {
+                    return Err(RecordingError::EncoderExit { status, stderr });
+                }
+                result
+            }
+            Sink::Gif { encoder, .. } => {
+                // The GIF trailer is written when the encoder is dropped.
+                drop(encoder);
+                result
+            }
+        }
+    }
+}
+
+// The encoder thread.
+fn run_encoder(
+    rx: mpsc::Receiver<RecordedFrame>,
+    dropped: &Dropped,
+    mut sink: Sink,
+) -> Result<(), RecordingError> {
+    let result = encode_frames(rx, dropped, |image, frames| sink.write(image, frames));
+    sink.finish(result)
+}
+
+// Writes each received frame via `write`.
+//
+// Frames are re-ordered by their submission index and each is written once the following frame
+// arrives, so that it may be held for every output slot until the next frame begins.
+fn encode_frames<F>(
+    rx: mpsc::Receiver<RecordedFrame>,
+    dropped: &Dropped,
+    mut write: F,
+) -> Result<(), RecordingError>
+where
+    F: FnMut(RgbaImage, u64) -> Result<(), RecordingError>,
+{
+    let mut next_index = 0;
+    let mut pending = BTreeMap::new();
+    let mut held: Option<(RgbaImage, u64)> = None;
+    for frame in rx {
+        pending.insert(frame.slot.index, frame);
+        // Dropped frames take the place of the frames that never arrive.
+        if let Ok(mut slots) = dropped.slots.lock() {
+            for slot in slots.drain(..) {
+                pending.insert(slot.index, RecordedFrame::missing(slot));
+            }
+        }
+        while let Some(frame) = pending.remove(&next_index) {
+            next_index += 1;
+            let image = match frame.image {
+                Some(image) => image,
+                None => continue,
+            };
+            if let Some((prev, prev_slot)) = held.take() {
+                write(prev, frame.slot.slot - prev_slot)?;
+            }
+            held = Some((image, frame.slot.slot));
+        }
+    }
+    // The channel has closed, so write any frames that remain.
+    for (_, frame) in pending {
+        if let Some(image) = frame.image {
+            if let Some((prev, prev_slot)) = held.take() {
+                write(prev, frame.slot.slot - prev_slot)?;
+            }
+            held = Some((image, frame.slot.slot));
+        }
+    }
+    if let Some((image, _)) = held {
+        write(image, 1)?;
+    }
+    Ok(())
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish_inner() {
+            // TODO: Replace eprintlns with proper logging.
+            eprintln!("failed to finish recording: {}", e);
+        }
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("config", &self.config)
+            .field("submitted", &self.submitted)
+            .field("dropped_frames", &self.dropped_frames())
+            .finish()
+    }
+}
+
+impl From<io::Error> for RecordingError {
+    fn from(err: io::Error) -> Self {
+        RecordingError::Io(err)
+    }
+}
+
+impl From<image::ImageError> for RecordingError {
+    fn from(err: image::ImageError) -> Self {
+        RecordingError::Image(err)
+    }
+}
+
+impl Error for RecordingError {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            RecordingError::Io(ref err) => Some(err),
+            RecordingError::Image(ref err) => Some(err),
+            RecordingError::EncoderExit { .. } | RecordingError::EncoderPanicked => None,
+        }
+    }
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RecordingError::Io(ref err) => fmt::Display::fmt(err, f),
+            RecordingError::Image(ref err) => fmt::Display::fmt(err, f),
+            RecordingError::EncoderExit { status, ref stderr } => {
+                write!(f, "the encoder exited unsuccessfully: {}", status)?;
+                match stderr.trim() {
+                    "" => Ok(()),
+                    stderr => write!(f, "\n{}", stderr),
+                }
+            }
+            RecordingError::EncoderPanicked => write!(f, "the encoder thread panicked"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_frames, Dropped, FrameSlot, RecordedFrame, Recorder, RecordingConfig};
+    use crate::image::RgbaImage;
+    use std::sync::{mpsc, Arc};
+
+    // A recorder without an encoder thread, along with the receiving end of its queue.
+    fn recorder(config: RecordingConfig) -> (Recorder, mpsc::Receiver<RecordedFrame>) {
+        let (tx, rx) = mpsc::sync_channel(Recorder::QUEUE_CAPACITY);
+        let tx = super::FrameSender {
+            tx,
+            dropped: Arc::new(Dropped::default()),
+            block: config.fixed_timestep,
+        };
+        let recorder = Recorder {
+            config,
+            start: None,
+            submitted: 0,
+            last_slot: None,
+            tx: Some(tx),
+            thread: None,
+        };
+        (recorder, rx)
+    }
+
+    // A frame whose single pixel identifies it.
+    fn frame(index: u64, slot: u64) -> RecordedFrame {
+        let image = RgbaImage::from_pixel(1, 1, [index as u8; 4].into());
+        RecordedFrame::new(FrameSlot { index, slot }, image)
+    }
+
+    // Encode the given frames, returning the id and duration of each written image.
+    fn encode(frames: Vec<RecordedFrame>, dropped: &Dropped) -> Vec<(u8, u64)> {
+        let (tx, rx) = mpsc::channel();
+        for frame in frames {
+            tx.send(frame).unwrap();
+        }
+        drop(tx);
+        let mut written = vec![];
+        encode_frames(rx, dropped, |image, frames| {
+            written.push((image.get_pixel(0, 0)[0], frames));
+            Ok(())
+        })
+        .unwrap();
+        written
+    }
+
+    #[test]
+    fn fixed_timestep_slots() {
+        let config = RecordingConfig::new().fixed_timestep(true);
+        let (mut rec, _rx) = recorder(config);
+        for i in 0..4 {
+            let (slot, _) = rec.next_frame().unwrap();
+            assert_eq!((slot.index, slot.slot), (i, i));
+        }
+    }
+
+    #[test]
+    fn real_time_slots_skip_fast_frames() {
+        let (mut rec, _rx) = recorder(RecordingConfig::new().fps(1.0));
+        let (slot, _) = rec.next_frame().unwrap();
+        assert_eq!((slot.index, slot.slot), (0, 0));
+        // Less than a second has passed, so the next frame belongs to the same slot.
+        assert!(rec.next_frame().is_none());
+        assert_eq!(rec.submitted, 1);
+    }
+
+    #[test]
+    fn frames_are_reordered() {
+        let frames = vec![frame(2, 5), frame(0, 0), frame(1, 2), frame(3, 6)];
+        let written = encode(frames, &Dropped::default());
+        assert_eq!(written, vec![(0, 2), (1, 3), (2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn missing_frames_hold_previous() {
+        let missing = RecordedFrame::missing(FrameSlot { index: 1, slot: 1 });
+        let frames = vec![frame(0, 0), missing, frame(2, 3)];
+        let written = encode(frames, &Dropped::default());
+        assert_eq!(written, vec![(0, 3), (2, 1)]);
+    }
+
+    #[test]
+    fn dropped_frames_hold_previous() {
+        let dropped = Dropped::default();
+        dropped
+            .slots
+            .lock()
+            .unwrap()
+            .push(FrameSlot { index: 1, slot: 1 });
+        let frames = vec![frame(0, 0), frame(2, 4), frame(3, 5)];
+        let written = encode(frames, &dropped);
+        assert_eq!(written, vec![(0, 4), (2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn full_queue_drops_frames_in_real_time() {
+        let (mut rec, rx) = recorder(RecordingConfig::new().fps(1_000_000.0));
+        let n = Recorder::QUEUE_CAPACITY + 2;
+        let mut sent = 0;
+        while sent < n {
+            if let Some((slot, tx)) = rec.next_frame() {
+                tx.send(RecordedFrame::missing(slot));
+                sent += 1;
+            }
+        }
+        assert_eq!(rec.dropped_frames(), 2);
+        assert_eq!(rx.try_iter().count(), Recorder::QUEUE_CAPACITY);
+    }
+
+    #[test]
+    fn slow_encoder_loses_no_frames_on_fixed_timestep() {
+        let (mut rec, rx) = recorder(RecordingConfig::new().fixed_timestep(true));
+        let encoder = std::thread::spawn(move || {
+            let mut written = vec![];
+            encode_frames(rx, &Dropped::default(), |image, frames| {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                written.push((image.get_pixel(0, 0)[0], frames));
+                Ok(())
+            })
+            .unwrap();
+            written
+        });
+        let n = Recorder::QUEUE_CAPACITY as u64 * 4;
+        for i in 0..n {
+            let (slot, tx) = rec.next_frame().unwrap();
+            tx.send(frame(i, slot.slot));
+        }
+        assert_eq!(rec.dropped_frames(), 0);
+        rec.tx.take();
+        let written = encoder.join().unwrap();
+        let expected: Vec<_> = (0..n).map(|i| (i as u8, 1)).collect();
+        assert_eq!(written, expected);
+    }
+}
@@ -24,8 +24,6 @@ pub use self::frame::Frame;
 #[doc(inline)]
 pub use nannou_core::{color, glam, math, rand};
 #[doc(inline)]
-pub use nannou_mesh as mesh;
-#[doc(inline)]
 pub use nannou_wgpu as wgpu;
 
 pub mod app;
@@ -36,9 +34,11 @@ pub mod frame;
 pub mod geom;
 pub mod image;
 pub mod io;
+pub mod mesh;
 pub mod noise;
 pub mod prelude;
 pub mod state;
+pub mod testing;
 pub mod text;
 pub mod time;
 pub mod window;
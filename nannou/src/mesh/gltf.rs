@@ -0,0 +1,692 @@
+//! Loading of glTF 2.0 files, in either their JSON (`.gltf`) or binary (`.glb`) form.
+//!
+//! Each triangle primitive of each mesh within the default scene becomes a single **MeshData**,
+//! with the transform of its node applied to its points and normals. The `POSITION`, `NORMAL`,
+//! `TEXCOORD_0` and `COLOR_0` attributes are loaded, along with each material's base color factor
+//! and texture. Primitives drawn as points or lines are skipped.
+//!
+//! Buffers and images may be embedded within the file, either via base64 data URIs or the binary
+//! chunk of a `.glb` file. Buffers and images referred to by a relative URI are only supported by
+//! **load**.
+
+use crate::color::LinSrgba;
+use crate::geom::{Point2, Point3};
+use crate::glam::{Mat3, Mat4, Quat, Vec3};
+use crate::mesh::{join_relative, Material, MeshData, Model};
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+/// Errors that might occur while loading a glTF file.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read the glTF file or one of the files that it refers to.
+    Io(io::Error),
+    /// Failed to deserialize the glTF JSON.
+    Json(serde_json::Error),
+    /// Failed to decode an image.
+    Image(image::ImageError),
+    /// The file is malformed.
+    Invalid(&'static str),
+    /// The file uses a feature that is not supported.
+    Unsupported(&'static str),
+}
+
+/// Load the glTF or GLB file at the given path.
+///
+/// Buffers and images referred to by a relative URI are located relative to the directory of the
+/// file. URIs that are absolute or refer to a parent directory are rejected, so that only files
+/// within the directory are read.
+pub fn load<P>(path: P) -> Result<Model, Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let data = fs::read(path)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    load_inner(&data, Some(dir))
+}
+
+/// Load a glTF or GLB file from its bytes.
+///
+/// All buffers and images must be embedded within the file.
+pub fn from_slice(data: &[u8]) -> Result<Model, Error> {
+    load_inner(data, None)
+}
+
+const GLB_MAGIC: &[u8] = b"glTF";
+const GLB_CHUNK_JSON: u32 = 0x4E4F534A;
+const GLB_CHUNK_BIN: u32 = 0x004E4942;
+
+const MODE_TRIANGLES: u32 = 4;
+const MODE_TRIANGLE_STRIP: u32 = 5;
+const MODE_TRIANGLE_FAN: u32 = 6;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    scene: Option<usize>,
+    #[serde(default)]
+    scenes: Vec<Scene>,
+    #[serde(default)]
+    nodes: Vec<Node>,
+    #[serde(default)]
+    meshes: Vec<Mesh>,
+    #[serde(default)]
+    accessors: Vec<Accessor>,
+    #[serde(default)]
+    buffer_views: Vec<BufferView>,
+    #[serde(default)]
+    buffers: Vec<Buffer>,
+    #[serde(default)]
+    materials: Vec<JsonMaterial>,
+    #[serde(default)]
+    textures: Vec<Texture>,
+    #[serde(default)]
+    images: Vec<Image>,
+}
+
+#[derive(Deserialize)]
+struct Scene {
+    #[serde(default)]
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(default)]
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    matrix: Option<[f32; 16]>,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+}
+
+#[derive(Deserialize)]
+struct Mesh {
+    name: Option<String>,
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "default_mode")]
+    mode: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    ty: String,
+    sparse: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Buffer {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonMaterial {
+    name: Option<String>,
+    pbr_metallic_roughness: Option<PbrMetallicRoughness>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PbrMetallicRoughness {
+    base_color_factor: Option<[f32; 4]>,
+    base_color_texture: Option<TextureInfo>,
+}
+
+#[derive(Deserialize)]
+struct TextureInfo {
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct Texture {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Image {
+    uri: Option<String>,
+    buffer_view: Option<usize>,
+}
+
+fn default_mode() -> u32 {
+    MODE_TRIANGLES
+}
+
+fn load_inner(data: &[u8], dir: Option<&Path>) -> Result<Model, Error> {
+    let (json, bin) = match data.starts_with(GLB_MAGIC) {
+        true => parse_glb(data)?,
+        false => (data, None),
+    };
+    let doc: Document = serde_json::from_slice(json)?;
+
+    // Load the contents of each buffer.
+    let mut buffers = Vec::with_capacity(doc.buffers.len());
+    for (i, buffer) in doc.buffers.iter().enumerate() {
+        let bytes = match buffer.uri {
+            Some(ref uri) => read_uri(uri, dir)?,
+            None if i == 0 => bin
+                .ok_or(Error::Invalid("buffer has no uri or binary chunk"))?
+                .to_vec(),
+            None => return Err(Error::Invalid("buffer has no uri")),
+        };
+        if bytes.len() < buffer.byte_length {
+            return Err(Error::Invalid("buffer is shorter than its byte length"));
+        }
+        buffers.push(bytes);
+    }
+
+    let mut model = Model::default();
+    let reader = Reader {
+        doc: &doc,
+        buffers: &buffers,
+    };
+
+    // Decode the images.
+    for image in &doc.images {
+        let bytes = match (&image.uri, image.buffer_view) {
+            (Some(uri), _) => read_uri(uri, dir)?,
+            (None, Some(view)) => reader.view(view)?.to_vec(),
+            (None, None) => return Err(Error::Invalid("image has no uri or buffer view")),
+        };
+        model.images.push(image::load_from_memory(&bytes)?);
+    }
+
+    // Describe the materials.
+    for material in &doc.materials {
+        let mut m = Material::new();
+        m.name = material.name.clone();
+        if let Some(ref pbr) = material.pbr_metallic_roughness {
+            if let Some([r, g, b, a]) = pbr.base_color_factor {
+                m.base_color = LinSrgba::new(r, g, b, a);
+            }
+            if let Some(ref info) = pbr.base_color_texture {
+                let texture = doc
+                    .textures
+                    .get(info.index)
+                    .ok_or(Error::Invalid("texture index out of range"))?;
+                m.base_color_texture = texture.source;
+            }
+        }
+        model.materials.push(m);
+    }
+
+    // Collect the meshes of the default scene's nodes. Files without scenes or nodes describe
+    // their meshes directly.
+    let scene = doc.scene.or_else(|| doc.scenes.first().map(|_| 0));
+    let roots: Vec<usize> = match scene {
+        Some(scene) => {
+            let scene = doc
+                .scenes
+                .get(scene)
+                .ok_or(Error::Invalid("scene index out of range"))?;
+            scene.nodes.clone()
+        }
+        None => {
+            let children: Vec<usize> = doc.nodes.iter().flat_map(|n| n.children.clone()).collect();
+            (0..doc.nodes.len())
+                .filter(|i| !children.contains(i))
+                .collect()
+        }
+    };
+    if doc.nodes.is_empty() {
+        for mesh in 0..doc.meshes.len() {
+            reader.mesh(mesh, Mat4::IDENTITY, &mut model.meshes)?;
+        }
+    }
+    let mut stack: Vec<(usize, Mat4)> = roots.into_iter().map(|n| (n, Mat4::IDENTITY)).collect();
+    let mut visited = 0;
+    while let Some((node_ix, parent)) = stack.pop() {
+        // Each node belongs to a single tree, so visiting more nodes than exist implies a cycle.
+        visited += 1;
+        if visited > doc.nodes.len() {
+            return Err(Error::Invalid("node hierarchy contains a cycle"));
+        }
+        let node = doc
+            .nodes
+            .get(node_ix)
+            .ok_or(Error::Invalid("node index out of range"))?;
+        let transform = parent * node_transform(node);
+        if let Some(mesh) = node.mesh {
+            reader.mesh(mesh, transform, &mut model.meshes)?;
+        }
+        stack.extend(node.children.iter().rev().map(|&child| (child, transform)));
+    }
+
+    Ok(model)
+}
+
+// Split a GLB file into its JSON chunk and optional binary chunk.
+fn parse_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>), Error> {
+    let u32_at = |i: usize| -> Result<u32, Error> {
+        data.get(i..i + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(Error::Invalid("unexpected end of GLB data"))
+    };
+    if u32_at(4)? != 2 {
+        return Err(Error::Unsupported(
+            "only version 2 of the GLB format is supported",
+        ));
+    }
+    let len = (u32_at(8)? as usize).min(data.len());
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = 12;
+    while offset + 8 <= len {
+        let chunk_len = u32_at(offset)? as usize;
+        let chunk_ty = u32_at(offset + 4)?;
+        let start = offset + 8;
+        let chunk = data
+            .get(start..start + chunk_len)
+            .ok_or(Error::Invalid("GLB chunk exceeds the length of the data"))?;
+        match chunk_ty {
+            GLB_CHUNK_JSON if json.is_none() => json = Some(chunk),
+            GLB_CHUNK_BIN if bin.is_none() => bin = Some(chunk),
+            _ => (),
+        }
+        offset = start + chunk_len;
+    }
+    let json = json.ok_or(Error::Invalid("GLB data has no JSON chunk"))?;
+    Ok((json, bin))
+}
+
+// Read the contents of either a data URI or a URI relative to the given directory.
+fn read_uri(uri: &str, dir: Option<&Path>) -> Result<Vec<u8>, Error> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        return match data.find(";base64,") {
+            Some(ix) => decode_base64(&data[ix + ";base64,".len()..]),
+            None => Err(Error::Unsupported("only base64 data URIs are supported")),
+        };
+    }
+    match dir {
+        Some(dir) => {
+            let path = join_relative(dir, &decode_percent(uri)).ok_or(Error::Invalid(
+                "URIs must be relative paths within the directory of the file",
+            ))?;
+            Ok(fs::read(path)?)
+        }
+        None => Err(Error::Unsupported(
+            "external URIs are only supported when loading from a path",
+        )),
+    }
+}
+
+fn decode_base64(s: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return Err(Error::Invalid("invalid base64 data")),
+        };
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+fn decode_percent(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn node_transform(node: &Node) -> Mat4 {
+    if let Some(ref m) = node.matrix {
+        return Mat4::from_cols_array(m);
+    }
+    let t = node.translation.map(Vec3::from).unwrap_or(Vec3::ZERO);
+    let r = node
+        .rotation
+        .map(Quat::from_array)
+        .unwrap_or(Quat::IDENTITY);
+    let s = node.scale.map(Vec3::from).unwrap_or(Vec3::ONE);
+    Mat4::from_scale_rotation_translation(s, r, t)
+}
+
+// Reads the contents of accessors and buffer views.
+struct Reader<'a> {
+    doc: &'a Document,
+    buffers: &'a [Vec<u8>],
+}
+
+impl<'a> Reader<'a> {
+    // The bytes of the buffer view at the given index.
+    fn view(&self, index: usize) -> Result<&'a [u8], Error> {
+        let view = self
+            .doc
+            .buffer_views
+            .get(index)
+            .ok_or(Error::Invalid("buffer view index out of range"))?;
+        let buffer = self
+            .buffers
+            .get(view.buffer)
+            .ok_or(Error::Invalid("buffer index out of range"))?;
+        view.byte_offset
+            .checked_add(view.byte_length)
+            .and_then(|end| buffer.get(view.byte_offset..end))
+            .ok_or(Error::Invalid(
+                "buffer view exceeds the length of its buffer",
+            ))
+    }
+
+    // Read the elements of the accessor at the given index, returning the number of components
+    // per element along with the components of all elements.
+    //
+    // Integer components are normalized to the `0.0..=1.0` or `-1.0..=1.0` range if the accessor
+    // is normalized or `normalize` is `true`.
+    fn accessor(&self, index: usize, normalize: bool) -> Result<(usize, Vec<f64>), Error> {
+        let accessor = self
+            .doc
+            .accessors
+            .get(index)
+            .ok_or(Error::Invalid("accessor index out of range"))?;
+        if accessor.sparse.is_some() {
+            return Err(Error::Unsupported("sparse accessors are not supported"));
+        }
+        let components = match &accessor.ty[..] {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            _ => return Err(Error::Unsupported("matrix accessors are not supported")),
+        };
+        let component_size = match accessor.component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            _ => return Err(Error::Invalid("unknown accessor component type")),
+        };
+        let element_size = components * component_size;
+        let len = accessor
+            .count
+            .checked_mul(components)
+            .ok_or(Error::Invalid("accessor count is too large"))?;
+        let view_ix = match accessor.buffer_view {
+            Some(view) => view,
+            None => {
+                // Accessors without a buffer view are zero-filled. Limit them to the size of the
+                // file's buffers so that a malformed count cannot exhaust memory.
+                let buffers_len: usize = self.buffers.iter().map(Vec::len).sum();
+                if accessor.count > buffers_len / element_size {
+                    return Err(Error::Invalid("accessor count is too large"));
+                }
+                return Ok((components, vec![0.0; len]));
+            }
+        };
+        let bytes = self.view(view_ix)?;
+        let stride = self.doc.buffer_views[view_ix]
+            .byte_stride
+            .unwrap_or(element_size);
+        if accessor.count > 0 {
+            let end = stride
+                .checked_mul(accessor.count - 1)
+                .and_then(|n| n.checked_add(accessor.byte_offset))
+                .and_then(|n| n.checked_add(element_size));
+            if !matches!(end, Some(end) if end <= bytes.len()) {
+                return Err(Error::Invalid(
+                    "accessor exceeds the length of its buffer view",
+                ));
+            }
+        }
+        let normalize = normalize || accessor.normalized;
+        let mut values = Vec::with_capacity(len);
+        for i in 0..accessor.count {
+            let element = accessor.byte_offset + stride * i;
+            for c in 0..components {
+                let b = &bytes[element + c * component_size..];
+                let v = match accessor.component_type {
+                    5120 if normalize => (b[0] as i8 as f64 / 127.0).max(-1.0),
+                    5120 => b[0] as i8 as f64,
+                    5121 if normalize => b[0] as f64 / 255.0,
+                    5121 => b[0] as f64,
+                    5122 if normalize => {
+                        (i16::from_le_bytes([b[0], b[1]]) as f64 / 32767.0).max(-1.0)
+                    }
+                    5122 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    5123 if normalize => u16::from_le_bytes([b[0], b[1]]) as f64 / 65535.0,
+                    5123 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                };
+                values.push(v);
+            }
+        }
+        Ok((components, values))
+    }
+
+    // Read an accessor whose elements must have exactly the given number of components.
+    fn vectors(&self, index: usize, components: usize, normalize: bool) -> Result<Vec<f64>, Error> {
+        let (n, values) = self.accessor(index, normalize)?;
+        if n != components {
+            return Err(Error::Invalid("accessor has an unexpected type"));
+        }
+        Ok(values)
+    }
+
+    // Append each triangle primitive of the mesh at the given index, transformed by the given
+    // matrix.
+    fn mesh(&self, index: usize, transform: Mat4, meshes: &mut Vec<MeshData>) -> Result<(), Error> {
+        let mesh = self
+            .doc
+            .meshes
+            .get(index)
+            .ok_or(Error::Invalid("mesh index out of range"))?;
+        let normal_matrix = Mat3::from_mat4(transform).inverse().transpose();
+        for primitive in &mesh.primitives {
+            match primitive.mode {
+                MODE_TRIANGLES | MODE_TRIANGLE_STRIP | MODE_TRIANGLE_FAN => (),
+                _ => continue,
+            }
+            let position = *primitive
+                .attributes
+                .get("POSITION")
+                .ok_or(Error::Invalid("primitive has no POSITION attribute"))?;
+            let points: Vec<Point3> = self
+                .vectors(position, 3, false)?
+                .chunks_exact(3)
+                .map(|v| transform.transform_point3(vec3(v)))
+                .collect();
+
+            let mut normals = vec![];
+            if let Some(&ix) = primitive.attributes.get("NORMAL") {
+                normals = self
+                    .vectors(ix, 3, false)?
+                    .chunks_exact(3)
+                    .map(|v| (normal_matrix * vec3(v)).normalize_or_zero())
+                    .collect();
+            }
+
+            let mut tex_coords = vec![];
+            if let Some(&ix) = primitive.attributes.get("TEXCOORD_0") {
+                tex_coords = self
+                    .vectors(ix, 2, true)?
+                    .chunks_exact(2)
+                    .map(|v| Point2::new(v[0] as f32, v[1] as f32))
+                    .collect();
+            }
+
+            let mut colors = vec![];
+            if let Some(&ix) = primitive.attributes.get("COLOR_0") {
+                let (n, values) = self.accessor(ix, true)?;
+                if n != 3 && n != 4 {
+                    return Err(Error::Invalid("COLOR_0 must be a VEC3 or VEC4"));
+                }
+                colors = values
+                    .chunks_exact(n)
+                    .map(|c| {
+                        let a = c.get(3).cloned().unwrap_or(1.0);
+                        LinSrgba::new(c[0] as f32, c[1] as f32, c[2] as f32, a as f32)
+                    })
+                    .collect();
+            }
+
+            let n = points.len();
+            if [normals.len(), tex_coords.len(), colors.len()]
+                .iter()
+                .any(|&len| len != 0 && len != n)
+            {
+                return Err(Error::Invalid("primitive attributes differ in length"));
+            }
+
+            let vertex_indices: Vec<u32> = match primitive.indices {
+                Some(ix) => self
+                    .vectors(ix, 1, false)?
+                    .into_iter()
+                    .map(|i| i as u32)
+                    .collect(),
+                None => (0..n as u32).collect(),
+            };
+            if vertex_indices.iter().any(|&i| i as usize >= n) {
+                return Err(Error::Invalid("primitive index out of range"));
+            }
+            let mut indices = triangulate(primitive.mode, &vertex_indices);
+            // A transform that mirrors the geometry also reverses the winding of its triangles.
+            if transform.determinant() < 0.0 {
+                for tri in indices.chunks_exact_mut(3) {
+                    tri.swap(1, 2);
+                }
+            }
+
+            if let Some(material) = primitive.material {
+                if material >= self.doc.materials.len() {
+                    return Err(Error::Invalid("material index out of range"));
+                }
+            }
+
+            meshes.push(MeshData {
+                name: mesh.name.clone(),
+                points,
+                indices,
+                normals,
+                tex_coords,
+                colors,
+                material: primitive.material,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn vec3(v: &[f64]) -> Vec3 {
+    Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32)
+}
+
+// Convert the indices of a triangle list, strip or fan into a triangle list.
+fn triangulate(mode: u32, indices: &[u32]) -> Vec<u32> {
+    match mode {
+        MODE_TRIANGLE_STRIP => (2..indices.len())
+            .flat_map(|i| match i % 2 {
+                0 => [indices[i - 2], indices[i - 1], indices[i]],
+                _ => [indices[i - 1], indices[i - 2], indices[i]],
+            })
+            .collect(),
+        MODE_TRIANGLE_FAN => (2..indices.len())
+            .flat_map(|i| [indices[0], indices[i - 1], indices[i]])
+            .collect(),
+        _ => indices[..indices.len() - indices.len() % 3].to_vec(),
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(err: image::ImageError) -> Self {
+        Error::Image(err)
+    }
+}
+
+impl StdError for Error {
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Json(ref err) => Some(err),
+            Error::Image(ref err) => Some(err),
+            Error::Invalid(_) | Error::Unsupported(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => fmt::Display::fmt(err, f),
+            Error::Json(ref err) => fmt::Display::fmt(err, f),
+            Error::Image(ref err) => fmt::Display::fmt(err, f),
+            Error::Invalid(s) => write!(f, "invalid glTF: {}", s),
+            Error::Unsupported(s) => write!(f, "unsupported glTF: {}", s),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Items related to meshes.
+//!
+//! Along with the mesh abstraction provided by the `nannou_mesh` crate, this module provides
+//! loaders for Wavefront OBJ files via the **obj** module and glTF 2.0 files via the **gltf**
+//! module. Both produce a **Model** whose meshes may be drawn via `draw.mesh().mesh_data(..)` or
+//! converted to a `nannou_mesh` type via **MeshData::to_mesh**.
+
+#[doc(inline)]
+pub use nannou_mesh::*;
+
+pub use self::model::{Material, Mesh, MeshData, Model};
+use std::path::{Component, Path, PathBuf};
+
+pub mod gltf;
+mod model;
+pub mod obj;
+
+// Join the given path, referred to by a file within `dir`, onto `dir`.
+//
+// Returns `None` if the path is absolute or refers to a parent directory, as it may then refer to a
+// file outside of `dir`.
+fn join_relative(dir: &Path, path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let within_dir = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if within_dir {
+        Some(dir.join(path))
+    } else {
+        None
+    }
+}
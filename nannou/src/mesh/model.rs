@@ -0,0 +1,152 @@
+use crate::color::LinSrgba;
+use crate::draw::primitive::mesh::smooth_normals;
+use crate::geom::{Point2, Point3};
+use crate::glam::Vec3;
+use crate::wgpu;
+use nannou_mesh::{self as mesh, MeshPoints, WithColors, WithIndices, WithNormals, WithTexCoords};
+
+/// The `nannou_mesh` type produced from a loaded **MeshData** via **MeshData::to_mesh**.
+///
+/// Each vertex is described by a point, color, normal and texture coordinates, while each trio of
+/// indices describes a triangle.
+pub type Mesh = WithTexCoords<
+    WithNormals<
+        WithColors<WithIndices<MeshPoints<Vec<Point3>>, Vec<u32>>, Vec<LinSrgba>>,
+        Vec<Vec3>,
+    >,
+    Vec<Point2>,
+>;
+
+/// A collection of meshes along with the materials and images that they refer to, as loaded from
+/// an OBJ or glTF file.
+#[derive(Clone, Debug, Default)]
+pub struct Model {
+    /// All meshes within the model.
+    pub meshes: Vec<MeshData>,
+    /// The materials referred to by the model's meshes.
+    pub materials: Vec<Material>,
+    /// The images referred to by the model's materials.
+    pub images: Vec<image::DynamicImage>,
+}
+
+/// A single triangle mesh, described by its vertex channels and indices.
+///
+/// The `normals`, `tex_coords` and `colors` channels are either empty, in which case the file did
+/// not describe them, or contain one element for each of the `points`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshData {
+    /// The name of the mesh, if any.
+    pub name: Option<String>,
+    /// The position of each vertex.
+    pub points: Vec<Point3>,
+    /// Each trio of indices describes a single counter-clockwise wound triangle.
+    pub indices: Vec<u32>,
+    /// The normal of each vertex.
+    pub normals: Vec<Vec3>,
+    /// The texture coordinates of each vertex, where `[0.0, 0.0]` is the top left of the texture.
+    pub tex_coords: Vec<Point2>,
+    /// The color of each vertex.
+    pub colors: Vec<LinSrgba>,
+    /// The index of the mesh's material within the model's `materials`, if any.
+    pub material: Option<usize>,
+}
+
+/// The surface properties of one or more meshes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    /// The name of the material, if any.
+    pub name: Option<String>,
+    /// The color of the surface, multiplied with the vertex colors and the base color texture.
+    pub base_color: LinSrgba,
+    /// The index of the base color texture within the model's `images`, if any.
+    pub base_color_texture: Option<usize>,
+}
+
+impl Model {
+    /// The material of the given mesh, if any.
+    pub fn material(&self, mesh: &MeshData) -> Option<&Material> {
+        mesh.material.and_then(|i| self.materials.get(i))
+    }
+
+    /// Upload each of the model's images to the GPU.
+    ///
+    /// The returned textures are in the same order as `images`, so that a material's
+    /// `base_color_texture` may be used to index into them.
+    ///
+    /// The `src` can be either the `App`, a `Window`, a `DeviceQueuePair` or a tuple
+    /// `(&Device, &Queue)`.
+    pub fn textures<T>(&self, src: T) -> Vec<wgpu::Texture>
+    where
+        T: wgpu::WithDeviceQueuePair + Copy,
+    {
+        self.images
+            .iter()
+            .map(|image| wgpu::Texture::from_image(src, image))
+            .collect()
+    }
+}
+
+impl MeshData {
+    /// The number of vertices in the mesh.
+    pub fn vertex_count(&self) -> usize {
+        self.points.len()
+    }
+
+    /// The number of triangles in the mesh.
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Produce the area-weighted smooth normal of each vertex, ignoring the `normals` channel.
+    pub fn smooth_normals(&self) -> Vec<Vec3> {
+        smooth_normals(&self.points, &self.indices, 0)
+    }
+
+    /// Convert the mesh data into a `nannou_mesh` mesh with all vertex channels.
+    ///
+    /// Channels missing from the data are filled in: normals are generated via
+    /// **smooth_normals**, colors default to white and texture coordinates default to zero.
+    pub fn to_mesh(&self) -> Mesh {
+        self.clone().into()
+    }
+}
+
+impl Material {
+    /// A white material without a texture.
+    pub fn new() -> Self {
+        Material {
+            name: None,
+            base_color: LinSrgba::new(1.0, 1.0, 1.0, 1.0),
+            base_color_texture: None,
+        }
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<MeshData> for Mesh {
+    fn from(data: MeshData) -> Self {
+        let n = data.points.len();
+        let normals = match data.normals.len() {
+            0 => data.smooth_normals(),
+            _ => data.normals,
+        };
+        let colors = match data.colors.len() {
+            0 => vec![LinSrgba::new(1.0, 1.0, 1.0, 1.0); n],
+            _ => data.colors,
+        };
+        let tex_coords = match data.tex_coords.len() {
+            0 => vec![Point2::ZERO; n],
+            _ => data.tex_coords,
+        };
+        let mesh = mesh::from_points(data.points);
+        let mesh = mesh::with_indices(mesh, data.indices);
+        let mesh = mesh::with_colors(mesh, colors);
+        let mesh = mesh::with_normals(mesh, normals);
+        mesh::with_tex_coords(mesh, tex_coords)
+    }
+}
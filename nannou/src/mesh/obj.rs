@@ -0,0 +1,398 @@
+//! Loading of Wavefront OBJ files and their MTL material libraries.
+//!
+//! Faces with more than three vertices are triangulated as a fan. A new **MeshData** is started
+//! for each `o`, `g` or `usemtl` statement, and each unique combination of position, texture
+//! coordinates and normal within a mesh becomes a single indexed vertex. Vertex colors may be
+//! given after a vertex position in the form `v x y z r g b`.
+//!
+//! Material libraries are only loaded by **load**, in which case the `Kd`, `d`, `Tr` and `map_Kd`
+//! statements are used to describe each material.
+
+use crate::color::{self, LinSrgba};
+use crate::geom::{Point2, Point3};
+use crate::glam::Vec3;
+use crate::mesh::{join_relative, Material, MeshData, Model};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Errors that might occur while loading an OBJ file.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read the OBJ file, one of its material libraries or one of their textures.
+    Io(io::Error),
+    /// Failed to decode a texture.
+    Image(image::ImageError),
+    /// A statement could not be parsed.
+    Parse { line: usize, message: String },
+    /// The path of a material library or texture is absolute or refers to a parent directory.
+    ExternalPath(String),
+}
+
+/// Load the OBJ file at the given path, along with its material libraries and textures.
+///
+/// Material libraries and textures are located relative to the directory of the OBJ file. Their
+/// paths must be relative and may not refer to a parent directory, so that only files within the
+/// directory are read.
+pub fn load<P>(path: P) -> Result<Model, Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let src = fs::read_to_string(path)?;
+    let (mut model, mtllibs) = parse_obj(&src)?;
+    let mut image_paths: Vec<PathBuf> = vec![];
+    for mtllib in mtllibs {
+        let mtl_path =
+            join_relative(dir, &mtllib).ok_or_else(|| Error::ExternalPath(mtllib.clone()))?;
+        let mtl_src = fs::read_to_string(&mtl_path)?;
+        let mtl_dir = mtl_path.parent().unwrap_or(dir);
+        for (name, mtl) in parse_mtl(&mtl_src)? {
+            let material = match model
+                .materials
+                .iter_mut()
+                .find(|m| m.name.as_deref() == Some(&name[..]))
+            {
+                Some(material) => material,
+                None => continue,
+            };
+            material.base_color = mtl.color;
+            if let Some(map) = mtl.map {
+                let image_path =
+                    join_relative(mtl_dir, &map).ok_or_else(|| Error::ExternalPath(map.clone()))?;
+                let ix = match image_paths.iter().position(|p| *p == image_path) {
+                    Some(ix) => ix,
+                    None => {
+                        model.images.push(image::open(&image_path)?);
+                        image_paths.push(image_path);
+                        image_paths.len() - 1
+                    }
+                };
+                material.base_color_texture = Some(ix);
+            }
+        }
+    }
+    Ok(model)
+}
+
+/// Parse the given OBJ source.
+///
+/// Material libraries are not loaded. Instead, each material referred to via `usemtl` is white
+/// and untextured.
+pub fn parse(src: &str) -> Result<Model, Error> {
+    parse_obj(src).map(|(model, _mtllibs)| model)
+}
+
+// The material properties described by an MTL file.
+struct Mtl {
+    color: LinSrgba,
+    map: Option<String>,
+}
+
+// The mesh being built from the faces of the current object, group or material.
+#[derive(Default)]
+struct Builder {
+    data: MeshData,
+    // Maps each unique combination of (position, tex coords, normal) to its vertex index.
+    vertices: HashMap<(usize, Option<usize>, Option<usize>), u32>,
+    has_tex_coords: bool,
+    has_normals: bool,
+}
+
+// Parse the OBJ source, returning the model along with the names of its material libraries.
+fn parse_obj(src: &str) -> Result<(Model, Vec<String>), Error> {
+    let mut model = Model::default();
+    let mut mtllibs = vec![];
+    let mut points: Vec<Point3> = vec![];
+    let mut colors: Vec<Option<LinSrgba>> = vec![];
+    let mut tex_coords: Vec<Point2> = vec![];
+    let mut normals: Vec<Vec3> = vec![];
+    let mut builder = Builder::default();
+
+    for (line, statement) in statements(src) {
+        let err = |message: String| Error::Parse { line, message };
+        let mut tokens = statement.split_whitespace();
+        let keyword = match tokens.next() {
+            None => continue,
+            Some(keyword) => keyword,
+        };
+        let args: Vec<&str> = tokens.collect();
+        match keyword {
+            "v" => {
+                let v = parse_floats(&args).map_err(err)?;
+                match v.len() {
+                    3 | 4 => colors.push(None),
+                    6 | 7 => {
+                        let c = color::Srgb::new(v[3], v[4], v[5]).into_linear();
+                        colors.push(Some(LinSrgba::new(c.red, c.green, c.blue, 1.0)));
+                    }
+                    _ => return Err(err("expected `v x y z [r g b]`".into())),
+                }
+                points.push(Point3::new(v[0], v[1], v[2]));
+            }
+            "vt" => {
+                let v = parse_floats(&args).map_err(err)?;
+                if v.is_empty() || v.len() > 3 {
+                    return Err(err("expected `vt u [v [w]]`".into()));
+                }
+                // OBJ texture coordinates have their origin at the bottom left.
+                let v_coord = v.get(1).cloned().unwrap_or(0.0);
+                tex_coords.push(Point2::new(v[0], 1.0 - v_coord));
+            }
+            "vn" => {
+                let v = parse_floats(&args).map_err(err)?;
+                if v.len() != 3 {
+                    return Err(err("expected `vn x y z`".into()));
+                }
+                normals.push(Vec3::new(v[0], v[1], v[2]).normalize_or_zero());
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(err("a face requires at least three vertices".into()));
+                }
+                let mut face = Vec::with_capacity(args.len());
+                for arg in &args {
+                    let mut parts = arg.split('/');
+                    let mut index = |len: usize| -> Result<Option<usize>, Error> {
+                        match parts.next() {
+                            None | Some("") => Ok(None),
+                            Some(s) => resolve_index(s, len).map(Some).map_err(err),
+                        }
+                    };
+                    let p = match index(points.len())? {
+                        Some(p) => p,
+                        None => return Err(err(format!("face vertex `{}` has no position", arg))),
+                    };
+                    let t = index(tex_coords.len())?;
+                    let n = index(normals.len())?;
+                    face.push(builder.vertex(p, t, n, &points, &tex_coords, &normals));
+                }
+                for i in 1..face.len() - 1 {
+                    builder.data.indices.extend([face[0], face[i], face[i + 1]]);
+                }
+            }
+            "o" | "g" => {
+                let name = Some(args.join(" ")).filter(|name| !name.is_empty());
+                let material = builder.data.material;
+                model.meshes.extend(builder.finish(&colors));
+                builder.data.name = name;
+                builder.data.material = material;
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                let ix = match model
+                    .materials
+                    .iter()
+                    .position(|m| m.name.as_deref() == Some(&name[..]))
+                {
+                    Some(ix) => ix,
+                    None => {
+                        let mut material = Material::new();
+                        material.name = Some(name);
+                        model.materials.push(material);
+                        model.materials.len() - 1
+                    }
+                };
+                let name = builder.data.name.clone();
+                model.meshes.extend(builder.finish(&colors));
+                builder.data.name = name;
+                builder.data.material = Some(ix);
+            }
+            "mtllib" => mtllibs.extend(args.iter().map(|s| s.to_string())),
+            // Smoothing groups, lines, points and free-form geometry are ignored.
+            _ => (),
+        }
+    }
+    model.meshes.extend(builder.finish(&colors));
+    Ok((model, mtllibs))
+}
+
+// Parse the MTL source into its named materials.
+fn parse_mtl(src: &str) -> Result<Vec<(String, Mtl)>, Error> {
+    let mut materials: Vec<(String, Mtl)> = vec![];
+    for (line, statement) in statements(src) {
+        let err = |message: String| Error::Parse { line, message };
+        let mut tokens = statement.split_whitespace();
+        let keyword = match tokens.next() {
+            None => continue,
+            Some(keyword) => keyword,
+        };
+        let args: Vec<&str> = tokens.collect();
+        if keyword == "newmtl" {
+            let color = LinSrgba::new(1.0, 1.0, 1.0, 1.0);
+            let mtl = Mtl { color, map: None };
+            materials.push((args.join(" "), mtl));
+            continue;
+        }
+        let mtl = match materials.last_mut() {
+            Some((_, mtl)) => mtl,
+            None => continue,
+        };
+        match keyword {
+            "Kd" => {
+                let v = parse_floats(&args).map_err(err)?;
+                if v.len() != 3 {
+                    return Err(err("expected `Kd r g b`".into()));
+                }
+                let c = color::Srgb::new(v[0], v[1], v[2]).into_linear();
+                mtl.color = LinSrgba::new(c.red, c.green, c.blue, mtl.color.alpha);
+            }
+            "d" | "Tr" => {
+                let v = parse_floats(&args).map_err(err)?;
+                if v.len() != 1 {
+                    return Err(err(format!("expected `{} alpha`", keyword)));
+                }
+                mtl.color.alpha = if keyword == "d" { v[0] } else { 1.0 - v[0] };
+            }
+            // Texture options may precede the file name, which is always last.
+            "map_Kd" => match args.last() {
+                Some(map) => mtl.map = Some(map.to_string()),
+                None => return Err(err("expected `map_Kd file`".into())),
+            },
+            _ => (),
+        }
+    }
+    Ok(materials)
+}
+
+// Yields each statement along with its line number, joining lines that end with a `\` and
+// stripping comments.
+fn statements(src: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+    let mut lines = src.lines().enumerate();
+    std::iter::from_fn(move || {
+        let (ix, first) = lines.next()?;
+        let mut statement = String::new();
+        let mut line = first;
+        loop {
+            let line_trimmed = line.trim_end();
+            match line_trimmed.strip_suffix('\\') {
+                Some(s) => {
+                    statement.push_str(s);
+                    statement.push(' ');
+                    match lines.next() {
+                        Some((_, next)) => line = next,
+                        None => break,
+                    }
+                }
+                None => {
+                    statement.push_str(line_trimmed);
+                    break;
+                }
+            }
+        }
+        if let Some(comment) = statement.find('#') {
+            statement.truncate(comment);
+        }
+        Some((ix + 1, statement))
+    })
+}
+
+fn parse_floats(args: &[&str]) -> Result<Vec<f32>, String> {
+    args.iter()
+        .map(|s| s.parse().map_err(|_| format!("invalid number `{}`", s)))
+        .collect()
+}
+
+// Resolve a one-based or negative (relative to the end) OBJ index into a zero-based index.
+fn resolve_index(s: &str, len: usize) -> Result<usize, String> {
+    let i: i64 = s.parse().map_err(|_| format!("invalid index `{}`", s))?;
+    let ix = if i > 0 { i - 1 } else { len as i64 + i };
+    if i == 0 || ix < 0 || ix >= len as i64 {
+        return Err(format!("index `{}` is out of range", s));
+    }
+    Ok(ix as usize)
+}
+
+impl Builder {
+    // The index of the vertex with the given attributes, adding it to the mesh if necessary.
+    fn vertex(
+        &mut self,
+        p: usize,
+        t: Option<usize>,
+        n: Option<usize>,
+        points: &[Point3],
+        tex_coords: &[Point2],
+        normals: &[Vec3],
+    ) -> u32 {
+        let data = &mut self.data;
+        let has_tex_coords = &mut self.has_tex_coords;
+        let has_normals = &mut self.has_normals;
+        *self.vertices.entry((p, t, n)).or_insert_with(|| {
+            let ix = data.points.len() as u32;
+            data.points.push(points[p]);
+            data.tex_coords
+                .push(t.map(|t| tex_coords[t]).unwrap_or(Point2::ZERO));
+            data.normals
+                .push(n.map(|n| normals[n]).unwrap_or(Vec3::ZERO));
+            *has_tex_coords |= t.is_some();
+            *has_normals |= n.is_some();
+            ix
+        })
+    }
+
+    // Take the mesh built so far, if it has any faces, leaving the builder empty.
+    fn finish(&mut self, colors: &[Option<LinSrgba>]) -> Option<MeshData> {
+        let builder = std::mem::take(self);
+        let mut data = builder.data;
+        if data.indices.is_empty() {
+            return None;
+        }
+        if !builder.has_tex_coords {
+            data.tex_coords.clear();
+        }
+        if !builder.has_normals {
+            data.normals.clear();
+        }
+        let mut positions = vec![0; data.points.len()];
+        for (&(p, _, _), &ix) in &builder.vertices {
+            positions[ix as usize] = p;
+        }
+        if positions.iter().any(|&p| colors[p].is_some()) {
+            let white = LinSrgba::new(1.0, 1.0, 1.0, 1.0);
+            data.colors = positions
+                .iter()
+                .map(|&p| colors[p].unwrap_or(white))
+                .collect();
+        }
+        Some(data)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(err: image::ImageError) -> Self {
+        Error::Image(err)
+    }
+}
+
+impl StdError for Error {
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Image(ref err) => Some(err),
+            Error::Parse { .. } | Error::ExternalPath(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => fmt::Display::fmt(err, f),
+            Error::Image(ref err) => fmt::Display::fmt(err, f),
+            Error::Parse { line, ref message } => write!(f, "line {}: {}", line, message),
+            Error::ExternalPath(ref path) => write!(
+                f,
+                "`{}` must be a relative path within the directory of the file",
+                path
+            ),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Perceptual comparison of images.
+
+use crate::color::{Lab, Srgb, Xyz};
+use crate::image;
+use std::fmt;
+
+/// The tolerance within which two images are considered to match.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tolerance {
+    /// The maximum perceptual difference between two pixels that are considered equal, measured
+    /// as the CIE76 delta E between their colors.
+    ///
+    /// A delta E of roughly `2.3` is considered to be just noticeable.
+    pub max_delta_e: f32,
+    /// The maximum fraction of pixels that may differ by more than `max_delta_e`.
+    pub max_differing_fraction: f32,
+}
+
+/// The result of comparing an image with a reference image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// The size of the compared image.
+    pub size: [u32; 2],
+    /// The size of the reference image.
+    pub reference_size: [u32; 2],
+    /// The number of pixels that differ by more than the tolerance's `max_delta_e`.
+    ///
+    /// If the sizes of the images differ, pixels beyond the bounds of either image are counted.
+    pub differing_pixels: usize,
+    /// The greatest difference between any two pixels.
+    pub max_delta_e: f32,
+    /// The tolerance with which the images were compared.
+    pub tolerance: Tolerance,
+    // The difference of each pixel of the overlapping region, in rows of the reference's width.
+    deltas: Vec<f32>,
+}
+
+impl Tolerance {
+    /// The default maximum delta E of pixels that are considered equal.
+    pub const DEFAULT_MAX_DELTA_E: f32 = 2.3;
+    /// The default maximum fraction of pixels that may differ.
+    pub const DEFAULT_MAX_DIFFERING_FRACTION: f32 = 0.001;
+
+    /// A tolerance that only accepts identical images.
+    pub fn exact() -> Self {
+        Tolerance {
+            max_delta_e: 0.0,
+            max_differing_fraction: 0.0,
+        }
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            max_delta_e: Self::DEFAULT_MAX_DELTA_E,
+            max_differing_fraction: Self::DEFAULT_MAX_DIFFERING_FRACTION,
+        }
+    }
+}
+
+/// Compare the given image with the reference image.
+///
+/// Pixels are composited over both black and white before comparison, so that differences in
+/// transparency are also detected. The greater of the two differences is used.
+pub fn compare(
+    image: &image::RgbaImage,
+    reference: &image::RgbaImage,
+    tolerance: &Tolerance,
+) -> Comparison {
+    let size = [image.width(), image.height()];
+    let reference_size = [reference.width(), reference.height()];
+    let [w, h] = reference_size;
+    let mut deltas = Vec::with_capacity(w as usize * h as usize);
+    let mut differing_pixels = 0;
+    let mut max_delta_e = 0.0f32;
+    for (x, y, expected) in reference.enumerate_pixels() {
+        let delta = match x < size[0] && y < size[1] {
+            true => delta_e(image.get_pixel(x, y).0, expected.0),
+            false => f32::INFINITY,
+        };
+        if delta > tolerance.max_delta_e {
+            differing_pixels += 1;
+        }
+        max_delta_e = max_delta_e.max(delta);
+        deltas.push(delta);
+    }
+    // Count the pixels of the image that lie beyond the reference.
+    let total = size[0] as usize * size[1] as usize;
+    let overlap = size[0].min(w) as usize * size[1].min(h) as usize;
+    differing_pixels += total - overlap;
+    if total > overlap {
+        max_delta_e = f32::INFINITY;
+    }
+    Comparison {
+        size,
+        reference_size,
+        differing_pixels,
+        max_delta_e,
+        tolerance: *tolerance,
+        deltas,
+    }
+}
+
+impl Comparison {
+    /// The number of pixels within the reference image.
+    pub fn total_pixels(&self) -> usize {
+        let [w, h] = self.reference_size;
+        w as usize * h as usize
+    }
+
+    /// Whether or not the images match within the tolerance.
+    pub fn is_match(&self) -> bool {
+        let max = (self.tolerance.max_differing_fraction * self.total_pixels() as f32) as usize;
+        self.size == self.reference_size && self.differing_pixels <= max
+    }
+
+    /// Produce an image highlighting the differences from the given reference image.
+    ///
+    /// The reference is drawn faded in greyscale. Pixels that differ within the tolerance are
+    /// drawn in yellow, while those that differ beyond the tolerance are drawn in red.
+    pub fn diff_image(&self, reference: &image::RgbaImage) -> image::RgbaImage {
+        let [w, h] = self.reference_size;
+        image::RgbaImage::from_fn(w, h, |x, y| {
+            let delta = self.deltas[y as usize * w as usize + x as usize];
+            if delta > self.tolerance.max_delta_e {
+                image::Rgba([255, 0, 0, 255])
+            } else if delta > 0.0 {
+                image::Rgba([255, 200, 0, 255])
+            } else {
+                let [r, g, b, a] = reference.get_pixel(x, y).0;
+                let luma = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+                // Fade towards white, respecting the reference's transparency.
+                let faded = 255 - (255 - luma) * a as u32 / 255 / 4;
+                let v = faded as u8;
+                image::Rgba([v, v, v, 255])
+            }
+        })
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.size != self.reference_size {
+            let ([w, h], [rw, rh]) = (self.size, self.reference_size);
+            return write!(
+                f,
+                "the image is {}x{} but the reference is {}x{}",
+                w, h, rw, rh
+            );
+        }
+        write!(
+            f,
+            "{} of {} pixels differ by more than a delta E of {} (at most {} may differ), with \
+             a maximum delta E of {:.2}",
+            self.differing_pixels,
+            self.total_pixels(),
+            self.tolerance.max_delta_e,
+            (self.tolerance.max_differing_fraction * self.total_pixels() as f32) as usize,
+            self.max_delta_e,
+        )
+    }
+}
+
+// The greater of the CIE76 differences between the two pixels when composited over black and
+// white.
+fn delta_e(a: [u8; 4], b: [u8; 4]) -> f32 {
+    if a == b {
+        return 0.0;
+    }
+    let over = |[r, g, b, a]: [u8; 4], bg: f32| {
+        let alpha = a as f32 / 255.0;
+        let c = |v: u8| v as f32 / 255.0 * alpha + bg * (1.0 - alpha);
+        let xyz = Xyz::from(Srgb::new(c(r), c(g), c(b)));
+        let lab = Lab::from(xyz);
+        (lab.l, lab.a, lab.b)
+    };
+    let diff = |bg: f32| {
+        let (l1, a1, b1) = over(a, bg);
+        let (l2, a2, b2) = over(b, bg);
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    };
+    diff(0.0).max(diff(1.0))
+}
@@ -0,0 +1,346 @@
+//! A harness for golden-image regression tests of sketches.
+//!
+//! A **Golden** test renders a sketch's view for a number of frames using the
+//! **draw::CpuRenderer**, so that tests may run on machines without a GPU. The final frame is
+//! compared against a stored reference image using a perceptual tolerance. On failure, the
+//! rendered frame and an image highlighting the differing pixels are written alongside the
+//! reference.
+//!
+//! Only the CPU renderer is used, even where a GPU is available, so that the same references
+//! may be shared between all machines. See the **draw::CpuRenderer** docs for the ways in which
+//! its output differs from that of a window. Views that render directly with **wgpu** rather than
+//! via **Draw** are not supported.
+//!
+//! Views are described by a function of a **Context** and a **Draw**. The context provides a
+//! fixed time step along with a seeded random number generator, so that each run produces the
+//! same frames.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::rand::Rng;
+//! use nannou::testing::{Context, Golden};
+//!
+//! fn view(ctxt: &mut Context, draw: &Draw) {
+//!     draw.background().color(BLACK);
+//!     let x = ctxt.rng.gen_range(-50.0..50.0);
+//!     draw.ellipse().x_y(x, ctxt.time.sin() * 50.0).color(PLUM);
+//! }
+//!
+//! #[test]
+//! fn ellipse() {
+//!     Golden::new("tests/golden/ellipse.png")
+//!         .size(200, 200)
+//!         .frames(30)
+//!         .assert(view);
+//! }
+//! ```
+//!
+//! A sketch's `view(&App, Frame)` function cannot be called by the harness, as neither an **App**
+//! nor a **Frame** exists without a window. Instead, move the drawing into a function of the
+//! state it depends on and a **Draw**, and call it from both the sketch and the test.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::testing::Golden;
+//!
+//! fn view(app: &App, frame: Frame) {
+//!     let draw = app.draw();
+//!     sketch(app.time, &draw);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//!
+//! fn sketch(time: f32, draw: &Draw) {
+//!     draw.background().color(BLACK);
+//!     draw.ellipse().x_y(time.sin() * 50.0, 0.0).color(PLUM);
+//! }
+//!
+//! #[test]
+//! fn sketch_matches_golden() {
+//!     Golden::new("tests/golden/sketch.png")
+//!         .frames(30)
+//!         .assert(|ctxt, draw| sketch(ctxt.time, draw));
+//! }
+//! ```
+//!
+//! If the reference image does not exist, the test fails. Set the `NANNOU_UPDATE_GOLDEN`
+//! environment variable to write the rendered frames as new references or to overwrite existing
+//! ones, e.g. after adding a test or an intended change to a sketch's output.
+//!
+//! Relative paths are relative to the current directory, which is the package's root when running
+//! `cargo test`.
+
+use crate::draw::{self, Draw};
+use crate::geom::Rect;
+use crate::image;
+use crate::rand::rngs::StdRng;
+use crate::rand::SeedableRng;
+use std::error::Error as StdError;
+use std::path::{Path, PathBuf};
+use std::{env, fmt, io};
+
+pub use self::compare::{compare, Comparison, Tolerance};
+
+mod compare;
+
+/// The environment variable that, when set, causes **Golden** tests to overwrite their references.
+pub const UPDATE_ENV_VAR: &str = "NANNOU_UPDATE_GOLDEN";
+
+/// Describes a golden-image test of a sketch.
+#[derive(Clone, Debug)]
+pub struct Golden {
+    path: PathBuf,
+    size: [u32; 2],
+    scale_factor: f32,
+    frames: u64,
+    fps: f64,
+    seed: u64,
+    tolerance: Tolerance,
+    renderer: draw::RendererBuilder,
+}
+
+/// The state of the sketch for the frame being rendered.
+#[derive(Clone, Debug)]
+pub struct Context {
+    /// The index of the frame, starting from `0`.
+    pub nth: u64,
+    /// The time since the first frame in seconds, advanced by a fixed step each frame.
+    pub time: f32,
+    /// The rect of the rendered window in points, centered on the origin.
+    pub window_rect: Rect,
+    /// A random number generator seeded via **Golden::seed**.
+    ///
+    /// Sketches should use this rather than the `random_*` functions, which rely on a
+    /// non-deterministic thread-local generator.
+    pub rng: StdRng,
+}
+
+/// Errors that might occur during a golden-image test.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to read or write an image.
+    Io(io::Error),
+    /// Failed to decode or encode an image.
+    Image(image::ImageError),
+    /// The reference image does not exist and `NANNOU_UPDATE_GOLDEN` is not set.
+    MissingReference(PathBuf),
+    /// The rendered frame differs from the reference beyond the tolerance.
+    Mismatch {
+        reference: PathBuf,
+        actual: PathBuf,
+        diff: PathBuf,
+        comparison: Box<Comparison>,
+    },
+}
+
+impl Golden {
+    /// The default size of the rendered frame in pixels.
+    pub const DEFAULT_SIZE: [u32; 2] = [256, 256];
+    /// The default number of pixels per point.
+    pub const DEFAULT_SCALE_FACTOR: f32 = 1.0;
+    /// The default number of frames rendered before comparison.
+    pub const DEFAULT_FRAMES: u64 = 1;
+    /// The default rate at which time advances.
+    pub const DEFAULT_FPS: f64 = 60.0;
+    /// The default seed of the random number generator.
+    pub const DEFAULT_SEED: u64 = 0;
+
+    /// Begin describing a golden-image test whose reference image is stored at the given path.
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Golden {
+            path: path.as_ref().to_path_buf(),
+            size: Self::DEFAULT_SIZE,
+            scale_factor: Self::DEFAULT_SCALE_FACTOR,
+            frames: Self::DEFAULT_FRAMES,
+            fps: Self::DEFAULT_FPS,
+            seed: Self::DEFAULT_SEED,
+            tolerance: Tolerance::default(),
+            renderer: draw::RendererBuilder::new(),
+        }
+    }
+
+    /// The size of the rendered frame in pixels.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = [width, height];
+        self
+    }
+
+    /// The number of pixels per point.
+    pub fn scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// The number of frames to render, the last of which is compared with the reference.
+    ///
+    /// Each frame is drawn over the previous, as is the case for a window's frames.
+    pub fn frames(mut self, frames: u64) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// The number of frames per second, determining the time step between frames.
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// The seed of the random number generator provided via the **Context**.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The tolerance within which the rendered frame must match the reference.
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The settings of the renderer, e.g. the text mode.
+    pub fn renderer(mut self, renderer: draw::RendererBuilder) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Render the frames of the given view, returning the last.
+    pub fn render<F>(&self, mut view: F) -> image::RgbaImage
+    where
+        F: FnMut(&mut Context, &Draw),
+    {
+        let [w, h] = self.size;
+        let mut renderer = self.renderer.clone().build_cpu();
+        let mut image = image::RgbaImage::new(w, h);
+        let s = self.scale_factor;
+        let window_rect = Rect::from_w_h(w as f32 / s, h as f32 / s);
+        let mut ctxt = Context {
+            nth: 0,
+            time: 0.0,
+            window_rect,
+            rng: StdRng::seed_from_u64(self.seed),
+        };
+        for nth in 0..self.frames {
+            ctxt.nth = nth;
+            ctxt.time = (nth as f64 / self.fps) as f32;
+            let draw = Draw::new();
+            view(&mut ctxt, &draw);
+            renderer.render_to_image(&draw, self.scale_factor, &mut image);
+        }
+        image
+    }
+
+    /// Render the given view and compare the last frame with the reference image.
+    ///
+    /// If `NANNOU_UPDATE_GOLDEN` is set, the frame is written as the reference. Otherwise, a
+    /// missing reference results in an error. If the frame does not match, it is written
+    /// alongside the reference with the `.actual.png` extension, along with an image highlighting
+    /// the differences with the `.diff.png` extension.
+    pub fn check<F>(&self, view: F) -> Result<(), Error>
+    where
+        F: FnMut(&mut Context, &Draw),
+    {
+        let actual = self.render(view);
+        let reference = &self.path;
+        let update = env::var_os(UPDATE_ENV_VAR).is_some();
+        if update {
+            if let Some(dir) = reference.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            actual.save(reference)?;
+            return Ok(());
+        }
+        if !reference.exists() {
+            return Err(Error::MissingReference(reference.clone()));
+        }
+
+        let expected = image::open(reference)?.to_rgba8();
+        let actual_path = reference.with_extension("actual.png");
+        let diff_path = reference.with_extension("diff.png");
+        let comparison = compare(&actual, &expected, &self.tolerance);
+        if comparison.is_match() {
+            // Remove the output of any previous failure.
+            for path in &[&actual_path, &diff_path] {
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+            }
+            return Ok(());
+        }
+        actual.save(&actual_path)?;
+        comparison.diff_image(&expected).save(&diff_path)?;
+        Err(Error::Mismatch {
+            reference: reference.clone(),
+            actual: actual_path,
+            diff: diff_path,
+            comparison: Box::new(comparison),
+        })
+    }
+
+    /// Render the given view and assert that the last frame matches the reference image.
+    ///
+    /// See **check** for details.
+    ///
+    /// **Panics** if the frame does not match or an image could not be read or written.
+    pub fn assert<F>(&self, view: F)
+    where
+        F: FnMut(&mut Context, &Draw),
+    {
+        if let Err(err) = self.check(view) {
+            panic!("golden image test failed: {}", err);
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(err: image::ImageError) -> Self {
+        Error::Image(err)
+    }
+}
+
+impl StdError for Error {
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Image(ref err) => Some(err),
+            Error::MissingReference(_) | Error::Mismatch { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => fmt::Display::fmt(err, f),
+            Error::Image(ref err) => fmt::Display::fmt(err, f),
+            Error::MissingReference(ref reference) => write!(
+                f,
+                "the reference `{}` does not exist. Set `{}` to write the rendered frame as the \
+                 reference",
+                reference.display(),
+                UPDATE_ENV_VAR,
+            ),
+            Error::Mismatch {
+                ref reference,
+                ref actual,
+                ref diff,
+                ref comparison,
+            } => write!(
+                f,
+                "the rendered frame does not match `{}`: {}. The frame was written to `{}` and \
+                 the differences to `{}`",
+                reference.display(),
+                comparison,
+                actual.display(),
+                diff.display(),
+            ),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 //! Logic related to the positioning of the cursor within text.
 
 use crate::geom::{Range, Rect};
-use crate::text::{self, FontSize, Point, Scalar};
+use crate::text::{self, shape, FontSize, Point, Scalar};
 
 /// An index representing the position of a cursor within some text.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -55,6 +55,25 @@ pub struct Xs<'a, 'b> {
     layout: text::LayoutIter<'a, 'b>,
 }
 
+/// Every possible cursor position within each shaped line of text yielded by the given iterator.
+///
+/// Yields `(xs, y_range)`, where `y_range` is the `Range` occupied by the line across the *y*
+/// axis and `xs` is every possible cursor position along the *x* axis.
+#[derive(Clone)]
+pub struct ShapedXysPerLine<I, J> {
+    shaped_lines_with_rects: I,
+    line_infos: J,
+}
+
+/// Each possible cursor position along the *x* axis within a shaped line of text.
+///
+/// Positions are yielded in logical order, i.e. the `n`th position is the cursor before the `n`th
+/// character of the line. As such, positions decrease within right-to-left runs.
+#[derive(Clone)]
+pub struct ShapedXs {
+    xs: std::vec::IntoIter<Scalar>,
+}
+
 impl Index {
     /// The cursor index of the beginning of the word (block of non-whitespace) before `self`.
     ///
@@ -318,9 +337,10 @@ where
 }
 
 /// Determine the *xy* location of the cursor at the given cursor `Index`.
-pub fn xy_at<'a, I>(xys_per_line: I, idx: Index) -> Option<(Scalar, Range)>
+pub fn xy_at<I, X>(xys_per_line: I, idx: Index) -> Option<(Scalar, Range)>
 where
-    I: Iterator<Item = (Xs<'a, 'a>, Range)>,
+    I: Iterator<Item = (X, Range)>,
+    X: Iterator<Item = Scalar>,
 {
     for (i, (xs, y)) in xys_per_line.enumerate() {
         if i == idx.line {
@@ -337,9 +357,9 @@ where
 /// Find the closest line for the given `y` position, and return the line index, Xs iterator, and y-range of that line
 ///
 /// Returns `None` if there are no lines
-pub fn closest_line<'a, I>(y_pos: Scalar, xys_per_line: I) -> Option<(usize, Xs<'a, 'a>, Range)>
+pub fn closest_line<I, X>(y_pos: Scalar, xys_per_line: I) -> Option<(usize, X, Range)>
 where
-    I: Iterator<Item = (Xs<'a, 'a>, Range)>,
+    I: Iterator<Item = (X, Range)>,
 {
     let mut xys_per_line_enumerated = xys_per_line.enumerate();
     xys_per_line_enumerated
@@ -369,9 +389,10 @@ where
 /// cursor.
 ///
 /// Returns `None` if the given `text` is empty.
-pub fn closest_cursor_index_and_xy<'a, I>(xy: Point, xys_per_line: I) -> Option<(Index, Point)>
+pub fn closest_cursor_index_and_xy<I, X>(xy: Point, xys_per_line: I) -> Option<(Index, Point)>
 where
-    I: Iterator<Item = (Xs<'a, 'a>, Range)>,
+    I: Iterator<Item = (X, Range)>,
+    X: Iterator<Item = Scalar>,
 {
     closest_line(xy[1], xys_per_line).and_then(
         |(closest_line_idx, closest_line_xs, closest_line_y)| {
@@ -389,7 +410,10 @@ where
 
 /// Find the closest cursor index to the given `x` position on the given line along with the
 /// `x` position of that cursor.
-pub fn closest_cursor_index_on_line<'a>(x_pos: Scalar, line_xs: Xs<'a, 'a>) -> (usize, Scalar) {
+pub fn closest_cursor_index_on_line<X>(x_pos: Scalar, line_xs: X) -> (usize, Scalar)
+where
+    X: Iterator<Item = Scalar>,
+{
     let mut xs_enumerated = line_xs.enumerate();
     // `xs` always yields at least one `x` (the start of the line).
     let (first_idx, first_x) = xs_enumerated.next().unwrap();
@@ -408,6 +432,105 @@ pub fn closest_cursor_index_on_line<'a>(x_pos: Scalar, line_xs: Xs<'a, 'a>) -> (
     closest
 }
 
+/// Find the closest cursor index to the given `x` position on the given shaped line along with
+/// the `x` position of that cursor.
+///
+/// Unlike `closest_cursor_index_on_line`, every position is considered, as the positions of a
+/// line containing right-to-left text do not increase monotonically.
+pub fn closest_shaped_cursor_index_on_line(x_pos: Scalar, line_xs: ShapedXs) -> (usize, Scalar) {
+    line_xs
+        .enumerate()
+        .fold(
+            None,
+            |closest: Option<(usize, Scalar)>, (i, x)| match closest {
+                Some((_, closest_x)) if (x_pos - closest_x).abs() <= (x_pos - x).abs() => closest,
+                _ => Some((i, x)),
+            },
+        )
+        // `xs` always yields at least one `x` (the start of the line).
+        .unwrap()
+}
+
+/// Find the closest cursor index to the given `xy` position within the given shaped lines, and
+/// the center `Point` of that cursor.
+///
+/// Returns `None` if there are no lines.
+pub fn closest_shaped_cursor_index_and_xy<I>(xy: Point, xys_per_line: I) -> Option<(Index, Point)>
+where
+    I: Iterator<Item = (ShapedXs, Range)>,
+{
+    closest_line(xy[1], xys_per_line).map(|(closest_line_idx, closest_line_xs, closest_line_y)| {
+        let (closest_char_idx, closest_x) =
+            closest_shaped_cursor_index_on_line(xy[0], closest_line_xs);
+        let index = Index {
+            line: closest_line_idx,
+            char: closest_char_idx,
+        };
+        let point = [closest_x, closest_line_y.middle()].into();
+        (index, point)
+    })
+}
+
+/// Every possible cursor position within each shaped line of text yielded by the given iterators.
+///
+/// `line_infos` must yield the info for each line yielded by `shaped_lines_with_rects`, as
+/// produced by `Text::line_infos` and `Text::shaped_lines_with_rects` respectively.
+///
+/// Yields `(xs, y_range)`, where `y_range` is the `Range` occupied by the line across the *y*
+/// axis and `xs` is every possible cursor position along the *x* axis.
+pub fn shaped_xys_per_line<'a, I, J>(
+    shaped_lines_with_rects: I,
+    line_infos: J,
+) -> ShapedXysPerLine<I, J>
+where
+    I: Iterator<Item = (&'a shape::Line, Rect)>,
+    J: Iterator<Item = text::line::Info>,
+{
+    ShapedXysPerLine {
+        shaped_lines_with_rects,
+        line_infos,
+    }
+}
+
+/// Every possible cursor position along the *x* axis within the given shaped line containing
+/// `num_chars` characters, where `x` is the start of the line.
+///
+/// The cursor before each character lies on the leading edge of its glyph, i.e. the left edge
+/// within left-to-right runs and the right edge within right-to-left runs. Glyphs that represent
+/// many characters (e.g. ligatures) are divided evenly between them. The final position lies on
+/// the trailing edge of the last character.
+pub fn shaped_xs(line: &shape::Line, num_chars: usize, x: Scalar) -> ShapedXs {
+    // Each cluster of glyphs representing the same characters as `(char, rtl, left, right)`.
+    let mut clusters: Vec<(usize, bool, Scalar, Scalar)> = vec![];
+    for g in &line.glyphs {
+        let left = x + g.position.x;
+        match clusters.last_mut() {
+            Some(cluster) if cluster.0 == g.char => cluster.3 = cluster.3.max(left + g.advance),
+            _ => clusters.push((g.char, g.rtl, left, left + g.advance)),
+        }
+    }
+    clusters.sort_by_key(|cluster| cluster.0);
+
+    let mut xs = vec![x; num_chars + 1];
+    for (i, &(start, rtl, left, right)) in clusters.iter().enumerate() {
+        let end = clusters
+            .get(i + 1)
+            .map(|next| next.0)
+            .unwrap_or(num_chars)
+            .min(num_chars);
+        let n = end.saturating_sub(start);
+        for (j, cursor_x) in xs[start.min(num_chars)..end].iter_mut().enumerate() {
+            let offset = (right - left) * j as Scalar / n as Scalar;
+            *cursor_x = if rtl { right - offset } else { left + offset };
+        }
+        if end == num_chars {
+            xs[num_chars] = if rtl { left } else { right };
+        }
+    }
+
+    ShapedXs { xs: xs.into_iter() }
+}
+
 impl<'a, I> Iterator for XysPerLine<'a, I>
 where
     I: Iterator<Item = (text::line::Info, Rect)>,
@@ -438,6 +561,28 @@ where
     }
 }
 
+impl<'a, I, J> Iterator for ShapedXysPerLine<I, J>
+where
+    I: Iterator<Item = (&'a shape::Line, Rect)>,
+    J: Iterator<Item = text::line::Info>,
+{
+    type Item = (ShapedXs, Range);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, line_rect) = self.shaped_lines_with_rects.next()?;
+        let info = self.line_infos.next()?;
+        let num_chars = info.end_char() - info.start_char;
+        let xs = shaped_xs(line, num_chars, line_rect.left());
+        Some((xs, line_rect.y))
+    }
+}
+
+impl Iterator for ShapedXs {
+    type Item = Scalar;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.xs.next()
+    }
+}
+
 impl<'a> Iterator for XysPerLineFromText<'a> {
     type Item = (Xs<'a, 'a>, Range);
     fn next(&mut self) -> Option<Self::Item> {
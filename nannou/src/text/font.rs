@@ -1,9 +1,14 @@
-//! The `font::Id` and `font::Map` types.
+//! The `font::Id` and `font::Map` types, along with the variation axes and OpenType features
+//! that may be applied to fonts when laying out text.
 
-use crate::text::{Font, FontCollection};
+use crate::text::FontCollection;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A four-byte OpenType tag identifying a feature or variation axis, e.g. `wght` or `smcp`.
+pub use rustybuzz::ttf_parser::Tag;
 
 /// A type-safe wrapper around the `FontId`.
 ///
@@ -14,11 +19,72 @@ use std::path::{Path, PathBuf};
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Id(usize);
 
-/// A collection of mappings from `font::Id`s to `rusttype::Font`s.
+/// A font loaded from TrueType or OpenType font data.
+///
+/// Wraps the `rusttype::Font` used for glyph metrics and rasterisation, retaining the raw font
+/// data so that the font's OpenType layout tables are available when shaping text. The font data
+/// is parsed for shaping and outlining once when the font is loaded.
+#[derive(Clone)]
+pub struct Font {
+    // Borrows from `data`, so must be declared first in order to be dropped first.
+    face: Arc<rustybuzz::Face<'static>>,
+    font: rusttype::Font<'static>,
+    data: rusttype::SharedBytes<'static>,
+    index: u32,
+    axes: Arc<[Axis]>,
+}
+
+/// A variation axis of a variable font, e.g. weight (`wght`), width (`wdth`) or slant (`slnt`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Axis {
+    /// The tag identifying the axis.
+    pub tag: Tag,
+    /// The name of the axis as described by the font, if any.
+    pub name: Option<String>,
+    /// The minimum value of the axis.
+    pub min: f32,
+    /// The value of the axis for the font's default instance.
+    pub default: f32,
+    /// The maximum value of the axis.
+    pub max: f32,
+    /// Whether or not the font recommends hiding the axis from user interfaces.
+    pub hidden: bool,
+}
+
+/// A value for one of a variable font's axes.
+///
+/// Variations for axes that a font does not have are ignored, as are all variations for fonts
+/// that are not variable.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Variation {
+    /// The tag identifying the axis.
+    pub tag: Tag,
+    /// The value of the axis, clamped to the axis' range by the font.
+    pub value: f32,
+}
+
+/// An OpenType feature setting, e.g. `smcp` for small capitals or `tnum` for tabular figures.
+///
+/// A `value` of `0` disables the feature, while `1` enables it. Features with alternates, such as
+/// `salt`, use the value to select the alternate.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Feature {
+    /// The tag identifying the feature.
+    pub tag: Tag,
+    /// The value of the feature.
+    pub value: u32,
+}
+
+/// A collection of mappings from `font::Id`s to `Font`s.
+///
+/// The `Map` also describes the font fallback chain. When laying out text in one of the fonts
+/// within the map, characters that have no glyph within that font are looked up within each of
+/// the fonts in the chain in order.
 #[derive(Debug)]
 pub struct Map {
     next_index: usize,
     map: HashMap<Id, Font>,
+    fallbacks: Vec<Id>,
 }
 
 /// An iterator yielding an `Id` for each new `rusttype::Font` inserted into the `Map` via the
@@ -45,6 +111,94 @@ pub enum Error {
 /// The name of the default directory that is searched for fonts.
 pub const DEFAULT_DIRECTORY_NAME: &str = "fonts";
 
+impl Font {
+    /// Load a font from the given TrueType or OpenType font data.
+    ///
+    /// If the data describes a font collection, the first font within the collection is loaded.
+    pub fn from_bytes<B>(bytes: B) -> Result<Self, rusttype::Error>
+    where
+        B: Into<rusttype::SharedBytes<'static>>,
+    {
+        let data = bytes.into();
+        // `rusttype::Font::from_bytes` rejects collections containing more than one font.
+        let index = 0;
+        let font = FontCollection::from_bytes(data.clone())?.font_at(index as usize)?;
+        // SAFETY: The bytes are either `'static` or owned by the `Arc` within `data`, which is
+        // stored alongside the face and never mutated. Moving `data` does not move the bytes.
+        let bytes: &'static [u8] = unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
+        let face = rustybuzz::Face::from_slice(bytes, index).ok_or(rusttype::Error::IllFormed)?;
+        let axes = variation_axes(&face).into();
+        let face = Arc::new(face);
+        Ok(Font {
+            face,
+            font,
+            data,
+            index,
+            axes,
+        })
+    }
+
+    /// The raw font data from which the font was loaded.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The index of the font within its font data.
+    ///
+    /// This is always `0` unless the font was loaded from a font collection.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The face used to shape and outline glyphs, parsed from the font data.
+    pub(crate) fn face(&self) -> &rustybuzz::Face<'_> {
+        &self.face
+    }
+
+    /// Whether or not the font contains a glyph for the given character.
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.font.glyph(ch).id() != rusttype::GlyphId(0)
+    }
+
+    /// Whether or not the font is a variable font with at least one variation axis.
+    pub fn is_variable(&self) -> bool {
+        !self.axes.is_empty()
+    }
+
+    /// The variation axes of the font.
+    ///
+    /// This is empty unless the font is a variable font.
+    pub fn variation_axes(&self) -> &[Axis] {
+        &self.axes
+    }
+}
+
+impl Variation {
+    /// A value for the axis with the given tag, e.g. `Variation::new(b"wght", 700.0)`.
+    pub fn new(tag: &[u8; 4], value: f32) -> Self {
+        let tag = Tag::from_bytes(tag);
+        Variation { tag, value }
+    }
+}
+
+impl Feature {
+    /// A setting for the feature with the given tag, e.g. `Feature::new(b"smcp", 1)`.
+    pub fn new(tag: &[u8; 4], value: u32) -> Self {
+        let tag = Tag::from_bytes(tag);
+        Feature { tag, value }
+    }
+
+    /// Enable the feature with the given tag.
+    pub fn enable(tag: &[u8; 4]) -> Self {
+        Self::new(tag, 1)
+    }
+
+    /// Disable the feature with the given tag, e.g. `Feature::disable(b"liga")`.
+    pub fn disable(tag: &[u8; 4]) -> Self {
+        Self::new(tag, 0)
+    }
+}
+
 impl Id {
     /// Returns the inner `usize` from the `Id`.
     pub fn index(self) -> usize {
@@ -58,6 +212,7 @@ impl Map {
         Map {
             next_index: 0,
             map: HashMap::default(),
+            fallbacks: Vec::new(),
         }
     }
 
@@ -107,6 +262,48 @@ impl Map {
             keys: self.map.keys(),
         }
     }
+
+    /// Specify the font fallback chain.
+    ///
+    /// Characters that are missing from the font used to lay out some text are looked up within
+    /// each font in the given order.
+    pub fn set_fallbacks<I>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = Id>,
+    {
+        self.fallbacks = ids.into_iter().collect();
+    }
+
+    /// Append a font to the end of the font fallback chain.
+    pub fn push_fallback(&mut self, id: Id) {
+        self.fallbacks.push(id);
+    }
+
+    /// The explicitly specified font fallback chain.
+    pub fn fallbacks(&self) -> &[Id] {
+        &self.fallbacks
+    }
+
+    /// The fonts to fall back to when laying out text with the font with the given `Id`.
+    ///
+    /// If a fallback chain has been specified, this is each font in the chain excluding the
+    /// `primary` font. Otherwise, this is every other font within the map in the order in which
+    /// they were inserted.
+    ///
+    /// The result can be passed to `text::layout::Builder::fallback_fonts`.
+    pub fn fallback_chain(&self, primary: Id) -> Vec<Font> {
+        let mut ids: Vec<Id> = if self.fallbacks.is_empty() {
+            let mut ids: Vec<Id> = self.ids().collect();
+            ids.sort();
+            ids
+        } else {
+            self.fallbacks.clone()
+        };
+        ids.retain(|&id| id != primary);
+        ids.into_iter()
+            .filter_map(|id| self.get(id).cloned())
+            .collect()
+    }
 }
 
 /// Produce a unique ID for the given font.
@@ -118,6 +315,28 @@ pub fn id(font: &Font) -> Id {
     Id((hasher.finish() % std::usize::MAX as u64) as usize)
 }
 
+// Read the variation axes of the given font face.
+fn variation_axes(face: &rustybuzz::ttf_parser::Face) -> Vec<Axis> {
+    face.variation_axes()
+        .into_iter()
+        .map(|axis| {
+            let name = face
+                .names()
+                .into_iter()
+                .filter(|name| name.name_id == axis.name_id && name.is_unicode())
+                .find_map(|name| name.to_string());
+            Axis {
+                tag: axis.tag,
+                name,
+                min: axis.min_value,
+                default: axis.def_value,
+                max: axis.max_value,
+                hidden: axis.hidden,
+            }
+        })
+        .collect()
+}
+
 /// Load a `FontCollection` from a file at a given path.
 pub fn collection_from_file<P>(path: P) -> Result<FontCollection, std::io::Error>
 where
@@ -136,8 +355,8 @@ pub fn from_file<P>(path: P) -> Result<Font, Error>
 where
     P: AsRef<std::path::Path>,
 {
-    let collection = collection_from_file(path)?;
-    collection.into_font().or(Err(Error::NoFont))
+    let bytes = std::fs::read(path)?;
+    Font::from_bytes(bytes).or(Err(Error::NoFont))
 }
 
 /// Load the default notosans font.
@@ -145,11 +364,8 @@ where
 /// This function is only available if the `notosans` feature is enabled, which it is by default.
 #[cfg(feature = "notosans")]
 pub fn default_notosans() -> Font {
-    let collection = FontCollection::from_bytes(notosans::REGULAR_TTF)
-        .expect("failed to load the `notosans::REGULAR_TTF` font collection");
-    collection
-        .into_font()
-        .expect("the `notosans::REGULAR_TTF` font collection contained no fonts")
+    Font::from_bytes(notosans::REGULAR_TTF)
+        .expect("failed to load the `notosans::REGULAR_TTF` font")
 }
 
 /// The directory that is searched for default fonts.
@@ -203,6 +419,38 @@ impl<'a> Iterator for Ids<'a> {
     }
 }
 
+impl std::ops::Deref for Font {
+    type Target = rusttype::Font<'static>;
+    fn deref(&self) -> &Self::Target {
+        &self.font
+    }
+}
+
+impl std::fmt::Debug for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Font")
+            .field("font", &self.font)
+            .field("index", &self.index)
+            .field("axes", &self.axes)
+            .finish()
+    }
+}
+
+impl From<Variation> for rustybuzz::Variation {
+    fn from(v: Variation) -> Self {
+        rustybuzz::Variation {
+            tag: v.tag,
+            value: v.value,
+        }
+    }
+}
+
+impl From<Feature> for rustybuzz::Feature {
+    fn from(f: Feature) -> Self {
+        rustybuzz::Feature::new(f.tag, f.value, ..)
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::Io(e)
@@ -1,7 +1,9 @@
 //! Logic and types specific to individual glyph layout.
 
 use crate::geom::{Range, Rect};
-use crate::text::{self, FontSize, Scalar, ScaledGlyph};
+use crate::text::font::Variation;
+use crate::text::{self, shape, FontSize, GlyphId, Scalar, Scale, ScaledGlyph};
+use std::borrow::Cow;
 
 /// Some position along the X axis (used within `CharXs`).
 pub type X = Scalar;
@@ -27,6 +29,21 @@ pub struct RectsPerLine<'a, I> {
     font_size: FontSize,
 }
 
+/// An iterator yielding each glyph and its `Rect` within a single shaped line of text.
+pub struct ShapedRects<'a> {
+    /// The bottom left of the line, at which the line's baseline begins.
+    origin: text::Point,
+    glyphs: std::slice::Iter<'a, shape::Glyph>,
+    fonts: &'a [text::Font],
+}
+
+/// An iterator that, for every `(shaped_line, line_rect)` pair yielded by the given iterator,
+/// produces an iterator that yields every glyph alongside its `Rect` in that line.
+pub struct ShapedRectsPerLine<'a, I> {
+    shaped_lines_with_rects: I,
+    fonts: &'a [text::Font],
+}
+
 /// Yields a `Rect` for each selected character in a single line of text.
 ///
 /// This iterator can only be produced by the `SelectedCharRectsPerLine` iterator.
@@ -48,6 +65,34 @@ pub struct SelectedRectsPerLine<'a, I> {
     end_cursor_idx: text::cursor::Index,
 }
 
+/// Yields each selected glyph alongside its `Rect` in a single shaped line of text.
+///
+/// This iterator can only be produced by the `ShapedSelectedRectsPerLine` iterator.
+pub struct ShapedSelectedRects<'a> {
+    rects: ShapedRects<'a>,
+    chars: std::ops::Range<usize>,
+}
+
+/// Yields an iterator yielding each selected glyph alongside its `Rect` for each line of shaped
+/// text within the given iterator.
+///
+/// Given some `start` and `end` indices, only glyphs representing `char`s between these two
+/// indices will be produced.
+pub struct ShapedSelectedRectsPerLine<'a, I> {
+    enumerated_rects_per_line: std::iter::Enumerate<ShapedRectsPerLine<'a, I>>,
+    start_cursor_idx: text::cursor::Index,
+    end_cursor_idx: text::cursor::Index,
+}
+
+// Collects the path events of a glyph outline read via `ttf-parser`, scaling from font units.
+struct OutlinePathEvents {
+    events: Vec<lyon::path::PathEvent>,
+    scale: lyon::math::Vector,
+    first: lyon::math::Point,
+    last: lyon::math::Point,
+    open: bool,
+}
+
 struct ContourPathEvents {
     segments: std::vec::IntoIter<rusttype::Segment>,
     first: lyon::math::Point,
@@ -103,6 +148,96 @@ where
     }
 }
 
+impl<'a> Iterator for ShapedRects<'a> {
+    type Item = (ScaledGlyph<'a>, Rect);
+    fn next(&mut self) -> Option<Self::Item> {
+        let ShapedRects {
+            origin,
+            ref mut glyphs,
+            fonts,
+        } = *self;
+        glyphs.next().map(|g| {
+            let g_origin = origin + g.position;
+            let scale = text::pt_to_scale(g.font_size);
+            let g = fonts[g.font].glyph(g.id).scaled(scale);
+            let (right, height) = g
+                .exact_bounding_box()
+                .map(|bb| (g_origin.x + bb.max.x, bb.max.y - bb.min.y))
+                .unwrap_or_else(|| (g_origin.x + g.h_metrics().advance_width, 0.0));
+            let x = Range::new(g_origin.x, right);
+            let y = Range::new(g_origin.y, g_origin.y + height);
+            (g, Rect { x, y })
+        })
+    }
+}
+
+impl<'a, I> Iterator for ShapedRectsPerLine<'a, I>
+where
+    I: Iterator<Item = (&'a shape::Line, Rect)>,
+{
+    type Item = ShapedRects<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let ShapedRectsPerLine {
+            ref mut shaped_lines_with_rects,
+            fonts,
+        } = *self;
+        shaped_lines_with_rects
+            .next()
+            .map(|(line, line_rect)| ShapedRects {
+                origin: line_rect.bottom_left(),
+                glyphs: line.glyphs.iter(),
+                fonts,
+            })
+    }
+}
+
+impl<'a> Iterator for ShapedSelectedRects<'a> {
+    type Item = (ScaledGlyph<'a>, Rect);
+    fn next(&mut self) -> Option<Self::Item> {
+        // Glyphs are in visual order, so the selected glyphs of a line containing right-to-left
+        // text may not be contiguous.
+        loop {
+            let char = self.rects.glyphs.as_slice().first()?.char;
+            let next = self.rects.next();
+            if self.chars.contains(&char) {
+                return next;
+            }
+        }
+    }
+}
+
+impl<'a, I> Iterator for ShapedSelectedRectsPerLine<'a, I>
+where
+    I: Iterator<Item = (&'a shape::Line, Rect)>,
+{
+    type Item = ShapedSelectedRects<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let ShapedSelectedRectsPerLine {
+            ref mut enumerated_rects_per_line,
+            start_cursor_idx,
+            end_cursor_idx,
+        } = *self;
+        enumerated_rects_per_line.next().map(|(i, rects)| {
+            let start = if i == start_cursor_idx.line {
+                start_cursor_idx.char
+            } else {
+                0
+            };
+            let end = if i == end_cursor_idx.line {
+                end_cursor_idx.char
+            } else if start_cursor_idx.line <= i && i < end_cursor_idx.line {
+                usize::MAX
+            } else {
+                0
+            };
+            ShapedSelectedRects {
+                rects,
+                chars: start..end,
+            }
+        })
+    }
+}
+
 impl<'a, 'b> Iterator for SelectedRects<'a, 'b> {
     type Item = (ScaledGlyph<'a>, Rect);
     fn next(&mut self) -> Option<Self::Item> {
@@ -207,6 +342,28 @@ where
     }
 }
 
+/// Produce an iterator that, for every `(shaped_line, line_rect)` pair yielded by the given
+/// iterator, produces an iterator that yields every glyph alongside its `Rect` in that line.
+///
+/// Unlike `rects_per_line`, a glyph may represent many characters (e.g. a ligature) and glyphs
+/// are yielded in visual order. The left edge of each `Rect` is the glyph's origin and the
+/// bottom edge is the glyph's baseline.
+///
+/// The `fonts` must be those with which the lines were shaped. Each glyph is scaled by its own
+/// font size.
+pub fn shaped_rects_per_line<'a, I>(
+    shaped_lines_with_rects: I,
+    fonts: &'a [text::Font],
+) -> ShapedRectsPerLine<'a, I>
+where
+    I: Iterator<Item = (&'a shape::Line, Rect)>,
+{
+    ShapedRectsPerLine {
+        shaped_lines_with_rects,
+        fonts,
+    }
+}
+
 /// Find the index of the character that directly follows the cursor at the given `cursor_idx`.
 ///
 /// Returns `None` if either the given `cursor::Index` `line` or `idx` fields are out of bounds
@@ -251,6 +408,31 @@ where
     }
 }
 
+/// Produces an iterator that yields iterators yielding each selected glyph alongside its `Rect`
+/// for each line of shaped text within the given iterator.
+///
+/// Given some `start` and `end` indices, only glyphs representing `char`s between these two
+/// indices will be produced. As glyphs are yielded in visual order, the selected glyphs of a line
+/// containing right-to-left text may not be adjacent.
+///
+/// The `fonts` must be those with which the lines were shaped.
+pub fn shaped_selected_rects_per_line<'a, I>(
+    shaped_lines_with_rects: I,
+    fonts: &'a [text::Font],
+    start: text::cursor::Index,
+    end: text::cursor::Index,
+) -> ShapedSelectedRectsPerLine<'a, I>
+where
+    I: Iterator<Item = (&'a shape::Line, Rect)>,
+{
+    let rects_per_line = shaped_rects_per_line(shaped_lines_with_rects, fonts);
+    ShapedSelectedRectsPerLine {
+        enumerated_rects_per_line: rects_per_line.enumerate(),
+        start_cursor_idx: start,
+        end_cursor_idx: end,
+    }
+}
+
 fn rt_segment_start(s: &rusttype::Segment) -> rusttype::Point<f32> {
     match *s {
         rusttype::Segment::Line(ref line) => line.p[0],
@@ -342,3 +524,97 @@ pub fn path_events(glyph: ScaledGlyph) -> Option<impl Iterator<Item = lyon::path
         .and_then(|bb| glyph.shape().map(|ctrs| (bb, ctrs)))
         .map(|(bb, ctrs)| contours_to_path(bb, ctrs))
 }
+
+/// Produce the lyon path for the given glyph with the given font variations applied.
+///
+/// Unlike `path_events`, which outlines glyphs via rusttype and so always produces the default
+/// instance of a variable font, the outline is read at the given variation coordinates. The path
+/// is relative to the glyph's origin with *y* up, matching `path_events`.
+///
+/// Returns `None` if the glyph has no outline.
+pub fn path_events_with_variations(
+    font: &text::Font,
+    id: GlyphId,
+    scale: Scale,
+    variations: &[Variation],
+) -> Option<Vec<lyon::path::PathEvent>> {
+    // Only copy the font's face when the variations must be applied to it.
+    let mut face = Cow::Borrowed(&**font.face());
+    if font.is_variable() && !variations.is_empty() {
+        let face = face.to_mut();
+        for v in variations {
+            face.set_variation(v.tag, v.value);
+        }
+    }
+    // Convert from font units to pixels in the same manner as rusttype.
+    let v_metrics = font.v_metrics_unscaled();
+    let height = v_metrics.ascent - v_metrics.descent;
+    let mut outline = OutlinePathEvents {
+        events: vec![],
+        scale: lyon::math::vector(scale.x / height, scale.y / height),
+        first: lyon::math::point(0.0, 0.0),
+        last: lyon::math::point(0.0, 0.0),
+        open: false,
+    };
+    let id = rustybuzz::ttf_parser::GlyphId(id.0 as u16);
+    face.outline_glyph(id, &mut outline)?;
+    outline.end(false);
+    Some(outline.events)
+}
+
+impl OutlinePathEvents {
+    fn point(&self, x: f32, y: f32) -> lyon::math::Point {
+        lyon::math::point(x * self.scale.x, y * self.scale.y)
+    }
+
+    fn end(&mut self, close: bool) {
+        if self.open {
+            self.events.push(lyon::path::PathEvent::End {
+                last: self.last,
+                first: self.first,
+                close,
+            });
+            self.open = false;
+        }
+    }
+}
+
+impl rustybuzz::ttf_parser::OutlineBuilder for OutlinePathEvents {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.end(false);
+        let at = self.point(x, y);
+        self.events.push(lyon::path::PathEvent::Begin { at });
+        self.first = at;
+        self.last = at;
+        self.open = true;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (from, to) = (self.last, self.point(x, y));
+        self.events.push(lyon::path::PathEvent::Line { from, to });
+        self.last = to;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (from, ctrl, to) = (self.last, self.point(x1, y1), self.point(x, y));
+        self.events
+            .push(lyon::path::PathEvent::Quadratic { from, ctrl, to });
+        self.last = to;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let from = self.last;
+        let (ctrl1, ctrl2, to) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.events.push(lyon::path::PathEvent::Cubic {
+            from,
+            ctrl1,
+            ctrl2,
+            to,
+        });
+        self.last = to;
+    }
+
+    fn close(&mut self) {
+        self.end(true);
+    }
+}
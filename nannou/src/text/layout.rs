@@ -1,5 +1,6 @@
 //! Items related to the styling of text.
 
+use crate::text::font::{Feature, Variation};
 use crate::text::{Align, Font, FontSize, Justify, Scalar, Wrap};
 
 /// A context for building a text layout.
@@ -10,6 +11,9 @@ pub struct Builder {
     pub font_size: Option<FontSize>,
     pub justify: Option<Justify>,
     pub font: Option<Option<Font>>,
+    pub fallback_fonts: Option<Vec<Font>>,
+    pub features: Option<Vec<Feature>>,
+    pub variations: Option<Vec<Variation>>,
     pub y_align: Option<Align>,
 }
 
@@ -21,6 +25,12 @@ pub struct Layout {
     pub justify: Justify,
     pub font_size: FontSize,
     pub font: Option<Font>,
+    /// Fonts used to display characters that are missing from the primary `font`, in order.
+    pub fallback_fonts: Vec<Font>,
+    /// OpenType features applied to the whole text when shaping.
+    pub features: Vec<Feature>,
+    /// Values for the variation axes of variable fonts, applied to every font.
+    pub variations: Vec<Variation>,
     pub y_align: Align,
 }
 
@@ -72,6 +82,64 @@ impl Builder {
         self
     }
 
+    /// Specify the font fallback chain.
+    ///
+    /// Characters that have no glyph within the primary font are displayed with the first of
+    /// these fonts that contains a glyph for the character.
+    ///
+    /// A chain may be produced from a `text::font::Map` via `Map::fallback_chain`.
+    pub fn fallback_fonts<I>(mut self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.fallback_fonts = Some(fonts.into_iter().collect());
+        self
+    }
+
+    /// Append a font to the end of the font fallback chain.
+    pub fn fallback_font(mut self, font: Font) -> Self {
+        self.fallback_fonts.get_or_insert_with(Vec::new).push(font);
+        self
+    }
+
+    /// Specify the OpenType features applied to the text.
+    ///
+    /// Where the same feature is specified more than once, the last setting takes precedence.
+    pub fn features<I>(mut self, features: I) -> Self
+    where
+        I: IntoIterator<Item = Feature>,
+    {
+        self.features = Some(features.into_iter().collect());
+        self
+    }
+
+    /// Set the OpenType feature with the given tag, e.g. `feature(b"smcp", 1)`.
+    ///
+    /// A value of `0` disables the feature, while `1` enables it.
+    pub fn feature(mut self, tag: &[u8; 4], value: u32) -> Self {
+        let feature = Feature::new(tag, value);
+        self.features.get_or_insert_with(Vec::new).push(feature);
+        self
+    }
+
+    /// Specify the values of the variation axes applied to variable fonts.
+    pub fn variations<I>(mut self, variations: I) -> Self
+    where
+        I: IntoIterator<Item = Variation>,
+    {
+        self.variations = Some(variations.into_iter().collect());
+        self
+    }
+
+    /// Set the value of the variation axis with the given tag, e.g. `variation(b"wght", 700.0)`.
+    ///
+    /// This only affects variable fonts that have the axis.
+    pub fn variation(mut self, tag: &[u8; 4], value: f32) -> Self {
+        let variation = Variation::new(tag, value);
+        self.variations.get_or_insert_with(Vec::new).push(variation);
+        self
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(mut self, justify: Justify) -> Self {
         self.justify = Some(justify);
@@ -125,6 +193,9 @@ impl Builder {
     /// Set all the parameters via an existing `Layout`
     pub fn layout(mut self, layout: &Layout) -> Self {
         self.font = Some(layout.font.clone());
+        self.fallback_fonts = Some(layout.fallback_fonts.clone());
+        self.features = Some(layout.features.clone());
+        self.variations = Some(layout.variations.clone());
         self.line_spacing(layout.line_spacing)
             .line_wrap(layout.line_wrap)
             .justify(layout.justify)
@@ -140,6 +211,9 @@ impl Builder {
             justify: self.justify.unwrap_or(DEFAULT_JUSTIFY),
             font_size: self.font_size.unwrap_or(DEFAULT_FONT_SIZE),
             font: self.font.unwrap_or(None),
+            fallback_fonts: self.fallback_fonts.unwrap_or_default(),
+            features: self.features.unwrap_or_default(),
+            variations: self.variations.unwrap_or_default(),
             y_align: self.y_align.unwrap_or(DEFAULT_Y_ALIGN),
         }
     }
@@ -153,6 +227,9 @@ impl Default for Layout {
             justify: DEFAULT_JUSTIFY,
             font_size: DEFAULT_FONT_SIZE,
             font: None,
+            fallback_fonts: Vec::new(),
+            features: Vec::new(),
+            variations: Vec::new(),
             y_align: DEFAULT_Y_ALIGN,
         }
     }
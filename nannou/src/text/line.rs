@@ -3,6 +3,7 @@
 //! This module is the core of multi-line text handling.
 
 use crate::geom::{Range, Rect};
+use crate::text::span::Attrs;
 use crate::text::{self, FontSize, Scalar, Wrap};
 
 /// The two types of **Break** indices returned by the **WrapIndicesBy** iterators.
@@ -98,11 +99,16 @@ pub struct Infos<'a, F> {
 /// An iterator yielding a `Rect` for each line in
 #[derive(Clone)]
 pub struct Rects<I> {
+    rects: RectsWithHeights<std::iter::Zip<I, std::iter::Repeat<Scalar>>>,
+}
+
+/// An iterator yielding a `Rect` for each line in some text whose lines may differ in height.
+#[derive(Clone)]
+pub struct RectsWithHeights<I> {
     infos: I,
     x_align: text::Justify,
     line_spacing: Scalar,
-    last_line_top: Scalar,
-    font_size: FontSize,
+    last_line_bottom: Scalar,
     next: Option<Rect>,
 }
 
@@ -231,11 +237,63 @@ fn advance_width_and_height(
 /// along with the width of the line.
 fn next_break(text: &str, font: &text::Font, font_size: FontSize) -> NextBreak {
     let scale = text::pt_to_scale(font_size);
+    let mut last_glyph = None;
+    next_break_with(text, |_, ch| {
+        advance_width_and_height(ch, font, scale, &mut last_glyph)
+    })
+}
+
+/// Returns the next index at which the text will break by either:
+/// - A newline character.
+/// - A line wrap at the beginning of the first character exceeding the `max_width`.
+///
+/// Also returns the width of each line alongside the Break.
+fn next_break_by_character(
+    text: &str,
+    font: &text::Font,
+    font_size: FontSize,
+    max_width: Scalar,
+) -> NextBreak {
+    let scale = text::pt_to_scale(font_size);
+    let mut last_glyph = None;
+    next_break_by_character_with(text, max_width, |_, ch| {
+        advance_width_and_height(ch, font, scale, &mut last_glyph)
+    })
+}
+
+/// Returns the next index at which the text will break by either:
+/// - A newline character.
+/// - A line wrap at the beginning of the whitespace that preceeds the first word
+///   exceeding the `max_width`.
+/// - A line wrap at the beginning of the first character exceeding the `max_width`,
+///   if no whitespace appears for `max_width` characters.
+///
+/// Also returns the width the line alongside the Break.
+fn next_break_by_whitespace(
+    text: &str,
+    font: &text::Font,
+    font_size: FontSize,
+    max_width: Scalar,
+) -> NextBreak {
+    let scale = text::pt_to_scale(font_size);
+    let mut last_glyph = None;
+    next_break_by_whitespace_with(text, max_width, |_, ch| {
+        advance_width_and_height(ch, font, scale, &mut last_glyph)
+    })
+}
+
+/// The same as `next_break`, but measures the advance width and height of each character
+/// via the given `metrics` function.
+///
+/// `metrics` is called with the byte index and value of each character in order.
+fn next_break_with<M>(text: &str, mut metrics: M) -> NextBreak
+where
+    M: FnMut(usize, char) -> (Scalar, Scalar),
+{
     let mut width = 0.0;
     let mut height = 0.0;
     let mut char_i = 0;
     let mut char_indices = text.char_indices().peekable();
-    let mut last_glyph = None;
     while let Some((byte_i, ch)) = char_indices.next() {
         // Check for a newline.
         if ch == '\r' {
@@ -267,7 +325,7 @@ fn next_break(text: &str, font: &text::Font, font_size: FontSize) -> NextBreak {
         }
 
         // Update the width.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
+        let (adv_w, h) = metrics(byte_i, ch);
         width += adv_w;
         height = height.max(h);
         char_i += 1;
@@ -283,23 +341,16 @@ fn next_break(text: &str, font: &text::Font, font_size: FontSize) -> NextBreak {
     }
 }
 
-/// Returns the next index at which the text will break by either:
-/// - A newline character.
-/// - A line wrap at the beginning of the first character exceeding the `max_width`.
-///
-/// Also returns the width of each line alongside the Break.
-fn next_break_by_character(
-    text: &str,
-    font: &text::Font,
-    font_size: FontSize,
-    max_width: Scalar,
-) -> NextBreak {
-    let scale = text::pt_to_scale(font_size);
+/// The same as `next_break_by_character`, but measures the advance width and height of each
+/// character via the given `metrics` function.
+fn next_break_by_character_with<M>(text: &str, max_width: Scalar, mut metrics: M) -> NextBreak
+where
+    M: FnMut(usize, char) -> (Scalar, Scalar),
+{
     let mut width = 0.0;
     let mut height = 0.0;
     let mut char_i = 0;
     let mut char_indices = text.char_indices().peekable();
-    let mut last_glyph = None;
     while let Some((byte_i, ch)) = char_indices.next() {
         // Check for a newline.
         if ch == '\r' {
@@ -331,7 +382,7 @@ fn next_break_by_character(
         }
 
         // Add the character's width to the width so far.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
+        let (adv_w, h) = metrics(byte_i, ch);
         let new_width = width + adv_w;
 
         // Check for a line wrap.
@@ -365,32 +416,22 @@ fn next_break_by_character(
     }
 }
 
-/// Returns the next index at which the text will break by either:
-/// - A newline character.
-/// - A line wrap at the beginning of the whitespace that preceeds the first word
-/// exceeding the `max_width`.
-/// - A line wrap at the beginning of the first character exceeding the `max_width`,
-/// if no whitespace appears for `max_width` characters.
-///
-/// Also returns the width the line alongside the Break.
-fn next_break_by_whitespace(
-    text: &str,
-    font: &text::Font,
-    font_size: FontSize,
-    max_width: Scalar,
-) -> NextBreak {
+/// The same as `next_break_by_whitespace`, but measures the advance width and height of each
+/// character via the given `metrics` function.
+fn next_break_by_whitespace_with<M>(text: &str, max_width: Scalar, mut metrics: M) -> NextBreak
+where
+    M: FnMut(usize, char) -> (Scalar, Scalar),
+{
     struct Last {
         byte: usize,
         char: usize,
         width_before: Scalar,
     }
-    let scale = text::pt_to_scale(font_size);
     let mut last_whitespace_start = None;
     let mut width = 0.0;
     let mut height = 0.0;
     let mut char_i = 0;
     let mut char_indices = text.char_indices().peekable();
-    let mut last_glyph = None;
     while let Some((byte_i, ch)) = char_indices.next() {
         // Check for a newline.
         if ch == '\r' {
@@ -422,7 +463,7 @@ fn next_break_by_whitespace(
         }
 
         // Add the character's width to the width so far.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
+        let (adv_w, h) = metrics(byte_i, ch);
         let new_width = width + adv_w;
 
         // Check for a line wrap.
@@ -562,6 +603,102 @@ pub fn infos_maybe_wrapped<'a>(
     }
 }
 
+/// The same as `infos_maybe_wrapped`, but measures each character by the advance of its shaped
+/// glyphs rather than the advance of its individual glyph.
+///
+/// The first of the given `fonts` is the primary font while the rest describe the font fallback
+/// chain. See the `text::shape` module for details.
+///
+/// **Panics** if `fonts` is empty.
+pub fn infos_shaped<'a>(
+    text: &'a str,
+    fonts: &'a [text::Font],
+    font_size: FontSize,
+    maybe_wrap: Option<Wrap>,
+    max_width: Scalar,
+) -> Infos<'a, impl 'a + for<'b> FnMut(&'b str, &'b text::Font, FontSize, Scalar) -> NextBreak> {
+    let attrs = Attrs {
+        font: 0,
+        font_size,
+        baseline_offset: 0.0,
+    };
+    let attrs = vec![attrs; text.len()];
+    let fallbacks = 1..fonts.len();
+    let options = text::shape::Options::default();
+    infos_shaped_with_attrs(
+        text, fonts, fallbacks, &attrs, options, maybe_wrap, max_width,
+    )
+}
+
+/// The same as `infos_shaped`, but applies the given attributes to each character.
+///
+/// `attrs` must contain the attributes of each character within `text`, indexed by byte, while
+/// `fallbacks` is the range of `fonts` that describes the font fallback chain. Characters are
+/// measured with the features and variations of the given shaping `options`. See
+/// `text::shape::shape_line_with_attrs` for details.
+///
+/// **Panics** if `fonts` is empty.
+pub fn infos_shaped_with_attrs<'a>(
+    text: &'a str,
+    fonts: &'a [text::Font],
+    fallbacks: std::ops::Range<usize>,
+    attrs: &[Attrs],
+    options: text::shape::Options,
+    maybe_wrap: Option<Wrap>,
+    max_width: Scalar,
+) -> Infos<'a, impl 'a + for<'b> FnMut(&'b str, &'b text::Font, FontSize, Scalar) -> NextBreak> {
+    // Each character is measured at its own font size, so this is only used to fill the `Infos`.
+    let font_size = attrs.first().map(|a| a.font_size).unwrap_or(0);
+    let char_metrics = shaped_metrics(text, fonts, fallbacks, attrs, options);
+    let max_width = match maybe_wrap {
+        None => f32::MAX,
+        Some(_) => max_width,
+    };
+    infos_wrapped_by(
+        text,
+        &fonts[0],
+        font_size,
+        max_width,
+        move |line: &str, _: &text::Font, _: FontSize, max_width: Scalar| {
+            // Each line is a suffix of the full text.
+            let start = text.len() - line.len();
+            let metrics = |byte_i: usize, _: char| char_metrics[start + byte_i];
+            match maybe_wrap {
+                None => next_break_with(line, metrics),
+                Some(Wrap::Character) => next_break_by_character_with(line, max_width, metrics),
+                Some(Wrap::Whitespace) => next_break_by_whitespace_with(line, max_width, metrics),
+            }
+        },
+    )
+}
+
+// The advance width and height of each character in the shaped text, indexed by byte.
+//
+// The advance of each glyph is attributed to the first character of the cluster that it
+// represents.
+fn shaped_metrics(
+    text: &str,
+    fonts: &[text::Font],
+    fallbacks: std::ops::Range<usize>,
+    attrs: &[Attrs],
+    options: text::shape::Options,
+) -> Vec<(Scalar, Scalar)> {
+    let mut metrics: Vec<(Scalar, Scalar)> = vec![(0.0, 0.0); text.len()];
+    let line = text::shape::shape_line_with_attrs(text, fonts, fallbacks, attrs, options);
+    for g in line.glyphs {
+        let height = fonts[g.font]
+            .glyph(g.id)
+            .scaled(text::pt_to_scale(g.font_size))
+            .exact_bounding_box()
+            .map(|bb| bb.min.y.abs() as Scalar)
+            .unwrap_or(0.0);
+        let (width, h) = &mut metrics[g.byte];
+        *width += g.advance;
+        *h = h.max(height);
+    }
+    metrics
+}
+
 /// Produce an iterator yielding the bounding `Rect` for each line in the text.
 ///
 /// Yielded `Rect`s will begin with the top-left of the first line at a [0.0, 0.0].
@@ -569,7 +706,7 @@ pub fn infos_maybe_wrapped<'a>(
 /// This function assumes that `font_size` and `max_width` are the same as those used to produce
 /// the `Info`s yielded by the `infos` Iterator.
 pub fn rects<I>(
-    mut infos: I,
+    infos: I,
     font_size: FontSize,
     max_width: Scalar,
     x_align: text::Justify,
@@ -578,7 +715,24 @@ pub fn rects<I>(
 where
     I: Iterator<Item = Info>,
 {
-    let first_rect = infos.next().map(|first_info| {
+    let heights = std::iter::repeat(font_size as Scalar);
+    let rects = rects_with_heights(infos.zip(heights), max_width, x_align, line_spacing);
+    Rects { rects }
+}
+
+/// The same as `rects`, but each line's `Info` is paired with the height of the line.
+///
+/// This is useful for text whose lines contain differing font sizes, e.g. via `text::span`.
+pub fn rects_with_heights<I>(
+    mut infos: I,
+    max_width: Scalar,
+    x_align: text::Justify,
+    line_spacing: Scalar,
+) -> RectsWithHeights<I>
+where
+    I: Iterator<Item = (Info, Scalar)>,
+{
+    let first_rect = infos.next().map(|(first_info, height)| {
         // Calculate the `x` `Range` of the first line `Rect`.
         let x_bounds = Range::new(0.0, max_width);
         let range = Range::new(0.0, first_info.width);
@@ -587,18 +741,17 @@ where
             text::Justify::Center => range.align_middle_of(x_bounds),
             text::Justify::Right => range.align_end_of(x_bounds),
         };
-        let y_start = -(font_size as Scalar);
+        let y_start = -height;
         //let y_end = y_start + first_info.height;
-        let y_end = y_start + font_size as Scalar;
+        let y_end = y_start + height;
         let y = Range::new(y_start, y_end);
         Rect { x: x, y: y }
     });
-    Rects {
+    RectsWithHeights {
         infos: infos,
         next: first_rect,
         x_align: x_align,
-        last_line_top: 0.0,
-        font_size: font_size,
+        last_line_bottom: first_rect.map(|r| r.bottom()).unwrap_or(0.0),
         line_spacing: line_spacing,
     }
 }
@@ -751,22 +904,31 @@ where
 {
     type Item = Rect;
     fn next(&mut self) -> Option<Self::Item> {
-        let Rects {
+        self.rects.next()
+    }
+}
+
+impl<I> Iterator for RectsWithHeights<I>
+where
+    I: Iterator<Item = (Info, Scalar)>,
+{
+    type Item = Rect;
+    fn next(&mut self) -> Option<Self::Item> {
+        let RectsWithHeights {
             ref mut next,
             ref mut infos,
             x_align,
-            ref mut last_line_top,
-            font_size,
+            ref mut last_line_bottom,
             line_spacing,
         } = *self;
         next.map(|line_rect| {
-            *next = infos.next().map(|info| {
+            *next = infos.next().map(|(info, height)| {
                 let y = {
-                    let line_top = *last_line_top - font_size as Scalar - line_spacing;
-                    *last_line_top = line_top;
-                    let y_start = line_top - font_size as Scalar;
+                    let line_top = *last_line_bottom - line_spacing;
+                    let y_start = line_top - height;
+                    *last_line_bottom = y_start;
                     //let y_end = y_start + info.height;
-                    let y_end = y_start + font_size as Scalar;
+                    let y_end = y_start + height;
                     Range::new(y_start, y_end)
                 };
 
@@ -8,13 +8,20 @@ pub mod font;
 pub mod glyph;
 pub mod layout;
 pub mod line;
+pub mod path;
+pub mod sdf;
+pub mod shape;
+pub mod span;
 pub mod rt {
     //! Re-exported RustType geometric types.
-    pub use rusttype::{gpu_cache, point, vector, Point, Rect, Vector};
+    pub use rusttype::{
+        gpu_cache, point, vector, Contour, Curve, Line, Point, Rect, Segment, Vector,
+    };
 }
 
-// Re-export all relevant rusttype types here.
+pub use self::font::Font;
 pub use self::layout::Layout;
+// Re-export all relevant rusttype types here.
 pub use rusttype::gpu_cache::Cache as GlyphCache;
 pub use rusttype::{Glyph, GlyphId, GlyphIter, LayoutIter, Scale, ScaledGlyph};
 
@@ -24,8 +31,6 @@ use std::borrow::Cow;
 
 /// The RustType `FontCollection` type used by nannou.
 pub type FontCollection = rusttype::FontCollection<'static>;
-/// The RustType `Font` type used by nannou.
-pub type Font = rusttype::Font<'static>;
 /// The RustType `PositionedGlyph` type used by nannou.
 pub type PositionedGlyph = rusttype::PositionedGlyph<'static>;
 
@@ -42,15 +47,18 @@ pub type FontSize = u32;
 pub struct Builder<'a> {
     text: Cow<'a, str>,
     layout_builder: layout::Builder,
+    spans: Vec<span::Span>,
 }
 
 /// An instance of some multi-line text and its layout.
 #[derive(Clone)]
 pub struct Text<'a> {
     text: Cow<'a, str>,
-    font: Font,
+    fonts: Vec<Font>,
     layout: Layout,
     line_infos: Vec<line::Info>,
+    shaped_lines: Vec<shape::Line>,
+    line_heights: Vec<Scalar>,
     rect: geom::Rect,
 }
 
@@ -72,7 +80,12 @@ pub type TextLines<'a> = Lines<
 >;
 
 /// An alias for the line rect iterator used internally within the `Text::line_rects` iterator.
-type LineRects<'a> = line::Rects<std::iter::Cloned<std::slice::Iter<'a, line::Info>>>;
+type LineRects<'a> = line::RectsWithHeights<
+    std::iter::Zip<
+        std::iter::Cloned<std::slice::Iter<'a, line::Info>>,
+        std::iter::Cloned<std::slice::Iter<'a, Scalar>>,
+    >,
+>;
 
 /// An alias for the line rect iterator yielded by `Text::line_rects`.
 #[derive(Clone)]
@@ -84,14 +97,24 @@ pub struct TextLineRects<'a> {
 /// An alias for the iterator yielded by `Text::lines_with_rects`.
 pub type TextLinesWithRects<'a> = std::iter::Zip<TextLines<'a>, TextLineRects<'a>>;
 
+/// An alias for the iterator yielded by `Text::shaped_lines_with_rects`.
+pub type TextShapedLinesWithRects<'a> =
+    std::iter::Zip<std::slice::Iter<'a, shape::Line>, TextLineRects<'a>>;
+
+/// An alias for the iterator yielded by `Text::cursor_xys_per_line`.
+pub type TextCursorXysPerLine<'a> = cursor::ShapedXysPerLine<
+    TextShapedLinesWithRects<'a>,
+    std::iter::Cloned<std::slice::Iter<'a, line::Info>>,
+>;
+
 /// An alias for the iterator yielded by `Text::glyphs_per_line`.
-pub type TextGlyphsPerLine<'a> = glyph::RectsPerLine<'a, TextLinesWithRects<'a>>;
+pub type TextGlyphsPerLine<'a> = glyph::ShapedRectsPerLine<'a, TextShapedLinesWithRects<'a>>;
 
 /// An alias for the iterator yielded by `Text::glyphs`.
 pub type TextGlyphs<'a> = std::iter::FlatMap<
     TextGlyphsPerLine<'a>,
-    glyph::Rects<'a, 'a>,
-    fn(glyph::Rects<'a, 'a>) -> glyph::Rects<'a, 'a>,
+    glyph::ShapedRects<'a>,
+    fn(glyph::ShapedRects<'a>) -> glyph::ShapedRects<'a>,
 >;
 
 /// Alignment along an axis.
@@ -132,6 +155,7 @@ impl<'a> From<Cow<'a, str>> for Builder<'a> {
         Builder {
             text,
             layout_builder,
+            spans: vec![],
         }
     }
 }
@@ -198,6 +222,45 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.font(font))
     }
 
+    /// Specify the fonts used to display characters that are missing from the primary font.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.map_layout(|l| l.fallback_fonts(fonts))
+    }
+
+    /// Append a font to the end of the font fallback chain.
+    pub fn fallback_font(self, font: Font) -> Self {
+        self.map_layout(|l| l.fallback_font(font))
+    }
+
+    /// Specify the OpenType features applied to the text.
+    pub fn features<I>(self, features: I) -> Self
+    where
+        I: IntoIterator<Item = font::Feature>,
+    {
+        self.map_layout(|l| l.features(features))
+    }
+
+    /// Set the OpenType feature with the given tag, e.g. `feature(b"smcp", 1)`.
+    pub fn feature(self, tag: &[u8; 4], value: u32) -> Self {
+        self.map_layout(|l| l.feature(tag, value))
+    }
+
+    /// Specify the values of the variation axes applied to variable fonts.
+    pub fn variations<I>(self, variations: I) -> Self
+    where
+        I: IntoIterator<Item = font::Variation>,
+    {
+        self.map_layout(|l| l.variations(variations))
+    }
+
+    /// Set the value of the variation axis with the given tag, e.g. `variation(b"wght", 700.0)`.
+    pub fn variation(self, tag: &[u8; 4], value: f32) -> Self {
+        self.map_layout(|l| l.variation(tag, value))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: Justify) -> Self {
         self.map_layout(|l| l.justify(justify))
@@ -250,10 +313,28 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.layout(layout))
     }
 
+    /// Apply the given style to the given byte range of the text.
+    ///
+    /// Where spans overlap, the style of the later span takes precedence.
+    pub fn span(mut self, range: std::ops::Range<usize>, style: span::Style) -> Self {
+        self.spans.push(span::Span { range, style });
+        self
+    }
+
+    /// Apply each of the given spans to the text.
+    pub fn spans<I>(mut self, spans: I) -> Self
+    where
+        I: IntoIterator<Item = span::Span>,
+    {
+        self.spans.extend(spans);
+        self
+    }
+
     /// Build the text.
     ///
     /// This iterates over the text in order to pre-calculates the text's multi-line information
-    /// using the `line::infos` function.
+    /// using the `line::infos_shaped_with_attrs` function, before shaping each line via the
+    /// `shape` module.
     ///
     /// The given `rect` will be used for applying the layout including text alignment, positioning
     /// of text, multi-line wrapping, etc,
@@ -270,15 +351,48 @@ impl<'a> Builder<'a> {
                 .expect("failed to detect the assets directory when searching for a default font");
             font::default(&assets).expect("failed to detect a default font")
         });
+        let mut fonts = vec![font];
+        fonts.extend(layout.fallback_fonts.iter().cloned());
+        let fallbacks = 1..fonts.len();
+        let attrs = span::resolve(text.len(), &self.spans, &mut fonts, layout.font_size);
+        let options = shape::Options {
+            features: &layout.features,
+            variations: &layout.variations,
+        };
         let max_width = rect.w();
-        let line_infos =
-            line::infos_maybe_wrapped(&text, &font, layout.font_size, layout.line_wrap, max_width)
-                .collect();
+        let mut line_infos: Vec<_> = line::infos_shaped_with_attrs(
+            &text,
+            &fonts,
+            fallbacks.clone(),
+            &attrs,
+            options,
+            layout.line_wrap,
+            max_width,
+        )
+        .collect();
+        let mut shaper = shape::Shaper::default();
+        let shaped_lines: Vec<_> = line_infos
+            .iter_mut()
+            .map(|info| {
+                let range = info.byte_range();
+                let line = &text[range.clone()];
+                let (fallbacks, attrs) = (fallbacks.clone(), &attrs[range]);
+                let shaped = shaper.shape_line_with_attrs(line, &fonts, fallbacks, attrs, options);
+                info.width = shaped.width;
+                shaped
+            })
+            .collect();
+        let line_heights = line_infos
+            .iter()
+            .map(|info| span::line_height(&attrs[info.byte_range()], layout.font_size))
+            .collect();
         Text {
             text,
-            font,
+            fonts,
             layout,
             line_infos,
+            shaped_lines,
+            line_heights,
             rect,
         }
     }
@@ -300,9 +414,30 @@ impl<'a> Text<'a> {
         &self.layout
     }
 
-    /// The font used for this text instance.
+    /// The primary font used for this text instance.
     pub fn font(&self) -> &Font {
-        &self.font
+        &self.fonts[0]
+    }
+
+    /// The primary font followed by the font fallback chain used for this text instance.
+    ///
+    /// The fonts of any spans that are not already present follow the fallback chain. The `font`
+    /// field of each `shape::Glyph` indexes into this slice.
+    pub fn fonts(&self) -> &[Font] {
+        &self.fonts
+    }
+
+    /// The shaped glyphs for each line.
+    pub fn shaped_lines(&self) -> &[shape::Line] {
+        &self.shaped_lines
+    }
+
+    /// The height of each line.
+    ///
+    /// This is the greatest font size within each line, or the layout's `font_size` for lines
+    /// without any spans.
+    pub fn line_heights(&self) -> &[Scalar] {
+        &self.line_heights
     }
 
     /// The number of lines in the text.
@@ -324,15 +459,15 @@ impl<'a> Text<'a> {
             None => return geom::Rect::from_w_h(0.0, 0.0),
             Some(info) => info,
         };
-        let line_h = self.layout.font_size as Scalar;
+        let line_h = self.line_heights[0];
         r.y.end -= line_h - info.height;
         r
     }
 
     /// The rectangle that describes the min and max bounds along each axis reached by the text.
     ///
-    /// This is similar to `bounding_rect` but assumes that all lines have a height equal to
-    /// `font_size`, rather than using the exact height.
+    /// This is similar to `bounding_rect` but assumes that each line has a height equal to its
+    /// greatest font size, rather than using the exact height.
     pub fn bounding_rect_by_lines(&self) -> geom::Rect {
         let mut lrs = self.line_rects();
         let lr = match lrs.next() {
@@ -359,25 +494,22 @@ impl<'a> Text<'a> {
             None => return 0.0,
             Some(info) => info,
         };
-        exact_height(
-            info.height,
-            self.num_lines(),
-            self.layout.font_size,
-            self.layout.line_spacing,
-        )
+        self.height_by_lines() - self.line_heights[0] + info.height
     }
 
     /// Determine the total height of a block of text with the given number of lines, font size and
     /// `line_spacing` (the space that separates each line of text).
     ///
-    /// The height of all lines of text are assumed to match the `font_size`. If looking for the exact
-    /// height, see the `exact_height` function.
+    /// The height of each line is assumed to match its greatest font size. If looking for the
+    /// exact height, see the `height` method.
     pub fn height_by_lines(&self) -> Scalar {
-        height_by_lines(
-            self.num_lines(),
-            self.layout.font_size,
-            self.layout.line_spacing,
-        )
+        let num_lines = self.num_lines();
+        if num_lines > 0 {
+            let lines_height: Scalar = self.line_heights.iter().sum();
+            lines_height + (num_lines - 1) as Scalar * self.layout.line_spacing
+        } else {
+            0.0
+        }
     }
 
     /// Produce an iterator yielding each wrapped line within the **Text**.
@@ -391,9 +523,9 @@ impl<'a> Text<'a> {
     /// The bounding rectangle for each line.
     pub fn line_rects(&self) -> TextLineRects {
         let offset = self.position_offset();
-        let line_rects = line::rects(
-            self.line_infos.iter().cloned(),
-            self.layout.font_size,
+        let infos = self.line_infos.iter().cloned();
+        let line_rects = line::rects_with_heights(
+            infos.zip(self.line_heights.iter().cloned()),
             self.rect.w(),
             self.layout.justify,
             self.layout.line_spacing,
@@ -406,10 +538,25 @@ impl<'a> Text<'a> {
         self.lines().zip(self.line_rects())
     }
 
+    /// Produce an iterator yielding all shaped lines of text alongside their bounding rects.
+    pub fn shaped_lines_with_rects(&self) -> TextShapedLinesWithRects<'_> {
+        self.shaped_lines.iter().zip(self.line_rects())
+    }
+
+    /// Produce an iterator yielding every possible cursor position within each line.
+    ///
+    /// See `cursor::shaped_xys_per_line` for details.
+    pub fn cursor_xys_per_line(&self) -> TextCursorXysPerLine<'_> {
+        let infos = self.line_infos.iter().cloned();
+        cursor::shaped_xys_per_line(self.shaped_lines_with_rects(), infos)
+    }
+
     /// Produce an iterator yielding iterators yielding every glyph alongside its bounding rect for
     /// each line.
+    ///
+    /// Glyphs are yielded in visual order and may be from any of the text's `fonts`.
     pub fn glyphs_per_line(&self) -> TextGlyphsPerLine {
-        glyph::rects_per_line(self.lines_with_rects(), &self.font, self.layout.font_size)
+        glyph::shaped_rects_per_line(self.shaped_lines_with_rects(), &self.fonts)
     }
 
     /// Produce an iterator yielding every glyph alongside its bounding rect.
@@ -470,12 +617,53 @@ impl<'a> Text<'a> {
             }
         }
 
-        self.glyphs().flat_map(|(g, r)| {
-            glyph::path_events(g)
-                .into_iter()
-                .flat_map(|es| es)
-                .map(move |e| trans_path_event(&e, r.bottom_left().into()))
-        })
+        self.shaped_lines_with_rects()
+            .flat_map(|(line, line_rect)| line.glyphs.iter().map(move |g| (g, line_rect)))
+            .flat_map(move |(g, line_rect)| {
+                let origin = line_rect.bottom_left() + g.position;
+                self.glyph_path_events(g)
+                    .into_iter()
+                    .map(move |e| trans_path_event(&e, origin))
+            })
+    }
+
+    /// Produce the path events for the outline of the given shaped glyph.
+    ///
+    /// The path is relative to the glyph's origin. If the glyph's font is a variable font and the
+    /// layout specifies `variations`, these are applied to the outline.
+    pub fn glyph_path_events(&self, g: &shape::Glyph) -> Vec<lyon::path::PathEvent> {
+        let font = &self.fonts[g.font];
+        let scale = pt_to_scale(g.font_size);
+        if self.is_glyph_varied(g) {
+            let variations = &self.layout.variations;
+            glyph::path_events_with_variations(font, g.id, scale, variations).unwrap_or_default()
+        } else {
+            let glyph = font.glyph(g.id).scaled(scale);
+            glyph::path_events(glyph).into_iter().flatten().collect()
+        }
+    }
+
+    /// Whether or not the given glyph is displayed with font variations applied.
+    ///
+    /// This is the case if the glyph's font is a variable font and the layout specifies
+    /// `variations`.
+    pub fn is_glyph_varied(&self, g: &shape::Glyph) -> bool {
+        self.fonts[g.font].is_variable() && !self.layout.variations.is_empty()
+    }
+
+    /// Produce the path events for every glyph placed along the given path.
+    ///
+    /// See the `text::path` module for details.
+    pub fn path_events_along_path<'b, I>(
+        &'b self,
+        path: I,
+        options: &path::Options,
+    ) -> impl 'b + Iterator<Item = lyon::path::PathEvent>
+    where
+        I: IntoIterator<Item = lyon::path::PathEvent>,
+    {
+        let placements = path::place_glyphs(self, path, options);
+        path::path_events(self, placements)
     }
 
     /// Produce an iterator yielding positioned rusttype glyphs ready for caching.
@@ -488,42 +676,66 @@ impl<'a> Text<'a> {
         window_size: Vec2,
         scale_factor: Scalar,
     ) -> impl 'a + 'b + Iterator<Item = PositionedGlyph> {
-        rt_positioned_glyphs(
-            self.lines_with_rects(),
-            &self.font,
-            self.layout.font_size,
-            window_size,
-            scale_factor,
-        )
+        self.rt_glyphs_with_font_ids(window_size, scale_factor)
+            .map(|(_, g)| g)
+    }
+
+    /// The same as `rt_glyphs`, but also yields the `font::Id` of each glyph's font.
+    ///
+    /// As glyphs may be from any of the text's `fonts`, glyphs must be cached under the `font::Id`
+    /// of their font.
+    pub fn rt_glyphs_with_font_ids<'b: 'a>(
+        &'b self,
+        window_size: Vec2,
+        scale_factor: Scalar,
+    ) -> impl 'a + 'b + Iterator<Item = (font::Id, PositionedGlyph)> {
+        // Functions for converting nannou coordinates to rusttype pixel coordinates.
+        let trans_x = move |x: Scalar| (x + window_size.x / 2.0) * scale_factor as Scalar;
+        let trans_y = move |y: Scalar| ((-y) + window_size.y / 2.0) * scale_factor as Scalar;
+
+        let fonts = &self.fonts;
+        let font_ids: Vec<_> = fonts.iter().map(font::id).collect();
+        self.shaped_lines_with_rects()
+            .flat_map(move |(line, line_rect)| {
+                let font_ids = font_ids.clone();
+                line.glyphs.iter().map(move |g| {
+                    let x = trans_x(line_rect.left() + g.position.x);
+                    let y = trans_y(line_rect.bottom() + g.position.y);
+                    let point = rt::Point { x, y };
+                    let g_font = &fonts[g.font];
+                    let scale = f32_pt_to_scale(g.font_size as f32 * scale_factor);
+                    let positioned = g_font.glyph(g.id).scaled(scale).positioned(point);
+                    (font_ids[g.font], positioned)
+                })
+            })
     }
 
     /// Converts this `Text` instance into an instance that owns the inner text string.
     pub fn into_owned(self) -> Text<'static> {
         let Text {
             text,
-            font,
+            fonts,
             layout,
             line_infos,
+            shaped_lines,
+            line_heights,
             rect,
         } = self;
         let text = Cow::Owned(text.into_owned());
         Text {
             text,
-            font,
+            fonts,
             layout,
             line_infos,
+            shaped_lines,
+            line_heights,
             rect,
         }
     }
 
     fn position_offset(&self) -> Vec2 {
-        position_offset(
-            self.num_lines(),
-            self.layout.font_size,
-            self.layout.line_spacing,
-            self.rect,
-            self.layout.y_align,
-        )
+        let total_text_height = self.height_by_lines();
+        position_offset_by_height(total_text_height, self.rect, self.layout.y_align)
     }
 }
 
@@ -603,11 +815,21 @@ pub fn position_offset(
     line_spacing: f32,
     bounding_rect: geom::Rect,
     y_align: Align,
+) -> Vec2 {
+    let total_text_height = height_by_lines(num_lines, font_size, line_spacing);
+    position_offset_by_height(total_text_height, bounding_rect, y_align)
+}
+
+// The position offset required to shift text of the given total height into the given bounding
+// rectangle.
+fn position_offset_by_height(
+    total_text_height: Scalar,
+    bounding_rect: geom::Rect,
+    y_align: Align,
 ) -> Vec2 {
     let x_offset = bounding_rect.x.start;
     let y_offset = {
         // Calculate the `y` `Range` of the first line `Rect`.
-        let total_text_height = height_by_lines(num_lines, font_size, line_spacing);
         let total_text_y_range = geom::Range::new(0.0, total_text_height);
         let total_text_y = match y_align {
             Align::Start => total_text_y_range.align_start_of(bounding_rect.y),
@@ -0,0 +1,220 @@
+//! Laying out text along a path.
+//!
+//! Rather than displaying each line of text along a straight baseline, the glyphs of each line
+//! may be placed along an arbitrary path. Each glyph is positioned so that the centre of its
+//! advance lies on the path, and is rotated to follow the path's tangent at that point.
+//!
+//! The baseline of the first line lies on the path, while following lines are offset along the
+//! path's normal by their distance from the first line. Glyphs that do not fit along the path are
+//! not placed.
+
+use crate::geom::pt2;
+use crate::text::{Align, Point, Scalar, Text};
+use lyon::algorithms::length::approximate_length;
+use lyon::algorithms::walk;
+use lyon::path::iterator::PathIterator;
+use lyon::path::PathEvent;
+
+/// The default maximum distance between a path and the line segments used to approximate it.
+pub const DEFAULT_TOLERANCE: Scalar = 0.1;
+
+/// Parameters describing how text is laid out along a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    /// The distance along the path by which each line is shifted after alignment.
+    pub start_offset: Scalar,
+    /// The alignment of each line along the length of the path.
+    pub align: Align,
+    /// Additional space inserted between each glyph.
+    pub spacing: Scalar,
+    /// The maximum distance between the path and the line segments used to approximate it.
+    pub tolerance: Scalar,
+}
+
+/// The placement of a single glyph along a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Placement {
+    /// The index of the line containing the glyph.
+    pub line: usize,
+    /// The index of the glyph within its line's `shape::Line`.
+    pub glyph: usize,
+    /// The position of the glyph's origin.
+    pub origin: Point,
+    /// The rotation of the glyph about its origin in radians.
+    pub rotation: Scalar,
+}
+
+impl Options {
+    /// Shift each line along the path by the given distance after alignment.
+    pub fn start_offset(mut self, offset: Scalar) -> Self {
+        self.start_offset = offset;
+        self
+    }
+
+    /// Specify the alignment of each line along the length of the path.
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Insert the given additional space between each glyph.
+    pub fn spacing(mut self, spacing: Scalar) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// The maximum distance between the path and the line segments used to approximate it.
+    pub fn tolerance(mut self, tolerance: Scalar) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl Placement {
+    /// Transform a point relative to the glyph's origin to its position along the path.
+    pub fn transform_point(&self, p: Point) -> Point {
+        let (sin, cos) = self.rotation.sin_cos();
+        self.origin + pt2(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            start_offset: 0.0,
+            align: Align::Start,
+            spacing: 0.0,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+/// Place the glyphs of every line of the given text along the given path.
+///
+/// Placements are yielded in the same order as the glyphs of `text.shaped_lines()`, skipping
+/// glyphs that do not fit along the path.
+pub fn place_glyphs<I>(text: &Text, path: I, options: &Options) -> Vec<Placement>
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    let events: Vec<PathEvent> = path.into_iter().collect();
+    let length = approximate_length(events.iter().cloned(), options.tolerance);
+    let mut placements = vec![];
+    let mut lines = text.shaped_lines_with_rects().enumerate().peekable();
+    let first_baseline = match lines.peek() {
+        None => return placements,
+        Some((_, (_, line_rect))) => line_rect.bottom(),
+    };
+    for (line_ix, (line, line_rect)) in lines {
+        let glyphs = &line.glyphs;
+        let n_gaps = glyphs.len().saturating_sub(1);
+        let width = line.width + options.spacing * n_gaps as Scalar;
+        let start = options.start_offset
+            + match options.align {
+                Align::Start => 0.0,
+                Align::Middle => (length - width) / 2.0,
+                Align::End => length - width,
+            };
+
+        // The distance along the path of the centre of each glyph that fits along the path.
+        let centres: Vec<(usize, Scalar)> = glyphs
+            .iter()
+            .enumerate()
+            .map(|(i, g)| {
+                let x = start + g.position.x + options.spacing * i as Scalar;
+                (i, x + g.advance / 2.0)
+            })
+            .filter(|&(_, d)| d >= 0.0 && d <= length)
+            .collect();
+        let first_centre = match centres.first() {
+            None => continue,
+            Some(&(_, d)) => d,
+        };
+
+        // Walk along the path, sampling the position and tangent at each centre.
+        let mut samples = Vec::with_capacity(centres.len());
+        let mut pattern = |position: lyon::math::Point, tangent: lyon::math::Vector, _: f32| {
+            samples.push((position, tangent));
+            let i = samples.len();
+            centres.get(i).map(|&(_, d)| d - centres[i - 1].1)
+        };
+        let flattened = events.iter().cloned().flattened(options.tolerance);
+        walk::walk_along_path(flattened, first_centre, &mut pattern);
+
+        // Offset lines following the first along the path's normal.
+        let line_offset = line_rect.bottom() - first_baseline;
+        for (&(i, _), (position, tangent)) in centres.iter().zip(samples) {
+            let g = &glyphs[i];
+            let t = pt2(tangent.x, tangent.y);
+            let normal = pt2(-t.y, t.x);
+            let origin = pt2(position.x, position.y) - t * (g.advance / 2.0)
+                + normal * (line_offset + g.position.y);
+            placements.push(Placement {
+                line: line_ix,
+                glyph: i,
+                origin,
+                rotation: t.y.atan2(t.x),
+            });
+        }
+    }
+    placements
+}
+
+/// Produce the path events for the outline of each of the given placed glyphs.
+///
+/// The `placements` must have been produced for the given `text` via `place_glyphs`.
+pub fn path_events<'a, I>(text: &'a Text, placements: I) -> impl 'a + Iterator<Item = PathEvent>
+where
+    I: 'a + IntoIterator<Item = Placement>,
+{
+    placements.into_iter().flat_map(move |p| {
+        let g = &text.shaped_lines()[p.line].glyphs[p.glyph];
+        text.glyph_path_events(g)
+            .into_iter()
+            .map(move |e| transform_event(&e, &p))
+    })
+}
+
+// Transform the given glyph path event to its placement along the path.
+fn transform_event(e: &PathEvent, placement: &Placement) -> PathEvent {
+    let tp = |p: &lyon::math::Point| {
+        let p = placement.transform_point(pt2(p.x, p.y));
+        lyon::math::point(p.x, p.y)
+    };
+    match *e {
+        PathEvent::Begin { ref at } => PathEvent::Begin { at: tp(at) },
+        PathEvent::Line { ref from, ref to } => PathEvent::Line {
+            from: tp(from),
+            to: tp(to),
+        },
+        PathEvent::Quadratic {
+            ref from,
+            ref ctrl,
+            ref to,
+        } => PathEvent::Quadratic {
+            from: tp(from),
+            ctrl: tp(ctrl),
+            to: tp(to),
+        },
+        PathEvent::Cubic {
+            ref from,
+            ref ctrl1,
+            ref ctrl2,
+            ref to,
+        } => PathEvent::Cubic {
+            from: tp(from),
+            ctrl1: tp(ctrl1),
+            ctrl2: tp(ctrl2),
+            to: tp(to),
+        },
+        PathEvent::End {
+            ref last,
+            ref first,
+            close,
+        } => PathEvent::End {
+            last: tp(last),
+            first: tp(first),
+            close,
+        },
+    }
+}
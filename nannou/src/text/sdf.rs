@@ -0,0 +1,544 @@
+//! Signed distance field generation for glyphs.
+//!
+//! Rather than rasterising a glyph's coverage at the exact size at which it is displayed, a signed
+//! distance field (SDF) stores the distance from each texel to the nearest edge of the glyph's
+//! outline. As the distance varies smoothly, the field may be sampled with linear filtering and
+//! thresholded at any scale, allowing a single cached glyph to be displayed crisply at any size
+//! and under any transform. Thresholding the field at other distances also allows for cheap
+//! outline, glow and drop shadow effects.
+//!
+//! Each texel is encoded as `0.5 + distance / (2 * spread)`, clamped to the range `0.0..=1.0`,
+//! where `distance` is positive inside the glyph. The glyph's edge lies at `0.5`.
+
+use crate::geom;
+use crate::text::font::Variation;
+use crate::text::{font, glyph, rt, Font, GlyphId, Scalar, Scale, ScaledGlyph};
+use lyon::path::iterator::PathIterator;
+use lyon::path::PathEvent;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// The default size in pixels at which glyph distance fields are generated.
+pub const DEFAULT_GLYPH_PX: f32 = 48.0;
+
+/// The default maximum distance in pixels encoded within a glyph's distance field.
+pub const DEFAULT_SPREAD: f32 = 8.0;
+
+// The number of line segments used to approximate each quadratic curve.
+const CURVE_STEPS: usize = 8;
+
+// The maximum distance in pixels between a path and the line segments used to approximate it.
+const PATH_TOLERANCE: f32 = 0.05;
+
+/// The signed distance field for a single glyph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphSdf {
+    /// The width of the field in texels.
+    pub width: u32,
+    /// The height of the field in texels.
+    pub height: u32,
+    /// The encoded distance for each texel, row by row from the top.
+    pub data: Vec<u8>,
+    /// The bounds of the field in pixels relative to the glyph's origin, where *y* is up.
+    ///
+    /// The bounds include `spread` pixels of padding on each side of the glyph's outline.
+    pub bounds: geom::Rect,
+}
+
+/// A glyph's location within an **Atlas**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The normalised texture coordinates of the glyph's field within the atlas.
+    ///
+    /// As with the `rusttype` glyph cache, `min` is the top left corner.
+    pub uv: rt::Rect<f32>,
+    /// The bounds of the glyph's field in atlas pixels relative to the glyph's origin, where *y*
+    /// is up.
+    pub bounds: geom::Rect,
+}
+
+/// A texture atlas of glyph signed distance fields.
+///
+/// Unlike the `rusttype` glyph cache, each glyph is only stored once regardless of the scale,
+/// position or transform at which it is displayed.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    size: [u32; 2],
+    glyph_px: f32,
+    spread: f32,
+    // `None` for glyphs with no outline, e.g. whitespace. Glyphs of variable fonts are also keyed
+    // by a hash of their variations.
+    entries: HashMap<(font::Id, GlyphId, u64), Option<Entry>>,
+    // The top left of the next free space within the current shelf.
+    shelf_x: u32,
+    shelf_y: u32,
+    // The height of the tallest glyph within the current shelf.
+    shelf_h: u32,
+}
+
+/// Returned by `Atlas::get_or_insert` when there is no space left for a new glyph.
+#[derive(Copy, Clone, Debug)]
+pub struct AtlasFullError;
+
+// A line segment of a flattened outline.
+type Segment = [rt::Point<f32>; 2];
+
+impl Atlas {
+    /// Create a new, empty atlas with the given dimensions in texels.
+    ///
+    /// Glyphs will be generated at `glyph_px` pixels with a maximum encoded distance of `spread`
+    /// pixels.
+    pub fn new(size: [u32; 2], glyph_px: f32, spread: f32) -> Self {
+        Atlas {
+            size,
+            glyph_px,
+            spread,
+            entries: Default::default(),
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_h: 0,
+        }
+    }
+
+    /// The dimensions of the atlas in texels.
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// The size in pixels at which glyph distance fields are generated.
+    pub fn glyph_px(&self) -> f32 {
+        self.glyph_px
+    }
+
+    /// The maximum distance in pixels encoded within each glyph's distance field.
+    pub fn spread(&self) -> f32 {
+        self.spread
+    }
+
+    /// Whether or not no glyph fields have been allocated within the atlas.
+    pub fn is_empty(&self) -> bool {
+        self.shelf_x == 0 && self.shelf_y == 0
+    }
+
+    /// Remove all glyphs from the atlas.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.shelf_x = 0;
+        self.shelf_y = 0;
+        self.shelf_h = 0;
+    }
+
+    /// Retrieve the entry for the given glyph, generating its distance field if necessary.
+    ///
+    /// If the font is a variable font, the given `variations` are applied to the glyph's outline
+    /// and each distinct set of variations is stored separately. Otherwise they are ignored.
+    ///
+    /// Newly generated fields are passed to `upload` along with the rect of texels that they
+    /// occupy within the atlas, in the same manner as the glyph cache's `cache_queued`.
+    ///
+    /// Returns `Ok(None)` if the glyph has no outline.
+    pub fn get_or_insert<F>(
+        &mut self,
+        font_id: font::Id,
+        font: &Font,
+        id: GlyphId,
+        variations: &[Variation],
+        mut upload: F,
+    ) -> Result<Option<Entry>, AtlasFullError>
+    where
+        F: FnMut(rt::Rect<u32>, &[u8]),
+    {
+        let variations = if font.is_variable() { variations } else { &[] };
+        let key = (font_id, id, variations_key(variations));
+        if let Some(entry) = self.entries.get(&key) {
+            return Ok(*entry);
+        }
+        let scale = Scale::uniform(self.glyph_px);
+        let sdf = if variations.is_empty() {
+            glyph_sdf(&font.glyph(id).scaled(scale), self.spread)
+        } else {
+            glyph::path_events_with_variations(font, id, scale, variations)
+                .and_then(|events| path_sdf(events, self.spread))
+        };
+        let sdf = match sdf {
+            None => {
+                self.entries.insert(key, None);
+                return Ok(None);
+            }
+            Some(sdf) => sdf,
+        };
+        let [x, y] = self.allocate(sdf.width, sdf.height)?;
+        let rect = rt::Rect {
+            min: rt::point(x, y),
+            max: rt::point(x + sdf.width, y + sdf.height),
+        };
+        upload(rect, &sdf.data);
+        let [w, h] = [self.size[0] as f32, self.size[1] as f32];
+        let uv = rt::Rect {
+            min: rt::point(rect.min.x as f32 / w, rect.min.y as f32 / h),
+            max: rt::point(rect.max.x as f32 / w, rect.max.y as f32 / h),
+        };
+        let entry = Entry {
+            uv,
+            bounds: sdf.bounds,
+        };
+        self.entries.insert(key, Some(entry));
+        Ok(Some(entry))
+    }
+
+    // Find space for a field of the given size, leaving a single texel gap between fields.
+    fn allocate(&mut self, w: u32, h: u32) -> Result<[u32; 2], AtlasFullError> {
+        let [atlas_w, atlas_h] = self.size;
+        let (mut x, mut y, mut shelf_h) = (self.shelf_x, self.shelf_y, self.shelf_h);
+        if x + w > atlas_w {
+            x = 0;
+            y += shelf_h + 1;
+            shelf_h = 0;
+        }
+        // Leave the atlas untouched if the field does not fit.
+        if x + w > atlas_w || y + h > atlas_h {
+            return Err(AtlasFullError);
+        }
+        self.shelf_x = x + w + 1;
+        self.shelf_y = y;
+        self.shelf_h = std::cmp::max(shelf_h, h);
+        Ok([x, y])
+    }
+}
+
+/// Generate the signed distance field for the given glyph at its current scale.
+///
+/// The field is padded by `spread` pixels on each side of the glyph's outline, which is also the
+/// maximum distance encoded within the field.
+///
+/// Returns `None` if the glyph has no outline.
+pub fn glyph_sdf(glyph: &ScaledGlyph, spread: f32) -> Option<GlyphSdf> {
+    let contours = glyph.shape()?;
+    let bb = glyph.exact_bounding_box()?;
+
+    // Flatten the outline into line segments. Contours are relative to the origin, *y* up.
+    let mut segments: Vec<Segment> = vec![];
+    for contour in &contours {
+        for segment in &contour.segments {
+            match *segment {
+                rt::Segment::Line(rt::Line { p }) => segments.push(p),
+                rt::Segment::Curve(rt::Curve { p: [a, b, c] }) => {
+                    let mut prev = a;
+                    for i in 1..=CURVE_STEPS {
+                        let t = i as f32 / CURVE_STEPS as f32;
+                        let u = 1.0 - t;
+                        let x = u * u * a.x + 2.0 * u * t * b.x + t * t * c.x;
+                        let y = u * u * a.y + 2.0 * u * t * b.y + t * t * c.y;
+                        let next = rt::point(x, y);
+                        segments.push([prev, next]);
+                        prev = next;
+                    }
+                }
+            }
+        }
+    }
+
+    // The bounding box is *y* down, so flip it.
+    let bounds = rt::Rect {
+        min: rt::point(bb.min.x, -bb.max.y),
+        max: rt::point(bb.max.x, -bb.min.y),
+    };
+    segments_sdf(&segments, bounds, spread)
+}
+
+/// Generate the signed distance field for the given glyph outline, where *y* is up.
+///
+/// This allows for generating fields from outlines that rusttype cannot produce, such as those
+/// of `glyph::path_events_with_variations`.
+///
+/// Returns `None` if the path is empty.
+pub fn path_sdf<I>(events: I, spread: f32) -> Option<GlyphSdf>
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    // Flatten the outline into line segments, closing every contour.
+    let mut segments: Vec<Segment> = vec![];
+    let p = |p: lyon::math::Point| rt::point(p.x, p.y);
+    for event in events.into_iter().flattened(PATH_TOLERANCE) {
+        match event {
+            PathEvent::Line { from, to } => segments.push([p(from), p(to)]),
+            PathEvent::End { last, first, .. } if last != first => {
+                segments.push([p(last), p(first)])
+            }
+            _ => (),
+        }
+    }
+    let bounds = segments
+        .iter()
+        .flat_map(|s| s.iter())
+        .fold(None, |bb, &pt| {
+            let bb: rt::Rect<f32> = match bb {
+                None => return Some(rt::Rect { min: pt, max: pt }),
+                Some(bb) => bb,
+            };
+            let min = rt::point(bb.min.x.min(pt.x), bb.min.y.min(pt.y));
+            let max = rt::point(bb.max.x.max(pt.x), bb.max.y.max(pt.y));
+            Some(rt::Rect { min, max })
+        })?;
+    segments_sdf(&segments, bounds, spread)
+}
+
+// Generate the signed distance field for the outline described by the given segments, whose
+// exact bounds are given with *y* up.
+fn segments_sdf(segments: &[Segment], bounds: rt::Rect<f32>, spread: f32) -> Option<GlyphSdf> {
+    if segments.is_empty() {
+        return None;
+    }
+
+    // Pad the bounds by the spread.
+    let left = (bounds.min.x - spread).floor();
+    let bottom = (bounds.min.y - spread).floor();
+    let right = (bounds.max.x + spread).ceil();
+    let top = (bounds.max.y + spread).ceil();
+    let width = (right - left) as u32;
+    let height = (top - bottom) as u32;
+
+    let mut data = Vec::with_capacity(width as usize * height as usize);
+    for row in 0..height {
+        let y = top - row as f32 - 0.5;
+        for col in 0..width {
+            let x = left + col as f32 + 0.5;
+            let p = rt::point(x, y);
+            let dist = segments
+                .iter()
+                .map(|s| segment_distance(p, s))
+                .fold(f32::MAX, f32::min);
+            let signed = if winding(p, segments) != 0 {
+                dist
+            } else {
+                -dist
+            };
+            let value = (0.5 + signed / (2.0 * spread)).clamp(0.0, 1.0);
+            data.push((value * 255.0).round() as u8);
+        }
+    }
+
+    let bounds = geom::Rect::from_corners(
+        [left as Scalar, bottom as Scalar].into(),
+        [right as Scalar, top as Scalar].into(),
+    );
+    Some(GlyphSdf {
+        width,
+        height,
+        data,
+        bounds,
+    })
+}
+
+// A hash of the given variations, or `0` if there are none.
+fn variations_key(variations: &[Variation]) -> u64 {
+    if variations.is_empty() {
+        return 0;
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for v in variations {
+        v.tag.hash(&mut hasher);
+        v.value.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+// The distance from the point to the line segment.
+fn segment_distance(p: rt::Point<f32>, [a, b]: &Segment) -> f32 {
+    let [abx, aby] = [b.x - a.x, b.y - a.y];
+    let [apx, apy] = [p.x - a.x, p.y - a.y];
+    let len_sq = abx * abx + aby * aby;
+    let t = if len_sq > 0.0 {
+        ((apx * abx + apy * aby) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let [dx, dy] = [apx - abx * t, apy - aby * t];
+    (dx * dx + dy * dy).sqrt()
+}
+
+// The winding number of the outline around the point.
+fn winding(p: rt::Point<f32>, segments: &[Segment]) -> i32 {
+    let mut winding = 0;
+    for [a, b] in segments {
+        // Which side of the segment the point lies on.
+        let side = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+        if a.y <= p.y {
+            if b.y > p.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+impl std::error::Error for AtlasFullError {}
+
+impl std::fmt::Display for AtlasFullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "no space left in the glyph distance field atlas")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glyph_sdf, path_sdf, variations_key, winding, Atlas, Segment};
+    use crate::text::font::{self, Variation};
+    use crate::text::{rt, Scale};
+    use lyon::path::PathEvent;
+
+    // The segments of an axis-aligned square with the given corners, wound anticlockwise.
+    fn square(min: f32, max: f32) -> Vec<Segment> {
+        let [a, b] = [rt::point(min, min), rt::point(max, min)];
+        let [c, d] = [rt::point(max, max), rt::point(min, max)];
+        vec![[a, b], [b, c], [c, d], [d, a]]
+    }
+
+    // The path events of an axis-aligned square, leaving the contour to be closed implicitly.
+    fn square_path(min: f32, max: f32) -> Vec<PathEvent> {
+        let p = lyon::math::point;
+        let [a, b, c, d] = [p(min, min), p(max, min), p(max, max), p(min, max)];
+        vec![
+            PathEvent::Begin { at: a },
+            PathEvent::Line { from: a, to: b },
+            PathEvent::Line { from: b, to: c },
+            PathEvent::Line { from: c, to: d },
+            PathEvent::End {
+                last: d,
+                first: a,
+                close: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn winding_of_square() {
+        let segments = square(0.0, 10.0);
+        assert_eq!(winding(rt::point(5.0, 5.0), &segments), 1);
+        assert_eq!(winding(rt::point(15.0, 5.0), &segments), 0);
+        assert_eq!(winding(rt::point(5.0, -5.0), &segments), 0);
+
+        // Reversing the contour reverses the winding.
+        let reversed: Vec<Segment> = segments.iter().rev().map(|&[a, b]| [b, a]).collect();
+        assert_eq!(winding(rt::point(5.0, 5.0), &reversed), -1);
+
+        // A hole wound in the opposite direction cancels out the outer contour.
+        let mut with_hole = segments.clone();
+        with_hole.extend(square(2.0, 8.0).iter().rev().map(|&[a, b]| [b, a]));
+        assert_eq!(winding(rt::point(5.0, 5.0), &with_hole), 0);
+        assert_eq!(winding(rt::point(1.0, 5.0), &with_hole), 1);
+    }
+
+    #[test]
+    fn path_sdf_of_square() {
+        let spread = 2.0;
+        let sdf = path_sdf(square_path(0.0, 10.0), spread).unwrap();
+        assert_eq!([sdf.width, sdf.height], [14, 14]);
+        assert_eq!(sdf.data.len(), 14 * 14);
+        assert_eq!(sdf.bounds.left(), -2.0);
+        assert_eq!(sdf.bounds.bottom(), -2.0);
+        assert_eq!(sdf.bounds.right(), 12.0);
+        assert_eq!(sdf.bounds.top(), 12.0);
+
+        let texel = |col: usize, row: usize| sdf.data[row * sdf.width as usize + col];
+        // Texels further than `spread` inside or outside the edge are saturated.
+        assert_eq!(texel(7, 7), 255);
+        assert_eq!(texel(0, 0), 0);
+        // The texels either side of the edge straddle `0.5`.
+        let (outside, inside) = (texel(1, 7), texel(2, 7));
+        assert!(outside < 128 && inside > 128);
+        assert_eq!(outside as u32 + inside as u32, 255);
+    }
+
+    #[test]
+    fn path_sdf_of_empty_path() {
+        assert!(path_sdf(vec![], 2.0).is_none());
+    }
+
+    #[cfg(feature = "notosans")]
+    #[test]
+    fn glyph_sdf_of_notosans() {
+        let font = font::default_notosans();
+        let scale = Scale::uniform(48.0);
+        let spread = 4.0;
+        let glyph = font.glyph('A').scaled(scale);
+        let bb = glyph.exact_bounding_box().unwrap();
+        let sdf = glyph_sdf(&glyph, spread).unwrap();
+        assert_eq!(sdf.data.len(), (sdf.width * sdf.height) as usize);
+        // The bounds pad the glyph's bounding box, flipped so that *y* is up.
+        assert!(sdf.bounds.left() <= bb.min.x - spread);
+        assert!(sdf.bounds.right() >= bb.max.x + spread);
+        assert!(sdf.bounds.bottom() <= -bb.max.y - spread);
+        assert!(sdf.bounds.top() >= -bb.min.y + spread);
+        // The padding is entirely outside the glyph.
+        assert_eq!(sdf.data[0], 0);
+        assert!(sdf.data.iter().any(|&d| d > 128));
+
+        // Whitespace has no outline.
+        let space = font.glyph(' ').scaled(scale);
+        assert!(glyph_sdf(&space, spread).is_none());
+    }
+
+    #[test]
+    fn variations_keys() {
+        let key = |variations: &[Variation]| variations_key(variations);
+        let wght = |value| Variation::new(b"wght", value);
+        let wdth = |value| Variation::new(b"wdth", value);
+        assert_eq!(key(&[]), 0);
+        assert_eq!(key(&[wght(700.0)]), key(&[wght(700.0)]));
+        assert_ne!(key(&[wght(700.0)]), key(&[wght(701.0)]));
+        assert_ne!(key(&[wght(700.0)]), key(&[wdth(700.0)]));
+        assert_ne!(key(&[wght(700.0)]), key(&[wght(700.0), wdth(100.0)]));
+        assert_ne!(key(&[wght(700.0)]), 0);
+    }
+
+    #[test]
+    fn atlas_allocate_shelves() {
+        let mut atlas = Atlas::new([10, 10], 48.0, 4.0);
+        assert!(atlas.is_empty());
+        assert_eq!(atlas.allocate(4, 4).unwrap(), [0, 0]);
+        assert!(!atlas.is_empty());
+        // Fields are separated by a single texel.
+        assert_eq!(atlas.allocate(4, 3).unwrap(), [5, 0]);
+        // The next field does not fit within the shelf, so a new shelf begins below the tallest.
+        assert_eq!(atlas.allocate(4, 4).unwrap(), [0, 5]);
+        // Fields that do not fit horizontally or vertically are rejected.
+        assert!(atlas.allocate(11, 1).is_err());
+        assert!(atlas.allocate(4, 6).is_err());
+        // Failed allocations leave the atlas untouched.
+        assert_eq!(atlas.allocate(4, 5).unwrap(), [5, 5]);
+        atlas.clear();
+        assert!(atlas.is_empty());
+        assert_eq!(atlas.allocate(4, 4).unwrap(), [0, 0]);
+    }
+
+    #[cfg(feature = "notosans")]
+    #[test]
+    fn atlas_caches_entries() {
+        let font = font::default_notosans();
+        let font_id = font::id(&font);
+        let mut atlas = Atlas::new([256, 256], 24.0, 2.0);
+        let mut uploads = vec![];
+        let id = font.glyph('a').id();
+        let mut upload = |rect: rt::Rect<u32>, data: &[u8]| {
+            let (w, h) = (rect.max.x - rect.min.x, rect.max.y - rect.min.y);
+            assert_eq!(data.len(), (w * h) as usize);
+            uploads.push(rect);
+        };
+        let a = atlas
+            .get_or_insert(font_id, &font, id, &[], &mut upload)
+            .unwrap()
+            .unwrap();
+        let b = atlas
+            .get_or_insert(font_id, &font, id, &[], &mut upload)
+            .unwrap()
+            .unwrap();
+        assert_eq!(a, b);
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].min, rt::point(0, 0));
+        assert_eq!(a.uv.min, rt::point(0.0, 0.0));
+        assert_eq!(a.uv.max.x, uploads[0].max.x as f32 / 256.0);
+    }
+}
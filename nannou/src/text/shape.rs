@@ -0,0 +1,298 @@
+//! Shaping of lines of text into positioned glyphs.
+//!
+//! Rather than laying out glyphs one `char` at a time, each line of text is:
+//!
+//! 1. Split into runs of uniform direction and reordered for display via the Unicode
+//!    bidirectional algorithm.
+//! 2. Split into runs of uniform font via the font fallback chain, so that characters missing
+//!    from the primary font are displayed with the first fallback font that provides them.
+//!    Characters within a `text::span` may use their own primary font, in which case the layout's
+//!    fallback fonts are used for characters missing from it.
+//! 3. Shaped via `rustybuzz`, applying the OpenType ligatures, kerning, mark positioning and
+//!    contextual forms required by scripts like Arabic, Devanagari and Thai, along with any
+//!    additional OpenType features and font variations described by the shaping **Options**.
+
+use crate::geom::pt2;
+use crate::text::font::{Feature, Variation};
+use crate::text::span::Attrs;
+use crate::text::{self, Font, FontSize, GlyphId, Point, Scalar};
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_bidi::BidiInfo;
+
+/// A single glyph produced by shaping a line of text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glyph {
+    /// The index of the glyph's font within the fonts used to shape the line.
+    ///
+    /// `0` refers to the primary font, while all following indices refer to fallback fonts.
+    pub font: usize,
+    /// The ID of the glyph within its font.
+    pub id: GlyphId,
+    /// The font size at which the glyph is displayed.
+    pub font_size: FontSize,
+    /// The byte index within the line of the first character represented by the glyph.
+    pub byte: usize,
+    /// The char index within the line of the first character represented by the glyph.
+    pub char: usize,
+    /// The position of the glyph's origin relative to the start of the line's baseline.
+    ///
+    /// This includes the baseline offset of the glyph's span, if any.
+    pub position: Point,
+    /// The distance by which the glyph advances along the *x* axis.
+    pub advance: Scalar,
+    /// Whether the glyph belongs to a right-to-left run.
+    pub rtl: bool,
+}
+
+/// A single line of text shaped into glyphs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Line {
+    /// The glyphs within the line in visual order, from left to right.
+    pub glyphs: Vec<Glyph>,
+    /// The total advance width of all glyphs within the line.
+    pub width: Scalar,
+}
+
+/// OpenType features and font variations applied when shaping text.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Options<'a> {
+    /// Features applied to the whole line in addition to the script's default features.
+    pub features: &'a [Feature],
+    /// Values for the variation axes of variable fonts, applied to every font.
+    pub variations: &'a [Variation],
+}
+
+/// Shapes lines of text, reusing its buffers between lines.
+///
+/// Shaping many lines with the same **Shaper** avoids reallocating the buffers used during
+/// shaping for each line.
+#[derive(Debug, Default)]
+pub struct Shaper {
+    // The char index of each byte of the line.
+    chars: Vec<usize>,
+    features: Vec<rustybuzz::Feature>,
+    variations: Vec<rustybuzz::Variation>,
+    buffer: rustybuzz::UnicodeBuffer,
+}
+
+// A run of text with uniform direction, font, font size and baseline offset.
+struct Run {
+    bytes: Range<usize>,
+    font: usize,
+    font_size: FontSize,
+    baseline_offset: Scalar,
+    rtl: bool,
+}
+
+/// Shape the given line of text.
+///
+/// `fonts` describes the font fallback chain, where the first font is the primary font. Each
+/// character is displayed with the first font in the chain that contains a glyph for it,
+/// preferring the font of the preceding character so that runs are not split unnecessarily.
+///
+/// The text is expected to contain a single line. Paragraph separators (e.g. `\n`) are shaped
+/// like any other character.
+pub fn shape_line(text: &str, fonts: &[Font], font_size: FontSize) -> Line {
+    let attrs = Attrs {
+        font: 0,
+        font_size,
+        baseline_offset: 0.0,
+    };
+    let attrs = vec![attrs; text.len()];
+    shape_line_with_attrs(text, fonts, 1..fonts.len(), &attrs, Options::default())
+}
+
+/// The same as `shape_line`, but applies the given attributes to each character.
+///
+/// `attrs` must contain the attributes of each character within `text`, indexed by byte. These
+/// may be produced via `text::span::resolve`.
+///
+/// `fonts` contains every font referred to by `attrs`, while `fallbacks` is the range of `fonts`
+/// that describes the font fallback chain. Each character is displayed with the first font that
+/// contains a glyph for it, beginning with the character's own font followed by the fallbacks.
+///
+/// The features and variations of the given `options` are applied to every run. As glyph
+/// advances may depend on both, the same `options` should be used when measuring the text.
+///
+/// Use a **Shaper** to reuse the buffers used during shaping between many lines.
+pub fn shape_line_with_attrs(
+    text: &str,
+    fonts: &[Font],
+    fallbacks: Range<usize>,
+    attrs: &[Attrs],
+    options: Options,
+) -> Line {
+    Shaper::default().shape_line_with_attrs(text, fonts, fallbacks, attrs, options)
+}
+
+impl Shaper {
+    /// Shape the given line of text with the given attributes.
+    ///
+    /// See the `shape_line_with_attrs` function for details.
+    pub fn shape_line_with_attrs(
+        &mut self,
+        text: &str,
+        fonts: &[Font],
+        fallbacks: Range<usize>,
+        attrs: &[Attrs],
+        options: Options,
+    ) -> Line {
+        // Map the byte index of each char to its char index.
+        self.chars.clear();
+        self.chars.resize(text.len(), 0);
+        for (char_i, (byte_i, _)) in text.char_indices().enumerate() {
+            self.chars[byte_i] = char_i;
+        }
+
+        self.features.clear();
+        let features = options
+            .features
+            .iter()
+            .map(|&f| rustybuzz::Feature::from(f));
+        self.features.extend(features);
+        self.variations.clear();
+        let variations = options
+            .variations
+            .iter()
+            .map(|&v| rustybuzz::Variation::from(v));
+        self.variations.extend(variations);
+
+        let mut glyphs = vec![];
+        let mut x = 0.0;
+        for run in runs(text, fonts, fallbacks, attrs) {
+            let font = &fonts[run.font];
+            // Only copy the font's face when the variations must be applied to it.
+            let mut face = Cow::Borrowed(font.face());
+            if font.is_variable() && !self.variations.is_empty() {
+                face.to_mut().set_variations(&self.variations);
+            }
+            let mut buffer = std::mem::take(&mut self.buffer);
+            buffer.push_str(&text[run.bytes.clone()]);
+            buffer.set_direction(if run.rtl {
+                rustybuzz::Direction::RightToLeft
+            } else {
+                rustybuzz::Direction::LeftToRight
+            });
+            let output = rustybuzz::shape(&face, &self.features, buffer);
+
+            // Convert from font units to pixels in the same manner as rusttype.
+            let px = text::pt_to_px(run.font_size);
+            let v_metrics = font.v_metrics_unscaled();
+            let scale = px / (v_metrics.ascent - v_metrics.descent);
+            let infos = output.glyph_infos();
+            let positions = output.glyph_positions();
+            for (info, pos) in infos.iter().zip(positions) {
+                let byte = run.bytes.start + info.cluster as usize;
+                let position = pt2(
+                    x + pos.x_offset as Scalar * scale,
+                    pos.y_offset as Scalar * scale + run.baseline_offset,
+                );
+                let advance = pos.x_advance as Scalar * scale;
+                glyphs.push(Glyph {
+                    font: run.font,
+                    id: GlyphId(info.glyph_id),
+                    font_size: run.font_size,
+                    byte,
+                    char: self.chars[byte],
+                    position,
+                    advance,
+                    rtl: run.rtl,
+                });
+                x += advance;
+            }
+            self.buffer = output.clear();
+        }
+
+        Line { glyphs, width: x }
+    }
+}
+
+/// Select the font with which the given character should be displayed.
+///
+/// Returns the index of the font within the given font fallback chain.
+pub fn select_font(ch: char, prev: Option<usize>, fonts: &[Font]) -> usize {
+    select_font_from(ch, prev, fonts, 0, 1..fonts.len())
+}
+
+// Select the font for the given character from the chain beginning with the `primary` font and
+// followed by the `fallbacks`.
+fn select_font_from(
+    ch: char,
+    prev: Option<usize>,
+    fonts: &[Font],
+    primary: usize,
+    fallbacks: Range<usize>,
+) -> usize {
+    let in_chain = |ix: &usize| *ix == primary || fallbacks.contains(ix);
+    let prev = prev.filter(in_chain);
+    if let Some(prev) = prev {
+        if fonts[prev].has_glyph(ch) {
+            return prev;
+        }
+    }
+    // If no font has the glyph, avoid splitting the run.
+    std::iter::once(primary)
+        .chain(fallbacks)
+        .find(|&ix| fonts[ix].has_glyph(ch))
+        .or(prev)
+        .unwrap_or(primary)
+}
+
+// Split the text into runs of uniform direction, font and attributes in visual order.
+fn runs(text: &str, fonts: &[Font], fallbacks: Range<usize>, attrs: &[Attrs]) -> Vec<Run> {
+    let mut runs = vec![];
+    if text.is_empty() || fonts.is_empty() {
+        return runs;
+    }
+    let bidi = BidiInfo::new(text, None);
+    for para in &bidi.paragraphs {
+        let (levels, visual_runs) = bidi.visual_runs(para, para.range.clone());
+        for bytes in visual_runs {
+            let rtl = levels[bytes.start].is_rtl();
+            let mut font_runs = font_runs(text, bytes, fonts, fallbacks.clone(), attrs, rtl);
+            // Runs within a right-to-left run are displayed from right to left.
+            if rtl {
+                font_runs.reverse();
+            }
+            runs.extend(font_runs);
+        }
+    }
+    runs
+}
+
+// Split the given range of text into runs of uniform font and attributes in logical order.
+fn font_runs(
+    text: &str,
+    bytes: Range<usize>,
+    fonts: &[Font],
+    fallbacks: Range<usize>,
+    attrs: &[Attrs],
+    rtl: bool,
+) -> Vec<Run> {
+    let mut runs: Vec<Run> = vec![];
+    for (i, ch) in text[bytes.clone()].char_indices() {
+        let start = bytes.start + i;
+        let end = start + ch.len_utf8();
+        let a = attrs[start];
+        let prev = runs.last().map(|run| run.font);
+        let font = select_font_from(ch, prev, fonts, a.font, fallbacks.clone());
+        match runs.last_mut() {
+            Some(run)
+                if run.font == font
+                    && run.font_size == a.font_size
+                    && run.baseline_offset == a.baseline_offset =>
+            {
+                run.bytes.end = end
+            }
+            _ => runs.push(Run {
+                bytes: start..end,
+                font,
+                font_size: a.font_size,
+                baseline_offset: a.baseline_offset,
+                rtl,
+            }),
+        }
+    }
+    runs
+}
@@ -0,0 +1,167 @@
+//! Styling of individual byte ranges within some text.
+//!
+//! A **Span** applies its own font, font size and baseline offset to a range of the text, while
+//! the rest of the text is displayed with the **Layout**'s font and font size. Where spans
+//! overlap, the attributes of later spans take precedence.
+
+use crate::text::{font, Font, FontSize, Scalar};
+use std::ops::Range;
+
+/// The layout attributes applied to a byte range of some text.
+///
+/// Attributes that are `None` are inherited from the text's **Layout**.
+#[derive(Clone, Debug, Default)]
+pub struct Style {
+    /// The primary font used to display the range.
+    ///
+    /// Characters missing from this font are displayed via the layout's font fallback chain.
+    pub font: Option<Font>,
+    /// The font size of the range.
+    pub font_size: Option<FontSize>,
+    /// The distance by which the range is shifted up from the line's baseline.
+    ///
+    /// Negative values shift the range down, e.g. for subscripts.
+    pub baseline_offset: Option<Scalar>,
+}
+
+/// A **Style** applied to the given byte range of some text.
+#[derive(Clone, Debug)]
+pub struct Span {
+    /// The byte range of the text to which the style is applied.
+    pub range: Range<usize>,
+    pub style: Style,
+}
+
+/// The resolved layout attributes of a single character.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Attrs {
+    /// The index of the character's primary font within the text's fonts.
+    pub font: usize,
+    pub font_size: FontSize,
+    pub baseline_offset: Scalar,
+}
+
+/// The position and thickness of a line decoration such as an underline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LineMetrics {
+    /// The distance from the baseline to the centre of the line, where positive values are up.
+    pub offset: Scalar,
+    /// The thickness of the line.
+    pub thickness: Scalar,
+}
+
+impl Style {
+    /// Specify the primary font used to display the range.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Specify the font size of the range.
+    pub fn font_size(mut self, size: FontSize) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Shift the range up from the line's baseline by the given distance.
+    pub fn baseline_offset(mut self, offset: Scalar) -> Self {
+        self.baseline_offset = Some(offset);
+        self
+    }
+}
+
+/// Resolve the attributes of every character in the text, indexed by byte.
+///
+/// Characters outside of all spans use the font at index `0` and the given `font_size`. The font
+/// of each span is appended to `fonts` if it is not already present.
+///
+/// Span ranges are clamped to the length of the text.
+pub fn resolve(
+    text_len: usize,
+    spans: &[Span],
+    fonts: &mut Vec<Font>,
+    font_size: FontSize,
+) -> Vec<Attrs> {
+    let default = Attrs {
+        font: 0,
+        font_size,
+        baseline_offset: 0.0,
+    };
+    let mut attrs = vec![default; text_len];
+    for span in spans {
+        let font = span.style.font.as_ref().map(|f| {
+            let id = font::id(f);
+            match fonts.iter().position(|other| font::id(other) == id) {
+                Some(ix) => ix,
+                None => {
+                    fonts.push(f.clone());
+                    fonts.len() - 1
+                }
+            }
+        });
+        let end = std::cmp::min(span.range.end, text_len);
+        let start = std::cmp::min(span.range.start, end);
+        for a in &mut attrs[start..end] {
+            if let Some(font) = font {
+                a.font = font;
+            }
+            if let Some(font_size) = span.style.font_size {
+                a.font_size = font_size;
+            }
+            if let Some(offset) = span.style.baseline_offset {
+                a.baseline_offset = offset;
+            }
+        }
+    }
+    attrs
+}
+
+/// The height of a line whose characters have the given attributes.
+///
+/// This is the greatest font size of all characters within the line, or `font_size` if the line
+/// is empty.
+pub fn line_height(attrs: &[Attrs], font_size: FontSize) -> Scalar {
+    attrs.iter().map(|a| a.font_size).max().unwrap_or(font_size) as Scalar
+}
+
+/// The position and thickness of the underline for the given font and font size.
+///
+/// Falls back to a reasonable default if the font does not describe its underline.
+pub fn underline_metrics(font: &Font, font_size: FontSize) -> LineMetrics {
+    line_metrics(font, font_size, |face| face.underline_metrics()).unwrap_or_else(|| {
+        let px = crate::text::pt_to_px(font_size);
+        LineMetrics {
+            offset: -px * 0.1,
+            thickness: px / 15.0,
+        }
+    })
+}
+
+/// The position and thickness of the strikethrough for the given font and font size.
+///
+/// Falls back to a reasonable default if the font does not describe its strikethrough.
+pub fn strikethrough_metrics(font: &Font, font_size: FontSize) -> LineMetrics {
+    line_metrics(font, font_size, |face| face.strikeout_metrics()).unwrap_or_else(|| {
+        let px = crate::text::pt_to_px(font_size);
+        LineMetrics {
+            offset: px * 0.25,
+            thickness: px / 15.0,
+        }
+    })
+}
+
+// Read the metrics of some line decoration from the font, converting from font units to pixels
+// in the same manner as rusttype.
+fn line_metrics<F>(font: &Font, font_size: FontSize, metrics: F) -> Option<LineMetrics>
+where
+    F: FnOnce(&rustybuzz::Face) -> Option<rustybuzz::ttf_parser::LineMetrics>,
+{
+    let face = rustybuzz::Face::from_slice(font.data(), font.index())?;
+    let m = metrics(&face)?;
+    let v_metrics = font.v_metrics_unscaled();
+    let scale = crate::text::pt_to_px(font_size) / (v_metrics.ascent - v_metrics.descent);
+    let thickness = m.thickness as Scalar * scale;
+    // The position describes the top of the line, so offset to its centre.
+    let offset = m.position as Scalar * scale - thickness / 2.0;
+    Some(LineMetrics { offset, thickness })
+}
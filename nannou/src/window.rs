@@ -7,15 +7,17 @@ use crate::color::IntoLinSrgba;
 use crate::event::{
     Key, MouseButton, MouseScrollDelta, TouchEvent, TouchPhase, TouchpadPressure, WindowEvent,
 };
-use crate::frame::{self, Frame, RawFrame};
+use crate::frame::{self, Frame, RawFrame, RecordingConfig, RecordingError};
 use crate::geom;
 use crate::geom::Point2;
 use crate::glam::Vec2;
 use crate::wgpu;
 use crate::App;
 use std::any::Any;
+use std::cell::Cell;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{self, AtomicU64};
+use std::sync::{Arc, MutexGuard};
 use std::time::Duration;
 use std::{env, fmt};
 use wgpu_upstream::CompositeAlphaMode;
@@ -53,6 +55,7 @@ pub struct Builder<'app> {
     max_capture_frame_jobs: u32,
     capture_frame_timeout: Option<Duration>,
     clear_color: Option<wgpu::Color>,
+    offscreen: bool,
 }
 
 /// For storing all user functions within the window.
@@ -239,10 +242,16 @@ fn_any!(ClosedFn<M>, ClosedFnAny);
 ///
 /// The **Window** acts as a wrapper around the `winit::window::Window` and the `wgpu::Surface`
 /// types.
+///
+/// Alternatively, an *offscreen* window renders to a `wgpu::Texture` without creating a winit
+/// window or wgpu surface. See `Builder::offscreen`. Methods that manipulate the platform window
+/// (e.g. `set_title`, `set_fullscreen`) have no effect on offscreen windows, while the
+/// `set_inner_size_*` methods resize the offscreen texture.
 #[derive(Debug)]
 pub struct Window {
-    pub(crate) window: winit::window::Window,
-    pub(crate) surface: wgpu::Surface,
+    pub(crate) id: Id,
+    pub(crate) target: Target,
+    // For offscreen windows, this describes the offscreen texture.
     pub(crate) surface_conf: wgpu::SurfaceConfiguration,
     pub(crate) device_queue_pair: Arc<wgpu::DeviceQueuePair>,
     msaa_samples: u32,
@@ -254,6 +263,24 @@ pub struct Window {
     pub(crate) clear_color: wgpu::Color,
 }
 
+// The destination of the frames rendered for a window.
+#[derive(Debug)]
+pub(crate) enum Target {
+    // A window on the display and its wgpu surface.
+    Surface {
+        window: winit::window::Window,
+        surface: wgpu::Surface,
+    },
+    // A texture rendered to without any windowing system.
+    Offscreen {
+        texture: wgpu::Texture,
+        // Whether or not a redraw has been requested since the last frame.
+        redraw_requested: bool,
+        // The size in pixels requested via `set_inner_size_*`, applied before the next redraw.
+        requested_size: Cell<Option<[u32; 2]>>,
+    },
+}
+
 // Data related to `Frame`s produced for this window's surface textures.
 #[derive(Debug)]
 pub(crate) struct FrameData {
@@ -289,6 +316,13 @@ impl SurfaceConfigurationBuilder {
     pub const DEFAULT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
     pub const DEFAULT_PRESENT_MODE: wgpu::PresentMode = wgpu::PresentMode::Fifo;
     pub const DEFAULT_USAGE: wgpu::TextureUsages = wgpu::TextureUsages::RENDER_ATTACHMENT;
+    /// The default texture format used for offscreen windows.
+    pub const DEFAULT_OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+    /// The default texture usages for offscreen windows, allowing the texture to be sampled and
+    /// read back after rendering.
+    pub const DEFAULT_OFFSCREEN_USAGE: wgpu::TextureUsages = wgpu::TextureUsages::RENDER_ATTACHMENT
+        .union(wgpu::TextureUsages::TEXTURE_BINDING)
+        .union(wgpu::TextureUsages::COPY_SRC);
 
     /// A new empty **SurfaceConfigurationBuilder** with all parameters set to `None`.
     pub fn new() -> Self {
@@ -353,6 +387,25 @@ impl SurfaceConfigurationBuilder {
             view_formats: Vec::new(),
         }
     }
+
+    /// Build the configuration describing an offscreen window's texture.
+    pub(crate) fn build_offscreen(
+        self,
+        [width_px, height_px]: [u32; 2],
+    ) -> wgpu::SurfaceConfiguration {
+        let usage = self.usage.unwrap_or(Self::DEFAULT_OFFSCREEN_USAGE);
+        let format = self.format.unwrap_or(Self::DEFAULT_OFFSCREEN_FORMAT);
+        let present_mode = self.present_mode.unwrap_or(Self::DEFAULT_PRESENT_MODE);
+        wgpu::SurfaceConfiguration {
+            usage,
+            format,
+            width: width_px,
+            height: height_px,
+            present_mode,
+            alpha_mode: CompositeAlphaMode::Auto,
+            view_formats: Vec::new(),
+        }
+    }
 }
 
 impl<'app> Builder<'app> {
@@ -376,6 +429,7 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs: Default::default(),
             capture_frame_timeout: Default::default(),
             clear_color: None,
+            offscreen: app.is_headless(),
         }
     }
 
@@ -385,6 +439,20 @@ impl<'app> Builder<'app> {
         self
     }
 
+    /// Build an offscreen window that renders to a texture of the given size in pixels rather than
+    /// to the surface of a window on the display.
+    ///
+    /// No winit window or wgpu surface is created. The window's `view` function is called
+    /// following each update as usual and `capture_frame` may be used to write frames to disk.
+    /// The resulting texture may also be accessed via `Window::offscreen_texture`. The scale
+    /// factor of offscreen windows is always `1.0`.
+    ///
+    /// All windows built for a headless **App** (see `app::Builder::headless`) are offscreen.
+    pub fn offscreen(mut self, width: u32, height: u32) -> Self {
+        self.offscreen = true;
+        self.size_pixels(width, height)
+    }
+
     /// Specify a set of parameters for building the window surface.
     pub fn surface_conf_builder(
         mut self,
@@ -760,6 +828,7 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs,
             capture_frame_timeout,
             clear_color,
+            offscreen,
         } = self;
 
         // If the title was not set, default to the "nannou - <exe_name>".
@@ -888,60 +957,112 @@ impl<'app> Builder<'app> {
             color
         });
 
-        // Build the window.
-        let window = {
-            let window_target = app
-                .event_loop_window_target
-                .as_ref()
-                .expect("unexpected invalid App.event_loop_window_target state - please report")
-                .as_ref();
-            window.build(window_target)?
-        };
+        // Build the winit window, unless this is an offscreen window.
+        let winit_window = match offscreen {
+            true => None,
+            false => {
+                let window_target = app
+                    .event_loop_window_target
+                    .as_ref()
+                    .expect("unexpected invalid App.event_loop_window_target state - please report")
+                    .as_ref();
+                let window = window.build(window_target)?;
+
+                #[cfg(target_arch = "wasm32")]
+                {
+                    use winit::platform::web::WindowExtWebSys;
+                    let canvas = window.canvas();
+
+                    web_sys::window()
+                        .expect("window")
+                        .document()
+                        .expect("document")
+                        .body()
+                        .expect("body")
+                        .append_child(&canvas)
+                        .expect("append_child");
+                }
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            use winit::platform::web::WindowExtWebSys;
-            let canvas = window.canvas();
-
-            web_sys::window()
-                .expect("window")
-                .document()
-                .expect("document")
-                .body()
-                .expect("body")
-                .append_child(&canvas)
-                .expect("append_child");
-        }
+                Some(window)
+            }
+        };
 
         // Build the wgpu surface.
-        let surface = unsafe {
+        let surface = winit_window.as_ref().map(|window| unsafe {
             app.instance()
-                .create_surface(&window)
+                .create_surface(window)
                 .expect("Could not create surface")
-        };
+        });
 
         // Request the adapter.
         let request_adapter_opts = wgpu::RequestAdapterOptions {
             power_preference,
-            compatible_surface: Some(&surface),
+            compatible_surface: surface.as_ref(),
             force_fallback_adapter,
         };
-        let adapter = app
+        let adapter = match app
             .wgpu_adapters()
             .get_or_request_async(request_adapter_opts, app.instance())
             .await
-            .ok_or(BuildError::NoAvailableAdapter)?;
+        {
+            Some(adapter) => adapter,
+            // Offscreen windows fall back to a software adapter if there is no other available,
+            // e.g. on servers without a GPU.
+            None if offscreen && !force_fallback_adapter => {
+                let request_adapter_opts = wgpu::RequestAdapterOptions {
+                    power_preference,
+                    compatible_surface: None,
+                    force_fallback_adapter: true,
+                };
+                app.wgpu_adapters()
+                    .get_or_request_async(request_adapter_opts, app.instance())
+                    .await
+                    .ok_or(BuildError::NoAvailableAdapter)?
+            }
+            None => return Err(BuildError::NoAvailableAdapter),
+        };
 
         // Instantiate the logical device.
         let device_desc = device_desc.unwrap_or_else(wgpu::default_device_descriptor);
         let device_queue_pair = adapter.get_or_request_device_async(device_desc).await;
 
-        // Configure the surface.
-        let win_physical_size = window.inner_size();
-        let win_dims_px: [u32; 2] = win_physical_size.into();
+        // Configure the surface, or create the offscreen texture.
         let device = device_queue_pair.device();
-        let surface_conf = surface_conf_builder.build(&surface, &*adapter, win_dims_px);
-        surface.configure(&device, &surface_conf);
+        let (window_id, target, surface_conf, tracked_state) = match (winit_window, surface) {
+            (Some(window), Some(surface)) => {
+                let win_physical_size = window.inner_size();
+                let win_dims_px: [u32; 2] = win_physical_size.into();
+                let surface_conf = surface_conf_builder.build(&surface, &*adapter, win_dims_px);
+                surface.configure(&device, &surface_conf);
+                let tracked_state = TrackedState {
+                    scale_factor: window.scale_factor(),
+                    physical_size: win_physical_size,
+                };
+                let window_id = window.id();
+                let target = Target::Surface { window, surface };
+                (window_id, target, surface_conf, tracked_state)
+            }
+            _ => {
+                let scale_factor = 1.0;
+                let size = initial_window_size.to_physical::<u32>(scale_factor);
+                let physical_size = PhysicalSize {
+                    width: size.width.max(MIN_SC_PIXELS.width),
+                    height: size.height.max(MIN_SC_PIXELS.height),
+                };
+                let surface_conf = surface_conf_builder.build_offscreen(physical_size.into());
+                let texture = create_offscreen_texture(device, &surface_conf);
+                let tracked_state = TrackedState {
+                    scale_factor,
+                    physical_size,
+                };
+                let target = Target::Offscreen {
+                    texture,
+                    redraw_requested: false,
+                    requested_size: Cell::new(None),
+                };
+                (next_offscreen_id(), target, surface_conf, tracked_state)
+            }
+        };
 
         // If we're using an intermediary image for rendering frames to surface textures, create
         // the necessary render data.
@@ -964,17 +1085,11 @@ impl<'app> Builder<'app> {
             Some(View::WithModelRaw(_)) => (None, 1),
         };
 
-        let window_id = window.id();
         let frame_count = 0;
 
-        let tracked_state = TrackedState {
-            scale_factor: window.scale_factor(),
-            physical_size: win_physical_size,
-        };
-
         let window = Window {
-            window,
-            surface,
+            id: window_id,
+            target,
             surface_conf,
             device_queue_pair,
             msaa_samples,
@@ -1012,6 +1127,7 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs,
             capture_frame_timeout,
             clear_color,
+            offscreen,
         } = self;
         let window = map(window);
         Builder {
@@ -1027,6 +1143,7 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs,
             capture_frame_timeout,
             clear_color,
+            offscreen,
         }
     }
 
@@ -1135,7 +1252,7 @@ impl Window {
 
     /// A unique identifier associated with this window.
     pub fn id(&self) -> Id {
-        self.window.id()
+        self.id
     }
 
     /// Returns the scale factor that can be used to map logical pixels to physical pixels and vice
@@ -1158,7 +1275,7 @@ impl Window {
     /// - **iOS:** Can only be called on the main thread. Returns the underlying `UiView`'s
     ///   `contentScaleFactor`.
     pub fn scale_factor(&self) -> geom::scalar::Default {
-        self.window.scale_factor() as _
+        self.tracked_state.scale_factor as _
     }
 
     /// The position of the top-left hand corner of the window relative to the top-left hand corner
@@ -1177,7 +1294,10 @@ impl Window {
     /// window in the screen space coordinate system.
     /// - **Web:** Returns the top-left coordinates relative to the viewport.
     pub fn outer_position_pixels(&self) -> Result<(i32, i32), winit::error::NotSupportedError> {
-        self.window.outer_position().map(Into::into)
+        match self.winit() {
+            Some(window) => window.outer_position().map(Into::into),
+            None => Ok((0, 0)),
+        }
     }
 
     /// Modifies the position of the window.
@@ -1191,15 +1311,19 @@ impl Window {
     ///   window in the screen space coordinate system.
     /// - **Web:** Sets the top-left coordinates relative to the viewport.
     pub fn set_outer_position_pixels(&self, x: i32, y: i32) {
-        self.window
-            .set_outer_position(winit::dpi::PhysicalPosition { x, y })
+        if let Some(window) = self.winit() {
+            window.set_outer_position(winit::dpi::PhysicalPosition { x, y })
+        }
     }
 
     /// The width and height in pixels of the client area of the window.
     ///
     /// The client area is the content of the window, excluding the title bar and borders.
     pub fn inner_size_pixels(&self) -> (u32, u32) {
-        self.window.inner_size().into()
+        match self.winit() {
+            Some(window) => window.inner_size().into(),
+            None => self.tracked_state.physical_size.into(),
+        }
     }
 
     /// The width and height in points of the client area of the window.
@@ -1208,8 +1332,8 @@ impl Window {
     ///
     /// This is the same as dividing the result  of `inner_size_pixels()` by `scale_factor()`.
     pub fn inner_size_points(&self) -> (geom::scalar::Default, geom::scalar::Default) {
-        self.window
-            .inner_size()
+        let (w_px, h_px) = self.inner_size_pixels();
+        winit::dpi::PhysicalSize::new(w_px, h_px)
             .to_logical::<f32>(self.tracked_state.scale_factor)
             .into()
     }
@@ -1217,17 +1341,37 @@ impl Window {
     /// Modifies the inner size of the window.
     ///
     /// See the `inner_size` methods for more informations about the values.
+    ///
+    /// The offscreen texture of an offscreen window is recreated at the new size before the
+    /// window is next redrawn.
     pub fn set_inner_size_pixels(&self, width: u32, height: u32) {
-        self.window
-            .set_inner_size(winit::dpi::PhysicalSize { width, height })
+        match self.target {
+            Target::Surface { ref window, .. } => {
+                window.set_inner_size(winit::dpi::PhysicalSize { width, height })
+            }
+            Target::Offscreen {
+                ref requested_size, ..
+            } => requested_size.set(Some([width, height])),
+        }
     }
 
     /// Modifies the inner size of the window using point values.
     ///
     /// See the `inner_size` methods for more informations about the values.
+    ///
+    /// The offscreen texture of an offscreen window is recreated at the new size before the
+    /// window is next redrawn.
     pub fn set_inner_size_points(&self, width: f32, height: f32) {
-        self.window
-            .set_inner_size(winit::dpi::LogicalSize { width, height })
+        match self.target {
+            Target::Surface { ref window, .. } => {
+                window.set_inner_size(winit::dpi::LogicalSize { width, height })
+            }
+            Target::Offscreen { .. } => {
+                let size = winit::dpi::LogicalSize { width, height }
+                    .to_physical::<u32>(self.tracked_state.scale_factor);
+                self.set_inner_size_pixels(size.width, size.height);
+            }
+        }
     }
 
     /// The width and height of the window in pixels.
@@ -1235,7 +1379,10 @@ impl Window {
     /// These dimensions include title bar and borders. If you don't want these, you should use
     /// `inner_size_pixels` instead.
     pub fn outer_size_pixels(&self) -> (u32, u32) {
-        self.window.outer_size().into()
+        match self.winit() {
+            Some(window) => window.outer_size().into(),
+            None => self.tracked_state.physical_size.into(),
+        }
     }
 
     /// The width and height of the window in points.
@@ -1245,8 +1392,8 @@ impl Window {
     ///
     /// This is the same as dividing the result  of `outer_size_pixels()` by `scale_factor()`.
     pub fn outer_size_points(&self) -> (f32, f32) {
-        self.window
-            .outer_size()
+        let (w_px, h_px) = self.outer_size_pixels();
+        winit::dpi::PhysicalSize::new(w_px, h_px)
             .to_logical::<f32>(self.tracked_state.scale_factor)
             .into()
     }
@@ -1254,20 +1401,26 @@ impl Window {
     /// Sets a minimum size for the window.
     pub fn set_min_inner_size_points(&self, size: Option<(f32, f32)>) {
         let size = size.map(|(width, height)| winit::dpi::LogicalSize { width, height });
-        self.window.set_min_inner_size(size)
+        if let Some(window) = self.winit() {
+            window.set_min_inner_size(size);
+        }
     }
 
     /// Sets a maximum size for the window.
     pub fn set_max_inner_size_points(&self, size: Option<(f32, f32)>) {
         let size = size.map(|(width, height)| winit::dpi::LogicalSize { width, height });
-        self.window.set_max_inner_size(size)
+        if let Some(window) = self.winit() {
+            window.set_max_inner_size(size);
+        }
     }
 
     /// Modifies the title of the window.
     ///
     /// This is a no-op if the window has already been closed.
     pub fn set_title(&self, title: &str) {
-        self.window.set_title(title);
+        if let Some(window) = self.winit() {
+            window.set_title(title);
+        }
     }
 
     /// Set the visibility of the window.
@@ -1278,7 +1431,9 @@ impl Window {
     /// - iOS: Can only be called on the main thread.
     /// - Web: Has no effect.
     pub fn set_visible(&self, visible: bool) {
-        self.window.set_visible(visible)
+        if let Some(window) = self.winit() {
+            window.set_visible(visible);
+        }
     }
 
     /// Sets whether the window is resizable or not.
@@ -1286,17 +1441,23 @@ impl Window {
     /// Note that making the window unresizable doesn't exempt you from handling **Resized**, as
     /// that event can still be triggered by DPI scaling, entering fullscreen mode, etc.
     pub fn set_resizable(&self, resizable: bool) {
-        self.window.set_resizable(resizable)
+        if let Some(window) = self.winit() {
+            window.set_resizable(resizable);
+        }
     }
 
     /// Sets the window to minimized or back.
     pub fn set_minimized(&self, minimized: bool) {
-        self.window.set_minimized(minimized)
+        if let Some(window) = self.winit() {
+            window.set_minimized(minimized);
+        }
     }
 
     /// Sets the window to maximized or back.
     pub fn set_maximized(&self, maximized: bool) {
-        self.window.set_maximized(maximized)
+        if let Some(window) = self.winit() {
+            window.set_maximized(maximized);
+        }
     }
 
     /// Set the window to fullscreen on the primary monitor.
@@ -1336,7 +1497,9 @@ impl Window {
     /// - Wayland: Does not support exclusive fullscreen mode.
     /// - Windows: Screen saver is disabled in fullscreen mode.
     pub fn set_fullscreen_with(&self, fullscreen: Option<Fullscreen>) {
-        self.window.set_fullscreen(fullscreen)
+        if let Some(window) = self.winit() {
+            window.set_fullscreen(fullscreen);
+        }
     }
 
     /// Gets the window's current fullscreen state.
@@ -1345,7 +1508,7 @@ impl Window {
     ///
     /// - **iOS:** Can only be called on the main thread.
     pub fn fullscreen(&self) -> Option<Fullscreen> {
-        self.window.fullscreen()
+        self.winit().and_then(|window| window.fullscreen())
     }
 
     /// Turn window decorations on or off.
@@ -1356,16 +1519,20 @@ impl Window {
     ///   via `setPrefersStatusBarHidden`.
     /// - **Web:** Has no effect.
     pub fn set_decorations(&self, decorations: bool) {
-        self.window.set_decorations(decorations)
+        if let Some(window) = self.winit() {
+            window.set_decorations(decorations);
+        }
     }
 
     /// Change whether or not the window will always be on top of other windows.
     pub fn set_always_on_top(&self, always_on_top: bool) {
-        self.window.set_window_level(if always_on_top {
-            WindowLevel::AlwaysOnTop
-        } else {
-            WindowLevel::Normal
-        })
+        if let Some(window) = self.winit() {
+            window.set_window_level(if always_on_top {
+                WindowLevel::AlwaysOnTop
+            } else {
+                WindowLevel::Normal
+            })
+        }
     }
 
     /// Sets the window icon. On Windows and X11, this is typically the small icon in the top-left
@@ -1381,7 +1548,9 @@ impl Window {
     /// X11 has no universal guidelines for icon sizes, so you're at the whims of the WM. That
     /// said, it's usually in the same ballpark as on Windows.
     pub fn set_window_icon(&self, window_icon: Option<winit::window::Icon>) {
-        self.window.set_window_icon(window_icon)
+        if let Some(window) = self.winit() {
+            window.set_window_icon(window_icon);
+        }
     }
 
     /// Sets the location of IME candidate box in client area coordinates relative to the top left.
@@ -1391,8 +1560,9 @@ impl Window {
     /// - **iOS:** Has no effect.
     /// - **Web:** Has no effect.
     pub fn set_ime_position_points(&self, x: f32, y: f32) {
-        self.window
-            .set_ime_position(winit::dpi::LogicalPosition { x, y })
+        if let Some(window) = self.winit() {
+            window.set_ime_position(winit::dpi::LogicalPosition { x, y })
+        }
     }
 
     /// Modifies the mouse cursor of the window.
@@ -1402,7 +1572,9 @@ impl Window {
     /// - **iOS:** Has no effect.
     /// - **Android:** Has no effect.
     pub fn set_cursor_icon(&self, state: winit::window::CursorIcon) {
-        self.window.set_cursor_icon(state);
+        if let Some(window) = self.winit() {
+            window.set_cursor_icon(state);
+        }
     }
 
     /// Changes the position of the cursor in logical window coordinates.
@@ -1416,8 +1588,10 @@ impl Window {
         x: f32,
         y: f32,
     ) -> Result<(), winit::error::ExternalError> {
-        self.window
-            .set_cursor_position(winit::dpi::LogicalPosition { x, y })
+        match self.winit() {
+            Some(window) => window.set_cursor_position(winit::dpi::LogicalPosition { x, y }),
+            None => Ok(()),
+        }
     }
 
     /// Grabs the cursor, preventing it from leaving the window.
@@ -1430,11 +1604,14 @@ impl Window {
     /// - **iOS:** Always returns an Err.
     /// - **Web:** Has no effect.
     pub fn set_cursor_grab(&self, grab: bool) -> Result<(), winit::error::ExternalError> {
-        self.window.set_cursor_grab(if grab {
-            CursorGrabMode::Locked
-        } else {
-            CursorGrabMode::None
-        })
+        match self.winit() {
+            Some(window) => window.set_cursor_grab(if grab {
+                CursorGrabMode::Locked
+            } else {
+                CursorGrabMode::None
+            }),
+            None => Ok(()),
+        }
     }
 
     /// Set the cursor's visibility.
@@ -1451,27 +1628,51 @@ impl Window {
     ///
     /// This has no effect on **Android** or **iOS**.
     pub fn set_cursor_visible(&self, visible: bool) {
-        self.window.set_cursor_visible(visible)
+        if let Some(window) = self.winit() {
+            window.set_cursor_visible(visible);
+        }
     }
 
     /// The current monitor that the window is, on or the primary monitor if nothing matches.
     /// If there's neither a current nor a primary monitor, returns none.
     pub fn current_monitor(&self) -> Option<winit::monitor::MonitorHandle> {
-        self.window.current_monitor()
+        self.winit().and_then(|window| window.current_monitor())
     }
 
     // Access to wgpu API.
 
     /// Returns a reference to the window's wgpu surface.
-    pub fn surface(&self) -> &wgpu::Surface {
-        &self.surface
+    ///
+    /// Returns `None` if this is an offscreen window.
+    pub fn surface(&self) -> Option<&wgpu::Surface> {
+        match self.target {
+            Target::Surface { ref surface, .. } => Some(surface),
+            Target::Offscreen { .. } => None,
+        }
     }
 
     /// The current configuration of the window's wgpu surface.
+    ///
+    /// For offscreen windows, this describes the offscreen texture.
     pub fn surface_configuration(&self) -> &wgpu::SurfaceConfiguration {
         &self.surface_conf
     }
 
+    /// The texture to which an offscreen window's frames are rendered.
+    ///
+    /// Returns `None` if this is not an offscreen window.
+    pub fn offscreen_texture(&self) -> Option<&wgpu::Texture> {
+        match self.target {
+            Target::Offscreen { ref texture, .. } => Some(texture),
+            Target::Surface { .. } => None,
+        }
+    }
+
+    /// Whether or not this window renders to an offscreen texture rather than to the display.
+    pub fn is_offscreen(&self) -> bool {
+        matches!(self.target, Target::Offscreen { .. })
+    }
+
     /// The wgpu logical device on which the window's wgpu surface is running.
     pub fn device(&self) -> &wgpu::Device {
         self.device_queue_pair.device()
@@ -1510,7 +1711,13 @@ impl Window {
         self.tracked_state.physical_size.height = h_px.max(MIN_SC_PIXELS.height);
         self.surface_conf.width = self.tracked_state.physical_size.width;
         self.surface_conf.height = self.tracked_state.physical_size.height;
-        self.surface.configure(self.device(), &self.surface_conf);
+        let device = self.device_queue_pair.device();
+        match self.target {
+            Target::Surface { ref surface, .. } => surface.configure(device, &self.surface_conf),
+            Target::Offscreen {
+                ref mut texture, ..
+            } => *texture = create_offscreen_texture(device, &self.surface_conf),
+        }
         if self.frame_data.is_some() {
             let render_data = frame::RenderData::new(
                 self.device(),
@@ -1560,11 +1767,122 @@ impl Window {
         self.capture_frame_inner(path.as_ref());
     }
 
+    /// Begin recording each frame drawn to this window to a video or animated image file at the
+    /// given path.
+    ///
+    /// Frames are captured as they are submitted and streamed to an encoder on a dedicated
+    /// thread. By default, the encoder is selected by the file extension: `gif` files are encoded
+    /// in-process while all other formats (e.g. `mp4`, `webm`) are encoded by an `ffmpeg`
+    /// subprocess. See **RecordingConfig** for frame rate, encoder and fixed timestep options.
+    ///
+    /// Frames are recorded at the window's size at the moment recording begins. If the window is
+    /// resized, subsequent frames are scaled to fit.
+    ///
+    /// If a recording is already in progress, it is stopped first.
+    ///
+    /// **Panics** if `view` draws to a `RawFrame` rather than a `Frame`.
+    pub fn start_recording<P>(&self, path: P, config: RecordingConfig) -> Result<(), RecordingError>
+    where
+        P: AsRef<Path>,
+    {
+        self.stop_recording()?;
+        let size = [self.surface_conf.width, self.surface_conf.height];
+        let recorder = frame::recording::Recorder::new(path.as_ref(), config, size)?;
+        *self.recorder() = Some(recorder);
+        Ok(())
+    }
+
+    /// Stop the active recording, if any.
+    ///
+    /// Blocks until all recorded frames have been written and the encoder has finished. Any
+    /// error that occurred while encoding is returned.
+    ///
+    /// This is called implicitly when the window is dropped.
+    pub fn stop_recording(&self) -> Result<(), RecordingError> {
+        let recorder = match self.frame_data {
+            None => None,
+            Some(_) => self.recorder().take(),
+        };
+        match recorder {
+            None => Ok(()),
+            Some(recorder) => {
+                // Ensure all in-flight frames have been delivered to the encoder.
+                if self.await_capture_frame_jobs().is_err() {
+                    // TODO: Replace eprintlns with proper logging.
+                    eprintln!("timed out while waiting for capture jobs to complete");
+                }
+                recorder.finish()
+            }
+        }
+    }
+
+    /// Whether or not this window is currently recording.
+    pub fn is_recording(&self) -> bool {
+        self.frame_data.is_some() && self.recorder().is_some()
+    }
+
+    /// The number of frames dropped from the active recording, if any.
+    ///
+    /// In real-time recordings, captured frames are queued for the encoder without blocking the
+    /// app. If the encoder falls behind and its queue is full, frames are dropped and the previous
+    /// frame is held in their place. Recordings on a fixed timestep never drop frames.
+    pub fn recording_dropped_frames(&self) -> Option<u64> {
+        self.frame_data.as_ref()?;
+        let recorder = self.recorder();
+        recorder.as_ref().map(|rec| rec.dropped_frames())
+    }
+
+    // The frame interval of the active recording if it requires a fixed timestep.
+    pub(crate) fn fixed_recording_interval(&self) -> Option<Duration> {
+        self.frame_data.as_ref()?;
+        let recorder = self.recorder();
+        let config = recorder.as_ref()?.config();
+        match config.fixed_timestep {
+            true => Some(config.frame_interval()),
+            false => None,
+        }
+    }
+
     /// Produces a reference to the inner winit window.
     ///
     /// This is sometimes useful for integration with other winit-aware libraries (e.g. UI).
-    pub fn winit_window(&self) -> &winit::window::Window {
-        &self.window
+    ///
+    /// Returns `None` if this is an offscreen window.
+    pub fn winit_window(&self) -> Option<&winit::window::Window> {
+        self.winit()
+    }
+
+    // The inner winit window, or `None` if this is an offscreen window.
+    pub(crate) fn winit(&self) -> Option<&winit::window::Window> {
+        match self.target {
+            Target::Surface { ref window, .. } => Some(window),
+            Target::Offscreen { .. } => None,
+        }
+    }
+
+    // Recreate the offscreen texture at the size requested via `set_inner_size_*`, if any.
+    pub(crate) fn apply_requested_size(&mut self) {
+        let size = match self.target {
+            Target::Offscreen {
+                ref requested_size, ..
+            } => requested_size.take(),
+            Target::Surface { .. } => None,
+        };
+        if let Some(size) = size {
+            self.reconfigure_surface(size);
+            self.request_redraw();
+        }
+    }
+
+    // Request that the window be redrawn following the current loop iteration.
+    pub(crate) fn request_redraw(&mut self) {
+        match self.target {
+            Target::Surface { ref window, .. } => window.request_redraw(),
+            Target::Offscreen {
+                ref mut redraw_requested,
+                ..
+            } => *redraw_requested = true,
+        }
     }
 
     fn capture_frame_inner(&self, path: &Path) {
@@ -1585,6 +1903,16 @@ impl Window {
         *capture_next_frame_path = Some(path.to_path_buf());
     }
 
+    fn recorder(&self) -> MutexGuard<'_, Option<frame::recording::Recorder>> {
+        self.frame_data
+            .as_ref()
+            .expect("window recording requires that `view` draws to a `Frame` (not a `RawFrame`)")
+            .capture
+            .recorder
+            .lock()
+            .expect("failed to lock `recorder`")
+    }
+
     /// Block and wait for all active capture frame jobs to complete.
     ///
     /// This is called implicitly when the window is dropped to ensure any pending captures
@@ -1601,10 +1929,35 @@ impl Window {
     }
 }
 
+// Produce a unique ID for an offscreen window.
+//
+// IDs are allocated downwards from `u64::MAX` in order to avoid colliding with those allocated by
+// the platform for winit windows.
+fn next_offscreen_id() -> Id {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(u64::MAX);
+    Id::from(NEXT_ID.fetch_sub(1, atomic::Ordering::Relaxed))
+}
+
+// Create the texture to which an offscreen window's frames are rendered.
+fn create_offscreen_texture(
+    device: &wgpu::Device,
+    conf: &wgpu::SurfaceConfiguration,
+) -> wgpu::Texture {
+    wgpu::TextureBuilder::new()
+        .size([conf.width, conf.height])
+        .format(conf.format)
+        .usage(conf.usage)
+        .build(device)
+}
+
 // Drop implementations.
 
 impl Drop for Window {
     fn drop(&mut self) {
+        if let Err(e) = self.stop_recording() {
+            // TODO: Replace eprintlns with proper logging.
+            eprintln!("failed to stop recording: {}", e);
+        }
         if self.await_capture_frame_jobs().is_err() {
             // TODO: Replace eprintlns with proper logging.
             eprintln!("timed out while waiting for capture jobs to complete");
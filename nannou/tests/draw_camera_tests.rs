@@ -0,0 +1,28 @@
+use nannou::draw::Camera;
+use nannou::prelude::*;
+
+fn assert_approx_eq(a: Vec3, b: Vec3) {
+    assert!((a - b).abs().max_element() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn orthographic_camera_spans_output() {
+    let size = vec2(800.0, 600.0);
+    let view_proj = Camera::default().view_projection(size);
+    let corner = view_proj.project_point3(vec3(400.0, 300.0, 0.0));
+    assert_approx_eq(corner, vec3(1.0, 1.0, 0.5));
+    let near = view_proj.project_point3(vec3(0.0, 0.0, 800.0));
+    assert_approx_eq(near, vec3(0.0, 0.0, 0.0));
+}
+
+#[test]
+fn perspective_camera_matches_orthographic_at_z_zero() {
+    let size = vec2(800.0, 600.0);
+    let ortho = Camera::orthographic().view_projection(size);
+    let persp = Camera::perspective(Camera::DEFAULT_FOV_Y).view_projection(size);
+    for &p in &[vec3(400.0, 300.0, 0.0), vec3(-120.0, 45.0, 0.0)] {
+        let a = ortho.project_point3(p);
+        let b = persp.project_point3(p);
+        assert_approx_eq(a.truncate().extend(0.0), b.truncate().extend(0.0));
+    }
+}
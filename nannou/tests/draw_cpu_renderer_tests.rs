@@ -0,0 +1,103 @@
+use nannou::draw::CpuRenderer;
+use nannou::image::{Rgba, RgbaImage};
+use nannou::prelude::*;
+
+const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+fn render(draw: &Draw, size: [u32; 2]) -> RgbaImage {
+    CpuRenderer::new().render(draw, size, 1.0)
+}
+
+#[test]
+fn background_clears_image() {
+    let draw = Draw::new();
+    draw.background().color(RED);
+    let image = render(&draw, [4, 4]);
+    assert!(image.pixels().all(|p| *p == Rgba([255, 0, 0, 255])));
+}
+
+#[test]
+fn rect_covers_expected_pixels() {
+    let draw = Draw::new();
+    draw.rect().w_h(4.0, 2.0).color(WHITE);
+    let image = render(&draw, [10, 10]);
+    for (x, y, p) in image.enumerate_pixels() {
+        let inside = (3..7).contains(&x) && (4..6).contains(&y);
+        let expected = if inside { Rgba([255; 4]) } else { CLEAR };
+        assert_eq!(*p, expected, "pixel ({}, {})", x, y);
+    }
+}
+
+#[test]
+fn shared_edges_are_drawn_once() {
+    // Two translucent triangles sharing a diagonal must not blend twice along it.
+    let draw = Draw::new();
+    let color = rgba(1.0, 1.0, 1.0, 0.5);
+    let [a, b, c, d] = [
+        pt2(-8.0, -8.0),
+        pt2(8.0, -8.0),
+        pt2(8.0, 8.0),
+        pt2(-8.0, 8.0),
+    ];
+    draw.tri().points(a, b, c).color(color);
+    draw.tri().points(a, c, d).color(color);
+    let image = render(&draw, [16, 16]);
+    let first = image.get_pixel(0, 0);
+    assert_ne!(*first, CLEAR);
+    assert!(image.pixels().all(|p| p == first));
+}
+
+#[test]
+fn scissor_limits_drawing() {
+    let draw = Draw::new();
+    // The top left quadrant of a 10x10 image.
+    let scissor = geom::Rect::from_corners(pt2(-5.0, 0.0), pt2(0.0, 5.0));
+    draw.scissor(scissor).rect().w_h(10.0, 10.0).color(WHITE);
+    let image = render(&draw, [10, 10]);
+    for (x, y, p) in image.enumerate_pixels() {
+        let inside = x < 5 && y < 5;
+        let expected = if inside { Rgba([255; 4]) } else { CLEAR };
+        assert_eq!(*p, expected, "pixel ({}, {})", x, y);
+    }
+}
+
+#[test]
+fn blend_modes_apply() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    let add = draw.blend(BLEND_ADD);
+    add.rect().w_h(4.0, 4.0).color(rgb(1.0, 0.0, 0.0));
+    add.rect().w_h(4.0, 4.0).color(rgb(0.0, 0.0, 1.0));
+    let image = render(&draw, [4, 4]);
+    assert!(image.pixels().all(|p| *p == Rgba([255, 0, 255, 255])));
+}
+
+#[test]
+fn render_to_image_blends_over_existing_pixels() {
+    let draw = Draw::new();
+    draw.rect().w_h(2.0, 2.0).color(rgba(0.0, 0.0, 0.0, 0.0));
+    let mut image = RgbaImage::from_pixel(2, 2, Rgba([10, 20, 30, 255]));
+    CpuRenderer::new().render_to_image(&draw, 1.0, &mut image);
+    assert!(image.pixels().all(|p| *p == Rgba([10, 20, 30, 255])));
+}
+
+#[test]
+fn scale_factor_scales_drawing() {
+    let draw = Draw::new();
+    draw.rect().w_h(2.0, 2.0).color(WHITE);
+    let image = CpuRenderer::new().render(&draw, [8, 8], 2.0);
+    let covered = image.pixels().filter(|p| **p != CLEAR).count();
+    assert_eq!(covered, 16);
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn text_is_rasterised() {
+    let draw = Draw::new();
+    draw.text("Hello").font_size(24).color(WHITE);
+    let image = render(&draw, [100, 50]);
+    let covered = image.pixels().filter(|p| p[3] > 0).count();
+    assert!(covered > 50, "only {} pixels covered", covered);
+    // Glyph edges are anti-aliased.
+    assert!(image.pixels().any(|p| p[3] > 0 && p[3] < 255));
+}
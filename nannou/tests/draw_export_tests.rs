@@ -0,0 +1,104 @@
+use nannou::draw::export::{Paint, Shape};
+use nannou::prelude::*;
+
+fn page() -> geom::Rect {
+    geom::Rect::from_w_h(200.0, 100.0)
+}
+
+#[test]
+fn export_paths_with_paint() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    draw.ellipse().w_h(50.0, 50.0).color(RED);
+    draw.rect()
+        .w_h(20.0, 20.0)
+        .no_fill()
+        .stroke(BLUE)
+        .stroke_weight(2.0);
+    let doc = draw.export(page());
+    assert_eq!(doc.elements.len(), 2);
+    assert!(doc.background.is_some());
+    match doc.elements[0].shape {
+        Shape::Path(ref path) => assert!(matches!(path.paint, Paint::Fill { .. })),
+        ref shape => panic!("expected a path, got {:?}", shape),
+    }
+    match doc.elements[1].shape {
+        Shape::Path(ref path) => match path.paint {
+            Paint::Stroke { options, .. } => assert_eq!(options.line_width, 2.0),
+            ref paint => panic!("expected a stroke, got {:?}", paint),
+        },
+        ref shape => panic!("expected a path, got {:?}", shape),
+    }
+}
+
+#[test]
+fn export_does_not_drain_commands() {
+    let draw = Draw::new();
+    draw.ellipse();
+    draw.export(page());
+    assert_eq!(draw.drain_commands().count(), 2);
+}
+
+#[test]
+fn export_scissor_as_clip() {
+    let draw = Draw::new();
+    let clip = geom::Rect::from_w_h(10.0, 10.0);
+    draw.scissor(clip).ellipse().color(RED);
+    draw.ellipse().color(GREEN);
+    let doc = draw.export(page());
+    assert_eq!(doc.elements[0].clip, Some(clip));
+    assert_eq!(doc.elements[1].clip, None);
+
+    let svg = doc.to_svg();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains(r#"<clipPath id="clip0"><rect x="-5" y="-5" width="10" height="10"/>"#));
+    assert!(svg.contains(r##"fill="#ff0000""##));
+    assert_eq!(svg.matches("<path").count(), 2);
+
+    let pdf = String::from_utf8(doc.to_pdf()).unwrap();
+    assert!(pdf.starts_with("%PDF"));
+    assert!(pdf.contains("q -5 -5 10 10 re W n"));
+    assert!(pdf.contains("/MediaBox [0 0 200 100]"));
+    assert!(pdf.ends_with("%%EOF\n"));
+}
+
+#[test]
+fn export_meshes_as_triangles() {
+    let draw = Draw::new();
+    draw.tri()
+        .points(pt2(0.0, 0.0), pt2(10.0, 0.0), pt2(0.0, 10.0));
+    draw.mesh().points_colored(vec![
+        (pt3(0.0, 0.0, 0.0), RED),
+        (pt3(10.0, 0.0, 0.0), RED),
+        (pt3(0.0, 10.0, 0.0), RED),
+    ]);
+    let doc = draw.export(page());
+    assert_eq!(doc.elements.len(), 2);
+    assert!(matches!(doc.elements[0].shape, Shape::Path(_)));
+    match doc.elements[1].shape {
+        Shape::Triangle { points, .. } => assert_eq!(points[1], pt2(10.0, 0.0)),
+        ref shape => panic!("expected a triangle, got {:?}", shape),
+    }
+}
+
+#[cfg(feature = "notosans")]
+#[test]
+fn export_text_as_outlines() {
+    let draw = Draw::new();
+    draw.text("Hi").font_size(24);
+    let doc = draw.export(page());
+    // One outline per glyph.
+    assert_eq!(doc.elements.len(), 2);
+    assert!(doc
+        .elements
+        .iter()
+        .all(|e| matches!(e.shape, Shape::Path(ref p) if !p.events.is_empty())));
+}
+
+#[test]
+fn save_requires_known_extension() {
+    let draw = Draw::new();
+    let path = std::env::temp_dir().join("nannou_draw_export_tests.png");
+    let err = draw.export(page()).save(path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
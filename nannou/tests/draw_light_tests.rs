@@ -0,0 +1,64 @@
+use nannou::draw::light::Light;
+use nannou::draw::primitive::{mesh, sphere, torus};
+use nannou::draw::Mesh;
+use nannou::prelude::*;
+
+#[test]
+fn mesh_normals_pad_missing_vertices() {
+    let mut mesh = Mesh::default();
+    assert!(mesh.normals().is_empty());
+    mesh.set_normals(2, vec![Vec3::Z, Vec3::Y]);
+    assert_eq!(mesh.normals(), &[Vec3::ZERO, Vec3::ZERO, Vec3::Z, Vec3::Y]);
+    mesh.set_normals(1, vec![Vec3::X]);
+    assert_eq!(mesh.normals(), &[Vec3::ZERO, Vec3::X, Vec3::Z, Vec3::Y]);
+    mesh.clear();
+    assert!(mesh.normals().is_empty());
+}
+
+#[test]
+fn smooth_normals_of_a_quad() {
+    let points = [
+        vec3(0.0, 0.0, 0.0),
+        vec3(1.0, 0.0, 0.0),
+        vec3(1.0, 1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(5.0, 5.0, 5.0),
+    ];
+    let indices = [10, 11, 12, 10, 12, 13];
+    let normals = mesh::smooth_normals(&points, &indices, 10);
+    assert_eq!(&normals[..4], &[Vec3::Z; 4]);
+    // Points that belong to no triangle have no normal.
+    assert_eq!(normals[4], Vec3::ZERO);
+}
+
+#[test]
+fn sphere_normals_face_outwards() {
+    let geometry = sphere::geometry(vec3(50.0, 20.0, 30.0), 16, 8);
+    assert_eq!(geometry.normals.len(), geometry.points.len());
+    for (p, n) in geometry.points.iter().zip(&geometry.normals) {
+        assert!((n.length() - 1.0).abs() < 1e-4);
+        assert!(n.dot(*p) > 0.0);
+    }
+}
+
+#[test]
+fn torus_normals_face_outwards() {
+    let (radius, tube_radius) = (50.0, 10.0);
+    let geometry = torus::geometry(radius, tube_radius, 16, 8);
+    assert_eq!(geometry.normals.len(), geometry.points.len());
+    for (p, n) in geometry.points.iter().zip(&geometry.normals) {
+        let tube_centre = vec3(p.x, 0.0, p.z).normalize() * radius;
+        assert!((*p - (tube_centre + *n * tube_radius)).length() < 1e-3);
+    }
+}
+
+#[test]
+fn point_light_range() {
+    let light = Light::point(vec3(1.0, 2.0, 3.0), lin_srgb(1.0, 1.0, 1.0)).range(100.0);
+    match light {
+        Light::Point { range, .. } => assert_eq!(range, Some(100.0)),
+        _ => panic!("expected a point light"),
+    }
+    let light = Light::directional(-Vec3::Y, lin_srgb(1.0, 1.0, 1.0)).range(100.0);
+    assert!(matches!(light, Light::Directional { .. }));
+}
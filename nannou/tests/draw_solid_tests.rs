@@ -0,0 +1,80 @@
+use nannou::draw::primitive::{cone, cuboid, cylinder, solid, sphere, torus};
+use nannou::prelude::*;
+use std::collections::HashMap;
+
+// Assert that every triangle of a convex solid centered on the origin faces outwards.
+fn assert_outward_winding(geometry: &solid::Geometry) {
+    for [a, b, c] in geometry.triangles() {
+        let normal = (b - a).cross(c - a);
+        let centroid = (a + b + c) / 3.0;
+        assert!(normal.dot(centroid) > 0.0, "inward facing triangle");
+    }
+}
+
+#[test]
+fn cuboid_geometry() {
+    let geometry = cuboid::geometry(vec3(10.0, 20.0, 30.0), 2);
+    assert_eq!(geometry.faces.len(), 6);
+    assert_eq!(geometry.indices.len(), 6 * 2 * 2 * 2 * 3);
+    assert_outward_winding(&geometry);
+}
+
+#[test]
+fn sphere_geometry() {
+    let geometry = sphere::geometry(vec3(50.0, 50.0, 50.0), 16, 8);
+    assert_eq!(geometry.faces.len(), 16 * 8);
+    assert_eq!(geometry.points.len(), 2 + 16 * 7);
+    assert_outward_winding(&geometry);
+}
+
+#[test]
+fn cylinder_geometry() {
+    let geometry = cylinder::geometry(vec3(50.0, 20.0, 50.0), 12);
+    assert_eq!(geometry.faces.len(), 12 + 2);
+    assert_outward_winding(&geometry);
+}
+
+#[test]
+fn cone_geometry() {
+    let geometry = cone::geometry(vec3(50.0, 20.0, 50.0), 12);
+    assert_eq!(geometry.faces.len(), 12 + 1);
+    assert_outward_winding(&geometry);
+}
+
+#[test]
+fn torus_geometry() {
+    let (segments, tube_segments) = (12, 6);
+    let geometry = torus::geometry(50.0, 10.0, segments, tube_segments);
+    assert_eq!(geometry.points.len(), segments * tube_segments);
+    assert_eq!(geometry.normals.len(), geometry.points.len());
+    assert_eq!(geometry.faces.len(), segments * tube_segments);
+    assert_eq!(geometry.indices.len(), segments * tube_segments * 2 * 3);
+
+    // The surface is closed, so each edge is shared by exactly two consistently wound triangles.
+    let mut edges = HashMap::new();
+    for tri in geometry.indices.chunks(3) {
+        for k in 0..3 {
+            *edges.entry((tri[k], tri[(k + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+    for (&(a, b), &count) in &edges {
+        assert_eq!(
+            count,
+            1,
+            "edge {:?} is wound in the same direction twice",
+            (a, b)
+        );
+        assert_eq!(edges.get(&(b, a)), Some(&1), "edge {:?} is open", (a, b));
+    }
+
+    // Triangles face the same way as the normals of their vertices.
+    for tri in geometry.indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| geometry.points[tri[k] as usize]);
+        let normal = (b - a).cross(c - a);
+        assert!(normal.dot(geometry.normals[tri[0] as usize]) > 0.0);
+    }
+
+    // Too few segments are clamped to a minimum of three.
+    let geometry = torus::geometry(50.0, 10.0, 0, 1);
+    assert_eq!(geometry.points.len(), 3 * 3);
+}
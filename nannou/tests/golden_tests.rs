@@ -0,0 +1,143 @@
+use nannou::image::{Rgba, RgbaImage};
+use nannou::prelude::*;
+use nannou::rand::Rng;
+use nannou::testing::{compare, Context, Error, Golden, Tolerance, UPDATE_ENV_VAR};
+use std::path::PathBuf;
+
+fn view(ctxt: &mut Context, draw: &Draw) {
+    if ctxt.nth == 0 {
+        draw.background().color(BLACK);
+    }
+    let x = ctxt.rng.gen_range(-50.0..50.0);
+    let y = (ctxt.time * TAU).sin() * 50.0;
+    draw.ellipse().x_y(x, y).w_h(20.0, 20.0).color(PLUM);
+}
+
+// Whether references are being updated, in which case checks never fail.
+fn updating() -> bool {
+    std::env::var_os(UPDATE_ENV_VAR).is_some()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("nannou_golden_tests").join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn ellipses() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/ellipses.png");
+    Golden::new(path).size(128, 128).frames(10).assert(view);
+}
+
+#[test]
+fn render_is_deterministic() {
+    let golden = Golden::new("unused.png").size(64, 64).frames(5);
+    assert_eq!(golden.render(view), golden.render(view));
+    let reseeded = golden.clone().seed(1);
+    assert_ne!(golden.render(view), reseeded.render(view));
+}
+
+#[test]
+fn identical_images_match() {
+    let image = RgbaImage::from_pixel(8, 8, Rgba([10, 200, 30, 255]));
+    let comparison = compare(&image, &image, &Tolerance::exact());
+    assert!(comparison.is_match());
+    assert_eq!(comparison.differing_pixels, 0);
+    assert_eq!(comparison.max_delta_e, 0.0);
+}
+
+#[test]
+fn imperceptible_differences_are_tolerated() {
+    let a = RgbaImage::from_pixel(8, 8, Rgba([100, 100, 100, 255]));
+    let b = RgbaImage::from_pixel(8, 8, Rgba([101, 100, 100, 255]));
+    assert!(compare(&a, &b, &Tolerance::default()).is_match());
+    assert!(!compare(&a, &b, &Tolerance::exact()).is_match());
+}
+
+#[test]
+fn differing_pixels_are_counted() {
+    let a = RgbaImage::from_pixel(10, 10, Rgba([0, 0, 0, 255]));
+    let mut b = a.clone();
+    b.put_pixel(3, 4, Rgba([255, 255, 255, 255]));
+    let comparison = compare(&a, &b, &Tolerance::default());
+    assert_eq!(comparison.differing_pixels, 1);
+    assert!(!comparison.is_match());
+    let loose = Tolerance {
+        max_differing_fraction: 0.01,
+        ..Default::default()
+    };
+    assert!(compare(&a, &b, &loose).is_match());
+
+    let diff = comparison.diff_image(&b);
+    assert_eq!(*diff.get_pixel(3, 4), Rgba([255, 0, 0, 255]));
+    assert_ne!(*diff.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+fn transparency_differences_are_detected() {
+    let a = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 0]));
+    let b = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 255]));
+    assert!(!compare(&a, &b, &Tolerance::default()).is_match());
+}
+
+#[test]
+fn size_mismatch_does_not_match() {
+    let a = RgbaImage::new(4, 4);
+    let b = RgbaImage::new(4, 5);
+    let comparison = compare(&a, &b, &Tolerance::default());
+    assert!(!comparison.is_match());
+    assert_eq!(comparison.differing_pixels, 4);
+    assert_eq!(comparison.diff_image(&b).dimensions(), (4, 5));
+}
+
+#[test]
+fn mismatch_writes_actual_and_diff() {
+    if updating() {
+        return;
+    }
+    let dir = temp_dir("mismatch");
+    let reference = dir.join("sketch.png");
+    let golden = Golden::new(&reference).size(32, 32);
+
+    std::fs::create_dir_all(&dir).unwrap();
+    golden.render(view).save(&reference).unwrap();
+    golden.check(view).unwrap();
+
+    let other = |_: &mut Context, draw: &Draw| {
+        draw.background().color(WHITE);
+    };
+    match golden.check(other) {
+        Err(Error::Mismatch {
+            actual,
+            diff,
+            comparison,
+            ..
+        }) => {
+            assert!(actual.exists());
+            assert!(diff.exists());
+            assert_eq!(comparison.differing_pixels, comparison.total_pixels());
+        }
+        other => panic!("expected a mismatch, found {:?}", other),
+    }
+
+    // A subsequent match removes the output of the failure.
+    golden.check(view).unwrap();
+    assert!(!reference.with_extension("actual.png").exists());
+    assert!(!reference.with_extension("diff.png").exists());
+}
+
+#[test]
+fn missing_reference_is_an_error() {
+    if updating() {
+        return;
+    }
+    let dir = temp_dir("missing");
+    let reference = dir.join("sketch.png");
+    let golden = Golden::new(&reference).size(32, 32);
+    match golden.check(view) {
+        Err(Error::MissingReference(path)) => assert_eq!(path, reference),
+        other => panic!("expected a missing reference, found {:?}", other),
+    }
+    assert!(!reference.exists());
+}
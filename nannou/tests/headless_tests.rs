@@ -0,0 +1,48 @@
+use nannou::prelude::*;
+
+struct Model {
+    window: window::Id,
+    // The size of the window's offscreen texture as seen by each update.
+    texture_sizes: Vec<[u32; 2]>,
+}
+
+fn model(app: &App) -> Model {
+    let window = app.new_window().size(64, 64).view(view).build().unwrap();
+    Model {
+        window,
+        texture_sizes: vec![],
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    let window = app.window(model.window).unwrap();
+    assert!(window.is_offscreen());
+    assert!(window.winit_window().is_none());
+    assert!(window.surface().is_none());
+    let texture = window.offscreen_texture().unwrap();
+    let [w, h] = texture.size();
+    model.texture_sizes.push([w, h]);
+    if model.texture_sizes.len() == 1 {
+        window.set_inner_size_pixels(32, 16);
+    }
+}
+
+fn view(app: &App, _model: &Model, frame: Frame) {
+    frame.clear(BLACK);
+    let (w, h) = app.main_window().inner_size_pixels();
+    assert_eq!(frame.texture_size(), [w, h]);
+}
+
+fn exit(_app: &App, model: Model) {
+    assert_eq!(model.texture_sizes, vec![[64, 64], [32, 16], [32, 16]]);
+}
+
+#[test]
+fn resize_offscreen_window() {
+    nannou::app(model)
+        .update(update)
+        .exit(exit)
+        .loop_mode(LoopMode::loop_ntimes(3))
+        .headless()
+        .run();
+}
@@ -0,0 +1,294 @@
+use nannou::mesh::{self, gltf, obj, MeshData};
+use nannou::prelude::*;
+
+const QUAD_OBJ: &str = "
+# A textured quad followed by a colored triangle.
+mtllib quad.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+o quad
+usemtl red
+f 1/1/1 2/2/1 3/3/1 4/4/1
+o tri
+usemtl green
+v 0 0 1 1 0 0
+v 1 0 1 0 1 0
+v 0 1 1 0 0 1
+f -3 -2 -1
+";
+
+// A single triangle in an embedded buffer of three f32 positions followed by three u16 indices.
+const TRIANGLE_GLTF: &str = r#"{
+    "asset": { "version": "2.0" },
+    "scene": 0,
+    "scenes": [{ "nodes": [0] }],
+    "nodes": [{ "mesh": 0, "translation": [10.0, 0.0, 0.0] }],
+    "meshes": [{ "name": "tri", "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1, "material": 0 }] }],
+    "materials": [{ "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.5, 0.25, 1.0] } }],
+    "accessors": [
+        { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" },
+        { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+    ],
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+    ],
+    "buffers": [{
+        "byteLength": 44,
+        "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA="
+    }]
+}"#;
+
+#[test]
+fn obj_parse() {
+    let model = obj::parse(QUAD_OBJ).unwrap();
+    assert_eq!(model.meshes.len(), 2);
+    assert_eq!(model.materials.len(), 2);
+    assert_eq!(model.materials[0].name.as_deref(), Some("red"));
+
+    // The quad is triangulated as a fan, with its texture coordinates flipped vertically.
+    let quad = &model.meshes[0];
+    assert_eq!(quad.name.as_deref(), Some("quad"));
+    assert_eq!(quad.material, Some(0));
+    assert_eq!(quad.points.len(), 4);
+    assert_eq!(quad.indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(quad.tex_coords[0], pt2(0.0, 1.0));
+    assert_eq!(quad.tex_coords[2], pt2(1.0, 0.0));
+    assert_eq!(quad.normals, vec![Vec3::Z; 4]);
+    assert!(quad.colors.is_empty());
+
+    // Negative indices refer to the most recent vertices.
+    let tri = &model.meshes[1];
+    assert_eq!(tri.name.as_deref(), Some("tri"));
+    assert_eq!(tri.material, Some(1));
+    assert_eq!(
+        tri.points,
+        vec![pt3(0.0, 0.0, 1.0), pt3(1.0, 0.0, 1.0), pt3(0.0, 1.0, 1.0)]
+    );
+    assert_eq!(tri.colors[1], LinSrgba::new(0.0, 1.0, 0.0, 1.0));
+    assert!(tri.tex_coords.is_empty());
+    assert!(tri.normals.is_empty());
+}
+
+#[test]
+fn obj_parse_errors() {
+    match obj::parse("v 0 0 0\nv 1 0 0\nf 1 2 3\n") {
+        Err(obj::Error::Parse { line: 3, .. }) => (),
+        other => panic!("expected a parse error on line 3, got {:?}", other),
+    }
+    assert!(obj::parse("v 0 0\n").is_err());
+    assert!(obj::parse("v 0 0 0\nf 0 0 0\n").is_err());
+}
+
+#[test]
+fn obj_load_with_materials() {
+    let dir = std::env::temp_dir().join("nannou_mesh_loader_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("quad.obj"), QUAD_OBJ).unwrap();
+    std::fs::write(
+        dir.join("quad.mtl"),
+        "newmtl red\nKd 1 0 0\nd 0.5\nnewmtl green\nKd 0 1 0\n",
+    )
+    .unwrap();
+    let model = obj::load(dir.join("quad.obj")).unwrap();
+    assert_eq!(
+        model.materials[0].base_color,
+        LinSrgba::new(1.0, 0.0, 0.0, 0.5)
+    );
+    assert_eq!(
+        model.materials[1].base_color,
+        LinSrgba::new(0.0, 1.0, 0.0, 1.0)
+    );
+    assert_eq!(model.material(&model.meshes[1]), Some(&model.materials[1]));
+}
+
+#[test]
+fn obj_load_rejects_paths_outside_dir() {
+    let dir = std::env::temp_dir().join("nannou_mesh_loader_tests_paths");
+    std::fs::create_dir_all(&dir).unwrap();
+    let absolute = dir.join("quad.mtl").display().to_string();
+    for mtllib in &["../quad.mtl", "./sub/../../quad.mtl", &absolute[..]] {
+        let src = QUAD_OBJ.replace("mtllib quad.mtl", &format!("mtllib {}", mtllib));
+        std::fs::write(dir.join("quad.obj"), src).unwrap();
+        match obj::load(dir.join("quad.obj")) {
+            Err(obj::Error::ExternalPath(path)) => assert_eq!(path, *mtllib),
+            other => panic!("expected an external path error, found {:?}", other),
+        }
+    }
+    std::fs::write(dir.join("quad.obj"), QUAD_OBJ).unwrap();
+    std::fs::write(dir.join("quad.mtl"), "newmtl red\nmap_Kd ../red.png\n").unwrap();
+    assert!(matches!(
+        obj::load(dir.join("quad.obj")),
+        Err(obj::Error::ExternalPath(_))
+    ));
+}
+
+#[test]
+fn gltf_from_slice() {
+    let model = gltf::from_slice(TRIANGLE_GLTF.as_bytes()).unwrap();
+    assert_eq!(model.meshes.len(), 1);
+    let tri = &model.meshes[0];
+    assert_eq!(tri.name.as_deref(), Some("tri"));
+    // The node's translation is applied to the points.
+    assert_eq!(
+        tri.points,
+        vec![
+            pt3(10.0, 0.0, 0.0),
+            pt3(11.0, 0.0, 0.0),
+            pt3(10.0, 1.0, 0.0)
+        ]
+    );
+    assert_eq!(tri.indices, vec![0, 1, 2]);
+    let material = model.material(tri).unwrap();
+    assert_eq!(material.base_color, LinSrgba::new(1.0, 0.5, 0.25, 1.0));
+    assert_eq!(material.base_color_texture, None);
+}
+
+#[test]
+fn glb_from_slice() {
+    // A triangle strip of two triangles with normalized u8 texture coordinates.
+    let mut bin = vec![];
+    for p in &[
+        [0.0f32, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+    ] {
+        bin.extend(p.iter().flat_map(|f| f.to_le_bytes()));
+    }
+    bin.extend([0, 0, 255, 0, 0, 255, 255, 255]);
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0, "TEXCOORD_0": 1 }, "mode": 5 }] }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3" },
+            { "bufferView": 1, "componentType": 5121, "normalized": true, "count": 4, "type": "VEC2" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 48 },
+            { "buffer": 0, "byteOffset": 48, "byteLength": 8 }
+        ],
+        "buffers": [{ "byteLength": 56 }]
+    }"#;
+    let mut json = json.as_bytes().to_vec();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    let mut glb = vec![];
+    glb.extend(b"glTF");
+    glb.extend(2u32.to_le_bytes());
+    glb.extend((12 + 8 + json.len() as u32 + 8 + bin.len() as u32).to_le_bytes());
+    glb.extend((json.len() as u32).to_le_bytes());
+    glb.extend(b"JSON");
+    glb.extend(&json);
+    glb.extend((bin.len() as u32).to_le_bytes());
+    glb.extend(b"BIN\0");
+    glb.extend(&bin);
+
+    let model = gltf::from_slice(&glb).unwrap();
+    let quad = &model.meshes[0];
+    assert_eq!(quad.points.len(), 4);
+    assert_eq!(quad.indices, vec![0, 1, 2, 2, 1, 3]);
+    assert_eq!(quad.tex_coords[1], pt2(1.0, 0.0));
+    assert_eq!(quad.tex_coords[3], pt2(1.0, 1.0));
+}
+
+#[test]
+fn gltf_external_uri_requires_load() {
+    let json = TRIANGLE_GLTF.replace(
+        "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=",
+        "triangle.bin",
+    );
+    assert!(matches!(
+        gltf::from_slice(json.as_bytes()),
+        Err(gltf::Error::Unsupported(_))
+    ));
+}
+
+#[test]
+fn gltf_load_rejects_uris_outside_dir() {
+    let dir = std::env::temp_dir().join("nannou_mesh_loader_tests_uris");
+    std::fs::create_dir_all(&dir).unwrap();
+    let absolute = dir.join("triangle.bin").display().to_string();
+    for uri in &["../triangle.bin", "%2E%2E/triangle.bin", &absolute[..]] {
+        let json = TRIANGLE_GLTF.replace(
+            "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=",
+            &uri.replace('\\', "/"),
+        );
+        std::fs::write(dir.join("triangle.gltf"), json).unwrap();
+        assert!(matches!(
+            gltf::load(dir.join("triangle.gltf")),
+            Err(gltf::Error::Invalid(_))
+        ));
+    }
+}
+
+#[test]
+fn gltf_malformed_sizes_are_errors() {
+    let invalid = |json: String| {
+        matches!(
+            gltf::from_slice(json.as_bytes()),
+            Err(gltf::Error::Invalid(_))
+        )
+    };
+    let max = usize::MAX.to_string();
+
+    // A buffer view whose end overflows.
+    let json = TRIANGLE_GLTF.replace(
+        r#""byteOffset": 36, "byteLength": 6"#,
+        &format!(r#""byteOffset": 36, "byteLength": {}"#, max),
+    );
+    assert!(invalid(json));
+
+    // An accessor whose last element overflows.
+    let json = TRIANGLE_GLTF.replace(
+        r#""count": 3, "type": "SCALAR""#,
+        &format!(r#""count": {}, "type": "SCALAR""#, max),
+    );
+    assert!(invalid(json));
+
+    // An accessor without a buffer view whose count exceeds the size of the buffers.
+    let json = TRIANGLE_GLTF.replace(
+        r#""bufferView": 0, "componentType": 5126, "count": 3"#,
+        r#""componentType": 5126, "count": 1000000000000"#,
+    );
+    assert!(invalid(json));
+    let json = TRIANGLE_GLTF.replace(
+        r#""bufferView": 0, "componentType": 5126, "count": 3"#,
+        &format!(r#""componentType": 5126, "count": {}"#, max),
+    );
+    assert!(invalid(json));
+
+    // Zero-filled accessors within the size of the buffers are still read.
+    let json = TRIANGLE_GLTF.replace(
+        r#""bufferView": 0, "componentType": 5126"#,
+        r#""componentType": 5126"#,
+    );
+    let model = gltf::from_slice(json.as_bytes()).unwrap();
+    assert_eq!(model.meshes[0].points, vec![pt3(10.0, 0.0, 0.0); 3]);
+}
+
+#[test]
+fn mesh_data_to_mesh_fills_missing_channels() {
+    let data = MeshData {
+        points: vec![pt3(0.0, 0.0, 0.0), pt3(1.0, 0.0, 0.0), pt3(0.0, 1.0, 0.0)],
+        indices: vec![0, 1, 2],
+        ..Default::default()
+    };
+    let mesh = data.to_mesh();
+    let vertices: Vec<_> = mesh::vertices(&mesh).collect();
+    assert_eq!(vertices.len(), 3);
+    for v in vertices {
+        assert_eq!(v.vertex.normal, Vec3::Z);
+        assert_eq!(v.tex_coords, pt2(0.0, 0.0));
+        assert_eq!(v.vertex.vertex.color, LinSrgba::new(1.0, 1.0, 1.0, 1.0));
+    }
+    assert_eq!(mesh::triangles(&mesh).count(), 1);
+}
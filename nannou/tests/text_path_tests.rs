@@ -0,0 +1,143 @@
+use nannou::geom::{self, Point2};
+use nannou::lyon::path::PathEvent;
+use nannou::text::{self, path, Align, Text};
+
+const EPSILON: f32 = 1e-3;
+
+fn text(s: &str) -> Text<'_> {
+    let rect = geom::Rect::from_w_h(1000.0, 1000.0);
+    text::text(s).font_size(24).no_line_wrap().build(rect)
+}
+
+// A straight path from `a` to `b`.
+fn line(a: Point2, b: Point2) -> Vec<PathEvent> {
+    let (a, b) = (
+        nannou::lyon::math::point(a.x, a.y),
+        nannou::lyon::math::point(b.x, b.y),
+    );
+    vec![
+        PathEvent::Begin { at: a },
+        PathEvent::Line { from: a, to: b },
+        PathEvent::End {
+            last: b,
+            first: a,
+            close: false,
+        },
+    ]
+}
+
+fn horizontal(length: f32) -> Vec<PathEvent> {
+    line(geom::pt2(0.0, 0.0), geom::pt2(length, 0.0))
+}
+
+fn assert_approx_eq(a: f32, b: f32) {
+    assert!((a - b).abs() < EPSILON, "{} != {}", a, b);
+}
+
+#[test]
+fn start_alignment() {
+    let text = text("abc");
+    let options = path::Options::default();
+    let placements = path::place_glyphs(&text, horizontal(500.0), &options);
+    let glyphs = &text.shaped_lines()[0].glyphs;
+    assert_eq!(placements.len(), glyphs.len());
+    for (i, (p, g)) in placements.iter().zip(glyphs).enumerate() {
+        assert_eq!((p.line, p.glyph), (0, i));
+        assert_approx_eq(p.origin.x, g.position.x);
+        assert_approx_eq(p.origin.y, 0.0);
+        assert_approx_eq(p.rotation, 0.0);
+    }
+}
+
+#[test]
+fn middle_and_end_alignment() {
+    let text = text("abc");
+    let line = &text.shaped_lines()[0];
+    let length = 500.0;
+
+    let options = path::Options::default().align(Align::Middle);
+    let placements = path::place_glyphs(&text, horizontal(length), &options);
+    assert_approx_eq(placements[0].origin.x, (length - line.width) / 2.0);
+
+    let options = path::Options::default().align(Align::End);
+    let placements = path::place_glyphs(&text, horizontal(length), &options);
+    let last = placements.last().unwrap();
+    let last_glyph = line.glyphs.last().unwrap();
+    assert_approx_eq(last.origin.x + last_glyph.advance, length);
+}
+
+#[test]
+fn start_offset_and_spacing() {
+    let text = text("abc");
+    let glyphs = &text.shaped_lines()[0].glyphs;
+    let options = path::Options::default().start_offset(10.0).spacing(5.0);
+    let placements = path::place_glyphs(&text, horizontal(500.0), &options);
+    for (i, (p, g)) in placements.iter().zip(glyphs).enumerate() {
+        assert_approx_eq(p.origin.x, 10.0 + g.position.x + 5.0 * i as f32);
+    }
+}
+
+#[test]
+fn glyphs_beyond_path_are_skipped() {
+    let text = text("abcdef");
+    let glyphs = &text.shaped_lines()[0].glyphs;
+    let centre = |i: usize| glyphs[i].position.x + glyphs[i].advance / 2.0;
+    // The path ends between the centres of the third and fourth glyphs.
+    let length = (centre(2) + centre(3)) / 2.0;
+    let placements = path::place_glyphs(&text, horizontal(length), &path::Options::default());
+    let placed: Vec<usize> = placements.iter().map(|p| p.glyph).collect();
+    assert_eq!(placed, vec![0, 1, 2]);
+
+    // Glyphs before the start of the path are skipped too.
+    let options = path::Options::default().start_offset(-(centre(0) + centre(1)) / 2.0);
+    let placements = path::place_glyphs(&text, horizontal(500.0), &options);
+    let placed: Vec<usize> = placements.iter().map(|p| p.glyph).collect();
+    assert_eq!(placed, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn empty_path_places_nothing() {
+    let text = text("abc");
+    let placements = path::place_glyphs(&text, vec![], &path::Options::default());
+    assert!(placements.is_empty());
+}
+
+#[test]
+fn following_lines_are_offset_along_normal() {
+    let text = text("ab\ncd");
+    let rects: Vec<geom::Rect> = text.line_rects().collect();
+    let line_offset = rects[1].bottom() - rects[0].bottom();
+    assert!(line_offset < 0.0);
+
+    // Along a horizontal path, the normal points up.
+    let placements = path::place_glyphs(&text, horizontal(500.0), &path::Options::default());
+    assert_eq!(placements.len(), 4);
+    for p in &placements {
+        let expected = if p.line == 0 { 0.0 } else { line_offset };
+        assert_approx_eq(p.origin.y, expected);
+    }
+
+    // Along a path heading up, the normal points left.
+    let up = line(geom::pt2(0.0, 0.0), geom::pt2(0.0, 500.0));
+    let placements = path::place_glyphs(&text, up, &path::Options::default());
+    for p in &placements {
+        let g = &text.shaped_lines()[p.line].glyphs[p.glyph];
+        let expected = if p.line == 0 { 0.0 } else { -line_offset };
+        assert_approx_eq(p.origin.x, expected);
+        assert_approx_eq(p.origin.y, g.position.x);
+        assert_approx_eq(p.rotation, std::f32::consts::FRAC_PI_2);
+    }
+}
+
+#[test]
+fn placement_transforms_points() {
+    let placement = path::Placement {
+        line: 0,
+        glyph: 0,
+        origin: geom::pt2(10.0, 20.0),
+        rotation: std::f32::consts::FRAC_PI_2,
+    };
+    let p = placement.transform_point(geom::pt2(1.0, 2.0));
+    assert_approx_eq(p.x, 8.0);
+    assert_approx_eq(p.y, 21.0);
+}
@@ -0,0 +1,238 @@
+use nannou::geom::Rect;
+use nannou::text::{self, cursor, font, glyph, shape, Font};
+
+fn font_from_assets(name: &str) -> Font {
+    let path = format!(
+        "{}/../assets/fonts/NotoSans/{}.ttf",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    font::from_file(path).unwrap()
+}
+
+// Visual order of the chars represented by each glyph in the line.
+fn glyph_chars(line: &shape::Line) -> Vec<usize> {
+    line.glyphs.iter().map(|g| g.char).collect()
+}
+
+#[test]
+fn shape_line_positions_glyphs() {
+    let fonts = vec![font::default_notosans()];
+    let line = shape::shape_line("abc", &fonts, 24);
+    assert_eq!(line.glyphs.len(), 3);
+    assert_eq!(glyph_chars(&line), vec![0, 1, 2]);
+    assert_eq!(
+        line.glyphs.iter().map(|g| g.byte).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    let mut x = 0.0;
+    for g in &line.glyphs {
+        assert_eq!(g.font, 0);
+        assert_eq!(g.font_size, 24);
+        assert!(!g.rtl);
+        assert!(g.advance > 0.0);
+        assert_eq!(g.position.x, x);
+        x += g.advance;
+    }
+    assert_eq!(line.width, x);
+}
+
+#[test]
+fn shape_line_scales_with_font_size() {
+    let fonts = vec![font::default_notosans()];
+    let small = shape::shape_line("nannou", &fonts, 12);
+    let large = shape::shape_line("nannou", &fonts, 24);
+    assert!((large.width - small.width * 2.0).abs() < 1e-3);
+}
+
+#[test]
+fn shape_line_empty() {
+    let fonts = vec![font::default_notosans()];
+    let line = shape::shape_line("", &fonts, 24);
+    assert!(line.glyphs.is_empty());
+    assert_eq!(line.width, 0.0);
+}
+
+#[test]
+fn shape_line_reorders_rtl_runs() {
+    let fonts = vec![font::default_notosans()];
+    // "ab", followed by three Hebrew letters, followed by "cd".
+    let line = shape::shape_line("ab אבג cd", &fonts, 24);
+    assert_eq!(glyph_chars(&line), vec![0, 1, 2, 5, 4, 3, 6, 7, 8]);
+    let rtl: Vec<bool> = line.glyphs.iter().map(|g| g.rtl).collect();
+    let expected = vec![false, false, false, true, true, true, false, false, false];
+    assert_eq!(rtl, expected);
+    // Glyphs are in visual order, from left to right.
+    for pair in line.glyphs.windows(2) {
+        assert!(pair[0].position.x <= pair[1].position.x);
+    }
+}
+
+#[test]
+fn shape_line_rtl_paragraph() {
+    let fonts = vec![font::default_notosans()];
+    // A paragraph beginning with Hebrew is right-to-left, so the Latin run is displayed first.
+    let line = shape::shape_line("אב cd", &fonts, 24);
+    assert_eq!(glyph_chars(&line), vec![3, 4, 2, 1, 0]);
+}
+
+#[test]
+fn shaper_reuses_buffers_between_lines() {
+    let fonts = vec![font::default_notosans()];
+    let mut shaper = shape::Shaper::default();
+    for text in &["abc", "\u{5d0}\u{5d1} d", "", "efgh ijk"] {
+        let attrs = text::span::Attrs {
+            font: 0,
+            font_size: 24,
+            baseline_offset: 0.0,
+        };
+        let attrs = vec![attrs; text.len()];
+        let options = shape::Options::default();
+        let shaped = shaper.shape_line_with_attrs(text, &fonts, 1..1, &attrs, options);
+        assert_eq!(shaped, shape::shape_line(text, &fonts, 24));
+    }
+}
+
+#[test]
+fn select_font_prefers_primary() {
+    let fonts = vec![
+        font_from_assets("NotoSans-Regular"),
+        font_from_assets("NotoSans-Bold"),
+    ];
+    assert_eq!(shape::select_font('a', None, &fonts), 0);
+}
+
+#[test]
+fn select_font_prefers_previous_font() {
+    let fonts = vec![
+        font_from_assets("NotoSans-Regular"),
+        font_from_assets("NotoSans-Bold"),
+    ];
+    assert_eq!(shape::select_font('a', Some(1), &fonts), 1);
+    // A previous font outside of the chain is ignored.
+    assert_eq!(shape::select_font('a', Some(2), &fonts), 0);
+}
+
+#[test]
+fn select_font_missing_glyph_keeps_previous_font() {
+    let fonts = vec![
+        font_from_assets("NotoSans-Regular"),
+        font_from_assets("NotoSans-Bold"),
+    ];
+    // Noto Sans does not cover Hebrew.
+    assert!(!fonts.iter().any(|f| f.has_glyph('א')));
+    assert_eq!(shape::select_font('א', Some(1), &fonts), 1);
+    assert_eq!(shape::select_font('א', None, &fonts), 0);
+}
+
+#[test]
+fn fallback_chain_order() {
+    let mut map = font::Map::new();
+    let regular = map.insert(font_from_assets("NotoSans-Regular"));
+    let bold = map.insert(font_from_assets("NotoSans-Bold"));
+    let italic = map.insert(font_from_assets("NotoSans-Italic"));
+    let ids = |fonts: Vec<Font>| fonts.iter().map(font::id).collect::<Vec<_>>();
+    let id = |map_id| font::id(map.get(map_id).unwrap());
+    let (regular_id, bold_id, italic_id) = (id(regular), id(bold), id(italic));
+
+    // Without a chain, every other font is used in the order in which they were inserted.
+    assert_eq!(ids(map.fallback_chain(regular)), vec![bold_id, italic_id]);
+
+    // With a chain, the chain's order is used and the primary font is excluded.
+    map.set_fallbacks(vec![italic, regular, bold]);
+    let expected = vec![italic_id, bold_id];
+    assert_eq!(ids(map.fallback_chain(regular)), expected);
+    let expected = vec![italic_id, regular_id];
+    assert_eq!(ids(map.fallback_chain(bold)), expected);
+}
+
+#[test]
+fn shaped_cursor_xs_ltr() {
+    let fonts = vec![font::default_notosans()];
+    let line = shape::shape_line("abc", &fonts, 24);
+    let xs: Vec<_> = cursor::shaped_xs(&line, 3, 10.0).collect();
+    let mut expected: Vec<_> = line.glyphs.iter().map(|g| 10.0 + g.position.x).collect();
+    expected.push(10.0 + line.width);
+    assert_eq!(xs, expected);
+}
+
+#[test]
+fn shaped_cursor_xs_rtl() {
+    let fonts = vec![font::default_notosans()];
+    let line = shape::shape_line("אבג", &fonts, 24);
+    let xs: Vec<_> = cursor::shaped_xs(&line, 3, 0.0).collect();
+    // The cursor before the first char is on the right edge of the line.
+    assert_eq!(xs.len(), 4);
+    assert_eq!(xs[0], line.width);
+    assert_eq!(xs[3], 0.0);
+    for pair in xs.windows(2) {
+        assert!(pair[0] > pair[1]);
+    }
+}
+
+#[test]
+fn shaped_cursor_xs_empty_line() {
+    let line = shape::Line::default();
+    let xs: Vec<_> = cursor::shaped_xs(&line, 0, 5.0).collect();
+    assert_eq!(xs, vec![5.0]);
+}
+
+#[test]
+fn text_cursor_xys_and_closest_index() {
+    let rect = Rect::from_w_h(400.0, 200.0);
+    let text = text::text("ab\ncd").font_size(24).build(rect);
+    let xys: Vec<_> = text.cursor_xys_per_line().collect();
+    assert_eq!(xys.len(), 2);
+    assert_eq!(xys[0].0.clone().count(), 3);
+    assert_eq!(xys[1].0.clone().count(), 3);
+
+    // The position after the first char of the second line.
+    let (x, y) = cursor::xy_at(
+        text.cursor_xys_per_line(),
+        cursor::Index { line: 1, char: 1 },
+    )
+    .unwrap();
+    let xy = [x, y.middle()].into();
+    let (index, point) =
+        cursor::closest_shaped_cursor_index_and_xy(xy, text.cursor_xys_per_line()).unwrap();
+    assert_eq!(index, cursor::Index { line: 1, char: 1 });
+    assert_eq!(point, xy);
+}
+
+#[test]
+fn shaped_selected_rects() {
+    let fonts = vec![font::default_notosans()];
+    let lines = [
+        shape::shape_line("ab אבג", &fonts, 24),
+        shape::shape_line("cd", &fonts, 24),
+    ];
+    let rects = [Rect::from_w_h(200.0, 24.0); 2];
+    let start = cursor::Index { line: 0, char: 1 };
+    let end = cursor::Index { line: 0, char: 4 };
+    let lines_with_rects = || lines.iter().zip(rects.iter().cloned());
+    let selected: Vec<Vec<_>> =
+        glyph::shaped_selected_rects_per_line(lines_with_rects(), &fonts, start, end)
+            .map(|rects| rects.map(|(_, r)| r.left()).collect())
+            .collect();
+    assert_eq!(selected.len(), 2);
+    assert!(selected[1].is_empty());
+
+    // Chars 1, 2 and 3 are selected, yielded in visual order.
+    let expected: Vec<_> = glyph::shaped_rects_per_line(lines_with_rects(), &fonts)
+        .next()
+        .unwrap()
+        .zip(&lines[0].glyphs)
+        .filter(|(_, g)| (1..4).contains(&g.char))
+        .map(|((_, r), _)| r.left())
+        .collect();
+    assert_eq!(expected.len(), 3);
+    assert_eq!(selected[0], expected);
+
+    // Selecting across lines selects the remainder of the first line and the start of the last.
+    let end = cursor::Index { line: 1, char: 1 };
+    let counts: Vec<usize> =
+        glyph::shaped_selected_rects_per_line(lines_with_rects(), &fonts, start, end)
+            .map(|rects| rects.count())
+            .collect();
+    assert_eq!(counts, vec![5, 1]);
+}
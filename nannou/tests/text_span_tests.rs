@@ -0,0 +1,156 @@
+use nannou::draw::primitive::text::Span;
+use nannou::draw::CpuRenderer;
+use nannou::prelude::*;
+use nannou::text::{self, font, span, Font};
+
+fn bold() -> Font {
+    let path = format!(
+        "{}/../assets/fonts/NotoSans/NotoSans-Bold.ttf",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    font::from_file(path).unwrap()
+}
+
+fn span(range: std::ops::Range<usize>, style: span::Style) -> span::Span {
+    span::Span { range, style }
+}
+
+#[test]
+fn resolve_without_spans() {
+    let mut fonts = vec![font::default_notosans()];
+    let attrs = span::resolve(3, &[], &mut fonts, 12);
+    let expected = span::Attrs {
+        font: 0,
+        font_size: 12,
+        baseline_offset: 0.0,
+    };
+    assert_eq!(attrs, vec![expected; 3]);
+    assert_eq!(fonts.len(), 1);
+}
+
+#[test]
+fn resolve_overlapping_spans() {
+    let mut fonts = vec![font::default_notosans()];
+    let spans = [
+        span(1..4, span::Style::default().font_size(24)),
+        span(3..6, span::Style::default().baseline_offset(2.0)),
+        span(5..6, span::Style::default().font_size(8)),
+    ];
+    let attrs = span::resolve(7, &spans, &mut fonts, 12);
+    let sizes: Vec<_> = attrs.iter().map(|a| a.font_size).collect();
+    assert_eq!(sizes, vec![12, 24, 24, 24, 12, 8, 12]);
+    let offsets: Vec<_> = attrs.iter().map(|a| a.baseline_offset).collect();
+    assert_eq!(offsets, vec![0.0, 0.0, 0.0, 2.0, 2.0, 2.0, 0.0]);
+    assert!(attrs.iter().all(|a| a.font == 0));
+}
+
+#[test]
+fn resolve_clamps_ranges() {
+    let mut fonts = vec![font::default_notosans()];
+    let spans = [
+        span(2..10, span::Style::default().font_size(24)),
+        span(8..12, span::Style::default().font_size(8)),
+    ];
+    let attrs = span::resolve(4, &spans, &mut fonts, 12);
+    let sizes: Vec<_> = attrs.iter().map(|a| a.font_size).collect();
+    assert_eq!(sizes, vec![12, 12, 24, 24]);
+}
+
+#[test]
+fn resolve_span_fonts() {
+    let regular = font::default_notosans();
+    let mut fonts = vec![regular.clone()];
+    let spans = [
+        span(0..1, span::Style::default().font(bold())),
+        span(1..2, span::Style::default().font(regular)),
+        span(2..3, span::Style::default().font(bold())),
+    ];
+    let attrs = span::resolve(4, &spans, &mut fonts, 12);
+    let font_ixs: Vec<_> = attrs.iter().map(|a| a.font).collect();
+    // Each distinct font is appended once, while the layout's own font is reused.
+    assert_eq!(font_ixs, vec![1, 0, 1, 0]);
+    assert_eq!(fonts.len(), 2);
+    assert_eq!(font::id(&fonts[1]), font::id(&bold()));
+}
+
+#[test]
+fn line_height_is_greatest_font_size() {
+    let mut fonts = vec![font::default_notosans()];
+    let spans = [span(1..2, span::Style::default().font_size(30))];
+    let attrs = span::resolve(3, &spans, &mut fonts, 12);
+    assert_eq!(span::line_height(&attrs, 12), 30.0);
+    assert_eq!(span::line_height(&attrs[2..], 12), 12.0);
+    assert_eq!(span::line_height(&[], 16), 16.0);
+}
+
+#[test]
+fn text_glyphs_use_span_attributes() {
+    let rect = geom::Rect::from_w_h(400.0, 200.0);
+    let text = text::text("ab cd")
+        .font_size(12)
+        .span(
+            3..5,
+            span::Style::default()
+                .font(bold())
+                .font_size(24)
+                .baseline_offset(3.0),
+        )
+        .build(rect);
+    assert_eq!(text.fonts().len(), 2);
+    assert_eq!(text.line_heights(), &[24.0]);
+    let glyphs = &text.shaped_lines()[0].glyphs;
+    for g in glyphs {
+        let in_span = (3..5).contains(&g.byte);
+        let (font, font_size, y) = if in_span { (1, 24, 3.0) } else { (0, 12, 0.0) };
+        assert_eq!(g.font, font, "glyph at byte {}", g.byte);
+        assert_eq!(g.font_size, font_size, "glyph at byte {}", g.byte);
+        assert_eq!(g.position.y, y, "glyph at byte {}", g.byte);
+    }
+}
+
+#[test]
+fn spans_are_split_across_wrapped_lines() {
+    // Each word is placed on its own line, splitting the span in two.
+    let rect = geom::Rect::from_w_h(40.0, 200.0);
+    let text = text::text("aaa bbb ccc")
+        .font_size(12)
+        .wrap_by_word()
+        .span(2..6, span::Style::default().font_size(20))
+        .build(rect);
+    assert_eq!(text.num_lines(), 3);
+    assert_eq!(text.line_heights(), &[20.0, 20.0, 12.0]);
+    for (info, line) in text.line_infos().iter().zip(text.shaped_lines()) {
+        for g in &line.glyphs {
+            let byte = info.start_byte + g.byte;
+            let expected = if (2..6).contains(&byte) { 20 } else { 12 };
+            assert_eq!(g.font_size, expected, "glyph at byte {}", byte);
+        }
+    }
+}
+
+#[test]
+fn span_colors_are_drawn() {
+    // Draw "II" in white, with the second "I" colored red by a span.
+    let draw = Draw::new();
+    draw.text("II")
+        .font_size(48)
+        .no_line_wrap()
+        .center_justify()
+        .color(WHITE)
+        .span(1..2, Span::default().color(RED));
+    let image = CpuRenderer::new().render(&draw, [64, 64], 1.0);
+    let is_white = |p: &nannou::image::Rgba<u8>| p[3] == 255 && p[0] > 200 && p[1] > 200;
+    let is_red = |p: &nannou::image::Rgba<u8>| p[3] == 255 && p[0] > 200 && p[1] < 50;
+    let (mut white, mut red) = ([0; 2], [0; 2]);
+    for (x, _, p) in image.enumerate_pixels() {
+        let half = if x < 32 { 0 } else { 1 };
+        if is_white(p) {
+            white[half] += 1;
+        }
+        if is_red(p) {
+            red[half] += 1;
+        }
+    }
+    assert!(white[0] > 0 && white[1] == 0, "white pixels: {:?}", white);
+    assert!(red[0] == 0 && red[1] > 0, "red pixels: {:?}", red);
+}
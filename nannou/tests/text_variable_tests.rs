@@ -0,0 +1,297 @@
+use nannou::geom;
+use nannou::text::font::Variation;
+use nannou::text::{self, font, glyph, rt, sdf, Font, GlyphId, Scale};
+
+// Builds a minimal variable TrueType font with a single `wght` axis ranging from 100 to 900,
+// defaulting to 400.
+//
+// The font maps 'A' to glyph 1, a square from (100, 0) to (500, 600) in font units. At the
+// maximum weight, the right edge of the square and the glyph's advance move 200 units right.
+mod variable_font {
+    fn u16s(data: &mut Vec<u8>, values: &[u16]) {
+        for v in values {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+
+    fn i16s(data: &mut Vec<u8>, values: &[i16]) {
+        for v in values {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+
+    fn u32s(data: &mut Vec<u8>, values: &[u32]) {
+        for v in values {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+
+    fn fixed(v: i32) -> u32 {
+        (v << 16) as u32
+    }
+
+    fn head() -> Vec<u8> {
+        let mut t = vec![];
+        u32s(&mut t, &[0x0001_0000, 0x0001_0000, 0, 0x5F0F_3CF5]);
+        // Flags and units per em.
+        u16s(&mut t, &[0, 1000]);
+        // Created and modified dates.
+        u32s(&mut t, &[0, 0, 0, 0]);
+        i16s(&mut t, &[0, 0, 500, 600]);
+        // Mac style, lowest rec PPEM, direction hint, short loca format and glyph data format.
+        i16s(&mut t, &[0, 8, 2, 0, 0]);
+        t
+    }
+
+    fn hhea() -> Vec<u8> {
+        let mut t = vec![];
+        u32s(&mut t, &[0x0001_0000]);
+        i16s(&mut t, &[800, -200, 0]);
+        u16s(&mut t, &[600]);
+        i16s(&mut t, &[0, 0, 500, 1, 0, 0, 0, 0, 0, 0, 0]);
+        // The number of horizontal metrics.
+        u16s(&mut t, &[2]);
+        t
+    }
+
+    fn maxp() -> Vec<u8> {
+        let mut t = vec![];
+        u32s(&mut t, &[0x0001_0000]);
+        // Glyphs, points, contours, then limits for composites, zones and instructions.
+        u16s(&mut t, &[2, 4, 1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+        t
+    }
+
+    fn hmtx() -> Vec<u8> {
+        let mut t = vec![];
+        u16s(&mut t, &[0, 0, 600, 100]);
+        t
+    }
+
+    fn cmap() -> Vec<u8> {
+        let mut t = vec![];
+        // A single Windows Unicode BMP encoding record.
+        u16s(&mut t, &[0, 1, 3, 1]);
+        u32s(&mut t, &[12]);
+        // A format 4 subtable with a segment for 'A' followed by the final segment.
+        let a = 'A' as u16;
+        u16s(&mut t, &[4, 32, 0, 4, 4, 1, 0]);
+        u16s(&mut t, &[a, 0xFFFF, 0, a, 0xFFFF]);
+        u16s(&mut t, &[1u16.wrapping_sub(a), 1, 0, 0]);
+        t
+    }
+
+    fn glyf() -> Vec<u8> {
+        let mut t = vec![];
+        i16s(&mut t, &[1, 100, 0, 500, 600]);
+        // The end point of the contour and the length of the instructions.
+        u16s(&mut t, &[3, 0]);
+        // Each point is on the curve, followed by the relative x and y coordinates.
+        t.extend_from_slice(&[1, 1, 1, 1]);
+        i16s(&mut t, &[100, 0, 400, 0]);
+        i16s(&mut t, &[0, 600, 0, -600]);
+        t
+    }
+
+    fn loca(glyf_len: usize) -> Vec<u8> {
+        let mut t = vec![];
+        u16s(&mut t, &[0, 0, (glyf_len / 2) as u16]);
+        t
+    }
+
+    fn post() -> Vec<u8> {
+        let mut t = vec![];
+        u32s(&mut t, &[0x0003_0000, 0]);
+        i16s(&mut t, &[-100, 50]);
+        u32s(&mut t, &[0, 0, 0, 0, 0]);
+        t
+    }
+
+    fn fvar() -> Vec<u8> {
+        let mut t = vec![];
+        u16s(&mut t, &[1, 0, 16, 2, 1, 20, 0, 8]);
+        t.extend_from_slice(b"wght");
+        u32s(&mut t, &[fixed(100), fixed(400), fixed(900)]);
+        u16s(&mut t, &[0, 256]);
+        t
+    }
+
+    fn gvar() -> Vec<u8> {
+        // The variation data of glyph 1, with a single tuple peaking at the maximum weight.
+        let mut data = vec![];
+        u16s(&mut data, &[1, 10]);
+        // The size of the serialised data, followed by an embedded peak tuple with private
+        // point numbers.
+        u16s(&mut data, &[19, 0x8000 | 0x2000, 0x4000]);
+        // All points, including the four phantom points, are varied.
+        data.push(0);
+        // Eight x deltas as words, moving the right edge and the advance.
+        data.push(0x40 | 7);
+        i16s(&mut data, &[0, 0, 200, 200, 0, 200, 0, 0]);
+        // Eight zero y deltas.
+        data.push(0x80 | 7);
+        data.push(0);
+
+        let mut t = vec![];
+        u16s(&mut t, &[1, 0, 1, 0]);
+        u32s(&mut t, &[20]);
+        u16s(&mut t, &[2, 0]);
+        u32s(&mut t, &[26]);
+        u16s(&mut t, &[0, 0, (data.len() / 2) as u16]);
+        t.extend(data);
+        t
+    }
+
+    pub fn build() -> Vec<u8> {
+        let glyf = glyf();
+        let mut tables: Vec<(&[u8; 4], Vec<u8>)> = vec![
+            (b"cmap", cmap()),
+            (b"fvar", fvar()),
+            (b"gvar", gvar()),
+            (b"head", head()),
+            (b"hhea", hhea()),
+            (b"hmtx", hmtx()),
+            (b"loca", loca(glyf.len())),
+            (b"maxp", maxp()),
+            (b"post", post()),
+        ];
+        tables.push((b"glyf", glyf));
+        tables.sort_by_key(|&(tag, _)| *tag);
+
+        let mut font = vec![];
+        u32s(&mut font, &[0x0001_0000]);
+        u16s(&mut font, &[tables.len() as u16, 128, 3, 16]);
+        let mut offset = 12 + 16 * tables.len();
+        for (tag, table) in &tables {
+            font.extend_from_slice(*tag);
+            u32s(&mut font, &[0, offset as u32, table.len() as u32]);
+            offset += table.len().div_ceil(4) * 4;
+        }
+        for (_, table) in &tables {
+            font.extend_from_slice(table);
+            font.resize(font.len().div_ceil(4) * 4, 0);
+        }
+        font
+    }
+}
+
+fn variable_font() -> Font {
+    Font::from_bytes(variable_font::build()).unwrap()
+}
+
+const SQUARE: GlyphId = GlyphId(1);
+
+fn wght(value: f32) -> Vec<Variation> {
+    vec![Variation::new(b"wght", value)]
+}
+
+// The horizontal extent of the given path events.
+fn x_range(events: &[nannou::lyon::path::PathEvent]) -> geom::Range {
+    use nannou::lyon::path::PathEvent;
+    let xs = events.iter().filter_map(|e| match *e {
+        PathEvent::Begin { at } => Some(at.x),
+        PathEvent::Line { to, .. } => Some(to.x),
+        _ => None,
+    });
+    let (min, max) = xs.fold((f32::MAX, f32::MIN), |(min, max), x| {
+        (min.min(x), max.max(x))
+    });
+    geom::Range::new(min, max)
+}
+
+fn assert_approx_eq(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+}
+
+#[test]
+fn variable_font_axes() {
+    let font = variable_font();
+    assert!(font.is_variable());
+    assert!(font.has_glyph('A'));
+    let axes = font.variation_axes();
+    assert_eq!(axes.len(), 1);
+    assert_eq!(
+        (axes[0].min, axes[0].default, axes[0].max),
+        (100.0, 400.0, 900.0)
+    );
+    assert!(!font::default_notosans().is_variable());
+}
+
+#[test]
+fn variations_produce_distinct_outlines() {
+    let font = variable_font();
+    // 1000 font units per pixel.
+    let scale = Scale::uniform(1.0);
+    let outline = |value| {
+        let events = glyph::path_events_with_variations(&font, SQUARE, scale, &wght(value));
+        x_range(&events.unwrap())
+    };
+    let regular = outline(400.0);
+    assert_approx_eq(regular.start, 0.1);
+    assert_approx_eq(regular.end, 0.5);
+    // Halfway to the maximum weight moves the right edge half the distance.
+    assert_approx_eq(outline(650.0).end, 0.6);
+    assert_approx_eq(outline(900.0).end, 0.7);
+    assert_approx_eq(outline(900.0).start, 0.1);
+    // Values beyond the axis are clamped.
+    assert_approx_eq(outline(2000.0).end, 0.7);
+}
+
+#[test]
+fn variations_produce_distinct_sdf_entries() {
+    let font = variable_font();
+    let font_id = font::id(&font);
+    let mut atlas = sdf::Atlas::new([512, 512], 48.0, 4.0);
+    let mut uploads = 0;
+    let mut entry = |atlas: &mut sdf::Atlas, variations: &[Variation]| {
+        let upload = |_: rt::Rect<u32>, _: &[u8]| uploads += 1;
+        atlas
+            .get_or_insert(font_id, &font, SQUARE, variations, upload)
+            .unwrap()
+            .unwrap()
+    };
+    let regular = entry(&mut atlas, &wght(400.0));
+    let bold = entry(&mut atlas, &wght(900.0));
+    assert_ne!(regular.uv, bold.uv);
+    assert!(bold.bounds.w() > regular.bounds.w());
+    // Repeated variations reuse the existing entry.
+    assert_eq!(entry(&mut atlas, &wght(900.0)), bold);
+    assert_eq!(uploads, 2);
+}
+
+#[test]
+fn variations_of_static_fonts_share_sdf_entries() {
+    let font = font::default_notosans();
+    let font_id = font::id(&font);
+    let id = font.glyph('A').id();
+    let mut atlas = sdf::Atlas::new([512, 512], 48.0, 4.0);
+    let mut uploads = 0;
+    let mut upload = |_: rt::Rect<u32>, _: &[u8]| uploads += 1;
+    let a = atlas.get_or_insert(font_id, &font, id, &[], &mut upload);
+    let b = atlas.get_or_insert(font_id, &font, id, &wght(900.0), &mut upload);
+    assert_eq!(a.unwrap(), b.unwrap());
+    assert_eq!(uploads, 1);
+}
+
+#[test]
+fn text_applies_layout_variations() {
+    let rect = geom::Rect::from_w_h(400.0, 200.0);
+    let glyph_width = |variations: Vec<Variation>| {
+        let text = text::text("A")
+            .font(variable_font())
+            .font_size(100)
+            .variations(variations)
+            .build(rect);
+        let g = text.shaped_lines()[0].glyphs[0];
+        let varied = text.is_glyph_varied(&g);
+        (varied, x_range(&text.glyph_path_events(&g)).len())
+    };
+    let (varied, default_width) = glyph_width(vec![]);
+    assert!(!varied);
+    let (varied, regular_width) = glyph_width(wght(400.0));
+    assert!(varied);
+    assert_approx_eq(regular_width, default_width);
+    let (varied, bold_width) = glyph_width(wght(900.0));
+    assert!(varied);
+    assert_approx_eq(bold_width, regular_width * 1.5);
+}
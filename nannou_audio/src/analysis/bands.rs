@@ -0,0 +1,74 @@
+//! Grouping of spectrum bins into frequency bands.
+
+use super::stft::Spectrum;
+
+/// A set of adjacent frequency bands.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bands {
+    edges: Vec<f32>,
+}
+
+impl Bands {
+    /// The given number of bands spaced logarithmically between the given frequencies in Hz.
+    ///
+    /// Logarithmic bands correspond to how pitch is perceived, with each band spanning the same
+    /// musical interval.
+    ///
+    /// **Panics** if `count` is `0` or the frequencies are not positive and ascending.
+    pub fn logarithmic(count: usize, min_hz: f32, max_hz: f32) -> Self {
+        assert!(count > 0);
+        assert!(0.0 < min_hz && min_hz < max_hz);
+        let ratio = (max_hz / min_hz).powf(1.0 / count as f32);
+        let edges = (0..=count).map(|i| min_hz * ratio.powi(i as i32)).collect();
+        Bands { edges }
+    }
+
+    /// Bands divided at the given ascending frequencies in Hz.
+    ///
+    /// **Panics** if there are fewer than two edges.
+    pub fn from_edges(edges: Vec<f32>) -> Self {
+        assert!(edges.len() >= 2, "at least two edges are required");
+        Bands { edges }
+    }
+
+    /// The number of bands.
+    pub fn count(&self) -> usize {
+        self.edges.len() - 1
+    }
+
+    /// The frequencies dividing the bands in Hz, one more than the number of bands.
+    pub fn edges(&self) -> &[f32] {
+        &self.edges
+    }
+
+    /// The center frequency of the band at the given index in Hz, i.e. the geometric mean of
+    /// its edges.
+    pub fn center(&self, index: usize) -> f32 {
+        (self.edges[index] * self.edges[index + 1]).sqrt()
+    }
+
+    /// Write the magnitude of each band of the given spectrum to `magnitudes`.
+    ///
+    /// The magnitude of a band is the root of the summed power of the bins within it, corrected for
+    /// the noise bandwidth of the window, such that a sinusoid has the same magnitude regardless of
+    /// the width of the band in which it lies. Bands narrower than a bin use the magnitude of the
+    /// bin nearest to their center.
+    ///
+    /// **Panics** if the length of `magnitudes` differs from the number of bands.
+    pub fn magnitudes(&self, spectrum: &Spectrum, magnitudes: &mut [f32]) {
+        assert_eq!(magnitudes.len(), self.count());
+        let bins = spectrum.magnitudes();
+        let bin_width = spectrum.bin_width();
+        let noise_bandwidth = spectrum.noise_bandwidth();
+        for (i, m) in magnitudes.iter_mut().enumerate() {
+            let start = (self.edges[i] / bin_width).ceil() as usize;
+            let end = ((self.edges[i + 1] / bin_width).ceil() as usize).min(bins.len());
+            *m = if start < end {
+                let power: f32 = bins[start..end].iter().map(|b| b * b).sum();
+                (power / noise_bandwidth).sqrt()
+            } else {
+                bins[spectrum.frequency_bin(self.center(i))]
+            };
+        }
+    }
+}
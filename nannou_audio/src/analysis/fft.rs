@@ -0,0 +1,141 @@
+//! A radix-2 fast Fourier transform.
+
+use std::ops::{Add, Mul, Sub};
+
+/// A complex number.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f32,
+    pub im: f32,
+}
+
+/// A fast Fourier transform of a fixed, power-of-two size.
+///
+/// The twiddle factors and bit-reversal permutation are computed once upon construction, so that
+/// transforms do not allocate.
+#[derive(Clone, Debug)]
+pub struct Fft {
+    twiddles: Vec<Complex>,
+    bit_reversed: Vec<usize>,
+}
+
+impl Complex {
+    /// A complex number with the given real and imaginary parts.
+    pub fn new(re: f32, im: f32) -> Self {
+        Complex { re, im }
+    }
+
+    /// The complex conjugate.
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// The magnitude.
+    pub fn norm(self) -> f32 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Fft {
+    /// Plan a transform of the given size.
+    ///
+    /// **Panics** if `size` is not a power of two.
+    pub fn new(size: usize) -> Self {
+        assert!(
+            size.is_power_of_two(),
+            "the FFT size must be a power of two"
+        );
+        let twiddles = (0..size / 2)
+            .map(|k| {
+                let angle = -2.0 * std::f64::consts::PI * k as f64 / size as f64;
+                Complex::new(angle.cos() as f32, angle.sin() as f32)
+            })
+            .collect();
+        let bits = size.trailing_zeros();
+        let bit_reversed = (0..size)
+            .map(|i| match bits {
+                0 => 0,
+                _ => i.reverse_bits() >> (usize::BITS - bits),
+            })
+            .collect();
+        Fft {
+            twiddles,
+            bit_reversed,
+        }
+    }
+
+    /// The size of the transform.
+    pub fn size(&self) -> usize {
+        self.bit_reversed.len()
+    }
+
+    /// Transform the given time domain data to the frequency domain in place.
+    ///
+    /// **Panics** if the length of `data` differs from the size of the transform.
+    pub fn forward(&self, data: &mut [Complex]) {
+        self.transform(data, false);
+    }
+
+    /// Transform the given frequency domain data to the time domain in place, scaling the result
+    /// by `1 / size`.
+    ///
+    /// **Panics** if the length of `data` differs from the size of the transform.
+    pub fn inverse(&self, data: &mut [Complex]) {
+        self.transform(data, true);
+        let scale = 1.0 / data.len() as f32;
+        for c in data {
+            c.re *= scale;
+            c.im *= scale;
+        }
+    }
+
+    fn transform(&self, data: &mut [Complex], inverse: bool) {
+        let n = self.size();
+        assert_eq!(data.len(), n, "the data length must match the FFT size");
+        for (i, &j) in self.bit_reversed.iter().enumerate() {
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let step = n / len;
+            for start in (0..n).step_by(len) {
+                for k in 0..half {
+                    let w = self.twiddles[k * step];
+                    let w = if inverse { w.conj() } else { w };
+                    let a = data[start + k];
+                    let b = data[start + k + half] * w;
+                    data[start + k] = a + b;
+                    data[start + k + half] = a - b;
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
@@ -0,0 +1,118 @@
+//! RMS and peak level meters.
+
+use dasp_sample::{Sample, ToSample};
+
+/// The level of a signal.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Level {
+    /// The root mean square of the signal, averaged over the meter's RMS time.
+    pub rms: f32,
+    /// The greatest absolute sample value, decaying over the meter's peak release time.
+    pub peak: f32,
+}
+
+/// Measures the RMS and peak levels of each channel of an interleaved signal.
+///
+/// Both measurements respond to increases in level immediately and decay exponentially, as
+/// is the case for the ballistics of a hardware meter.
+#[derive(Clone, Debug)]
+pub struct Meter {
+    rms_time: f32,
+    peak_release: f32,
+    mean_squares: Vec<f32>,
+    levels: Vec<Level>,
+}
+
+impl Meter {
+    /// The default time over which the RMS is averaged in seconds.
+    pub const DEFAULT_RMS_TIME: f32 = 0.3;
+    /// The default time over which the peak decays by a factor of `e` in seconds.
+    pub const DEFAULT_PEAK_RELEASE: f32 = 0.5;
+
+    /// A meter with the default RMS time and peak release.
+    pub fn new() -> Self {
+        Meter {
+            rms_time: Self::DEFAULT_RMS_TIME,
+            peak_release: Self::DEFAULT_PEAK_RELEASE,
+            mean_squares: vec![],
+            levels: vec![],
+        }
+    }
+
+    /// The time over which the RMS is averaged in seconds.
+    ///
+    /// A time of `0` measures the level of each sample.
+    pub fn rms_time(mut self, secs: f32) -> Self {
+        self.rms_time = secs;
+        self
+    }
+
+    /// The time over which the peak decays by a factor of `e` in seconds.
+    ///
+    /// A time of `0` measures the peak of each sample.
+    pub fn peak_release(mut self, secs: f32) -> Self {
+        self.peak_release = secs;
+        self
+    }
+
+    /// Measure the given interleaved samples.
+    ///
+    /// The number of levels is updated to match the number of channels, allocating only when the
+    /// number of channels increases.
+    pub fn process<S>(&mut self, samples: &[S], channels: usize, sample_rate: u32)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        if self.levels.len() != channels {
+            self.levels.resize(channels, Level::default());
+            self.mean_squares.resize(channels, 0.0);
+        }
+        let decay = |secs: f32| match secs > 0.0 {
+            true => (-1.0 / (secs * sample_rate as f32)).exp(),
+            false => 0.0,
+        };
+        let rms_decay = decay(self.rms_time);
+        let peak_decay = decay(self.peak_release);
+        for frame in samples.chunks_exact(channels) {
+            let channels = frame
+                .iter()
+                .zip(&mut self.mean_squares)
+                .zip(&mut self.levels);
+            for ((&s, ms), level) in channels {
+                let s: f32 = s.to_sample();
+                *ms = *ms * rms_decay + s * s * (1.0 - rms_decay);
+                level.peak = (level.peak * peak_decay).max(s.abs());
+            }
+        }
+        for (level, &ms) in self.levels.iter_mut().zip(&self.mean_squares) {
+            level.rms = ms.sqrt();
+        }
+    }
+
+    /// The level of each channel.
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// The level across all channels, i.e. the RMS of all channels and the greatest peak.
+    pub fn level(&self) -> Level {
+        let n = self.mean_squares.len().max(1) as f32;
+        let ms = self.mean_squares.iter().sum::<f32>() / n;
+        let peak = self.levels.iter().fold(0.0f32, |peak, l| peak.max(l.peak));
+        Level {
+            rms: ms.sqrt(),
+            peak,
+        }
+    }
+}
+
+impl Default for Meter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert the given amplitude to decibels relative to full scale.
+pub fn to_db(amplitude: f32) -> f32 {
+    20.0 * amplitude.max(f32::MIN_POSITIVE).log10()
+}
@@ -0,0 +1,430 @@
+//! Real-time analysis of audio streams.
+//!
+//! The **Analyzer** is designed to live within the model of an input or output stream, where it
+//! can process each buffer without allocating or locking. Each call to **process** publishes the
+//! latest **Analysis**, which may be read from another thread, e.g. the main thread, via the
+//! **Reader** returned alongside the **Analyzer**.
+//!
+//! ```
+//! use nannou_audio::analysis::{self, Analyzer, Reader};
+//! use nannou_audio::Buffer;
+//!
+//! // Within the audio stream's capture function.
+//! fn capture(analyzer: &mut Analyzer, buffer: &Buffer) {
+//!     analyzer.process(buffer);
+//! }
+//!
+//! // Within the main thread, e.g. within `update` or `view`.
+//! fn update(reader: &mut Reader) {
+//!     let analysis = reader.read();
+//!     println!("{:?} {:?}", analysis.level, analysis.pitch);
+//! }
+//!
+//! let (analyzer, reader) = analysis::Builder::new().bands(8, 60.0, 12_000.0).build();
+//! ```
+//!
+//! Each of the underlying components is also available for use on its own.
+//!
+//! - [**Stft**](./struct.Stft.html) - the short-time Fourier transform of a stream of samples.
+//! - [**Meter**](./struct.Meter.html) - RMS and peak levels.
+//! - [**Bands**](./struct.Bands.html) - grouping of spectrum bins into frequency bands.
+//! - [**OnsetDetector**](./struct.OnsetDetector.html) - spectral flux onset detection.
+//! - [**PitchDetector**](./struct.PitchDetector.html) - fundamental frequency estimation.
+
+use crate::Buffer;
+use dasp_sample::{Sample, ToSample};
+
+pub use self::bands::Bands;
+pub use self::fft::{Complex, Fft};
+pub use self::meter::{to_db, Level, Meter};
+pub use self::onset::{Onset, OnsetDetector};
+pub use self::pitch::{Pitch, PitchDetector};
+pub use self::stft::{Spectrum, Stft};
+pub use self::window::Window;
+
+pub mod bands;
+pub mod fft;
+pub mod meter;
+pub mod onset;
+pub mod pitch;
+pub mod stft;
+mod triple_buffer;
+pub mod window;
+
+/// A type used for building an **Analyzer** and its **Reader**.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    pub fft_size: usize,
+    pub hop: usize,
+    pub window: Window,
+    pub bands: Bands,
+    pub smoothing: f32,
+    pub meter: Meter,
+    pub onset_detector: OnsetDetector,
+    pub pitch: bool,
+    pub pitch_threshold: f32,
+    pub pitch_range: (f32, f32),
+}
+
+/// Analyses a stream of audio buffers, publishing the results to a **Reader**.
+pub struct Analyzer {
+    stft: Stft,
+    bands: Bands,
+    smoothing: f32,
+    meter: Meter,
+    onset_detector: OnsetDetector,
+    pitch_detector: Option<PitchDetector>,
+    frame: Vec<f32>,
+    band_magnitudes: Vec<f32>,
+    analysis: Analysis,
+    writer: triple_buffer::Writer<Analysis>,
+}
+
+/// Reads the most recent **Analysis** published by an **Analyzer**, e.g. on the main thread.
+pub struct Reader {
+    reader: triple_buffer::Reader<Analysis>,
+}
+
+/// The results of analysing the buffers processed by an **Analyzer** so far.
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    /// The sample rate of the most recently processed buffer.
+    pub sample_rate: u32,
+    /// The total number of frames processed.
+    pub frames: u64,
+    /// The level across all channels.
+    pub level: Level,
+    /// The level of each channel.
+    pub channel_levels: Vec<Level>,
+    /// The smoothed magnitude of each frequency bin, from DC up to the Nyquist frequency.
+    pub spectrum: Vec<f32>,
+    /// The size of the transform that produced the spectrum.
+    pub fft_size: usize,
+    /// The smoothed magnitude of each frequency band.
+    pub bands: Vec<f32>,
+    /// The frequencies dividing the bands in Hz, one more than the number of bands.
+    pub band_edges: Vec<f32>,
+    /// The spectral flux of the most recent spectrum.
+    pub flux: f32,
+    /// The total number of onsets detected.
+    ///
+    /// Comparing this with its value at the previous read reveals whether any onsets occurred in
+    /// between, even if more than one spectrum was analysed.
+    pub onsets: u64,
+    /// The most recently detected onset.
+    pub onset: Option<Onset>,
+    /// The pitch of the most recent frame, if it is pitched and pitch detection is enabled.
+    pub pitch: Option<Pitch>,
+}
+
+impl Builder {
+    /// The default number of samples per frame of the spectrum.
+    pub const DEFAULT_FFT_SIZE: usize = 2048;
+    /// The default number of samples between the start of each frame.
+    pub const DEFAULT_HOP: usize = 512;
+    /// The default number of logarithmically spaced bands.
+    pub const DEFAULT_BAND_COUNT: usize = 16;
+    /// The default range covered by the bands in Hz.
+    pub const DEFAULT_BAND_RANGE: (f32, f32) = (40.0, 16_000.0);
+    /// The default amount by which the spectrum and bands are smoothed between frames.
+    pub const DEFAULT_SMOOTHING: f32 = 0.5;
+
+    /// A builder with the default parameters.
+    pub fn new() -> Self {
+        let (min_hz, max_hz) = Self::DEFAULT_BAND_RANGE;
+        Builder {
+            fft_size: Self::DEFAULT_FFT_SIZE,
+            hop: Self::DEFAULT_HOP,
+            window: Window::default(),
+            bands: Bands::logarithmic(Self::DEFAULT_BAND_COUNT, min_hz, max_hz),
+            smoothing: Self::DEFAULT_SMOOTHING,
+            meter: Meter::new(),
+            onset_detector: OnsetDetector::new(),
+            pitch: true,
+            pitch_threshold: PitchDetector::DEFAULT_THRESHOLD,
+            pitch_range: (PitchDetector::DEFAULT_MIN_HZ, PitchDetector::DEFAULT_MAX_HZ),
+        }
+    }
+
+    /// The number of samples per frame of the spectrum.
+    ///
+    /// Larger sizes yield finer frequency resolution and detect lower pitches at the cost of time
+    /// resolution.
+    ///
+    /// **Panics** if `size` is not a power of two or is less than `4`.
+    pub fn fft_size(mut self, size: usize) -> Self {
+        assert!(
+            size.is_power_of_two(),
+            "the FFT size must be a power of two"
+        );
+        assert!(size >= 4, "the FFT size must be at least 4");
+        self.fft_size = size;
+        self
+    }
+
+    /// The number of samples between the start of each frame.
+    ///
+    /// **Panics** if `hop` is `0`.
+    pub fn hop(mut self, hop: usize) -> Self {
+        assert!(hop > 0, "the hop size must be greater than 0");
+        self.hop = hop;
+        self
+    }
+
+    /// The window applied to each frame before the transform.
+    pub fn window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// The given number of bands spaced logarithmically between the given frequencies in Hz.
+    pub fn bands(self, count: usize, min_hz: f32, max_hz: f32) -> Self {
+        self.custom_bands(Bands::logarithmic(count, min_hz, max_hz))
+    }
+
+    /// The bands into which the spectrum is grouped.
+    pub fn custom_bands(mut self, bands: Bands) -> Self {
+        self.bands = bands;
+        self
+    }
+
+    /// The amount by which the spectrum and bands are smoothed between frames, from `0` (none)
+    /// up to but excluding `1`.
+    ///
+    /// **Panics** if `smoothing` is outside of the range `0.0..1.0`.
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        assert!((0.0..1.0).contains(&smoothing));
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// The meter used to measure levels.
+    pub fn meter(mut self, meter: Meter) -> Self {
+        self.meter = meter;
+        self
+    }
+
+    /// The detector used to detect onsets.
+    pub fn onset_detector(mut self, detector: OnsetDetector) -> Self {
+        self.onset_detector = detector;
+        self
+    }
+
+    /// Whether or not to estimate the pitch of each frame.
+    ///
+    /// Pitch detection is the most expensive part of the analysis and may be disabled if it is
+    /// not required.
+    pub fn pitch(mut self, enabled: bool) -> Self {
+        self.pitch = enabled;
+        self
+    }
+
+    /// The threshold of the normalised difference below which a period is accepted.
+    pub fn pitch_threshold(mut self, threshold: f32) -> Self {
+        self.pitch_threshold = threshold;
+        self
+    }
+
+    /// The range of detectable pitches in Hz.
+    pub fn pitch_range(mut self, min_hz: f32, max_hz: f32) -> Self {
+        assert!(0.0 < min_hz && min_hz < max_hz);
+        self.pitch_range = (min_hz, max_hz);
+        self
+    }
+
+    /// Build the **Analyzer** and the **Reader** of its results.
+    pub fn build(self) -> (Analyzer, Reader) {
+        let Builder {
+            fft_size,
+            hop,
+            window,
+            bands,
+            smoothing,
+            meter,
+            onset_detector,
+            pitch,
+            pitch_threshold,
+            pitch_range: (min_hz, max_hz),
+        } = self;
+        let stft = Stft::new(fft_size, hop, window);
+        let pitch_detector = match pitch {
+            true => Some(
+                PitchDetector::new(fft_size)
+                    .threshold(pitch_threshold)
+                    .frequency_range(min_hz, max_hz),
+            ),
+            false => None,
+        };
+        let analysis = Analysis {
+            sample_rate: 0,
+            frames: 0,
+            level: Level::default(),
+            channel_levels: vec![],
+            spectrum: vec![0.0; fft_size / 2 + 1],
+            fft_size,
+            bands: vec![0.0; bands.count()],
+            band_edges: bands.edges().to_vec(),
+            flux: 0.0,
+            onsets: 0,
+            onset: None,
+            pitch: None,
+        };
+        let (writer, reader) = triple_buffer::new(analysis.clone());
+        let analyzer = Analyzer {
+            stft,
+            band_magnitudes: vec![0.0; bands.count()],
+            bands,
+            smoothing,
+            meter,
+            onset_detector,
+            pitch_detector,
+            frame: vec![0.0; fft_size],
+            analysis,
+            writer,
+        };
+        let reader = Reader { reader };
+        (analyzer, reader)
+    }
+}
+
+impl Analyzer {
+    /// Analyse the given buffer and publish the results.
+    pub fn process<S>(&mut self, buffer: &Buffer<S>)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        self.process_interleaved(buffer, buffer.channels(), buffer.sample_rate());
+    }
+
+    /// Analyse the given interleaved samples and publish the results.
+    ///
+    /// Channels are mixed down to mono for all analysis other than metering.
+    ///
+    /// This does not allocate unless the number of channels increases.
+    pub fn process_interleaved<S>(&mut self, samples: &[S], channels: usize, sample_rate: u32)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        assert!(
+            channels > 0,
+            "the number of channels must be greater than 0"
+        );
+        self.meter.process(samples, channels, sample_rate);
+        let hop_secs = self.stft.hop() as f32 / sample_rate as f32;
+        for frame in samples.chunks_exact(channels) {
+            let sum: f32 = frame.iter().map(|&s| s.to_sample::<f32>()).sum();
+            if self.stft.push(sum / channels as f32, sample_rate) {
+                self.analyse_frame(hop_secs);
+            }
+        }
+
+        let analysis = &mut self.analysis;
+        analysis.sample_rate = sample_rate;
+        analysis.frames += (samples.len() / channels) as u64;
+        analysis.level = self.meter.level();
+        analysis.channel_levels.clear();
+        analysis
+            .channel_levels
+            .extend_from_slice(self.meter.levels());
+        self.writer.write().clone_from(analysis);
+        self.writer.publish();
+    }
+
+    /// The results of the analysis so far.
+    pub fn analysis(&self) -> &Analysis {
+        &self.analysis
+    }
+
+    fn analyse_frame(&mut self, hop_secs: f32) {
+        let spectrum = self.stft.spectrum();
+        let analysis = &mut self.analysis;
+        let smoothing = self.smoothing;
+        let smooth = |smoothed: &mut f32, m: f32| {
+            *smoothed = *smoothed * smoothing + m * (1.0 - smoothing);
+        };
+
+        for (s, &m) in analysis.spectrum.iter_mut().zip(spectrum.magnitudes()) {
+            smooth(s, m);
+        }
+        self.bands.magnitudes(spectrum, &mut self.band_magnitudes);
+        for (b, &m) in analysis.bands.iter_mut().zip(&self.band_magnitudes) {
+            smooth(b, m);
+        }
+
+        if let Some(onset) = self.onset_detector.process(spectrum, hop_secs) {
+            analysis.onsets += 1;
+            analysis.onset = Some(onset);
+        }
+        analysis.flux = self.onset_detector.flux();
+
+        if let Some(ref mut detector) = self.pitch_detector {
+            self.stft.copy_frame(&mut self.frame);
+            analysis.pitch = detector.detect(&self.frame, spectrum.sample_rate());
+        }
+    }
+}
+
+impl Reader {
+    /// The most recent analysis published by the **Analyzer**.
+    ///
+    /// This never waits on the audio thread.
+    pub fn read(&mut self) -> &Analysis {
+        self.reader.read()
+    }
+}
+
+impl Analysis {
+    /// The center frequency of the spectrum bin at the given index in Hz.
+    pub fn bin_frequency(&self, index: usize) -> f32 {
+        index as f32 * self.sample_rate as f32 / self.fft_size as f32
+    }
+
+    /// The number of seconds of audio processed.
+    pub fn time(&self) -> f64 {
+        match self.sample_rate {
+            0 => 0.0,
+            sr => self.frames as f64 / sr as f64,
+        }
+    }
+}
+
+// Implemented manually such that `clone_from` reuses the existing allocations when publishing.
+impl Clone for Analysis {
+    fn clone(&self) -> Self {
+        Analysis {
+            sample_rate: self.sample_rate,
+            frames: self.frames,
+            level: self.level,
+            channel_levels: self.channel_levels.clone(),
+            spectrum: self.spectrum.clone(),
+            fft_size: self.fft_size,
+            bands: self.bands.clone(),
+            band_edges: self.band_edges.clone(),
+            flux: self.flux,
+            onsets: self.onsets,
+            onset: self.onset,
+            pitch: self.pitch,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.sample_rate = source.sample_rate;
+        self.frames = source.frames;
+        self.level = source.level;
+        self.channel_levels.clone_from(&source.channel_levels);
+        self.spectrum.clone_from(&source.spectrum);
+        self.fft_size = source.fft_size;
+        self.bands.clone_from(&source.bands);
+        self.band_edges.clone_from(&source.band_edges);
+        self.flux = source.flux;
+        self.onsets = source.onsets;
+        self.onset = source.onset;
+        self.pitch = source.pitch;
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,137 @@
+//! Onset detection via spectral flux.
+
+use super::stft::Spectrum;
+
+/// Detects onsets, e.g. beats and note attacks, within a sequence of spectra.
+///
+/// The spectral flux of each spectrum, i.e. the increase in magnitude across all bins since the
+/// previous spectrum, is compared with an adaptive threshold derived from the mean and standard
+/// deviation of the recent flux.
+#[derive(Clone, Debug)]
+pub struct OnsetDetector {
+    sensitivity: f32,
+    min_flux: f32,
+    min_interval: f32,
+    previous: Vec<f32>,
+    history: Vec<f32>,
+    history_index: usize,
+    history_filled: usize,
+    since_onset: f32,
+    flux: f32,
+}
+
+/// An onset detected by an **OnsetDetector**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Onset {
+    /// The ratio of the spectral flux to the threshold, always greater than `1`.
+    pub strength: f32,
+}
+
+impl OnsetDetector {
+    /// The default number of standard deviations above the mean flux at which onsets are detected.
+    pub const DEFAULT_SENSITIVITY: f32 = 2.0;
+    /// The default flux below which onsets are never detected, e.g. to ignore noise.
+    pub const DEFAULT_MIN_FLUX: f32 = 0.01;
+    /// The default minimum time between onsets in seconds.
+    pub const DEFAULT_MIN_INTERVAL: f32 = 0.1;
+    /// The default number of spectra over which the threshold is derived.
+    pub const DEFAULT_HISTORY_LEN: usize = 32;
+
+    /// A detector with the default parameters.
+    pub fn new() -> Self {
+        OnsetDetector {
+            sensitivity: Self::DEFAULT_SENSITIVITY,
+            min_flux: Self::DEFAULT_MIN_FLUX,
+            min_interval: Self::DEFAULT_MIN_INTERVAL,
+            previous: vec![],
+            history: vec![0.0; Self::DEFAULT_HISTORY_LEN],
+            history_index: 0,
+            history_filled: 0,
+            since_onset: f32::INFINITY,
+            flux: 0.0,
+        }
+    }
+
+    /// The number of standard deviations above the mean flux at which onsets are detected.
+    ///
+    /// Lower values detect more onsets.
+    pub fn sensitivity(mut self, sensitivity: f32) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    /// The flux below which onsets are never detected, e.g. to ignore noise.
+    pub fn min_flux(mut self, min_flux: f32) -> Self {
+        self.min_flux = min_flux;
+        self
+    }
+
+    /// The minimum time between onsets in seconds.
+    pub fn min_interval(mut self, secs: f32) -> Self {
+        self.min_interval = secs;
+        self
+    }
+
+    /// The number of spectra over which the threshold is derived.
+    ///
+    /// **Panics** if `len` is `0`.
+    pub fn history_len(mut self, len: usize) -> Self {
+        assert!(len > 0);
+        self.history = vec![0.0; len];
+        self.history_index = 0;
+        self.history_filled = 0;
+        self
+    }
+
+    /// The spectral flux of the most recent spectrum.
+    pub fn flux(&self) -> f32 {
+        self.flux
+    }
+
+    /// Process the next spectrum, produced `hop_secs` seconds after the previous one.
+    ///
+    /// Returns an **Onset** if one was detected.
+    pub fn process(&mut self, spectrum: &Spectrum, hop_secs: f32) -> Option<Onset> {
+        // Compress the magnitudes so that changes in quiet bins are not drowned out.
+        let compress = |m: f32| (1.0 + 100.0 * m).ln();
+        let bins = spectrum.magnitudes();
+        if self.previous.len() != bins.len() {
+            self.previous.clear();
+            self.previous.extend(bins.iter().map(|&m| compress(m)));
+            return None;
+        }
+        let mut flux = 0.0;
+        for (prev, &m) in self.previous.iter_mut().zip(bins) {
+            let m = compress(m);
+            flux += (m - *prev).max(0.0);
+            *prev = m;
+        }
+        self.flux = flux / bins.len() as f32;
+
+        // Derive the threshold from the flux preceding this spectrum.
+        let recent = &self.history[..self.history_filled];
+        let n = recent.len().max(1) as f32;
+        let mean = recent.iter().sum::<f32>() / n;
+        let variance = recent.iter().map(|f| (f - mean).powi(2)).sum::<f32>() / n;
+        let threshold = (mean + self.sensitivity * variance.sqrt()).max(self.min_flux);
+
+        self.history[self.history_index] = self.flux;
+        self.history_index = (self.history_index + 1) % self.history.len();
+        self.history_filled = (self.history_filled + 1).min(self.history.len());
+
+        self.since_onset += hop_secs;
+        if self.flux <= threshold || self.since_onset < self.min_interval {
+            return None;
+        }
+        self.since_onset = 0.0;
+        Some(Onset {
+            strength: self.flux / threshold,
+        })
+    }
+}
+
+impl Default for OnsetDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,148 @@
+//! Fundamental frequency estimation via the YIN algorithm.
+
+use super::fft::{Complex, Fft};
+
+/// Estimates the fundamental frequency of frames of a monophonic signal.
+///
+/// This is an implementation of the YIN algorithm, using an FFT to compute the difference function
+/// in `O(n log n)` time.
+#[derive(Clone, Debug)]
+pub struct PitchDetector {
+    size: usize,
+    threshold: f32,
+    min_hz: f32,
+    max_hz: f32,
+    fft: Fft,
+    a: Vec<Complex>,
+    b: Vec<Complex>,
+    energies: Vec<f32>,
+    difference: Vec<f32>,
+}
+
+/// A pitch estimated by a **PitchDetector**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pitch {
+    /// The fundamental frequency in Hz.
+    pub frequency: f32,
+    /// How periodic the frame is, from `0` to `1`.
+    pub clarity: f32,
+}
+
+impl PitchDetector {
+    /// The default threshold of the normalised difference below which a period is accepted.
+    pub const DEFAULT_THRESHOLD: f32 = 0.15;
+    /// The default lowest detectable frequency in Hz.
+    pub const DEFAULT_MIN_HZ: f32 = 50.0;
+    /// The default highest detectable frequency in Hz.
+    pub const DEFAULT_MAX_HZ: f32 = 2_000.0;
+
+    /// A detector for frames of the given number of samples.
+    ///
+    /// Periods of up to half the frame size may be detected, so the frame must span at least two
+    /// periods of the lowest frequency of interest.
+    ///
+    /// **Panics** if `size` is not a power of two or is less than `4`.
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 4, "the frame size must be at least 4");
+        let fft = Fft::new(size * 2);
+        PitchDetector {
+            size,
+            threshold: Self::DEFAULT_THRESHOLD,
+            min_hz: Self::DEFAULT_MIN_HZ,
+            max_hz: Self::DEFAULT_MAX_HZ,
+            fft,
+            a: vec![Complex::default(); size * 2],
+            b: vec![Complex::default(); size * 2],
+            energies: vec![0.0; size + 1],
+            difference: vec![0.0; size / 2],
+        }
+    }
+
+    /// The threshold of the normalised difference below which a period is accepted.
+    ///
+    /// Lower values reject more frames as unpitched.
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// The range of detectable frequencies in Hz.
+    pub fn frequency_range(mut self, min_hz: f32, max_hz: f32) -> Self {
+        assert!(0.0 < min_hz && min_hz < max_hz);
+        self.min_hz = min_hz;
+        self.max_hz = max_hz;
+        self
+    }
+
+    /// Estimate the pitch of the given frame.
+    ///
+    /// Returns `None` if the frame is silent or not sufficiently periodic.
+    ///
+    /// **Panics** if the length of `frame` differs from the detector's size.
+    pub fn detect(&mut self, frame: &[f32], sample_rate: u32) -> Option<Pitch> {
+        assert_eq!(frame.len(), self.size);
+        let w = self.size / 2;
+
+        // The energy of each window of `w` samples via a running sum.
+        self.energies[0] = 0.0;
+        for (i, &s) in frame.iter().enumerate() {
+            self.energies[i + 1] = self.energies[i] + s * s;
+        }
+        let energies = &self.energies;
+        let energy = |start: usize| energies[start + w] - energies[start];
+        let e0 = energy(0);
+        if e0 <= f32::EPSILON {
+            return None;
+        }
+
+        // The correlation of the first window with each lag of the frame via the FFT.
+        for (i, (a, b)) in self.a.iter_mut().zip(&mut self.b).enumerate() {
+            let s = frame.get(i).cloned().unwrap_or(0.0);
+            *a = Complex::new(if i < w { s } else { 0.0 }, 0.0);
+            *b = Complex::new(s, 0.0);
+        }
+        self.fft.forward(&mut self.a);
+        self.fft.forward(&mut self.b);
+        for (a, &b) in self.a.iter_mut().zip(&self.b) {
+            *a = a.conj() * b;
+        }
+        self.fft.inverse(&mut self.a);
+
+        // The cumulative mean normalised difference function.
+        let mut sum = 0.0;
+        self.difference[0] = 1.0;
+        for tau in 1..w {
+            let d = (e0 + energy(tau) - 2.0 * self.a[tau].re).max(0.0);
+            sum += d;
+            self.difference[tau] = if sum > 0.0 { d * tau as f32 / sum } else { 1.0 };
+        }
+
+        // Find the first minimum below the threshold within the frequency range.
+        let min_tau = ((sample_rate as f32 / self.max_hz).floor() as usize).max(1);
+        let max_tau = ((sample_rate as f32 / self.min_hz).ceil() as usize).min(w - 1);
+        let d = &self.difference;
+        let mut tau = (min_tau..max_tau).find(|&tau| d[tau] < self.threshold)?;
+        while tau + 1 < max_tau && d[tau + 1] < d[tau] {
+            tau += 1;
+        }
+
+        // Refine the period via parabolic interpolation.
+        let offset = match tau > 0 && tau + 1 < w {
+            true => {
+                let (l, c, r) = (d[tau - 1], d[tau], d[tau + 1]);
+                let denom = l - 2.0 * c + r;
+                if denom.abs() > f32::EPSILON {
+                    (0.5 * (l - r) / denom).clamp(-0.5, 0.5)
+                } else {
+                    0.0
+                }
+            }
+            false => 0.0,
+        };
+        let period = tau as f32 + offset;
+        Some(Pitch {
+            frequency: sample_rate as f32 / period,
+            clarity: (1.0 - d[tau]).clamp(0.0, 1.0),
+        })
+    }
+}
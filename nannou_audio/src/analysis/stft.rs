@@ -0,0 +1,170 @@
+//! A short-time Fourier transform over a stream of mono samples.
+
+use super::fft::{Complex, Fft};
+use super::window::Window;
+
+/// Produces the magnitude spectrum of overlapping, windowed frames of a stream of samples.
+#[derive(Clone, Debug)]
+pub struct Stft {
+    fft: Fft,
+    window: Window,
+    coefficients: Vec<f32>,
+    hop: usize,
+    // The most recent `size` samples, written cyclically.
+    samples: Vec<f32>,
+    // The index of the oldest sample within `samples`.
+    next: usize,
+    // The number of samples pushed, up to `size`.
+    filled: usize,
+    // The number of samples pushed since the last frame.
+    since_frame: usize,
+    scratch: Vec<Complex>,
+    spectrum: Spectrum,
+}
+
+/// The magnitude spectrum of a single frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum {
+    magnitudes: Vec<f32>,
+    fft_size: usize,
+    sample_rate: u32,
+    noise_bandwidth: f32,
+}
+
+impl Stft {
+    /// A transform with frames of `size` samples, produced every `hop` samples.
+    ///
+    /// **Panics** if `size` is not a power of two or `hop` is `0`.
+    pub fn new(size: usize, hop: usize, window: Window) -> Self {
+        assert!(hop > 0, "the hop size must be greater than 0");
+        let mut coefficients = vec![0.0; size];
+        window.fill(&mut coefficients);
+        let sum: f32 = coefficients.iter().sum();
+        let sum_squares: f32 = coefficients.iter().map(|w| w * w).sum();
+        Stft {
+            fft: Fft::new(size),
+            window,
+            coefficients,
+            hop,
+            samples: vec![0.0; size],
+            next: 0,
+            filled: 0,
+            since_frame: 0,
+            scratch: vec![Complex::default(); size],
+            spectrum: Spectrum {
+                magnitudes: vec![0.0; size / 2 + 1],
+                fft_size: size,
+                sample_rate: 0,
+                noise_bandwidth: size as f32 * sum_squares / (sum * sum),
+            },
+        }
+    }
+
+    /// The number of samples per frame.
+    pub fn size(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// The number of samples between the start of each frame.
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    /// The window applied to each frame.
+    pub fn window(&self) -> Window {
+        self.window
+    }
+
+    /// The spectrum of the most recent frame.
+    pub fn spectrum(&self) -> &Spectrum {
+        &self.spectrum
+    }
+
+    /// Push a single sample sampled at the given rate.
+    ///
+    /// Returns `true` if a new frame was completed, in which case its spectrum is available via
+    /// **spectrum**.
+    pub fn push(&mut self, sample: f32, sample_rate: u32) -> bool {
+        let size = self.size();
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % size;
+        self.filled = (self.filled + 1).min(size);
+        self.since_frame += 1;
+        if self.filled < size || self.since_frame < self.hop {
+            return false;
+        }
+        self.since_frame = 0;
+        self.transform(sample_rate);
+        true
+    }
+
+    /// Copy the samples of the most recent frame in chronological order, without the window
+    /// applied.
+    ///
+    /// **Panics** if the length of `frame` differs from the frame size.
+    pub fn copy_frame(&self, frame: &mut [f32]) {
+        let (newer, older) = self.samples.split_at(self.next);
+        let (a, b) = frame.split_at_mut(older.len());
+        a.copy_from_slice(older);
+        b.copy_from_slice(newer);
+    }
+
+    fn transform(&mut self, sample_rate: u32) {
+        let size = self.size();
+        let (newer, older) = self.samples.split_at(self.next);
+        let samples = older.iter().chain(newer);
+        for ((c, &s), &w) in self.scratch.iter_mut().zip(samples).zip(&self.coefficients) {
+            *c = Complex::new(s * w, 0.0);
+        }
+        self.fft.forward(&mut self.scratch);
+
+        // Normalise so that a full-scale sinusoid has a magnitude of `1`.
+        let gain: f32 = self.coefficients.iter().sum();
+        let nyquist = size / 2;
+        for (k, m) in self.spectrum.magnitudes.iter_mut().enumerate() {
+            let scale = if k == 0 || k == nyquist { 1.0 } else { 2.0 };
+            *m = self.scratch[k].norm() * scale / gain;
+        }
+        self.spectrum.sample_rate = sample_rate;
+    }
+}
+
+impl Spectrum {
+    /// The magnitude of each frequency bin, from DC up to and including the Nyquist frequency.
+    pub fn magnitudes(&self) -> &[f32] {
+        &self.magnitudes
+    }
+
+    /// The size of the transform that produced the spectrum.
+    pub fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// The sample rate of the analysed samples.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The width of each bin in Hz.
+    pub fn bin_width(&self) -> f32 {
+        self.sample_rate as f32 / self.fft_size as f32
+    }
+
+    /// The equivalent noise bandwidth of the window in bins.
+    ///
+    /// The power of a sinusoid is spread across this many bins by the window.
+    pub fn noise_bandwidth(&self) -> f32 {
+        self.noise_bandwidth
+    }
+
+    /// The center frequency of the bin at the given index in Hz.
+    pub fn bin_frequency(&self, index: usize) -> f32 {
+        index as f32 * self.bin_width()
+    }
+
+    /// The index of the bin nearest to the given frequency in Hz.
+    pub fn frequency_bin(&self, hz: f32) -> usize {
+        let bin = (hz / self.bin_width()).round().max(0.0) as usize;
+        bin.min(self.magnitudes.len() - 1)
+    }
+}
@@ -0,0 +1,81 @@
+//! A wait-free triple buffer for delivering the latest value from one thread to another.
+//!
+//! The writer and reader each own one of three buffers. The third, the "back" buffer, is swapped
+//! with the writer's buffer on each publish and with the reader's buffer on each read that follows
+//! a publish. Neither side ever waits on the other, allocates or locks.
+
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+// Set within the back index when the back buffer holds a value that the reader has not yet seen.
+const NEW: u8 = 0b100;
+const INDEX: u8 = 0b011;
+
+struct Shared<T> {
+    buffers: [UnsafeCell<T>; 3],
+    back: AtomicU8,
+}
+
+// Safety: each buffer is only ever accessed by the side that currently owns its index, and
+// ownership is transferred via the atomic swap of the back index.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// The side of a triple buffer that publishes values.
+pub(crate) struct Writer<T> {
+    shared: Arc<Shared<T>>,
+    index: u8,
+}
+
+/// The side of a triple buffer that reads the most recently published value.
+pub(crate) struct Reader<T> {
+    shared: Arc<Shared<T>>,
+    index: u8,
+}
+
+/// Create a triple buffer with each buffer initialised to a clone of the given value.
+pub(crate) fn new<T: Clone>(value: T) -> (Writer<T>, Reader<T>) {
+    let buffers = [
+        UnsafeCell::new(value.clone()),
+        UnsafeCell::new(value.clone()),
+        UnsafeCell::new(value),
+    ];
+    let shared = Arc::new(Shared {
+        buffers,
+        back: AtomicU8::new(1),
+    });
+    let writer = Writer {
+        shared: shared.clone(),
+        index: 0,
+    };
+    let reader = Reader { shared, index: 2 };
+    (writer, reader)
+}
+
+impl<T> Writer<T> {
+    /// The buffer to be published next.
+    ///
+    /// This holds a previously published value, allowing its allocations to be reused.
+    pub fn write(&mut self) -> &mut T {
+        // Safety: the writer's index is never held by the reader or stored as the back index.
+        unsafe { &mut *self.shared.buffers[self.index as usize].get() }
+    }
+
+    /// Publish the buffer returned by **write**, making it available to the reader.
+    pub fn publish(&mut self) {
+        let back = self.shared.back.swap(self.index | NEW, Ordering::AcqRel);
+        self.index = back & INDEX;
+    }
+}
+
+impl<T> Reader<T> {
+    /// The most recently published value.
+    pub fn read(&mut self) -> &T {
+        if self.shared.back.load(Ordering::Relaxed) & NEW != 0 {
+            let back = self.shared.back.swap(self.index, Ordering::AcqRel);
+            self.index = back & INDEX;
+        }
+        // Safety: the reader's index is never held by the writer or stored as the back index.
+        unsafe { &*self.shared.buffers[self.index as usize].get() }
+    }
+}
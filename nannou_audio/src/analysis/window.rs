@@ -0,0 +1,48 @@
+//! Window functions applied to each frame of a short-time Fourier transform.
+
+/// A window function, tapering the edges of a frame to reduce spectral leakage.
+///
+/// Windows are periodic, as is preferable for spectral analysis.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Window {
+    /// No tapering. Provides the best frequency resolution at the cost of the most leakage.
+    Rectangular,
+    /// A good general purpose window.
+    #[default]
+    Hann,
+    /// Similar to `Hann`, trading a wider fall-off for a lower nearest side lobe.
+    Hamming,
+    /// Lower leakage than `Hann` at the cost of frequency resolution.
+    Blackman,
+    /// Very low leakage, suitable for measuring quiet components near loud ones.
+    BlackmanHarris,
+}
+
+impl Window {
+    /// The coefficient at the given index of a window of the given length.
+    pub fn coefficient(&self, index: usize, len: usize) -> f32 {
+        let x = 2.0 * std::f64::consts::PI * index as f64 / len as f64;
+        let cosine_sum = |a: &[f64]| {
+            a.iter()
+                .enumerate()
+                .map(|(k, a)| if k % 2 == 0 { 1.0 } else { -1.0 } * a * (k as f64 * x).cos())
+                .sum::<f64>()
+        };
+        let c = match *self {
+            Window::Rectangular => 1.0,
+            Window::Hann => cosine_sum(&[0.5, 0.5]),
+            Window::Hamming => cosine_sum(&[0.54, 0.46]),
+            Window::Blackman => cosine_sum(&[0.42, 0.5, 0.08]),
+            Window::BlackmanHarris => cosine_sum(&[0.35875, 0.48829, 0.14128, 0.01168]),
+        };
+        c as f32
+    }
+
+    /// Fill the given slice with the coefficients of a window of the same length.
+    pub fn fill(&self, coefficients: &mut [f32]) {
+        let len = coefficients.len();
+        for (i, c) in coefficients.iter_mut().enumerate() {
+            *c = self.coefficient(i, len);
+        }
+    }
+}
@@ -0,0 +1,531 @@
+//! Playback and recording of audio files within audio streams.
+//!
+//! - [**FilePlayer**](./struct.FilePlayer.html) - plays an **AudioFile** into the buffers of an
+//!   output stream, resampling to the stream's rate, with support for looping, seeking and gain.
+//! - [**FileRecorder**](./struct.FileRecorder.html) - records the buffers of an input stream to a
+//!   WAV file, writing to disk on a dedicated thread such that the audio thread never blocks or
+//!   allocates.
+
+use crate::queue;
+use crate::stream::offline::WavWriter;
+use crate::Buffer;
+use dasp_sample::{FromSample, Sample, ToSample};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use thiserror::Error;
+
+/// The decoded, interleaved samples of an audio file.
+///
+/// Cloning an **AudioFile** is cheap, as the samples are shared.
+#[derive(Clone, Debug)]
+pub struct AudioFile {
+    samples: Arc<[f32]>,
+    channels: usize,
+    sample_rate: u32,
+}
+
+/// Plays an **AudioFile** into the buffers of an output stream.
+///
+/// The player is designed to live within the model of an output stream and mixes the file into
+/// each buffer via **render**. Rendering never allocates, even when resampling.
+#[derive(Clone, Debug)]
+pub struct FilePlayer {
+    file: AudioFile,
+    // The playhead in frames of the file. Fractional when resampling.
+    position: f64,
+    is_playing: bool,
+    is_looping: bool,
+    gain: f32,
+    // The gain reached at the end of the last rendered buffer, ramped towards `gain`.
+    current_gain: f32,
+}
+
+/// Records the buffers of an input stream to a WAV file.
+///
+/// The recorder is designed to live within the model of an input stream. Samples are pushed into
+/// a lock-free queue and written to disk by a dedicated thread, which is controlled via the
+/// **RecorderHandle** returned alongside the recorder.
+pub struct FileRecorder {
+    producer: queue::Producer<f32>,
+    shared: Arc<RecorderShared>,
+    channels: usize,
+    sample_rate: u32,
+}
+
+/// A handle to the thread writing the samples of a **FileRecorder** to disk.
+///
+/// Dropping the handle finishes the recording, ignoring any errors.
+pub struct RecorderHandle {
+    shared: Arc<RecorderShared>,
+    thread: Option<thread::JoinHandle<Result<(), hound::Error>>>,
+}
+
+// State shared between the recorder and the writing thread.
+struct RecorderShared {
+    channels: usize,
+    frames_dropped: AtomicU64,
+    samples_written: AtomicU64,
+    stop: AtomicBool,
+}
+
+/// Errors that might occur when reading or writing audio files.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to read audio file: {err}")]
+    Read { err: audrey::read::ReadError },
+    #[error("failed to write WAV file: {err}")]
+    Wav { err: hound::Error },
+    #[error("the recording thread panicked")]
+    RecorderPanicked,
+}
+
+/// The interval at which the writing thread checks for new samples.
+const RECORDER_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+impl AudioFile {
+    /// Decode the WAV, FLAC or OGG Vorbis file at the given path.
+    ///
+    /// The format is determined by the file's extension. The entire file is decoded into memory,
+    /// so this should be called before the file is passed to the audio thread.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut reader = audrey::open(path)?;
+        let description = reader.description();
+        let samples = reader
+            .samples::<f32>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(audrey::read::ReadError::from)?;
+        let channels = description.channel_count() as usize;
+        Ok(Self::from_samples(
+            samples,
+            channels,
+            description.sample_rate(),
+        ))
+    }
+
+    /// An audio file from the given interleaved samples.
+    ///
+    /// Any trailing samples that do not form a complete frame are ignored.
+    ///
+    /// **Panics** if `channels` or `sample_rate` is `0`.
+    pub fn from_samples(mut samples: Vec<f32>, channels: usize, sample_rate: u32) -> Self {
+        assert!(
+            channels > 0,
+            "the number of channels must be greater than 0"
+        );
+        assert!(sample_rate > 0, "the sample rate must be greater than 0");
+        samples.truncate(samples.len() / channels * channels);
+        AudioFile {
+            samples: samples.into(),
+            channels,
+            sample_rate,
+        }
+    }
+
+    /// The interleaved samples of the file.
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// The number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The sample rate in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of frames.
+    pub fn len_frames(&self) -> usize {
+        self.samples.len() / self.channels
+    }
+
+    /// The duration of the file.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.len_frames() as f64 / self.sample_rate as f64)
+    }
+}
+
+impl FilePlayer {
+    /// A player of the given file, playing from the start at unity gain without looping.
+    pub fn new(file: AudioFile) -> Self {
+        FilePlayer {
+            file,
+            position: 0.0,
+            is_playing: true,
+            is_looping: false,
+            gain: 1.0,
+            current_gain: 1.0,
+        }
+    }
+
+    /// Decode the file at the given path and create a player for it.
+    ///
+    /// See **AudioFile::open** for details.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        AudioFile::open(path).map(Self::new)
+    }
+
+    /// Whether or not the player should return to the start of the file upon reaching the end.
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.set_looping(looping);
+        self
+    }
+
+    /// The amplitude by which the file's samples are multiplied.
+    pub fn gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self.current_gain = gain;
+        self
+    }
+
+    /// The file being played.
+    pub fn file(&self) -> &AudioFile {
+        &self.file
+    }
+
+    /// Whether or not the player is currently playing.
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    /// Whether or not the player has reached the end of the file without looping.
+    pub fn is_finished(&self) -> bool {
+        !self.is_looping && self.position >= self.file.len_frames() as f64
+    }
+
+    /// Whether or not the player returns to the start of the file upon reaching the end.
+    pub fn is_looping(&self) -> bool {
+        self.is_looping
+    }
+
+    /// Set whether or not the player should return to the start of the file upon reaching the
+    /// end.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.is_looping = looping;
+    }
+
+    /// Set the amplitude by which the file's samples are multiplied.
+    ///
+    /// Changes in gain are ramped over the next rendered buffer to avoid clicks.
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+    }
+
+    /// Start or resume playback, restarting from the beginning if the player had finished.
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.position = 0.0;
+        }
+        self.is_playing = true;
+    }
+
+    /// Pause playback at the current position.
+    pub fn pause(&mut self) {
+        self.is_playing = false;
+    }
+
+    /// Pause playback and return to the start of the file.
+    pub fn stop(&mut self) {
+        self.is_playing = false;
+        self.position = 0.0;
+    }
+
+    /// The position of the playhead within the file.
+    pub fn position(&self) -> Duration {
+        Duration::from_secs_f64(self.position / self.file.sample_rate as f64)
+    }
+
+    /// Move the playhead to the given position within the file.
+    ///
+    /// Positions beyond the end of the file are clamped to the end.
+    pub fn seek(&mut self, position: Duration) {
+        let frame = position.as_secs_f64() * self.file.sample_rate as f64;
+        self.position = frame.min(self.file.len_frames() as f64);
+    }
+
+    /// Mix the file into the given buffer, resampling to the buffer's sample rate.
+    ///
+    /// See **render_interleaved** for details.
+    pub fn render<S>(&mut self, buffer: &mut Buffer<S>)
+    where
+        S: Sample + ToSample<f32> + FromSample<f32>,
+    {
+        let channels = buffer.channels();
+        let sample_rate = buffer.sample_rate();
+        self.render_interleaved(buffer, channels, sample_rate);
+    }
+
+    /// Mix the file into the given interleaved samples, resampling to the given sample rate.
+    ///
+    /// The file is added to the existing samples, allowing multiple players to be mixed into the
+    /// same buffer. If the file has fewer channels than the output, its channels are repeated
+    /// across the output channels. If it has more, the surplus channels are mixed down.
+    pub fn render_interleaved<S>(&mut self, samples: &mut [S], channels: usize, sample_rate: u32)
+    where
+        S: Sample + ToSample<f32> + FromSample<f32>,
+    {
+        assert!(
+            channels > 0,
+            "the number of channels must be greater than 0"
+        );
+        let len_frames = samples.len() / channels;
+        let start_gain = self.current_gain;
+        self.current_gain = self.gain;
+        let file_len = self.file.len_frames() as f64;
+        if !self.is_playing || file_len == 0.0 {
+            return;
+        }
+        let step = self.file.sample_rate as f64 / sample_rate as f64;
+        for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+            if self.position >= file_len {
+                if !self.is_looping {
+                    self.is_playing = false;
+                    break;
+                }
+                self.position %= file_len;
+            }
+            let t = (i + 1) as f32 / len_frames as f32;
+            let gain = start_gain + (self.gain - start_gain) * t;
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let value = self.channel_sample(channel, channels) * gain;
+                *sample = (sample.to_sample::<f32>() + value).to_sample::<S>();
+            }
+            self.position += step;
+        }
+    }
+
+    // The value of the given output channel at the current position.
+    fn channel_sample(&self, channel: usize, channels: usize) -> f32 {
+        let file_channels = self.file.channels;
+        if file_channels <= channels {
+            return self.interpolate(channel % file_channels);
+        }
+        let mixed = (channel..file_channels).step_by(channels);
+        let count = mixed.len();
+        mixed.map(|c| self.interpolate(c)).sum::<f32>() / count as f32
+    }
+
+    // The value of the given file channel at the current position via cubic interpolation.
+    fn interpolate(&self, channel: usize) -> f32 {
+        let index = self.position.floor();
+        let t = (self.position - index) as f32;
+        let index = index as isize;
+        let sample = |offset: isize| self.file_sample(index + offset, channel);
+        let (a, b, c, d) = (sample(-1), sample(0), sample(1), sample(2));
+        if t == 0.0 {
+            return b;
+        }
+        // Catmull-Rom spline through the four surrounding samples.
+        let c0 = b;
+        let c1 = 0.5 * (c - a);
+        let c2 = a - 2.5 * b + 2.0 * c - 0.5 * d;
+        let c3 = 0.5 * (d - a) + 1.5 * (b - c);
+        ((c3 * t + c2) * t + c1) * t + c0
+    }
+
+    // The sample of the given file channel at the given frame, wrapping when looping and silent
+    // beyond the bounds of the file otherwise.
+    fn file_sample(&self, frame: isize, channel: usize) -> f32 {
+        let len = self.file.len_frames() as isize;
+        let frame = match self.is_looping {
+            true => frame.rem_euclid(len),
+            false if frame < 0 || frame >= len => return 0.0,
+            false => frame,
+        };
+        self.file.samples[frame as usize * self.file.channels + channel]
+    }
+}
+
+impl FileRecorder {
+    /// The default duration of audio that may be queued for writing, in seconds.
+    pub const DEFAULT_QUEUE_SECS: f32 = 2.0;
+
+    /// Create a 32-bit float WAV file at the given path and a recorder that writes to it.
+    ///
+    /// The file is written by a newly spawned thread until the returned **RecorderHandle** is
+    /// finished or dropped, or the **FileRecorder** is dropped.
+    pub fn create<P>(
+        path: P,
+        channels: usize,
+        sample_rate: u32,
+    ) -> Result<(FileRecorder, RecorderHandle), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let queue_frames = (Self::DEFAULT_QUEUE_SECS * sample_rate as f32) as usize;
+        Self::with_queue_frames(path, channels, sample_rate, queue_frames)
+    }
+
+    /// The same as **create**, but with a queue that holds the given number of frames.
+    ///
+    /// If the writing thread falls behind such that the queue is full, recorded buffers are
+    /// dropped rather than blocking the audio thread.
+    ///
+    /// **Panics** if `channels` or `queue_frames` is `0`.
+    pub fn with_queue_frames<P>(
+        path: P,
+        channels: usize,
+        sample_rate: u32,
+        queue_frames: usize,
+    ) -> Result<(FileRecorder, RecorderHandle), Error>
+    where
+        P: AsRef<Path>,
+    {
+        assert!(
+            channels > 0,
+            "the number of channels must be greater than 0"
+        );
+        assert!(queue_frames > 0, "the queue must hold at least one frame");
+        let mut writer = WavWriter::create(path, channels, sample_rate)?;
+        let (producer, mut consumer) = queue::bounded(queue_frames * channels);
+        let shared = Arc::new(RecorderShared {
+            channels,
+            frames_dropped: AtomicU64::new(0),
+            samples_written: AtomicU64::new(0),
+            stop: AtomicBool::new(false),
+        });
+
+        let thread_shared = shared.clone();
+        let thread = thread::Builder::new()
+            .name("nannou_audio file recorder".into())
+            .spawn(move || {
+                let shared = thread_shared;
+                let mut chunk = vec![0.0f32; (queue_frames * channels).min(8192)];
+                loop {
+                    // Check before draining so that no samples pushed before stopping are lost.
+                    let done = shared.stop.load(Ordering::Acquire) || consumer.is_abandoned();
+                    loop {
+                        let n = consumer.pop_slice(&mut chunk);
+                        if n == 0 {
+                            break;
+                        }
+                        writer.write(&chunk[..n])?;
+                        shared
+                            .samples_written
+                            .fetch_add(n as u64, Ordering::Relaxed);
+                    }
+                    if done {
+                        break;
+                    }
+                    thread::sleep(RECORDER_POLL_INTERVAL);
+                }
+                writer.finalize()
+            })
+            .expect("failed to spawn file recorder thread");
+
+        let recorder = FileRecorder {
+            producer,
+            shared: shared.clone(),
+            channels,
+            sample_rate,
+        };
+        let handle = RecorderHandle {
+            shared,
+            thread: Some(thread),
+        };
+        Ok((recorder, handle))
+    }
+
+    /// The number of channels recorded.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The sample rate of the file in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Record the given buffer.
+    ///
+    /// See **record_interleaved** for details.
+    pub fn record<S>(&mut self, buffer: &Buffer<S>)
+    where
+        S: Sample + ToSample<f32>,
+    {
+        self.record_interleaved(buffer);
+    }
+
+    /// Record the given interleaved samples.
+    ///
+    /// This never blocks or allocates. If the queue does not have room for all of the samples,
+    /// e.g. because the disk cannot keep up, or the writing thread has stopped, the samples are
+    /// dropped and counted via **RecorderHandle::frames_dropped**.
+    ///
+    /// **Panics** if the samples do not contain a whole number of frames.
+    pub fn record_interleaved<S>(&mut self, samples: &[S])
+    where
+        S: Sample + ToSample<f32>,
+    {
+        assert_eq!(samples.len() % self.channels, 0, "incomplete frame");
+        if self.producer.is_abandoned() || self.producer.slots() < samples.len() {
+            let frames = (samples.len() / self.channels) as u64;
+            self.shared
+                .frames_dropped
+                .fetch_add(frames, Ordering::Relaxed);
+            return;
+        }
+        let samples = samples.iter().map(|s| s.to_sample::<f32>());
+        self.producer.push_iter(samples);
+    }
+}
+
+impl RecorderHandle {
+    /// The number of frames written to the file so far.
+    pub fn frames_written(&self) -> u64 {
+        let samples = self.shared.samples_written.load(Ordering::Relaxed);
+        samples / self.shared.channels as u64
+    }
+
+    /// The number of frames dropped because the queue was full.
+    pub fn frames_dropped(&self) -> u64 {
+        self.shared.frames_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Write any queued samples, finalize the file and stop the writing thread.
+    ///
+    /// Samples recorded after this is called are dropped.
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        self.shared.stop.store(true, Ordering::Release);
+        match self.thread.take() {
+            None => Ok(()),
+            Some(thread) => match thread.join() {
+                Ok(result) => result.map_err(Error::from),
+                Err(_) => Err(Error::RecorderPanicked),
+            },
+        }
+    }
+}
+
+impl Drop for RecorderHandle {
+    fn drop(&mut self) {
+        self.stop().ok();
+    }
+}
+
+impl From<audrey::read::ReadError> for Error {
+    fn from(err: audrey::read::ReadError) -> Self {
+        Error::Read { err }
+    }
+}
+
+impl From<hound::Error> for Error {
+    fn from(err: hound::Error) -> Self {
+        Error::Wav { err }
+    }
+}
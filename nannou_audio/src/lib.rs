@@ -3,11 +3,21 @@
 //! - [**Host**](./Host.html) - top-level access to device enumeration and spawning streams.
 //! - [**Stream**](./stream/struct.Stream.html) - for managing an input/output audio stream. This may be
 //!   created via the **App**'s **Audio** API.
+//! - [**offline::Stream**](./stream/offline/struct.Stream.html) - for rendering an output stream
+//!   on demand without an audio device, e.g. to a WAV file faster than realtime.
+//! - [**analysis**](./analysis/index.html) - real-time spectrum, level, onset and pitch analysis
+//!   of audio streams, with results delivered to the main thread without locking.
 //! - [**Buffer**](./buffer/struct.Buffer.html) - contains audio data, either for reading or writing.
 //!   This is passed to the `capture` or `render` function for each stream.
+//! - [**FilePlayer**](./file/struct.FilePlayer.html) and
+//!   [**FileRecorder**](./file/struct.FileRecorder.html) - for playing WAV, FLAC and OGG files
+//!   within output streams and recording input streams to WAV files.
 //! - [**Devices**](./device/struct.Devices.html) - for enumerating all audio devices on the system.
 //! - [**Device**](./device/struct.Device.html) - for querying information about supported stream
 //!   formats or for creating a stream targeted towards a specific audio device.
+//! - [**param**](./param/index.html) and [**queue**](./queue/index.html) - for communicating with
+//!   the audio thread without locking or allocating, via smoothed atomic parameters registered on
+//!   the stream builder and bounded lock-free queues.
 //! - [**Receiver**](./receiver/struct.Receiver.html) and
 //!   [**Requester**](./requester/struct.Requester.html) for buffering input and output streams that
 //!   may deliver buffers of inconsistent sizes into a stream of consistently sized buffers.
@@ -18,9 +28,11 @@ use std::sync::Arc;
 
 pub use self::buffer::Buffer;
 pub use self::device::{Device, Devices};
+pub use self::file::{FilePlayer, FileRecorder};
 pub use self::receiver::Receiver;
 pub use self::requester::Requester;
 pub use self::stream::Stream;
+pub use audrey;
 pub use cpal;
 #[doc(inline)]
 pub use cpal::{
@@ -31,9 +43,14 @@ pub use cpal::{
     SupportedStreamConfigsError,
 };
 pub use dasp_sample;
+pub use hound;
 
+pub mod analysis;
 pub mod buffer;
 pub mod device;
+pub mod file;
+pub mod param;
+pub mod queue;
 pub mod receiver;
 pub mod requester;
 pub mod stream;
@@ -126,6 +143,23 @@ impl Host {
         }
     }
 
+    /// Begin building a new duplex audio stream.
+    ///
+    /// A duplex stream captures input from one device and renders output to another (or the
+    /// same) device via a single process function, which receives an input buffer and an output
+    /// buffer of the same number of frames.
+    pub fn new_duplex_stream<M, S>(&self, model: M) -> stream::duplex::BuilderInit<M, S> {
+        stream::duplex::Builder {
+            process: stream::duplex::default_process_fn,
+            xrun: stream::duplex::default_xrun_fn,
+            error: stream::default_error_fn,
+            input_device: None,
+            input_channels: None,
+            latency_frames: None,
+            builder: self.new_stream(model),
+        }
+    }
+
     // Builder initialisation shared between input and output streams.
     //
     // If this is the first time a stream has been created, this method will spawn the
@@ -139,6 +173,7 @@ impl Host {
             frames_per_buffer: None,
             device_buffer_size: None,
             device: None,
+            params: param::Params::new(),
             sample_format: PhantomData,
         }
     }
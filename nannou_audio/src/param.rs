@@ -0,0 +1,232 @@
+//! Parameters shared between the main thread and the audio thread without locking.
+//!
+//! A **FloatParam** or **BoolParam** is a cheaply cloneable handle to an atomic value that may be
+//! set from any thread. Parameters are registered on a stream builder along with the field of the
+//! audio model that they drive. Before each buffer is processed, the stream copies the value of
+//! each parameter into its field:
+//!
+//! - **FloatParam**s drive a **Smoothed** value, which ramps towards each new value over a short
+//!   time to avoid clicks.
+//! - **BoolParam**s drive a `bool`.
+//!
+//! ```no_run
+//! use nannou_audio::param::{BoolParam, FloatParam, Smoothed};
+//! use nannou_audio::{Buffer, Host};
+//!
+//! struct Audio {
+//!     volume: Smoothed,
+//!     mute: bool,
+//! }
+//!
+//! fn render(audio: &mut Audio, buffer: &mut Buffer) {
+//!     for frame in buffer.frames_mut() {
+//!         let volume = audio.volume.next_value();
+//!         // ...
+//!     }
+//! }
+//!
+//! let volume = FloatParam::new(0.5);
+//! let mute = BoolParam::new(false);
+//! let audio = Audio {
+//!     volume: Smoothed::new(0.0),
+//!     mute: false,
+//! };
+//! let stream = Host::new()
+//!     .new_output_stream(audio)
+//!     .render(render)
+//!     .float_param(&volume, |audio| &mut audio.volume)
+//!     .bool_param(&mute, |audio| &mut audio.mute)
+//!     .build()
+//!     .unwrap();
+//!
+//! // Later, on the main thread.
+//! volume.set(0.25);
+//! mute.set(true);
+//! ```
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// A handle to an atomic `f32` parameter.
+#[derive(Clone, Debug)]
+pub struct FloatParam {
+    bits: Arc<AtomicU32>,
+}
+
+/// A handle to an atomic `bool` parameter.
+#[derive(Clone, Debug)]
+pub struct BoolParam {
+    value: Arc<AtomicBool>,
+}
+
+/// A value that ramps linearly towards its target over a fixed time.
+///
+/// This is typically a field of the audio model driven by a **FloatParam**, in which case the
+/// stream sets its target before each buffer. The ramp is advanced one frame at a time via
+/// **next_value**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Smoothed {
+    current: f32,
+    target: f32,
+    step: f32,
+    remaining: usize,
+    time: f32,
+}
+
+/// Selects a field of the audio model.
+pub type Field<M, T> = fn(&mut M) -> &mut T;
+
+// The parameters registered on a stream builder.
+pub(crate) struct Params<M> {
+    floats: Vec<(FloatParam, Field<M, Smoothed>)>,
+    bools: Vec<(BoolParam, Field<M, bool>)>,
+}
+
+impl FloatParam {
+    /// A parameter with the given initial value.
+    pub fn new(value: f32) -> Self {
+        FloatParam {
+            bits: Arc::new(AtomicU32::new(value.to_bits())),
+        }
+    }
+
+    /// The current value.
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.bits.load(Ordering::Relaxed))
+    }
+
+    /// Set the value, to be applied before the next buffer is processed.
+    pub fn set(&self, value: f32) {
+        self.bits.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl BoolParam {
+    /// A parameter with the given initial value.
+    pub fn new(value: bool) -> Self {
+        BoolParam {
+            value: Arc::new(AtomicBool::new(value)),
+        }
+    }
+
+    /// The current value.
+    pub fn get(&self) -> bool {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Set the value, to be applied before the next buffer is processed.
+    pub fn set(&self, value: bool) {
+        self.value.store(value, Ordering::Relaxed);
+    }
+
+    /// Invert the value, returning the new value.
+    pub fn toggle(&self) -> bool {
+        !self.value.fetch_xor(true, Ordering::Relaxed)
+    }
+}
+
+impl Smoothed {
+    /// The default time over which the value ramps towards a new target in seconds.
+    pub const DEFAULT_TIME: f32 = 0.02;
+
+    /// A value starting at the given value with the default ramp time.
+    pub fn new(value: f32) -> Self {
+        Smoothed {
+            current: value,
+            target: value,
+            step: 0.0,
+            remaining: 0,
+            time: Self::DEFAULT_TIME,
+        }
+    }
+
+    /// The time over which the value ramps towards a new target in seconds.
+    ///
+    /// A time of `0` jumps to each new target immediately.
+    pub fn time(mut self, secs: f32) -> Self {
+        assert!(secs >= 0.0);
+        self.time = secs;
+        self
+    }
+
+    /// The current value.
+    pub fn value(&self) -> f32 {
+        self.current
+    }
+
+    /// The value being ramped towards.
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Whether or not the value is still ramping towards its target.
+    pub fn is_smoothing(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Begin ramping towards the given target at the given sample rate.
+    ///
+    /// Has no effect if the target is unchanged, such that the ramp is not restarted.
+    pub fn set_target(&mut self, target: f32, sample_rate: u32) {
+        if target == self.target {
+            return;
+        }
+        self.target = target;
+        self.remaining = (self.time * sample_rate as f32).round() as usize;
+        if self.remaining == 0 {
+            self.current = target;
+        } else {
+            self.step = (target - self.current) / self.remaining as f32;
+        }
+    }
+
+    /// Jump to the given value immediately.
+    pub fn reset(&mut self, value: f32) {
+        self.current = value;
+        self.target = value;
+        self.remaining = 0;
+    }
+
+    /// Advance the ramp by one frame, returning the new value.
+    pub fn next_value(&mut self) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.current = match self.remaining {
+                0 => self.target,
+                _ => self.current + self.step,
+            };
+        }
+        self.current
+    }
+}
+
+impl<M> Params<M> {
+    pub fn new() -> Self {
+        Params {
+            floats: vec![],
+            bools: vec![],
+        }
+    }
+
+    // Register the given param, initialising the field to its current value.
+    pub fn float(&mut self, model: &mut M, param: &FloatParam, field: Field<M, Smoothed>) {
+        field(model).reset(param.get());
+        self.floats.push((param.clone(), field));
+    }
+
+    // Register the given param, initialising the field to its current value.
+    pub fn bool(&mut self, model: &mut M, param: &BoolParam, field: Field<M, bool>) {
+        *field(model) = param.get();
+        self.bools.push((param.clone(), field));
+    }
+
+    // Copy the value of each param into its field.
+    pub fn apply(&self, model: &mut M, sample_rate: u32) {
+        for (param, field) in &self.floats {
+            field(model).set_target(param.get(), sample_rate);
+        }
+        for (param, field) in &self.bools {
+            *field(model) = param.get();
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! A bounded, lock-free, single-producer single-consumer queue.
+//!
+//! Neither side ever waits on the other, allocates or locks, making the queue suitable for sending
+//! events to and from the audio thread. Create one queue for each direction, e.g. one carrying
+//! commands from the main thread to the audio thread and another carrying events back.
+//!
+//! ```
+//! use nannou_audio::queue;
+//!
+//! let (mut producer, mut consumer) = queue::bounded(2);
+//! assert_eq!(producer.push("a"), Ok(()));
+//! assert_eq!(producer.push("b"), Ok(()));
+//! assert_eq!(producer.push("c"), Err("c"));
+//! assert_eq!(consumer.pop(), Some("a"));
+//! assert_eq!(consumer.pop(), Some("b"));
+//! assert_eq!(consumer.pop(), None);
+//! ```
+//!
+//! Note that any items popped on the audio thread are dropped there too, so items that own heap
+//! allocations should be sent back to another thread to be dropped.
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct Shared<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // The total number of items popped, only written by the consumer.
+    head: AtomicUsize,
+    // The total number of items pushed, only written by the producer.
+    tail: AtomicUsize,
+}
+
+// Safety: each slot is only accessed by the producer while it lies outside of `head..tail` and by
+// the consumer while it lies within, with ownership transferred via the release and acquire of
+// `tail` and `head`.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+/// The side of a queue that pushes items.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The side of a queue that pops items.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Create a queue that can hold up to `capacity` items.
+///
+/// This is the only point at which the queue allocates.
+///
+/// **Panics** if `capacity` is `0`.
+pub fn bounded<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "the capacity must be greater than 0");
+    let slots = (0..capacity)
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+    let shared = Arc::new(Shared {
+        slots,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+    let producer = Producer {
+        shared: shared.clone(),
+    };
+    let consumer = Consumer { shared };
+    (producer, consumer)
+}
+
+impl<T> Shared<T> {
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        self.slots[index % self.capacity()].get()
+    }
+}
+
+impl<T> Producer<T> {
+    /// The maximum number of items that the queue can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// The number of items that may be pushed before the queue is full.
+    pub fn slots(&self) -> usize {
+        let head = self.shared.head.load(Ordering::Acquire);
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        self.shared.capacity() - tail.wrapping_sub(head)
+    }
+
+    /// Whether or not the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.slots() == 0
+    }
+
+    /// Push the given item onto the back of the queue.
+    ///
+    /// Returns the item in the case that the queue is full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        self.push_iter(Some(item));
+        Ok(())
+    }
+
+    /// Push items from the given iterator until either it is exhausted or the queue is full.
+    ///
+    /// Returns the number of items pushed.
+    pub fn push_iter<I>(&mut self, items: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        let slots = self.slots();
+        let mut pushed = 0;
+        for item in items.into_iter().take(slots) {
+            // Safety: the slot lies outside of `head..tail` and so is owned by the producer.
+            unsafe { (*self.shared.slot(tail.wrapping_add(pushed))).write(item) };
+            pushed += 1;
+        }
+        self.shared
+            .tail
+            .store(tail.wrapping_add(pushed), Ordering::Release);
+        pushed
+    }
+
+    /// Whether or not the consumer has been dropped.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+}
+
+impl<T: Copy> Producer<T> {
+    /// Push items from the given slice until either all are pushed or the queue is full.
+    ///
+    /// Returns the number of items pushed.
+    pub fn push_slice(&mut self, items: &[T]) -> usize {
+        self.push_iter(items.iter().cloned())
+    }
+}
+
+impl<T> Consumer<T> {
+    /// The maximum number of items that the queue can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// The number of items in the queue.
+    pub fn len(&self) -> usize {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        let tail = self.shared.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    /// Whether or not the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pop the item at the front of the queue.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let head = self.shared.head.load(Ordering::Relaxed);
+        // Safety: the slot lies within `head..tail` and so is owned by the consumer and
+        // initialised.
+        let item = unsafe { (*self.shared.slot(head)).assume_init_read() };
+        self.shared
+            .head
+            .store(head.wrapping_add(1), Ordering::Release);
+        Some(item)
+    }
+
+    /// Pop and drop up to `count` items from the front of the queue.
+    ///
+    /// Returns the number of items discarded.
+    pub fn discard(&mut self, count: usize) -> usize {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        let count = count.min(self.len());
+        for i in 0..count {
+            // Safety: as for `pop`.
+            unsafe { (*self.shared.slot(head.wrapping_add(i))).assume_init_drop() };
+        }
+        self.shared
+            .head
+            .store(head.wrapping_add(count), Ordering::Release);
+        count
+    }
+
+    /// Whether or not the producer has been dropped.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+}
+
+impl<T: Copy> Consumer<T> {
+    /// Pop items into the given slice until either it is full or the queue is empty.
+    ///
+    /// Returns the number of items popped.
+    pub fn pop_slice(&mut self, items: &mut [T]) -> usize {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        let popped = self.len().min(items.len());
+        for (i, item) in items[..popped].iter_mut().enumerate() {
+            // Safety: as for `pop`.
+            *item = unsafe { (*self.shared.slot(head.wrapping_add(i))).assume_init_read() };
+        }
+        self.shared
+            .head
+            .store(head.wrapping_add(popped), Ordering::Release);
+        popped
+    }
+}
+
+impl<T> Iterator for Consumer<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pop()
+    }
+}
+
+impl<T> fmt::Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("capacity", &self.capacity())
+            .field("slots", &self.slots())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        for i in 0..tail.wrapping_sub(head) {
+            // Safety: the slots within `head..tail` are initialised and no longer shared.
+            unsafe { (*self.slot(head.wrapping_add(i))).assume_init_drop() };
+        }
+    }
+}
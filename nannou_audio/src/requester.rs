@@ -24,7 +24,7 @@ where
     pub fn new(num_frames: usize, num_channels: usize) -> Self {
         // We can't make any progress filling buffers of `0` frames.
         assert!(num_frames > 0);
-        let num_samples = num_frames + num_channels;
+        let num_samples = num_frames * num_channels;
         Requester {
             samples: vec![S::EQUILIBRIUM; num_samples],
             num_frames: num_frames,
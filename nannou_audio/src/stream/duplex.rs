@@ -0,0 +1,861 @@
+use crate::{
+    param::{BoolParam, Field, FloatParam, Smoothed},
+    queue,
+    stream::{self, DefaultErrorFn, ErrorFn},
+    Buffer, Device, Requester, Stream,
+};
+use cpal::traits::{DeviceTrait, HostTrait};
+use dasp_sample::{FromSample, Sample, ToSample};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+/// The function that will be called with each captured input `Buffer` and the output `Buffer`
+/// that is to be rendered.
+///
+/// Both buffers contain the same number of frames, though their channel counts may differ.
+pub trait ProcessFn<M, S>: Fn(&mut M, &Buffer<S>, &mut Buffer<S>) {}
+
+/// The function that will be called when an xrun occurs.
+///
+/// This is called on the audio thread immediately before the `ProcessFn` for the affected buffer.
+pub trait XrunFn<M>: Fn(&mut M, Xrun) {}
+
+/// The default process function type used when unspecified.
+pub type DefaultProcessFn<M, S> = fn(&mut M, &Buffer<S>, &mut Buffer<S>);
+/// The default xrun function type used when unspecified.
+pub type DefaultXrunFn<M> = fn(&mut M, Xrun);
+
+// The default process function used when unspecified.
+pub(crate) fn default_process_fn<M, S>(_: &mut M, _: &Buffer<S>, _: &mut Buffer<S>) {}
+
+// The default xrun function used when unspecified.
+pub(crate) fn default_xrun_fn<M>(_: &mut M, xrun: Xrun) {
+    eprintln!("An xrun occurred on a duplex stream: {:?}", xrun);
+}
+
+/// A type used for building a duplex stream.
+///
+/// The input and output devices are driven by independent clocks. Captured input is buffered
+/// until the output device requests a buffer, at which point an input buffer with the same number
+/// of frames is delivered to the process function along with the output buffer.
+///
+/// Slight differences between the rates of the two clocks are corrected by occasionally dropping
+/// or repeating a single input frame. Larger discontinuities, e.g. due to an overloaded system,
+/// are reported as an **Xrun**.
+///
+/// The audio callbacks never block on the model. If the model is locked elsewhere when the output
+/// device requests a buffer, e.g. while the error function runs, silence is output and an
+/// `Xrun::ModelLocked` is reported once the model is next available. Such callbacks are also
+/// counted by `Stream::skipped_callbacks`.
+pub struct Builder<M, FP, FX, FE, S = f32> {
+    /// Parameters of the output device, which drives the stream.
+    pub builder: super::Builder<M, S>,
+    pub input_device: Option<Device>,
+    pub input_channels: Option<usize>,
+    pub latency_frames: Option<usize>,
+    pub process: FP,
+    pub xrun: FX,
+    pub error: FE,
+}
+
+/// The builder when first initialised.
+pub type BuilderInit<M, S = f32> =
+    Builder<M, DefaultProcessFn<M, S>, DefaultXrunFn<M>, DefaultErrorFn<M>, S>;
+
+/// A discontinuity in the input delivered to the process function.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Xrun {
+    /// The input device captured more frames than could be buffered before the output device
+    /// requested them, so the most recently captured frames were discarded.
+    InputOverflow { frames: usize },
+    /// The output device requested frames before the input device had captured them, so the
+    /// input buffer was padded with silence.
+    InputUnderflow { frames: usize },
+    /// The model was locked by another thread when the output device requested frames, so
+    /// silence was output without calling the process function.
+    ///
+    /// This is reported once the model is next available.
+    ModelLocked { frames: usize },
+}
+
+// Statistics about the captured input shared between the input and output stream callbacks.
+struct Capture {
+    // The number of frames discarded due to overflow since last reported.
+    overflowed_frames: AtomicUsize,
+    // The greatest number of frames delivered by a single input callback.
+    max_chunk_frames: AtomicUsize,
+}
+
+// The input side of the stream, read by the output stream callback.
+struct Input<S> {
+    // The captured input samples, written by the input stream callback.
+    consumer: queue::Consumer<S>,
+    capture: Arc<Capture>,
+    // The samples of the input buffer, sized to `frames_per_buffer`.
+    samples: Box<[S]>,
+    channels: usize,
+    sample_rate: u32,
+    latency_frames: usize,
+    // Whether enough input has been buffered to begin reading it.
+    primed: bool,
+    // Whether the input has ever been primed, as the initial priming is not an xrun.
+    started: bool,
+    // The number of frames to drop (if positive) or repeat (if negative) to correct for the drift
+    // between the input and output clocks, at most one per read.
+    slips: isize,
+    // The least number of frames remaining in the queue after a read within the current window.
+    window_min_frames: usize,
+    // The number of frames read within the current window.
+    window_frames: usize,
+}
+
+impl<M, S, F> ProcessFn<M, S> for F where F: Fn(&mut M, &Buffer<S>, &mut Buffer<S>) {}
+impl<M, F> XrunFn<M> for F where F: Fn(&mut M, Xrun) {}
+
+impl<M, FP, FX, FE, S> Builder<M, FP, FX, FE, S> {
+    /// Specify the function used to process each input buffer and render each output buffer.
+    pub fn process<GP>(self, process: GP) -> Builder<M, GP, FX, FE, S> {
+        let Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            xrun,
+            error,
+            ..
+        } = self;
+        Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            xrun,
+            error,
+        }
+    }
+
+    /// Specify a function for handling xruns.
+    ///
+    /// By default, xruns are printed to stderr.
+    pub fn xrun<GX>(self, xrun: GX) -> Builder<M, FP, GX, FE, S> {
+        let Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            error,
+            ..
+        } = self;
+        Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            xrun,
+            error,
+        }
+    }
+
+    /// Specify a function for handling errors on either the input or output stream.
+    pub fn error<GE>(self, error: GE) -> Builder<M, FP, FX, GE, S> {
+        let Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            xrun,
+            ..
+        } = self;
+        Builder {
+            builder,
+            input_device,
+            input_channels,
+            latency_frames,
+            process,
+            xrun,
+            error,
+        }
+    }
+
+    /// The sample rate shared by both the input and output devices.
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(sample_rate > 0);
+        self.builder.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// The number of channels of both the input and output buffers.
+    pub fn channels(self, channels: usize) -> Self {
+        self.input_channels(channels).output_channels(channels)
+    }
+
+    pub fn input_channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0);
+        self.input_channels = Some(channels);
+        self
+    }
+
+    pub fn output_channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0);
+        self.builder.channels = Some(channels);
+        self
+    }
+
+    pub fn input_device(mut self, device: Device) -> Self {
+        self.input_device = Some(device);
+        self
+    }
+
+    pub fn output_device(mut self, device: Device) -> Self {
+        self.builder.device = Some(device);
+        self
+    }
+
+    pub fn frames_per_buffer(mut self, frames_per_buffer: usize) -> Self {
+        assert!(frames_per_buffer > 0);
+        self.builder.frames_per_buffer = Some(frames_per_buffer);
+        self
+    }
+
+    /// The buffer size requested of both devices.
+    pub fn device_buffer_size(mut self, buffer_size: cpal::BufferSize) -> Self {
+        self.builder.device_buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// The minimum number of captured frames to keep buffered as a safety margin against
+    /// underflow.
+    ///
+    /// This is in addition to the frames required to bridge the buffer sizes of the two devices.
+    /// Greater values reduce the likelihood of xruns at the cost of latency. By default, this is
+    /// `frames_per_buffer`.
+    pub fn latency_frames(mut self, latency_frames: usize) -> Self {
+        self.latency_frames = Some(latency_frames);
+        self
+    }
+
+    /// Drive the given field of the model with the given parameter.
+    ///
+    /// The field is set to the parameter's current value immediately and then smoothly ramps
+    /// towards each new value, with targets updated before each buffer is processed.
+    pub fn float_param(mut self, param: &FloatParam, field: Field<M, Smoothed>) -> Self {
+        let builder = &mut self.builder;
+        builder.params.float(&mut builder.model, param, field);
+        self
+    }
+
+    /// Drive the given field of the model with the given parameter.
+    ///
+    /// The field is set to the parameter's current value immediately and then updated before
+    /// each buffer is processed.
+    pub fn bool_param(mut self, param: &BoolParam, field: Field<M, bool>) -> Self {
+        let builder = &mut self.builder;
+        builder.params.bool(&mut builder.model, param, field);
+        self
+    }
+
+    pub fn build(self) -> std::result::Result<Stream<M>, super::BuildError>
+    where
+        S: 'static
+            + Send
+            + Sample
+            + FromSample<u16>
+            + FromSample<i16>
+            + FromSample<f32>
+            + ToSample<u16>
+            + ToSample<i16>
+            + ToSample<f32>,
+        M: 'static + Send,
+        FP: 'static + ProcessFn<M, S> + Send,
+        FX: 'static + XrunFn<M> + Send,
+        FE: 'static + ErrorFn<M> + Send,
+    {
+        let Builder {
+            process,
+            xrun,
+            error,
+            input_device,
+            input_channels,
+            latency_frames,
+            builder:
+                stream::Builder {
+                    host,
+                    model,
+                    sample_rate,
+                    channels,
+                    frames_per_buffer,
+                    device_buffer_size,
+                    device,
+                    params,
+                    ..
+                },
+        } = self;
+
+        let output_device = match device {
+            None => host
+                .default_output_device()
+                .ok_or(super::BuildError::DefaultDevice)?,
+            Some(Device { device }) => device,
+        };
+        let input_device = match input_device {
+            None => host
+                .default_input_device()
+                .ok_or(super::BuildError::DefaultDevice)?,
+            Some(Device { device }) => device,
+        };
+
+        // Find the best matching output config.
+        let desired = super::DesiredStreamConfig {
+            sample_format: super::cpal_sample_format::<S>(),
+            channels,
+            sample_rate: sample_rate.map(cpal::SampleRate),
+            device_buffer_size: device_buffer_size.clone(),
+        };
+        let output_matching = super::find_best_matching_config(
+            &output_device,
+            desired,
+            output_device.default_output_config().ok(),
+            |device| device.supported_output_configs().map(|fs| fs.collect()),
+        )?
+        .expect("no matching supported audio output formats for the target device");
+
+        // The input must run at the same sample rate as the output.
+        let desired = super::DesiredStreamConfig {
+            sample_format: super::cpal_sample_format::<S>(),
+            channels: input_channels,
+            sample_rate: Some(output_matching.config.sample_rate),
+            device_buffer_size,
+        };
+        let input_matching = super::find_best_matching_config(
+            &input_device,
+            desired,
+            input_device.default_input_config().ok(),
+            |device| device.supported_input_configs().map(|fs| fs.collect()),
+        )?
+        .expect(
+            "no matching supported audio input formats for the target device at the sample rate \
+             of the output device",
+        );
+
+        let (update_tx, update_rx) = mpsc::channel::<Box<dyn FnMut(&mut M) + 'static + Send>>();
+        let model = Arc::new(Mutex::new(Some(model)));
+        let model_render = model.clone();
+        let model_error = model.clone();
+        let skipped_callbacks = Arc::new(AtomicUsize::new(0));
+        let skipped_callbacks_render = skipped_callbacks.clone();
+        let num_channels = output_matching.config.channels as usize;
+        let num_input_channels = input_matching.config.channels as usize;
+        let sample_rate = output_matching.config.sample_rate.0;
+        let sample_format = output_matching.sample_format;
+        let input_sample_format = input_matching.sample_format;
+        let stream_config = output_matching.config;
+        let input_stream_config = input_matching.config;
+
+        // A buffer for collecting model updates.
+        let mut pending_updates = Vec::new();
+
+        // Get the specified frames_per_buffer or fall back to a default.
+        let frames_per_buffer = frames_per_buffer.unwrap_or(Buffer::<S>::DEFAULT_LEN_FRAMES);
+        let latency_frames = latency_frames.unwrap_or(frames_per_buffer);
+
+        // The captured input, written by the input stream and read by the output stream. Allow
+        // for a generous amount of drift and for input buffers of up to half a second before
+        // discarding input.
+        let capacity_frames = 4 * (latency_frames + frames_per_buffer) + sample_rate as usize / 2;
+        let capacity = capacity_frames * num_input_channels;
+        let (mut producer, consumer) = queue::bounded(capacity);
+        let capture = Arc::new(Capture {
+            overflowed_frames: AtomicUsize::new(0),
+            max_chunk_frames: AtomicUsize::new(0),
+        });
+        let capture_input = capture.clone();
+        let mut input = Input {
+            consumer,
+            capture,
+            samples: vec![S::EQUILIBRIUM; frames_per_buffer * num_input_channels].into(),
+            channels: num_input_channels,
+            sample_rate,
+            latency_frames,
+            primed: false,
+            started: false,
+            slips: 0,
+            window_min_frames: usize::MAX,
+            window_frames: 0,
+        };
+        // The number of frames over which clock drift is measured, roughly a tenth of a second.
+        let window_len_frames = (sample_rate as usize / 10).max(frames_per_buffer);
+
+        // An audio requester which requests frames from the model+process pair with a specific
+        // buffer size, regardless of the buffer size requested by the OS.
+        let mut requester = Requester::new(frames_per_buffer, num_channels);
+
+        // Reads the next input buffer and calls the user's functions for each requested buffer.
+        let render = move |state: &mut (M, &mut Input<S>, usize), output: &mut Buffer<S>| {
+            let (ref mut model, ref mut input, ref mut skipped_frames) = *state;
+            params.apply(model, output.sample_rate());
+            if *skipped_frames > 0 {
+                let frames = std::mem::replace(skipped_frames, 0);
+                xrun(model, Xrun::ModelLocked { frames });
+            }
+            if let Some(overflow) = input.take_overflow() {
+                xrun(model, overflow);
+            }
+            if let Some(underflow) = input.read(output.len_frames(), window_len_frames) {
+                xrun(model, underflow);
+            }
+            let interleaved_samples = std::mem::replace(&mut input.samples, Box::new([]));
+            let buffer = Buffer {
+                interleaved_samples,
+                channels: input.channels,
+                sample_rate: input.sample_rate,
+            };
+            process(model, &buffer, output);
+            input.samples = buffer.interleaved_samples;
+        };
+
+        // An intermediary buffer for converting cpal samples to the target sample format. Output
+        // is rendered in chunks of this length so that the callback never allocates.
+        let mut samples = vec![S::EQUILIBRIUM; frames_per_buffer * num_channels];
+
+        // The number of frames of silence output due to contention on the model lock since last
+        // reported.
+        let mut skipped_frames = 0;
+
+        // The function used to process a buffer of output samples.
+        let render_fn = move |data: &mut cpal::Data, _info: &cpal::OutputCallbackInfo| {
+            // Collect and process any pending updates.
+            macro_rules! process_pending_updates {
+                () => {
+                    // Collect any pending updates.
+                    pending_updates.extend(update_rx.try_iter());
+
+                    // If there are some updates available, try to take the lock and apply them.
+                    // If the lock is contended, the updates are applied on a later callback.
+                    if !pending_updates.is_empty() {
+                        if let Ok(mut guard) = model_render.try_lock() {
+                            let mut model = guard.take().unwrap();
+                            for mut update in pending_updates.drain(..) {
+                                update(&mut model);
+                            }
+                            *guard = Some(model);
+                        }
+                    }
+                };
+            }
+
+            process_pending_updates!();
+
+            // Never block the audio thread. If the lock is contended, output silence.
+            let mut guard = model_render.try_lock();
+            if guard.is_err() {
+                skipped_callbacks_render.fetch_add(1, atomic::Ordering::Relaxed);
+            }
+            let mut render_samples = |samples: &mut [S]| match guard {
+                Ok(ref mut guard) => {
+                    let m = guard.take().unwrap();
+                    let state = (m, &mut input, skipped_frames);
+                    let (m, _, skipped) =
+                        requester.fill_buffer(state, &render, samples, num_channels, sample_rate);
+                    skipped_frames = skipped;
+                    **guard = Some(m);
+                }
+                Err(_) => {
+                    silence(samples);
+                    skipped_frames += samples.len() / num_channels;
+                }
+            };
+
+            // A function to simplify filling the unknown buffer type.
+            fn fill_output<O, S, F>(output: &mut [O], samples: &mut [S], mut render: F)
+            where
+                O: Sample,
+                S: Sample + ToSample<O>,
+                F: FnMut(&mut [S]),
+            {
+                for output in output.chunks_mut(samples.len()) {
+                    let samples = &mut samples[..output.len()];
+                    render(samples);
+                    for (out_sample, sample) in output.iter_mut().zip(samples.iter()) {
+                        *out_sample = sample.to_sample();
+                    }
+                }
+            }
+
+            // Process the given buffer.
+            match sample_format {
+                cpal::SampleFormat::U16 => {
+                    let output = data.as_slice_mut::<u16>().expect("expected u16 data");
+                    fill_output(output, &mut samples, &mut render_samples);
+                }
+                cpal::SampleFormat::I16 => {
+                    let output = data.as_slice_mut::<i16>().expect("expected i16 data");
+                    fill_output(output, &mut samples, &mut render_samples);
+                }
+                cpal::SampleFormat::F32 => {
+                    let output = data.as_slice_mut::<f32>().expect("expected f32 data");
+                    fill_output(output, &mut samples, &mut render_samples);
+                }
+            }
+        };
+
+        // The function used to capture a buffer of input samples.
+        let capture_fn = move |data: &cpal::Data, _info: &cpal::InputCallbackInfo| {
+            let (producer, capture) = (&mut producer, &*capture_input);
+            let channels = num_input_channels;
+            match input_sample_format {
+                cpal::SampleFormat::U16 => {
+                    let input = data.as_slice::<u16>().expect("expected u16 data");
+                    push_input(producer, capture, channels, input);
+                }
+                cpal::SampleFormat::I16 => {
+                    let input = data.as_slice::<i16>().expect("expected i16 data");
+                    push_input(producer, capture, channels, input);
+                }
+                cpal::SampleFormat::F32 => {
+                    let input = data.as_slice::<f32>().expect("expected f32 data");
+                    push_input(producer, capture, channels, input);
+                }
+            }
+        };
+
+        // Wrap the user's error function, shared between the input and output streams.
+        let error = Arc::new(Mutex::new(error));
+        let input_error = error.clone();
+        let model_input_error = model.clone();
+        let err_fn = move |err| {
+            if let (Ok(mut guard), Ok(error)) = (model_error.lock(), error.lock()) {
+                if let Some(ref mut model) = *guard {
+                    (*error)(model, err);
+                }
+            }
+        };
+        let input_err_fn = move |err| {
+            if let (Ok(mut guard), Ok(error)) = (model_input_error.lock(), input_error.lock()) {
+                if let Some(ref mut model) = *guard {
+                    (*error)(model, err);
+                }
+            }
+        };
+
+        let input_stream = input_device.build_input_stream_raw(
+            &input_stream_config,
+            input_sample_format,
+            capture_fn,
+            input_err_fn,
+        )?;
+        let output_stream = output_device.build_output_stream_raw(
+            &stream_config,
+            sample_format,
+            render_fn,
+            err_fn,
+        )?;
+
+        let shared = Arc::new(super::Shared {
+            streams: vec![input_stream, output_stream],
+            model,
+            is_paused: AtomicBool::new(false),
+            skipped_callbacks,
+        });
+
+        let stream = Stream {
+            shared,
+            update_tx,
+            cpal_config: stream_config,
+        };
+        Ok(stream)
+    }
+}
+
+// Convert and push as many whole frames of the captured input as there is room for, discarding
+// the rest.
+fn push_input<I, S>(
+    producer: &mut queue::Producer<S>,
+    capture: &Capture,
+    channels: usize,
+    input: &[I],
+) where
+    I: Sample + ToSample<S>,
+{
+    let chunk_frames = input.len() / channels;
+    capture
+        .max_chunk_frames
+        .fetch_max(chunk_frames, atomic::Ordering::Relaxed);
+    let frames = chunk_frames.min(producer.slots() / channels);
+    let samples = input[..frames * channels].iter().map(|s| s.to_sample());
+    producer.push_iter(samples);
+    if frames < chunk_frames {
+        capture
+            .overflowed_frames
+            .fetch_add(chunk_frames - frames, atomic::Ordering::Relaxed);
+    }
+}
+
+impl<S> Input<S>
+where
+    S: Sample,
+{
+    // Take the number of frames discarded due to overflow since last called.
+    //
+    // Returns an xrun in the case that input was discarded, after which the input begins priming
+    // again to discard the excess.
+    fn take_overflow(&mut self) -> Option<Xrun> {
+        let frames = self
+            .capture
+            .overflowed_frames
+            .swap(0, atomic::Ordering::Relaxed);
+        if frames == 0 {
+            return None;
+        }
+        self.primed = false;
+        Some(Xrun::InputOverflow { frames })
+    }
+
+    // Read the next `frames` frames of input into `samples`.
+    //
+    // Returns an xrun in the case that the input underflowed.
+    fn read(&mut self, frames: usize, window_len_frames: usize) -> Option<Xrun> {
+        let channels = self.channels;
+        let consumer = &mut self.consumer;
+        let mut available = consumer.len() / channels;
+
+        // Wait until enough input is buffered to bridge the buffer sizes of both devices.
+        if !self.primed {
+            let max_chunk_frames = self
+                .capture
+                .max_chunk_frames
+                .load(atomic::Ordering::Relaxed);
+            let target = self.latency_frames + max_chunk_frames + frames;
+            if available < target {
+                silence(&mut self.samples);
+                return match self.started {
+                    true => Some(Xrun::InputUnderflow { frames }),
+                    false => None,
+                };
+            }
+            // Discard any input beyond the target to avoid unnecessary latency.
+            consumer.discard((available - target) * channels);
+            available = target;
+            self.primed = true;
+            self.slips = 0;
+            self.started = true;
+            self.window_min_frames = usize::MAX;
+            self.window_frames = 0;
+        }
+
+        // If there is not enough input, pad what there is with silence and begin priming again.
+        if available < frames {
+            let len = available * channels;
+            consumer.pop_slice(&mut self.samples[..len]);
+            silence(&mut self.samples[len..]);
+            self.primed = false;
+            return Some(Xrun::InputUnderflow {
+                frames: frames - available,
+            });
+        }
+
+        // Read the input, applying any correction for clock drift.
+        let len = frames * channels;
+        if self.slips > 0 && available > frames {
+            consumer.discard(channels);
+            consumer.pop_slice(&mut self.samples);
+            self.slips -= 1;
+        } else if self.slips < 0 && frames > 1 {
+            let last = len - channels;
+            consumer.pop_slice(&mut self.samples[..last]);
+            let (head, tail) = self.samples.split_at_mut(last);
+            tail.copy_from_slice(&head[last - channels..]);
+            self.slips += 1;
+        } else {
+            consumer.pop_slice(&mut self.samples);
+        }
+
+        // Measure the clock drift via the least number of frames remaining over a window, and
+        // schedule slips in proportion to the deviation from the latency.
+        let remaining = consumer.len() / channels;
+        self.window_min_frames = self.window_min_frames.min(remaining);
+        self.window_frames += frames;
+        if self.window_frames >= window_len_frames {
+            let min = self.window_min_frames as isize;
+            let latency = self.latency_frames as isize;
+            let max_slips = (window_len_frames / frames) as isize;
+            self.slips = if min > latency + frames as isize {
+                ((min - latency) / 4).clamp(1, max_slips)
+            } else if min < latency {
+                -((latency - min) / 4).clamp(1, max_slips)
+            } else {
+                0
+            };
+            self.window_min_frames = usize::MAX;
+            self.window_frames = 0;
+        }
+
+        None
+    }
+}
+
+// Fill the given buffer with silence.
+fn silence<S: Sample>(buffer: &mut [S]) {
+    for sample in buffer {
+        *sample = S::EQUILIBRIUM;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAMES: usize = 4;
+    const WINDOW: usize = 16;
+
+    // An input of one channel with the given latency, along with the producer of its queue.
+    fn new_input(latency_frames: usize, capacity: usize) -> (queue::Producer<f32>, Input<f32>) {
+        let (producer, consumer) = queue::bounded(capacity);
+        let capture = Arc::new(Capture {
+            overflowed_frames: AtomicUsize::new(0),
+            max_chunk_frames: AtomicUsize::new(0),
+        });
+        let input = Input {
+            consumer,
+            capture,
+            samples: vec![0.0; FRAMES].into(),
+            channels: 1,
+            sample_rate: 44_100,
+            latency_frames,
+            primed: false,
+            started: false,
+            slips: 0,
+            window_min_frames: usize::MAX,
+            window_frames: 0,
+        };
+        (producer, input)
+    }
+
+    // Push the given frames as a single captured chunk.
+    fn capture(producer: &mut queue::Producer<f32>, input: &Input<f32>, frames: &[f32]) {
+        push_input(producer, &input.capture, 1, frames);
+    }
+
+    fn ramp(range: std::ops::Range<usize>) -> Vec<f32> {
+        range.map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn priming_waits_for_latency_and_discards_excess() {
+        let (mut producer, mut input) = new_input(2, 64);
+        // The target is the latency, the greatest chunk and the read, or 2 + 4 + 4 frames.
+        capture(&mut producer, &input, &ramp(1..5));
+        assert_eq!(input.read(FRAMES, WINDOW), None);
+        assert_eq!(&input.samples[..], &[0.0; FRAMES]);
+        assert!(!input.primed);
+
+        capture(&mut producer, &input, &ramp(5..9));
+        capture(&mut producer, &input, &ramp(9..13));
+        assert_eq!(input.read(FRAMES, WINDOW), None);
+        assert!(input.primed);
+        // The two frames beyond the target were discarded.
+        assert_eq!(&input.samples[..], &ramp(3..7)[..]);
+        assert_eq!(input.consumer.len(), 6);
+    }
+
+    #[test]
+    fn underflow_pads_with_silence_and_primes_again() {
+        let (mut producer, mut input) = new_input(0, 64);
+        capture(&mut producer, &input, &ramp(1..5));
+        capture(&mut producer, &input, &ramp(5..9));
+        assert_eq!(input.read(FRAMES, WINDOW), None);
+        assert_eq!(&input.samples[..], &ramp(1..5)[..]);
+
+        capture(&mut producer, &input, &ramp(9..11));
+        assert_eq!(input.read(FRAMES, WINDOW), None);
+        assert_eq!(
+            input.read(FRAMES, WINDOW),
+            Some(Xrun::InputUnderflow { frames: 2 })
+        );
+        assert_eq!(&input.samples[..], &[9.0, 10.0, 0.0, 0.0]);
+        assert!(!input.primed);
+
+        // Once started, the frames read while priming again are reported as underflow too.
+        assert_eq!(
+            input.read(FRAMES, WINDOW),
+            Some(Xrun::InputUnderflow { frames: FRAMES })
+        );
+        assert_eq!(&input.samples[..], &[0.0; FRAMES]);
+    }
+
+    #[test]
+    fn slips_drop_or_repeat_a_frame() {
+        let (mut producer, mut input) = new_input(0, 64);
+        capture(&mut producer, &input, &ramp(1..17));
+        input.primed = true;
+        input.started = true;
+
+        input.slips = 1;
+        assert_eq!(input.read(FRAMES, 1000), None);
+        assert_eq!(&input.samples[..], &[2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(input.slips, 0);
+
+        input.slips = -1;
+        assert_eq!(input.read(FRAMES, 1000), None);
+        assert_eq!(&input.samples[..], &[6.0, 7.0, 8.0, 8.0]);
+        assert_eq!(input.slips, 0);
+
+        assert_eq!(input.read(FRAMES, 1000), None);
+        assert_eq!(&input.samples[..], &[9.0, 10.0, 11.0, 12.0]);
+    }
+
+    #[test]
+    fn drift_schedules_slips() {
+        // More input remaining than the latency requires schedules dropped frames.
+        let (mut producer, mut input) = new_input(0, 64);
+        capture(&mut producer, &input, &ramp(0..4));
+        capture(&mut producer, &input, &ramp(0..24));
+        assert_eq!(input.read(FRAMES, FRAMES), None);
+        assert!(input.slips > 0);
+
+        // Less input remaining than the latency requires schedules repeated frames.
+        let (mut producer, mut input) = new_input(8, 64);
+        capture(&mut producer, &input, &ramp(0..12));
+        input.primed = true;
+        input.started = true;
+        assert_eq!(input.read(FRAMES, FRAMES), None);
+        assert_eq!(input.consumer.len(), 8);
+        assert_eq!(input.slips, 0);
+        assert_eq!(input.read(FRAMES, FRAMES), None);
+        assert_eq!(input.slips, -1);
+    }
+
+    #[test]
+    fn overflow_discards_recent_frames_and_primes_again() {
+        let (mut producer, mut input) = new_input(0, 8);
+        assert_eq!(input.take_overflow(), None);
+        capture(&mut producer, &input, &ramp(0..6));
+        capture(&mut producer, &input, &ramp(6..12));
+        input.primed = true;
+        assert_eq!(
+            input.take_overflow(),
+            Some(Xrun::InputOverflow { frames: 4 })
+        );
+        let mut buffered = [0.0; 8];
+        assert_eq!(input.consumer.pop_slice(&mut buffered), 8);
+        assert_eq!(&buffered[..], &ramp(0..8)[..]);
+        assert!(!input.primed);
+        assert_eq!(input.take_overflow(), None);
+
+        // Only whole frames are pushed.
+        let (mut producer, input) = new_input(0, 5);
+        push_input(&mut producer, &input.capture, 2, &ramp(0..6));
+        assert_eq!(input.consumer.len(), 4);
+        assert_eq!(
+            input
+                .capture
+                .overflowed_frames
+                .load(atomic::Ordering::Relaxed),
+            1
+        );
+    }
+}
@@ -1,20 +1,21 @@
+use crate::param::Params;
 use crate::Device;
 use cpal::traits::StreamTrait;
 use std;
 use std::any::{Any, TypeId};
 use std::marker::PhantomData;
-use std::sync::atomic::{self, AtomicBool};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
 use std::sync::{mpsc, Arc, Mutex};
 use thiserror::Error;
 
+/// Items related to duplex (synchronised input/output) audio streams.
+pub mod duplex;
 /// Items related to input audio streams.
 pub mod input;
+/// Items related to offline audio streams, rendered faster than realtime without a device.
+pub mod offline;
 /// Items related to output audio streams.
 pub mod output;
-/// Items related to duplex (synchronised input/output) audio streams.
-///
-/// *Progress is currently pending implementation of duplex audio streams in CPAL.*
-pub mod duplex {}
 
 /// Called by the audio host in the case that an error occurs on an audio stream thread.
 pub trait ErrorFn<M>: Fn(&mut M, cpal::StreamError) {}
@@ -36,12 +37,15 @@ pub struct Stream<M> {
 
 // Data shared between each `Stream` handle to a single stream.
 struct Shared<M> {
-    // The CPAL stream handle.
-    stream: cpal::Stream,
+    // The CPAL stream handles, i.e. the input and output streams of a duplex stream.
+    streams: Vec<cpal::Stream>,
     // The user's audio model
     model: Arc<Mutex<Option<M>>>,
     // Whether or not the stream is currently paused.
     is_paused: AtomicBool,
+    // The number of callbacks skipped as the model was locked elsewhere, counted by the audio
+    // thread.
+    skipped_callbacks: Arc<AtomicUsize>,
 }
 
 /// Stream building parameters that are common between input and output streams.
@@ -53,6 +57,7 @@ pub struct Builder<M, S = f32> {
     pub frames_per_buffer: Option<usize>,
     pub device_buffer_size: Option<cpal::BufferSize>,
     pub device: Option<Device>,
+    pub(crate) params: Params<M>,
     pub(crate) sample_format: PhantomData<S>,
 }
 
@@ -114,6 +119,17 @@ impl<M> Stream<M> {
         self.shared.is_paused()
     }
 
+    /// The number of audio callbacks skipped since the stream was built as the model was locked
+    /// elsewhere, e.g. by the error function.
+    ///
+    /// The audio thread never blocks on the model. A skipped input callback drops the captured
+    /// buffer, while a skipped output callback outputs silence.
+    pub fn skipped_callbacks(&self) -> usize {
+        self.shared
+            .skipped_callbacks
+            .load(atomic::Ordering::Relaxed)
+    }
+
     /// Send the given model update to the audio thread to be applied ASAP.
     ///
     /// If the audio is currently rendering, the update will be applied immediately after the
@@ -124,6 +140,11 @@ impl<M> Stream<M> {
     /// **Note:** This function will be applied on the real-time audio thread so users should
     /// avoid performing any kind of I/O, locking, blocking, (de)allocations or anything that
     /// may run for an indeterminate amount of time.
+    ///
+    /// **Note:** Each call allocates in order to box the update, and the audio thread applies
+    /// updates while holding the lock on the model. For parameters that change frequently or
+    /// events that must reach the audio thread without locking or allocating, see the
+    /// [**param**](../param/index.html) and [**queue**](../queue/index.html) modules.
     pub fn send<F>(
         &self,
         update: F,
@@ -178,13 +199,17 @@ impl<M> Stream<M> {
 
 impl<M> Shared<M> {
     fn play(&self) -> Result<(), cpal::PlayStreamError> {
-        self.stream.play()?;
+        for stream in &self.streams {
+            stream.play()?;
+        }
         self.is_paused.store(false, atomic::Ordering::Relaxed);
         Ok(())
     }
 
     fn pause(&self) -> Result<(), cpal::PauseStreamError> {
-        self.stream.pause()?;
+        for stream in &self.streams {
+            stream.pause()?;
+        }
         self.is_paused.store(true, atomic::Ordering::Relaxed);
         Ok(())
     }
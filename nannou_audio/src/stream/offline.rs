@@ -0,0 +1,298 @@
+use crate::param::{BoolParam, Field, FloatParam, Params, Smoothed};
+use crate::stream::output::{self, DefaultRenderFn, RenderFn};
+use crate::{Buffer, Requester};
+use dasp_sample::{Sample, ToSample};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::Duration;
+
+/// A type used for building an offline stream.
+pub struct Builder<M, FR, S = f32> {
+    pub model: M,
+    pub render: FR,
+    pub sample_rate: u32,
+    pub channels: usize,
+    pub frames_per_buffer: usize,
+    params: Params<M>,
+    sample_format: std::marker::PhantomData<S>,
+}
+
+/// The builder when first initialised.
+pub type BuilderInit<M, S = f32> = Builder<M, DefaultRenderFn<M, S>, S>;
+
+/// An output stream that is rendered on demand rather than by an audio device.
+///
+/// The stream keeps a virtual clock that advances by the number of frames rendered, allowing
+/// audio to be rendered faster than realtime, e.g. to disk or in sync with offline frame capture,
+/// or in tests on machines without a sound card.
+pub struct Stream<M, FR, S = f32> {
+    model: M,
+    render: FR,
+    params: Params<M>,
+    requester: Requester<S>,
+    sample_rate: u32,
+    channels: usize,
+    frames_per_buffer: usize,
+    frames_rendered: u64,
+}
+
+/// Writes interleaved samples to a WAV file as 32-bit floats.
+pub struct WavWriter {
+    writer: hound::WavWriter<BufWriter<File>>,
+}
+
+/// The default number of channels of an offline stream.
+pub const DEFAULT_CHANNELS: usize = 2;
+
+impl<M, S> BuilderInit<M, S> {
+    /// Begin building an offline stream for the given model.
+    pub fn new(model: M) -> Self {
+        Builder {
+            model,
+            render: output::default_render_fn,
+            sample_rate: super::DEFAULT_SAMPLE_RATE,
+            channels: DEFAULT_CHANNELS,
+            frames_per_buffer: Buffer::<S>::DEFAULT_LEN_FRAMES,
+            params: Params::new(),
+            sample_format: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M, FR, S> Builder<M, FR, S> {
+    /// Specify the render function to use for rendering the model to the buffer.
+    pub fn render<GR>(self, render: GR) -> Builder<M, GR, S> {
+        let Builder {
+            model,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            params,
+            sample_format,
+            ..
+        } = self;
+        Builder {
+            model,
+            render,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            params,
+            sample_format,
+        }
+    }
+
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        assert!(sample_rate > 0);
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    pub fn channels(mut self, channels: usize) -> Self {
+        assert!(channels > 0);
+        self.channels = channels;
+        self
+    }
+
+    pub fn frames_per_buffer(mut self, frames_per_buffer: usize) -> Self {
+        assert!(frames_per_buffer > 0);
+        self.frames_per_buffer = frames_per_buffer;
+        self
+    }
+
+    /// Drive the given field of the model with the given parameter.
+    ///
+    /// The field is set to the parameter's current value immediately and then smoothly ramps
+    /// towards each new value, with targets updated before each buffer is rendered.
+    pub fn float_param(mut self, param: &FloatParam, field: Field<M, Smoothed>) -> Self {
+        self.params.float(&mut self.model, param, field);
+        self
+    }
+
+    /// Drive the given field of the model with the given parameter.
+    ///
+    /// The field is set to the parameter's current value immediately and then updated before
+    /// each buffer is rendered.
+    pub fn bool_param(mut self, param: &BoolParam, field: Field<M, bool>) -> Self {
+        self.params.bool(&mut self.model, param, field);
+        self
+    }
+
+    pub fn build(self) -> Stream<M, FR, S>
+    where
+        S: Sample,
+        FR: RenderFn<M, S>,
+    {
+        let Builder {
+            model,
+            render,
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            params,
+            ..
+        } = self;
+        Stream {
+            model,
+            render,
+            params,
+            requester: Requester::new(frames_per_buffer, channels),
+            sample_rate,
+            channels,
+            frames_per_buffer,
+            frames_rendered: 0,
+        }
+    }
+}
+
+impl<M, FR, S> Stream<M, FR, S>
+where
+    S: Sample,
+    FR: RenderFn<M, S>,
+{
+    /// Render the given number of frames, appending the interleaved samples to `output`.
+    ///
+    /// The render function is always called with buffers of `frames_per_buffer` frames. Frames
+    /// rendered beyond the requested number are retained and written by the following call.
+    pub fn render_frames(&mut self, frames: usize, output: &mut Vec<S>) {
+        let Stream {
+            ref mut model,
+            ref render,
+            ref params,
+            ref mut requester,
+            sample_rate,
+            channels,
+            ..
+        } = *self;
+        let start = output.len();
+        output.resize(start + frames * channels, S::EQUILIBRIUM);
+        let render = |model: &mut &mut M, buffer: &mut Buffer<S>| {
+            params.apply(model, buffer.sample_rate());
+            render(*model, buffer)
+        };
+        requester.fill_buffer(model, &render, &mut output[start..], channels, sample_rate);
+        self.frames_rendered += frames as u64;
+    }
+
+    /// Render all frames up until the given time on the stream's clock, appending the
+    /// interleaved samples to `output`.
+    ///
+    /// Has no effect if the clock has already reached the given time. This is useful for
+    /// rendering audio in sync with some other offline process, e.g. by rendering up until the
+    /// time of each captured video frame.
+    pub fn render_until(&mut self, time: Duration, output: &mut Vec<S>) {
+        let end = (time.as_secs_f64() * self.sample_rate as f64).round() as u64;
+        let frames = end.saturating_sub(self.frames_rendered);
+        self.render_frames(frames as usize, output);
+    }
+
+    /// Render the given duration of audio to a new `Vec` of interleaved samples.
+    pub fn render_vec(&mut self, duration: Duration) -> Vec<S> {
+        let mut output = vec![];
+        self.render_frames(self.duration_frames(duration), &mut output);
+        output
+    }
+
+    /// Render the given duration of audio to a WAV file at the given path.
+    pub fn render_wav<P>(&mut self, path: P, duration: Duration) -> Result<(), hound::Error>
+    where
+        P: AsRef<Path>,
+        S: ToSample<f32>,
+    {
+        let mut wav = WavWriter::create(path, self.channels, self.sample_rate)?;
+        let mut remaining = self.duration_frames(duration);
+        let mut samples = Vec::with_capacity(self.frames_per_buffer * self.channels);
+        while remaining > 0 {
+            let frames = remaining.min(self.frames_per_buffer);
+            samples.clear();
+            self.render_frames(frames, &mut samples);
+            wav.write(&samples)?;
+            remaining -= frames;
+        }
+        wav.finalize()
+    }
+}
+
+impl<M, FR, S> Stream<M, FR, S> {
+    /// A reference to the model.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    /// A mutable reference to the model, e.g. for updating it between renders.
+    pub fn model_mut(&mut self) -> &mut M {
+        &mut self.model
+    }
+
+    /// Consume the stream, producing the model.
+    pub fn into_model(self) -> M {
+        self.model
+    }
+
+    /// The sample rate of the stream.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of channels of the stream.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// The number of frames per buffer passed to the render function.
+    pub fn frames_per_buffer(&self) -> usize {
+        self.frames_per_buffer
+    }
+
+    /// The number of frames rendered so far.
+    pub fn frames_rendered(&self) -> u64 {
+        self.frames_rendered
+    }
+
+    /// The time of the stream's clock, i.e. the duration of the audio rendered so far.
+    pub fn time(&self) -> Duration {
+        let secs = self.frames_rendered as f64 / self.sample_rate as f64;
+        Duration::from_secs_f64(secs)
+    }
+
+    // The number of frames within the given duration.
+    fn duration_frames(&self, duration: Duration) -> usize {
+        (duration.as_secs_f64() * self.sample_rate as f64).round() as usize
+    }
+}
+
+impl WavWriter {
+    /// Create a WAV file at the given path.
+    pub fn create<P>(path: P, channels: usize, sample_rate: u32) -> Result<Self, hound::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let spec = hound::WavSpec {
+            channels: channels as u16,
+            sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let writer = hound::WavWriter::create(path, spec)?;
+        Ok(WavWriter { writer })
+    }
+
+    /// Write the given interleaved samples.
+    pub fn write<S>(&mut self, samples: &[S]) -> Result<(), hound::Error>
+    where
+        S: Sample + ToSample<f32>,
+    {
+        for &sample in samples {
+            self.writer.write_sample(sample.to_sample::<f32>())?;
+        }
+        Ok(())
+    }
+
+    /// Finish writing the file, updating its header.
+    ///
+    /// This is called automatically when the writer is dropped, though any error will be ignored.
+    pub fn finalize(self) -> Result<(), hound::Error> {
+        self.writer.finalize()
+    }
+}
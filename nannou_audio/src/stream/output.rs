@@ -1,10 +1,11 @@
 use crate::{
+    param::{BoolParam, Field, FloatParam, Smoothed},
     stream::{self, DefaultErrorFn, ErrorFn},
     Buffer, Device, Requester, Stream,
 };
 use cpal::traits::{DeviceTrait, HostTrait};
 use dasp_sample::{Sample, ToSample};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 
@@ -18,6 +19,10 @@ pub type DefaultRenderFn<M, S> = fn(&mut M, &mut Buffer<S>);
 pub(crate) fn default_render_fn<M, S>(_: &mut M, _: &mut Buffer<S>) {}
 
 /// A type used for building an output stream.
+///
+/// The audio callback never blocks on the model. If the model is locked elsewhere when a buffer is
+/// requested, e.g. while the error function runs, silence is output in place of the rendered
+/// buffer and the callback is counted by `Stream::skipped_callbacks`.
 pub struct Builder<M, FR, FE, S = f32> {
     pub builder: super::Builder<M, S>,
     pub render: FR,
@@ -87,6 +92,26 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
         self
     }
 
+    /// Drive the given field of the model with the given parameter.
+    ///
+    /// The field is set to the parameter's current value immediately and then smoothly ramps
+    /// towards each new value, with targets updated before each buffer is rendered.
+    pub fn float_param(mut self, param: &FloatParam, field: Field<M, Smoothed>) -> Self {
+        let builder = &mut self.builder;
+        builder.params.float(&mut builder.model, param, field);
+        self
+    }
+
+    /// Drive the given field of the model with the given parameter.
+    ///
+    /// The field is set to the parameter's current value immediately and then updated before
+    /// each buffer is rendered.
+    pub fn bool_param(mut self, param: &BoolParam, field: Field<M, bool>) -> Self {
+        let builder = &mut self.builder;
+        builder.params.bool(&mut builder.model, param, field);
+        self
+    }
+
     pub fn build(self) -> std::result::Result<Stream<M>, super::BuildError>
     where
         S: 'static + Send + Sample + ToSample<u16> + ToSample<i16> + ToSample<f32>,
@@ -106,6 +131,7 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
                     frames_per_buffer,
                     device_buffer_size,
                     device,
+                    params,
                     ..
                 },
         } = self;
@@ -136,11 +162,19 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
         let model = Arc::new(Mutex::new(Some(model)));
         let model_render = model.clone();
         let model_error = model.clone();
+        let skipped_callbacks = Arc::new(AtomicUsize::new(0));
+        let skipped_callbacks_render = skipped_callbacks.clone();
         let num_channels = matching.config.channels as usize;
         let sample_rate = matching.config.sample_rate.0;
         let sample_format = matching.sample_format;
         let stream_config = matching.config.into();
 
+        // Apply the params before rendering each buffer.
+        let render = move |model: &mut M, buffer: &mut Buffer<S>| {
+            params.apply(model, buffer.sample_rate());
+            render(model, buffer);
+        };
+
         // A buffer for collecting model updates.
         let mut pending_updates: Vec<Box<dyn FnMut(&mut M) + 'static + Send>> = Vec::new();
 
@@ -164,9 +198,10 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
                     // Collect any pending updates.
                     pending_updates.extend(update_rx.try_iter());
 
-                    // If there are some updates available, take the lock and apply them.
+                    // If there are some updates available, try to take the lock and apply them.
+                    // If the lock is contended, the updates are applied on a later callback.
                     if !pending_updates.is_empty() {
-                        if let Ok(mut guard) = model_render.lock() {
+                        if let Ok(mut guard) = model_render.try_lock() {
                             let mut model = guard.take().unwrap();
                             for mut update in pending_updates.drain(..) {
                                 update(&mut model);
@@ -182,10 +217,16 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
             samples.clear();
             samples.resize(data.len(), S::EQUILIBRIUM);
 
-            if let Ok(mut guard) = model_render.lock() {
-                let mut m = guard.take().unwrap();
-                m = requester.fill_buffer(m, &render, &mut samples, num_channels, sample_rate);
-                *guard = Some(m);
+            // Never block the audio thread. If the lock is contended, output silence.
+            match model_render.try_lock() {
+                Ok(mut guard) => {
+                    let mut m = guard.take().unwrap();
+                    m = requester.fill_buffer(m, &render, &mut samples, num_channels, sample_rate);
+                    *guard = Some(m);
+                }
+                Err(_) => {
+                    skipped_callbacks_render.fetch_add(1, atomic::Ordering::Relaxed);
+                }
             }
 
             // A function to simplify filling the unknown buffer type.
@@ -229,9 +270,10 @@ impl<M, FR, FE, S> Builder<M, FR, FE, S> {
             device.build_output_stream_raw(&stream_config, sample_format, render_fn, err_fn)?;
 
         let shared = Arc::new(super::Shared {
-            stream,
+            streams: vec![stream],
             model,
             is_paused: AtomicBool::new(false),
+            skipped_callbacks,
         });
 
         let stream = Stream {
@@ -0,0 +1,195 @@
+use nannou_audio::analysis::{
+    self, Bands, Complex, Fft, Meter, OnsetDetector, PitchDetector, Stft, Window,
+};
+use std::f32::consts::PI;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+fn sine(hz: f32, amp: f32, frames: usize) -> Vec<f32> {
+    (0..frames)
+        .map(|i| amp * (2.0 * PI * hz * i as f32 / SAMPLE_RATE as f32).sin())
+        .collect()
+}
+
+#[test]
+fn fft_round_trip() {
+    let fft = Fft::new(64);
+    let input: Vec<_> = (0..64)
+        .map(|i| Complex::new((i as f32 * 0.3).sin(), 0.0))
+        .collect();
+    let mut data = input.clone();
+    fft.forward(&mut data);
+    fft.inverse(&mut data);
+    for (a, b) in input.iter().zip(&data) {
+        assert!((a.re - b.re).abs() < 1e-5 && b.im.abs() < 1e-5);
+    }
+}
+
+#[test]
+fn window_shapes() {
+    let len = 16;
+    assert_eq!(Window::Rectangular.coefficient(0, len), 1.0);
+    assert!(Window::Hann.coefficient(0, len).abs() < 1e-6);
+    assert!((Window::Hann.coefficient(len / 2, len) - 1.0).abs() < 1e-6);
+    assert!((Window::Hamming.coefficient(0, len) - 0.08).abs() < 1e-6);
+    assert!(Window::Blackman.coefficient(0, len).abs() < 1e-6);
+}
+
+#[test]
+fn stft_sine_peak() {
+    let size = 1024;
+    let mut stft = Stft::new(size, size, Window::Hann);
+    // Centered on a bin to avoid scalloping loss.
+    let hz = 40.0 * SAMPLE_RATE as f32 / size as f32;
+    let mut frames = 0;
+    for s in sine(hz, 0.5, size) {
+        if stft.push(s, SAMPLE_RATE) {
+            frames += 1;
+        }
+    }
+    assert_eq!(frames, 1);
+    let spectrum = stft.spectrum();
+    let (peak, &magnitude) = spectrum
+        .magnitudes()
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .unwrap();
+    assert_eq!(peak, 40);
+    assert_eq!(spectrum.frequency_bin(hz), 40);
+    assert!((magnitude - 0.5).abs() < 0.01, "{}", magnitude);
+}
+
+#[test]
+fn meter_levels() {
+    let mut meter = Meter::new().rms_time(0.05);
+    let left = sine(1_000.0, 0.8, SAMPLE_RATE as usize);
+    let interleaved: Vec<f32> = left.iter().flat_map(|&s| vec![s, 0.0]).collect();
+    meter.process(&interleaved, 2, SAMPLE_RATE);
+    let levels = meter.levels();
+    assert_eq!(levels.len(), 2);
+    assert!((levels[0].rms - 0.8 / 2f32.sqrt()).abs() < 0.02);
+    assert!((levels[0].peak - 0.8).abs() < 0.01);
+    assert_eq!(levels[1].rms, 0.0);
+    assert!((meter.level().rms - 0.4).abs() < 0.02);
+}
+
+#[test]
+fn bands_contain_sine() {
+    let bands = Bands::logarithmic(8, 50.0, 12_800.0);
+    assert_eq!(bands.count(), 8);
+    assert!((bands.edges()[1] - 100.0).abs() < 0.01);
+    let mut stft = Stft::new(2048, 2048, Window::Hann);
+    for s in sine(1_000.0, 1.0, 2048) {
+        stft.push(s, SAMPLE_RATE);
+    }
+    let mut magnitudes = vec![0.0; bands.count()];
+    bands.magnitudes(stft.spectrum(), &mut magnitudes);
+    // 1 kHz lies within 800 Hz..1600 Hz.
+    let loudest = (0..8)
+        .max_by(|&a, &b| magnitudes[a].partial_cmp(&magnitudes[b]).unwrap())
+        .unwrap();
+    assert_eq!(loudest, 4);
+    assert!((magnitudes[4] - 1.0).abs() < 0.05, "{}", magnitudes[4]);
+}
+
+#[test]
+fn onsets_at_clicks() {
+    let hop = 256;
+    let mut stft = Stft::new(1024, hop, Window::Hann);
+    let mut detector = OnsetDetector::new();
+    let hop_secs = hop as f32 / SAMPLE_RATE as f32;
+    // A short burst every half second over low noise.
+    let mut onsets = vec![];
+    let mut noise = 1u32;
+    for i in 0..SAMPLE_RATE as usize * 2 {
+        noise = noise.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let mut s = (noise >> 16) as f32 / 65_536.0 * 0.002 - 0.001;
+        if i % (SAMPLE_RATE as usize / 2) < 64 {
+            s += 0.8 * (i as f32 * 0.5).sin();
+        }
+        if stft.push(s, SAMPLE_RATE) && detector.process(stft.spectrum(), hop_secs).is_some() {
+            onsets.push(i);
+        }
+    }
+    // The first burst precedes the first complete frame.
+    assert_eq!(onsets.len(), 3, "{:?}", onsets);
+    for (n, &i) in onsets.iter().enumerate() {
+        let burst = (n + 1) * SAMPLE_RATE as usize / 2;
+        assert!(i >= burst && i < burst + 1024, "{:?}", onsets);
+    }
+}
+
+#[test]
+fn pitch_of_sine() {
+    let mut detector = PitchDetector::new(2048);
+    for &hz in &[82.4, 220.0, 440.0, 1_046.5] {
+        let frame = sine(hz, 0.5, 2048);
+        let pitch = detector.detect(&frame, SAMPLE_RATE).unwrap();
+        assert!(
+            (pitch.frequency - hz).abs() / hz < 0.005,
+            "{} {:?}",
+            hz,
+            pitch
+        );
+        assert!(pitch.clarity > 0.9);
+    }
+}
+
+#[test]
+fn no_pitch_for_silence() {
+    let mut detector = PitchDetector::new(2048);
+    assert_eq!(detector.detect(&[0.0; 2048], SAMPLE_RATE), None);
+}
+
+#[test]
+fn smallest_pitch_detector() {
+    let mut detector = PitchDetector::new(4);
+    assert_eq!(detector.detect(&[0.0; 4], SAMPLE_RATE), None);
+    detector.detect(&[1.0, -1.0, 1.0, -1.0], SAMPLE_RATE);
+    let (mut analyzer, _reader) = analysis::Builder::new().fft_size(4).hop(4).build();
+    analyzer.process_interleaved(&sine(440.0, 0.5, 64), 1, SAMPLE_RATE);
+}
+
+#[test]
+#[should_panic(expected = "the FFT size must be at least 4")]
+fn fft_size_too_small() {
+    analysis::Builder::new().fft_size(2);
+}
+
+#[test]
+fn reader_sees_latest_analysis() {
+    let (mut analyzer, mut reader) = analysis::Builder::new().build();
+    assert_eq!(reader.read().frames, 0);
+    let samples: Vec<f32> = sine(440.0, 0.5, 4096)
+        .iter()
+        .flat_map(|&s| vec![s, s])
+        .collect();
+    for chunk in samples.chunks(512 * 2) {
+        analyzer.process_interleaved(chunk, 2, SAMPLE_RATE);
+    }
+    let analysis = reader.read();
+    assert_eq!(analysis, analyzer.analysis());
+    assert_eq!(analysis.frames, 4096);
+    assert_eq!(analysis.channel_levels.len(), 2);
+    let pitch = analysis.pitch.unwrap();
+    assert!((pitch.frequency - 440.0).abs() < 2.0);
+}
+
+#[test]
+fn reader_on_another_thread() {
+    let (mut analyzer, mut reader) = analysis::Builder::new().pitch(false).build();
+    let handle = std::thread::spawn(move || {
+        let mut last = 0;
+        while last < 100 * 256 {
+            let frames = reader.read().frames;
+            assert!(frames >= last && frames % 256 == 0);
+            last = frames;
+        }
+    });
+    let samples = sine(440.0, 0.5, 256);
+    for _ in 0..100 {
+        analyzer.process_interleaved(&samples, 1, SAMPLE_RATE);
+    }
+    handle.join().unwrap();
+}
@@ -0,0 +1,153 @@
+use nannou_audio::file::{AudioFile, FilePlayer, FileRecorder};
+use nannou_audio::hound;
+use nannou_audio::stream::offline::WavWriter;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+fn ramp(frames: usize, channels: usize) -> Vec<f32> {
+    (0..frames * channels).map(|i| i as f32 / 1000.0).collect()
+}
+
+fn render(player: &mut FilePlayer, frames: usize, channels: usize, sample_rate: u32) -> Vec<f32> {
+    let mut output = vec![0.0; frames * channels];
+    player.render_interleaved(&mut output, channels, sample_rate);
+    output
+}
+
+#[test]
+fn open_wav() {
+    let path = std::env::temp_dir().join("nannou_audio_file_tests_open.wav");
+    let samples = ramp(100, 2);
+    let mut writer = WavWriter::create(&path, 2, 22_050).unwrap();
+    writer.write(&samples).unwrap();
+    writer.finalize().unwrap();
+
+    let file = AudioFile::open(&path).unwrap();
+    assert_eq!(file.channels(), 2);
+    assert_eq!(file.sample_rate(), 22_050);
+    assert_eq!(file.len_frames(), 100);
+    assert_eq!(file.samples(), &samples[..]);
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn play_at_file_rate() {
+    let file = AudioFile::from_samples(ramp(10, 2), 2, 44_100);
+    let mut player = FilePlayer::new(file.clone()).gain(0.5);
+    let output = render(&mut player, 16, 2, 44_100);
+    for (out, sample) in output.iter().zip(file.samples()) {
+        assert!((out - sample * 0.5).abs() < 1e-6);
+    }
+    assert!(output[20..].iter().all(|&s| s == 0.0));
+    assert!(!player.is_playing());
+    assert!(player.is_finished());
+
+    // Playing again restarts from the beginning.
+    player.play();
+    let output = render(&mut player, 1, 2, 44_100);
+    assert_eq!(output, vec![0.0, 0.0005]);
+}
+
+#[test]
+fn render_mixes_into_buffer() {
+    let file = AudioFile::from_samples(vec![0.25; 8], 1, 44_100);
+    let mut player = FilePlayer::new(file);
+    let mut output = vec![0.5; 8];
+    player.render_interleaved(&mut output, 1, 44_100);
+    assert!(output.iter().all(|&s| s == 0.75));
+}
+
+#[test]
+fn looping_and_seeking() {
+    let file = AudioFile::from_samples(ramp(4, 1), 1, 1_000);
+    let mut player = FilePlayer::new(file).looping(true);
+    let output = render(&mut player, 10, 1, 1_000);
+    let expected = [
+        0.0, 0.001, 0.002, 0.003, 0.0, 0.001, 0.002, 0.003, 0.0, 0.001,
+    ];
+    assert_eq!(output, expected);
+    assert!(player.is_playing());
+
+    player.seek(Duration::from_millis(3));
+    assert_eq!(player.position(), Duration::from_millis(3));
+    let output = render(&mut player, 2, 1, 1_000);
+    assert_eq!(output, vec![0.003, 0.0]);
+
+    player.pause();
+    assert_eq!(render(&mut player, 2, 1, 1_000), vec![0.0, 0.0]);
+}
+
+#[test]
+fn resample_to_stream_rate() {
+    let hz = 441.0;
+    let file_rate = 22_050;
+    let samples = (0..file_rate as usize / 10)
+        .map(|i| (2.0 * PI * hz * i as f32 / file_rate as f32).sin())
+        .collect();
+    let mut player = FilePlayer::new(AudioFile::from_samples(samples, 1, file_rate));
+    let output = render(&mut player, 4_400, 1, 44_100);
+    // Skip the first frames, which are interpolated with the silence preceding the file.
+    for (i, &s) in output.iter().enumerate().skip(4) {
+        let expected = (2.0 * PI * hz * i as f32 / 44_100.0).sin();
+        assert!((s - expected).abs() < 1e-3, "{}: {} != {}", i, s, expected);
+    }
+}
+
+#[test]
+fn channel_mapping() {
+    // Mono is repeated across stereo.
+    let mono = AudioFile::from_samples(vec![0.5; 2], 1, 44_100);
+    let output = render(&mut FilePlayer::new(mono), 2, 2, 44_100);
+    assert_eq!(output, vec![0.5; 4]);
+
+    // Stereo is mixed down to mono.
+    let stereo = AudioFile::from_samples(vec![0.2, 0.4, 0.2, 0.4], 2, 44_100);
+    let output = render(&mut FilePlayer::new(stereo), 2, 1, 44_100);
+    for s in output {
+        assert!((s - 0.3).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn gain_changes_are_ramped() {
+    let file = AudioFile::from_samples(vec![1.0; 8], 1, 44_100);
+    let mut player = FilePlayer::new(file);
+    player.set_gain(0.0);
+    let output = render(&mut player, 4, 1, 44_100);
+    assert_eq!(output, vec![0.75, 0.5, 0.25, 0.0]);
+    assert_eq!(render(&mut player, 4, 1, 44_100), vec![0.0; 4]);
+}
+
+#[test]
+fn record_wav() {
+    let path = std::env::temp_dir().join("nannou_audio_file_tests_record.wav");
+    let (mut recorder, handle) = FileRecorder::create(&path, 2, 48_000).unwrap();
+    let samples = ramp(1_000, 2);
+    for chunk in samples.chunks(64 * 2) {
+        recorder.record_interleaved(chunk);
+    }
+    handle.finish().unwrap();
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, 48_000);
+    let recorded: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+    assert_eq!(recorded, samples);
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn record_drops_when_full() {
+    let path = std::env::temp_dir().join("nannou_audio_file_tests_dropped.wav");
+    let (mut recorder, handle) = FileRecorder::with_queue_frames(&path, 1, 48_000, 100).unwrap();
+    recorder.record_interleaved(&[0.0f32; 60]);
+    recorder.record_interleaved(&[0.0f32; 150]);
+    drop(recorder);
+    assert_eq!(handle.frames_dropped(), 150);
+    // Dropping the recorder stops the writing thread once the queue is drained.
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(handle.frames_written(), 60);
+    handle.finish().unwrap();
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,103 @@
+use nannou_audio::stream::offline;
+use nannou_audio::{hound, Buffer};
+use std::time::Duration;
+
+// Writes the index of each frame to every channel.
+struct Counter {
+    frame: usize,
+    buffer_lens: Vec<usize>,
+}
+
+fn count(counter: &mut Counter, buffer: &mut Buffer) {
+    counter.buffer_lens.push(buffer.len_frames());
+    for frame in buffer.frames_mut() {
+        for sample in frame {
+            *sample = counter.frame as f32;
+        }
+        counter.frame += 1;
+    }
+}
+
+fn counter() -> Counter {
+    Counter {
+        frame: 0,
+        buffer_lens: vec![],
+    }
+}
+
+#[test]
+fn render_frames_is_continuous() {
+    let mut stream = offline::Builder::new(counter())
+        .render(count)
+        .channels(2)
+        .frames_per_buffer(16)
+        .build();
+    let mut output = vec![];
+    for &frames in &[5, 40, 1, 0, 17] {
+        stream.render_frames(frames, &mut output);
+    }
+    assert_eq!(stream.frames_rendered(), 63);
+    assert_eq!(output.len(), 63 * 2);
+    for (i, frame) in output.chunks(2).enumerate() {
+        assert_eq!(frame, &[i as f32, i as f32]);
+    }
+    // The render function only ever sees buffers of `frames_per_buffer` frames.
+    assert!(stream.model().buffer_lens.iter().all(|&len| len == 16));
+}
+
+#[test]
+fn render_until_follows_clock() {
+    let mut stream = offline::Builder::new(counter())
+        .render(count)
+        .sample_rate(44_100)
+        .channels(1)
+        .build();
+    let mut output = vec![];
+    // Render in sync with 60 video frames at 60 fps.
+    for frame in 1..=60 {
+        let time = Duration::from_secs_f64(frame as f64 / 60.0);
+        stream.render_until(time, &mut output);
+        assert_eq!(stream.frames_rendered(), (frame * 44_100 + 30) as u64 / 60);
+    }
+    assert_eq!(output.len(), 44_100);
+    assert_eq!(stream.time(), Duration::from_secs(1));
+
+    // Rendering up until an earlier time has no effect.
+    stream.render_until(Duration::from_millis(500), &mut output);
+    assert_eq!(output.len(), 44_100);
+}
+
+#[test]
+fn render_vec_renders_duration() {
+    let mut stream = offline::Builder::new(counter())
+        .render(count)
+        .sample_rate(48_000)
+        .channels(2)
+        .build();
+    let samples = stream.render_vec(Duration::from_millis(250));
+    assert_eq!(samples.len(), 12_000 * 2);
+    assert_eq!(stream.time(), Duration::from_millis(250));
+}
+
+#[test]
+fn render_wav_writes_file() {
+    let path = std::env::temp_dir().join("nannou_audio_offline_tests.wav");
+    let mut stream = offline::Builder::new(counter())
+        .render(count)
+        .sample_rate(8_000)
+        .channels(2)
+        .build();
+    stream
+        .render_wav(&path, Duration::from_millis(100))
+        .unwrap();
+
+    let mut reader = hound::WavReader::open(&path).unwrap();
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, 8_000);
+    assert_eq!(reader.duration(), 800);
+    let samples: Vec<f32> = reader.samples().map(Result::unwrap).collect();
+    assert_eq!(samples[0..4], [0.0, 0.0, 1.0, 1.0]);
+    assert_eq!(samples[samples.len() - 1], 799.0);
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,102 @@
+use nannou_audio::param::{BoolParam, FloatParam, Smoothed};
+use nannou_audio::stream::offline;
+use nannou_audio::Buffer;
+
+struct Model {
+    gain: Smoothed,
+    mute: bool,
+}
+
+fn render(model: &mut Model, buffer: &mut Buffer) {
+    for frame in buffer.frames_mut() {
+        let gain = model.gain.next_value();
+        let amp = if model.mute { 0.0 } else { gain };
+        for sample in frame {
+            *sample = amp;
+        }
+    }
+}
+
+#[test]
+fn smoothed_ramp() {
+    let mut value = Smoothed::new(0.0).time(0.004);
+    assert_eq!(value.value(), 0.0);
+    assert!(!value.is_smoothing());
+
+    value.set_target(1.0, 1_000);
+    assert!(value.is_smoothing());
+    assert_eq!(value.target(), 1.0);
+    let ramp: Vec<f32> = (0..6).map(|_| value.next_value()).collect();
+    assert_eq!(ramp, vec![0.25, 0.5, 0.75, 1.0, 1.0, 1.0]);
+    assert!(!value.is_smoothing());
+
+    // Setting the same target does not restart the ramp.
+    value.set_target(0.0, 1_000);
+    value.next_value();
+    value.set_target(0.0, 1_000);
+    assert_eq!(value.next_value(), 0.5);
+
+    value.reset(2.0);
+    assert_eq!(value.value(), 2.0);
+    assert!(!value.is_smoothing());
+}
+
+#[test]
+fn smoothed_zero_time() {
+    let mut value = Smoothed::new(0.0).time(0.0);
+    value.set_target(1.0, 44_100);
+    assert_eq!(value.value(), 1.0);
+    assert!(!value.is_smoothing());
+    assert_eq!(value.next_value(), 1.0);
+}
+
+#[test]
+fn param_handles() {
+    let float = FloatParam::new(0.5);
+    let clone = float.clone();
+    clone.set(-3.25);
+    assert_eq!(float.get(), -3.25);
+
+    let boolean = BoolParam::new(false);
+    let clone = boolean.clone();
+    assert!(clone.toggle());
+    assert!(boolean.get());
+    boolean.set(false);
+    assert!(!clone.get());
+}
+
+#[test]
+fn params_drive_model() {
+    let gain = FloatParam::new(0.5);
+    let mute = BoolParam::new(false);
+    let model = Model {
+        gain: Smoothed::new(0.0).time(0.004),
+        mute: true,
+    };
+    let mut stream = offline::Builder::new(model)
+        .render(render)
+        .channels(1)
+        .sample_rate(1_000)
+        .frames_per_buffer(4)
+        .float_param(&gain, |model| &mut model.gain)
+        .bool_param(&mute, |model| &mut model.mute)
+        .build();
+
+    // The fields take the params' values immediately.
+    assert_eq!(stream.model().gain.value(), 0.5);
+    assert!(!stream.model().mute);
+    let mut output = vec![];
+    stream.render_frames(4, &mut output);
+    assert_eq!(output, vec![0.5; 4]);
+
+    // New values are applied before the next buffer, with floats ramped.
+    gain.set(1.5);
+    output.clear();
+    stream.render_frames(8, &mut output);
+    assert_eq!(output, vec![0.75, 1.0, 1.25, 1.5, 1.5, 1.5, 1.5, 1.5]);
+
+    mute.set(true);
+    output.clear();
+    stream.render_frames(4, &mut output);
+    assert_eq!(output, vec![0.0; 4]);
+}
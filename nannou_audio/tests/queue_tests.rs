@@ -0,0 +1,107 @@
+use nannou_audio::queue;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn fifo_with_wraparound() {
+    let (mut producer, mut consumer) = queue::bounded(3);
+    for i in 0..10 {
+        assert_eq!(producer.push(i), Ok(()));
+        assert_eq!(producer.push(i + 100), Ok(()));
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.pop(), Some(i));
+        assert_eq!(consumer.pop(), Some(i + 100));
+        assert!(consumer.is_empty());
+    }
+    assert_eq!(consumer.pop(), None);
+}
+
+#[test]
+fn push_when_full() {
+    let (mut producer, mut consumer) = queue::bounded(2);
+    assert_eq!(producer.capacity(), 2);
+    assert_eq!(producer.slots(), 2);
+    producer.push(1).unwrap();
+    producer.push(2).unwrap();
+    assert!(producer.is_full());
+    assert_eq!(producer.push(3), Err(3));
+    assert_eq!(consumer.pop(), Some(1));
+    assert_eq!(producer.slots(), 1);
+    assert_eq!(producer.push(3), Ok(()));
+    assert_eq!(consumer.collect::<Vec<_>>(), vec![2, 3]);
+}
+
+#[test]
+fn slices() {
+    let (mut producer, mut consumer) = queue::bounded(4);
+    assert_eq!(producer.push_slice(&[1, 2, 3, 4, 5, 6]), 4);
+    let mut items = [0; 3];
+    assert_eq!(consumer.pop_slice(&mut items), 3);
+    assert_eq!(items, [1, 2, 3]);
+    assert_eq!(producer.push_slice(&[5, 6]), 2);
+    let mut items = [0; 8];
+    assert_eq!(consumer.pop_slice(&mut items), 3);
+    assert_eq!(&items[..3], &[4, 5, 6]);
+}
+
+#[test]
+fn discard() {
+    let (mut producer, mut consumer) = queue::bounded(8);
+    assert_eq!(producer.push_iter(0..5), 5);
+    assert_eq!(consumer.discard(3), 3);
+    assert_eq!(consumer.pop(), Some(3));
+    assert_eq!(consumer.discard(3), 1);
+    assert!(consumer.is_empty());
+}
+
+#[test]
+fn remaining_items_are_dropped() {
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (mut producer, mut consumer) = queue::bounded(4);
+    for _ in 0..4 {
+        assert!(producer.push(Counted(drops.clone())).is_ok());
+    }
+    consumer.pop();
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    consumer.discard(1);
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+    drop(producer);
+    drop(consumer);
+    assert_eq!(drops.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn abandoned() {
+    let (producer, consumer) = queue::bounded::<()>(1);
+    assert!(!producer.is_abandoned());
+    drop(consumer);
+    assert!(producer.is_abandoned());
+}
+
+#[test]
+fn transfer_between_threads() {
+    const COUNT: usize = 10_000;
+    let (mut producer, mut consumer) = queue::bounded(64);
+    let thread = std::thread::spawn(move || {
+        let mut next = 0;
+        while next < COUNT {
+            next += producer.push_iter(next..COUNT);
+        }
+    });
+    let mut expected = 0;
+    while expected < COUNT {
+        if let Some(item) = consumer.pop() {
+            assert_eq!(item, expected);
+            expected += 1;
+        }
+    }
+    thread.join().unwrap();
+    assert!(consumer.is_empty());
+}
@@ -0,0 +1,425 @@
+//! Buffering and analysis of the audio fed to ISF `audio` and `audioFFT` inputs.
+//!
+//! Following the ISF spec, both kinds of input are presented to the shader as an image with one
+//! row per channel. Each column of an `audio` image is a sample, mapped from the range `-1.0..1.0`
+//! to `0.0..1.0` such that silence is `0.5`. Each column of an `audioFFT` image is the amplitude
+//! of a band of frequencies, from the lowest to the highest.
+
+use nannou_audio::analysis::{Complex, Fft, Window};
+use nannou_audio::queue::{self, Consumer, Producer};
+use std::collections::VecDeque;
+
+/// A handle for sending audio to an `IsfPipeline` from another thread.
+///
+/// Produced via `IsfPipeline::audio_sender`. This is useful for sending the buffers captured by an
+/// audio input stream, which are processed on the audio thread. Sent audio is received by the
+/// pipeline on its next call to `encode_update`.
+///
+/// Samples are pushed onto a bounded queue that is allocated along with the sender, so sending
+/// never blocks, locks or allocates. If the pipeline falls behind and the queue is full, the
+/// frames that do not fit are dropped.
+#[derive(Debug)]
+pub struct AudioSender {
+    producer: Producer<AudioItem>,
+    // The number of channels of the most recently sent frames.
+    channels: usize,
+}
+
+/// The receiving end of an `AudioSender`, owned by the pipeline.
+#[derive(Debug)]
+pub(crate) struct AudioReceiver {
+    consumer: Consumer<AudioItem>,
+    // The number of channels of the samples being received.
+    channels: usize,
+    // The samples of a frame that has only been partially received.
+    frame: Vec<f32>,
+}
+
+// An item within an audio queue.
+//
+// Changes to the number of channels are sent in-band so that they apply to exactly the samples
+// that follow.
+#[derive(Debug)]
+enum AudioItem {
+    Channels(usize),
+    Sample(f32),
+}
+
+/// The number of items in the queue of each `AudioSender`, enough for a few hundred milliseconds
+/// of stereo audio.
+pub(crate) const QUEUE_CAPACITY: usize = 32_768;
+
+/// Create a sender along with the receiver for the pipeline, allocating a queue with room for
+/// `capacity` samples.
+pub(crate) fn channel(capacity: usize) -> (AudioSender, AudioReceiver) {
+    let (producer, consumer) = queue::bounded(capacity);
+    let channels = 0;
+    let tx = AudioSender { producer, channels };
+    let frame = vec![];
+    let rx = AudioReceiver {
+        consumer,
+        channels,
+        frame,
+    };
+    (tx, rx)
+}
+
+impl AudioSender {
+    /// Send the given interleaved samples to the pipeline.
+    ///
+    /// Only whole frames are sent. Any frames that do not fit within the queue are dropped, as is
+    /// all audio sent after the pipeline has been dropped.
+    pub fn send<I>(&mut self, channels: usize, samples: I)
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        if channels == 0 {
+            return;
+        }
+        let channels_changed = channels != self.channels;
+        let slots = self
+            .producer
+            .slots()
+            .saturating_sub(channels_changed as usize);
+        let frames = slots / channels;
+        if frames == 0 {
+            return;
+        }
+        if channels_changed {
+            self.producer.push(AudioItem::Channels(channels)).ok();
+            self.channels = channels;
+        }
+        let samples = samples.into_iter().take(frames * channels);
+        self.producer.push_iter(samples.map(AudioItem::Sample));
+    }
+
+    /// Send the samples of the given `nannou_audio` buffer to the pipeline.
+    pub fn send_buffer(&mut self, buffer: &nannou_audio::Buffer) {
+        self.send(buffer.channels(), buffer.iter().cloned());
+    }
+}
+
+impl AudioReceiver {
+    /// Push all frames received so far onto the given history.
+    ///
+    /// The samples of a partially received frame are held back until the rest of the frame
+    /// arrives, so that the history only ever receives whole frames.
+    pub fn receive(&mut self, history: &mut AudioHistory) {
+        while let Some(item) = self.consumer.pop() {
+            match item {
+                AudioItem::Channels(channels) => {
+                    self.channels = channels;
+                    self.frame.clear();
+                }
+                AudioItem::Sample(sample) => {
+                    self.frame.push(sample);
+                    if self.frame.len() >= self.channels {
+                        history.push(self.channels, self.frame.drain(..));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether or not the sender has been dropped and all of its audio received.
+    pub fn is_finished(&self) -> bool {
+        self.consumer.is_abandoned() && self.consumer.is_empty()
+    }
+}
+
+/// The most recent interleaved audio frames pushed to an `IsfPipeline`.
+#[derive(Debug, Default)]
+pub(crate) struct AudioHistory {
+    channels: usize,
+    samples: VecDeque<f32>,
+    capacity_frames: usize,
+    // The FFT and buffers used by `spectrum`, reused for as long as the FFT size is unchanged.
+    analyser: Option<SpectrumAnalyser>,
+}
+
+// The state required to produce a spectrum from frames of `fft.size()` samples.
+#[derive(Debug)]
+struct SpectrumAnalyser {
+    fft: Fft,
+    window: Vec<f32>,
+    window_sum: f32,
+    frame: Vec<f32>,
+    data: Vec<Complex>,
+}
+
+impl AudioHistory {
+    /// The number of channels, or `1` if no audio has been pushed yet.
+    pub fn channels(&self) -> usize {
+        self.channels.max(1)
+    }
+
+    /// Set the maximum number of frames retained by the history.
+    pub fn set_capacity_frames(&mut self, frames: usize) {
+        self.capacity_frames = frames;
+        self.truncate();
+    }
+
+    /// Push the given interleaved samples onto the end of the history.
+    ///
+    /// If the number of channels differs from the previous push, the history is first cleared.
+    /// Any trailing samples that do not make up a whole frame are ignored.
+    pub fn push<I>(&mut self, channels: usize, samples: I)
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        if channels == 0 {
+            return;
+        }
+        if channels != self.channels {
+            self.channels = channels;
+            self.samples.clear();
+        }
+        self.samples.extend(samples);
+        let partial = self.samples.len() % channels;
+        self.samples.truncate(self.samples.len() - partial);
+        self.truncate();
+    }
+
+    // Drop the oldest frames that exceed the capacity.
+    fn truncate(&mut self) {
+        let frames = self.samples.len() / self.channels();
+        let excess = frames.saturating_sub(self.capacity_frames);
+        self.samples.drain(..excess * self.channels());
+    }
+
+    /// Write the latest `out.len()` samples of the given channel to `out`, oldest first.
+    ///
+    /// Any samples preceding the start of the history are written as silence.
+    fn channel(&self, channel: usize, out: &mut [f32]) {
+        let frames = self.samples.len() / self.channels();
+        let skip = frames.saturating_sub(out.len());
+        let pad = out.len().saturating_sub(frames);
+        let (silence, history) = out.split_at_mut(pad);
+        silence.iter_mut().for_each(|s| *s = 0.0);
+        let samples = self
+            .samples
+            .iter()
+            .skip(skip * self.channels() + channel)
+            .step_by(self.channels());
+        for (s, &sample) in history.iter_mut().zip(samples) {
+            *s = sample;
+        }
+    }
+
+    /// Write the waveform image data for an `audio` input with `n_samples` columns.
+    pub fn waveform(&self, n_samples: usize, out: &mut Vec<f32>) {
+        out.resize(n_samples * self.channels(), 0.0);
+        for (ch, row) in out.chunks_mut(n_samples.max(1)).enumerate() {
+            self.channel(ch, row);
+            for s in row {
+                *s = *s * 0.5 + 0.5;
+            }
+        }
+    }
+
+    /// Write the spectrum image data for an `audioFFT` input with `n_columns` columns.
+    pub fn spectrum(&mut self, n_columns: usize, out: &mut Vec<f32>) {
+        out.resize(n_columns * self.channels(), 0.0);
+        if n_columns == 0 {
+            return;
+        }
+        let n = fft_len(n_columns);
+        let bins = n / 2;
+        let mut analyser = match self.analyser.take() {
+            Some(analyser) if analyser.fft.size() == n => analyser,
+            _ => SpectrumAnalyser::new(n),
+        };
+        {
+            let SpectrumAnalyser {
+                ref fft,
+                ref window,
+                window_sum,
+                ref mut frame,
+                ref mut data,
+            } = analyser;
+            for (ch, row) in out.chunks_mut(n_columns).enumerate() {
+                self.channel(ch, frame);
+                for ((c, s), w) in data.iter_mut().zip(frame.iter()).zip(window) {
+                    *c = Complex::new(s * w, 0.0);
+                }
+                fft.forward(data);
+
+                // Average the amplitude of the bins covered by each column.
+                for (col, amp) in row.iter_mut().enumerate() {
+                    let start = col * bins / n_columns;
+                    let end = ((col + 1) * bins / n_columns).max(start + 1);
+                    let sum: f32 = data[start..end].iter().map(|c| c.norm()).sum();
+                    *amp = 2.0 * sum / ((end - start) as f32 * window_sum);
+                }
+            }
+        }
+        self.analyser = Some(analyser);
+    }
+}
+
+impl SpectrumAnalyser {
+    fn new(n: usize) -> Self {
+        let fft = Fft::new(n);
+        let mut window = vec![0.0; n];
+        Window::Hann.fill(&mut window);
+        let window_sum = window.iter().sum();
+        let frame = vec![0.0; n];
+        let data = vec![Complex::default(); n];
+        SpectrumAnalyser {
+            fft,
+            window,
+            window_sum,
+            frame,
+            data,
+        }
+    }
+}
+
+/// The number of frames required to produce a spectrum with the given number of columns.
+pub(crate) fn fft_len(n_columns: usize) -> usize {
+    (n_columns * 2).next_power_of_two()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{channel, fft_len, AudioHistory, AudioItem};
+    use std::f32::consts::PI;
+
+    fn assert_approx(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn fft_len_fits_columns() {
+        assert_eq!(fft_len(1), 2);
+        assert_eq!(fft_len(256), 512);
+        assert_eq!(fft_len(300), 1024);
+    }
+
+    #[test]
+    fn waveform_rows_per_channel() {
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(4);
+        // Three stereo frames, the first of which is padded with silence.
+        history.push(2, vec![1.0, -1.0, 0.5, -0.5, 0.0, 0.0]);
+        let mut out = vec![];
+        history.waveform(4, &mut out);
+        assert_eq!(out, vec![0.5, 1.0, 0.75, 0.5, 0.5, 0.0, 0.25, 0.5]);
+    }
+
+    #[test]
+    fn waveform_keeps_latest_frames() {
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(2);
+        history.push(1, vec![-1.0, 0.0, 1.0]);
+        let mut out = vec![];
+        history.waveform(2, &mut out);
+        assert_eq!(out, vec![0.5, 1.0]);
+        // Changing the number of channels clears the history.
+        history.push(2, vec![1.0, 1.0]);
+        history.waveform(2, &mut out);
+        assert_eq!(out, vec![0.5, 1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn history_keeps_whole_frames() {
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(2);
+        history.push(2, vec![1.0, 2.0, 3.0, 4.0]);
+        // A trailing partial frame must not shift the channels of the history.
+        history.push(2, vec![5.0]);
+        let mut out = vec![];
+        history.waveform(2, &mut out);
+        assert_eq!(out, vec![1.0, 2.0, 1.5, 2.5]);
+        // Truncation drops the oldest whole frames.
+        history.push(2, vec![-1.0, -1.0, -1.0, -1.0, -1.0]);
+        history.set_capacity_frames(1);
+        history.waveform(2, &mut out);
+        assert_eq!(out, vec![0.5, 0.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn receiver_holds_back_partial_frames() {
+        let (mut tx, mut rx) = channel(8);
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(2);
+        // Simulate the receiver catching up with the sender part way through a frame.
+        tx.producer.push(AudioItem::Channels(2)).unwrap();
+        for s in &[1.0, -1.0, 1.0] {
+            tx.producer.push(AudioItem::Sample(*s)).unwrap();
+        }
+        rx.receive(&mut history);
+        let mut out = vec![];
+        history.waveform(2, &mut out);
+        assert_eq!(out, vec![0.5, 1.0, 0.5, 0.0]);
+        tx.producer.push(AudioItem::Sample(-1.0)).unwrap();
+        rx.receive(&mut history);
+        history.waveform(2, &mut out);
+        assert_eq!(out, vec![1.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn spectrum_reuses_analyser_for_same_size() {
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(64);
+        history.push(1, vec![0.5; 64]);
+        let mut out = vec![];
+        history.spectrum(16, &mut out);
+        let data_ptr = history.analyser.as_ref().unwrap().data.as_ptr();
+        history.spectrum(16, &mut out);
+        assert_eq!(history.analyser.as_ref().unwrap().data.as_ptr(), data_ptr);
+        history.spectrum(32, &mut out);
+        assert_eq!(history.analyser.as_ref().unwrap().fft.size(), fft_len(32));
+    }
+
+    #[test]
+    fn spectrum_peaks_at_sine_column() {
+        let n_columns = 16;
+        let n = fft_len(n_columns);
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(n);
+        let bin = 8;
+        let sine = (0..n).map(|i| (2.0 * PI * bin as f32 * i as f32 / n as f32).sin());
+        history.push(1, sine);
+        let mut out = vec![];
+        history.spectrum(n_columns, &mut out);
+        assert_eq!(out.len(), n_columns);
+        let peak = (0..n_columns)
+            .max_by(|&a, &b| out[a].partial_cmp(&out[b]).unwrap())
+            .unwrap();
+        assert_eq!(peak, bin * n_columns / (n / 2));
+        assert_approx(out[peak], 1.0);
+    }
+
+    #[test]
+    fn sender_drops_frames_when_full() {
+        let (mut tx, mut rx) = channel(8);
+        let mut history = AudioHistory::default();
+        history.set_capacity_frames(8);
+        // The channel count and three stereo frames fit, the rest are dropped.
+        tx.send(2, (1..=10).map(|s| s as f32));
+        rx.receive(&mut history);
+        let mut out = vec![];
+        history.waveform(3, &mut out);
+        let expected: Vec<f32> = [1.0, 3.0, 5.0, 2.0, 4.0, 6.0]
+            .iter()
+            .map(|s| s * 0.5 + 0.5)
+            .collect();
+        assert_eq!(out, expected);
+        // Once received, there is room for more.
+        tx.send(2, vec![7.0, 8.0]);
+        rx.receive(&mut history);
+        history.waveform(1, &mut out);
+        assert_eq!(out, vec![4.0, 4.5]);
+    }
+
+    #[test]
+    fn receiver_finishes_after_sender_dropped() {
+        let (mut tx, mut rx) = channel(8);
+        tx.send(1, vec![0.0]);
+        drop(tx);
+        assert!(!rx.is_finished());
+        rx.receive(&mut AudioHistory::default());
+        assert!(rx.is_finished());
+    }
+}
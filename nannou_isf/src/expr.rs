@@ -0,0 +1,397 @@
+//! A small evaluator for the arithmetic expressions used by the `WIDTH` and `HEIGHT` of ISF
+//! `PASSES`, e.g. `"$WIDTH / 2.0"` or `"floor($HEIGHT / $blurAmount)"`.
+//!
+//! Expressions may contain numbers, variables prefixed with `$`, the `+`, `-`, `*` and `/`
+//! operators, parentheses and the functions `abs`, `ceil`, `floor`, `round`, `sqrt`, `min`, `max`
+//! and `pow`.
+//!
+//! The comparison operators `<`, `<=`, `>`, `>=`, `==` and `!=` along with the logical operators
+//! `&&` (or `and`), `||` (or `or`) and `!` (or `not`) are also supported. These produce `1.0` for
+//! true and `0.0` for false, while any non-zero operand is considered true.
+
+use std::iter::Peekable;
+use std::str::Chars;
+use thiserror::Error;
+
+/// Errors that might occur while evaluating an expression.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum EvalError {
+    #[error("unexpected character `{0}`")]
+    UnexpectedChar(char),
+    #[error("unexpected end of expression")]
+    UnexpectedEnd,
+    #[error("unknown variable `${0}`")]
+    UnknownVariable(String),
+    #[error("unknown function `{0}`")]
+    UnknownFunction(String),
+    #[error("function `{name}` expects {expected} argument(s)")]
+    ArgumentCount { name: String, expected: usize },
+}
+
+struct Parser<'a, F> {
+    chars: Peekable<Chars<'a>>,
+    var: F,
+}
+
+/// Evaluate the given expression.
+///
+/// The `var` function is used to look up the value of each variable by its name, excluding the
+/// `$` prefix.
+pub fn eval<F>(expr: &str, var: F) -> Result<f32, EvalError>
+where
+    F: Fn(&str) -> Option<f32>,
+{
+    let chars = expr.chars().peekable();
+    let mut parser = Parser { chars, var };
+    let value = parser.expr()?;
+    match parser.next_non_whitespace() {
+        None => Ok(value),
+        Some(c) => Err(EvalError::UnexpectedChar(c)),
+    }
+}
+
+impl<'a, F> Parser<'a, F>
+where
+    F: Fn(&str) -> Option<f32>,
+{
+    // expr := and (('||' | 'or') and)*
+    fn expr(&mut self) -> Result<f32, EvalError> {
+        let mut value = self.and()?;
+        while self.eat("||") || self.eat_keyword("or") {
+            let rhs = self.and()?;
+            value = truth(is_true(value) || is_true(rhs));
+        }
+        Ok(value)
+    }
+
+    // and := comparison (('&&' | 'and') comparison)*
+    fn and(&mut self) -> Result<f32, EvalError> {
+        let mut value = self.comparison()?;
+        while self.eat("&&") || self.eat_keyword("and") {
+            let rhs = self.comparison()?;
+            value = truth(is_true(value) && is_true(rhs));
+        }
+        Ok(value)
+    }
+
+    // comparison := sum (('<' | '<=' | '>' | '>=' | '==' | '!=') sum)*
+    fn comparison(&mut self) -> Result<f32, EvalError> {
+        let mut value = self.sum()?;
+        loop {
+            // Two-character operators must be checked first.
+            value = if self.eat("<=") {
+                truth(value <= self.sum()?)
+            } else if self.eat(">=") {
+                truth(value >= self.sum()?)
+            } else if self.eat("==") {
+                truth(value == self.sum()?)
+            } else if self.eat("!=") {
+                truth(value != self.sum()?)
+            } else if self.eat("<") {
+                truth(value < self.sum()?)
+            } else if self.eat(">") {
+                truth(value > self.sum()?)
+            } else {
+                return Ok(value);
+            };
+        }
+    }
+
+    // sum := term (('+' | '-') term)*
+    fn sum(&mut self) -> Result<f32, EvalError> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek_non_whitespace() {
+                Some('+') => {
+                    self.chars.next();
+                    value += self.term()?;
+                }
+                Some('-') => {
+                    self.chars.next();
+                    value -= self.term()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    // term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<f32, EvalError> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek_non_whitespace() {
+                Some('*') => {
+                    self.chars.next();
+                    value *= self.unary()?;
+                }
+                Some('/') => {
+                    self.chars.next();
+                    value /= self.unary()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    // unary := ('-' | '+' | '!' | 'not') unary | primary
+    fn unary(&mut self) -> Result<f32, EvalError> {
+        if self.eat_keyword("not") || (!self.peek_str("!=") && self.eat("!")) {
+            return Ok(truth(!is_true(self.unary()?)));
+        }
+        match self.peek_non_whitespace() {
+            Some('-') => {
+                self.chars.next();
+                Ok(-self.unary()?)
+            }
+            Some('+') => {
+                self.chars.next();
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    // primary := number | '$' ident | ident '(' args ')' | '(' expr ')'
+    fn primary(&mut self) -> Result<f32, EvalError> {
+        match self.peek_non_whitespace() {
+            None => Err(EvalError::UnexpectedEnd),
+            Some('(') => {
+                self.chars.next();
+                let value = self.expr()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some('$') => {
+                self.chars.next();
+                let name = self.ident();
+                (self.var)(&name).ok_or(EvalError::UnknownVariable(name))
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name = self.ident();
+                self.expect('(')?;
+                let mut args = vec![self.expr()?];
+                while let Some(',') = self.peek_non_whitespace() {
+                    self.chars.next();
+                    args.push(self.expr()?);
+                }
+                self.expect(')')?;
+                call(&name, &args)
+            }
+            Some(c) => Err(EvalError::UnexpectedChar(c)),
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, EvalError> {
+        let mut s = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() && c != '.' {
+                break;
+            }
+            s.push(c);
+            self.chars.next();
+        }
+        s.parse()
+            .map_err(|_| EvalError::UnexpectedChar(s.chars().last().unwrap_or('.')))
+    }
+
+    fn ident(&mut self) -> String {
+        let mut s = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_alphanumeric() && c != '_' {
+                break;
+            }
+            s.push(c);
+            self.chars.next();
+        }
+        s
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), EvalError> {
+        match self.next_non_whitespace() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(EvalError::UnexpectedChar(c)),
+            None => Err(EvalError::UnexpectedEnd),
+        }
+    }
+
+    // Consume the given operator if it is next.
+    fn eat(&mut self, op: &str) -> bool {
+        if !self.peek_str(op) {
+            return false;
+        }
+        for _ in op.chars() {
+            self.chars.next();
+        }
+        true
+    }
+
+    // Consume the given keyword if it is the next identifier.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.peek_non_whitespace();
+        let mut chars = self.chars.clone();
+        let ident_len = chars
+            .by_ref()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .count();
+        if ident_len != keyword.chars().count() || !self.peek_str(keyword) {
+            return false;
+        }
+        self.eat(keyword)
+    }
+
+    // Whether or not the given string follows, ignoring leading whitespace.
+    fn peek_str(&mut self, s: &str) -> bool {
+        self.peek_non_whitespace();
+        let mut chars = self.chars.clone();
+        s.chars().all(|c| chars.next() == Some(c))
+    }
+
+    fn peek_non_whitespace(&mut self) -> Option<char> {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                return Some(c);
+            }
+            self.chars.next();
+        }
+        None
+    }
+
+    fn next_non_whitespace(&mut self) -> Option<char> {
+        self.peek_non_whitespace();
+        self.chars.next()
+    }
+}
+
+// Convert a boolean to the value produced by comparison and logical operators.
+fn truth(b: bool) -> f32 {
+    match b {
+        true => 1.0,
+        false => 0.0,
+    }
+}
+
+// Whether or not the value is considered true by logical operators.
+fn is_true(value: f32) -> bool {
+    value != 0.0
+}
+
+// Call the function with the given name.
+fn call(name: &str, args: &[f32]) -> Result<f32, EvalError> {
+    let expect = |expected: usize| match args.len() == expected {
+        true => Ok(()),
+        false => Err(EvalError::ArgumentCount {
+            name: name.to_string(),
+            expected,
+        }),
+    };
+    let value = match name {
+        "abs" => expect(1).map(|_| args[0].abs())?,
+        "ceil" => expect(1).map(|_| args[0].ceil())?,
+        "floor" => expect(1).map(|_| args[0].floor())?,
+        "round" => expect(1).map(|_| args[0].round())?,
+        "sqrt" => expect(1).map(|_| args[0].sqrt())?,
+        "min" => expect(2).map(|_| args[0].min(args[1]))?,
+        "max" => expect(2).map(|_| args[0].max(args[1]))?,
+        "pow" => expect(2).map(|_| args[0].powf(args[1]))?,
+        _ => return Err(EvalError::UnknownFunction(name.to_string())),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eval, EvalError};
+
+    fn eval_vars(expr: &str) -> Result<f32, EvalError> {
+        eval(expr, |name| match name {
+            "WIDTH" => Some(640.0),
+            "HEIGHT" => Some(480.0),
+            "blurAmount" => Some(4.0),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval_vars("1 + 2"), Ok(3.0));
+        assert_eq!(eval_vars("$WIDTH / 2.0"), Ok(320.0));
+        assert_eq!(eval_vars("floor($HEIGHT / $blurAmount)"), Ok(120.0));
+        assert_eq!(eval_vars("max(1, pow(2, 3))"), Ok(8.0));
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval_vars("2 + 3 * 4"), Ok(14.0));
+        assert_eq!(eval_vars("(2 + 3) * 4"), Ok(20.0));
+        assert_eq!(eval_vars("8 - 4 - 2"), Ok(2.0));
+        assert_eq!(eval_vars("8 / 4 / 2"), Ok(1.0));
+        assert_eq!(eval_vars("1 + 2 < 4"), Ok(1.0));
+        assert_eq!(eval_vars("1 < 2 && 3 < 2"), Ok(0.0));
+        assert_eq!(eval_vars("1 || 0 && 0"), Ok(1.0));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval_vars("-2"), Ok(-2.0));
+        assert_eq!(eval_vars("--2"), Ok(2.0));
+        assert_eq!(eval_vars("-2 * 3"), Ok(-6.0));
+        assert_eq!(eval_vars("4 - -2"), Ok(6.0));
+        assert_eq!(eval_vars("-$WIDTH"), Ok(-640.0));
+        assert_eq!(eval_vars("+3"), Ok(3.0));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(eval_vars("$WIDTH > $HEIGHT"), Ok(1.0));
+        assert_eq!(eval_vars("$WIDTH < $HEIGHT"), Ok(0.0));
+        assert_eq!(eval_vars("2 <= 2"), Ok(1.0));
+        assert_eq!(eval_vars("2 >= 3"), Ok(0.0));
+        assert_eq!(eval_vars("2 == 2.0"), Ok(1.0));
+        assert_eq!(eval_vars("2 != 2"), Ok(0.0));
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(eval_vars("1 && 2"), Ok(1.0));
+        assert_eq!(eval_vars("1 and 0"), Ok(0.0));
+        assert_eq!(eval_vars("0 || 0"), Ok(0.0));
+        assert_eq!(eval_vars("0 or 3"), Ok(1.0));
+        assert_eq!(eval_vars("!0"), Ok(1.0));
+        assert_eq!(eval_vars("not 2"), Ok(0.0));
+        assert_eq!(eval_vars("!(1 && 0)"), Ok(1.0));
+        // Logical results may be used arithmetically to select a value.
+        assert_eq!(eval_vars("($WIDTH > 100) * 2 + 1"), Ok(3.0));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(eval_vars(""), Err(EvalError::UnexpectedEnd));
+        assert_eq!(eval_vars("1 +"), Err(EvalError::UnexpectedEnd));
+        assert_eq!(eval_vars("(1 + 2"), Err(EvalError::UnexpectedEnd));
+        assert_eq!(eval_vars("1 + 2)"), Err(EvalError::UnexpectedChar(')')));
+        assert_eq!(eval_vars("1 2"), Err(EvalError::UnexpectedChar('2')));
+        assert_eq!(eval_vars("1 # 2"), Err(EvalError::UnexpectedChar('#')));
+        assert!(eval_vars("1.2.3").is_err());
+        assert!(eval_vars(".").is_err());
+        assert!(eval_vars("1 <").is_err());
+        assert!(eval_vars("&& 1").is_err());
+        assert_eq!(
+            eval_vars("$DEPTH"),
+            Err(EvalError::UnknownVariable("DEPTH".into()))
+        );
+        assert_eq!(eval_vars("$"), Err(EvalError::UnknownVariable("".into())));
+        assert_eq!(
+            eval_vars("sin(1)"),
+            Err(EvalError::UnknownFunction("sin".into()))
+        );
+        assert_eq!(
+            eval_vars("min(1)"),
+            Err(EvalError::ArgumentCount {
+                name: "min".into(),
+                expected: 2,
+            })
+        );
+        assert_eq!(eval_vars("floor 2"), Err(EvalError::UnexpectedChar('2')));
+    }
+}
@@ -1,8 +1,13 @@
 //! A crate aimed at making it easy to set up an ISF hot-loading environment with nannou.
 
-pub use crate::pipeline::{IsfPipeline, IsfTime};
+pub use crate::audio::AudioSender;
+pub use crate::pipeline::{
+    IsfInputValue, IsfPipeline, IsfTime, PassData, PassTarget, SetInputError,
+};
 use std::path::Path;
 
+mod audio;
+mod expr;
 mod pipeline;
 
 /// Read the ISF from the shader file at the given path.
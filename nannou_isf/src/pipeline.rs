@@ -1,3 +1,4 @@
+use crate::audio::{self, AudioHistory, AudioReceiver, AudioSender};
 use nannou::image;
 use nannou::prelude::*;
 use nannou::wgpu::BufferInitDescriptor;
@@ -19,6 +20,7 @@ pub struct IsfPipeline {
     sampler_filtering: bool,
     isf_uniform_buffer: wgpu::Buffer,
     isf_inputs_uniform_buffer: wgpu::Buffer,
+    isf_input_uniforms: IsfInputUniforms,
     isf_bind_group_layout: wgpu::BindGroupLayout,
     isf_inputs_bind_group_layout: wgpu::BindGroupLayout,
     isf_textures_bind_group_layout: wgpu::BindGroupLayout,
@@ -26,23 +28,40 @@ pub struct IsfPipeline {
     isf_inputs_bind_group: wgpu::BindGroup,
     isf_textures_bind_group: wgpu::BindGroup,
     layout: wgpu::PipelineLayout,
-    render_pipeline: Option<wgpu::RenderPipeline>,
+    render_pipelines: Option<RenderPipelines>,
     vertex_buffer: wgpu::Buffer,
     dst_format: wgpu::TextureFormat,
     dst_texture_size: [u32; 2],
     dst_sample_count: u32,
+    // The receiving end of each `AudioSender`.
+    audio_receivers: Vec<AudioReceiver>,
+}
+
+/// The render pipelines for each kind of render target.
+///
+/// Pass targets are never multisampled and may differ in format from the output.
+#[derive(Debug)]
+struct RenderPipelines {
+    output: wgpu::RenderPipeline,
+    pass: wgpu::RenderPipeline,
+    float_pass: wgpu::RenderPipeline,
 }
 
 /// The first set of ISF uniforms that are available to every ISF shader.
+///
+/// Padded to match the `std140` layout of the `IsfData` uniform block.
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
 struct IsfUniforms {
     pass_index: i32,
+    _pad0: u32,
     render_size: [f32; 2],
     time: f32,
     time_delta: f32,
+    _pad1: [u32; 2],
     date: [f32; 4],
     frame_index: i32,
+    _pad2: [u32; 3],
 }
 
 /// Timing information passed into the shader.
@@ -65,7 +84,32 @@ pub struct IsfTime {
 pub struct IsfData {
     imported: HashMap<ImportName, ImageState>,
     inputs: HashMap<InputName, IsfInputData>,
-    passes: Vec<wgpu::Texture>,
+    input_names: Vec<InputName>,
+    passes: Vec<PassData>,
+    audio: AudioHistory,
+}
+
+/// The state associated with each of the ISF `PASSES`.
+#[derive(Debug)]
+pub struct PassData {
+    /// The buffer rendered to by the pass, or `None` if the pass renders to the output.
+    pub target: Option<PassTarget>,
+}
+
+/// The buffer to which a pass with a `TARGET` renders.
+///
+/// A pass may sample the previous contents of its own target. To allow for this, each pass renders
+/// to an intermediary texture, the contents of which are then copied to the sampled `texture`.
+#[derive(Debug)]
+pub struct PassTarget {
+    /// The texture sampled via the target's name. Contains the result of the latest render.
+    pub texture: wgpu::Texture,
+    // The texture rendered to by the pass.
+    render_texture: wgpu::Texture,
+    /// Whether or not the contents of the buffer are preserved between frames.
+    ///
+    /// Buffers that are not persistent are cleared at the beginning of each frame.
+    pub persistent: bool,
 }
 
 /// The state of the image.
@@ -97,18 +141,34 @@ pub enum IsfInputData {
     Point2d(Point2),
     Color(LinSrgba),
     Image(ImageState),
+    /// The waveform of the latest audio, with a row of samples for each channel.
     Audio {
         samples: Vec<f32>,
+        channels: usize,
         texture: wgpu::Texture,
     },
+    /// The spectrum of the latest audio, with a row of columns for each channel.
     AudioFft {
         columns: Vec<f32>,
+        channels: usize,
         texture: wgpu::Texture,
     },
 }
 
 type IsfInputUniforms = [u32; 128];
 
+/// A value that may be assigned to one of the non-texture ISF inputs via `IsfPipeline::set_input`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IsfInputValue {
+    /// Triggers an `event` input for the next frame.
+    Event,
+    Bool(bool),
+    Long(i32),
+    Float(f32),
+    Point2d(Point2),
+    Color(LinSrgba),
+}
+
 // #[derive(Clone, Debug)]
 // struct IsfInputUniforms {
 //     // Each supported uniform field type is 32-bit long, so store them as such.
@@ -170,6 +230,20 @@ pub enum ImageLoadError {
     },
 }
 
+/// Errors that might occur while setting the value of an ISF input.
+#[derive(Debug, Error, PartialEq)]
+pub enum SetInputError {
+    #[error("the ISF declares no input named `{name}`")]
+    UnknownInput { name: String },
+    #[error("input `{name}` expects a value of type `{expected}`")]
+    TypeMismatch {
+        name: String,
+        expected: &'static str,
+    },
+    #[error("{value} is not one of the `VALUES` of input `{name}`")]
+    InvalidValue { name: String, value: i32 },
+}
+
 /// Errors that might occur while loading a shader.
 #[derive(Debug, Error)]
 pub enum ShaderError {
@@ -273,10 +347,131 @@ impl IsfData {
         &self.inputs
     }
 
-    /// The texture stored for each pass.
-    pub fn passes(&self) -> &[wgpu::Texture] {
+    /// The data stored for each pass.
+    pub fn passes(&self) -> &[PassData] {
         &self.passes
     }
+
+    // The value of the `$`-prefixed variable with the given name within a pass size expression.
+    fn size_expr_var(&self, name: &str, [w, h]: [u32; 2]) -> Option<f32> {
+        match name {
+            "WIDTH" => Some(w as f32),
+            "HEIGHT" => Some(h as f32),
+            _ => match *self.inputs.get(name)? {
+                IsfInputData::Event { happening } | IsfInputData::Bool(happening) => {
+                    Some(if happening { 1.0 } else { 0.0 })
+                }
+                IsfInputData::Long(n) => Some(n as f32),
+                IsfInputData::Float(f) => Some(f),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl PassTarget {
+    /// The size of the target in pixels.
+    pub fn size(&self) -> [u32; 2] {
+        self.texture.size()
+    }
+
+    /// Whether or not the target uses a floating point texture format.
+    pub fn is_float(&self) -> bool {
+        self.texture.format() == IsfPipeline::FLOAT_PASS_TEXTURE_FORMAT
+    }
+}
+
+impl RenderPipelines {
+    // The pipeline for rendering to the given pass target.
+    fn pass(&self, target: &PassTarget) -> &wgpu::RenderPipeline {
+        match target.is_float() {
+            true => &self.float_pass,
+            false => &self.pass,
+        }
+    }
+}
+
+impl IsfInputValue {
+    /// Validate the value against the given input type, producing the data for the input.
+    ///
+    /// `long` values must be one of the input's `VALUES` if any are declared. `float`, `long`,
+    /// `point2D` and `color` values are clamped to the input's `MIN` and `MAX` where specified.
+    fn validate(self, input: &isf::Input) -> Result<IsfInputData, SetInputError> {
+        let data = match (self, &input.ty) {
+            (IsfInputValue::Event, isf::InputType::Event) => {
+                IsfInputData::Event { happening: true }
+            }
+            (IsfInputValue::Bool(b), isf::InputType::Bool(_)) => IsfInputData::Bool(b),
+            (IsfInputValue::Long(n), isf::InputType::Long(ty)) => {
+                if !ty.values.is_empty() && !ty.values.contains(&n) {
+                    let name = input.name.clone();
+                    return Err(SetInputError::InvalidValue { name, value: n });
+                }
+                let n = ty.min.map(|min| n.max(min)).unwrap_or(n);
+                let n = ty.max.map(|max| n.min(max)).unwrap_or(n);
+                IsfInputData::Long(n)
+            }
+            (IsfInputValue::Float(f), isf::InputType::Float(ty)) => {
+                let f = ty.min.map(|min| f.max(min)).unwrap_or(f);
+                let f = ty.max.map(|max| f.min(max)).unwrap_or(f);
+                IsfInputData::Float(f)
+            }
+            (IsfInputValue::Point2d(p), isf::InputType::Point2d(ty)) => {
+                let p = ty.min.map(|[x, y]| p.max(pt2(x, y))).unwrap_or(p);
+                let p = ty.max.map(|[x, y]| p.min(pt2(x, y))).unwrap_or(p);
+                IsfInputData::Point2d(p)
+            }
+            (IsfInputValue::Color(c), isf::InputType::Color(ty)) => {
+                let mut channels = [c.red, c.green, c.blue, c.alpha];
+                for (i, channel) in channels.iter_mut().enumerate() {
+                    if let Some(&min) = ty.min.as_ref().and_then(|min| min.get(i)) {
+                        *channel = channel.max(min);
+                    }
+                    if let Some(&max) = ty.max.as_ref().and_then(|max| max.get(i)) {
+                        *channel = channel.min(max);
+                    }
+                }
+                let [r, g, b, a] = channels;
+                IsfInputData::Color(lin_srgba(r, g, b, a))
+            }
+            (_, ty) => {
+                let name = input.name.clone();
+                let expected = input_type_name(ty);
+                return Err(SetInputError::TypeMismatch { name, expected });
+            }
+        };
+        Ok(data)
+    }
+}
+
+impl From<bool> for IsfInputValue {
+    fn from(b: bool) -> Self {
+        IsfInputValue::Bool(b)
+    }
+}
+
+impl From<i32> for IsfInputValue {
+    fn from(n: i32) -> Self {
+        IsfInputValue::Long(n)
+    }
+}
+
+impl From<f32> for IsfInputValue {
+    fn from(f: f32) -> Self {
+        IsfInputValue::Float(f)
+    }
+}
+
+impl From<Point2> for IsfInputValue {
+    fn from(p: Point2) -> Self {
+        IsfInputValue::Point2d(p)
+    }
+}
+
+impl From<LinSrgba> for IsfInputValue {
+    fn from(c: LinSrgba) -> Self {
+        IsfInputValue::Color(c)
+    }
 }
 
 impl IsfInputData {
@@ -328,21 +523,27 @@ impl IsfInputData {
                 let n_samples = a
                     .num_samples
                     .unwrap_or(IsfPipeline::DEFAULT_AUDIO_SAMPLE_COUNT);
-                let samples = vec![0.0; n_samples as usize];
-                let size = [n_samples, 1];
-                let format = IsfPipeline::DEFAULT_AUDIO_TEXTURE_FORMAT;
-                let texture = create_black_texture(device, encoder, size, format);
-                IsfInputData::Audio { samples, texture }
+                let samples = vec![0.5; n_samples as usize];
+                let channels = 1;
+                let texture = create_audio_texture(device, encoder, &samples, channels);
+                IsfInputData::Audio {
+                    samples,
+                    channels,
+                    texture,
+                }
             }
             isf::InputType::AudioFft(a) => {
                 let n_columns = a
                     .num_columns
                     .unwrap_or(IsfPipeline::DEFAULT_AUDIO_FFT_COLUMNS);
                 let columns = vec![0.0; n_columns as usize];
-                let size = [n_columns, 1];
-                let format = IsfPipeline::DEFAULT_AUDIO_TEXTURE_FORMAT;
-                let texture = create_black_texture(device, encoder, size, format);
-                IsfInputData::AudioFft { columns, texture }
+                let channels = 1;
+                let texture = create_audio_texture(device, encoder, &columns, channels);
+                IsfInputData::AudioFft {
+                    columns,
+                    channels,
+                    texture,
+                }
             }
         }
     }
@@ -367,14 +568,19 @@ impl IsfInputData {
     }
 
     /// Update an existing instance ISF input data instance with the given input.
+    ///
+    /// Audio inputs are updated with the latest audio from the given history.
+    ///
+    /// Returns whether or not the texture associated with the input was replaced.
     fn update(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
         image_loader: &ImageLoader,
         images_path: &Path,
+        audio: &mut AudioHistory,
         input: &isf::Input,
-    ) {
+    ) -> bool {
         match (self, &input.ty) {
             (IsfInputData::Event { .. }, isf::InputType::Event) => (),
             (IsfInputData::Bool(_), isf::InputType::Bool(_)) => (),
@@ -387,9 +593,57 @@ impl IsfInputData {
                     state.update(device, encoder, image_loader, img_path);
                 }
             }
-            (IsfInputData::Audio { .. }, isf::InputType::Audio(_)) => {}
-            (IsfInputData::AudioFft { .. }, isf::InputType::AudioFft(_)) => {}
-            (data, _) => *data = Self::new(device, encoder, image_loader, images_path, input),
+            (
+                IsfInputData::Audio {
+                    samples,
+                    channels,
+                    texture,
+                },
+                isf::InputType::Audio(a),
+            ) => {
+                let n_samples = a
+                    .num_samples
+                    .unwrap_or(IsfPipeline::DEFAULT_AUDIO_SAMPLE_COUNT);
+                audio.waveform(n_samples as usize, samples);
+                *channels = audio.channels();
+                return update_audio_texture(device, encoder, texture, samples, *channels);
+            }
+            (
+                IsfInputData::AudioFft {
+                    columns,
+                    channels,
+                    texture,
+                },
+                isf::InputType::AudioFft(a),
+            ) => {
+                let n_columns = a
+                    .num_columns
+                    .unwrap_or(IsfPipeline::DEFAULT_AUDIO_FFT_COLUMNS);
+                audio.spectrum(n_columns as usize, columns);
+                *channels = audio.channels();
+                return update_audio_texture(device, encoder, texture, columns, *channels);
+            }
+            (data, _) => {
+                *data = Self::new(device, encoder, image_loader, images_path, input);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl IsfUniforms {
+    fn new(pass_index: i32, [w, h]: [u32; 2], isf_time: IsfTime) -> Self {
+        IsfUniforms {
+            pass_index,
+            _pad0: 0,
+            render_size: [w as f32, h as f32],
+            time: isf_time.time,
+            time_delta: isf_time.time_delta,
+            _pad1: [0; 2],
+            date: isf_time.date,
+            frame_index: isf_time.frame_index,
+            _pad2: [0; 3],
         }
     }
 }
@@ -477,7 +731,11 @@ impl IsfPipeline {
         wgpu::TextureFormat::Rgba8UnormSrgb;
     pub const DEFAULT_AUDIO_SAMPLE_COUNT: u32 = 64;
     pub const DEFAULT_AUDIO_FFT_COLUMNS: u32 = 64;
-    pub const DEFAULT_AUDIO_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+    pub const DEFAULT_AUDIO_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R16Float;
+    /// The texture format used for pass targets.
+    pub const PASS_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+    /// The texture format used for pass targets that specify `FLOAT`.
+    pub const FLOAT_PASS_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
 
     /// Construct a new **IsfPipeline**.
     pub fn new(
@@ -523,17 +781,12 @@ impl IsfPipeline {
         }
 
         // Prepare the uniform buffers.
-        let [dst_tex_w, dst_tex_h] = dst_texture_size;
-        let isf_uniforms = IsfUniforms {
-            pass_index: 0,
-            render_size: [dst_tex_w as f32, dst_tex_h as f32],
-            time: 0.0,
-            time_delta: 0.0,
-            date: [0.0; 4],
-            frame_index: 0,
-        };
+        let isf_uniforms = [IsfUniforms::new(0, dst_texture_size, IsfTime::default())];
         let isf_uniforms_bytes = isf_uniforms_as_bytes(&isf_uniforms);
-        let isf_input_uniforms: IsfInputUniforms = [0u32; 128];
+        let isf_input_uniforms = match isf {
+            None => [0u32; 128],
+            Some(ref isf) => isf_input_uniforms(isf, &isf_data.inputs),
+        };
         let isf_input_uniforms_bytes = isf_input_uniforms_as_bytes(&isf_input_uniforms);
         let uniforms_usage = wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST;
         let isf_uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
@@ -585,8 +838,8 @@ impl IsfPipeline {
                 &isf_textures_bind_group_layout,
             ],
         );
-        let render_pipeline = match (vs.module.as_ref(), fs.module.as_ref()) {
-            (Some(vs_mod), Some(fs_mod)) => Some(create_render_pipeline(
+        let render_pipelines = match (vs.module.as_ref(), fs.module.as_ref()) {
+            (Some(vs_mod), Some(fs_mod)) => Some(create_render_pipelines(
                 device,
                 &layout,
                 vs_mod,
@@ -617,6 +870,7 @@ impl IsfPipeline {
             sampler_filtering,
             isf_uniform_buffer,
             isf_inputs_uniform_buffer,
+            isf_input_uniforms,
             isf_bind_group_layout,
             isf_inputs_bind_group_layout,
             isf_textures_bind_group_layout,
@@ -624,11 +878,12 @@ impl IsfPipeline {
             isf_inputs_bind_group,
             isf_textures_bind_group,
             layout,
-            render_pipeline,
+            render_pipelines,
             vertex_buffer,
             dst_format,
             dst_texture_size,
             dst_sample_count,
+            audio_receivers: vec![],
         }
     }
 
@@ -638,11 +893,17 @@ impl IsfPipeline {
     ///
     /// - First attempts to recompile the given sequence of touched shaders, both for ISF and GLSL.
     /// - Synchronises the ISF data with the latest successfully parsed `Isf` instance. Any images
-    ///   that have completed loading will be uploaded to textures.
+    ///   that have completed loading will be uploaded to textures. The latest audio pushed via
+    ///   `push_audio` or an `AudioSender` is written to the textures of `audio` and `audioFFT`
+    ///   inputs. Pass targets are resized in accordance with their `WIDTH` and `HEIGHT`
+    ///   expressions.
     /// - If the number of textures has changed, recreates the texture bind group, layout and
-    ///   render pipeline layout.
+    ///   render pipeline layout. If only the pass targets or audio textures have changed,
+    ///   recreates the texture bind group.
     /// - If any of the shaders successfully recompiled, or if the number of textures changed, the
     ///   pipeline is recreated.
+    /// - Packs the values of all inputs for upload during the following render passes. Any
+    ///   triggered `event` inputs are reset so that they are only `true` for a single frame.
     pub fn encode_update<I>(
         &mut self,
         device: &wgpu::Device,
@@ -687,6 +948,13 @@ impl IsfPipeline {
         // UPDATE ISF DATA
         // ---------------
 
+        // Receive any audio sent from other threads.
+        for receiver in &mut self.audio_receivers {
+            receiver.receive(&mut self.isf_data.audio);
+        }
+        self.audio_receivers
+            .retain(|receiver| !receiver.is_finished());
+
         // We can only update the isf data if we have an isf instance to work with.
         let isf = match self.isf {
             None => return,
@@ -697,7 +965,7 @@ impl IsfPipeline {
         let texture_count = isf_data_textures(&self.isf_data).count();
 
         // Synchronise the ISF data.
-        sync_isf_data(
+        let textures_changed = sync_isf_data(
             device,
             encoder,
             isf,
@@ -707,6 +975,14 @@ impl IsfPipeline {
             &mut self.isf_data,
         );
 
+        // Pack the input uniforms and reset events now that they have been captured.
+        self.isf_input_uniforms = isf_input_uniforms(isf, &self.isf_data.inputs);
+        for data in self.isf_data.inputs.values_mut() {
+            if let IsfInputData::Event { ref mut happening } = *data {
+                *happening = false;
+            }
+        }
+
         // UPDATE TEXTURE BIND GROUP
         // -------------------------
 
@@ -733,6 +1009,13 @@ impl IsfPipeline {
                     &self.isf_textures_bind_group_layout,
                 ],
             );
+        } else if textures_changed {
+            self.isf_textures_bind_group = create_isf_textures_bind_group(
+                device,
+                &self.isf_textures_bind_group_layout,
+                &self.sampler,
+                &self.isf_data,
+            );
         }
 
         // UPDATE RENDER PIPELINE
@@ -741,7 +1024,7 @@ impl IsfPipeline {
         if shader_recompiled || texture_count_changed {
             if let (Some(vs_mod), Some(fs_mod)) = (self.vs.module.as_ref(), self.fs.module.as_ref())
             {
-                self.render_pipeline = Some(create_render_pipeline(
+                self.render_pipelines = Some(create_render_pipelines(
                     device,
                     &self.layout,
                     vs_mod,
@@ -753,11 +1036,18 @@ impl IsfPipeline {
         }
     }
 
-    /// Given an encoder, submits a render pass command for drawing the pipeline to the given
+    /// Given an encoder, submits the render pass commands for drawing the pipeline to the given
     /// texture.
     ///
+    /// Each of the ISF `PASSES` is rendered in order, with `PASSINDEX` and `RENDERSIZE` set
+    /// accordingly. Passes with a `TARGET` render to their buffer, which may be sampled by
+    /// subsequent passes (and by the same pass during the following frame if `PERSISTENT`).
+    /// Passes without a `TARGET` render to the given texture. If the final pass has a `TARGET`,
+    /// it is rendered to the given texture as well. If no `PASSES` are declared, a single pass is
+    /// rendered to the given texture.
+    ///
     /// If the pipeline has not yet been created because it has not yet compiled the necessary
-    /// shaders correctly, the render pass will not be encoded.
+    /// shaders correctly, the render passes will not be encoded.
     pub fn encode_render_pass(
         &self,
         device: &wgpu::Device,
@@ -765,47 +1055,177 @@ impl IsfPipeline {
         dst_texture: &wgpu::TextureViewHandle,
         isf_time: IsfTime,
     ) {
-        if let Some(ref pipeline) = self.render_pipeline {
-            // Encode an update for the ISF uniform buffer.
-            let [w, h] = self.dst_texture_size;
-            let isf_uniforms = IsfUniforms {
-                pass_index: 0,
-                render_size: [w as f32, h as f32],
-                time: isf_time.time,
-                time_delta: isf_time.time_delta,
-                date: isf_time.date,
-                frame_index: isf_time.frame_index,
-            };
-            let isf_uniforms_bytes = isf_uniforms_as_bytes(&isf_uniforms);
-            let usage = wgpu::BufferUsages::COPY_SRC;
-            let new_buffer = device.create_buffer_init(&BufferInitDescriptor {
-                label: None,
-                contents: &isf_uniforms_bytes,
-                usage,
-            });
-            let size = isf_uniforms_bytes.len() as wgpu::BufferAddress;
-            encoder.copy_buffer_to_buffer(&new_buffer, 0, &self.isf_uniform_buffer, 0, size);
-
-            // TODO: Update the inputs.
-            let _ = &self.isf_inputs_uniform_buffer;
-            //let size = std::mem::size_of::<IsfInputUniforms>() as wgpu::BufferAddress;
-            //encoder.copy_buffer_to_buffer(&new_buffer, 0, &self.isf_inputs_uniform_buffer, 0, size);
-
-            // Encode the render pass.
-            let mut render_pass = wgpu::RenderPassBuilder::new()
-                .color_attachment(dst_texture, |color| color)
-                .begin(encoder);
-            render_pass.set_pipeline(pipeline);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_bind_group(0, &self.isf_bind_group, &[]);
-            render_pass.set_bind_group(1, &self.isf_inputs_bind_group, &[]);
-            render_pass.set_bind_group(2, &self.isf_textures_bind_group, &[]);
-            let vertex_range = 0..VERTICES.len() as u32;
-            let instance_range = 0..1;
-            render_pass.draw(vertex_range, instance_range);
+        let pipelines = match self.render_pipelines {
+            None => return,
+            Some(ref pipelines) => pipelines,
+        };
+
+        // Determine the render target of each pass.
+        enum Target<'a> {
+            Pass(&'a PassTarget),
+            Output,
+        }
+        let mut targets: Vec<_> = self
+            .isf_data
+            .passes
+            .iter()
+            .map(|pass| match pass.target {
+                Some(ref target) => Target::Pass(target),
+                None => Target::Output,
+            })
+            .collect();
+        match targets.last() {
+            None | Some(Target::Pass(_)) => targets.push(Target::Output),
+            Some(Target::Output) => (),
+        }
+
+        // Prepare the ISF uniforms for every pass within a single buffer.
+        let isf_uniforms: Vec<_> = targets
+            .iter()
+            .enumerate()
+            .map(|(i, target)| {
+                // The extra output pass for a final pass with a target shares its index.
+                let pass_index = i.min(self.isf_data.passes.len().saturating_sub(1));
+                let size = match *target {
+                    Target::Pass(target) => target.size(),
+                    Target::Output => self.dst_texture_size,
+                };
+                IsfUniforms::new(pass_index as i32, size, isf_time)
+            })
+            .collect();
+        let isf_uniforms_bytes = isf_uniforms_as_bytes(&isf_uniforms);
+        let usage = wgpu::BufferUsages::COPY_SRC;
+        let new_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: &isf_uniforms_bytes,
+            usage,
+        });
+
+        // Update the inputs.
+        let isf_input_uniforms_bytes = isf_input_uniforms_as_bytes(&self.isf_input_uniforms);
+        let new_inputs_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: None,
+            contents: &isf_input_uniforms_bytes,
+            usage,
+        });
+        let size = std::mem::size_of::<IsfInputUniforms>() as wgpu::BufferAddress;
+        encoder.copy_buffer_to_buffer(
+            &new_inputs_buffer,
+            0,
+            &self.isf_inputs_uniform_buffer,
+            0,
+            size,
+        );
+
+        // Clear the buffers that do not persist between frames.
+        for pass in &self.isf_data.passes {
+            if let Some(ref target) = pass.target {
+                if !target.persistent {
+                    let view = target.texture.view().build();
+                    wgpu::RenderPassBuilder::new()
+                        .color_attachment(&view, |color| color)
+                        .begin(encoder);
+                }
+            }
+        }
+
+        // Encode each pass.
+        let size = std::mem::size_of::<IsfUniforms>() as wgpu::BufferAddress;
+        for (i, target) in targets.iter().enumerate() {
+            let offset = i as wgpu::BufferAddress * size;
+            encoder.copy_buffer_to_buffer(&new_buffer, offset, &self.isf_uniform_buffer, 0, size);
+            match *target {
+                Target::Pass(target) => {
+                    let view = target.render_texture.view().build();
+                    let pipeline = pipelines.pass(target);
+                    self.encode_pass(encoder, pipeline, &view);
+                    encoder.copy_texture_to_texture(
+                        target.render_texture.as_image_copy(),
+                        target.texture.as_image_copy(),
+                        target.texture.extent(),
+                    );
+                }
+                Target::Output => {
+                    self.encode_pass(encoder, &pipelines.output, dst_texture);
+                }
+            }
         }
     }
 
+    // Encode a single render pass drawing the ISF quad to the given texture.
+    fn encode_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        dst_texture: &wgpu::TextureViewHandle,
+    ) {
+        let mut render_pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(dst_texture, |color| color)
+            .begin(encoder);
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_bind_group(0, &self.isf_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.isf_inputs_bind_group, &[]);
+        render_pass.set_bind_group(2, &self.isf_textures_bind_group, &[]);
+        let vertex_range = 0..VERTICES.len() as u32;
+        let instance_range = 0..1;
+        render_pass.draw(vertex_range, instance_range);
+    }
+
+    /// Set the value of the input with the given name.
+    ///
+    /// The value is validated against the input's type as declared by the ISF. `float`, `long`,
+    /// `point2D` and `color` values are clamped to the `MIN` and `MAX` of the input where
+    /// specified, and `long` values must be one of the input's `VALUES` if any are declared.
+    ///
+    /// Setting an `event` input via `IsfInputValue::Event` triggers the event for the next frame
+    /// only, after which it is automatically reset.
+    ///
+    /// Values are uploaded to the GPU on each frame via `encode_update` and `encode_render_pass`.
+    pub fn set_input<V>(&mut self, name: &str, value: V) -> Result<(), SetInputError>
+    where
+        V: Into<IsfInputValue>,
+    {
+        let input = self
+            .isf
+            .as_ref()
+            .and_then(|isf| isf.inputs.iter().find(|input| input.name == name))
+            .ok_or_else(|| SetInputError::UnknownInput {
+                name: name.to_string(),
+            })?;
+        let data = value.into().validate(input)?;
+        self.isf_data.inputs.insert(input.name.clone(), data);
+        Ok(())
+    }
+
+    /// Push the given interleaved audio samples to the pipeline.
+    ///
+    /// The pipeline retains as many of the most recent frames as are required by the `audio` and
+    /// `audioFFT` inputs of the ISF. On the following call to `encode_update`, the waveform of
+    /// the latest frames is written to each `audio` input and their spectrum to each `audioFFT`
+    /// input, with one row for each channel.
+    ///
+    /// Samples may be pushed any number of times between updates, e.g. once for each buffer
+    /// received from an audio input stream. Pushing a different number of channels discards the
+    /// existing history.
+    pub fn push_audio<I>(&mut self, channels: usize, samples: I)
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        self.isf_data.audio.push(channels, samples);
+    }
+
+    /// Produce a handle for sending audio to the pipeline from another thread, e.g. from the
+    /// capture function of a `nannou_audio` input stream.
+    ///
+    /// Audio sent via the handle is pushed to the pipeline as though via `push_audio` on the next
+    /// call to `encode_update`. Each sender has its own bounded queue, allocated here.
+    pub fn audio_sender(&mut self) -> AudioSender {
+        let (tx, rx) = audio::channel(audio::QUEUE_CAPACITY);
+        self.audio_receivers.push(rx);
+        tx
+    }
+
     /// Encode a render pass command for drawing the output of the pipeline to the given frame.
     ///
     /// Uses `encode_render_pass` internally.
@@ -887,6 +1307,24 @@ fn create_pipeline_layout(
     device.create_pipeline_layout(&desc)
 }
 
+fn create_render_pipelines(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    vs_mod: &wgpu::ShaderModule,
+    fs_mod: &wgpu::ShaderModule,
+    dst_format: wgpu::TextureFormat,
+    dst_sample_count: u32,
+) -> RenderPipelines {
+    let pipeline = |format, sample_count| {
+        create_render_pipeline(device, layout, vs_mod, fs_mod, format, sample_count)
+    };
+    RenderPipelines {
+        output: pipeline(dst_format, dst_sample_count),
+        pass: pipeline(IsfPipeline::PASS_TEXTURE_FORMAT, 1),
+        float_pass: pipeline(IsfPipeline::FLOAT_PASS_TEXTURE_FORMAT, 1),
+    }
+}
+
 fn create_render_pipeline(
     device: &wgpu::Device,
     layout: &wgpu::PipelineLayout,
@@ -915,8 +1353,9 @@ fn isf_data_textures(isf_data: &IsfData) -> impl Iterator<Item = &wgpu::Texture>
         _ => None,
     });
     let inputs = isf_data
-        .inputs
-        .values()
+        .input_names
+        .iter()
+        .filter_map(move |name| isf_data.inputs.get(name))
         .filter_map(|input_data| match input_data {
             IsfInputData::Image(ref img_state) => match *img_state {
                 ImageState::Ready(Ok(ref data)) => Some(&data.texture),
@@ -926,11 +1365,16 @@ fn isf_data_textures(isf_data: &IsfData) -> impl Iterator<Item = &wgpu::Texture>
             | IsfInputData::AudioFft { ref texture, .. } => Some(texture),
             _ => None,
         });
-    let passes = isf_data.passes.iter();
+    let passes = isf_data
+        .passes
+        .iter()
+        .filter_map(|pass| pass.target.as_ref().map(|target| &target.texture));
     imported.chain(inputs).chain(passes)
 }
 
 // Ensure the image state map is up to date.
+//
+// Returns whether or not any of the existing input or pass textures were replaced.
 fn sync_isf_data(
     device: &wgpu::Device,
     encoder: &mut wgpu::CommandEncoder,
@@ -939,7 +1383,7 @@ fn sync_isf_data(
     image_loader: &ImageLoader,
     images_path: &Path,
     isf_data: &mut IsfData,
-) {
+) -> bool {
     // Update imported images. first.
     isf_data
         .imported
@@ -956,6 +1400,11 @@ fn sync_isf_data(
     isf_data
         .inputs
         .retain(|key, _| isf.inputs.iter().map(|i| &i.name).any(|n| n == key));
+    isf_data.input_names = isf.inputs.iter().map(|i| i.name.clone()).collect();
+    isf_data
+        .audio
+        .set_capacity_frames(audio_history_capacity(isf));
+    let mut textures_changed = false;
     for input in &isf.inputs {
         let input_data = isf_data
             .inputs
@@ -963,30 +1412,126 @@ fn sync_isf_data(
             .or_insert_with(|| {
                 IsfInputData::new(device, encoder, image_loader, images_path, input)
             });
-        input_data.update(device, encoder, image_loader, images_path, input);
+        let audio = &mut isf_data.audio;
+        textures_changed |=
+            input_data.update(device, encoder, image_loader, images_path, audio, input);
     }
 
     // Prepare the textures that will be written to for passes.
-    isf_data.passes.resize_with(isf.passes.len(), || {
-        let texture = wgpu::TextureBuilder::new()
-            .format(Frame::TEXTURE_FORMAT)
-            .size(output_attachment_size)
-            .usage(default_isf_texture_usage())
-            .build(device);
-        let data = vec![0u8; texture.size_bytes()];
-        texture.upload_data(device, encoder, &data);
-        texture
-    });
+    textures_changed |= isf_data.passes.len() != isf.passes.len();
+    isf_data
+        .passes
+        .resize_with(isf.passes.len(), || PassData { target: None });
+    for (i, pass) in isf.passes.iter().enumerate() {
+        if pass.target.is_none() {
+            textures_changed |= isf_data.passes[i].target.take().is_some();
+            continue;
+        }
+
+        // Evaluate the size of the pass, falling back to the output size.
+        let eval_dim = |expr: &Option<String>, default: u32| {
+            expr.as_ref()
+                .and_then(|expr| {
+                    let var = |name: &str| isf_data.size_expr_var(name, output_attachment_size);
+                    crate::expr::eval(expr, var).ok()
+                })
+                .filter(|dim| dim.is_finite())
+                .map(|dim| (dim as u32).max(1))
+                .unwrap_or(default)
+        };
+        let [w, h] = output_attachment_size;
+        let size = [eval_dim(&pass.width, w), eval_dim(&pass.height, h)];
+        let format = match pass.float {
+            true => IsfPipeline::FLOAT_PASS_TEXTURE_FORMAT,
+            false => IsfPipeline::PASS_TEXTURE_FORMAT,
+        };
+
+        // Update the existing target if it is still compatible.
+        if let Some(ref mut target) = isf_data.passes[i].target {
+            if target.size() == size && target.texture.format() == format {
+                target.persistent = pass.persistent;
+                continue;
+            }
+        }
+
+        let texture = create_pass_texture(device, encoder, size, format);
+        let render_texture = create_pass_texture(device, encoder, size, format);
+        let persistent = pass.persistent;
+        isf_data.passes[i].target = Some(PassTarget {
+            texture,
+            render_texture,
+            persistent,
+        });
+        textures_changed = true;
+    }
+    textures_changed
 }
 
-fn create_black_texture(
+// The number of frames of audio history required by the audio inputs of the given ISF.
+fn audio_history_capacity(isf: &isf::Isf) -> usize {
+    isf.inputs
+        .iter()
+        .map(|input| match input.ty {
+            isf::InputType::Audio(ref a) => {
+                a.num_samples
+                    .unwrap_or(IsfPipeline::DEFAULT_AUDIO_SAMPLE_COUNT) as usize
+            }
+            isf::InputType::AudioFft(ref a) => audio::fft_len(
+                a.num_columns
+                    .unwrap_or(IsfPipeline::DEFAULT_AUDIO_FFT_COLUMNS) as usize,
+            ),
+            _ => 0,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+// Create a texture for an audio input with a row of the given data for each channel.
+fn create_audio_texture(
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    data: &[f32],
+    channels: usize,
+) -> wgpu::Texture {
+    let width = (data.len() / channels.max(1)).max(1) as u32;
+    let texture = wgpu::TextureBuilder::new()
+        .usage(default_isf_texture_usage())
+        .size([width, channels as u32])
+        .format(IsfPipeline::DEFAULT_AUDIO_TEXTURE_FORMAT)
+        .build(device);
+    let data: Vec<u16> = data.iter().cloned().map(f16_bits).collect();
+    texture.upload_data(device, encoder, f16_bits_as_bytes(&data));
+    texture
+}
+
+// Upload the given data to the audio texture, replacing the texture if its size has changed.
+//
+// Returns whether or not the texture was replaced.
+fn update_audio_texture(
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    texture: &mut wgpu::Texture,
+    data: &[f32],
+    channels: usize,
+) -> bool {
+    let width = (data.len() / channels.max(1)).max(1) as u32;
+    if texture.size() != [width, channels as u32] {
+        *texture = create_audio_texture(device, encoder, data, channels);
+        return true;
+    }
+    let data: Vec<u16> = data.iter().cloned().map(f16_bits).collect();
+    texture.upload_data(device, encoder, f16_bits_as_bytes(&data));
+    false
+}
+
+fn create_pass_texture(
     device: &wgpu::Device,
     encoder: &mut wgpu::CommandEncoder,
     size: [u32; 2],
     format: wgpu::TextureFormat,
 ) -> wgpu::Texture {
     let texture = wgpu::TextureBuilder::new()
-        .usage(default_isf_texture_usage())
+        .usage(pass_texture_usage())
         .size(size)
         .format(format)
         .build(device);
@@ -999,6 +1544,13 @@ fn default_isf_texture_usage() -> wgpu::TextureUsages {
     wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING
 }
 
+// Pass targets are rendered to, copied between and sampled.
+fn pass_texture_usage() -> wgpu::TextureUsages {
+    default_isf_texture_usage()
+        | wgpu::TextureUsages::RENDER_ATTACHMENT
+        | wgpu::TextureUsages::COPY_SRC
+}
+
 fn read_isf_from_path(path: &Path) -> Result<isf::Isf, IsfError> {
     std::fs::read_to_string(path)
         .map_err(|err| IsfError::from(err))
@@ -1016,8 +1568,90 @@ fn image_paths(dir: &Path) -> impl Iterator<Item = PathBuf> {
 
 // Conversions to bytes for GPU buffer uploads.
 
-fn isf_uniforms_as_bytes(data: &IsfUniforms) -> &[u8] {
-    unsafe { wgpu::bytes::from(data) }
+fn isf_uniforms_as_bytes(data: &[IsfUniforms]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
+// Pack the values of all non-texture inputs in accordance with the `std140` layout of the
+// `IsfDataInputs` uniform block, in the order in which they are declared.
+fn isf_input_uniforms(
+    isf: &isf::Isf,
+    inputs: &HashMap<InputName, IsfInputData>,
+) -> IsfInputUniforms {
+    let mut uniforms = [0u32; 128];
+    let mut offset = 0;
+    for input in &isf.inputs {
+        // The alignment of the field in 32-bit words.
+        let align = match input.ty {
+            isf::InputType::Event
+            | isf::InputType::Bool(_)
+            | isf::InputType::Long(_)
+            | isf::InputType::Float(_) => 1,
+            isf::InputType::Point2d(_) => 2,
+            isf::InputType::Color(_) => 4,
+            isf::InputType::Image | isf::InputType::Audio(_) | isf::InputType::AudioFft(_) => {
+                continue
+            }
+        };
+        offset = (offset + align - 1) / align * align;
+        let words = match inputs.get(&input.name) {
+            Some(IsfInputData::Event { happening }) => vec![*happening as u32],
+            Some(IsfInputData::Bool(b)) => vec![*b as u32],
+            Some(IsfInputData::Long(n)) => vec![*n as u32],
+            Some(IsfInputData::Float(f)) => vec![f.to_bits()],
+            Some(IsfInputData::Point2d(p)) => vec![p.x.to_bits(), p.y.to_bits()],
+            Some(IsfInputData::Color(c)) => vec![
+                c.red.to_bits(),
+                c.green.to_bits(),
+                c.blue.to_bits(),
+                c.alpha.to_bits(),
+            ],
+            _ => vec![],
+        };
+        let end = offset + align;
+        if end > uniforms.len() {
+            break;
+        }
+        uniforms[offset..offset + words.len()].copy_from_slice(&words);
+        offset = end;
+    }
+    uniforms
+}
+
+// The name of the input type as it appears within the ISF `TYPE` field.
+fn input_type_name(ty: &isf::InputType) -> &'static str {
+    match ty {
+        isf::InputType::Event => "event",
+        isf::InputType::Bool(_) => "bool",
+        isf::InputType::Long(_) => "long",
+        isf::InputType::Float(_) => "float",
+        isf::InputType::Point2d(_) => "point2D",
+        isf::InputType::Color(_) => "color",
+        isf::InputType::Image => "image",
+        isf::InputType::Audio(_) => "audio",
+        isf::InputType::AudioFft(_) => "audioFFT",
+    }
+}
+
+// Convert to the bits of a half-precision float, rounding toward zero.
+//
+// Values too small to be represented are flushed to zero and values too large become infinite.
+fn f16_bits(f: f32) -> u16 {
+    let bits = f.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let mantissa = ((bits >> 13) & 0x3ff) as u16;
+    if exp <= 0 {
+        sign
+    } else if exp >= 0x1f {
+        sign | 0x7c00
+    } else {
+        sign | ((exp as u16) << 10) | mantissa
+    }
+}
+
+fn f16_bits_as_bytes(data: &[u16]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
 }
 
 fn isf_input_uniforms_as_bytes(data: &[u32]) -> &[u8] {
@@ -1027,3 +1661,111 @@ fn isf_input_uniforms_as_bytes(data: &[u32]) -> &[u8] {
 fn vertices_as_bytes(data: &[Vertex]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{isf_input_uniforms, IsfInputData};
+    use nannou::prelude::*;
+    use std::collections::HashMap;
+
+    const INPUTS_ISF: &str = r#"/*{
+    "ISFVSN": "2",
+    "INPUTS": [
+        {
+            "DEFAULT": 0.5,
+            "NAME": "amount",
+            "TYPE": "float"
+        },
+        {
+            "DEFAULT": [
+                0.5,
+                0.5
+            ],
+            "NAME": "center",
+            "TYPE": "point2D"
+        },
+        {
+            "NAME": "flash",
+            "TYPE": "event"
+        },
+        {
+            "DEFAULT": [
+                1,
+                1,
+                1,
+                1
+            ],
+            "NAME": "tint",
+            "TYPE": "color"
+        },
+        {
+            "NAME": "inputImage",
+            "TYPE": "image"
+        },
+        {
+            "DEFAULT": 1,
+            "NAME": "mode",
+            "TYPE": "long",
+            "VALUES": [
+                0,
+                1
+            ]
+        },
+        {
+            "DEFAULT": false,
+            "NAME": "invert",
+            "TYPE": "bool"
+        }
+    ]
+}*/
+
+void main() {
+    gl_FragColor = vec4(0.0);
+}
+"#;
+
+    fn inputs() -> HashMap<String, IsfInputData> {
+        let mut inputs = HashMap::new();
+        inputs.insert("amount".to_string(), IsfInputData::Float(0.25));
+        inputs.insert("center".to_string(), IsfInputData::Point2d(pt2(1.0, 2.0)));
+        inputs.insert("flash".to_string(), IsfInputData::Event { happening: true });
+        let tint = lin_srgba(0.1, 0.2, 0.3, 0.4);
+        inputs.insert("tint".to_string(), IsfInputData::Color(tint));
+        inputs.insert("mode".to_string(), IsfInputData::Long(-1));
+        inputs.insert("invert".to_string(), IsfInputData::Bool(true));
+        inputs
+    }
+
+    #[test]
+    fn input_uniforms_are_aligned() {
+        let isf = isf::parse(INPUTS_ISF).unwrap();
+        let uniforms = isf_input_uniforms(&isf, &inputs());
+        // `float` at 0, `point2D` aligned to 2, `event` at 4, `color` aligned to 4, `image`
+        // skipped, then `long` and `bool`.
+        assert_eq!(uniforms[0], 0.25f32.to_bits());
+        assert_eq!(uniforms[1], 0);
+        assert_eq!(uniforms[2..4], [1.0f32.to_bits(), 2.0f32.to_bits()]);
+        assert_eq!(uniforms[4], 1);
+        assert_eq!(uniforms[5..8], [0, 0, 0]);
+        let tint = [0.1f32, 0.2, 0.3, 0.4].map(f32::to_bits);
+        assert_eq!(uniforms[8..12], tint);
+        assert_eq!(uniforms[12], -1i32 as u32);
+        assert_eq!(uniforms[13], 1);
+        assert!(uniforms[14..].iter().all(|&w| w == 0));
+    }
+
+    #[test]
+    fn missing_input_values_are_zeroed() {
+        let isf = isf::parse(INPUTS_ISF).unwrap();
+        let mut inputs = inputs();
+        inputs.remove("center");
+        inputs.remove("tint");
+        let uniforms = isf_input_uniforms(&isf, &inputs);
+        // The layout is unchanged while the values of the missing inputs are zero.
+        assert_eq!(uniforms[0], 0.25f32.to_bits());
+        assert_eq!(uniforms[2..4], [0, 0]);
+        assert_eq!(uniforms[4], 1);
+        assert_eq!(uniforms[8..12], [0, 0, 0, 0]);
+        assert_eq!(uniforms[12], -1i32 as u32);
+    }
+}
@@ -0,0 +1,194 @@
+//! The Ether Dream protocol backend.
+
+use super::{Backend, BackendError, DacStream, Detector};
+use crate::util::{clamp, map_range};
+use crate::{DetectedDac, RawPoint};
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// The Ether Dream protocol backend.
+///
+/// Ether Dream DACs are discovered via the broadcast messages that they emit once per second and
+/// are streamed to over TCP.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EtherDream;
+
+/// Yields Ether Dream DACs as their broadcasts are received.
+pub struct EtherDreamDetector {
+    dac_broadcasts: ether_dream::RecvDacBroadcasts,
+}
+
+/// A TCP stream of communication with an Ether Dream DAC.
+pub struct EtherDreamStream {
+    stream: ether_dream::dac::stream::Stream,
+    // Whether or not the control value on the next point should trigger a point rate change.
+    point_rate_changed: bool,
+    // For collecting the ether-dream points.
+    points: Vec<ether_dream::protocol::DacPoint>,
+}
+
+impl Backend for EtherDream {
+    fn detector(&self) -> io::Result<Box<dyn Detector>> {
+        let dac_broadcasts = ether_dream::recv_dac_broadcasts()?;
+        dac_broadcasts.set_nonblocking(true)?;
+        Ok(Box::new(EtherDreamDetector { dac_broadcasts }))
+    }
+}
+
+impl Detector for EtherDreamDetector {
+    fn try_next(&mut self) -> io::Result<Option<DetectedDac>> {
+        match self.dac_broadcasts.next() {
+            None => Ok(None),
+            Some(Err(err)) => match err.kind() {
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Ok(None),
+                _ => Err(err),
+            },
+            Some(Ok((broadcast, source_addr))) => Ok(Some(DetectedDac::EtherDream {
+                broadcast,
+                source_addr,
+            })),
+        }
+    }
+}
+
+impl DacStream for EtherDreamStream {
+    fn prepare(&mut self) -> Result<(), BackendError> {
+        self.stream.queue_commands().prepare_stream().submit()?;
+        Ok(())
+    }
+
+    fn begin(&mut self, point_hz: u32) -> Result<(), BackendError> {
+        // Queue the initial frame and tell the DAC to begin producing output.
+        let low_water_mark = 0;
+        let n_points = dac_remaining_buffer_capacity(self.stream.dac());
+        self.stream
+            .queue_commands()
+            .data((0..n_points).map(|_| centered_blank()))
+            .begin(low_water_mark, point_hz)
+            .submit()?;
+        Ok(())
+    }
+
+    fn set_point_hz(&mut self, point_hz: u32) -> Result<(), BackendError> {
+        self.stream.queue_commands().point_rate(point_hz).submit()?;
+        self.point_rate_changed = true;
+        Ok(())
+    }
+
+    fn points_to_generate(&mut self, latency_points: u32) -> Result<u32, BackendError> {
+        let latency_points = std::cmp::min(latency_points, std::u16::MAX as u32) as u16;
+        Ok(points_to_generate(self.stream.dac(), latency_points) as u32)
+    }
+
+    fn submit_points(&mut self, points: &[RawPoint]) -> Result<(), BackendError> {
+        self.points
+            .extend(points.iter().cloned().map(point_to_ether_dream_point));
+
+        // If the point rate changed, set the control value on the first point to trigger it.
+        if self.point_rate_changed && !self.points.is_empty() {
+            self.points[0].control = ether_dream::dac::PointControl::CHANGE_RATE.bits();
+            self.point_rate_changed = false;
+        }
+
+        // Submit the points.
+        self.stream
+            .queue_commands()
+            .data(self.points.drain(..))
+            .submit()?;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), BackendError> {
+        self.stream.queue_commands().stop().submit()?;
+        Ok(())
+    }
+}
+
+/// Establish a TCP stream with the Ether Dream DAC that emitted the given broadcast.
+pub fn connect(
+    broadcast: &ether_dream::protocol::DacBroadcast,
+    source_addr: &SocketAddr,
+    timeout: Option<Duration>,
+) -> Result<EtherDreamStream, ether_dream::dac::stream::CommunicationError> {
+    let ip = source_addr.ip().clone();
+    let stream = match timeout {
+        None => ether_dream::dac::stream::connect(broadcast, ip)?,
+        Some(timeout) => {
+            let stream = ether_dream::dac::stream::connect_timeout(broadcast, ip, timeout)?;
+            stream.set_timeout(Some(timeout))?;
+            stream
+        }
+    };
+    Ok(EtherDreamStream {
+        stream,
+        point_rate_changed: false,
+        points: vec![],
+    })
+}
+
+// The number of remaining points in the DAC.
+fn dac_remaining_buffer_capacity(dac: &ether_dream::dac::Dac) -> u16 {
+    dac.buffer_capacity - 1 - dac.status.buffer_fullness
+}
+
+// Determine the number of points needed to fill the DAC.
+fn points_to_generate(dac: &ether_dream::dac::Dac, latency_points: u16) -> u16 {
+    let remaining_capacity = dac_remaining_buffer_capacity(dac);
+    let n = if dac.status.buffer_fullness < latency_points {
+        latency_points - dac.status.buffer_fullness
+    } else {
+        0
+    };
+    std::cmp::min(n, remaining_capacity)
+}
+
+// Constructor for a centered, blank ether dream DAC point.
+fn centered_blank() -> ether_dream::protocol::DacPoint {
+    ether_dream::protocol::DacPoint {
+        control: 0,
+        x: 0,
+        y: 0,
+        r: 0,
+        g: 0,
+        b: 0,
+        i: 0,
+        u1: 0,
+        u2: 0,
+    }
+}
+
+// Convert a `lase::point::Position` type to an `i16` representation compatible with ether dream.
+fn position_to_ether_dream_position([px, py]: crate::point::Position) -> [i16; 2] {
+    let min = std::i16::MIN;
+    let max = std::i16::MAX;
+    let x = map_range(clamp(px, -1.0, 1.0), -1.0, 1.0, min as f64, max as f64) as i16;
+    let y = map_range(clamp(py, -1.0, 1.0), -1.0, 1.0, min as f64, max as f64) as i16;
+    [x, y]
+}
+
+// Convert a `lase::point::Rgb` type to an `u16` representation compatible with ether dream.
+fn color_to_ether_dream_color([pr, pg, pb]: crate::point::Rgb) -> [u16; 3] {
+    let r = (clamp(pr, 0.0, 1.0) * std::u16::MAX as f32) as u16;
+    let g = (clamp(pg, 0.0, 1.0) * std::u16::MAX as f32) as u16;
+    let b = (clamp(pb, 0.0, 1.0) * std::u16::MAX as f32) as u16;
+    [r, g, b]
+}
+
+// Convert the laser point to an ether dream DAC point.
+fn point_to_ether_dream_point(p: RawPoint) -> ether_dream::protocol::DacPoint {
+    let [x, y] = position_to_ether_dream_position(p.position);
+    let [r, g, b] = color_to_ether_dream_color(p.color);
+    let (control, i, u1, u2) = (0, 0, 0, 0);
+    ether_dream::protocol::DacPoint {
+        control,
+        x,
+        y,
+        r,
+        g,
+        b,
+        i,
+        u1,
+        u2,
+    }
+}
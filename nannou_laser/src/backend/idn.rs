@@ -0,0 +1,458 @@
+//! The ILDA Digital Network (IDN) protocol backend.
+//!
+//! IDN devices are discovered by broadcasting an IDN-Hello scan request and are streamed to via
+//! real-time channel messages over UDP. Points are sent as "wave" samples with 16-bit XY and 8-bit
+//! RGB components, each chunk annotated with its duration.
+//!
+//! The protocol provides no feedback of the fullness of the device's buffer. Instead, the stream
+//! paces itself against the system clock, sending points no further ahead than the requested
+//! latency.
+
+use super::{Backend, BackendError, DacStream, Detector};
+use crate::util::{clamp, map_range};
+use crate::{DetectedDac, RawPoint};
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// The UDP port on which IDN-Hello devices listen.
+pub const PORT: u16 = 7255;
+
+/// IDN devices do not report a maximum point rate, so this conservative value is assumed.
+pub const MAX_POINT_HZ: u32 = 100_000;
+
+/// IDN devices do not report the capacity of their buffers, so this value is assumed.
+///
+/// This limits the latency of IDN streams to a little under 100ms at `MAX_POINT_HZ`.
+pub const BUFFER_CAPACITY: u32 = 8_192;
+
+/// The interval at which scan requests are broadcast during detection.
+pub const SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+// The maximum number of points to send within a single channel message, keeping each datagram
+// within a typical ethernet MTU.
+const MAX_POINTS_PER_MESSAGE: usize = 200;
+
+// The interval at which the channel configuration is re-sent.
+const CONFIG_INTERVAL: Duration = Duration::from_millis(200);
+
+// IDN-Hello commands.
+const CMD_SCAN_REQUEST: u8 = 0x10;
+const CMD_SCAN_RESPONSE: u8 = 0x11;
+const CMD_RT_CNLMSG: u8 = 0x40;
+const CMD_RT_CNLMSG_CLOSE: u8 = 0x44;
+
+// IDN-Stream channel message content ID flags and values.
+const CONTENT_ID_CHANNEL_MSG: u16 = 0x8000;
+const CONTENT_ID_CONFIG_LSTFRG: u16 = 0x4000;
+const CNKTYPE_LPGRF_WAVE: u16 = 0x01;
+
+// IDN-Stream channel configuration flags and values.
+const CHNCFG_ROUTING: u8 = 0x01;
+const SMOD_LPGRF_CONTINUOUS: u8 = 0x01;
+
+// The sample descriptors for 16-bit X and Y followed by 8-bit red, green and blue.
+const SAMPLE_DESCRIPTORS: [u16; 8] = [
+    0x4200, 0x4010, // X, 16-bit precision.
+    0x4210, 0x4010, // Y, 16-bit precision.
+    0x527E, // Red, 638nm.
+    0x5214, // Green, 532nm.
+    0x51CC, // Blue, 460nm.
+    0x0000, // Void, for alignment.
+];
+
+// The size of each sample in bytes.
+const SAMPLE_SIZE: usize = 7;
+
+/// The IDN protocol backend.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Idn;
+
+/// Information about an IDN device, as reported in response to a scan request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScanResponse {
+    /// The version of the IDN-Hello protocol spoken by the device.
+    pub protocol_version: u8,
+    /// The status flags of the device.
+    pub status: u8,
+    /// The unit ID of the device. The first byte is the length of the ID that follows.
+    pub unit_id: [u8; 16],
+    /// The name of the device.
+    pub host_name: String,
+}
+
+/// Broadcasts scan requests and yields the IDN devices that respond.
+pub struct IdnDetector {
+    socket: UdpSocket,
+    last_scan: Option<Instant>,
+    sequence: u16,
+}
+
+/// A stream of IDN real-time channel messages to an IDN device.
+pub struct IdnStream {
+    socket: UdpSocket,
+    sequence: u16,
+    point_hz: u32,
+    // The time at which streaming began, used for message timestamps.
+    start: Instant,
+    // The time up to which points have been submitted.
+    submitted_until: Instant,
+    // The last time that the channel configuration was sent.
+    last_config: Option<Instant>,
+    // For building each message.
+    message: Vec<u8>,
+}
+
+/// Errors that might occur while parsing an IDN-Hello message.
+#[derive(Debug)]
+pub struct InvalidMessage;
+
+impl Backend for Idn {
+    fn detector(&self) -> io::Result<Box<dyn Detector>> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.set_broadcast(true)?;
+        socket.set_nonblocking(true)?;
+        let last_scan = None;
+        let sequence = 0;
+        Ok(Box::new(IdnDetector {
+            socket,
+            last_scan,
+            sequence,
+        }))
+    }
+}
+
+impl IdnDetector {
+    // Broadcast a scan request if one has not been sent within the last `SCAN_INTERVAL`.
+    fn scan(&mut self) -> io::Result<()> {
+        let now = Instant::now();
+        if let Some(last) = self.last_scan {
+            if now.duration_since(last) < SCAN_INTERVAL {
+                return Ok(());
+            }
+        }
+        self.last_scan = Some(now);
+        self.sequence = self.sequence.wrapping_add(1);
+        let request = hello_header(CMD_SCAN_REQUEST, self.sequence);
+        self.socket
+            .send_to(&request, (Ipv4Addr::BROADCAST, PORT))
+            .map(|_| ())
+    }
+}
+
+impl Detector for IdnDetector {
+    fn try_next(&mut self) -> io::Result<Option<DetectedDac>> {
+        self.scan()?;
+        let mut buffer = [0u8; 64];
+        loop {
+            let (len, source_addr) = match self.socket.recv_from(&mut buffer) {
+                Ok(res) => res,
+                Err(err) => match err.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => return Ok(None),
+                    _ => return Err(err),
+                },
+            };
+            // Ignore anything other than a valid scan response.
+            if let Ok(scan_response) = ScanResponse::from_bytes(&buffer[..len]) {
+                let dac = DetectedDac::Idn {
+                    scan_response,
+                    source_addr,
+                };
+                return Ok(Some(dac));
+            }
+        }
+    }
+}
+
+impl ScanResponse {
+    /// Parse a scan response from the given IDN-Hello message, including its header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, InvalidMessage> {
+        // The 4-byte header followed by the 40-byte response.
+        if bytes.len() < 44 || bytes[0] != CMD_SCAN_RESPONSE {
+            return Err(InvalidMessage);
+        }
+        let body = &bytes[4..];
+        let struct_size = body[0] as usize;
+        if struct_size < 40 {
+            return Err(InvalidMessage);
+        }
+        let protocol_version = body[1];
+        let status = body[2];
+        let mut unit_id = [0u8; 16];
+        unit_id.copy_from_slice(&body[4..20]);
+        let name = &body[20..40];
+        let name_len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+        let host_name = String::from_utf8_lossy(&name[..name_len]).into_owned();
+        Ok(ScanResponse {
+            protocol_version,
+            status,
+            unit_id,
+            host_name,
+        })
+    }
+}
+
+impl DacStream for IdnStream {
+    fn begin(&mut self, point_hz: u32) -> Result<(), BackendError> {
+        self.point_hz = point_hz;
+        self.start = Instant::now();
+        self.submitted_until = self.start;
+        self.last_config = None;
+        Ok(())
+    }
+
+    fn set_point_hz(&mut self, point_hz: u32) -> Result<(), BackendError> {
+        self.point_hz = point_hz;
+        Ok(())
+    }
+
+    fn points_to_generate(&mut self, latency_points: u32) -> Result<u32, BackendError> {
+        let point_hz = self.point_hz.max(1) as f64;
+        let latency = Duration::from_secs_f64(latency_points as f64 / point_hz);
+        loop {
+            let now = Instant::now();
+            // If we have fallen behind, resume from now.
+            if self.submitted_until < now {
+                self.submitted_until = now;
+            }
+            let ahead = self.submitted_until - now;
+            if ahead < latency {
+                let n = ((latency - ahead).as_secs_f64() * point_hz) as u32;
+                if n > 0 {
+                    return Ok(n);
+                }
+            }
+            // Wait until roughly a tenth of the latency has been consumed.
+            let wait = latency / 10;
+            std::thread::sleep(std::cmp::max(wait, Duration::from_millis(1)));
+        }
+    }
+
+    fn submit_points(&mut self, points: &[RawPoint]) -> Result<(), BackendError> {
+        for chunk in points.chunks(MAX_POINTS_PER_MESSAGE) {
+            self.send_chunk(chunk)?;
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), BackendError> {
+        self.sequence = self.sequence.wrapping_add(1);
+        let close = hello_header(CMD_RT_CNLMSG_CLOSE, self.sequence);
+        self.socket.send(&close)?;
+        Ok(())
+    }
+}
+
+impl IdnStream {
+    // Send the given points as a single channel message of wave samples.
+    fn send_chunk(&mut self, points: &[RawPoint]) -> io::Result<()> {
+        let point_hz = self.point_hz.max(1) as f64;
+        let duration = Duration::from_secs_f64(points.len() as f64 / point_hz);
+        let timestamp = (self.submitted_until - self.start).as_micros() as u32;
+        let duration_us = std::cmp::min(duration.as_micros(), 0x00FF_FFFF) as u32;
+
+        // Periodically include the channel configuration so that devices can join late.
+        let now = Instant::now();
+        let send_config = match self.last_config {
+            None => true,
+            Some(last) => now.duration_since(last) >= CONFIG_INTERVAL,
+        };
+        if send_config {
+            self.last_config = Some(now);
+        }
+
+        self.sequence = self.sequence.wrapping_add(1);
+        let chunk = WaveChunk {
+            sequence: self.sequence,
+            timestamp,
+            duration_us,
+            send_config,
+            points,
+        };
+        chunk.write(&mut self.message);
+        self.socket.send(&self.message)?;
+        self.submitted_until += duration;
+        Ok(())
+    }
+}
+
+// A real-time channel message containing a single chunk of wave samples.
+struct WaveChunk<'a> {
+    sequence: u16,
+    // The time of the chunk relative to the start of the stream in microseconds.
+    timestamp: u32,
+    // The duration of the chunk in microseconds, limited to 24 bits.
+    duration_us: u32,
+    // Whether or not to include the channel configuration.
+    send_config: bool,
+    points: &'a [RawPoint],
+}
+
+impl<'a> WaveChunk<'a> {
+    // Write the complete IDN-Hello packet to `msg`, replacing its contents.
+    fn write(&self, msg: &mut Vec<u8>) {
+        let WaveChunk {
+            sequence,
+            timestamp,
+            duration_us,
+            send_config,
+            points,
+        } = *self;
+        msg.clear();
+        msg.extend_from_slice(&hello_header(CMD_RT_CNLMSG, sequence));
+
+        // The channel message header. The total size is written once the message is complete.
+        let channel_id = 0u16;
+        let mut content_id = CONTENT_ID_CHANNEL_MSG | ((channel_id << 8) & 0x3F00);
+        content_id |= CNKTYPE_LPGRF_WAVE;
+        if send_config {
+            content_id |= CONTENT_ID_CONFIG_LSTFRG;
+        }
+        let channel_msg_start = msg.len();
+        msg.extend_from_slice(&[0, 0]);
+        msg.extend_from_slice(&content_id.to_be_bytes());
+        msg.extend_from_slice(&timestamp.to_be_bytes());
+
+        // The channel configuration header and sample descriptors.
+        if send_config {
+            let word_count = (SAMPLE_DESCRIPTORS.len() / 2) as u8;
+            let service_id = 0;
+            msg.extend_from_slice(&[word_count, CHNCFG_ROUTING, service_id]);
+            msg.push(SMOD_LPGRF_CONTINUOUS);
+            for descriptor in SAMPLE_DESCRIPTORS.iter() {
+                msg.extend_from_slice(&descriptor.to_be_bytes());
+            }
+        }
+
+        // The sample chunk header: 8 bits of flags followed by the 24-bit duration.
+        let flags = 0u32;
+        msg.extend_from_slice(&((flags << 24) | duration_us).to_be_bytes());
+
+        // The samples.
+        msg.reserve(points.len() * SAMPLE_SIZE);
+        for p in points {
+            let [x, y] = position_to_idn_position(p.position);
+            let [r, g, b] = color_to_idn_color(p.color);
+            msg.extend_from_slice(&x.to_be_bytes());
+            msg.extend_from_slice(&y.to_be_bytes());
+            msg.extend_from_slice(&[r, g, b]);
+        }
+
+        // Write the total size of the channel message.
+        let total_size = (msg.len() - channel_msg_start) as u16;
+        msg[channel_msg_start..channel_msg_start + 2].copy_from_slice(&total_size.to_be_bytes());
+    }
+}
+
+impl std::fmt::Display for InvalidMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid IDN-Hello message")
+    }
+}
+
+impl std::error::Error for InvalidMessage {}
+
+/// Connect a UDP socket to the IDN device at the given address.
+pub fn connect(source_addr: &SocketAddr, timeout: Option<Duration>) -> io::Result<IdnStream> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect(SocketAddr::new(source_addr.ip(), PORT))?;
+    socket.set_write_timeout(timeout)?;
+    let now = Instant::now();
+    Ok(IdnStream {
+        socket,
+        sequence: 0,
+        point_hz: crate::stream::DEFAULT_POINT_HZ,
+        start: now,
+        submitted_until: now,
+        last_config: None,
+        message: Vec::with_capacity(1_500),
+    })
+}
+
+// The 4-byte IDN-Hello packet header.
+fn hello_header(command: u8, sequence: u16) -> [u8; 4] {
+    let flags = 0;
+    let [s0, s1] = sequence.to_be_bytes();
+    [command, flags, s0, s1]
+}
+
+// Convert a laser position to the signed 16-bit representation used by IDN.
+fn position_to_idn_position([px, py]: crate::point::Position) -> [i16; 2] {
+    let min = i16::MIN;
+    let max = i16::MAX;
+    let x = map_range(clamp(px, -1.0, 1.0), -1.0, 1.0, min as f64, max as f64) as i16;
+    let y = map_range(clamp(py, -1.0, 1.0), -1.0, 1.0, min as f64, max as f64) as i16;
+    [x, y]
+}
+
+// Convert a laser colour to the 8-bit representation used by IDN.
+fn color_to_idn_color([pr, pg, pb]: crate::point::Rgb) -> [u8; 3] {
+    let r = (clamp(pr, 0.0, 1.0) * u8::MAX as f32) as u8;
+    let g = (clamp(pg, 0.0, 1.0) * u8::MAX as f32) as u8;
+    let b = (clamp(pb, 0.0, 1.0) * u8::MAX as f32) as u8;
+    [r, g, b]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{color_to_idn_color, position_to_idn_position, WaveChunk, CMD_RT_CNLMSG};
+    use crate::RawPoint;
+
+    fn chunk(send_config: bool, points: &[RawPoint]) -> Vec<u8> {
+        let chunk = WaveChunk {
+            sequence: 0x0102,
+            timestamp: 0x0A0B0C0D,
+            duration_us: 2_000,
+            send_config,
+            points,
+        };
+        let mut msg = vec![0xFF; 4];
+        chunk.write(&mut msg);
+        msg
+    }
+
+    #[test]
+    fn wave_chunk_without_config() {
+        let points = [
+            RawPoint::new([-1.0, 1.0], [1.0, 0.0, 0.5]),
+            RawPoint::new([0.0, 0.0], [0.0, 0.0, 0.0]),
+        ];
+        let msg = chunk(false, &points);
+        // The IDN-Hello header.
+        assert_eq!(msg[..4], [CMD_RT_CNLMSG, 0, 0x01, 0x02]);
+        // The channel message header: total size, content ID and timestamp.
+        let total_size = 8 + 4 + 2 * 7;
+        assert_eq!(msg.len(), 4 + total_size);
+        assert_eq!(msg[4..6], (total_size as u16).to_be_bytes());
+        assert_eq!(msg[6..8], [0x80, 0x01]);
+        assert_eq!(msg[8..12], [0x0A, 0x0B, 0x0C, 0x0D]);
+        // The chunk header: flags and 24-bit duration.
+        assert_eq!(msg[12..16], 2_000u32.to_be_bytes());
+        // The samples.
+        assert_eq!(msg[16..20], [0x80, 0x00, 0x7F, 0xFF]);
+        assert_eq!(msg[20..23], [255, 0, 127]);
+        assert_eq!(msg[23..30], [0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn wave_chunk_with_config() {
+        let points = [RawPoint::new([0.0, 0.0], [1.0, 1.0, 1.0])];
+        let msg = chunk(true, &points);
+        let total_size = 8 + 4 + 16 + 4 + 7;
+        assert_eq!(msg.len(), 4 + total_size);
+        assert_eq!(msg[4..6], (total_size as u16).to_be_bytes());
+        assert_eq!(msg[6..8], [0xC0, 0x01]);
+        // The channel configuration header: word count, flags, service ID and mode.
+        assert_eq!(msg[12..16], [4, 0x01, 0, 0x01]);
+        assert_eq!(msg[16..18], [0x42, 0x00]);
+        assert_eq!(msg[30..32], [0x00, 0x00]);
+        assert_eq!(msg[32..36], 2_000u32.to_be_bytes());
+        assert_eq!(msg[40..43], [255, 255, 255]);
+    }
+
+    #[test]
+    fn position_and_color_are_clamped() {
+        assert_eq!(position_to_idn_position([-2.0, 2.0]), [i16::MIN, i16::MAX]);
+        assert_eq!(color_to_idn_color([-1.0, 2.0, 0.0]), [0, 255, 0]);
+    }
+}
@@ -0,0 +1,223 @@
+//! A software DAC for running laser streams without any hardware.
+//!
+//! The **LoopbackDac** behaves like a physical DAC with a fixed buffer capacity, consuming the
+//! submitted points in real time at the stream's point rate. Each point is recorded as it is
+//! "emitted", allowing tests and previews to inspect the exact sequence of points produced by a
+//! raw or frame stream.
+//!
+//! ```no_run
+//! use nannou_laser as laser;
+//! use nannou_laser::backend::loopback::LoopbackDac;
+//!
+//! let dac = LoopbackDac::default();
+//! let api = laser::Api::new();
+//! let _stream = api
+//!     .new_raw_stream((), |_model, _buffer| {})
+//!     .detected_dac(dac.detected_dac())
+//!     .build()
+//!     .unwrap();
+//! std::thread::sleep(std::time::Duration::from_millis(100));
+//! let emitted = dac.take_points();
+//! ```
+
+use super::{Backend, BackendError, Dac, DacStream, Detector};
+use crate::{DacId, DetectedDac, RawPoint};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{self, AtomicU64};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The maximum point rate reported by a default **LoopbackDac**, matching the Ether Dream.
+pub const DEFAULT_MAX_POINT_HZ: u32 = 100_000;
+
+/// The buffer capacity reported by a default **LoopbackDac**, matching the Ether Dream.
+pub const DEFAULT_BUFFER_CAPACITY: u32 = 1_800;
+
+/// The name of the backend used within the DAC's `DacId::Custom`.
+pub const BACKEND_NAME: &str = "loopback";
+
+// Used to give each loopback DAC a unique ID.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A simulated DAC that consumes points in real time and records them as they are emitted.
+///
+/// Clones of a **LoopbackDac** refer to the same DAC.
+///
+/// The DAC may be passed to a stream builder via `detected_dac`, or used as a **Backend** via
+/// `Api::with_backends`, in which case it is detected once for each detection.
+#[derive(Clone, Debug)]
+pub struct LoopbackDac {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    id: u64,
+    max_point_hz: u32,
+    buffer_capacity: u32,
+    // The points emitted since the last call to `take_points`.
+    emitted: Mutex<Vec<RawPoint>>,
+}
+
+/// Yields the loopback DAC a single time.
+pub struct LoopbackDetector {
+    dac: Option<LoopbackDac>,
+}
+
+/// A stream of points to a **LoopbackDac**.
+pub struct LoopbackStream {
+    shared: Arc<Shared>,
+    point_hz: u32,
+    // The points that have been submitted but not yet emitted.
+    buffer: VecDeque<RawPoint>,
+    // The last time at which points were consumed from the buffer.
+    last_update: Instant,
+    // The fraction of a point that has been consumed since the last emitted point.
+    consumed: f64,
+}
+
+impl LoopbackDac {
+    /// Create a new loopback DAC with the given maximum point rate and buffer capacity.
+    pub fn new(max_point_hz: u32, buffer_capacity: u32) -> Self {
+        let id = NEXT_ID.fetch_add(1, atomic::Ordering::Relaxed);
+        let emitted = Mutex::new(vec![]);
+        let shared = Arc::new(Shared {
+            id,
+            max_point_hz,
+            buffer_capacity,
+            emitted,
+        });
+        LoopbackDac { shared }
+    }
+
+    /// The DAC as a **DetectedDac**, for passing to a stream builder.
+    pub fn detected_dac(&self) -> DetectedDac {
+        DetectedDac::Custom(Arc::new(self.clone()))
+    }
+
+    /// Take all points emitted by the DAC since the last call to `take_points`.
+    ///
+    /// Points are emitted in the order in which the DAC would have output them, at the rate at
+    /// which the DAC would have output them.
+    ///
+    /// **Note:** emitted points accumulate until they are taken, so this should be called
+    /// regularly for long-running streams.
+    pub fn take_points(&self) -> Vec<RawPoint> {
+        match self.shared.emitted.lock() {
+            Ok(mut emitted) => std::mem::replace(&mut *emitted, vec![]),
+            Err(_) => vec![],
+        }
+    }
+}
+
+impl LoopbackStream {
+    // Emit the points that would have been output since the last update.
+    fn update(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+        self.consumed += elapsed * self.point_hz as f64;
+        let n = std::cmp::min(self.consumed as usize, self.buffer.len());
+        self.consumed -= n as f64;
+        // If the buffer has run dry, the DAC idles until more points arrive.
+        if self.buffer.len() == n {
+            self.consumed = 0.0;
+        }
+        if let Ok(mut emitted) = self.shared.emitted.lock() {
+            emitted.extend(self.buffer.drain(..n));
+        }
+    }
+}
+
+impl Default for LoopbackDac {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_POINT_HZ, DEFAULT_BUFFER_CAPACITY)
+    }
+}
+
+impl Dac for LoopbackDac {
+    fn max_point_hz(&self) -> u32 {
+        self.shared.max_point_hz
+    }
+
+    fn buffer_capacity(&self) -> u32 {
+        self.shared.buffer_capacity
+    }
+
+    fn id(&self) -> DacId {
+        DacId::Custom {
+            backend: BACKEND_NAME,
+            id: self.shared.id,
+        }
+    }
+
+    fn connect(&self, _timeout: Option<Duration>) -> Result<Box<dyn DacStream>, BackendError> {
+        let stream = LoopbackStream {
+            shared: self.shared.clone(),
+            point_hz: crate::stream::DEFAULT_POINT_HZ,
+            buffer: VecDeque::with_capacity(self.shared.buffer_capacity as usize),
+            last_update: Instant::now(),
+            consumed: 0.0,
+        };
+        Ok(Box::new(stream))
+    }
+}
+
+impl Backend for LoopbackDac {
+    fn detector(&self) -> io::Result<Box<dyn Detector>> {
+        let dac = Some(self.clone());
+        Ok(Box::new(LoopbackDetector { dac }))
+    }
+}
+
+impl Detector for LoopbackDetector {
+    fn try_next(&mut self) -> io::Result<Option<DetectedDac>> {
+        Ok(self.dac.take().map(|dac| dac.detected_dac()))
+    }
+}
+
+impl DacStream for LoopbackStream {
+    fn begin(&mut self, point_hz: u32) -> Result<(), BackendError> {
+        self.point_hz = point_hz;
+        self.last_update = Instant::now();
+        self.consumed = 0.0;
+        Ok(())
+    }
+
+    fn set_point_hz(&mut self, point_hz: u32) -> Result<(), BackendError> {
+        self.update();
+        self.point_hz = point_hz;
+        Ok(())
+    }
+
+    fn points_to_generate(&mut self, latency_points: u32) -> Result<u32, BackendError> {
+        let capacity = self.shared.buffer_capacity as usize;
+        let latency = std::cmp::min(latency_points as usize, capacity);
+        loop {
+            self.update();
+            let fullness = self.buffer.len();
+            if fullness < latency {
+                return Ok((latency - fullness) as u32);
+            }
+            // Wait until there is room for at least one more point.
+            let points = (fullness - latency + 1) as f64;
+            let secs = points / self.point_hz.max(1) as f64;
+            let wait = std::cmp::max(Duration::from_secs_f64(secs), Duration::from_millis(1));
+            std::thread::sleep(wait);
+        }
+    }
+
+    fn submit_points(&mut self, points: &[RawPoint]) -> Result<(), BackendError> {
+        self.update();
+        self.buffer.extend(points.iter().cloned());
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), BackendError> {
+        // Like a physical DAC, any points remaining in the buffer are never output.
+        self.update();
+        self.buffer.clear();
+        Ok(())
+    }
+}
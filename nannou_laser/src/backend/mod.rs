@@ -0,0 +1,95 @@
+//! The protocols via which laser DACs may be detected and streamed to.
+//!
+//! Each protocol is implemented as a **Backend**. A backend produces a **Detector** that yields
+//! the DACs it discovers, and each detected DAC may be connected to, producing a **DacStream**.
+//! The raw and frame streams only interact with DACs via these traits, allowing for new protocols
+//! to be added without modifying the streams themselves.
+
+use crate::{DetectedDac, RawPoint};
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod ether_dream;
+pub mod idn;
+pub mod loopback;
+
+/// The error type produced by a backend's DAC stream.
+pub type BackendError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A protocol via which laser DACs may be detected.
+pub trait Backend: Send + Sync {
+    /// Begin detecting the DACs that speak this protocol.
+    fn detector(&self) -> io::Result<Box<dyn Detector>>;
+}
+
+/// Yields the DACs discovered by a **Backend** as they are detected.
+pub trait Detector: Send {
+    /// Retrieve the next detected DAC without blocking.
+    ///
+    /// Returns `Ok(None)` if no DAC is currently pending.
+    fn try_next(&mut self) -> io::Result<Option<DetectedDac>>;
+}
+
+/// A DAC detected by a custom **Backend**.
+///
+/// DACs of the protocols provided by this crate are described by the other variants of
+/// **DetectedDac**. DACs of other protocols may be represented via `DetectedDac::Custom`.
+pub trait Dac: std::fmt::Debug + Send + Sync {
+    /// The maximum point rate allowed by the DAC.
+    fn max_point_hz(&self) -> u32;
+
+    /// The number of points that can be stored within the buffer.
+    fn buffer_capacity(&self) -> u32;
+
+    /// A persistent, unique identifier associated with the DAC.
+    fn id(&self) -> crate::DacId;
+
+    /// Establish a new stream of communication with the DAC.
+    fn connect(&self, timeout: Option<Duration>) -> Result<Box<dyn DacStream>, BackendError>;
+}
+
+/// An established stream of communication with a DAC.
+///
+/// The raw stream drives the DAC stream as follows:
+///
+/// 1. `prepare` and `begin` are called once the connection is established.
+/// 2. `set_point_hz` is called whenever the user changes the point rate.
+/// 3. `points_to_generate` is called to determine how many points the user should render, after
+///    which the rendered points are passed to `submit_points`. This step is repeated until the
+///    stream is closed.
+/// 4. `stop` is called before the stream is dropped.
+pub trait DacStream: Send {
+    /// Prepare the DAC's playback engine.
+    fn prepare(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+
+    /// Begin producing output at the given point rate.
+    fn begin(&mut self, point_hz: u32) -> Result<(), BackendError>;
+
+    /// Change the rate at which the DAC processes points.
+    ///
+    /// The new rate should apply from the next submitted point onwards.
+    fn set_point_hz(&mut self, point_hz: u32) -> Result<(), BackendError>;
+
+    /// The number of points that should be submitted to fill the DAC's buffer up to the given
+    /// latency.
+    ///
+    /// This is called once per iteration of the stream loop. Backends that cannot report the
+    /// fullness of the DAC's buffer should block here as necessary in order to pace the stream.
+    fn points_to_generate(&mut self, latency_points: u32) -> Result<u32, BackendError>;
+
+    /// Submit the given points to the DAC.
+    fn submit_points(&mut self, points: &[RawPoint]) -> Result<(), BackendError>;
+
+    /// Stop the DAC from producing output.
+    fn stop(&mut self) -> Result<(), BackendError>;
+}
+
+/// The backends used by `Api::new`.
+///
+/// This includes the Ether Dream and IDN protocols.
+pub fn default_backends() -> Vec<Arc<dyn Backend>> {
+    vec![Arc::new(ether_dream::EtherDream), Arc::new(idn::Idn)]
+}
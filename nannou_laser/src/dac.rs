@@ -1,10 +1,15 @@
 //! Items related to DACs and DAC detection.
 
+use crate::backend::{self, Backend, BackendError, DacStream, Detector};
+use std::cell::Cell;
 use std::io;
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::mpsc;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// The interval at which `DetectDacs` polls each backend's detector while blocking.
+const DETECT_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Callback functions that may be passed to the `detect_dacs_async` function.
 pub trait DetectedDacCallback: FnMut(io::Result<DetectedDac>) {}
@@ -15,7 +20,17 @@ impl<F> DetectedDacCallback for F where F: FnMut(io::Result<DetectedDac>) {}
 /// It should be possible to use this to uniquely identify the same DAC on different occasions.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Id {
-    EtherDream { mac_address: [u8; 6] },
+    EtherDream {
+        mac_address: [u8; 6],
+    },
+    Idn {
+        unit_id: [u8; 16],
+    },
+    /// A DAC detected by a custom backend.
+    Custom {
+        backend: &'static str,
+        id: u64,
+    },
 }
 
 /// An available DAC detected on the system.
@@ -26,11 +41,22 @@ pub enum DetectedDac {
         broadcast: ether_dream::protocol::DacBroadcast,
         source_addr: std::net::SocketAddr,
     },
+    /// An ILDA Digital Network device discovered via an IDN-Hello scan.
+    Idn {
+        scan_response: backend::idn::ScanResponse,
+        source_addr: std::net::SocketAddr,
+    },
+    /// A DAC discovered by a custom backend.
+    Custom(Arc<dyn backend::Dac>),
 }
 
 /// An iterator yielding laser DACs available on the system as they are discovered.
+///
+/// Polls the detector of each backend in turn.
 pub struct DetectDacs {
-    pub(crate) dac_broadcasts: ether_dream::RecvDacBroadcasts,
+    pub(crate) detectors: Vec<Box<dyn Detector>>,
+    pub(crate) timeout: Cell<Option<Duration>>,
+    pub(crate) nonblocking: Cell<bool>,
 }
 
 /// Messages that driver forward the DAC detector thread.
@@ -52,6 +78,8 @@ impl DetectedDac {
     pub fn max_point_hz(&self) -> u32 {
         match self {
             DetectedDac::EtherDream { ref broadcast, .. } => broadcast.max_point_rate as _,
+            DetectedDac::Idn { .. } => backend::idn::MAX_POINT_HZ,
+            DetectedDac::Custom(ref dac) => dac.max_point_hz(),
         }
     }
 
@@ -59,6 +87,8 @@ impl DetectedDac {
     pub fn buffer_capacity(&self) -> u32 {
         match self {
             DetectedDac::EtherDream { ref broadcast, .. } => broadcast.buffer_capacity as _,
+            DetectedDac::Idn { .. } => backend::idn::BUFFER_CAPACITY,
+            DetectedDac::Custom(ref dac) => dac.buffer_capacity(),
         }
     }
 
@@ -70,6 +100,34 @@ impl DetectedDac {
             DetectedDac::EtherDream { ref broadcast, .. } => Id::EtherDream {
                 mac_address: broadcast.mac_address,
             },
+            DetectedDac::Idn {
+                ref scan_response, ..
+            } => Id::Idn {
+                unit_id: scan_response.unit_id,
+            },
+            DetectedDac::Custom(ref dac) => dac.id(),
+        }
+    }
+
+    /// Establish a new stream of communication with the DAC.
+    ///
+    /// The given `timeout` applies to both the connection and subsequent communication attempts.
+    pub fn connect(&self, timeout: Option<Duration>) -> Result<Box<dyn DacStream>, BackendError> {
+        match self {
+            DetectedDac::EtherDream {
+                ref broadcast,
+                ref source_addr,
+            } => {
+                let stream = backend::ether_dream::connect(broadcast, source_addr, timeout)?;
+                Ok(Box::new(stream))
+            }
+            DetectedDac::Idn {
+                ref source_addr, ..
+            } => {
+                let stream = backend::idn::connect(source_addr, timeout)?;
+                Ok(Box::new(stream))
+            }
+            DetectedDac::Custom(ref dac) => dac.connect(timeout),
         }
     }
 }
@@ -77,12 +135,14 @@ impl DetectedDac {
 impl DetectDacs {
     /// Specify a duration for the detection to wait before timing out.
     pub fn set_timeout(&self, duration: Option<std::time::Duration>) -> io::Result<()> {
-        self.dac_broadcasts.set_timeout(duration)
+        self.timeout.set(duration);
+        Ok(())
     }
 
     /// Specify whether or not retrieving the next DAC should block.
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        self.dac_broadcasts.set_nonblocking(nonblocking)
+        self.nonblocking.set(nonblocking);
+        Ok(())
     }
 }
 
@@ -105,13 +165,24 @@ impl DetectDacsAsync {
 impl Iterator for DetectDacs {
     type Item = io::Result<DetectedDac>;
     fn next(&mut self) -> Option<Self::Item> {
-        let res = self.dac_broadcasts.next()?;
-        match res {
-            Err(err) => Some(Err(err)),
-            Ok((broadcast, source_addr)) => Some(Ok(DetectedDac::EtherDream {
-                broadcast,
-                source_addr,
-            })),
+        let start = Instant::now();
+        loop {
+            for detector in &mut self.detectors {
+                match detector.try_next() {
+                    Ok(None) => (),
+                    Ok(Some(dac)) => return Some(Ok(dac)),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+            if self.nonblocking.get() {
+                return Some(Err(io::ErrorKind::WouldBlock.into()));
+            }
+            if let Some(timeout) = self.timeout.get() {
+                if start.elapsed() >= timeout {
+                    return Some(Err(io::ErrorKind::TimedOut.into()));
+                }
+            }
+            std::thread::sleep(DETECT_POLL_INTERVAL);
         }
     }
 }
@@ -122,31 +193,40 @@ impl Drop for DetectDacsAsync {
     }
 }
 
-/// An iterator yielding DACs available on the system as they are discovered.
-pub(crate) fn detect_dacs() -> io::Result<DetectDacs> {
-    let dac_broadcasts = ether_dream::recv_dac_broadcasts()?;
-    Ok(DetectDacs { dac_broadcasts })
+/// An iterator yielding DACs available on the system via the given backends as they are discovered.
+pub(crate) fn detect_dacs(backends: &[Arc<dyn Backend>]) -> io::Result<DetectDacs> {
+    let detectors = backends
+        .iter()
+        .map(|backend| backend.detector())
+        .collect::<io::Result<_>>()?;
+    Ok(DetectDacs {
+        detectors,
+        timeout: Cell::new(None),
+        nonblocking: Cell::new(false),
+    })
 }
 
 /// Spawn a thread for DAC detection.
 ///
 /// Calls the given `callback` with broadcasts as they are received.
 pub(crate) fn detect_dacs_async<F>(
+    backends: &[Arc<dyn Backend>],
     timeout: Option<Duration>,
     callback: F,
 ) -> io::Result<DetectDacsAsync>
 where
     F: 'static + DetectedDacCallback + Send,
 {
-    detect_dacs_async_inner(timeout, Box::new(callback) as Box<_>)
+    detect_dacs_async_inner(backends, timeout, Box::new(callback) as Box<_>)
 }
 
 /// Inner implementation of `detect_dacs_async` removing static dispatch indirection.
 fn detect_dacs_async_inner(
+    backends: &[Arc<dyn Backend>],
     timeout: Option<Duration>,
     mut callback: Box<dyn 'static + DetectedDacCallback + Send>,
 ) -> io::Result<DetectDacsAsync> {
-    let mut detect_dacs = detect_dacs()?;
+    let mut detect_dacs = detect_dacs(backends)?;
     detect_dacs.set_nonblocking(true)?;
     let (msg_tx, msg_rx) = mpsc::channel();
     let msg_tx2 = msg_tx.clone();
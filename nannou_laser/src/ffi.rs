@@ -117,6 +117,8 @@ pub enum Result {
     DetectDacsAsyncFailed,
     CloseStreamFailed,
     NullPointer,
+    /// The detected DAC is of a kind that is not representable via the FFI.
+    UnsupportedDac,
 }
 
 #[repr(C)]
@@ -244,9 +246,13 @@ pub type StreamErrorCallback =
     extern "C" fn(*mut raw::c_void, *const StreamError, *mut StreamErrorAction);
 
 /// Given some uninitialized pointer to an `Api` struct, fill it with a new Api instance.
+///
+/// Only Ether Dream DACs are currently representable via the FFI, so only the Ether Dream backend
+/// is enabled.
 #[no_mangle]
 pub unsafe extern "C" fn api_new(api: *mut Api) {
-    let inner = crate::Api::new();
+    let backend = crate::backend::ether_dream::EtherDream;
+    let inner = crate::Api::with_backends(vec![Arc::new(backend)]);
     let last_error = None;
     let boxed_inner = Box::new(ApiInner { inner, last_error });
     (*api).inner = Box::into_raw(boxed_inner);
@@ -293,11 +299,12 @@ pub unsafe extern "C" fn available_dacs(
     *first_dac = std::ptr::null_mut();
     *len = 0;
     if let Ok(dacs) = detect_dacs_async.dacs.lock() {
+        // DACs that are not representable via the FFI are skipped.
+        let mut dacs: Box<[_]> = dacs
+            .values()
+            .filter_map(|&(_, ref dac)| detected_dac_to_ffi(dac.clone()))
+            .collect();
         if !dacs.is_empty() {
-            let mut dacs: Box<[_]> = dacs
-                .values()
-                .map(|&(_, ref dac)| detected_dac_to_ffi(dac.clone()))
-                .collect();
             *len = dacs.len() as _;
             *first_dac = dacs.as_mut_ptr();
             std::mem::forget(dacs);
@@ -319,10 +326,17 @@ pub unsafe extern "C" fn detect_dac(api: *mut Api, detected_dac: *mut DetectedDa
     match iter.next() {
         None => return Result::DetectDacFailed,
         Some(res) => match res {
-            Ok(dac) => {
-                *detected_dac = detected_dac_to_ffi(dac);
-                return Result::Success;
-            }
+            Ok(dac) => match detected_dac_to_ffi(dac) {
+                Some(dac) => {
+                    *detected_dac = dac;
+                    return Result::Success;
+                }
+                None => {
+                    let err = "the detected DAC is not representable via the FFI";
+                    api.last_error = Some(string_to_cstring(err.to_string()));
+                    return Result::UnsupportedDac;
+                }
+            },
             Err(err) => {
                 api.last_error = Some(err_to_cstring(&err));
                 return Result::DetectDacFailed;
@@ -1091,7 +1105,8 @@ fn socket_addr_from_ffi(addr: SocketAddr) -> std::net::SocketAddr {
     std::net::SocketAddr::new(ip, addr.port)
 }
 
-fn detected_dac_to_ffi(dac: crate::DetectedDac) -> DetectedDac {
+// Returns `None` for DACs other than Ether Dream, which are not yet representable via the FFI.
+fn detected_dac_to_ffi(dac: crate::DetectedDac) -> Option<DetectedDac> {
     match dac {
         crate::DetectedDac::EtherDream {
             broadcast,
@@ -1103,8 +1118,9 @@ fn detected_dac_to_ffi(dac: crate::DetectedDac) -> DetectedDac {
                 source_addr,
             };
             let kind = DetectedDacKind { ether_dream };
-            DetectedDac { kind }
+            Some(DetectedDac { kind })
         }
+        crate::DetectedDac::Idn { .. } | crate::DetectedDac::Custom(_) => None,
     }
 }
 
@@ -1120,41 +1136,35 @@ fn detected_dac_from_ffi(ffi_dac: DetectedDac) -> crate::DetectedDac {
 }
 
 fn stream_error_to_kind(err: &crate::StreamError) -> StreamErrorKind {
-    use crate::stream::raw::EtherDreamStreamError;
     match *err {
-        crate::StreamError::EtherDreamStream { ref err } => match *err {
-            EtherDreamStreamError::FailedToDetectDacs { .. } => {
-                StreamErrorKind::EtherDreamFailedToDetectDacs
-            }
-            EtherDreamStreamError::FailedToConnectStream { .. } => {
-                StreamErrorKind::EtherDreamFailedToConnectStream
-            }
-            EtherDreamStreamError::FailedToPrepareStream { .. } => {
-                StreamErrorKind::EtherDreamFailedToPrepareStream
-            }
-            EtherDreamStreamError::FailedToBeginStream { .. } => {
-                StreamErrorKind::EtherDreamFailedToBeginStream
-            }
-            EtherDreamStreamError::FailedToSubmitData { .. } => {
-                StreamErrorKind::EtherDreamFailedToSubmitData
-            }
-            EtherDreamStreamError::FailedToSubmitPointRate { .. } => {
-                StreamErrorKind::EtherDreamFailedToSubmitPointRate
-            }
-            EtherDreamStreamError::FailedToStopStream { .. } => {
-                StreamErrorKind::EtherDreamFailedToStopStream
-            }
-        },
+        crate::StreamError::FailedToDetectDacs { .. } => {
+            StreamErrorKind::EtherDreamFailedToDetectDacs
+        }
+        crate::StreamError::FailedToConnectStream { .. } => {
+            StreamErrorKind::EtherDreamFailedToConnectStream
+        }
+        crate::StreamError::FailedToPrepareStream { .. } => {
+            StreamErrorKind::EtherDreamFailedToPrepareStream
+        }
+        crate::StreamError::FailedToBeginStream { .. } => {
+            StreamErrorKind::EtherDreamFailedToBeginStream
+        }
+        crate::StreamError::FailedToSubmitData { .. } => {
+            StreamErrorKind::EtherDreamFailedToSubmitData
+        }
+        crate::StreamError::FailedToSubmitPointRate { .. } => {
+            StreamErrorKind::EtherDreamFailedToSubmitPointRate
+        }
+        crate::StreamError::FailedToStopStream { .. } => {
+            StreamErrorKind::EtherDreamFailedToStopStream
+        }
     }
 }
 
 fn stream_error_to_attempts(err: &crate::StreamError) -> u32 {
-    use crate::stream::raw::EtherDreamStreamError;
     match *err {
-        crate::StreamError::EtherDreamStream { ref err } => match *err {
-            EtherDreamStreamError::FailedToDetectDacs { attempts, .. }
-            | EtherDreamStreamError::FailedToConnectStream { attempts, .. } => attempts,
-            _ => 0,
-        },
+        crate::StreamError::FailedToDetectDacs { attempts, .. }
+        | crate::StreamError::FailedToConnectStream { attempts, .. } => attempts,
+        _ => 0,
     }
 }
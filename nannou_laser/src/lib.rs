@@ -2,6 +2,7 @@
 
 pub extern crate ether_dream;
 
+pub mod backend;
 pub mod dac;
 #[cfg(feature = "ffi")]
 pub mod ffi;
@@ -32,20 +33,33 @@ pub struct Api {
 //
 // This is useful for allowing streams to re-scan and find their associated DAC in the case it
 // drops out for some reason.
-pub(crate) struct Inner;
+pub(crate) struct Inner {
+    // The backends via which DACs are detected.
+    backends: Vec<Arc<dyn backend::Backend>>,
+}
 
 impl Api {
     /// Instantiate the laser API.
+    ///
+    /// DACs are detected via the `backend::default_backends`.
     pub fn new() -> Self {
+        Self::with_backends(backend::default_backends())
+    }
+
+    /// Instantiate the laser API with the given set of backends.
+    ///
+    /// This allows for detecting DACs via custom protocols, or for limiting detection to a subset
+    /// of the protocols supported by this crate.
+    pub fn with_backends(backends: Vec<Arc<dyn backend::Backend>>) -> Self {
         Api {
-            inner: Arc::new(Inner),
+            inner: Arc::new(Inner { backends }),
         }
     }
 
     /// An iterator yielding laser DACs available on the system as they are discovered.
     ///
-    /// Currently, the only laser protocol supported is the ether dream protocol. Thus, this
-    /// enumerates ether dream DACs that are discovered on the LAN.
+    /// Enumerates the DACs discovered on the LAN via each of the API's backends. By default this
+    /// includes Ether Dream and IDN (ILDA Digital Network) DACs.
     ///
     /// **Note** that the produced iterator will iterate forever and never terminate unless
     /// `set_timeout` is called on the returned `DetectDacs` instance.
@@ -129,7 +143,7 @@ impl Api {
 impl Inner {
     /// See the `Api::detect_dacs` docs.
     pub(crate) fn detect_dacs(&self) -> io::Result<DetectDacs> {
-        dac::detect_dacs()
+        dac::detect_dacs(&self.backends)
     }
 
     /// Block and wait until the DAC with the given `Id` is detected.
@@ -152,7 +166,7 @@ impl Inner {
     where
         F: 'static + DetectedDacCallback + Send,
     {
-        dac::detect_dacs_async(timeout, callback)
+        dac::detect_dacs_async(&self.backends, timeout, callback)
     }
 }
 
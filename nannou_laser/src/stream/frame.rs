@@ -130,7 +130,7 @@ impl<M> Stream<M> {
             .map_err(|_| mpsc::SendError(()))
     }
 
-    /// Close the TCP communication thread and wait for the thread to join.
+    /// Close the DAC communication thread and wait for the thread to join.
     ///
     /// This consumes and drops the `Stream`, returning the result produced by joining the thread.
     ///
@@ -165,7 +165,7 @@ impl<M, F, R, E> Builder<M, F, R, E> {
         self
     }
 
-    /// The duration before TCP connection or communication attempts will time out.
+    /// The duration before DAC connection or communication attempts will time out.
     ///
     /// If this value is `None` (the default case), no timeout will be applied and the stream will
     /// wait forever.
@@ -301,7 +301,7 @@ impl<M, F, R, E> Builder<M, F, R, E> {
         }
     }
 
-    /// Specify a function that allows for handling errors that occur on the TCP stream thread.
+    /// Specify a function that allows for handling errors that occur on the DAC stream thread.
     ///
     /// If this method is not called, the `stream::raw::default_stream_error_fn` is used by default.
     pub fn stream_error<E2>(self, stream_error: E2) -> Builder<M, F, R, E2> {
@@ -1,4 +1,4 @@
-use crate::util::{clamp, map_range};
+use crate::backend::BackendError;
 use crate::Inner as ApiInner;
 use crate::{DetectedDac, RawPoint};
 use std::io;
@@ -12,7 +12,7 @@ use thiserror::Error;
 pub trait RenderFn<M>: Fn(&mut M, &mut Buffer) {}
 impl<M, F> RenderFn<M> for F where F: Fn(&mut M, &mut Buffer) {}
 
-/// The function called when an error occurs on the TCP communication stream.
+/// The function called when an error occurs on the DAC communication stream.
 ///
 /// The default `StreamErrorAction` is always `CloseThread`, in which case the thread will be
 /// closed and the error returned.
@@ -86,16 +86,6 @@ pub type ModelUpdate<M> = Box<dyn FnMut(&mut M) + 'static + Send>;
 /// Errors that may occur while running a laser stream.
 #[derive(Debug, Error)]
 pub enum StreamError {
-    #[error("an Ether Dream DAC stream error occurred: {err}")]
-    EtherDreamStream {
-        #[from]
-        err: EtherDreamStreamError,
-    },
-}
-
-/// Errors that may occur while creating a node crate.
-#[derive(Debug, Error)]
-pub enum EtherDreamStreamError {
     #[error("laser DAC detection failed: {err}")]
     FailedToDetectDacs {
         #[source]
@@ -106,34 +96,34 @@ pub enum EtherDreamStreamError {
     #[error("failed to connect the DAC stream (attempt {attempts}): {err}")]
     FailedToConnectStream {
         #[source]
-        err: ether_dream::dac::stream::CommunicationError,
+        err: BackendError,
         /// The number of connection attempts so far.
         attempts: u32,
     },
     #[error("failed to prepare the DAC stream: {err}")]
     FailedToPrepareStream {
         #[source]
-        err: ether_dream::dac::stream::CommunicationError,
+        err: BackendError,
     },
     #[error("failed to begin the DAC stream: {err}")]
     FailedToBeginStream {
         #[source]
-        err: ether_dream::dac::stream::CommunicationError,
+        err: BackendError,
     },
     #[error("failed to submit data over the DAC stream: {err}")]
     FailedToSubmitData {
         #[source]
-        err: ether_dream::dac::stream::CommunicationError,
+        err: BackendError,
     },
     #[error("failed to submit point rate change over the DAC stream: {err}")]
     FailedToSubmitPointRate {
         #[source]
-        err: ether_dream::dac::stream::CommunicationError,
+        err: BackendError,
     },
     #[error("failed to submit stop command to the DAC stream: {err}")]
     FailedToStopStream {
         #[source]
-        err: ether_dream::dac::stream::CommunicationError,
+        err: BackendError,
     },
 }
 
@@ -152,7 +142,7 @@ pub enum StreamErrorAction {
         /// How long to wait for a broadcast from the DAC before timing out.
         timeout: Option<Duration>,
     },
-    /// Close the TCP communication thread and return the error responsible.
+    /// Close the DAC communication thread and return the error responsible.
     CloseThread,
 }
 
@@ -246,7 +236,7 @@ impl<M> Stream<M> {
         self.shared.is_closed.load(atomic::Ordering::Relaxed)
     }
 
-    /// Close the TCP communication thread and wait for the thread to join.
+    /// Close the DAC communication thread and wait for the thread to join.
     ///
     /// This consumes and drops the `Stream`, returning the result produced by joining the thread.
     ///
@@ -342,7 +332,7 @@ impl<M, F, E> Builder<M, F, E> {
         self
     }
 
-    /// The duration before TCP connection or communication attempts will time out.
+    /// The duration before DAC connection or communication attempts will time out.
     ///
     /// If this value is `None` (the default case), no timeout will be applied and the stream will
     /// wait forever.
@@ -351,7 +341,7 @@ impl<M, F, E> Builder<M, F, E> {
         self
     }
 
-    /// Specify a function that allows for handling errors that occur on the TCP stream thread.
+    /// Specify a function that allows for handling errors that occur on the DAC stream thread.
     ///
     /// If this method is not called, the `default_stream_error_fn` is used by default.
     pub fn stream_error<E2>(self, stream_error: E2) -> Builder<M, F, E2> {
@@ -414,7 +404,7 @@ impl<M, F, E> Builder<M, F, E> {
         let maybe_dac = builder.dac;
         let maybe_dac2 = maybe_dac.clone();
 
-        // The TCP timeout duration.
+        // The DAC connection timeout duration.
         let tcp_timeout = builder.tcp_timeout;
 
         // A flag for tracking whether or not the stream has been closed.
@@ -536,8 +526,7 @@ where
                     }
                     Err(err) => {
                         let attempts = detect_attempts;
-                        let err = EtherDreamStreamError::FailedToDetectDacs { err, attempts };
-                        let err = StreamError::from(err);
+                        let err = StreamError::FailedToDetectDacs { err, attempts };
                         let mut guard = lock_or_return_err!(model, err);
                         let mut model = guard.take().unwrap();
                         let mut action = StreamErrorAction::default();
@@ -563,7 +552,7 @@ where
             None => {
                 detect_attempts += 1;
                 let attempts = detect_attempts;
-                let detect_err = &|err| EtherDreamStreamError::FailedToDetectDacs { err, attempts };
+                let detect_err = &|err| StreamError::FailedToDetectDacs { err, attempts };
                 match api_inner
                     .detect_dacs()
                     .map_err(detect_err)
@@ -575,7 +564,7 @@ where
                     })
                     .and_then(|mut dacs| {
                         dacs.next()
-                            .expect("ether dream DAC detection iterator should never return `None`")
+                            .expect("DAC detection iterator should never return `None`")
                             .map_err(detect_err)
                     }) {
                     Ok(dac) => {
@@ -583,7 +572,6 @@ where
                         dac
                     }
                     Err(err) => {
-                        let err = StreamError::from(err);
                         let mut guard = lock_or_return_err!(model, err);
                         let mut model = guard.take().unwrap();
                         let mut action = StreamErrorAction::default();
@@ -603,7 +591,7 @@ where
         };
 
         // Connect and run the laser stream.
-        match run_laser_stream_tcp_loop(
+        match run_laser_stream_dac_loop(
             &dac,
             tcp_timeout,
             &state,
@@ -636,10 +624,10 @@ where
     Ok(())
 }
 
-// Attempts to connect to the DAC via TCP and enters the stream loop.
-fn run_laser_stream_tcp_loop<M, F>(
+// Attempts to connect to the DAC and enters the stream loop.
+fn run_laser_stream_dac_loop<M, F>(
     dac: &DetectedDac,
-    tcp_timeout: Option<Duration>,
+    timeout: Option<Duration>,
     state: &Arc<Mutex<State>>,
     model: &Arc<Mutex<Option<M>>>,
     render: F,
@@ -651,43 +639,24 @@ fn run_laser_stream_tcp_loop<M, F>(
 where
     F: RenderFn<M>,
 {
-    // Currently only ether dream is supported, so retrieve the broadcast and addr.
-    let (broadcast, src_addr) = match dac {
-        DetectedDac::EtherDream {
-            broadcast,
-            source_addr,
-        } => (broadcast, source_addr),
-    };
-
     // A buffer for collecting model updates.
     let mut pending_model_updates: Vec<ModelUpdate<M>> = Vec::new();
 
-    // Establish the TCP connection.
-    let ip = src_addr.ip().clone();
-    let result = match tcp_timeout {
-        None => ether_dream::dac::stream::connect(&broadcast, ip),
-        Some(timeout) => ether_dream::dac::stream::connect_timeout(&broadcast, ip, timeout)
-            .and_then(|stream| {
-                stream.set_timeout(Some(timeout))?;
-                Ok(stream)
-            }),
-    };
-    let mut stream = match result {
+    // Establish the connection.
+    let mut stream = match dac.connect(timeout) {
         Ok(stream) => stream,
         Err(err) => {
             *connection_attempts += 1;
             let attempts = *connection_attempts;
-            return Err(EtherDreamStreamError::FailedToConnectStream { err, attempts }.into());
+            return Err(StreamError::FailedToConnectStream { err, attempts });
         }
     };
     *connection_attempts = 0;
 
     // Prepare the DAC's playback engine and await the repsonse.
     stream
-        .queue_commands()
-        .prepare_stream()
-        .submit()
-        .map_err(|err| EtherDreamStreamError::FailedToPrepareStream { err })?;
+        .prepare()
+        .map_err(|err| StreamError::FailedToPrepareStream { err })?;
 
     let dac_max_point_hz = dac.max_point_hz();
 
@@ -700,18 +669,10 @@ where
         std::cmp::min(hz, dac_max_point_hz)
     };
 
-    // Queue the initial frame and tell the DAC to begin producing output.
-    let low_water_mark = 0;
-    let n_points = dac_remaining_buffer_capacity(stream.dac());
+    // Tell the DAC to begin producing output.
     stream
-        .queue_commands()
-        .data((0..n_points).map(|_| centered_blank()))
-        .begin(low_water_mark, init_point_hz)
-        .submit()
-        .map_err(|err| EtherDreamStreamError::FailedToBeginStream { err })?;
-
-    // For collecting the ether-dream points.
-    let mut ether_dream_points = vec![];
+        .begin(init_point_hz)
+        .map_err(|err| StreamError::FailedToBeginStream { err })?;
 
     while !is_closed.load(atomic::Ordering::Relaxed) {
         // Collect any pending updates.
@@ -732,7 +693,7 @@ where
             let mut state = state.lock().expect("failed to acquare raw state lock");
 
             // Keep track of whether or not the `point_hz` as changed.
-            let prev_point_hz = std::cmp::min(state.point_hz, dac.max_point_hz());
+            let prev_point_hz = std::cmp::min(state.point_hz, dac_max_point_hz);
 
             // Apply updates.
             for mut state_update in state_update_rx.try_iter() {
@@ -743,22 +704,22 @@ where
         };
 
         // Clamp the point hz by the DAC's maximum point rate.
-        let point_hz = std::cmp::min(state.point_hz, dac.max_point_hz());
+        let point_hz = std::cmp::min(state.point_hz, dac_max_point_hz);
 
-        // If the point rate changed, we need to tell the DAC and set the control value on point 0.
-        let point_rate_changed = point_hz != prev_point_hz;
-        if point_rate_changed {
+        // If the point rate changed, we need to tell the DAC.
+        if point_hz != prev_point_hz {
             stream
-                .queue_commands()
-                .point_rate(point_hz)
-                .submit()
-                .map_err(|err| EtherDreamStreamError::FailedToSubmitPointRate { err })?;
+                .set_point_hz(point_hz)
+                .map_err(|err| StreamError::FailedToSubmitPointRate { err })?;
         }
 
         // Clamp the latency by the DAC's buffer capacity.
         let latency_points = std::cmp::min(state.latency_points, dac.buffer_capacity());
         // Determine how many points the DAC can currently receive.
-        let n_points = points_to_generate(stream.dac(), latency_points as u16) as usize;
+        let n_points = stream
+            .points_to_generate(latency_points)
+            .map_err(|err| StreamError::FailedToSubmitData { err })?
+            as usize;
 
         // The buffer that the user will write to. TODO: Re-use this points buffer.
         let mut buffer = Buffer {
@@ -774,103 +735,25 @@ where
             *guard = Some(m);
         }
 
-        // Retrieve the points.
-        ether_dream_points.extend(buffer.iter().cloned().map(point_to_ether_dream_point));
-
-        // If the point rate changed, set the control value on the first point to trigger it.
-        if point_rate_changed && !ether_dream_points.is_empty() {
-            ether_dream_points[0].control = ether_dream::dac::PointControl::CHANGE_RATE.bits();
-        }
-
         // Submit the points.
         stream
-            .queue_commands()
-            .data(ether_dream_points.drain(..))
-            .submit()
-            .map_err(|err| EtherDreamStreamError::FailedToSubmitData { err })?;
+            .submit_points(&buffer)
+            .map_err(|err| StreamError::FailedToSubmitData { err })?;
     }
 
     stream
-        .queue_commands()
         .stop()
-        .submit()
-        .map_err(|err| EtherDreamStreamError::FailedToStopStream { err })?;
+        .map_err(|err| StreamError::FailedToStopStream { err })?;
 
     Ok(())
 }
 
-// The number of remaining points in the DAC.
-fn dac_remaining_buffer_capacity(dac: &ether_dream::dac::Dac) -> u16 {
-    dac.buffer_capacity - 1 - dac.status.buffer_fullness
-}
-
-// Determine the number of points needed to fill the DAC.
-fn points_to_generate(dac: &ether_dream::dac::Dac, latency_points: u16) -> u16 {
-    let remaining_capacity = dac_remaining_buffer_capacity(dac);
-    let n = if dac.status.buffer_fullness < latency_points {
-        latency_points - dac.status.buffer_fullness
-    } else {
-        0
-    };
-    std::cmp::min(n, remaining_capacity)
-}
-
-// Constructor for a centered, blank ether dream DAC point.
-fn centered_blank() -> ether_dream::protocol::DacPoint {
-    ether_dream::protocol::DacPoint {
-        control: 0,
-        x: 0,
-        y: 0,
-        r: 0,
-        g: 0,
-        b: 0,
-        i: 0,
-        u1: 0,
-        u2: 0,
-    }
-}
-
-// Convert a `lase::point::Position` type to an `i16` representation compatible with ether dream.
-fn position_to_ether_dream_position([px, py]: crate::point::Position) -> [i16; 2] {
-    let min = std::i16::MIN;
-    let max = std::i16::MAX;
-    let x = map_range(clamp(px, -1.0, 1.0), -1.0, 1.0, min as f64, max as f64) as i16;
-    let y = map_range(clamp(py, -1.0, 1.0), -1.0, 1.0, min as f64, max as f64) as i16;
-    [x, y]
-}
-
-// Convert a `lase::point::Rgb` type to an `u16` representation compatible with ether dream.
-fn color_to_ether_dream_color([pr, pg, pb]: crate::point::Rgb) -> [u16; 3] {
-    let r = (clamp(pr, 0.0, 1.0) * std::u16::MAX as f32) as u16;
-    let g = (clamp(pg, 0.0, 1.0) * std::u16::MAX as f32) as u16;
-    let b = (clamp(pb, 0.0, 1.0) * std::u16::MAX as f32) as u16;
-    [r, g, b]
-}
-
-// Convert the laser point to an ether dream DAC point.
-fn point_to_ether_dream_point(p: RawPoint) -> ether_dream::protocol::DacPoint {
-    let [x, y] = position_to_ether_dream_position(p.position);
-    let [r, g, b] = color_to_ether_dream_color(p.color);
-    let (control, i, u1, u2) = (0, 0, 0, 0);
-    ether_dream::protocol::DacPoint {
-        control,
-        x,
-        y,
-        r,
-        g,
-        b,
-        i,
-        u1,
-        u2,
-    }
-}
-
 /// The default function used for the `stream_error` function if none is specified.
 ///
-/// If an error occurs while the TCP stream is running, an attempt will be made to re-establish a
-/// TCP connection.
+/// If an error occurs while the DAC stream is running, an attempt will be made to re-establish a
+/// connection.
 ///
-/// In the case that a TCP connection attempt fails, 2 more attempts will be made. Following this,
+/// In the case that a connection attempt fails, 2 more attempts will be made. Following this,
 /// an attempt will be made to re-detect the DAC with a 2 second timeout.
 ///
 /// In the case that a DAC could not be detected, 2 more attempts will be made each with a 2 second
@@ -884,26 +767,19 @@ pub fn default_stream_error_fn<M>(
         let timeout = Some(Duration::from_secs(2));
         StreamErrorAction::RedetectDac { timeout }
     }
-    let ether_dream_err = match *err {
-        StreamError::EtherDreamStream { ref err } => err,
-    };
-    *action = match *ether_dream_err {
-        EtherDreamStreamError::FailedToDetectDacs { attempts, .. } if attempts < 3 => {
-            redetect_dac_action()
-        }
-        EtherDreamStreamError::FailedToConnectStream { attempts, .. } if attempts < 3 => {
+    *action = match *err {
+        StreamError::FailedToDetectDacs { attempts, .. } if attempts < 3 => redetect_dac_action(),
+        StreamError::FailedToConnectStream { attempts, .. } if attempts < 3 => {
             std::thread::sleep(std::time::Duration::from_millis(16));
             StreamErrorAction::ReattemptConnect
         }
-        EtherDreamStreamError::FailedToConnectStream { attempts, .. } if attempts == 3 => {
+        StreamError::FailedToConnectStream { attempts, .. } if attempts == 3 => {
             redetect_dac_action()
         }
-        EtherDreamStreamError::FailedToPrepareStream { .. }
-        | EtherDreamStreamError::FailedToBeginStream { .. }
-        | EtherDreamStreamError::FailedToSubmitData { .. }
-        | EtherDreamStreamError::FailedToSubmitPointRate { .. } => {
-            StreamErrorAction::ReattemptConnect
-        }
+        StreamError::FailedToPrepareStream { .. }
+        | StreamError::FailedToBeginStream { .. }
+        | StreamError::FailedToSubmitData { .. }
+        | StreamError::FailedToSubmitPointRate { .. } => StreamErrorAction::ReattemptConnect,
         _ => StreamErrorAction::CloseThread,
     };
 }
@@ -0,0 +1,60 @@
+//! Tests parsing of the IDN-Hello messages used to detect IDN devices.
+
+use nannou_laser::backend::idn::ScanResponse;
+
+// A scan response from a device with the given unit ID and host name.
+fn scan_response(unit_id: &[u8], host_name: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x11, 0x00, 0x00, 0x01];
+    bytes.extend_from_slice(&[40, 0x01, 0x02, 0x00]);
+    let mut id = [0u8; 16];
+    id[0] = unit_id.len() as u8;
+    id[1..1 + unit_id.len()].copy_from_slice(unit_id);
+    bytes.extend_from_slice(&id);
+    let mut name = [0u8; 20];
+    name[..host_name.len()].copy_from_slice(host_name);
+    bytes.extend_from_slice(&name);
+    bytes
+}
+
+/// A well-formed scan response should be parsed.
+#[test]
+fn test_scan_response_from_bytes() {
+    let bytes = scan_response(&[7, 1, 2, 3], b"laser-1");
+    let response = ScanResponse::from_bytes(&bytes).unwrap();
+    assert_eq!(response.protocol_version, 0x01);
+    assert_eq!(response.status, 0x02);
+    assert_eq!(response.unit_id[..5], [4, 7, 1, 2, 3]);
+    assert_eq!(response.host_name, "laser-1");
+}
+
+/// A host name that fills the entire field has no null terminator.
+#[test]
+fn test_scan_response_full_host_name() {
+    let name = b"abcdefghijklmnopqrst";
+    let bytes = scan_response(&[], name);
+    let response = ScanResponse::from_bytes(&bytes).unwrap();
+    assert_eq!(response.host_name.as_bytes(), &name[..]);
+}
+
+/// Responses with a larger struct size from newer protocol versions are accepted.
+#[test]
+fn test_scan_response_larger_struct() {
+    let mut bytes = scan_response(&[1], b"a");
+    bytes[4] = 48;
+    bytes.extend_from_slice(&[0; 8]);
+    assert!(ScanResponse::from_bytes(&bytes).is_ok());
+}
+
+/// Truncated messages, other commands and undersized structs are rejected.
+#[test]
+fn test_scan_response_invalid() {
+    let bytes = scan_response(&[1], b"a");
+    assert!(ScanResponse::from_bytes(&[]).is_err());
+    assert!(ScanResponse::from_bytes(&bytes[..43]).is_err());
+    let mut request = bytes.clone();
+    request[0] = 0x10;
+    assert!(ScanResponse::from_bytes(&request).is_err());
+    let mut small = bytes;
+    small[4] = 36;
+    assert!(ScanResponse::from_bytes(&small).is_err());
+}
@@ -0,0 +1,115 @@
+//! Tests streaming to the software `LoopbackDac`, without any laser hardware.
+
+use nannou_laser as laser;
+use nannou_laser::backend::loopback::LoopbackDac;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const POINT_HZ: u32 = 10_000;
+
+// The longest to wait for the DAC to emit the expected points before failing.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The loopback DAC should be detected when used as a backend.
+#[test]
+fn test_detect_loopback_dac() {
+    let dac = LoopbackDac::default();
+    let api = laser::Api::with_backends(vec![Arc::new(dac.clone())]);
+    let detected = api.detect_dacs().unwrap().next().unwrap().unwrap();
+    assert_eq!(detected.id(), dac.detected_dac().id());
+    assert_eq!(
+        detected.max_point_hz(),
+        laser::backend::loopback::DEFAULT_MAX_POINT_HZ
+    );
+    assert_eq!(
+        detected.buffer_capacity(),
+        laser::backend::loopback::DEFAULT_BUFFER_CAPACITY
+    );
+}
+
+/// Every point rendered by a raw stream should be emitted in order, in real time.
+#[test]
+fn test_raw_stream_emits_points_in_order() {
+    let dac = LoopbackDac::default();
+    let api = laser::Api::new();
+    let start = Instant::now();
+    let stream = api
+        .new_raw_stream(0u32, render_raw)
+        .detected_dac(dac.detected_dac())
+        .point_hz(POINT_HZ)
+        .build()
+        .unwrap();
+    let mut points = take_points_until(&dac, |points| points.len() >= 1_000);
+    stream.close().unwrap().unwrap().unwrap();
+    let elapsed = start.elapsed().as_secs_f64();
+
+    points.extend(dac.take_points());
+    assert!(points.len() as f64 <= elapsed * POINT_HZ as f64 + 1.0);
+    for (i, p) in points.iter().enumerate() {
+        assert_eq!(p.position, index_position(i as u32));
+    }
+}
+
+/// A frame stream should emit the lit points of each frame along with the blank points between.
+#[test]
+fn test_frame_stream_emits_lit_and_blank_points() {
+    let dac = LoopbackDac::default();
+    let api = laser::Api::new();
+    let stream = api
+        .new_frame_stream((), render_frame)
+        .detected_dac(dac.detected_dac())
+        .point_hz(POINT_HZ)
+        .build()
+        .unwrap();
+    let points = take_points_until(&dac, |points| {
+        points.iter().any(|p| !p.is_blank()) && points.iter().any(|p| p.is_blank())
+    });
+    stream.close().unwrap().unwrap().unwrap();
+
+    for p in points.iter().filter(|p| !p.is_blank()) {
+        let [x, y] = p.position;
+        assert!(x.abs() <= 0.5 + std::f32::EPSILON);
+        assert!(y.abs() <= std::f32::EPSILON);
+    }
+}
+
+// Take the points emitted by the DAC until `done` returns `true` for all points taken so far.
+//
+// Panics if `TIMEOUT` elapses first.
+fn take_points_until<F>(dac: &LoopbackDac, mut done: F) -> Vec<laser::RawPoint>
+where
+    F: FnMut(&[laser::RawPoint]) -> bool,
+{
+    let start = Instant::now();
+    let mut points = vec![];
+    loop {
+        points.extend(dac.take_points());
+        if done(&points) {
+            return points;
+        }
+        assert!(
+            start.elapsed() < TIMEOUT,
+            "timed out waiting for the loopback DAC to emit points"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+// Write each point's index to its position so that the emitted order can be checked.
+fn render_raw(count: &mut u32, buffer: &mut laser::Buffer) {
+    for p in buffer.iter_mut() {
+        p.position = index_position(*count);
+        p.color = [1.0; 3];
+        *count += 1;
+    }
+}
+
+fn render_frame(_: &mut (), frame: &mut laser::Frame) {
+    let a = laser::Point::new([-0.5, 0.0], [1.0; 3]);
+    let b = laser::Point::new([0.5, 0.0], [1.0; 3]);
+    frame.add_lines(vec![a, b]);
+}
+
+fn index_position(i: u32) -> [f32; 2] {
+    [(i % 1_000) as f32 / 1_000.0, (i / 1_000) as f32 / 1_000.0]
+}
@@ -83,10 +83,11 @@ impl ThreadPool {
             interval_us *= 2;
         }
 
-        // Wrap the future with the counter.
+        // Wrap the future with the counter. The counter is incremented before spawning so that
+        // the future is accounted for even if it has not yet started.
         let active_futures = self.active_futures.clone();
+        active_futures.fetch_add(1, atomic::Ordering::SeqCst);
         let future = async move {
-            active_futures.fetch_add(1, atomic::Ordering::SeqCst);
             future.await;
             active_futures.fetch_sub(1, atomic::Ordering::SeqCst);
         };